@@ -44,6 +44,8 @@
 ## [code][{ ]}[/code] - Previous/Next unmatched {[br]
 ## [code][( ])[/code] - Previous/Next unmatched ([br]
 ## [code][m ]m[/code] - Previous/Next method start (requires treesitter, not available for GDScript)[br]
+## [code][f ]f[/code] - Previous/Next function start[br]
+## [code][d ]d[/code] - Previous/Next LSP diagnostic[br]
 ##
 ## [br][b]Operators[/b][br]
 ## [code]d{motion}[/code] - Delete[br]
@@ -51,7 +53,7 @@
 ## [code]y{motion}[/code] - Yank (copy)[br]
 ## [code]>{motion}[/code] - Indent right[br]
 ## [code]<{motion}[/code] - Indent left[br]
-## [code]gq{motion}[/code] - Format[br]
+## [code]gq{motion}[/code] - Format with gdformat[br]
 ## [code]gu{motion}[/code] - Lowercase[br]
 ## [code]gU{motion}[/code] - Uppercase[br]
 ## [code]g~{motion}[/code] - Toggle case[br]
@@ -60,7 +62,8 @@
 ## [code]dd cc yy[/code] - Operate on entire line[br]
 ## [code]D C Y[/code] - Operate to end of line[br]
 ## [code]x X[/code] - Delete char under/before cursor[br]
-## [code]s S[/code] - Substitute char/line[br]
+## [code]s{char}{char}[/code] - Jump to two characters on screen (Sneak setting; off: substitute char)[br]
+## [code]S[/code] - Substitute line[br]
 ## [code]r{char}[/code] - Replace char under cursor[br]
 ## [code]~[/code] - Toggle case of char[br]
 ## [code]J gJ[/code] - Join lines (with/without space)[br]
@@ -105,7 +108,7 @@
 ##
 ## [br][b]Registers[/b][br]
 ## [code]"{a-z}{op}[/code] - Use named register[br]
-## [code]"+ "*[/code] - System clipboard[br]
+## [code]"+ "*[/code] - System clipboard ([code]"*[/code] is the primary selection on Linux)[br]
 ## [code]"_[/code] - Black hole register[br]
 ## [code]"0[/code] - Yank register[br]
 ## [code]:registers :reg[/code] - Show all registers[br]
@@ -119,24 +122,41 @@
 ## [br][b]Search[/b][br]
 ## [code]/{pattern}[/code] - Search forward[br]
 ## [code]?{pattern}[/code] - Search backward[br]
-## [code]n N[/code] - Next/Previous match[br]
+## [code]/{pattern}/e[/code] - Search with an offset (e, s, b, +N, -N)[br]
+## [code]n N[/code] - Next/Previous match (with count: 3n)[br]
 ## [code]* #[/code] - Search word under cursor forward/backward[br]
+## [code]g* g#[/code] - Same, without word boundaries[br]
+## [code]Up Down[/code] - Browse search history (in / or ?)[br]
+## [code]q/ q?[/code] - Pick from search history[br]
 ##
 ## [br][b]Go Commands[/b][br]
-## [code]gd[/code] - Go to definition (LSP)[br]
+## [code]gd[/code] - Go to definition (LSP, tags, then Godot's symbol lookup)[br]
+## [code]gD[/code] - Go to declaration in the current file[br]
 ## [code]gf[/code] - Go to file under cursor[br]
 ## [code]gx[/code] - Open URL under cursor[br]
 ## [code]gt gT[/code] - Next/Previous tab[br]
+## [code]<leader>ff[/code] - Find project file ([code]leader_key[/code], default Space)[br]
+## [code]<leader>fg[/code] - Live grep the project's GDScript files[br]
+## [code]<leader>o[/code] - Outline of the current script's symbols (LSP)[br]
+## [code]<leader>e[/code] - Show the diagnostics of the cursor line[br]
+## [code]<leader>ca[/code] - Code actions at the cursor (LSP)[br]
+## [code]<leader>rp <leader>rs <leader>rq[/code] - Run project/Run current scene/Stop[br]
+## [code]<leader>tb[/code] - Toggle the bottom panel[br]
+## [code]<leader>fe[/code] - Focus the FileSystem dock[br]
+## [code]<leader>fh[/code] - Search help[br]
 ##
 ## [br][b]Info Commands[/b][br]
 ## [code]K[/code] - Show documentation (LSP)[br]
 ## [code]ga[/code] - Show ASCII/Unicode value[br]
 ## [code]Ctrl+G[/code] - Show file info[br]
+## [code]Ctrl+W h/j/k/l[/code] - Move focus between the script editor and docks[br]
+## [code]Ctrl+W o[/code] - Toggle distraction-free mode[br]
 ##
 ## [br][b]Folding[/b][br]
 ## [code]za[/code] - Toggle fold[br]
 ## [code]zo zc[/code] - Open/Close fold[br]
 ## [code]zR zM[/code] - Open/Close all folds[br]
+## [code]zf[/code] - Create a code region from the selection (Visual)[br]
 ##
 ## [br][b]Jump List[/b][br]
 ## [code]Ctrl+O[/code] - Jump back[br]
@@ -150,19 +170,34 @@
 ## [br][b]Undo/Redo[/b][br]
 ## [code]u[/code] - Undo[br]
 ## [code]Ctrl+R[/code] - Redo[br]
+## [code]g- g+[/code] - Older/Newer text state (across undo branches)[br]
 ## [code].[/code] - Repeat last change[br]
 ##
 ## [br][b]Ex Commands[/b][br]
 ## [code]:help :h[/code] - Open this help[br]
 ## [code]:version :ver[/code] - Show version in status label[br]
+## [code]:checkhealth :che[/code] - Diagnostics report (Neovim, RPC latency, Lua plugin, buffer sync, LSP)[br]
+## [code]:PluginReloadConfig[/code] - Restart Neovim to apply the config settings (Clean, Init Path, Config Files)[br]
 ## [code]:w :wa :wall[/code] - Save / Save all[br]
-## [code]:q :qa :qall[/code] - Close / Close all[br]
-## [code]:wq :x[/code] - Save and close[br]
-## [code]:wqa[/code] - Save all and close all[br]
+## [code]:w {file} :saveas {file}[/code] - Write to another file / and open it[br]
+## [code]:q :qa :qall[/code] - Close (E37 when modified) / Close all[br]
+## [code]:q![/code] - Close, discarding changes[br]
+## [code]:wq :x[/code] - Save and close (:x saves only when modified)[br]
+## [code]:wqa :xa[/code] - Save all and close all[br]
 ## [code]:e {file}[/code] - Open file[br]
 ## [code]:e! :edit![/code] - Reload from disk[br]
 ## [code]:bn :bp :bd[/code] - Next/Previous/Delete buffer[br]
-## [code]:ls[/code] - List buffers[br]
+## [code]:bd[!] {N|path}[/code] - Close the script of a buffer number or path[br]
+## [code]:ls[/code] - Pick a buffer[br]
+## [code]:b {N|name}[/code] - Switch to buffer by number or fuzzy name[br]
+## [code]:grep {pattern}[/code] - Search the project, list matches[br]
+## [code]:undotree[/code] - Pick an undo state to restore[br]
+## [code]:format[/code] - Format the script with gdformat[br]
+## [code]:symbols[/code] - Outline of the current script's symbols (LSP)[br]
+## [code]:wsymbols [query][/code] - Search the project's symbols (LSP)[br]
+## [code]:cnext :cprev :copen[/code] - Next/Previous/List quickfix entries[br]
+## [code]:run :run scene :run {file}[/code] - Run the project/current scene/a scene[br]
+## [code]:stop[/code] - Stop the running project[br]
 ## [code]:{number}[/code] - Go to line[br]
 ## [code]:%s/old/new/g[/code] - Substitute all[br]
 ## [code]:g/{pattern}/d[/code] - Delete matching lines[br]
@@ -175,6 +210,7 @@
 ## [code]@:[/code] - Repeat last Ex command[br]
 ## [code]g&[/code] - Repeat last :s on entire file[br]
 ## [code]Up Down[/code] - Browse command history[br]
+## [code]Tab Shift+Tab[/code] - Complete commands, files and buffers (cycle the matches)[br]
 ##
 ## [br][b]Settings[/b][br]
 ## Configure via [code]Editor > Editor Settings > Godot Neovim[/code].[br]
@@ -8,6 +8,7 @@
 ## - Control: "<C-a>", "<C-f>", etc.
 ## - Special: "<CR>", "<Esc>", "<Tab>", etc.
 ## - Sequences: "gg", "gd", "zo", "ZZ", etc.
+## - Leader: "<leader>ff" (the leader key is godot_neovim/leader_key)
 class_name GodotNeovimDefaultKeymaps
 
 
@@ -36,6 +37,9 @@ static func get_normal_keymap() -> Dictionary:
 		# --- File info ---
 		"<C-g>": "action_show_file_info",
 
+		# --- Multicursor ---
+		"<C-n>": "action_multicursor_next",
+
 		# --- Search ---
 		"/": "action_open_search_forward",
 		"?": "action_open_search_backward",
@@ -43,19 +47,35 @@ static func get_normal_keymap() -> Dictionary:
 		"N": "action_search_prev",
 		"*": "action_search_word_forward",
 		"#": "action_search_word_backward",
+		"g*": "action_search_partial_word_forward",
+		"g#": "action_search_partial_word_backward",
 
 		# --- Command line ---
 		":": "action_open_command_line",
 
+		# --- Two-character jump (sneak) ---
+		"s": "action_sneak",
+
+		# --- Sub-word motions (camelCase / snake_case segments) ---
+		"<A-w>": "action_subword_forward",
+		"<A-b>": "action_subword_backward",
+		"<A-e>": "action_subword_end",
+
 		# --- Undo / Redo ---
 		"u": "action_undo",
 		"<C-r>": "action_redo",
+		"g-": "action_undo_earlier",
+		"g+": "action_undo_later",
 
 		# --- Documentation ---
 		"K": "action_open_documentation",
 
 		# --- g-prefix commands (resolved as sequences) ---
 		"gd": "action_goto_definition",
+		"gD": "action_goto_declaration",
+		"gr": "action_lsp_references",
+		"grr": "action_lsp_references",
+		"gR": "action_lsp_rename",
 		"gf": "action_goto_file",
 		"gx": "action_open_url",
 		"gt": "action_next_tab",
@@ -85,6 +105,21 @@ static func get_normal_keymap() -> Dictionary:
 		# --- Z-prefix commands (resolved as sequences) ---
 		"ZZ": "action_save_and_close",
 		"ZQ": "action_close_discard",
+
+		# --- Leader mappings (leader key set in Editor Settings, default <Space>) ---
+		"<leader>ff": "action_find_files",
+		"<leader>fg": "action_live_grep",
+		"<leader>fh": "action_search_help",
+		"<leader>o": "action_document_symbols",
+		"<leader>e": "action_show_line_diagnostics",
+		"<leader>ca": "action_code_actions",
+		"<leader>fe": "action_focus_filesystem",
+		"<leader>tb": "action_toggle_bottom_panel",
+
+		# --- Leader: run the project (editor actions) ---
+		"<leader>rp": "action_run_project",
+		"<leader>rs": "action_run_current_scene",
+		"<leader>rq": "action_stop_project",
 	}
 
 
@@ -110,10 +145,20 @@ static func get_visual_keymap() -> Dictionary:
 		"N": "action_search_prev",
 		"*": "action_search_word_forward",
 		"#": "action_search_word_backward",
+		"g*": "action_search_partial_word_forward",
+		"g#": "action_search_partial_word_backward",
 
 		# Command line
 		":": "action_open_command_line",
 
+		# Multicursor from the selected text
+		"<C-n>": "action_multicursor_next",
+
+		# Sub-word motions
+		"<A-w>": "action_subword_forward",
+		"<A-b>": "action_subword_backward",
+		"<A-e>": "action_subword_end",
+
 		# g-prefix commands available in visual mode
 		"gv": "action_visual_block_toggle",
 		"gj": "action_display_line_down",
@@ -41,6 +41,7 @@ func setup(p_plugin: Object) -> void:
 
 	# Load custom keymaps from EditorSettings (godot_neovim/custom_keymaps)
 	_load_custom_keymaps_from_settings()
+	_sync_leader_mappings()
 
 	# Note: input handler registration is done by plugin.gd after setup() returns,
 	# to avoid re-entrant borrow issues with &mut self.
@@ -98,6 +99,8 @@ func apply_keymap_changes(p_changes: Dictionary) -> void:
 			continue
 		_apply_mode_changes(mode_key, p_changes[mode_key])
 
+	_sync_leader_mappings()
+
 
 
 ## Apply changes for a single mode.
@@ -112,6 +115,20 @@ func _apply_mode_changes(mode: String, mode_changes: Dictionary) -> void:
 		keymaps[mode][key] = set_dict[key]
 
 
+## Tell the plugin the keys that follow <leader> in the normal mode keymap,
+## so the leader key waits for them instead of moving the cursor.
+## Their action names are listed by the leader palette.
+func _sync_leader_mappings() -> void:
+	var leader_mappings := {}
+	var normal_map: Dictionary = keymaps.get("n", {})
+	for key in normal_map:
+		if key is String and key.begins_with("<leader>") and key.length() > 8:
+			var action = normal_map[key]
+			var action_name: String = action.get_method() if action is Callable else str(action)
+			leader_mappings[key.substr(8)] = action_name  # len("<leader>") == 8
+	plugin.set_leader_mappings(leader_mappings)
+
+
 ## Load custom keymaps from EditorSettings (godot_neovim/custom_keymaps).
 ## Same format as keymap_editor.gd saves.
 func _load_custom_keymaps_from_settings() -> void:
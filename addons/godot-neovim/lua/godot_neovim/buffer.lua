@@ -7,7 +7,32 @@ local M = {}
 -- Will be set by init.lua to avoid circular dependency
 M._setup_buffer_autocmds = nil
 
--- Register a buffer with initial content (clears undo history)
+-- Undo file of a buffer in 'undodir', nil when persistent undo is off
+local function undo_file_path(bufnr)
+    if not vim.go.undofile then
+        return nil
+    end
+    local name = vim.api.nvim_buf_get_name(bufnr)
+    if name == '' then
+        return nil
+    end
+    return vim.fn.undofile(name)
+end
+
+-- Read the undo history of a buffer whose initial content was just set
+-- Neovim refuses the undo file (silently here) unless the text is the one it was
+-- written for, so a file changed outside the editor keeps its empty history
+local function read_undo_file(bufnr)
+    local file = undo_file_path(bufnr)
+    if not file or vim.fn.filereadable(file) ~= 1 then
+        return
+    end
+    vim.api.nvim_buf_call(bufnr, function()
+        vim.cmd('silent! rundo ' .. vim.fn.fnameescape(file))
+    end)
+end
+
+-- Register a buffer with initial content (replaces undo history with the undo file's, if any)
 -- @param bufnr number: Buffer number (0 for current buffer)
 -- @param lines table: Array of lines to set
 -- @return number: changedtick after registration
@@ -29,6 +54,9 @@ function M.buffer_register(bufnr, lines)
     -- Restore undolevels
     vim.bo[bufnr].undolevels = saved_ul
 
+    -- The history saved with the file, if the content is still the same
+    read_undo_file(bufnr)
+
     -- Clear modified flag (this is initial content)
     vim.bo[bufnr].modified = false
 
@@ -58,6 +86,9 @@ function M.buffer_register_and_attach(bufnr, lines)
     -- Restore undolevels
     vim.bo[bufnr].undolevels = saved_ul
 
+    -- The history saved with the file, if the content is still the same
+    read_undo_file(bufnr)
+
     -- Clear modified flag (this is initial content)
     vim.bo[bufnr].modified = false
 
@@ -101,11 +132,44 @@ function M.set_indent_options(bufnr, use_spaces, indent_size)
     vim.bo[bufnr].softtabstop = indent_size
 end
 
+-- Set comment continuation ('formatoptions' r and o) for all buffers
+-- Leaders are only recognized at the start of a line (after indent), so a `#`
+-- inside a string literal never starts a continuation
+-- @param on_enter boolean: Continue the leader on <CR> in Insert mode ('r')
+-- @param on_open boolean: Continue the leader on o/O ('o')
+function M.set_comment_continuation(on_enter, on_open)
+    local function with_flags(fo)
+        fo = fo:gsub('[ro]', '')
+        if on_enter then
+            fo = fo .. 'r'
+        end
+        if on_open then
+            fo = fo .. 'o'
+        end
+        return fo
+    end
+    -- GDScript doc comments (##) before plain comments (#)
+    local function with_leaders(comments)
+        if comments:find('b:##', 1, true) then
+            return comments
+        end
+        return 'b:##,' .. comments
+    end
+
+    -- Global values are used by buffers created later
+    vim.go.formatoptions = with_flags(vim.go.formatoptions)
+    vim.go.comments = with_leaders(vim.go.comments)
+    for _, bufnr in ipairs(vim.api.nvim_list_bufs()) do
+        vim.bo[bufnr].formatoptions = with_flags(vim.bo[bufnr].formatoptions)
+        vim.bo[bufnr].comments = with_leaders(vim.bo[bufnr].comments)
+    end
+end
+
 -- Switch to buffer by path, creating and initializing if needed
 -- @param path string: Absolute file path
 -- @param lines table|nil: Lines to initialize with (only used for new buffers)
 -- @param indent_opts table|nil: { use_spaces = bool, indent_size = number }
--- @return table: { bufnr, tick, is_new, cursor }
+-- @return table: { bufnr, tick, is_new, initialized, attached, cursor }
 function M.switch_to_buffer(path, lines, indent_opts)
     -- Find existing buffer by path
     local bufnr = vim.fn.bufnr(path)
@@ -150,12 +214,25 @@ function M.switch_to_buffer(path, lines, indent_opts)
         -- Disable undo for initial content
         vim.bo[bufnr].undolevels = -1
 
+        -- A buffer Neovim loaded itself (e.g. a jump to a file not open in Godot)
+        -- gets the same write handling as buffers created here
+        if vim.bo[bufnr].buftype ~= 'acwrite' then
+            vim.bo[bufnr].buftype = 'acwrite'
+            vim.bo[bufnr].swapfile = false
+            if M._setup_buffer_autocmds then
+                M._setup_buffer_autocmds(bufnr)
+            end
+        end
+
         -- Set buffer content
         vim.api.nvim_buf_set_lines(bufnr, 0, -1, false, lines)
 
         -- Restore undolevels
         vim.bo[bufnr].undolevels = saved_ul
 
+        -- The history saved with the file (a large file's is read after its last chunk)
+        read_undo_file(bufnr)
+
         -- Clear modified flag
         vim.bo[bufnr].modified = false
 
@@ -168,6 +245,15 @@ function M.switch_to_buffer(path, lines, indent_opts)
     if not core._attached_buffers[bufnr] then
         attached = vim.api.nvim_buf_attach(bufnr, false, {
             on_lines = function(_, buf, tick, first_line, last_line, last_line_updated, byte_count)
+                -- Godot only mirrors the current buffer; edits to background buffers
+                -- (e.g., LSP rename across files) are applied on the Godot side directly
+                if buf ~= vim.api.nvim_get_current_buf() then
+                    return false
+                end
+                -- Chunks of a large file are already in Godot
+                if core._loading_buffers[buf] then
+                    return false
+                end
                 -- Get the new lines content
                 local new_lines = vim.api.nvim_buf_get_lines(buf, first_line, last_line_updated, false)
                 -- Send RPC notification with change details
@@ -200,11 +286,71 @@ function M.switch_to_buffer(path, lines, indent_opts)
         bufnr = bufnr,
         tick = tick,
         is_new = is_new,
+        initialized = should_init,
         attached = attached,
         cursor = cursor
     }
 end
 
+-- Append a chunk of a large file after switch_to_buffer set the first lines
+-- The lines are not undoable and not notified (Godot already has them)
+-- @param bufnr number: Buffer number
+-- @param lines table: Lines to append
+-- @param last boolean: Whether this is the last chunk (clears the modified flag)
+-- @return number: changedtick after the chunk
+function M.buffer_append_lines(bufnr, lines, last)
+    if not vim.api.nvim_buf_is_loaded(bufnr) then
+        return -1
+    end
+
+    local saved_ul = vim.bo[bufnr].undolevels
+    vim.bo[bufnr].undolevels = -1
+    core._loading_buffers[bufnr] = true
+    vim.api.nvim_buf_set_lines(bufnr, -1, -1, false, lines)
+    core._loading_buffers[bufnr] = nil
+    vim.bo[bufnr].undolevels = saved_ul
+
+    if last then
+        read_undo_file(bufnr)
+        vim.bo[bufnr].modified = false
+    end
+    return vim.api.nvim_buf_get_changedtick(bufnr)
+end
+
+-- Keep undo files in a folder, so undo history survives closing a buffer and
+-- restarting Neovim (the folder is created if needed)
+-- @param dir string: Undo folder ("" turns persistent undo off)
+function M.set_undo_dir(dir)
+    local enabled = dir ~= nil and dir ~= ''
+    if enabled then
+        vim.fn.mkdir(dir, 'p')
+        vim.go.undodir = dir
+    end
+    vim.go.undofile = enabled
+    for _, bufnr in ipairs(vim.api.nvim_list_bufs()) do
+        vim.bo[bufnr].undofile = enabled
+    end
+end
+
+-- Write the undo file of a buffer after Godot saved its file
+-- (BufWriteCmd replaces Neovim's own write, which would write it)
+-- @param path string: File path
+-- @return boolean: Whether the buffer exists and persistent undo is on
+function M.write_undo_file(path)
+    local bufnr = vim.fn.bufnr(path)
+    if bufnr == -1 or not core._initialized_buffers[bufnr] then
+        return false
+    end
+    local file = undo_file_path(bufnr)
+    if not file then
+        return false
+    end
+    vim.api.nvim_buf_call(bufnr, function()
+        vim.cmd('silent! wundo ' .. vim.fn.fnameescape(file))
+    end)
+    return true
+end
+
 -- Get buffer info without switching
 -- @param path string: File path
 -- @return table|nil: { bufnr, initialized, attached } or nil if not exists
@@ -220,6 +366,34 @@ function M.get_buffer_info(path)
     }
 end
 
+-- List buffers managed by godot-neovim (as shown by :ls)
+-- The cursor is the window cursor for the current buffer and the last cursor
+-- position ('" mark) for the others.
+-- @return table: { {bufnr, path, modified, alternate, line, col}, ... } by buffer number
+--   (line 1-indexed, col as a 0-indexed character column)
+function M.list_buffers()
+    local current = vim.api.nvim_get_current_buf()
+    local alternate = vim.fn.bufnr('#')
+    local result = {}
+    for _, bufnr in ipairs(vim.api.nvim_list_bufs()) do
+        if core._initialized_buffers[bufnr] and vim.api.nvim_buf_is_loaded(bufnr) then
+            local pos = bufnr == current and vim.api.nvim_win_get_cursor(0)
+                or vim.api.nvim_buf_get_mark(bufnr, '"')
+            local line = math.max(pos[1], 1)
+            local text = vim.api.nvim_buf_get_lines(bufnr, line - 1, line, false)[1] or ''
+            table.insert(result, {
+                bufnr,
+                vim.api.nvim_buf_get_name(bufnr),
+                vim.bo[bufnr].modified,
+                bufnr == alternate,
+                line,
+                vim.fn.strchars(text:sub(1, pos[2])),
+            })
+        end
+    end
+    return result
+end
+
 -- Reload current buffer from disk (:e!) and re-attach for notifications
 -- Returns the new buffer content and cursor position to sync to Godot
 -- @return table: { lines = buffer lines, tick = changedtick, cursor = {row, col} }
@@ -233,6 +407,9 @@ function M.reload_buffer()
     core._attached_buffers[bufnr] = nil  -- Clear old attachment flag
     local attached = vim.api.nvim_buf_attach(bufnr, false, {
         on_lines = function(_, buf, tick, first_line, last_line, last_line_updated, byte_count)
+            if buf ~= vim.api.nvim_get_current_buf() then
+                return false
+            end
             local new_lines = vim.api.nvim_buf_get_lines(buf, first_line, last_line_updated, false)
             vim.rpcnotify(0, "godot_buf_lines", buf, tick, first_line, last_line, new_lines)
             return false
@@ -259,6 +436,43 @@ function M.reload_buffer()
     }
 end
 
+-- Apply text edits (e.g., from LSP rename) to a buffer
+-- Edits must be sorted bottom-up so earlier edits don't shift later positions
+-- @param path string: Absolute file path ("" for current buffer)
+-- @param edits table: Array of { start_line, start_col, end_line, end_col, text }
+--                     (0-indexed lines, CHARACTER columns from Godot LSP)
+-- @return table: { applied = boolean }
+function M.apply_text_edits(path, edits)
+    local bufnr
+    if path == "" then
+        bufnr = vim.api.nvim_get_current_buf()
+    else
+        bufnr = vim.fn.bufnr(path)
+    end
+    if bufnr == -1 or not core._initialized_buffers[bufnr] then
+        return { applied = false }
+    end
+
+    if bufnr == vim.api.nvim_get_current_buf() then
+        core.transaction_join()
+    end
+    for _, edit in ipairs(edits) do
+        local start_line, start_col, end_line, end_col, text = edit[1], edit[2], edit[3], edit[4], edit[5]
+        local start_text = vim.api.nvim_buf_get_lines(bufnr, start_line, start_line + 1, false)[1] or ""
+        local end_text = vim.api.nvim_buf_get_lines(bufnr, end_line, end_line + 1, false)[1] or ""
+        vim.api.nvim_buf_set_text(
+            bufnr,
+            start_line,
+            core.char_col_to_byte_col(start_text, start_col),
+            end_line,
+            core.char_col_to_byte_col(end_text, end_col),
+            vim.split(text, "\n", { plain = true })
+        )
+    end
+
+    return { applied = true }
+end
+
 -- Join lines without space (gJ) while preserving comment leaders
 -- Temporarily clears 'comments' option to prevent comment leader removal
 function M.join_no_space()
@@ -307,4 +521,43 @@ function M.set_visual_selection(from_line, from_col, to_line, to_col)
     return { mode = vim.api.nvim_get_mode().mode }
 end
 
+-- Range found by the probe operator of text_object_range
+M._text_object = nil
+
+function M.text_object_opfunc(type)
+    local s = vim.api.nvim_buf_get_mark(0, '[')
+    local e = vim.api.nvim_buf_get_mark(0, ']')
+    local lines = vim.api.nvim_buf_get_lines(0, s[1] - 1, e[1], false)
+    M._text_object = {
+        s[1],
+        core.byte_col_to_char_col(lines[1], s[2]),
+        e[1],
+        core.byte_col_to_char_col(lines[#lines], e[2]),
+        type == 'line',
+    }
+end
+
+-- Range of a text object at the cursor (i(, a", 2it, ...), leaving buffer and cursor as they are
+-- The object is given to g@ with an operator that only records its range: when the
+-- object does not apply, Neovim cancels the operator and no range is returned
+-- @param obj string: Text object keys, with an optional count
+-- @return table|nil: { start_line, start_col, end_line, end_col, linewise } (1-indexed lines,
+--   0-indexed CHARACTER columns, end inclusive), nil if there is no such object here
+function M.text_object_range(obj)
+    local view = vim.fn.winsaveview()
+    local saved_opfunc = vim.o.operatorfunc
+    local saved_start = vim.fn.getpos("'[")
+    local saved_end = vim.fn.getpos("']")
+
+    M._text_object = nil
+    vim.o.operatorfunc = "v:lua.require'godot_neovim.buffer'.text_object_opfunc"
+    pcall(vim.cmd, 'silent! normal! g@' .. obj)
+
+    vim.o.operatorfunc = saved_opfunc
+    vim.fn.setpos("'[", saved_start)
+    vim.fn.setpos("']", saved_end)
+    vim.fn.winrestview(view)
+    return M._text_object
+end
+
 return M
@@ -0,0 +1,71 @@
+-- godot_neovim/clipboard.lua: Clipboard provider backed by Godot's DisplayServer
+--
+-- The embedded Neovim has no clipboard tool to talk to, so the + and * registers
+-- are bridged to the plugin: copies are sent to it (godot_clipboard_set), which
+-- writes the OS clipboard, and pastes return the OS clipboard text the plugin
+-- loads with load() before Neovim may paste it.
+
+local M = {}
+
+-- Content of each clipboard register: { lines, regtype }
+M._content = {
+    ['+'] = { { '' }, 'v' },
+    ['*'] = { { '' }, 'v' },
+}
+
+-- Text of register content as it is on the OS clipboard (linewise ends with a newline)
+local function content_text(content)
+    local text = table.concat(content[1], '\n')
+    if content[2] == 'V' then
+        text = text .. '\n'
+    end
+    return text
+end
+
+local function copy(reg)
+    return function(lines, regtype)
+        M._content[reg] = { vim.deepcopy(lines), regtype }
+        vim.rpcnotify(0, 'godot_clipboard_set', reg, content_text(M._content[reg]))
+    end
+end
+
+local function paste(reg)
+    return function()
+        return M._content[reg]
+    end
+end
+
+-- Load the OS clipboard text into a register (called by the plugin before pastes)
+-- The text of the last copy keeps its register type, so a yanked line stays linewise
+-- @param reg string: '+' or '*'
+-- @param text string: Clipboard text
+function M.load(reg, text)
+    if content_text(M._content[reg]) == text then
+        return
+    end
+    local lines = vim.split(text, '\n', { plain = true })
+    local regtype = 'v'
+    if #lines > 1 and lines[#lines] == '' then
+        table.remove(lines)
+        regtype = 'V'
+    end
+    M._content[reg] = { lines, regtype }
+end
+
+-- Make this the clipboard provider (unless the user's config set one)
+function M.setup()
+    if vim.g.clipboard ~= nil then
+        return
+    end
+    vim.g.clipboard = {
+        name = 'godot-neovim',
+        copy = { ['+'] = copy('+'), ['*'] = copy('*') },
+        paste = { ['+'] = paste('+'), ['*'] = paste('*') },
+        cache_enabled = 0,
+    }
+    -- Reload the provider in case it was already probed (e.g. clipboard=unnamedplus in init)
+    vim.g.loaded_clipboard_provider = nil
+    vim.cmd('runtime autoload/provider/clipboard.vim')
+end
+
+return M
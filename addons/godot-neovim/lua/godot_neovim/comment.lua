@@ -0,0 +1,67 @@
+-- godot_neovim/comment.lua: Comment toggle operator (gc{motion}, gcc, Visual gc)
+--
+-- Rust sends operator() in a <Cmd> before g@, so the toggle runs as
+-- 'operatorfunc': one undo step, and `.` repeats it.
+
+local M = {}
+
+-- Comment leader of the last operator (reused by `.`)
+M._leader = '#'
+
+local function is_blank(line)
+    return line:match('^%s*$') ~= nil
+end
+
+-- Toggle line comments on a range of lines
+-- The lines are uncommented if every non-blank line is commented, and
+-- commented at their smallest indentation otherwise. Blank lines are kept.
+-- @param first number: First line (1-indexed)
+-- @param last number: Last line (1-indexed, inclusive)
+-- @param leader string: Comment leader ("#" or "//")
+function M.toggle_lines(first, last, leader)
+    local lines = vim.api.nvim_buf_get_lines(0, first - 1, last, false)
+    local commented_pattern = '^%s*' .. vim.pesc(leader)
+    local uncommented = false
+    local indent = nil
+    for _, line in ipairs(lines) do
+        if not is_blank(line) then
+            if not line:match(commented_pattern) then
+                uncommented = true
+            end
+            local width = #line:match('^%s*')
+            if not indent or width < indent then
+                indent = width
+            end
+        end
+    end
+    if not indent then
+        return
+    end
+
+    local result = {}
+    for i, line in ipairs(lines) do
+        if is_blank(line) then
+            result[i] = line
+        elseif uncommented then
+            result[i] = line:sub(1, indent) .. leader .. ' ' .. line:sub(indent + 1)
+        else
+            result[i] = line:gsub('^(%s*)' .. vim.pesc(leader) .. ' ?', '%1', 1)
+        end
+    end
+    vim.api.nvim_buf_set_lines(0, first - 1, last, false, result)
+end
+
+function M.opfunc()
+    local first = vim.api.nvim_buf_get_mark(0, '[')[1]
+    local last = vim.api.nvim_buf_get_mark(0, ']')[1]
+    M.toggle_lines(first, last, M._leader)
+end
+
+-- Make g@ the comment toggle operator
+-- @param leader string: Comment leader of the current file
+function M.operator(leader)
+    M._leader = leader
+    vim.o.operatorfunc = "v:lua.require'godot_neovim.comment'.opfunc"
+end
+
+return M
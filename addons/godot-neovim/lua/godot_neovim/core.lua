@@ -8,6 +8,9 @@ M._initialized_buffers = {}
 -- Track which buffers have been attached for notifications
 M._attached_buffers = {}
 
+-- Buffers receiving a chunk of a large file (changes are not notified)
+M._loading_buffers = {}
+
 -- Track last cursor position and mode for throttling RPC notifications
 M._last_cursor = { 0, 0 }
 M._last_mode = ""
@@ -44,6 +47,212 @@ function M.get_state()
     }
 end
 
+-- Registers shown by :registers, in the same order as Neovim
+local REGISTER_NAMES = '"0123456789abcdefghijklmnopqrstuvwxyz-*+.:%#/'
+
+-- Get the contents of all non-empty registers
+-- @return table: Array of { name, regtype, text } (regtype: 'v', 'V' or '<C-v>{width}')
+function M.get_registers()
+    local registers = {}
+    for name in REGISTER_NAMES:gmatch('.') do
+        local ok, lines = pcall(vim.fn.getreg, name, 1, true)
+        if ok and type(lines) == 'table' and #lines > 0 then
+            local text = table.concat(lines, '\n')
+            if text ~= '' then
+                local regtype = vim.fn.getregtype(name)
+                if regtype:sub(1, 1) == '\22' then
+                    regtype = '<C-v>' .. regtype:sub(2)
+                end
+                table.insert(registers, { name, regtype, text })
+            end
+        end
+    end
+    return registers
+end
+
+-- Get the marks of the current buffer (a-z) and the file marks (A-Z)
+-- @return table: Array of { mark, line, col, file }: 1-indexed line, byte column
+--                (0-indexed) and the absolute file path ('' for buffer marks)
+function M.get_marks()
+    local marks = {}
+    for _, m in ipairs(vim.fn.getmarklist('%')) do
+        local name = m.mark:sub(2)
+        if name:match('^[a-z]$') then
+            table.insert(marks, { name, m.pos[2], m.pos[3] - 1, '' })
+        end
+    end
+    for _, m in ipairs(vim.fn.getmarklist()) do
+        local name = m.mark:sub(2)
+        if name:match('^[A-Z]$') then
+            table.insert(marks, { name, m.pos[2], m.pos[3] - 1, vim.fn.fnamemodify(m.file, ':p') })
+        end
+    end
+    return marks
+end
+
+-- Set marks saved with get_marks (after Neovim was restarted)
+-- @param marks table: Array of { mark, line, col, file } as returned by get_marks
+-- @param buffer_marks boolean: Whether the buffer marks belong to the current buffer
+-- @return number: Number of marks set
+function M.restore_marks(marks, buffer_marks)
+    local count = 0
+    for _, m in ipairs(marks) do
+        local name, line, col, file = m[1], m[2], m[3], m[4]
+        local bufnr = 0
+        if file ~= '' then
+            -- File marks may point to files with no buffer yet (unlisted, not loaded)
+            bufnr = vim.fn.bufadd(file)
+        end
+        if file ~= '' or buffer_marks then
+            if vim.fn.setpos("'" .. name, { bufnr, line, col + 1, 0 }) == 0 then
+                count = count + 1
+            end
+        end
+    end
+    return count
+end
+
+-- Get the jumplist of the current window (as shown by :jumps)
+-- @return table: { current, { {file, line, col, text}, ... } } where current is
+--                the 0-indexed position in the list (#list after the newest jump),
+--                line is 1-indexed, col a 0-indexed byte column and text the line
+--                (empty if the buffer is not loaded)
+function M.get_jumplist()
+    local list, current = unpack(vim.fn.getjumplist())
+    local entries = {}
+    for _, jump in ipairs(list) do
+        local name = vim.fn.bufname(jump.bufnr)
+        local text = ''
+        if vim.api.nvim_buf_is_loaded(jump.bufnr) then
+            text = vim.api.nvim_buf_get_lines(jump.bufnr, jump.lnum - 1, jump.lnum, false)[1] or ''
+        end
+        local file = name ~= '' and vim.fn.fnamemodify(name, ':p') or ''
+        table.insert(entries, { file, jump.lnum, jump.col, text })
+    end
+    return { current, entries }
+end
+
+-- Find the first tag named `name` in the tags files ('tags' option)
+-- @param name string: Identifier to look up
+-- @return table|nil: { file, line, col } where file is absolute, line 1-indexed
+--                    and col the 0-indexed byte column of the name in the line,
+--                    nil if there is no tags file or no tag with that name
+function M.find_tag(name)
+    if #vim.fn.tagfiles() == 0 then
+        return nil
+    end
+    local ok, tags = pcall(vim.fn.taglist, '^' .. vim.fn.escape(name, '\\^$.*[]~') .. '$')
+    if not ok or #tags == 0 then
+        return nil
+    end
+    local tag = tags[1]
+    local file = vim.fn.fnamemodify(tag.filename, ':p')
+    if vim.fn.filereadable(file) == 0 then
+        return nil
+    end
+    local lines = vim.fn.readfile(file)
+
+    -- The tag's Ex command is a line number or a search (/^text$/, 'magic' off)
+    local line = tonumber(tag.cmd:match('^(%d+)'))
+    if not line then
+        local delim = tag.cmd:sub(1, 1)
+        local pattern = tag.cmd:sub(2):gsub(';?"?$', '')
+        if pattern:sub(-1) == delim then
+            pattern = pattern:sub(1, -2)
+        end
+        -- The delimiter is escaped inside the pattern (\/)
+        pattern = '\\M' .. pattern:gsub('\\%' .. delim, delim)
+        for i, text in ipairs(lines) do
+            if vim.fn.match(text, pattern) >= 0 then
+                line = i
+                break
+            end
+        end
+    end
+    if not line or not lines[line] then
+        return nil
+    end
+    local col = (lines[line]:find(name, 1, true) or 1) - 1
+    return { file, line, col }
+end
+
+-- Keys typed in Godot's insert mode while recording a macro (Neovim never sees
+-- them). A marker is typed into the recording in their place; when recording stops
+-- the markers are replaced with these keys, in order.
+M._macro_inserts = {}
+
+-- Marker typed into the recording (an empty command: no effect on the buffer)
+M.MACRO_INSERT_MARKER = '<Cmd><CR>'
+
+-- Queue Godot-side insert keys for the next marker in the recording
+-- @param keys string: Keys in Neovim notation (e.g., 'foo<CR>bar')
+function M.macro_insert(keys)
+    table.insert(M._macro_inserts, keys)
+end
+
+-- Replace the markers in a just recorded register with the queued insert keys
+-- Called (scheduled) after RecordingLeave, once the register has been written
+-- @param regname string: Register the macro was recorded into
+function M.macro_finish(regname)
+    local inserts = M._macro_inserts
+    M._macro_inserts = {}
+    if #inserts == 0 then
+        return
+    end
+
+    local function termcodes(keys)
+        return vim.api.nvim_replace_termcodes(keys, true, true, true)
+    end
+    local marker = termcodes(M.MACRO_INSERT_MARKER)
+    local contents = vim.fn.getreg(regname)
+    local parts = {}
+    local pos = 1
+    for _, keys in ipairs(inserts) do
+        local first, last = contents:find(marker, pos, true)
+        if not first then
+            break
+        end
+        table.insert(parts, contents:sub(pos, first - 1))
+        table.insert(parts, termcodes(keys))
+        pos = last + 1
+    end
+    table.insert(parts, contents:sub(pos))
+    vim.fn.setreg(regname, table.concat(parts), 'v')
+end
+
+-- Open edit transaction: bulk edits made by the plugin (e.g., :s, :g, LSP rename)
+-- until commit are a single undo step. nil when no transaction is open.
+M._transaction = nil
+
+-- Begin an edit transaction in the current buffer
+function M.transaction_begin()
+    M._transaction = { tick = vim.api.nvim_buf_get_changedtick(0) }
+end
+
+-- Join the next change to the transaction's previous changes (undojoin)
+-- Call right before each change made inside a transaction
+function M.transaction_join()
+    local transaction = M._transaction
+    if transaction and vim.api.nvim_buf_get_changedtick(0) ~= transaction.tick then
+        -- Fails after undo (E790); the change then starts a new undo step
+        pcall(vim.cmd, 'undojoin')
+    end
+end
+
+-- Run an Ex command as part of the open transaction
+-- @param cmd string: Ex command (e.g., '%s/foo/bar/g')
+function M.transaction_command(cmd)
+    M.transaction_join()
+    vim.cmd(cmd)
+end
+
+-- Commit the open transaction
+-- @return number: changedtick after the transaction's last change
+function M.transaction_commit()
+    M._transaction = nil
+    return vim.api.nvim_buf_get_changedtick(0)
+end
+
 -- Convert character column to byte column for a given line
 -- Godot uses character positions, Neovim uses byte positions
 -- For multi-byte characters (e.g., Japanese), this conversion is essential
@@ -64,4 +273,15 @@ function M.char_col_to_byte_col(line, char_col)
     return byte_col
 end
 
+-- Convert byte column to character column for a given line
+-- @param line string: The line content
+-- @param byte_col number: Byte column (0-indexed)
+-- @return number: Character column (0-indexed)
+function M.byte_col_to_char_col(line, byte_col)
+    if not line or byte_col <= 0 then
+        return 0
+    end
+    return vim.fn.strchars(line:sub(1, byte_col))
+end
+
 return M
@@ -0,0 +1,145 @@
+-- godot_neovim/format.lua: Format GDScript with an external formatter (gdformat)
+--
+-- 'formatexpr' makes every gq (gq{motion}, gqq, Visual gq, counts and `.`) pipe
+-- the lines through the formatter; format_range() (:format in Godot) and :Format
+-- do the same for a range, the whole buffer by default. The result replaces the
+-- lines with one nvim_buf_set_lines call, so it is a single undo step and reaches
+-- Godot like any other change.
+
+local M = {}
+
+-- Formatter executable ("" uses Neovim's own formatting)
+M._formatter = ''
+
+local FORMATEXPR = "v:lua.require'godot_neovim'.format.formatexpr()"
+
+-- Longest whitespace prefix shared by the non-blank lines
+local function common_indent(lines)
+    local indent
+    for _, line in ipairs(lines) do
+        if line:find('%S') then
+            local lead = line:match('^%s*')
+            if indent == nil then
+                indent = lead
+            else
+                local len = 0
+                while len < #indent and len < #lead and indent:byte(len + 1) == lead:byte(len + 1) do
+                    len = len + 1
+                end
+                indent = indent:sub(1, len)
+            end
+        end
+    end
+    return indent or ''
+end
+
+-- First meaningful line of the formatter's error output
+local function error_summary(output, code)
+    for i = #output, 1, -1 do
+        local line = vim.trim(output[i])
+        if line ~= '' then
+            return line
+        end
+    end
+    return 'exit code ' .. code
+end
+
+local function is_gdscript(bufnr)
+    return vim.api.nvim_buf_get_name(bufnr):match('%.gd$') ~= nil
+end
+
+-- Set the formatter and make gq use it in all buffers
+-- @param path string: Formatter executable ("" turns it off)
+function M.set_formatter(path)
+    M._formatter = path or ''
+    vim.go.formatexpr = FORMATEXPR
+    for _, bufnr in ipairs(vim.api.nvim_list_bufs()) do
+        vim.bo[bufnr].formatexpr = FORMATEXPR
+    end
+end
+
+-- Format lines first..last (1-indexed, inclusive) of the current buffer
+-- A range inside a block is formatted without its common indent, which is put back
+-- @return string|nil: The error, nil when the lines were formatted
+function M.format_lines(first, last)
+    local name = vim.fn.fnamemodify(M._formatter, ':t')
+    if M._formatter == '' then
+        return 'No formatter set (Formatter Executable Path)'
+    end
+    if not is_gdscript(0) then
+        return name .. ': Not a GDScript buffer'
+    end
+    if vim.fn.executable(M._formatter) ~= 1 then
+        return name .. ': Executable not found: ' .. M._formatter
+    end
+
+    local lines = vim.api.nvim_buf_get_lines(0, first - 1, last, false)
+    local indent = common_indent(lines)
+    local input = {}
+    for i, line in ipairs(lines) do
+        input[i] = line:find('%S') and line:sub(#indent + 1) or ''
+    end
+
+    local output = vim.fn.systemlist({ M._formatter, '-' }, input)
+    if vim.v.shell_error ~= 0 then
+        return name .. ': ' .. error_summary(output, vim.v.shell_error)
+    end
+
+    local formatted = {}
+    for i, line in ipairs(output) do
+        line = line:gsub('\r$', '')
+        formatted[i] = line ~= '' and indent .. line or ''
+    end
+    if not vim.deep_equal(formatted, lines) then
+        vim.api.nvim_buf_set_lines(0, first - 1, last, false, formatted)
+    end
+
+    -- Like gq: the cursor goes to the last formatted line
+    local cursor_line = math.max(first, first + #formatted - 1)
+    vim.api.nvim_win_set_cursor(0, { math.min(cursor_line, vim.api.nvim_buf_line_count(0)), 0 })
+    vim.cmd('normal! ^')
+    return nil
+end
+
+-- Format an Ex range of the current buffer (:format in Godot)
+-- @param range string: Ex range ('<,'>, 10,20, ...), "" for the whole buffer
+-- @return string|nil: The error, nil when the lines were formatted
+function M.format_range(range)
+    local first, last = 1, vim.api.nvim_buf_line_count(0)
+    if range ~= '' then
+        local ok, parsed = pcall(vim.api.nvim_parse_cmd, range .. 'Format', {})
+        if not ok then
+            return 'E16: Invalid range'
+        end
+        first = parsed.range[1]
+        last = parsed.range[2] or first
+    end
+    return M.format_lines(first, last)
+end
+
+-- 'formatexpr': 0 when the lines were handled, 1 to use Neovim's formatting
+function M.formatexpr()
+    -- Auto-wrap while typing ('textwidth'), other buffers and a formatter that is
+    -- not installed keep the built-in formatting
+    if M._formatter == '' or vim.v.char ~= '' or not is_gdscript(0)
+        or vim.fn.executable(M._formatter) ~= 1 then
+        return 1
+    end
+    local err = M.format_lines(vim.v.lnum, vim.v.lnum + vim.v.count - 1)
+    if err then
+        vim.notify(err, vim.log.levels.ERROR)
+    end
+    return 0
+end
+
+-- :[range]Format - Format the range (default: the whole buffer)
+function M.setup_commands()
+    vim.api.nvim_create_user_command('Format', function(opts)
+        local err = M.format_lines(opts.line1, opts.line2)
+        if err then
+            vim.notify(err, vim.log.levels.ERROR)
+        end
+    end, { range = '%' })
+end
+
+return M
@@ -3,10 +3,19 @@
 
 local M = {}
 
+-- Addon version (must match the library; checked at plugin activation)
+M.VERSION = '1.0.2'
+
 -- Load submodules
 local core = require('godot_neovim.core')
 local buffer = require('godot_neovim.buffer')
 local integration = require('godot_neovim.integration')
+local search = require('godot_neovim.search')
+local surround = require('godot_neovim.surround')
+local comment = require('godot_neovim.comment')
+local replace = require('godot_neovim.replace')
+local format = require('godot_neovim.format')
+local clipboard = require('godot_neovim.clipboard')
 
 -- Inject integration function into buffer module to avoid circular dependency
 buffer._setup_buffer_autocmds = integration.setup_buffer_autocmds
@@ -15,22 +24,72 @@ buffer._setup_buffer_autocmds = integration.setup_buffer_autocmds
 M.core = core
 M.buffer = buffer
 M.integration = integration
+M.search = search
+M.surround = surround
+M.comment = comment
+M.replace = replace
+M.format = format
+M.clipboard = clipboard
 
 -- Backward-compatible API: Buffer operations
 M.buffer_register = buffer.buffer_register
 M.buffer_register_and_attach = buffer.buffer_register_and_attach
 M.buffer_update = buffer.buffer_update
 M.switch_to_buffer = buffer.switch_to_buffer
+M.buffer_append_lines = buffer.buffer_append_lines
 M.get_buffer_info = buffer.get_buffer_info
+M.list_buffers = buffer.list_buffers
 M.reload_buffer = buffer.reload_buffer
 M.set_indent_options = buffer.set_indent_options
+M.set_comment_continuation = buffer.set_comment_continuation
 M.set_visual_selection = buffer.set_visual_selection
+M.text_object_range = buffer.text_object_range
 M.join_no_space = buffer.join_no_space
+M.apply_text_edits = buffer.apply_text_edits
+M.set_undo_dir = buffer.set_undo_dir
+M.write_undo_file = buffer.write_undo_file
+
+-- Search helpers
+M.find_matches = search.find_matches
+M.search_state = search.search_state
+M.find_line_matches = search.find_line_matches
+M.substitute_preview = search.substitute_preview
+M.find_char_jump = search.find_char_jump
+M.sneak_jump = search.sneak_jump
+
+-- Surround (ys, cs, ds)
+M.surround_add = surround.add
+M.surround_delete = surround.delete
+M.surround_change = surround.change
+
+-- Comment toggle (gc, gcc)
+M.comment_operator = comment.operator
+
+-- Replace with register (gr) and exchange (cx)
+M.replace_operator = replace.operator
+M.exchange_operator = replace.exchange_operator
+M.exchange_clear = replace.exchange_clear
+
+-- Formatting (gq, :Format)
+M.set_formatter = format.set_formatter
+
+-- Clipboard provider ("+, "*, clipboard=unnamedplus)
+M.clipboard_load = clipboard.load
 
 -- Backward-compatible API: Core functions
 M.send_keys = core.send_keys
 M.get_state = core.get_state
 M.get_changedtick = core.get_changedtick
+M.get_registers = core.get_registers
+M.get_marks = core.get_marks
+M.restore_marks = core.restore_marks
+M.get_jumplist = core.get_jumplist
+M.find_tag = core.find_tag
+M.get_user_mappings = integration.get_user_mappings
+M.macro_insert = core.macro_insert
+M.transaction_begin = core.transaction_begin
+M.transaction_command = core.transaction_command
+M.transaction_commit = core.transaction_commit
 
 -- Backward-compatible API: State (direct reference to core tables)
 M._initialized_buffers = core._initialized_buffers
@@ -45,8 +104,11 @@ function M.setup()
 
     -- Setup autocmds and commands
     integration.setup_autocmds()
+    integration.setup_autocmd_bridge()
     integration.setup_file_commands()
     integration.setup_debug_command()
+    format.setup_commands()
+    clipboard.setup()
 end
 
 -- Auto-setup on require
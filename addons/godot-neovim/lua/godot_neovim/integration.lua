@@ -4,6 +4,29 @@ local core = require('godot_neovim.core')
 
 local M = {}
 
+-- Autocmds forwarded to the plugin by the autocmd bridge (typed in events.rs AutocmdEvent)
+-- TextYankPost is forwarded with the yanked text and region by setup_autocmds
+M.BRIDGED_AUTOCMDS = { 'InsertLeave', 'CursorHold', 'BufWritePost' }
+
+-- Forward the bridged autocmds as godot_autocmd notifications:
+-- { event, buf, file, line, col } with the cursor as 0-indexed line and character column
+function M.setup_autocmd_bridge()
+    local augroup = vim.api.nvim_create_augroup('godot_neovim_bridge', { clear = true })
+    vim.api.nvim_create_autocmd(M.BRIDGED_AUTOCMDS, {
+        group = augroup,
+        callback = function(ev)
+            local cursor = vim.api.nvim_win_get_cursor(0)
+            vim.rpcnotify(0, "godot_autocmd", {
+                event = ev.event,
+                buf = ev.buf,
+                file = ev.file,
+                line = cursor[1] - 1,
+                col = core.byte_col_to_char_col(vim.api.nvim_get_current_line(), cursor[2]),
+            })
+        end
+    })
+end
+
 -- Setup global autocmds
 function M.setup_autocmds()
     -- Create autocmd group for godot-neovim
@@ -39,18 +62,56 @@ function M.setup_autocmds()
     })
 
     -- Send buffer enter notification (for Ctrl+O/Ctrl+I cross-buffer jumps)
-    -- This fires when entering a buffer, allowing Godot to sync script tabs
+    -- This fires when entering a buffer, allowing Godot to follow with its script tab
     vim.api.nvim_create_autocmd('BufEnter', {
         group = augroup,
         callback = function()
             local bufnr = vim.api.nvim_get_current_buf()
             local path = vim.api.nvim_buf_get_name(bufnr)
-            -- Only notify for initialized buffers (managed by godot-neovim)
-            if core._initialized_buffers[bufnr] and path ~= '' then
+            -- Notify for buffers managed by godot-neovim, and for files Neovim
+            -- loaded itself (a jump into a script that is not open in Godot)
+            local is_file = vim.bo[bufnr].buftype == '' and vim.fn.filereadable(path) == 1
+            if path ~= '' and (core._initialized_buffers[bufnr] or is_file) then
                 vim.rpcnotify(0, "godot_buf_enter", bufnr, path)
             end
         end
     })
+
+    -- Send yanked text (for the "+ / "* OS clipboard sync) and the yanked
+    -- region (for highlight on yank). The region comes from the '[ and '] marks,
+    -- converted to 0-indexed lines and character columns (end inclusive).
+    vim.api.nvim_create_autocmd('TextYankPost', {
+        group = augroup,
+        callback = function()
+            local event = vim.v.event
+            local start_mark = vim.api.nvim_buf_get_mark(0, '[')
+            local end_mark = vim.api.nvim_buf_get_mark(0, ']')
+            local start_line = vim.fn.getline(start_mark[1])
+            local end_line = vim.fn.getline(end_mark[1])
+            vim.rpcnotify(0, "godot_yank", {
+                regname = event.regname,
+                text = table.concat(event.regcontents, "\n"),
+                operator = event.operator,
+                regtype = event.regtype,
+                start_line = start_mark[1] - 1,
+                start_col = core.byte_col_to_char_col(start_line, start_mark[2]),
+                end_line = end_mark[1] - 1,
+                end_col = core.byte_col_to_char_col(end_line, end_mark[2]),
+            })
+        end
+    })
+
+    -- Splice keys typed in Godot's insert mode into a just recorded macro
+    -- Scheduled: the register is written after RecordingLeave
+    vim.api.nvim_create_autocmd('RecordingLeave', {
+        group = augroup,
+        callback = function()
+            local regname = vim.v.event.regname
+            vim.schedule(function()
+                core.macro_finish(regname)
+            end)
+        end
+    })
 end
 
 -- Setup file commands (:q, :wq, etc.) to delegate to Godot
@@ -102,8 +163,34 @@ function M.setup_file_commands()
     ]])
 
     -- ZZ and ZQ mappings
-    vim.keymap.set('n', 'ZZ', '<Cmd>Wq<CR>', { silent = true })
-    vim.keymap.set('n', 'ZQ', '<Cmd>Quit!<CR>', { silent = true })
+    vim.keymap.set('n', 'ZZ', '<Cmd>Wq<CR>', { silent = true, desc = 'godot-neovim' })
+    vim.keymap.set('n', 'ZQ', '<Cmd>Quit!<CR>', { silent = true, desc = 'godot-neovim' })
+end
+
+-- Left-hand sides of the Normal mode mappings defined by the user's config
+-- Neovim's default mappings are recorded in g:godot_neovim_default_maps before
+-- the user config runs (--cmd); they are skipped unless the config changed them.
+-- <Plug>/<SNR> mappings and the mappings of this plugin are skipped too.
+-- @return table: List of lhs in key notation (e.g. "<Space>w")
+function M.get_user_mappings()
+    local defaults = vim.g.godot_neovim_default_maps
+    if type(defaults) ~= 'table' then
+        defaults = {}
+    end
+    local result = {}
+    for _, map in ipairs(vim.api.nvim_get_keymap('n')) do
+        local lhs = map.lhs
+        local signature = (map.rhs or '') .. '|' .. (map.desc or '')
+        if lhs
+            and defaults[lhs] ~= signature
+            and map.desc ~= 'godot-neovim'
+            and not lhs:match('^<Plug>')
+            and not lhs:match('^<SNR>')
+        then
+            table.insert(result, lhs)
+        end
+    end
+    return result
 end
 
 -- Setup buffer-local autocmds for BufWriteCmd (vscode-neovim style)
@@ -0,0 +1,154 @@
+-- godot_neovim/replace.lua: Replace with register (gr{motion}) and exchange (cx{motion})
+--
+-- Rust sends operator() / exchange_operator() in a <Cmd> before g@, so both run
+-- as 'operatorfunc': one undo step, and `.` repeats them. The text is replaced
+-- with nvim_buf_set_text, which leaves the registers untouched.
+
+local M = {}
+
+-- Register of the last replace (reused by `.`)
+M._register = '"'
+
+-- First range of a pending exchange: { buf, extmark id, linewise }
+M._exchange = nil
+
+local ns = vim.api.nvim_create_namespace('godot_neovim_exchange')
+
+local function get_line(row)
+    return vim.api.nvim_buf_get_lines(0, row - 1, row, false)[1] or ''
+end
+
+-- Byte length of the character starting at a 0-indexed byte column
+local function char_len_at(line, col)
+    local char = line:sub(col + 1):match('^[%z\1-\127\194-\244][\128-\191]*')
+    return char and #char or 0
+end
+
+-- Range of the last operator motion
+-- @return table: { start_row, start_col, end_row, end_col } (0-indexed, end exclusive)
+local function operator_range(type)
+    local s = vim.api.nvim_buf_get_mark(0, '[')
+    local e = vim.api.nvim_buf_get_mark(0, ']')
+    if type == 'line' then
+        return { s[1] - 1, 0, e[1] - 1, #get_line(e[1]) }
+    end
+    return { s[1] - 1, s[2], e[1] - 1, e[2] + char_len_at(get_line(e[1]), e[2]) }
+end
+
+-- Extend a range to whole lines
+local function linewise(range)
+    return { range[1], 0, range[3], #get_line(range[3] + 1) }
+end
+
+local function get_text(range)
+    return vim.api.nvim_buf_get_text(0, range[1], range[2], range[3], range[4], {})
+end
+
+local function set_text(range, lines)
+    vim.api.nvim_buf_set_text(0, range[1], range[2], range[3], range[4], lines)
+end
+
+-- Whether position a (row, col) is before position b
+local function before(a_row, a_col, b_row, b_col)
+    return a_row < b_row or (a_row == b_row and a_col < b_col)
+end
+
+function M.opfunc(type)
+    local lines = vim.fn.getreg(M._register, 1, true)
+    if #lines == 0 then
+        return
+    end
+    local range = operator_range(type)
+    -- A linewise register replaces whole lines like a linewise motion
+    if type == 'line' or vim.fn.getregtype(M._register) == 'V' then
+        range = linewise(range)
+    end
+    set_text(range, lines)
+    vim.api.nvim_win_set_cursor(0, { range[1] + 1, range[2] })
+end
+
+-- Make g@ the replace-with-register operator
+-- @param register string: Register to put ('"' for the unnamed register)
+function M.operator(register)
+    M._register = register
+    vim.o.operatorfunc = "v:lua.require'godot_neovim.replace'.opfunc"
+end
+
+-- Cancel a pending exchange (cxc)
+function M.exchange_clear()
+    if M._exchange then
+        pcall(vim.api.nvim_buf_del_extmark, M._exchange[1], ns, M._exchange[2])
+    end
+    M._exchange = nil
+end
+
+-- Range of the pending exchange, if it is still in the current buffer
+local function pending_range()
+    local pending = M._exchange
+    if not pending or pending[1] ~= vim.api.nvim_get_current_buf() then
+        return nil
+    end
+    local mark = vim.api.nvim_buf_get_extmark_by_id(0, ns, pending[2], { details = true })
+    if #mark == 0 then
+        return nil
+    end
+    return { mark[1], mark[2], mark[3].end_row, mark[3].end_col }
+end
+
+function M.opfunc_exchange(type)
+    local range = operator_range(type)
+    local first = pending_range()
+    if not first then
+        -- First cx: remember the range until the second one
+        M.exchange_clear()
+        local id = vim.api.nvim_buf_set_extmark(0, ns, range[1], range[2], {
+            end_row = range[3],
+            end_col = range[4],
+            right_gravity = false,
+            end_right_gravity = true,
+        })
+        M._exchange = { vim.api.nvim_get_current_buf(), id, type == 'line' }
+        return
+    end
+
+    local first_linewise = M._exchange[3]
+    M.exchange_clear()
+    -- Either range being linewise exchanges whole lines
+    if first_linewise or type == 'line' then
+        first, range = linewise(first), linewise(range)
+    end
+
+    local a, b = first, range
+    if before(b[1], b[2], a[1], a[2]) then
+        a, b = b, a
+    end
+    if before(b[1], b[2], a[3], a[4]) then
+        vim.notify('[godot-neovim] Exchange: ranges overlap', vim.log.levels.WARN)
+        return
+    end
+
+    local a_text, b_text = get_text(a), get_text(b)
+    -- Later range first, so the earlier one stays valid
+    set_text(b, a_text)
+    set_text(a, b_text)
+
+    -- Cursor to the start of the second range, where its text now is
+    local row, col = range[1], range[2]
+    if range == b then
+        local end_row = a[1] + #b_text - 1
+        local end_col = #b_text == 1 and a[2] + #b_text[1] or #b_text[#b_text]
+        if b[1] == a[3] then
+            row, col = end_row, end_col + (b[2] - a[4])
+        else
+            row = b[1] + (end_row - a[3])
+        end
+    end
+    vim.api.nvim_win_set_cursor(0, { row + 1, col })
+end
+
+-- Make g@ the exchange operator
+function M.exchange_operator()
+    vim.o.operatorfunc = "v:lua.require'godot_neovim.replace'.opfunc_exchange"
+end
+
+return M
@@ -0,0 +1,267 @@
+-- godot_neovim/search.lua: Search helpers (incremental search, hlsearch, substitute preview,
+-- f/t across lines, sneak)
+
+local core = require('godot_neovim.core')
+
+local M = {}
+
+-- Matches of a pattern in a single line
+-- @param all boolean: All matches (false: first match only, like :s without g)
+-- @return table: Array of { start_col, end_col } in character columns (empty for
+--                invalid patterns)
+local function find_matches_in_line(line, pattern, all)
+    local matches = {}
+    local start = 0
+    while start <= #line do
+        local ok, result = pcall(vim.fn.matchstrpos, line, pattern, start)
+        if not ok then
+            return {}
+        end
+        local s, e = result[2], result[3]
+        if s < 0 then
+            break
+        end
+        table.insert(matches, {
+            core.byte_col_to_char_col(line, s),
+            core.byte_col_to_char_col(line, e),
+        })
+        if not all then
+            break
+        end
+        start = e > s and e or s + 1
+    end
+    return matches
+end
+
+-- Whether a pattern contains an upper case letter (like Vim's pat_has_uppercase)
+-- The character after a backslash is not counted: \S and \W are classes
+local function has_uppercase(pattern)
+    local i = 1
+    while i <= #pattern do
+        local c = pattern:sub(i, i)
+        if c == '\\' then
+            -- \_x and \%x take one more character
+            local next = pattern:sub(i + 1, i + 1)
+            i = i + ((next == '_' or next == '%') and 3 or 2)
+        elseif c:match('%u') then
+            return true
+        else
+            i = i + 1
+        end
+    end
+    return false
+end
+
+-- Apply 'ignorecase' and 'smartcase' to a pattern by prefixing \c or \C
+-- matchstrpos() and substitute() honor 'ignorecase' but never 'smartcase', so every
+-- local match goes through here to agree with the search Neovim executes
+-- @param smartcase boolean: Whether 'smartcase' applies (false for * and #, like Vim)
+-- @return string: Pattern with an explicit case flag (unchanged if it has \c or \C)
+function M.apply_search_case(pattern, smartcase)
+    if pattern:find('\\[cC]') then
+        return pattern
+    end
+    if not vim.o.ignorecase then
+        return '\\C' .. pattern
+    end
+    if smartcase and vim.o.smartcase and has_uppercase(pattern) then
+        return '\\C' .. pattern
+    end
+    return '\\c' .. pattern
+end
+
+-- Find all matches of a Vim search pattern in the current buffer
+-- Uses Vim regex semantics ('magic', and 'ignorecase' / 'smartcase' via apply_search_case)
+-- @param pattern string: Search pattern (without the leading / or ?)
+-- @param max_matches number: Stop after this many matches
+-- @param smartcase boolean: Whether 'smartcase' applies (typed patterns)
+-- @return table: Array of { line, start_col, end_col } (0-indexed line, character
+--                columns, end exclusive). Empty for invalid patterns.
+function M.find_matches(pattern, max_matches, smartcase)
+    local matches = {}
+    if pattern == '' then
+        return matches
+    end
+    pattern = M.apply_search_case(pattern, smartcase ~= false)
+
+    local lines = vim.api.nvim_buf_get_lines(0, 0, -1, false)
+    for lnum, line in ipairs(lines) do
+        for _, m in ipairs(find_matches_in_line(line, pattern, true)) do
+            table.insert(matches, { lnum - 1, m[1], m[2] })
+            if #matches >= max_matches then
+                return matches
+            end
+        end
+    end
+
+    return matches
+end
+
+-- State of the last search pattern (for the search index, 'hlsearch' and [n/N] count)
+-- @param smartcase boolean: Whether 'smartcase' applies (false after * and #)
+-- @return table: { pattern, case_pattern, hlsearch }: the last search pattern ('' if
+--                none), the pattern with its case flag applied, and whether matches are
+--                highlighted ('hlsearch' set and not suspended by :nohlsearch)
+function M.search_state(smartcase)
+    local pattern = vim.fn.getreg('/')
+    local case_pattern = pattern ~= '' and M.apply_search_case(pattern, smartcase) or ''
+    return { pattern, case_pattern, vim.o.hlsearch and vim.v.hlsearch == 1 }
+end
+
+-- Matches of a pattern per line, for a range of lines of the current buffer
+-- @param pattern string: Pattern with its case flag applied (see search_state)
+-- @param first number: First line (0-indexed)
+-- @param last number: Last line (exclusive, -1 for the end of the buffer)
+-- @return table: One entry per line: array of { start_col, end_col } (character columns)
+function M.find_line_matches(pattern, first, last)
+    local result = {}
+    for _, line in ipairs(vim.api.nvim_buf_get_lines(0, first, last, false)) do
+        table.insert(result, find_matches_in_line(line, pattern, true))
+    end
+    return result
+end
+
+-- Split the part after the command name of :s into pattern, replacement and flags
+-- Backslash-escaped delimiters are kept in place (\/ stays \/)
+-- @return pattern, replacement (nil until the second delimiter is typed), flags
+local function split_substitute_arg(arg)
+    local delim = arg:sub(1, 1)
+    local parts = { '' }
+    local i = 2
+    while i <= #arg do
+        local c = arg:sub(i, i)
+        if c == '\\' and i < #arg then
+            parts[#parts] = parts[#parts] .. arg:sub(i, i + 1)
+            i = i + 2
+        else
+            if c == delim and #parts < 3 then
+                table.insert(parts, '')
+            else
+                parts[#parts] = parts[#parts] .. c
+            end
+            i = i + 1
+        end
+    end
+    return parts[1], parts[2], parts[3] or ''
+end
+
+-- Preview a :substitute command without modifying the buffer ('inccommand')
+-- @param cmdline string: Ex command as typed (without the leading ':')
+-- @param max_lines number: Stop after this many affected lines
+-- @return table|nil: nil if cmdline is not a :substitute command. Otherwise an array of
+--                    { line, text, start_col, end_col }: 0-indexed line, the line text
+--                    after substitution and a highlighted region in character columns
+--                    (end exclusive). Until the replacement is typed, text is the
+--                    original line and each match is one entry.
+function M.substitute_preview(cmdline, max_lines)
+    -- Skip the range (numbers, . $ % , ; + - and 'marks)
+    local rest = cmdline
+    repeat
+        local prev = rest
+        rest = rest:gsub("^[%s%d%.,;%$%%%+%-]+", ""):gsub("^'.", "")
+    until rest == prev
+
+    local name, arg = rest:match("^(s%a*)(.*)$")
+    if not name or ("substitute"):sub(1, #name) ~= name or arg == '' then
+        return nil
+    end
+    if arg:sub(1, 1):match('[%w%s"|\\]') then
+        return nil
+    end
+
+    local ok, parsed = pcall(vim.api.nvim_parse_cmd, cmdline, {})
+    if not ok or parsed.cmd ~= 'substitute' then
+        return nil
+    end
+    local cursor_line = vim.api.nvim_win_get_cursor(0)[1]
+    local line1 = parsed.range[1] or cursor_line
+    local line2 = parsed.range[2] or line1
+    -- Opened from visual mode: the '< '> marks are only set when the selection ends
+    if cmdline:match("^'<,'>") and vim.fn.mode():match('^[vV\22]') then
+        line1 = math.min(vim.fn.line('v'), cursor_line)
+        line2 = math.max(vim.fn.line('v'), cursor_line)
+    end
+
+    local pattern, replacement, flags = split_substitute_arg(arg)
+    if pattern == '' then
+        pattern = vim.fn.getreg('/')
+    end
+    if pattern == '' then
+        return {}
+    end
+    if flags:find('i', 1, true) then
+        pattern = '\\c' .. pattern
+    elseif flags:find('I', 1, true) then
+        pattern = '\\C' .. pattern
+    else
+        pattern = M.apply_search_case(pattern, true)
+    end
+    local sub_flags = flags:find('g', 1, true) and 'g' or ''
+
+    local results = {}
+    local lines = vim.api.nvim_buf_get_lines(0, line1 - 1, line2, false)
+    for offset, line in ipairs(lines) do
+        local lnum = line1 - 1 + offset - 1
+        if replacement == nil then
+            -- Pattern only: highlight the matches
+            for _, m in ipairs(find_matches_in_line(line, pattern, sub_flags == 'g')) do
+                table.insert(results, { lnum, line, m[1], m[2] })
+            end
+        else
+            local ok_sub, new_line = pcall(vim.fn.substitute, line, pattern, replacement, sub_flags)
+            if not ok_sub then
+                return {}
+            end
+            if new_line ~= line then
+                -- Highlight the changed region (common prefix/suffix are unchanged)
+                local prefix = 0
+                while prefix < #line and prefix < #new_line
+                    and line:byte(prefix + 1) == new_line:byte(prefix + 1) do
+                    prefix = prefix + 1
+                end
+                local suffix = 0
+                while suffix < #line - prefix and suffix < #new_line - prefix
+                    and line:byte(#line - suffix) == new_line:byte(#new_line - suffix) do
+                    suffix = suffix + 1
+                end
+                table.insert(results, {
+                    lnum,
+                    new_line,
+                    core.byte_col_to_char_col(new_line, prefix),
+                    core.byte_col_to_char_col(new_line, #new_line - suffix),
+                })
+            end
+        end
+        if #results >= max_lines then
+            break
+        end
+    end
+
+    return results
+end
+
+-- Move the cursor to a character f/t found on another line (Find Across Lines)
+-- Neovim's f/t only search the cursor line; the character becomes the one of ; and ,
+-- @param char string: Character of the find
+-- @param forward boolean: f/t (true) or F/T (false)
+-- @param till boolean: t/T (true) or f/F (false)
+-- @param line number: Target line (1-indexed)
+-- @param col number: Target character column (1-indexed)
+function M.find_char_jump(char, forward, till, line, col)
+    vim.fn.setcharsearch({
+        char = char,
+        forward = forward and 1 or 0,
+        ['until'] = till and 1 or 0,
+    })
+    vim.fn.setcursorcharpos(line, col)
+end
+
+-- Move the cursor to a sneak target (s{char}{char}), with a jumplist entry
+-- @param line number: Target line (1-indexed)
+-- @param col number: Target character column (1-indexed)
+function M.sneak_jump(line, col)
+    vim.cmd("normal! m'")
+    vim.fn.setcursorcharpos(line, col)
+end
+
+return M
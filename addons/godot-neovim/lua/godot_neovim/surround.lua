@@ -0,0 +1,201 @@
+-- godot_neovim/surround.lua: vim-surround style ys, cs and ds
+--
+-- Rust collects the whole command and calls add/delete/change. The edit itself
+-- runs as 'operatorfunc' through g@, so `.` repeats it with the same characters.
+
+local M = {}
+
+-- Characters of the last command of each kind (reused by `.`)
+M._add_char = nil
+M._delete_char = nil
+M._change_chars = nil
+
+-- Brackets by surround character: the opening bracket adds (and removes) inner
+-- spaces, the closing bracket and the alias do not
+local PAIRS = {
+    ['('] = { '( ', ' )' }, [')'] = { '(', ')' }, b = { '(', ')' },
+    ['{'] = { '{ ', ' }' }, ['}'] = { '{', '}' }, B = { '{', '}' },
+    ['['] = { '[ ', ' ]' }, [']'] = { '[', ']' }, r = { '[', ']' },
+    ['<'] = { '< ', ' >' }, ['>'] = { '<', '>' }, a = { '<', '>' },
+}
+
+local OPFUNC = "v:lua.require'godot_neovim.surround'.opfunc_"
+
+-- Text put around the target for a surround character
+-- @return string, string: Opening and closing text
+local function surrounding(char)
+    local pair = PAIRS[char]
+    if pair then
+        return pair[1], pair[2]
+    end
+    return char, char
+end
+
+local function get_line(row)
+    return vim.api.nvim_buf_get_lines(0, row - 1, row, false)[1] or ''
+end
+
+-- Byte length of the character starting at a 0-indexed byte column
+local function char_len_at(line, col)
+    local char = line:sub(col + 1):match('^[%z\1-\127\194-\244][\128-\191]*')
+    return char and #char or 0
+end
+
+-- Find the pair of surround characters around the cursor
+-- Quotes are paired up on the cursor line (backslash escapes skipped); brackets
+-- are matched with nesting across lines.
+-- @return table|nil, table|nil, number: Opening and closing { row, byte_col }
+--                                       and the byte length of each character
+local function find_pair(char)
+    local open, close = surrounding(char)
+    open, close = vim.trim(open), vim.trim(close)
+    local cursor = vim.api.nvim_win_get_cursor(0)
+    local line = get_line(cursor[1])
+
+    if open == close then
+        local positions = {}
+        local i = 1
+        while i <= #line do
+            local c = line:sub(i, i + #open - 1)
+            if line:sub(i, i) == '\\' then
+                i = i + 1
+            elseif c == open then
+                table.insert(positions, i - 1)
+            end
+            i = i + 1
+        end
+        -- The pair around the cursor, else the next pair on the line (like vim-surround)
+        local next_pair = nil
+        for k = 1, #positions - 1, 2 do
+            local s, e = positions[k], positions[k + 1]
+            if s <= cursor[2] and cursor[2] <= e then
+                return { cursor[1], s }, { cursor[1], e }, #open
+            end
+            if not next_pair and s > cursor[2] then
+                next_pair = { s, e }
+            end
+        end
+        if next_pair then
+            return { cursor[1], next_pair[1] }, { cursor[1], next_pair[2] }, #open
+        end
+        return nil, nil, 0
+    end
+
+    -- On the closing bracket the backward search must not count it as nesting
+    local under = line:sub(cursor[2] + 1, cursor[2] + 1)
+    local flags = under == close and 'bnW' or 'bcnW'
+    local start = vim.fn.searchpairpos('\\V' .. open, '', '\\V' .. close, flags)
+    if start[1] == 0 then
+        return nil, nil, 0
+    end
+    vim.api.nvim_win_set_cursor(0, { start[1], start[2] - 1 })
+    local finish = vim.fn.searchpairpos('\\V' .. open, '', '\\V' .. close, 'nW')
+    vim.api.nvim_win_set_cursor(0, cursor)
+    if finish[1] == 0 then
+        return nil, nil, 0
+    end
+    return { start[1], start[2] - 1 }, { finish[1], finish[2] - 1 }, #open
+end
+
+-- Replace a pair of surround characters
+-- @param trim boolean: Also remove the spaces just inside the pair
+local function replace_pair(s, e, len, open_text, close_text, trim)
+    local same_line = s[1] == e[1]
+
+    -- Closing side first, so the opening position stays valid
+    local close_line = get_line(e[1])
+    local close_start = e[2]
+    local limit = same_line and s[2] + len or 0
+    if trim then
+        while close_start > limit and close_line:sub(close_start, close_start):match('%s') do
+            close_start = close_start - 1
+        end
+    end
+    vim.api.nvim_buf_set_text(0, e[1] - 1, close_start, e[1] - 1, e[2] + len, { close_text })
+
+    local open_line = get_line(s[1])
+    local open_end = s[2] + len
+    limit = same_line and close_start or #open_line
+    if trim then
+        while open_end < limit and open_line:sub(open_end + 1, open_end + 1):match('%s') do
+            open_end = open_end + 1
+        end
+    end
+    vim.api.nvim_buf_set_text(0, s[1] - 1, s[2], s[1] - 1, open_end, { open_text })
+    vim.api.nvim_win_set_cursor(0, s)
+end
+
+-- Whether removing a pair also removes its inner spaces (`ds(`, `cs(`)
+local function trims(char)
+    local pair = PAIRS[char]
+    return pair ~= nil and pair[1] ~= vim.trim(pair[1])
+end
+
+function M.opfunc_add(type)
+    local char = M._add_char
+    local s = vim.api.nvim_buf_get_mark(0, '[')
+    local e = vim.api.nvim_buf_get_mark(0, ']')
+    local open_text, close_text = surrounding(char)
+
+    local start_col, end_col
+    local last = get_line(e[1])
+    if type == 'line' then
+        -- Like yss: the line content without its indentation
+        start_col = #get_line(s[1]):match('^%s*')
+        end_col = #last
+    else
+        start_col = s[2]
+        end_col = e[2] + char_len_at(last, e[2])
+    end
+
+    vim.api.nvim_buf_set_text(0, e[1] - 1, end_col, e[1] - 1, end_col, { close_text })
+    vim.api.nvim_buf_set_text(0, s[1] - 1, start_col, s[1] - 1, start_col, { open_text })
+    vim.api.nvim_win_set_cursor(0, { s[1], start_col })
+end
+
+function M.opfunc_delete()
+    local char = M._delete_char
+    local s, e, len = find_pair(char)
+    if not s then
+        return
+    end
+    replace_pair(s, e, len, '', '', trims(char))
+end
+
+function M.opfunc_change()
+    local target, replacement = M._change_chars[1], M._change_chars[2]
+    local s, e, len = find_pair(target)
+    if not s then
+        return
+    end
+    local open_text, close_text = surrounding(replacement)
+    replace_pair(s, e, len, open_text, close_text, trims(target))
+end
+
+-- ys{motion}{char}: surround the text of a motion
+-- @param motion string: Motion or text object with its count ("s" for the line, yss)
+-- @param char string: Surround character
+function M.add(motion, char)
+    M._add_char = char
+    vim.o.operatorfunc = OPFUNC .. 'add'
+    if motion:sub(-1) == 's' then
+        motion = motion:sub(1, -2) .. '_'
+    end
+    vim.cmd('normal! g@' .. motion)
+end
+
+-- ds{char}: delete the surrounding characters
+function M.delete(char)
+    M._delete_char = char
+    vim.o.operatorfunc = OPFUNC .. 'delete'
+    vim.cmd('normal! g@l')
+end
+
+-- cs{target}{replacement}: change the surrounding characters
+function M.change(target, replacement)
+    M._change_chars = { target, replacement }
+    vim.o.operatorfunc = OPFUNC .. 'change'
+    vim.cmd('normal! g@l')
+end
+
+return M
@@ -1,11 +1,16 @@
 use lsp_types::{
-    DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams,
-    InitializeParams, InitializeResult, Location, Position, TextDocumentIdentifier,
-    TextDocumentItem, TextDocumentPositionParams, Uri, WorkspaceFolder,
+    CodeActionContext, CodeActionOrCommand, CodeActionParams, CodeActionTriggerKind, Command,
+    Diagnostic, DidOpenTextDocumentParams, DocumentSymbolParams, DocumentSymbolResponse,
+    ExecuteCommandParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams,
+    InitializeParams, InitializeResult, Location, Position, PublishDiagnosticsParams, Range,
+    ReferenceContext, ReferenceParams, RenameParams, SignatureHelp, SignatureHelpParams,
+    TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Uri, WorkspaceEdit,
+    WorkspaceFolder, WorkspaceSymbolParams, WorkspaceSymbolResponse,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::io::{BufRead, BufReader, Read, Write};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
 use std::net::TcpStream;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Mutex;
@@ -47,6 +52,8 @@ pub struct GodotLspClient {
     stream: Mutex<Option<TcpStream>>,
     request_id: AtomicI64,
     initialized: std::sync::atomic::AtomicBool,
+    /// Latest diagnostics published by the server (textDocument/publishDiagnostics), by URI
+    diagnostics: Mutex<HashMap<String, Vec<Diagnostic>>>,
 }
 
 impl GodotLspClient {
@@ -55,6 +62,7 @@ impl GodotLspClient {
             stream: Mutex::new(None),
             request_id: AtomicI64::new(1),
             initialized: std::sync::atomic::AtomicBool::new(false),
+            diagnostics: Mutex::new(HashMap::new()),
         }
     }
 
@@ -84,6 +92,9 @@ impl GodotLspClient {
             }
         }
         self.initialized.store(false, Ordering::SeqCst);
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.clear();
+        }
         crate::verbose_print!("[godot-neovim] LSP disconnected");
     }
 
@@ -206,6 +217,220 @@ impl GodotLspClient {
         Ok(result)
     }
 
+    /// Get signature help for the call at the given position (textDocument/signatureHelp)
+    pub fn signature_help(
+        &self,
+        uri: &str,
+        line: u32,
+        col: u32,
+    ) -> Result<Option<SignatureHelp>, String> {
+        let doc_uri = uri.parse::<Uri>().map_err(|e| e.to_string())?;
+        let params = SignatureHelpParams {
+            context: None,
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: doc_uri },
+                position: Position {
+                    line,
+                    character: col,
+                },
+            },
+            work_done_progress_params: Default::default(),
+        };
+
+        let result: Option<SignatureHelp> = self.send_request(
+            "textDocument/signatureHelp",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        // An empty signature list means "no signature here"
+        Ok(result.filter(|help| !help.signatures.is_empty()))
+    }
+
+    /// Find all references to the symbol at the given position (textDocument/references)
+    pub fn references(
+        &self,
+        uri: &str,
+        line: u32,
+        col: u32,
+        include_declaration: bool,
+    ) -> Result<Vec<Location>, String> {
+        let doc_uri = uri.parse::<Uri>().map_err(|e| e.to_string())?;
+        let params = ReferenceParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: doc_uri },
+                position: Position {
+                    line,
+                    character: col,
+                },
+            },
+            context: ReferenceContext {
+                include_declaration,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let result: Option<Vec<Location>> = self.send_request(
+            "textDocument/references",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Rename the symbol at the given position (textDocument/rename)
+    pub fn rename(
+        &self,
+        uri: &str,
+        line: u32,
+        col: u32,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>, String> {
+        let doc_uri = uri.parse::<Uri>().map_err(|e| e.to_string())?;
+        let params = RenameParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: doc_uri },
+                position: Position {
+                    line,
+                    character: col,
+                },
+            },
+            new_name: new_name.to_string(),
+            work_done_progress_params: Default::default(),
+        };
+
+        let result: Option<WorkspaceEdit> = self.send_request(
+            "textDocument/rename",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        Ok(result)
+    }
+
+    /// Get the symbols of a document (textDocument/documentSymbol)
+    pub fn document_symbols(&self, uri: &str) -> Result<Option<DocumentSymbolResponse>, String> {
+        let doc_uri = uri.parse::<Uri>().map_err(|e| e.to_string())?;
+        let params = DocumentSymbolParams {
+            text_document: TextDocumentIdentifier { uri: doc_uri },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let result: Option<DocumentSymbolResponse> = self.send_request(
+            "textDocument/documentSymbol",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        Ok(result)
+    }
+
+    /// Get the code actions (quick fixes, refactorings) for a range (textDocument/codeAction)
+    /// `diagnostics` are the published diagnostics overlapping the range
+    pub fn code_actions(
+        &self,
+        uri: &str,
+        range: Range,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Result<Vec<CodeActionOrCommand>, String> {
+        let doc_uri = uri.parse::<Uri>().map_err(|e| e.to_string())?;
+        let params = CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: doc_uri },
+            range,
+            context: CodeActionContext {
+                diagnostics,
+                only: None,
+                trigger_kind: Some(CodeActionTriggerKind::INVOKED),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let result: Option<Vec<CodeActionOrCommand>> = self.send_request(
+            "textDocument/codeAction",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Run a command of the server (workspace/executeCommand)
+    pub fn execute_command(&self, command: &Command) -> Result<(), String> {
+        let params = ExecuteCommandParams {
+            command: command.command.clone(),
+            arguments: command.arguments.clone().unwrap_or_default(),
+            work_done_progress_params: Default::default(),
+        };
+
+        let _result: Option<Value> = self.send_request(
+            "workspace/executeCommand",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        Ok(())
+    }
+
+    /// Search the symbols of the whole project by name (workspace/symbol)
+    pub fn workspace_symbols(
+        &self,
+        query: &str,
+    ) -> Result<Option<WorkspaceSymbolResponse>, String> {
+        let params = WorkspaceSymbolParams {
+            query: query.to_string(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let result: Option<WorkspaceSymbolResponse> = self.send_request(
+            "workspace/symbol",
+            Some(serde_json::to_value(params).map_err(|e| format!("Failed to serialize: {}", e))?),
+        )?;
+
+        Ok(result)
+    }
+
+    /// Read the notifications the server sent since the last request without
+    /// waiting for more. Returns true if diagnostics were published.
+    pub fn poll_notifications(&self) -> Result<bool, String> {
+        let mut guard = self
+            .stream
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let Some(stream) = guard.as_mut() else {
+            return Ok(false);
+        };
+
+        let mut published = false;
+        loop {
+            stream
+                .set_nonblocking(true)
+                .map_err(|e| format!("Failed to set non-blocking: {}", e))?;
+            let pending = stream.peek(&mut [0u8; 1]);
+            stream
+                .set_nonblocking(false)
+                .map_err(|e| format!("Failed to set blocking: {}", e))?;
+            match pending {
+                Ok(0) => return Err("Connection closed by LSP server".to_string()),
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(published),
+                Err(e) => return Err(format!("Failed to read: {}", e)),
+            }
+
+            // A message has started arriving: read all of it
+            let body = Self::read_message(stream)?;
+            let value: Value =
+                serde_json::from_str(&body).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+            published |= self.handle_notification(&value);
+        }
+    }
+
+    /// Diagnostics of every document the server published them for, by URI
+    pub fn diagnostics(&self) -> HashMap<String, Vec<Diagnostic>> {
+        self.diagnostics
+            .lock()
+            .map(|diagnostics| diagnostics.clone())
+            .unwrap_or_default()
+    }
+
     pub fn is_connected(&self) -> bool {
         self.stream
             .lock()
@@ -255,7 +480,7 @@ impl GodotLspClient {
         crate::verbose_print!("[godot-neovim] LSP: Request sent, waiting for response...");
 
         // Read response
-        let response = Self::read_response(stream)?;
+        let response = self.read_response(stream)?;
 
         crate::verbose_print!(
             "[godot-neovim] LSP: Response received for id={:?}",
@@ -315,15 +540,22 @@ impl GodotLspClient {
         Ok(())
     }
 
-    fn read_message(reader: &mut BufReader<&mut TcpStream>) -> Result<String, String> {
+    /// Read one message (headers byte by byte, so nothing after the message is
+    /// consumed and the next message stays in the stream)
+    fn read_message(stream: &mut TcpStream) -> Result<String, String> {
         // Read headers
         let mut content_length: Option<usize> = None;
         loop {
-            let mut line = String::new();
-            reader
-                .read_line(&mut line)
-                .map_err(|e| format!("Failed to read header: {}", e))?;
+            let mut line = Vec::new();
+            let mut byte = [0u8; 1];
+            while !line.ends_with(b"\r\n") {
+                stream
+                    .read_exact(&mut byte)
+                    .map_err(|e| format!("Failed to read header: {}", e))?;
+                line.push(byte[0]);
+            }
 
+            let line = String::from_utf8_lossy(&line);
             let line = line.trim();
             if line.is_empty() {
                 break;
@@ -343,19 +575,17 @@ impl GodotLspClient {
 
         // Read body
         let mut body = vec![0u8; content_length];
-        reader
+        stream
             .read_exact(&mut body)
             .map_err(|e| format!("Failed to read body: {}", e))?;
 
         String::from_utf8(body).map_err(|e| format!("Invalid UTF-8 in response: {}", e))
     }
 
-    fn read_response(stream: &mut TcpStream) -> Result<JsonRpcResponse, String> {
-        let mut reader = BufReader::new(stream);
-
+    fn read_response(&self, stream: &mut TcpStream) -> Result<JsonRpcResponse, String> {
         // Loop to skip notifications (messages without id)
         loop {
-            let body_str = Self::read_message(&mut reader)?;
+            let body_str = Self::read_message(stream)?;
 
             // Try to parse as response
             let value: Value = serde_json::from_str(&body_str)
@@ -368,9 +598,33 @@ impl GodotLspClient {
                     .map_err(|e| format!("Failed to parse response: {}", e));
             }
 
-            // This is a notification, skip it and continue reading
-            // Optionally log it for debugging
+            // This is a notification: keep the diagnostics, skip the others
+            self.handle_notification(&value);
+        }
+    }
+
+    /// Record a notification from the server. Returns true for published diagnostics.
+    fn handle_notification(&self, value: &Value) -> bool {
+        if value.get("method").and_then(Value::as_str) != Some("textDocument/publishDiagnostics") {
+            return false;
+        }
+        let Some(params) = value
+            .get("params")
+            .cloned()
+            .and_then(|params| serde_json::from_value::<PublishDiagnosticsParams>(params).ok())
+        else {
+            return false;
+        };
+
+        crate::verbose_print!(
+            "[godot-neovim] LSP: {} diagnostic(s) for {}",
+            params.diagnostics.len(),
+            params.uri.as_str()
+        );
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.insert(params.uri.to_string(), params.diagnostics);
         }
+        true
     }
 }
 
@@ -1,10 +1,34 @@
-//! Buffer operations: buffer_update, switch_to_buffer, attach
+//! Buffer operations: buffer_update, buffer_register, switch_to_buffer, list_buffers, attach
 
 use super::{
-    IndentOptions, NeovimClient, SwitchBufferResult, RPC_EXTENDED_TIMEOUT_MS, RPC_TIMEOUT_MS,
+    IndentOptions, NeovimClient, RpcRequest, SwitchBufferResult, RPC_EXTENDED_TIMEOUT_MS,
+    RPC_TIMEOUT_MS,
 };
 use rmpv::Value;
 
+/// A search match: (line, start_col, end_col), 0-indexed line, character columns, end exclusive
+pub type SearchMatch = (i64, i64, i64);
+
+/// A line of a substitute preview: (line, text after substitution, highlight start, highlight end)
+pub type SubstitutePreviewLine = (i64, String, i64, i64);
+
+/// A buffer managed by godot-neovim (an entry of :ls)
+#[derive(Debug, Clone)]
+pub struct BufferEntry {
+    /// Buffer number
+    pub number: i64,
+    /// Absolute file path
+    pub file: String,
+    /// Buffer has unsaved changes
+    pub modified: bool,
+    /// Alternate buffer (`#`)
+    pub alternate: bool,
+    /// Cursor line (1-indexed): last position for buffers that are not current
+    pub line: i64,
+    /// Cursor column (0-indexed, character position)
+    pub col: i64,
+}
+
 impl NeovimClient {
     /// Update buffer content (preserves undo history for 'u' command)
     /// Uses Lua function to properly manage undo history
@@ -33,6 +57,32 @@ impl NeovimClient {
         })
     }
 
+    /// Replace buffer content as initial content (clears the modified flag, and
+    /// replaces undo history with the undo file's, like a freshly opened file)
+    pub fn buffer_register(&self, lines: Vec<String>) -> Result<i64, String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let nvim_lock = neovim_arc.lock().await;
+            if let Some(neovim) = nvim_lock.as_ref() {
+                let lines_value: Vec<Value> = lines.into_iter().map(Value::from).collect();
+                let args = vec![Value::from(0i64), Value::Array(lines_value)];
+
+                let result = neovim
+                    .exec_lua("return _G.godot_neovim.buffer_register(...)", args)
+                    .await
+                    .map_err(|e| format!("Failed to register buffer: {}", e))?;
+
+                // Return changedtick
+                result
+                    .as_i64()
+                    .ok_or_else(|| "Invalid changedtick returned".to_string())
+            } else {
+                Err("Neovim not connected".to_string())
+            }
+        })
+    }
+
     /// Switch to buffer by path, creating and initializing if needed
     /// Returns (bufnr, tick, is_new, cursor) where cursor is (line, col) 1-indexed
     pub fn switch_to_buffer(
@@ -93,6 +143,35 @@ impl NeovimClient {
         })
     }
 
+    /// List the buffers managed by godot-neovim, by buffer number
+    pub fn list_buffers(&self) -> Result<Vec<BufferEntry>, String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .exec_lua("return _G.godot_neovim.list_buffers()", vec![])
+                            .await
+                            .map_err(|e| format!("Failed to list buffers: {}", e))?;
+                        Ok(parse_buffer_list(result))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout listing buffers".to_string()),
+            }
+        })
+    }
+
     /// Set indent options for the current buffer
     pub fn set_indent_options(&self, use_spaces: bool, indent_size: i32) -> Result<(), String> {
         let neovim_arc = self.neovim.clone();
@@ -118,12 +197,181 @@ impl NeovimClient {
         })
     }
 
+    /// Set comment continuation for all buffers ('formatoptions' r and o)
+    pub fn set_comment_continuation(&self, on_enter: bool, on_open: bool) -> Result<(), String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let nvim_lock = neovim_arc.lock().await;
+            if let Some(neovim) = nvim_lock.as_ref() {
+                let args = vec![Value::from(on_enter), Value::from(on_open)];
+
+                neovim
+                    .exec_lua("_G.godot_neovim.set_comment_continuation(...)", args)
+                    .await
+                    .map_err(|e| format!("Failed to set comment continuation: {}", e))?;
+
+                Ok(())
+            } else {
+                Err("Neovim not connected".to_string())
+            }
+        })
+    }
+
+    /// Set the external formatter run by gq and :Format on GDScript buffers
+    /// ("" uses Neovim's own formatting)
+    pub fn set_formatter(&self, path: &str) -> Result<(), String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let nvim_lock = neovim_arc.lock().await;
+            if let Some(neovim) = nvim_lock.as_ref() {
+                let args = vec![Value::from(path)];
+
+                neovim
+                    .exec_lua("_G.godot_neovim.set_formatter(...)", args)
+                    .await
+                    .map_err(|e| format!("Failed to set formatter: {}", e))?;
+
+                Ok(())
+            } else {
+                Err("Neovim not connected".to_string())
+            }
+        })
+    }
+
+    /// Apply text edits to a buffer (e.g., LSP rename results)
+    /// Each edit is (start_line, start_col, end_line, end_col, text) with 0-indexed lines
+    /// and character columns, sorted bottom-up. Pass "" as path for the current buffer.
+    /// Returns false if the buffer is not loaded in Neovim.
+    pub fn apply_text_edits(
+        &self,
+        path: &str,
+        edits: Vec<(i64, i64, i64, i64, String)>,
+    ) -> Result<bool, String> {
+        let neovim_arc = self.neovim.clone();
+        let path = path.to_string();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let edits_value: Vec<Value> = edits
+                            .into_iter()
+                            .map(|(sl, sc, el, ec, text)| {
+                                Value::Array(vec![
+                                    Value::from(sl),
+                                    Value::from(sc),
+                                    Value::from(el),
+                                    Value::from(ec),
+                                    Value::from(text),
+                                ])
+                            })
+                            .collect();
+                        let args = vec![Value::from(path), Value::Array(edits_value)];
+
+                        let result = neovim
+                            .exec_lua("return _G.godot_neovim.apply_text_edits(...)", args)
+                            .await
+                            .map_err(|e| format!("Failed to apply text edits: {}", e))?;
+
+                        let applied = match result {
+                            Value::Map(map) => map.into_iter().any(|(k, v)| {
+                                k.as_str() == Some("applied") && v.as_bool() == Some(true)
+                            }),
+                            _ => false,
+                        };
+                        Ok(applied)
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout applying text edits".to_string()),
+            }
+        })
+    }
+
+    /// Find all matches of a Vim search pattern in the current buffer, after the
+    /// queued input (the result arrives in the inbox, see parse_matches)
+    /// 'ignorecase' always applies, 'smartcase' only if `smartcase` (typed patterns).
+    pub fn request_matches(&self, pattern: &str, max_matches: i64, smartcase: bool) -> bool {
+        self.request_lua(
+            RpcRequest::SearchMatches(pattern.to_string()),
+            "return _G.godot_neovim.find_matches(...)",
+            vec![
+                Value::from(pattern),
+                Value::from(max_matches),
+                Value::from(smartcase),
+            ],
+        )
+    }
+
+    /// State of the last search pattern for the script at `path`, after the queued
+    /// input (the result arrives in the inbox, see parse_search_state)
+    /// `smartcase` is false when the pattern came from `*` / `#` (Vim ignores 'smartcase').
+    pub fn request_search_state(&self, path: &str, smartcase: bool) -> bool {
+        self.request_lua(
+            RpcRequest::SearchState(path.to_string()),
+            "return _G.godot_neovim.search_state(...)",
+            vec![Value::from(smartcase)],
+        )
+    }
+
+    /// Matches of a pattern (with its case flag applied) per line for line ranges
+    /// (first, last): 0-indexed, last exclusive. Sent after the queued input; the
+    /// result arrives in the inbox with one parse_line_matches list per range.
+    /// `generation` identifies the state of the search index the ranges belong to.
+    pub fn request_line_matches(
+        &self,
+        path: &str,
+        pattern: &str,
+        generation: u64,
+        ranges: Vec<(usize, usize)>,
+    ) -> bool {
+        let args = vec![
+            Value::from(pattern),
+            Value::Array(
+                ranges
+                    .iter()
+                    .map(|&(first, last)| {
+                        Value::Array(vec![Value::from(first as i64), Value::from(last as i64)])
+                    })
+                    .collect(),
+            ),
+        ];
+        self.request_lua(
+            RpcRequest::SearchLines {
+                path: path.to_string(),
+                pattern: pattern.to_string(),
+                generation,
+                ranges,
+            },
+            r#"
+            local pattern, ranges = ...
+            local result = {}
+            for i, range in ipairs(ranges) do
+                result[i] = _G.godot_neovim.find_line_matches(pattern, range[1], range[2])
+            end
+            return result
+            "#,
+            args,
+        )
+    }
+
     /// Parse the result from switch_to_buffer Lua function
     fn parse_switch_buffer_result(result: rmpv::Value) -> Result<SwitchBufferResult, String> {
         if let Value::Map(map) = result {
             let mut bufnr: Option<i64> = None;
             let mut tick: Option<i64> = None;
             let mut is_new: Option<bool> = None;
+            let mut initialized = false;
             let mut attached: Option<bool> = None;
             let mut cursor: Option<(i64, i64)> = None;
 
@@ -133,6 +381,7 @@ impl NeovimClient {
                         Some("bufnr") => bufnr = value.as_i64(),
                         Some("tick") => tick = value.as_i64(),
                         Some("is_new") => is_new = value.as_bool(),
+                        Some("initialized") => initialized = value.as_bool().unwrap_or(false),
                         Some("attached") => attached = value.as_bool(),
                         Some("cursor") => {
                             // cursor is [row, col] array, 1-indexed
@@ -154,6 +403,7 @@ impl NeovimClient {
                     bufnr: b,
                     tick: t,
                     is_new: n,
+                    initialized,
                     attached: a,
                     cursor: c,
                 }),
@@ -218,4 +468,138 @@ impl NeovimClient {
             .get_buf_events_flag()
             .store(false, std::sync::atomic::Ordering::SeqCst)
     }
+
+    /// Preview a :substitute command without modifying the buffer ('inccommand')
+    /// Returns None if the command line is not a :substitute command
+    pub fn substitute_preview(
+        &self,
+        cmdline: &str,
+        max_lines: i64,
+    ) -> Result<Option<Vec<SubstitutePreviewLine>>, String> {
+        let neovim_arc = self.neovim.clone();
+        let cmdline = cmdline.to_string();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let args = vec![Value::from(cmdline), Value::from(max_lines)];
+                        let result = neovim
+                            .exec_lua("return _G.godot_neovim.substitute_preview(...)", args)
+                            .await
+                            .map_err(|e| format!("Failed to preview substitute: {}", e))?;
+
+                        let items = match result {
+                            Value::Nil => return Ok(None),
+                            Value::Array(items) => items,
+                            // An empty Lua table may be encoded as an empty map
+                            _ => Vec::new(),
+                        };
+                        let lines = items
+                            .into_iter()
+                            .filter_map(|item| match item {
+                                Value::Array(l) if l.len() >= 4 => Some((
+                                    l[0].as_i64()?,
+                                    l[1].as_str()?.to_string(),
+                                    l[2].as_i64()?,
+                                    l[3].as_i64()?,
+                                )),
+                                _ => None,
+                            })
+                            .collect();
+                        Ok(Some(lines))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout previewing substitute".to_string()),
+            }
+        })
+    }
+}
+
+/// Convert a Lua match list ({ line, start_col, end_col } arrays) returned by
+/// find_matches to tuples
+pub fn parse_matches(result: Value) -> Vec<SearchMatch> {
+    match result {
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| match item {
+                Value::Array(m) if m.len() >= 3 => {
+                    Some((m[0].as_i64()?, m[1].as_i64()?, m[2].as_i64()?))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse `{ { {start_col, end_col}, ... }, ... }` returned by find_line_matches
+pub fn parse_line_matches(result: Value) -> Vec<Vec<(i64, i64)>> {
+    let Value::Array(lines) = result else {
+        return Vec::new();
+    };
+    lines
+        .into_iter()
+        .map(|line| match line {
+            Value::Array(matches) => matches
+                .into_iter()
+                .filter_map(|m| match m {
+                    Value::Array(cols) if cols.len() >= 2 => {
+                        Some((cols[0].as_i64()?, cols[1].as_i64()?))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Parse `{ pattern, pattern with its case flag, hlsearch }` returned by search_state
+/// hlsearch is false for 'nohlsearch' and after :nohlsearch.
+pub fn parse_search_state(result: &Value) -> Result<(String, String, bool), String> {
+    let Value::Array(fields) = result else {
+        return Err("Invalid search state".to_string());
+    };
+    let text = |i: usize| {
+        fields
+            .get(i)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let hlsearch = fields.get(2).and_then(|v| v.as_bool()).unwrap_or(false);
+    Ok((text(0), text(1), hlsearch))
+}
+
+/// Parse `{ {bufnr, path, modified, alternate, line, col}, ... }` returned by list_buffers
+fn parse_buffer_list(result: Value) -> Vec<BufferEntry> {
+    let Value::Array(entries) = result else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let Value::Array(fields) = entry else {
+                return None;
+            };
+            Some(BufferEntry {
+                number: fields.first()?.as_i64()?,
+                file: fields.get(1)?.as_str()?.to_string(),
+                modified: fields.get(2)?.as_bool()?,
+                alternate: fields.get(3)?.as_bool()?,
+                line: fields.get(4)?.as_i64()?,
+                col: fields.get(5)?.as_i64()?,
+            })
+        })
+        .collect()
 }
@@ -1,28 +1,77 @@
 //! Connection management: new, start, stop
-
-use super::{NeovimClient, NeovimVersion, Writer, LUA_FALLBACK_CODE, NEOVIM_REQUIRED_VERSION};
+//!
+//! A client either spawns an embedded headless Neovim (talking over its
+//! stdin/stdout) or attaches to a running Neovim started with `--listen`, over
+//! TCP or a Unix socket / Windows named pipe. An attached Neovim is not owned:
+//! stopping the client gives back the settings changed on attach, detaches the
+//! UI and closes the connection.
+
+use super::input::{run_input_command, InputCommand};
+use super::processes;
+use super::{
+    NeovimClient, NeovimVersion, Reader, Writer, LUA_FALLBACK_CODE, NEOVIM_REQUIRED_VERSION,
+    RPC_EXTENDED_TIMEOUT_MS,
+};
 use crate::neovim::NeovimHandler;
 use crate::settings;
 use godot::prelude::godot_warn;
-use nvim_rs::create::tokio as create;
 use nvim_rs::{Neovim, UiAttachOptions};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::process::Command;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::process::{Child, Command};
 use tokio::runtime::Builder;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::Mutex;
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
 #[cfg(target_os = "windows")]
 use super::CREATE_NO_WINDOW;
 
+/// Limit for connecting to and setting up an attached Neovim
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Swap file handling for a Neovim without a terminal to answer E325 ATTENTION:
+/// no swap files, no swap warnings, "edit anyway" if one exists. The user's
+/// settings are saved so that an attached Neovim gets them back (SWAP_RESTORE_LUA).
+const SWAP_SETUP_LUA: &str = r#"
+    vim.g.godot_neovim_saved_swap = vim.g.godot_neovim_saved_swap
+        or { swapfile = vim.o.swapfile, shortmess = vim.o.shortmess }
+    vim.o.swapfile = false
+    vim.opt.shortmess:append("A")
+    local group = vim.api.nvim_create_augroup("godot_neovim_swap", { clear = true })
+    vim.api.nvim_create_autocmd("SwapExists", {
+        group = group,
+        command = "let v:swapchoice = 'e'",
+    })
+"#;
+
+/// Undo SWAP_SETUP_LUA when detaching from a Neovim that keeps running
+const SWAP_RESTORE_LUA: &str = r#"
+    local saved = vim.g.godot_neovim_saved_swap
+    if saved then
+        vim.o.swapfile = saved.swapfile
+        vim.o.shortmess = saved.shortmess
+        vim.g.godot_neovim_saved_swap = nil
+    end
+    pcall(vim.api.nvim_del_augroup_by_name, "godot_neovim_swap")
+"#;
+
 impl NeovimClient {
     pub fn new() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // Use current-thread runtime so all tasks run on the same thread
-        // This ensures io_handler is processed during block_on calls
-        let runtime = Builder::new_current_thread().enable_all().build()?;
+        // Worker threads run the IO handler and the input queue in the background,
+        // so redraw events and queued keys never wait for the Godot main thread
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("godot-neovim-rpc")
+            .enable_all()
+            .build()?;
         let nvim_path = settings::get_neovim_path();
         let clean = settings::get_neovim_clean();
+        let init_path = settings::get_neovim_init_path();
+        let config_files = settings::get_neovim_config_files();
         let handler = NeovimHandler::new();
         let state = handler.get_state();
         let has_updates = handler.get_updates_flag();
@@ -32,15 +81,37 @@ impl NeovimClient {
             handler,
             nvim_path,
             clean,
+            init_path,
+            config_files,
+            remote_address: None,
             state,
             has_updates,
             io_handle: None,
             key_input_tx: None,
+            pending_input: Arc::new(AtomicUsize::new(0)),
+            responses: Arc::new(std::sync::Mutex::new(Vec::new())),
+            cancelled_chunks: Arc::new(std::sync::Mutex::new(Default::default())),
             key_input_handle: None,
+            process_id: None,
+            started_at: None,
+            version: None,
+            exit_status: Arc::new(std::sync::Mutex::new(None)),
+            supervisor_handle: None,
         })
     }
 
-    /// Start Neovim process and establish connection
+    /// Attach to the running Neovim at `address` on start instead of spawning one
+    /// (`host:port`, a Unix socket path or a Windows named pipe; empty spawns)
+    pub fn set_remote_address(&mut self, address: &str) {
+        self.remote_address = (!address.is_empty()).then(|| address.to_string());
+    }
+
+    /// Address of the attached Neovim (None for an embedded Neovim)
+    pub fn remote_address(&self) -> Option<&str> {
+        self.remote_address.as_deref()
+    }
+
+    /// Start Neovim process (or attach to the remote one) and establish connection
     /// If addons_path is provided, loads the Lua plugin from that directory
     pub fn start(
         &mut self,
@@ -50,120 +121,210 @@ impl NeovimClient {
         let neovim_arc = self.neovim.clone();
         let nvim_path = self.nvim_path.clone();
         let clean = self.clean;
+        let init_path = self.init_path.clone();
+        let config_files = self.config_files.clone();
+        let remote_address = self.remote_address.clone();
         let addons_path_owned = addons_path.map(String::from);
 
-        crate::verbose_print!(
-            "[godot-neovim] Starting Neovim: {} (clean={}, addons_path={:?})",
-            nvim_path,
-            clean,
-            addons_path
-        );
+        if let Some(ref address) = remote_address {
+            crate::verbose_print!(
+                "[godot-neovim] Attaching to Neovim at {} (addons_path={:?})",
+                address,
+                addons_path
+            );
+        } else {
+            crate::verbose_print!(
+                "[godot-neovim] Starting Neovim: {} (clean={}, init={:?}, addons_path={:?})",
+                nvim_path,
+                clean,
+                init_path,
+                addons_path
+            );
+        }
 
-        let io_handle = self.runtime.block_on(async {
-            let mut cmd = create_nvim_command(&nvim_path, clean);
-
-            let (neovim, io_handler, _child) = create::new_child_cmd(&mut cmd, handler).await?;
-
-            // Attach UI to receive redraw events
-            // ext_multigrid enables win_viewport events for viewport synchronization
-            let mut ui_opts = UiAttachOptions::new();
-            ui_opts.set_rgb(true);
-            ui_opts.set_linegrid_external(true);
-            ui_opts.set_multigrid_external(true);
-            neovim
-                .ui_attach(80, 24, &ui_opts)
-                .await
-                .map_err(|e| format!("Failed to attach UI: {}", e))?;
-
-            crate::verbose_print!("[godot-neovim] UI attached successfully");
-
-            // Disable swap files and handle E325 ATTENTION errors in headless mode
-            // - noswapfile: Don't create new swap files
-            // - shortmess+=A: Suppress swap file warnings
-            // - SwapExists autocmd: Auto-select 'edit anyway' if swap exists
-            neovim
-                .command(
-                    "set noswapfile shortmess+=A | autocmd SwapExists * let v:swapchoice = 'e'",
-                )
-                .await
-                .map_err(|e| format!("Failed to configure swapfile handling: {}", e))?;
-
-            // Check Neovim version before storing
-            let version = get_neovim_version(&neovim).await;
-            let (req_major, req_minor, req_patch) = NEOVIM_REQUIRED_VERSION;
-
-            if let Some(ref ver) = version {
-                crate::verbose_print!("[godot-neovim] Neovim version: {}", ver);
-
-                if !ver.meets_requirement(req_major, req_minor, req_patch) {
-                    let msg = format!(
-                        "Neovim version {} is below minimum required {}.{}.{}. Some features may not work correctly.",
-                        ver, req_major, req_minor, req_patch
-                    );
-                    godot_warn!("[godot-neovim] {}", msg);
+        let exit_status = self.exit_status.clone();
+        if let Ok(mut exit) = exit_status.lock() {
+            *exit = None;
+        }
+
+        let (io_handle, child, version) = self.runtime.block_on(async {
+            let (reader, writer, child) = match remote_address {
+                Some(ref address) => {
+                    // Bounded: an unreachable address must not freeze the editor
+                    let (reader, writer) =
+                        tokio::time::timeout(REMOTE_TIMEOUT, connect_remote(address))
+                            .await
+                            .map_err(|_| format!("Timed out connecting to Neovim at {}", address))?
+                            .map_err(|e| {
+                                format!("Failed to connect to Neovim at {}: {}", address, e)
+                            })?;
+                    (reader, writer, None)
                 }
-            } else {
-                crate::verbose_print!("[godot-neovim] Could not determine Neovim version");
-            }
+                None => {
+                    let (reader, writer, child) = spawn_embedded(&nvim_path, clean, &init_path)?;
+                    (reader, writer, Some(child))
+                }
+            };
+
+            let (neovim, io) = Neovim::new(reader, writer, handler);
+            // The end of the connection to an attached Neovim is its exit for us
+            // (an embedded process is watched by the supervisor instead)
+            let attached = child.is_none();
+            let io_handler = tokio::spawn(async move {
+                let result = io.await;
+                if attached {
+                    crate::verbose_print!("[godot-neovim] Connection to Neovim closed");
+                    if let Ok(mut exit) = exit_status.lock() {
+                        *exit = Some("connection closed".to_string());
+                    }
+                }
+                result
+            });
 
-            // Initialize godot_neovim Lua module
-            // Prefer external plugin if addons_path is provided
-            if let Some(ref path) = addons_path_owned {
-                // Escape backslashes for Lua string (Windows paths)
-                let lua_path = path.replace('\\', "/");
-                let init_code = format!(
-                    r#"
-                    -- Add addons path to runtimepath
-                    vim.opt.runtimepath:append("{}")
-                    -- Load the godot_neovim module
-                    require('godot_neovim')
-                    "#,
-                    lua_path
-                );
+            // Without a reply from an attached Neovim (e.g. waiting at a prompt) the
+            // connection is given up; the IO handler never outlives a failed start
+            let setup = async {
+                // Attach UI to receive redraw events
+                // ext_multigrid enables win_viewport events for viewport synchronization
+                // ext_messages sends messages as msg_show events (no hit-enter prompts)
+                let mut ui_opts = UiAttachOptions::new();
+                ui_opts.set_rgb(true);
+                ui_opts.set_linegrid_external(true);
+                ui_opts.set_multigrid_external(true);
+                ui_opts.set_messages_externa(true);
                 neovim
-                    .exec_lua(&init_code, vec![])
+                    .ui_attach(80, 24, &ui_opts)
                     .await
-                    .map_err(|e| format!("Failed to load Lua plugin from {}: {}", path, e))?;
-                crate::verbose_print!(
-                    "[godot-neovim] Lua module loaded from external file: {}",
-                    path
-                );
-            } else {
-                // Fallback to embedded Lua code
+                    .map_err(|e| format!("Failed to attach UI: {}", e))?;
+
+                crate::verbose_print!("[godot-neovim] UI attached successfully");
+
+                // Disable swap files and handle E325 ATTENTION errors in headless mode
+                // - noswapfile: Don't create new swap files
+                // - shortmess+=A: Suppress swap file warnings
+                // - SwapExists autocmd: Auto-select 'edit anyway' if swap exists
+                // (an attached Neovim gets its settings back on detach, see stop)
                 neovim
-                    .exec_lua(LUA_FALLBACK_CODE, vec![])
+                    .exec_lua(SWAP_SETUP_LUA, vec![])
                     .await
-                    .map_err(|e| format!("Failed to initialize Lua module: {}", e))?;
-                crate::verbose_print!("[godot-neovim] Lua module initialized (embedded fallback)");
-            }
+                    .map_err(|e| format!("Failed to configure swapfile handling: {}", e))?;
+
+                // Check Neovim version before storing
+                let version = get_neovim_version(&neovim).await;
+                let (req_major, req_minor, req_patch) = NEOVIM_REQUIRED_VERSION;
+
+                if let Some(ref ver) = version {
+                    crate::verbose_print!("[godot-neovim] Neovim version: {}", ver);
+
+                    if !ver.meets_requirement(req_major, req_minor, req_patch) {
+                        let msg = format!(
+                            "Neovim version {} is below minimum required {}.{}.{}. Some features may not work correctly.",
+                            ver, req_major, req_minor, req_patch
+                        );
+                        godot_warn!("[godot-neovim] {}", msg);
+                    }
+                } else {
+                    crate::verbose_print!("[godot-neovim] Could not determine Neovim version");
+                }
+
+                // Initialize godot_neovim Lua module
+                // Prefer external plugin if addons_path is provided
+                if let Some(ref path) = addons_path_owned {
+                    // Escape backslashes for Lua string (Windows paths)
+                    let lua_path = path.replace('\\', "/");
+                    let init_code = format!(
+                        r#"
+                        -- Add addons path to runtimepath
+                        vim.opt.runtimepath:append("{}")
+                        -- Load the godot_neovim module
+                        require('godot_neovim')
+                        "#,
+                        lua_path
+                    );
+                    neovim
+                        .exec_lua(&init_code, vec![])
+                        .await
+                        .map_err(|e| format!("Failed to load Lua plugin from {}: {}", path, e))?;
+                    crate::verbose_print!(
+                        "[godot-neovim] Lua module loaded from external file: {}",
+                        path
+                    );
+                } else {
+                    // Fallback to embedded Lua code
+                    neovim
+                        .exec_lua(LUA_FALLBACK_CODE, vec![])
+                        .await
+                        .map_err(|e| format!("Failed to initialize Lua module: {}", e))?;
+                    crate::verbose_print!("[godot-neovim] Lua module initialized (embedded fallback)");
+                }
+
+                // Source the allowed user config files (an attached Neovim has its own config)
+                if child.is_some() && !config_files.is_empty() {
+                    source_config_files(&neovim, config_files).await;
+                }
+
+                Ok::<_, String>(version)
+            };
+            let setup = if attached {
+                tokio::time::timeout(REMOTE_TIMEOUT, setup)
+                    .await
+                    .unwrap_or_else(|_| Err("Timed out attaching to Neovim".to_string()))
+            } else {
+                setup.await
+            };
+            let version = match setup {
+                Ok(version) => version,
+                Err(e) => {
+                    io_handler.abort();
+                    return Err(e.into());
+                }
+            };
 
             let mut nvim_lock = neovim_arc.lock().await;
             *nvim_lock = Some(neovim);
 
             crate::verbose_print!("[godot-neovim] Neovim started successfully");
-            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(io_handler)
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>((io_handler, child, version))
         })?;
 
         self.io_handle = Some(io_handle);
+        self.process_id = child.as_ref().and_then(Child::id);
+        if let Some(pid) = self.process_id {
+            processes::register(pid);
+        }
+        self.started_at = Some(std::time::Instant::now());
+        self.version = version;
+
+        // Supervisor: record the exit of the process (crash, OOM, killed externally)
+        // An intentional stop aborts it first, so only unexpected exits are reported.
+        if let Some(mut child) = child {
+            let exit_status = self.exit_status.clone();
+            self.supervisor_handle = Some(self.runtime.spawn(async move {
+                let status = match child.wait().await {
+                    Ok(status) => status.to_string(),
+                    Err(e) => format!("wait failed: {}", e),
+                };
+                crate::verbose_print!("[godot-neovim] Neovim process exited: {}", status);
+                if let Ok(mut exit) = exit_status.lock() {
+                    *exit = Some(status);
+                }
+            }));
+        }
 
         // Create unbounded channel for key input (no key drops)
-        let (tx, mut rx) = unbounded_channel::<String>();
+        let (tx, mut rx) = unbounded_channel::<InputCommand>();
         self.key_input_tx = Some(tx);
+        self.pending_input.store(0, Ordering::SeqCst);
 
-        // Spawn key input processor task
+        // Spawn input queue processor task
         let neovim_arc = self.neovim.clone();
+        let pending_input = self.pending_input.clone();
+        let responses = self.responses.clone();
+        let cancelled_chunks = self.cancelled_chunks.clone();
         let key_input_handle = self.runtime.spawn(async move {
-            while let Some(keys) = rx.recv().await {
-                let nvim_lock = neovim_arc.lock().await;
-                if let Some(neovim) = nvim_lock.as_ref() {
-                    if let Err(e) = neovim.input(&keys).await {
-                        // Log error but continue processing
-                        // Note: Can't use godot_error here (tokio thread)
-                        eprintln!("[godot-neovim] Failed to send key '{}': {}", keys, e);
-                    }
-                }
-                // Release lock before next iteration
-                drop(nvim_lock);
+            while let Some(command) = rx.recv().await {
+                run_input_command(&neovim_arc, command, &responses, &cancelled_chunks).await;
+                pending_input.fetch_sub(1, Ordering::SeqCst);
             }
         });
         self.key_input_handle = Some(key_input_handle);
@@ -179,6 +340,16 @@ impl NeovimClient {
 
     /// Stop Neovim process
     pub fn stop(&mut self) {
+        // Stop supervising first: this exit is intentional
+        if let Some(handle) = self.supervisor_handle.take() {
+            handle.abort();
+        }
+
+        // An attached Neovim keeps running: give the user's session back
+        if self.remote_address.is_some() && self.io_handle.is_some() {
+            self.detach_remote();
+        }
+
         // Abort the key input handler first
         if let Some(handle) = self.key_input_handle.take() {
             handle.abort();
@@ -192,6 +363,9 @@ impl NeovimClient {
             handle.abort();
             crate::verbose_print!("[godot-neovim] IO handler aborted");
         }
+        if let Ok(mut exit) = self.exit_status.lock() {
+            *exit = None;
+        }
 
         // Clear the neovim instance without sending quit command
         // (IO is already aborted, command would timeout anyway)
@@ -200,20 +374,171 @@ impl NeovimClient {
             let mut nvim_lock = neovim_arc.lock().await;
             nvim_lock.take();
         });
+        // The child was killed with the supervisor task (an attached Neovim keeps
+        // running: only the connection was closed)
+        if let Some(pid) = self.process_id.take() {
+            processes::unregister(pid);
+        }
+        self.started_at = None;
         crate::verbose_print!("[godot-neovim] Neovim stopped");
     }
+
+    /// Restore the settings changed on attach and detach the UI, so the attached
+    /// Neovim's screen goes back to the size of its own UIs (bounded: the remote
+    /// may be gone already)
+    fn detach_remote(&self) {
+        let neovim_arc = self.neovim.clone();
+        let result = self.runtime.block_on(async {
+            tokio::time::timeout(Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS), async {
+                let nvim_lock = neovim_arc.lock().await;
+                let Some(neovim) = nvim_lock.as_ref() else {
+                    return Ok(());
+                };
+                neovim
+                    .exec_lua(SWAP_RESTORE_LUA, vec![])
+                    .await
+                    .map_err(|e| format!("Failed to restore swapfile settings: {}", e))?;
+                neovim
+                    .ui_detach()
+                    .await
+                    .map_err(|e| format!("Failed to detach UI: {}", e))
+            })
+            .await
+            .unwrap_or_else(|_| Err("Timeout detaching from Neovim".to_string()))
+        });
+        if let Err(e) = result {
+            crate::verbose_print!("[godot-neovim] {}", e);
+        }
+    }
+
+    /// How the Neovim process exited ("exit status: 1", "signal: 9 (SIGKILL)",
+    /// "connection closed" for an attached Neovim) if it died while supervised;
+    /// None while it runs and after an intentional stop
+    pub fn process_exit(&self) -> Option<String> {
+        self.exit_status.lock().ok().and_then(|exit| exit.clone())
+    }
+
+    /// Process ID of the running Neovim (None before start and after stop)
+    pub fn process_id(&self) -> Option<u32> {
+        self.process_id
+    }
+
+    /// Time since the running Neovim was started
+    pub fn uptime(&self) -> Option<std::time::Duration> {
+        self.started_at.map(|started| started.elapsed())
+    }
+
+    /// Version of the running Neovim ("0.10.2"), if it could be determined
+    pub fn version_string(&self) -> Option<String> {
+        self.version.as_ref().map(|version| version.to_string())
+    }
+}
+
+/// Spawn an embedded Neovim, returning its stdout, stdin and process
+fn spawn_embedded(
+    nvim_path: &str,
+    clean: bool,
+    init_path: &str,
+) -> std::io::Result<(Reader, Writer, Child)> {
+    let mut child = create_nvim_command(nvim_path, clean, init_path).spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| std::io::Error::other("Can't open stdout"))?;
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| std::io::Error::other("Can't open stdin"))?;
+    Ok((compat_reader(stdout), compat_writer(stdin), child))
+}
+
+/// Whether a connection address is `host:port` (otherwise it is a socket path
+/// or named pipe; `C:\...` has no numeric port)
+fn is_tcp_address(address: &str) -> bool {
+    address
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+}
+
+/// Connect to a Neovim listening on `address` (`nvim --listen {address}`)
+async fn connect_remote(address: &str) -> std::io::Result<(Reader, Writer)> {
+    if is_tcp_address(address) {
+        let stream = tokio::net::TcpStream::connect(address).await?;
+        let (reader, writer) = stream.into_split();
+        return Ok((compat_reader(reader), compat_writer(writer)));
+    }
+
+    #[cfg(unix)]
+    let (reader, writer) = tokio::net::UnixStream::connect(address).await?.into_split();
+
+    #[cfg(windows)]
+    let (reader, writer) =
+        tokio::io::split(tokio::net::windows::named_pipe::ClientOptions::new().open(address)?);
+
+    Ok((compat_reader(reader), compat_writer(writer)))
+}
+
+fn compat_reader(reader: impl AsyncRead + Send + Unpin + 'static) -> Reader {
+    let reader: Box<dyn AsyncRead + Send + Unpin> = Box::new(reader);
+    reader.compat()
+}
+
+fn compat_writer(writer: impl AsyncWrite + Send + Unpin + 'static) -> Writer {
+    let writer: Box<dyn AsyncWrite + Send + Unpin> = Box::new(writer);
+    writer.compat_write()
+}
+
+/// Source the config files of the allow-list, warning about the ones that fail
+async fn source_config_files(neovim: &Neovim<Writer>, files: Vec<String>) {
+    let files = rmpv::Value::Array(files.into_iter().map(rmpv::Value::from).collect());
+    let code = r#"
+        local failed = {}
+        for _, file in ipairs(...) do
+            local ok, err = pcall(vim.cmd.source, vim.fn.fnameescape(vim.fn.expand(file)))
+            if not ok then
+                table.insert(failed, file .. ": " .. tostring(err))
+            end
+        end
+        return failed
+    "#;
+    match neovim.exec_lua(code, vec![files]).await {
+        Ok(rmpv::Value::Array(failed)) => {
+            for failure in failed.iter().filter_map(rmpv::Value::as_str) {
+                godot_warn!("[godot-neovim] Failed to source config file {}", failure);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => godot_warn!("[godot-neovim] Failed to source config files: {}", e),
+    }
 }
 
+/// Ex command recording the default Normal mode mappings (lhs -> "rhs|desc") in
+/// g:godot_neovim_default_maps, so user mappings can be told apart from them
+const DEFAULT_MAPS_SNAPSHOT: &str = "lua local maps = {} \
+    for _, map in ipairs(vim.api.nvim_get_keymap('n')) do \
+    maps[map.lhs] = (map.rhs or '') .. '|' .. (map.desc or '') end \
+    vim.g.godot_neovim_default_maps = maps";
+
 /// Create Neovim command with platform-specific settings
-fn create_nvim_command(nvim_path: &str, clean: bool) -> Command {
+/// Without --clean, `init_path` (if set) replaces the user's init file (-u)
+fn create_nvim_command(nvim_path: &str, clean: bool, init_path: &str) -> Command {
     // -n: No swap file (prevents E325 ATTENTION errors in headless mode)
-    let mut args = vec!["--embed", "--headless", "-n"];
+    // --cmd: record Neovim's default mappings before any user config is loaded
+    let mut args = vec![
+        "--embed",
+        "--headless",
+        "-n",
+        "--cmd",
+        DEFAULT_MAPS_SNAPSHOT,
+    ];
     if clean {
         args.push("--clean");
+    } else if !init_path.is_empty() {
+        args.extend(["-u", init_path]);
     }
 
     #[cfg(target_os = "windows")]
-    {
+    let mut cmd = {
         use std::os::windows::process::CommandExt;
         let mut std_cmd = std::process::Command::new(nvim_path);
         std_cmd
@@ -223,17 +548,22 @@ fn create_nvim_command(nvim_path: &str, clean: bool) -> Command {
             .stderr(Stdio::piped())
             .creation_flags(CREATE_NO_WINDOW);
         Command::from(std_cmd)
-    }
+    };
 
     #[cfg(not(target_os = "windows"))]
-    {
+    let mut cmd = {
         let mut cmd = Command::new(nvim_path);
         cmd.args(&args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
         cmd
-    }
+    };
+
+    // Mark the process as ours (for the orphan sweep) and kill it with its client
+    cmd.env(processes::OWNER_ENV, std::process::id().to_string())
+        .kill_on_drop(true);
+    cmd
 }
 
 /// Get Neovim version from API info
@@ -272,3 +602,19 @@ pub(super) async fn get_neovim_version(neovim: &Neovim<Writer>) -> Option<Neovim
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_tcp_address() {
+        assert!(is_tcp_address("127.0.0.1:6666"));
+        assert!(is_tcp_address("localhost:6666"));
+        assert!(is_tcp_address("[::1]:6666"));
+        assert!(!is_tcp_address("/tmp/nvim.sock"));
+        assert!(!is_tcp_address(r"\\.\pipe\nvim"));
+        assert!(!is_tcp_address(r"C:\Users\me\nvim.sock"));
+        assert!(!is_tcp_address(":6666"));
+    }
+}
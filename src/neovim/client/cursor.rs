@@ -4,32 +4,6 @@ use super::{NeovimClient, RPC_TIMEOUT_MS};
 use rmpv::Value;
 
 impl NeovimClient {
-    /// Get cursor position (1-indexed line, 0-indexed column) with timeout
-    pub fn get_cursor(&self) -> Result<(i64, i64), String> {
-        let neovim_arc = self.neovim.clone();
-
-        self.runtime.block_on(async {
-            // Use timeout to avoid blocking on operator-pending commands
-            let result =
-                tokio::time::timeout(std::time::Duration::from_millis(RPC_TIMEOUT_MS), async {
-                    let nvim_lock = neovim_arc.lock().await;
-                    if let Some(neovim) = nvim_lock.as_ref() {
-                        let window = neovim.get_current_win().await.ok()?;
-                        window.get_cursor().await.ok()
-                    } else {
-                        None
-                    }
-                })
-                .await;
-
-            match result {
-                Ok(Some(pos)) => Ok(pos),
-                Ok(None) => Err("Failed to get cursor".to_string()),
-                Err(_) => Err("Timeout getting cursor".to_string()),
-            }
-        })
-    }
-
     /// Set cursor position with timeout
     pub fn set_cursor(&self, line: i64, col: i64) -> Result<(), String> {
         let neovim_arc = self.neovim.clone();
@@ -1,14 +1,16 @@
 //! Command and Lua execution
 
-use super::NeovimClient;
+use super::input::wait_for_queue;
+use super::{NeovimClient, RPC_LUA_TIMEOUT_MS};
 
 impl NeovimClient {
-    /// Execute Neovim command
+    /// Execute Neovim command (after the queued input)
     pub fn command(&self, cmd: &str) -> Result<(), String> {
         let neovim_arc = self.neovim.clone();
         let cmd = cmd.to_string();
 
         self.runtime.block_on(async {
+            wait_for_queue(&self.pending_input).await;
             let nvim_lock = neovim_arc.lock().await;
             if let Some(neovim) = nvim_lock.as_ref() {
                 neovim
@@ -22,12 +24,13 @@ impl NeovimClient {
         })
     }
 
-    /// Execute Lua code and return the result
+    /// Execute Lua code and return the result (after the queued input)
     pub fn execute_lua_with_result(&self, lua_code: &str) -> Result<rmpv::Value, String> {
         let neovim_arc = self.neovim.clone();
         let lua_code = lua_code.to_string();
 
         self.runtime.block_on(async {
+            wait_for_queue(&self.pending_input).await;
             let nvim_lock = neovim_arc.lock().await;
             if let Some(neovim) = nvim_lock.as_ref() {
                 neovim
@@ -40,7 +43,8 @@ impl NeovimClient {
         })
     }
 
-    /// Execute Lua code with arguments and return the result
+    /// Execute Lua code with arguments and return the result (after the queued input)
+    /// Bounded by RPC_LUA_TIMEOUT_MS: the code may run external tools (formatter)
     pub fn execute_lua_with_args(
         &self,
         lua_code: &str,
@@ -50,14 +54,26 @@ impl NeovimClient {
         let lua_code = lua_code.to_string();
 
         self.runtime.block_on(async {
-            let nvim_lock = neovim_arc.lock().await;
-            if let Some(neovim) = nvim_lock.as_ref() {
-                neovim
-                    .exec_lua(&lua_code, args)
-                    .await
-                    .map_err(|e| format!("Failed to execute Lua: {}", e))
-            } else {
-                Err("Neovim not connected".to_string())
+            wait_for_queue(&self.pending_input).await;
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_LUA_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        neovim
+                            .exec_lua(&lua_code, args)
+                            .await
+                            .map_err(|e| format!("Failed to execute Lua: {}", e))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout executing Lua".to_string()),
             }
         })
     }
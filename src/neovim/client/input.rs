@@ -1,14 +1,203 @@
 //! Key input: input, send_keys, channels
+//!
+//! Keys, cursor syncs and asynchronous requests go through one unbounded queue,
+//! sent to Neovim in order by a task on the runtime's worker threads. The Godot
+//! main thread never waits for them: failures and request results are collected
+//! in the response inbox, drained with take_responses() every frame.
+//! The blocking calls (input, command, execute_lua_with_*) first wait (bounded)
+//! for the queue to be sent, so that they run after the keys typed before them;
+//! queries that can be answered later are made with request_lua instead.
 
-use super::{NeovimClient, RPC_TIMEOUT_MS};
+use super::{NeovimClient, Writer, RPC_EXTENDED_TIMEOUT_MS, RPC_TIMEOUT_MS};
+use nvim_rs::Neovim;
+use rmpv::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// What an asynchronous request was made for (returned with its response)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcRequest {
+    /// Cursor sync from Godot (only failures are reported)
+    SetCursor,
+    /// Neovim's cursor after it entered the buffer of this file (BufEnter)
+    BufEnterCursor(String),
+    /// Chunk of a large file appended after the buffer switch (script path,
+    /// whether it is the last chunk)
+    BufferChunk { path: String, last: bool },
+    /// Round trip measured by the status dock
+    StatusPing,
+    /// Marks saved to be restored if Neovim crashes (script path of the buffer marks)
+    MarksSnapshot(String),
+    /// Matches of the pattern typed so far (incremental search)
+    SearchMatches(String),
+    /// Last search pattern and 'hlsearch' state, for the search index of a script
+    SearchState(String),
+    /// Matches of the lines of a search index still to be searched (script path,
+    /// pattern with its case flag, generation of the index, line ranges)
+    SearchLines {
+        path: String,
+        pattern: String,
+        generation: u64,
+        ranges: Vec<(usize, usize)>,
+    },
+    /// Neovim's cursor after a search command (n, *, /foo<CR>)
+    SearchCursor,
+    /// Neovim's top line moved to where the editor can show it (only failures are
+    /// reported)
+    SetTopline,
+}
+
+/// Response to an asynchronous request
+#[derive(Debug, Clone)]
+pub struct RpcResponse {
+    pub request: RpcRequest,
+    pub result: Result<Value, String>,
+}
+
+/// Inbox of asynchronous responses, filled by the input task
+pub(in crate::neovim) type ResponseInbox = Arc<std::sync::Mutex<Vec<RpcResponse>>>;
+
+/// Scripts whose remaining large-file chunks are answered with an error instead
+/// of being appended (see NeovimClient::cancel_buffer_chunks)
+pub(in crate::neovim) type CancelledChunks = Arc<std::sync::Mutex<HashSet<String>>>;
+
+/// Error of the chunks skipped after cancel_buffer_chunks
+pub const CHUNK_CANCELLED: &str = "Cancelled";
+
+/// Item of the input queue
+pub(in crate::neovim) enum InputCommand {
+    /// Keys for nvim_input
+    Keys(String),
+    /// Cursor position (1-indexed line, 0-indexed byte column)
+    Cursor(i64, i64),
+    /// Lua code whose result is delivered to the inbox
+    Lua {
+        request: RpcRequest,
+        code: String,
+        args: Vec<Value>,
+    },
+}
+
+/// Send one queued item to Neovim (runs on the input task)
+pub(super) async fn run_input_command(
+    neovim_arc: &Arc<Mutex<Option<Neovim<Writer>>>>,
+    command: InputCommand,
+    responses: &ResponseInbox,
+    cancelled_chunks: &CancelledChunks,
+) {
+    // Checked before Neovim's state, so that the last chunk always clears its path
+    let cancelled = is_cancelled_chunk(&command, cancelled_chunks);
+    let nvim_lock = neovim_arc.lock().await;
+    let Some(neovim) = nvim_lock.as_ref() else {
+        // Requests are still answered, so that nothing waits for them forever
+        if let InputCommand::Lua { request, .. } = command {
+            if let Ok(mut inbox) = responses.lock() {
+                inbox.push(RpcResponse {
+                    request,
+                    result: Err("Neovim not connected".to_string()),
+                });
+            }
+        }
+        return;
+    };
+
+    let response = match command {
+        InputCommand::Keys(keys) => {
+            if let Err(e) = neovim.input(&keys).await {
+                // Log error but continue processing
+                // Note: Can't use godot_error here (tokio thread)
+                eprintln!("[godot-neovim] Failed to send key '{}': {}", keys, e);
+            }
+            None
+        }
+        InputCommand::Cursor(line, col) => {
+            // Timeout so that a busy Neovim does not stall the keys queued behind
+            let result = tokio::time::timeout(Duration::from_millis(RPC_TIMEOUT_MS), async {
+                let window = neovim
+                    .get_current_win()
+                    .await
+                    .map_err(|e| format!("Failed to set cursor: {}", e))?;
+                window
+                    .set_cursor((line, col))
+                    .await
+                    .map_err(|e| format!("Failed to set cursor: {}", e))
+            })
+            .await
+            .unwrap_or_else(|_| Err("Timeout setting cursor".to_string()));
+            result.err().map(|e| RpcResponse {
+                request: RpcRequest::SetCursor,
+                result: Err(e),
+            })
+        }
+        InputCommand::Lua {
+            request,
+            code,
+            args,
+        } => {
+            let result = if cancelled {
+                Err(CHUNK_CANCELLED.to_string())
+            } else {
+                tokio::time::timeout(
+                    Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                    neovim.exec_lua(&code, args),
+                )
+                .await
+                .map_err(|_| "Timeout executing Lua".to_string())
+                .and_then(|result| result.map_err(|e| format!("Failed to execute Lua: {}", e)))
+            };
+            Some(RpcResponse { request, result })
+        }
+    };
+
+    if let Some(response) = response {
+        if let Ok(mut inbox) = responses.lock() {
+            inbox.push(response);
+        }
+    }
+}
+
+/// Wait (bounded) until the queued input has been sent, so that a blocking
+/// request issued after it is answered after it
+pub(super) async fn wait_for_queue(pending_input: &AtomicUsize) {
+    let _ = tokio::time::timeout(Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS), async {
+        while pending_input.load(Ordering::SeqCst) > 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await;
+}
+
+/// Whether `command` is a large-file chunk skipped by cancel_buffer_chunks
+/// (the last chunk of a cancelled script clears it)
+fn is_cancelled_chunk(command: &InputCommand, cancelled_chunks: &CancelledChunks) -> bool {
+    let InputCommand::Lua {
+        request: RpcRequest::BufferChunk { path, last },
+        ..
+    } = command
+    else {
+        return false;
+    };
+    let Ok(mut cancelled) = cancelled_chunks.lock() else {
+        return false;
+    };
+    if *last {
+        cancelled.remove(path)
+    } else {
+        cancelled.contains(path)
+    }
+}
 
 impl NeovimClient {
-    /// Send keys to Neovim with timeout
+    /// Send keys to Neovim with timeout (after the queued input)
     pub fn input(&self, keys: &str) -> Result<(), String> {
         let neovim_arc = self.neovim.clone();
         let keys = keys.to_string();
 
         self.runtime.block_on(async {
+            wait_for_queue(&self.pending_input).await;
             let result =
                 tokio::time::timeout(std::time::Duration::from_millis(RPC_TIMEOUT_MS), async {
                     let nvim_lock = neovim_arc.lock().await;
@@ -36,11 +225,84 @@ impl NeovimClient {
     /// Keys are processed in order by a dedicated task
     /// Returns true if key was queued, false if channel is not available
     pub fn send_key_via_channel(&self, keys: &str) -> bool {
+        self.queue_input(InputCommand::Keys(keys.to_string()))
+    }
+
+    /// Set the cursor (1-indexed line, 0-indexed byte column) after the keys
+    /// queued so far, without waiting. A failure arrives in the inbox.
+    pub fn set_cursor_async(&self, line: i64, col: i64) -> bool {
+        self.queue_input(InputCommand::Cursor(line, col))
+    }
+
+    /// Skip the chunks of `path` still in the input queue: they are answered
+    /// with CHUNK_CANCELLED instead of being appended, up to the last one
+    pub fn cancel_buffer_chunks(&self, path: &str) {
+        if let Ok(mut cancelled) = self.cancelled_chunks.lock() {
+            cancelled.insert(path.to_string());
+        }
+    }
+
+    /// Run Lua code after the keys queued so far, without waiting
+    /// The result arrives in the inbox, tagged with `request`.
+    pub fn request_lua(&self, request: RpcRequest, code: &str, args: Vec<Value>) -> bool {
+        self.queue_input(InputCommand::Lua {
+            request,
+            code: code.to_string(),
+            args,
+        })
+    }
+
+    /// Take the responses received since the last call
+    pub fn take_responses(&self) -> Vec<RpcResponse> {
+        self.responses
+            .lock()
+            .map(|mut inbox| std::mem::take(&mut *inbox))
+            .unwrap_or_default()
+    }
+
+    fn queue_input(&self, command: InputCommand) -> bool {
         if let Some(ref tx) = self.key_input_tx {
             // send() on unbounded channel never blocks and only fails if receiver is dropped
-            tx.send(keys.to_string()).is_ok()
-        } else {
-            false
+            self.pending_input.fetch_add(1, Ordering::SeqCst);
+            if tx.send(command).is_ok() {
+                return true;
+            }
+            self.pending_input.fetch_sub(1, Ordering::SeqCst);
         }
+        false
+    }
+
+    /// Wait until the keys queued via the channel are sent and Neovim has processed
+    /// them (Neovim answers a request only after consuming its typeahead)
+    pub fn wait_for_input(&self) -> Result<(), String> {
+        let neovim_arc = self.neovim.clone();
+        let pending_input = self.pending_input.clone();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    while pending_input.load(Ordering::SeqCst) > 0 {
+                        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                    }
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        neovim
+                            .exec_lua("return true", vec![])
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| format!("Failed to sync input: {}", e))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout waiting for input".to_string()),
+            }
+        })
     }
 }
@@ -0,0 +1,201 @@
+//! Mark operations: list buffer marks, file marks and the jumplist, find tags
+
+use super::{NeovimClient, RPC_EXTENDED_TIMEOUT_MS};
+use rmpv::Value;
+
+/// A Neovim mark: buffer mark (`a`-`z`) or file mark (`A`-`Z`)
+#[derive(Debug, Clone)]
+pub struct MarkEntry {
+    /// Mark name
+    pub name: char,
+    /// Line (1-indexed)
+    pub line: i64,
+    /// Byte column (0-indexed)
+    pub col: i64,
+    /// Absolute file path (empty for buffer marks, which belong to the current buffer)
+    pub file: String,
+}
+
+/// An entry of Neovim's jumplist
+#[derive(Debug, Clone)]
+pub struct JumpEntry {
+    /// Line (1-indexed)
+    pub line: i64,
+    /// Byte column (0-indexed)
+    pub col: i64,
+    /// Absolute file path (empty if the buffer has no name)
+    pub file: String,
+    /// Text of the line (empty if the buffer is not loaded)
+    pub text: String,
+}
+
+impl NeovimClient {
+    /// Get the marks of the current buffer and all file marks (as shown by :marks)
+    pub fn get_marks(&self) -> Result<Vec<MarkEntry>, String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .exec_lua("return _G.godot_neovim.get_marks()", vec![])
+                            .await
+                            .map_err(|e| format!("Failed to get marks: {}", e))?;
+                        Ok(parse_marks(result))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout getting marks".to_string()),
+            }
+        })
+    }
+
+    /// Get the jumplist of the current window (as shown by :jumps)
+    /// Returns the entries (oldest first) and the current position in them
+    /// (`entries.len()` after the newest jump).
+    pub fn get_jumplist(&self) -> Result<(Vec<JumpEntry>, usize), String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .exec_lua("return _G.godot_neovim.get_jumplist()", vec![])
+                            .await
+                            .map_err(|e| format!("Failed to get jumplist: {}", e))?;
+                        Ok(parse_jumplist(result))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout getting jumplist".to_string()),
+            }
+        })
+    }
+
+    /// Find the first tag named `name` in the tags files of the 'tags' option
+    /// (None if there is no tags file or no such tag)
+    pub fn find_tag(&self, name: &str) -> Result<Option<TagLocation>, String> {
+        let neovim_arc = self.neovim.clone();
+        let name = name.to_string();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .exec_lua(
+                                "return _G.godot_neovim.find_tag(...)",
+                                vec![Value::from(name)],
+                            )
+                            .await
+                            .map_err(|e| format!("Failed to find tag: {}", e))?;
+                        Ok(parse_tag_location(result))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout finding tag".to_string()),
+            }
+        })
+    }
+}
+
+/// Location of a tag found in the tags files
+#[derive(Debug, Clone)]
+pub struct TagLocation {
+    /// Absolute file path
+    pub file: String,
+    /// Line (1-indexed)
+    pub line: i64,
+    /// Byte column of the tag name in the line (0-indexed)
+    pub col: i64,
+}
+
+/// Parse `{ {mark, line, col, file}, ... }` returned by get_marks
+fn parse_marks(value: Value) -> Vec<MarkEntry> {
+    let Value::Array(entries) = value else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let Value::Array(fields) = entry else {
+                return None;
+            };
+            let name = fields.first()?.as_str()?.chars().next()?;
+            let line = fields.get(1)?.as_i64()?;
+            let col = fields.get(2)?.as_i64()?;
+            let file = fields.get(3)?.as_str()?.to_string();
+            Some(MarkEntry {
+                name,
+                line,
+                col,
+                file,
+            })
+        })
+        .collect()
+}
+
+/// Parse `{ current, { {file, line, col, text}, ... } }` returned by get_jumplist
+fn parse_jumplist(value: Value) -> (Vec<JumpEntry>, usize) {
+    let Value::Array(fields) = value else {
+        return (Vec::new(), 0);
+    };
+    let current = fields.first().and_then(Value::as_u64).unwrap_or(0) as usize;
+    let Some(Value::Array(entries)) = fields.get(1) else {
+        return (Vec::new(), 0);
+    };
+    let entries: Vec<JumpEntry> = entries
+        .iter()
+        .filter_map(|entry| {
+            let Value::Array(fields) = entry else {
+                return None;
+            };
+            Some(JumpEntry {
+                file: fields.first()?.as_str()?.to_string(),
+                line: fields.get(1)?.as_i64()?,
+                col: fields.get(2)?.as_i64()?,
+                text: fields.get(3)?.as_str()?.to_string(),
+            })
+        })
+        .collect();
+    let current = current.min(entries.len());
+    (entries, current)
+}
+
+/// Parse `{ file, line, col }` (or nil) returned by find_tag
+fn parse_tag_location(value: Value) -> Option<TagLocation> {
+    let Value::Array(fields) = value else {
+        return None;
+    };
+    Some(TagLocation {
+        file: fields.first()?.as_str()?.to_string(),
+        line: fields.get(1)?.as_i64()?,
+        col: fields.get(2)?.as_i64()?,
+    })
+}
@@ -1,41 +1,57 @@
 //! Neovim client module
 //!
 //! This module organizes the NeovimClient into submodules:
-//! - connection: Process management (new, start, stop)
-//! - state: State polling (take_state, take_viewport, poll)
-//! - input: Key input (input, send_keys, channels)
-//! - buffer: Buffer operations (buffer_update, switch_to_buffer, attach)
+//! - connection: Process management and remote attach (new, start, stop)
+//! - state: State polling (take_state, viewport_since, poll)
+//! - input: Key input (input, send_keys, channels) and asynchronous requests
+//! - buffer: Buffer operations (buffer_update, switch_to_buffer, list_buffers, attach)
 //! - cursor: Cursor and visual selection
 //! - execution: Command and Lua execution
+//! - registers: Register contents
+//! - marks: Buffer and file marks
+//! - processes: Ownership of spawned Neovim processes (PID file, orphan sweep)
 
 mod buffer;
 mod connection;
 mod cursor;
 mod execution;
 mod input;
+mod marks;
+mod processes;
+mod registers;
 mod state;
+mod undo;
 
 use crate::neovim::{NeovimHandler, NeovimState};
+use input::{CancelledChunks, InputCommand, ResponseInbox};
 use nvim_rs::Neovim;
 use std::fmt;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex;
 
+pub use buffer::{parse_line_matches, parse_matches, parse_search_state, BufferEntry};
+pub use input::{RpcRequest, CHUNK_CANCELLED};
+pub use processes::sweep_orphans;
+pub use registers::RegisterEntry;
+
 #[cfg(target_os = "windows")]
 pub(super) const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 /// Minimum required Neovim version (major, minor, patch)
-pub(super) const NEOVIM_REQUIRED_VERSION: (u64, u64, u64) = (0, 9, 0);
+pub const NEOVIM_REQUIRED_VERSION: (u64, u64, u64) = (0, 9, 0);
 
-/// Default timeout for RPC commands (milliseconds)
+/// Default timeout for blocking RPC commands (milliseconds)
 pub(super) const RPC_TIMEOUT_MS: u64 = 100;
 
 /// Extended timeout for operations that may trigger dialogs (e.g., swap file)
 pub(super) const RPC_EXTENDED_TIMEOUT_MS: u64 = 500;
 
+/// Timeout for blocking Lua calls that may run external tools (e.g. the formatter)
+pub(super) const RPC_LUA_TIMEOUT_MS: u64 = 5000;
+
 /// Timeout recovery: Number of timeout errors to trigger recovery
 pub const TIMEOUT_RECOVERY_THRESHOLD: u32 = 3;
 
@@ -71,7 +87,11 @@ function _G.godot_neovim.buffer_update(bufnr, lines)
 end
 "#;
 
-pub(super) type Writer = nvim_rs::compat::tokio::Compat<tokio::process::ChildStdin>;
+/// RPC channel halves: the stdio of an embedded Neovim or a socket to a remote one
+pub(super) type Reader =
+    nvim_rs::compat::tokio::Compat<Box<dyn tokio::io::AsyncRead + Send + Unpin>>;
+pub(super) type Writer =
+    nvim_rs::compat::tokio::Compat<Box<dyn tokio::io::AsyncWrite + Send + Unpin>>;
 
 /// Neovim version information
 #[derive(Debug, Clone, Default)]
@@ -117,6 +137,8 @@ pub struct SwitchBufferResult {
     pub tick: i64,
     /// Whether this is a newly created buffer
     pub is_new: bool,
+    /// Whether the buffer content was set from the lines passed (not an existing buffer)
+    pub initialized: bool,
     /// Whether buffer is attached for notifications
     pub attached: bool,
     /// Cursor position (line, col) - line is 1-indexed, col is 0-indexed
@@ -140,6 +162,12 @@ pub struct NeovimClient {
     pub(super) nvim_path: String,
     /// Start Neovim with --clean flag (no plugins or user config)
     pub(super) clean: bool,
+    /// Init file loaded with -u when not clean (empty: the user's default init)
+    pub(super) init_path: String,
+    /// User config files sourced after start (allow-list, also in clean mode)
+    pub(super) config_files: Vec<String>,
+    /// Address of a running Neovim to attach to instead of spawning one
+    pub(super) remote_address: Option<String>,
     /// Shared state from handler (mode, cursor position)
     pub(super) state: Arc<Mutex<NeovimState>>,
     /// Flag indicating new updates from redraw events
@@ -148,11 +176,28 @@ pub struct NeovimClient {
     #[allow(dead_code)]
     pub(super) io_handle:
         Option<tokio::task::JoinHandle<Result<(), Box<nvim_rs::error::LoopError>>>>,
-    /// Key input channel sender (unbounded for no key drops)
-    pub(super) key_input_tx: Option<UnboundedSender<String>>,
+    /// Input queue sender: keys, cursor syncs, asynchronous requests (unbounded
+    /// for no key drops)
+    pub(super) key_input_tx: Option<UnboundedSender<InputCommand>>,
+    /// Items in the input queue that have not been sent to Neovim yet
+    pub(super) pending_input: Arc<AtomicUsize>,
+    /// Responses to asynchronous requests, drained by the plugin every frame
+    pub(super) responses: ResponseInbox,
+    /// Scripts whose queued large-file chunks are skipped
+    pub(super) cancelled_chunks: CancelledChunks,
     /// Key input processor task handle
     #[allow(dead_code)]
     pub(super) key_input_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Process ID of the running Neovim
+    pub(super) process_id: Option<u32>,
+    /// When the running Neovim was started
+    pub(super) started_at: Option<std::time::Instant>,
+    /// Version of the running Neovim
+    pub(super) version: Option<NeovimVersion>,
+    /// How the Neovim process exited, set by the supervisor task (None while running)
+    pub(super) exit_status: Arc<std::sync::Mutex<Option<String>>>,
+    /// Supervisor task waiting for the Neovim process to exit
+    pub(super) supervisor_handle: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl Default for NeovimClient {
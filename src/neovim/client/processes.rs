@@ -0,0 +1,244 @@
+//! Ownership of spawned Neovim processes
+//!
+//! Every Neovim is started with GODOT_NEOVIM_OWNER set to the editor's process ID
+//! and recorded in a PID file under the project's .godot directory. A client kills
+//! its process when it stops (the child is killed on drop), so disabling the plugin
+//! leaves nothing behind. Entries left by an editor that crashed are swept when the
+//! plugin is next activated: their processes are killed if they are still running
+//! and still are Neovim (otherwise the entry is only removed).
+
+use crate::settings;
+use godot::classes::{Os, ProjectSettings};
+use godot::obj::Singleton;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable marking a Neovim started by this plugin (value: editor PID)
+pub(super) const OWNER_ENV: &str = "GODOT_NEOVIM_OWNER";
+
+/// PID file: one `{nvim pid} {editor pid}` line per running Neovim
+const PID_FILE: &str = "res://.godot/godot-neovim/nvim.pids";
+
+/// A Neovim process recorded in the PID file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcessEntry {
+    pid: u32,
+    owner: u32,
+}
+
+fn parse_entries(text: &str) -> Vec<ProcessEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let (pid, owner) = line.trim().split_once(' ')?;
+            Some(ProcessEntry {
+                pid: pid.parse().ok()?,
+                owner: owner.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn format_entries(entries: &[ProcessEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| format!("{} {}\n", entry.pid, entry.owner))
+        .collect()
+}
+
+fn pid_file_path() -> PathBuf {
+    PathBuf::from(
+        ProjectSettings::singleton()
+            .globalize_path(PID_FILE)
+            .to_string(),
+    )
+}
+
+fn read_entries(path: &Path) -> Vec<ProcessEntry> {
+    std::fs::read_to_string(path)
+        .map(|text| parse_entries(&text))
+        .unwrap_or_default()
+}
+
+fn write_entries(path: &Path, entries: &[ProcessEntry]) {
+    let result = if entries.is_empty() {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    } else {
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(path, format_entries(entries)))
+    };
+    if let Err(e) = result {
+        crate::verbose_print!("[godot-neovim] Failed to update {}: {}", path.display(), e);
+    }
+}
+
+/// Record a Neovim started by this editor
+pub(super) fn register(pid: u32) {
+    let path = pid_file_path();
+    let mut entries = read_entries(&path);
+    entries.retain(|entry| entry.pid != pid);
+    entries.push(ProcessEntry {
+        pid,
+        owner: std::process::id(),
+    });
+    write_entries(&path, &entries);
+}
+
+/// Forget a Neovim that was stopped
+pub(super) fn unregister(pid: u32) {
+    let path = pid_file_path();
+    let mut entries = read_entries(&path);
+    entries.retain(|entry| entry.pid != pid);
+    write_entries(&path, &entries);
+}
+
+/// Whether process `pid` still is the Neovim started by editor `owner` (the PID
+/// may have been reused, e.g. after a reboot)
+/// On Linux its environment must have the owner marker; elsewhere its executable
+/// must be Neovim. False if this cannot be checked.
+fn is_owned(pid: u32, owner: u32) -> bool {
+    if !Path::new("/proc/self").exists() {
+        return process_executable(pid)
+            .is_some_and(|exe| is_neovim_executable(&exe, &settings::get_neovim_path()));
+    }
+    match std::fs::read(format!("/proc/{}/environ", pid)) {
+        Ok(environ) => {
+            let marker = format!("{}={}", OWNER_ENV, owner);
+            environ
+                .split(|&byte| byte == 0)
+                .any(|var| var == marker.as_bytes())
+        }
+        Err(_) => false,
+    }
+}
+
+/// Executable of process `pid`, from the OS's process list (tasklist on Windows,
+/// ps elsewhere). None if it cannot be read.
+fn process_executable(pid: u32) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut command = Command::new("tasklist");
+        command
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+            .creation_flags(CREATE_NO_WINDOW);
+        command
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut command = Command::new("ps");
+        command.args(["-p", &pid.to_string(), "-o", "comm="]);
+        command
+    };
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_executable(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Executable in the output of `ps -o comm=` (a path) or `tasklist /FO CSV /NH`
+/// (the image name is the first quoted field; a process that is not running
+/// gives an unquoted message)
+fn parse_executable(output: &str) -> Option<String> {
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    match line.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().map(str::to_string),
+        None => Some(line.to_string()),
+    }
+}
+
+/// Whether `exe` (a name or path) is Neovim: `nvim` or the configured executable
+fn is_neovim_executable(exe: &str, configured: &str) -> bool {
+    let stem = |path: &str| {
+        Path::new(&path.replace('\\', "/"))
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+    };
+    stem(exe).is_some_and(|exe| exe == "nvim" || stem(configured).as_ref() == Some(&exe))
+}
+
+/// Kill the Neovim processes left by editors that are no longer running
+/// (e.g. after a crash). Returns the number of processes killed.
+pub fn sweep_orphans() -> usize {
+    let path = pid_file_path();
+    let entries = read_entries(&path);
+    if entries.is_empty() {
+        return 0;
+    }
+
+    let mut os = Os::singleton();
+    let mut killed = 0;
+    let mut live = Vec::new();
+    for entry in entries {
+        if entry.owner == std::process::id() || os.is_process_running(entry.owner as i32) {
+            // Owned by this editor or another one that is still open
+            live.push(entry);
+            continue;
+        }
+        if os.is_process_running(entry.pid as i32) && is_owned(entry.pid, entry.owner) {
+            crate::verbose_print!(
+                "[godot-neovim] Killing orphaned Neovim (pid {}, editor {} exited)",
+                entry.pid,
+                entry.owner
+            );
+            if os.kill(entry.pid as i32) == godot::global::Error::OK {
+                killed += 1;
+            }
+        } else {
+            crate::verbose_print!(
+                "[godot-neovim] Dropping stale Neovim entry (pid {} is not running or not Neovim)",
+                entry.pid
+            );
+        }
+    }
+    write_entries(&path, &live);
+    killed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_file_entries_round_trip() {
+        let entries = parse_entries("123 45\nbroken\n678 45\n\n9 x\n");
+        assert_eq!(
+            entries,
+            vec![
+                ProcessEntry {
+                    pid: 123,
+                    owner: 45
+                },
+                ProcessEntry {
+                    pid: 678,
+                    owner: 45
+                },
+            ]
+        );
+        assert_eq!(parse_entries(&format_entries(&entries)), entries);
+    }
+
+    #[test]
+    fn test_neovim_executable() {
+        let ps = parse_executable("/opt/homebrew/bin/nvim\n").unwrap();
+        assert!(is_neovim_executable(&ps, "nvim"));
+        let tasklist = parse_executable("\"nvim.exe\",\"1234\",\"Console\",\"1\",\"9 K\"\r\n");
+        assert_eq!(tasklist.as_deref(), Some("nvim.exe"));
+        assert!(is_neovim_executable("C:\\Neovim\\bin\\NVIM.EXE", ""));
+        assert!(is_neovim_executable(
+            "/usr/local/bin/nvim-0.11",
+            "/usr/local/bin/nvim-0.11"
+        ));
+        assert!(!is_neovim_executable("/usr/bin/bash", "nvim"));
+        assert_eq!(parse_executable("\n"), None);
+    }
+}
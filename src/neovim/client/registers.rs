@@ -0,0 +1,69 @@
+//! Register operations: list register contents
+
+use super::{NeovimClient, RPC_EXTENDED_TIMEOUT_MS};
+use rmpv::Value;
+
+/// A non-empty Neovim register
+#[derive(Debug, Clone)]
+pub struct RegisterEntry {
+    /// Register name (`"`, `0`-`9`, `a`-`z`, `-`, `*`, `+`, `.`, `:`, `%`, `#`, `/`)
+    pub name: char,
+    /// Register type: `v` (charwise), `V` (linewise) or `<C-v>{width}` (blockwise)
+    pub regtype: String,
+    /// Content, lines joined with '\n'
+    pub text: String,
+}
+
+impl NeovimClient {
+    /// Get all non-empty registers (as shown by :registers)
+    pub fn get_registers(&self) -> Result<Vec<RegisterEntry>, String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .exec_lua("return _G.godot_neovim.get_registers()", vec![])
+                            .await
+                            .map_err(|e| format!("Failed to get registers: {}", e))?;
+                        Ok(parse_registers(result))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout getting registers".to_string()),
+            }
+        })
+    }
+}
+
+/// Parse `{ {name, regtype, text}, ... }` returned by get_registers
+fn parse_registers(value: Value) -> Vec<RegisterEntry> {
+    let Value::Array(entries) = value else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let Value::Array(fields) = entry else {
+                return None;
+            };
+            let name = fields.first()?.as_str()?.chars().next()?;
+            let regtype = fields.get(1)?.as_str()?.to_string();
+            let text = fields.get(2)?.as_str()?.to_string();
+            Some(RegisterEntry {
+                name,
+                regtype,
+                text,
+            })
+        })
+        .collect()
+}
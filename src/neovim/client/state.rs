@@ -1,6 +1,7 @@
 //! State management: poll, take_state, viewport
 
 use super::NeovimClient;
+use crate::neovim::{NeovimMessage, Viewport};
 use std::sync::atomic::Ordering;
 
 impl NeovimClient {
@@ -26,34 +27,18 @@ impl NeovimClient {
         })
     }
 
-    /// Take viewport changes (topline, botline, curline, curcol) if viewport has changed
-    /// Returns None if viewport hasn't changed since last call
-    /// The curline/curcol are the buffer cursor positions from win_viewport
-    pub fn take_viewport(&self) -> Option<(i64, i64, i64, i64)> {
-        self.runtime.block_on(async {
-            let mut state = self.state.lock().await;
-            if state.viewport_changed {
-                state.viewport_changed = false;
-                Some((
-                    state.viewport_topline,
-                    state.viewport_botline,
-                    state.viewport_curline,
-                    state.viewport_curcol,
-                ))
-            } else {
-                None
-            }
-        })
+    /// Latest viewport (win_viewport) if it is newer than the one numbered `seen_seq`
+    /// Viewports are numbered in the order Neovim sent them; repeated ones within a
+    /// redraw batch collapse into the last (see RedrawBatch)
+    pub fn viewport_since(&self, seen_seq: u64) -> Option<Viewport> {
+        self.runtime
+            .block_on(async { self.state.lock().await.viewport_since(seen_seq) })
     }
 
-    /// Force viewport_changed flag to true
-    /// Used after buffer switch to ensure next viewport event is processed
-    /// even if the values haven't changed from previous buffer
-    pub fn force_viewport_changed(&self) {
-        self.runtime.block_on(async {
-            let mut state = self.state.lock().await;
-            state.viewport_changed = true;
-        })
+    /// Sequence number of the latest viewport
+    pub fn viewport_seq(&self) -> u64 {
+        self.runtime
+            .block_on(async { self.state.lock().await.viewport.seq })
     }
 
     /// Resize Neovim's UI to match Godot editor's visible area
@@ -78,17 +63,11 @@ impl NeovimClient {
         });
     }
 
-    /// Poll the runtime to process pending async events (like redraw notifications)
-    /// This must be called regularly (e.g., every frame) to receive events
+    /// Give Neovim a moment to process the input just sent and send its events
+    /// Events are received in the background; this is only needed by callers
+    /// that read the resulting state right away.
     pub fn poll(&self) {
-        self.runtime.block_on(async {
-            // Give the runtime a chance to process IO events
-            // 1ms allows enough time for:
-            // 1. spawn() tasks to execute (input_async)
-            // 2. Neovim to process input and send redraw events
-            // 3. IO handler to receive and process events
-            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-        });
+        std::thread::sleep(std::time::Duration::from_millis(1));
     }
 
     /// Take pending debug messages from Lua
@@ -99,4 +78,14 @@ impl NeovimClient {
             std::mem::take(&mut state.debug_messages)
         })
     }
+
+    /// Take the messages shown by Neovim since the last call (ext_messages)
+    /// Returns whether the message area was cleared before them, and the messages.
+    pub fn take_messages(&self) -> (bool, Vec<NeovimMessage>) {
+        self.runtime.block_on(async {
+            let mut state = self.state.lock().await;
+            let cleared = std::mem::take(&mut state.messages_cleared);
+            (cleared, std::mem::take(&mut state.messages))
+        })
+    }
 }
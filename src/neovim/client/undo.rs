@@ -0,0 +1,203 @@
+//! Undo tree: the undo states of the current buffer (undotree()), and the undo
+//! files that keep them across sessions
+
+use super::{NeovimClient, RPC_EXTENDED_TIMEOUT_MS};
+use rmpv::Value;
+
+/// An undo state of the current buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoNode {
+    /// Undo sequence number (`:undo {seq}` restores it)
+    pub seq: i64,
+    /// When the change was made (seconds since the epoch)
+    pub time: i64,
+    /// Branch depth: 0 for the main branch, +1 for each alternate branch
+    pub depth: usize,
+    /// Whether the buffer was written in this state
+    pub saved: bool,
+}
+
+/// The undo tree of the current buffer
+#[derive(Debug, Clone, Default)]
+pub struct UndoTree {
+    /// Sequence number of the current state (0: the original text)
+    pub seq_cur: i64,
+    /// Every state, newest first
+    pub nodes: Vec<UndoNode>,
+}
+
+impl NeovimClient {
+    /// Get the undo tree of the current buffer (undotree())
+    pub fn get_undotree(&self) -> Result<UndoTree, String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .call_function("undotree", vec![])
+                            .await
+                            .map_err(|e| format!("Failed to get undo tree: {}", e))?;
+                        Ok(parse_undotree(&result))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout getting undo tree".to_string()),
+            }
+        })
+    }
+
+    /// Keep undo files in `dir` ("" turns persistent undo off)
+    pub fn set_undo_dir(&self, dir: &str) -> Result<(), String> {
+        let neovim_arc = self.neovim.clone();
+
+        self.runtime.block_on(async {
+            let nvim_lock = neovim_arc.lock().await;
+            if let Some(neovim) = nvim_lock.as_ref() {
+                let args = vec![Value::from(dir)];
+
+                neovim
+                    .exec_lua("_G.godot_neovim.set_undo_dir(...)", args)
+                    .await
+                    .map_err(|e| format!("Failed to set undo directory: {}", e))?;
+
+                Ok(())
+            } else {
+                Err("Neovim not connected".to_string())
+            }
+        })
+    }
+
+    /// Write the undo file of the buffer of `path` (after the file was saved)
+    /// Returns false if the buffer is not loaded or persistent undo is off.
+    pub fn write_undo_file(&self, path: &str) -> Result<bool, String> {
+        let neovim_arc = self.neovim.clone();
+        let path = path.to_string();
+
+        self.runtime.block_on(async {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_millis(RPC_EXTENDED_TIMEOUT_MS),
+                async {
+                    let nvim_lock = neovim_arc.lock().await;
+                    if let Some(neovim) = nvim_lock.as_ref() {
+                        let result = neovim
+                            .exec_lua(
+                                "return _G.godot_neovim.write_undo_file(...)",
+                                vec![Value::from(path)],
+                            )
+                            .await
+                            .map_err(|e| format!("Failed to write undo file: {}", e))?;
+                        Ok(result.as_bool().unwrap_or(false))
+                    } else {
+                        Err("Neovim not connected".to_string())
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(inner) => inner,
+                Err(_) => Err("Timeout writing undo file".to_string()),
+            }
+        })
+    }
+}
+
+/// Value of `key` in a Dictionary returned by Neovim
+fn field<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    let Value::Map(map) = value else {
+        return None;
+    };
+    map.iter()
+        .find(|(k, _)| k.as_str() == Some(key))
+        .map(|(_, v)| v)
+}
+
+/// Add the undo blocks of a branch, and their alternate branches one level deeper
+fn collect_nodes(entries: &[Value], depth: usize, nodes: &mut Vec<UndoNode>) {
+    for entry in entries {
+        let Some(seq) = field(entry, "seq").and_then(Value::as_i64) else {
+            continue;
+        };
+        nodes.push(UndoNode {
+            seq,
+            time: field(entry, "time").and_then(Value::as_i64).unwrap_or(0),
+            depth,
+            saved: field(entry, "save").is_some(),
+        });
+        if let Some(Value::Array(alt)) = field(entry, "alt") {
+            collect_nodes(alt, depth + 1, nodes);
+        }
+    }
+}
+
+/// Parse the Dictionary returned by undotree()
+fn parse_undotree(value: &Value) -> UndoTree {
+    let mut nodes = Vec::new();
+    if let Some(Value::Array(entries)) = field(value, "entries") {
+        collect_nodes(entries, 0, &mut nodes);
+    }
+    nodes.sort_by_key(|node| std::cmp::Reverse(node.seq));
+    UndoTree {
+        seq_cur: field(value, "seq_cur").and_then(Value::as_i64).unwrap_or(0),
+        nodes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seq: i64, extra: Vec<(&str, Value)>) -> Value {
+        let mut map = vec![
+            (Value::from("seq"), Value::from(seq)),
+            (Value::from("time"), Value::from(1000 + seq)),
+        ];
+        map.extend(extra.into_iter().map(|(k, v)| (Value::from(k), v)));
+        Value::Map(map)
+    }
+
+    #[test]
+    fn test_parse_undotree() {
+        // 1 -> 2 -> 4 (saved), with 3 undone and replaced by 4
+        let tree = Value::Map(vec![
+            (Value::from("seq_cur"), Value::from(2)),
+            (
+                Value::from("entries"),
+                Value::Array(vec![
+                    entry(1, vec![]),
+                    entry(2, vec![]),
+                    entry(
+                        4,
+                        vec![
+                            ("save", Value::from(1)),
+                            ("alt", Value::Array(vec![entry(3, vec![])])),
+                        ],
+                    ),
+                ]),
+            ),
+        ]);
+
+        let tree = parse_undotree(&tree);
+        assert_eq!(tree.seq_cur, 2);
+        let nodes: Vec<(i64, usize, bool)> = tree
+            .nodes
+            .iter()
+            .map(|node| (node.seq, node.depth, node.saved))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![(4, 0, true), (3, 1, false), (2, 0, false), (1, 0, false)]
+        );
+        assert_eq!(tree.nodes[1].time, 1003);
+    }
+}
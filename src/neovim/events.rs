@@ -1,7 +1,8 @@
-//! Typed redraw events from Neovim
+//! Typed redraw and autocmd events from Neovim
 //!
 //! Inspired by neovide's event parsing, this module provides type-safe
-//! parsing of Neovim redraw events.
+//! parsing of Neovim redraw events, and of the autocmds forwarded by the
+//! autocmd bridge of the Lua plugin (godot_autocmd notifications).
 
 use rmpv::Value;
 
@@ -24,12 +25,78 @@ pub enum RedrawEvent {
         line_count: i64,
         scroll_delta: i64,
     },
+    /// Message shown by Neovim (from ext_messages)
+    /// `content` is the text of its chunks without highlights
+    MsgShow {
+        kind: String,
+        content: String,
+        replace_last: bool,
+    },
+    /// Messages removed from the message area (from ext_messages)
+    MsgClear,
     /// Flush signals end of redraw batch
     Flush,
     /// Unknown or unhandled event
     Unknown(String),
 }
 
+/// Autocmd forwarded by the autocmd bridge (integration.lua setup_autocmd_bridge)
+/// Positions are the cursor when the event fired (0-indexed line, character column).
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutocmdEvent {
+    /// Insert mode left in Neovim
+    InsertLeave { buf: i64 },
+    /// Cursor rested for 'updatetime' ms in Normal mode
+    CursorHold { buf: i64, line: i64, col: i64 },
+    /// Buffer written by Neovim itself (Godot buffers are saved by Godot)
+    BufWritePost { buf: i64, file: String },
+    /// Event without a typed variant
+    Unknown(String),
+}
+
+impl AutocmdEvent {
+    /// Parse the `{ event, buf, file, line, col }` of a godot_autocmd notification
+    pub fn parse(value: &Value) -> Result<AutocmdEvent, ParseError> {
+        let Value::Map(map) = value else {
+            return Err(ParseError {
+                event_name: "godot_autocmd".to_string(),
+                reason: "Expected a map".to_string(),
+            });
+        };
+        let field = |name: &str| {
+            map.iter()
+                .find(|(key, _)| key.as_str() == Some(name))
+                .map(|(_, value)| value)
+        };
+        let number = |name: &str| field(name).and_then(|v| v.as_i64()).unwrap_or(0);
+
+        let event = field("event")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ParseError {
+                event_name: "godot_autocmd".to_string(),
+                reason: "Missing event".to_string(),
+            })?;
+        let buf = number("buf");
+
+        Ok(match event {
+            "InsertLeave" => AutocmdEvent::InsertLeave { buf },
+            "CursorHold" => AutocmdEvent::CursorHold {
+                buf,
+                line: number("line"),
+                col: number("col"),
+            },
+            "BufWritePost" => AutocmdEvent::BufWritePost {
+                buf,
+                file: field("file")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            },
+            _ => AutocmdEvent::Unknown(event.to_string()),
+        })
+    }
+}
+
 /// Error type for event parsing
 #[derive(Debug, Clone)]
 pub struct ParseError {
@@ -83,6 +150,17 @@ impl RedrawEvent {
                     }
                 }
             }
+            "msg_show" => {
+                // msg_show: ["msg_show", [kind, content, replace_last, ...], ...]
+                for i in 1..event_data.len() {
+                    if let Some(event) = Self::parse_msg_show(event_data.get(i))? {
+                        events.push(event);
+                    }
+                }
+            }
+            "msg_clear" => {
+                events.push(RedrawEvent::MsgClear);
+            }
             "flush" => {
                 events.push(RedrawEvent::Flush);
             }
@@ -193,6 +271,44 @@ impl RedrawEvent {
             scroll_delta,
         }))
     }
+
+    fn parse_msg_show(value: Option<&Value>) -> Result<Option<RedrawEvent>, ParseError> {
+        let Some(Value::Array(info)) = value else {
+            return Ok(None);
+        };
+
+        let kind = info
+            .first()
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ParseError {
+                event_name: "msg_show".to_string(),
+                reason: "Missing kind".to_string(),
+            })?
+            .to_string();
+
+        // content: [[attr_id, text_chunk, hl_id], ...]
+        let Some(Value::Array(chunks)) = info.get(1) else {
+            return Err(ParseError {
+                event_name: "msg_show".to_string(),
+                reason: "Missing content".to_string(),
+            });
+        };
+        let content = chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Value::Array(chunk) => chunk.get(1).and_then(|v| v.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let replace_last = info.get(2).and_then(|v| v.as_bool()).unwrap_or(false);
+
+        Ok(Some(RedrawEvent::MsgShow {
+            kind,
+            content,
+            replace_last,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -273,6 +389,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_msg_show() {
+        let chunk = |text: &str| Value::Array(vec![Value::from(0u64), Value::from(text)]);
+        let event_data = vec![
+            Value::from("msg_show"),
+            Value::Array(vec![
+                Value::from("emsg"),
+                Value::Array(vec![chunk("E486: "), chunk("Pattern not found: foo")]),
+                Value::from(false),
+            ]),
+        ];
+
+        let events = RedrawEvent::parse(&event_data).unwrap();
+        assert_eq!(
+            events,
+            vec![RedrawEvent::MsgShow {
+                kind: "emsg".to_string(),
+                content: "E486: Pattern not found: foo".to_string(),
+                replace_last: false,
+            }]
+        );
+
+        let events = RedrawEvent::parse(&[Value::from("msg_clear")]).unwrap();
+        assert_eq!(events, vec![RedrawEvent::MsgClear]);
+    }
+
+    #[test]
+    fn test_parse_autocmd() {
+        let event = |fields: Vec<(&str, Value)>| {
+            Value::Map(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (Value::from(key), value))
+                    .collect(),
+            )
+        };
+
+        let value = event(vec![
+            ("event", Value::from("CursorHold")),
+            ("buf", Value::from(3)),
+            ("file", Value::from("/project/player.gd")),
+            ("line", Value::from(12)),
+            ("col", Value::from(4)),
+        ]);
+        assert_eq!(
+            AutocmdEvent::parse(&value).unwrap(),
+            AutocmdEvent::CursorHold {
+                buf: 3,
+                line: 12,
+                col: 4
+            }
+        );
+
+        let value = event(vec![("event", Value::from("BufEnter"))]);
+        assert_eq!(
+            AutocmdEvent::parse(&value).unwrap(),
+            AutocmdEvent::Unknown("BufEnter".to_string())
+        );
+        assert!(AutocmdEvent::parse(&event(vec![])).is_err());
+    }
+
     #[test]
     fn test_parse_flush() {
         let event_data = vec![Value::from("flush")];
@@ -1,4 +1,4 @@
-use super::events::RedrawEvent;
+use super::events::{AutocmdEvent, RedrawEvent};
 use crate::sync::BufLinesEvent;
 use nvim_rs::Handler;
 use rmpv::Value;
@@ -19,18 +19,139 @@ pub struct NeovimState {
     /// Actual cursor position (line, col) - line is 0-indexed, col is byte position
     /// This comes from CursorMoved autocmd and is the true buffer position
     pub actual_cursor: Option<(i64, i64)>,
-    /// Viewport top line (0-indexed) - first visible line from win_viewport
-    pub viewport_topline: i64,
-    /// Viewport bottom line (0-indexed, exclusive) - last visible line + 1 from win_viewport
-    pub viewport_botline: i64,
-    /// Cursor line from win_viewport (buffer position, 0-indexed)
-    pub viewport_curline: i64,
-    /// Cursor column from win_viewport (buffer position, 0-indexed)
-    pub viewport_curcol: i64,
-    /// Flag indicating viewport has changed since last read
-    pub viewport_changed: bool,
+    /// Latest viewport from win_viewport (seq 0 until the first one arrives)
+    pub viewport: Viewport,
     /// Debug messages from Lua (printed on Godot main thread)
     pub debug_messages: Vec<String>,
+    /// Messages shown by Neovim since the last read (from ext_messages)
+    pub messages: Vec<NeovimMessage>,
+    /// Whether the message area was cleared since the last read (before `messages`)
+    pub messages_cleared: bool,
+}
+
+/// Window viewport from win_viewport
+/// The plugin takes both the scroll and the cursor position from it: its cursor is
+/// in buffer coordinates, while grid_cursor_goto is screen-relative with ext_multigrid.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Viewport {
+    /// Sequence number, increased for every published win_viewport (also with
+    /// unchanged values), so the plugin can tell a new viewport from a stale one
+    pub seq: u64,
+    /// First visible line (0-indexed)
+    pub topline: i64,
+    /// Last visible line + 1 (0-indexed, exclusive)
+    pub botline: i64,
+    /// Cursor line (0-indexed)
+    pub curline: i64,
+    /// Cursor column (0-indexed byte position)
+    pub curcol: i64,
+}
+
+impl NeovimState {
+    /// Publish a viewport from win_viewport with the next sequence number
+    fn publish_viewport(&mut self, viewport: Viewport) {
+        self.viewport = Viewport {
+            seq: self.viewport.seq + 1,
+            ..viewport
+        };
+    }
+
+    /// The latest viewport if it is newer than the one numbered `seen_seq`
+    pub fn viewport_since(&self, seen_seq: u64) -> Option<Viewport> {
+        (self.viewport.seq > seen_seq).then_some(self.viewport)
+    }
+}
+
+/// Redraw updates received since the last `flush`
+/// Neovim sends a screen update in several redraw notifications ending with
+/// `flush`. Staging them until then keeps the plugin from seeing a half-updated
+/// state (e.g. the cursor of a motion without its scroll), and repeated cursor
+/// and viewport updates of one batch collapse into the final one.
+#[derive(Debug, Default)]
+struct RedrawBatch {
+    /// Mode from mode_change
+    mode: Option<String>,
+    /// (grid, (row, col)) from grid_cursor_goto
+    cursor: Option<(i64, (i64, i64))>,
+    /// Viewport from win_viewport (numbered when published)
+    viewport: Option<Viewport>,
+    /// Messages from msg_show (after the last msg_clear)
+    messages: Vec<NeovimMessage>,
+    /// Whether msg_clear was received
+    messages_cleared: bool,
+}
+
+impl RedrawBatch {
+    fn is_empty(&self) -> bool {
+        self.mode.is_none()
+            && self.cursor.is_none()
+            && self.viewport.is_none()
+            && self.messages.is_empty()
+            && !self.messages_cleared
+    }
+}
+
+/// Merge a buffer change into the previous one when the second only rewrites
+/// lines of the first (or covers all of them), so that e.g. repeated edits of the
+/// line being typed are applied to Godot once. Returns false if they cannot be
+/// merged without knowing the buffer text.
+fn coalesce_lines(prev: &mut BufLinesEvent, next: &BufLinesEvent) -> bool {
+    if prev.buf != next.buf
+        || next.changedtick <= prev.changedtick
+        || prev.last_line < 0
+        || next.last_line < 0
+    {
+        return false;
+    }
+
+    // Lines written by prev, in the buffer after prev
+    let prev_end = prev.first_line + prev.line_data.len() as i64;
+
+    if prev.first_line <= next.first_line && next.last_line <= prev_end {
+        // next rewrites lines of prev: splice it into prev's lines
+        let start = (next.first_line - prev.first_line) as usize;
+        let end = (next.last_line - prev.first_line) as usize;
+        prev.line_data
+            .splice(start..end, next.line_data.iter().cloned());
+    } else if next.first_line <= prev.first_line && prev_end <= next.last_line {
+        // next replaces all of prev's lines: its range before prev
+        prev.last_line =
+            next.last_line - prev.line_data.len() as i64 + prev.last_line - prev.first_line;
+        prev.first_line = next.first_line;
+        prev.line_data = next.line_data.clone();
+    } else {
+        return false;
+    }
+
+    prev.changedtick = next.changedtick;
+    prev.more = next.more;
+    true
+}
+
+/// Message shown by Neovim (msg_show of ext_messages)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeovimMessage {
+    /// Message kind ("emsg", "wmsg", "echo", "" for unclassified, ...)
+    pub kind: String,
+    /// Message text (may have several lines)
+    pub text: String,
+}
+
+/// Yank reported by the TextYankPost autocmd
+#[derive(Debug, Clone, Default)]
+pub struct YankEvent {
+    /// Register name ("" for the unnamed register)
+    pub regname: String,
+    /// Yanked text (lines joined with "\n")
+    pub text: String,
+    /// Operator that yanked the text ("y", "d", "c")
+    pub operator: String,
+    /// Register type ("v" charwise, "V" linewise, "\x16{width}" blockwise)
+    pub regtype: String,
+    /// Start of the yanked region (0-indexed line, character column)
+    pub start: (i64, i64),
+    /// End of the yanked region (0-indexed line, character column, inclusive)
+    pub end: (i64, i64),
 }
 
 /// Buffer events from nvim_buf_attach
@@ -54,6 +175,12 @@ pub enum BufEvent {
     SaveAndClose,
     /// Save all and close all request (from :wqa command)
     SaveAllAndClose,
+    /// Text yanked into a register (from TextYankPost autocmd)
+    Yank(YankEvent),
+    /// Text copied to a clipboard register (from the clipboard provider)
+    ClipboardSet { register: String, text: String },
+    /// Autocmd forwarded by the autocmd bridge
+    Autocmd(AutocmdEvent),
 }
 
 /// Handler for Neovim RPC notifications and requests
@@ -67,6 +194,8 @@ pub struct NeovimHandler {
     buf_events: Arc<Mutex<VecDeque<BufEvent>>>,
     /// Flag indicating new buffer events are available
     has_buf_events: Arc<AtomicBool>,
+    /// Redraw updates not yet published to state (until `flush`)
+    redraw_batch: Arc<Mutex<RedrawBatch>>,
 }
 
 impl NeovimHandler {
@@ -77,19 +206,32 @@ impl NeovimHandler {
                 cursor: (0, 0),
                 cursor_grid: 1,
                 actual_cursor: None,
-                viewport_topline: 0,
-                viewport_botline: 0,
-                viewport_curline: 0,
-                viewport_curcol: 0,
-                viewport_changed: false,
+                viewport: Viewport::default(),
                 debug_messages: Vec::new(),
+                messages: Vec::new(),
+                messages_cleared: false,
             })),
             has_updates: Arc::new(AtomicBool::new(false)),
             buf_events: Arc::new(Mutex::new(VecDeque::new())),
             has_buf_events: Arc::new(AtomicBool::new(false)),
+            redraw_batch: Arc::new(Mutex::new(RedrawBatch::default())),
         }
     }
 
+    /// Queue a buffer change, merged into the previous one not yet taken by the
+    /// plugin when possible
+    async fn push_lines_event(&self, event: BufLinesEvent) {
+        let mut events = self.buf_events.lock().await;
+        let merged = match events.back_mut() {
+            Some(BufEvent::Lines(prev)) => coalesce_lines(prev, &event),
+            _ => false,
+        };
+        if !merged {
+            events.push_back(BufEvent::Lines(event));
+        }
+        self.has_buf_events.store(true, Ordering::SeqCst);
+    }
+
     /// Get a clone of the state Arc for sharing with plugin
     pub fn get_state(&self) -> Arc<Mutex<NeovimState>> {
         self.state.clone()
@@ -173,9 +315,7 @@ impl NeovimHandler {
             more,
         };
 
-        let mut events = self.buf_events.lock().await;
-        events.push_back(BufEvent::Lines(event));
-        self.has_buf_events.store(true, Ordering::SeqCst);
+        self.push_lines_event(event).await;
     }
 
     /// Parse godot_cursor_moved notification from Lua CursorMoved autocmd
@@ -257,9 +397,7 @@ impl NeovimHandler {
             more: false,
         };
 
-        let mut events = self.buf_events.lock().await;
-        events.push_back(BufEvent::Lines(event));
-        self.has_buf_events.store(true, Ordering::SeqCst);
+        self.push_lines_event(event).await;
     }
 
     /// Parse nvim_buf_changedtick_event notification
@@ -435,6 +573,97 @@ impl NeovimHandler {
         self.has_buf_events.store(true, Ordering::SeqCst);
     }
 
+    /// Parse godot_clipboard_set notification from the Lua clipboard provider
+    /// args: [register, text]
+    async fn handle_godot_clipboard_set(&self, args: Vec<Value>) {
+        let [Value::String(register), Value::String(text), ..] = args.as_slice() else {
+            return;
+        };
+        let register = register.as_str().unwrap_or("").to_string();
+        let text = text.as_str().unwrap_or("").to_string();
+
+        crate::verbose_print!(
+            "[godot-neovim] godot_clipboard_set: register='{}', {} bytes",
+            register,
+            text.len()
+        );
+
+        let mut events = self.buf_events.lock().await;
+        events.push_back(BufEvent::ClipboardSet { register, text });
+        self.has_buf_events.store(true, Ordering::SeqCst);
+    }
+
+    /// Parse godot_autocmd notification from the Lua autocmd bridge
+    /// args: [{ event, buf, file, line, col }]
+    async fn handle_godot_autocmd(&self, args: Vec<Value>) {
+        let Some(value) = args.first() else {
+            return;
+        };
+        let event = match AutocmdEvent::parse(value) {
+            Ok(event) => event,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] godot_autocmd: {}", e);
+                return;
+            }
+        };
+
+        crate::verbose_print!("[godot-neovim] godot_autocmd: {:?}", event);
+
+        let mut events = self.buf_events.lock().await;
+        events.push_back(BufEvent::Autocmd(event));
+        self.has_buf_events.store(true, Ordering::SeqCst);
+    }
+
+    /// Parse godot_yank notification from Lua TextYankPost autocmd
+    /// args: [{ regname, text, operator, regtype, start_line, start_col, end_line, end_col }]
+    async fn handle_godot_yank(&self, args: Vec<Value>) {
+        let Some(Value::Map(map)) = args.first() else {
+            return;
+        };
+
+        let mut yank = YankEvent::default();
+
+        for (key, value) in map {
+            let Value::String(k) = key else {
+                continue;
+            };
+            match (k.as_str(), value) {
+                (Some("regname"), Value::String(v)) => {
+                    yank.regname = v.as_str().unwrap_or("").to_string()
+                }
+                (Some("text"), Value::String(v)) => {
+                    yank.text = v.as_str().unwrap_or("").to_string()
+                }
+                (Some("operator"), Value::String(v)) => {
+                    yank.operator = v.as_str().unwrap_or("").to_string()
+                }
+                (Some("regtype"), Value::String(v)) => {
+                    yank.regtype = v.as_str().unwrap_or("").to_string()
+                }
+                (Some("start_line"), v) => yank.start.0 = v.as_i64().unwrap_or(0),
+                (Some("start_col"), v) => yank.start.1 = v.as_i64().unwrap_or(0),
+                (Some("end_line"), v) => yank.end.0 = v.as_i64().unwrap_or(0),
+                (Some("end_col"), v) => yank.end.1 = v.as_i64().unwrap_or(0),
+                _ => {}
+            }
+        }
+
+        crate::verbose_print!(
+            "[godot-neovim] godot_yank: register='{}', operator='{}', {} bytes, ({}, {})-({}, {})",
+            yank.regname,
+            yank.operator,
+            yank.text.len(),
+            yank.start.0,
+            yank.start.1,
+            yank.end.0,
+            yank.end.1
+        );
+
+        let mut events = self.buf_events.lock().await;
+        events.push_back(BufEvent::Yank(yank));
+        self.has_buf_events.store(true, Ordering::SeqCst);
+    }
+
     async fn handle_godot_debug_print(&self, args: Vec<Value>) {
         if args.is_empty() {
             return;
@@ -450,7 +679,8 @@ impl NeovimHandler {
     }
 
     async fn handle_redraw(&self, args: Vec<Value>) {
-        let mut state = self.state.lock().await;
+        let mut batch = self.redraw_batch.lock().await;
+        let mut flush = false;
 
         // Neovim redraw format: ["redraw", ["event_name", args...], ["event_name2", args...], ...]
         // Each arg is an event: ["event_name", [batch1_args], [batch2_args], ...]
@@ -462,13 +692,10 @@ impl NeovimHandler {
                     for event in events {
                         match event {
                             RedrawEvent::ModeChange { mode, .. } => {
-                                state.mode = mode;
-                                self.has_updates.store(true, Ordering::SeqCst);
+                                batch.mode = Some(mode);
                             }
                             RedrawEvent::GridCursorGoto { grid, row, col } => {
-                                state.cursor_grid = grid as i64;
-                                state.cursor = (row as i64, col as i64);
-                                self.has_updates.store(true, Ordering::SeqCst);
+                                batch.cursor = Some((grid as i64, (row as i64, col as i64)));
                             }
                             RedrawEvent::WinViewport {
                                 topline,
@@ -477,44 +704,73 @@ impl NeovimHandler {
                                 curcol,
                                 ..
                             } => {
-                                // Update viewport and cursor from win_viewport
                                 // curline/curcol are the buffer positions (more accurate than grid_cursor_goto)
                                 crate::verbose_print!(
                                     "[godot-neovim] win_viewport: topline={}, botline={}, curline={}, curcol={}",
                                     topline, botline, curline, curcol
                                 );
-                                let viewport_changed = state.viewport_topline != topline
-                                    || state.viewport_botline != botline;
-                                let cursor_changed = state.viewport_curline != curline
-                                    || state.viewport_curcol != curcol;
-
-                                // Always update state and set flag when win_viewport event arrives
-                                // Even if values are the same, we need to use win_viewport cursor
-                                // instead of grid_cursor_goto (which gives screen-relative position with ext_multigrid)
-                                if viewport_changed || cursor_changed {
-                                    crate::verbose_print!(
-                                        "[godot-neovim] win_viewport changed: viewport={}, cursor={}",
-                                        viewport_changed, cursor_changed
-                                    );
+                                batch.viewport = Some(Viewport {
+                                    seq: 0,
+                                    topline,
+                                    botline,
+                                    curline,
+                                    curcol,
+                                });
+                            }
+                            RedrawEvent::MsgShow {
+                                kind,
+                                content,
+                                replace_last,
+                            } => {
+                                if replace_last {
+                                    batch.messages.pop();
                                 }
-                                state.viewport_topline = topline;
-                                state.viewport_botline = botline;
-                                state.viewport_curline = curline;
-                                state.viewport_curcol = curcol;
-                                // Always set flag so take_viewport() returns data
-                                // This ensures we use win_viewport cursor instead of grid_cursor_goto
-                                state.viewport_changed = true;
-                                self.has_updates.store(true, Ordering::SeqCst);
+                                batch.messages.push(NeovimMessage {
+                                    kind,
+                                    text: content,
+                                });
+                            }
+                            RedrawEvent::MsgClear => {
+                                batch.messages.clear();
+                                batch.messages_cleared = true;
                             }
-                            RedrawEvent::Flush | RedrawEvent::Unknown(_) => {
-                                // Flush: No longer needed since we set flag immediately
-                                // Unknown: Silently ignore unhandled events
+                            RedrawEvent::Flush => flush = true,
+                            RedrawEvent::Unknown(_) => {
+                                // Silently ignore unhandled events
                             }
                         }
                     }
                 }
             }
         }
+
+        if !flush || batch.is_empty() {
+            return;
+        }
+        let batch = std::mem::take(&mut *batch);
+        let state_changed =
+            batch.mode.is_some() || batch.cursor.is_some() || batch.viewport.is_some();
+
+        // Publish the final state of the batch at once
+        let mut state = self.state.lock().await;
+        if let Some(mode) = batch.mode {
+            state.mode = mode;
+        }
+        if let Some((grid, cursor)) = batch.cursor {
+            state.cursor_grid = grid;
+            state.cursor = cursor;
+        }
+        if let Some(viewport) = batch.viewport {
+            state.publish_viewport(viewport);
+        }
+        if batch.messages_cleared {
+            state.messages.clear();
+            state.messages_cleared = true;
+        }
+        state.messages.extend(batch.messages);
+        if state_changed {
+            self.has_updates.store(true, Ordering::SeqCst);
+        }
     }
 }
 
@@ -526,7 +782,7 @@ impl Default for NeovimHandler {
 
 #[async_trait::async_trait]
 impl Handler for NeovimHandler {
-    type Writer = nvim_rs::compat::tokio::Compat<tokio::process::ChildStdin>;
+    type Writer = crate::neovim::client::Writer;
 
     async fn handle_notify(
         &self,
@@ -548,6 +804,9 @@ impl Handler for NeovimHandler {
             "godot_close_buffer" => self.handle_godot_close_buffer(args).await,
             "godot_save_and_close" => self.handle_godot_save_and_close(args).await,
             "godot_save_all_and_close" => self.handle_godot_save_all_and_close(args).await,
+            "godot_yank" => self.handle_godot_yank(args).await,
+            "godot_clipboard_set" => self.handle_godot_clipboard_set(args).await,
+            "godot_autocmd" => self.handle_godot_autocmd(args).await,
             "godot_debug_print" => self.handle_godot_debug_print(args).await,
             _ => {}
         }
@@ -563,3 +822,85 @@ impl Handler for NeovimHandler {
         Ok(Value::Nil)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(tick: i64, first: i64, last: i64, data: &[&str]) -> BufLinesEvent {
+        BufLinesEvent {
+            buf: 1,
+            changedtick: tick,
+            first_line: first,
+            last_line: last,
+            line_data: data.iter().map(|s| s.to_string()).collect(),
+            more: false,
+        }
+    }
+
+    #[test]
+    fn test_coalesce_lines() {
+        // Typing on one line: each change rewrites the previous one
+        let mut prev = lines(5, 3, 4, &["a"]);
+        assert!(coalesce_lines(&mut prev, &lines(6, 3, 4, &["ab"])));
+        assert_eq!(
+            (prev.changedtick, prev.first_line, prev.last_line),
+            (6, 3, 4)
+        );
+        assert_eq!(prev.line_data, vec!["ab"]);
+
+        // o then typing: the inserted line is rewritten
+        let mut prev = lines(5, 4, 4, &[""]);
+        assert!(coalesce_lines(&mut prev, &lines(6, 4, 5, &["x"])));
+        assert_eq!((prev.first_line, prev.last_line), (4, 4));
+        assert_eq!(prev.line_data, vec!["x"]);
+
+        // A change covering the previous one replaces it (range before both)
+        let mut prev = lines(5, 2, 3, &["a", "b"]);
+        assert!(coalesce_lines(&mut prev, &lines(6, 1, 5, &["z"])));
+        assert_eq!((prev.first_line, prev.last_line), (1, 4));
+        assert_eq!(prev.line_data, vec!["z"]);
+
+        // Unrelated lines need the buffer text: kept separate
+        let mut prev = lines(5, 2, 3, &["a"]);
+        assert!(!coalesce_lines(&mut prev, &lines(6, 7, 8, &["b"])));
+        assert!(!coalesce_lines(&mut prev, &lines(6, 0, -1, &["b"])));
+    }
+
+    #[test]
+    fn test_viewport_seq() {
+        let mut state = NeovimState::default();
+        assert_eq!(state.viewport_since(0), None);
+
+        let viewport = Viewport {
+            seq: 0,
+            topline: 10,
+            botline: 43,
+            curline: 12,
+            curcol: 4,
+        };
+        state.publish_viewport(viewport);
+        let seen = state.viewport_since(0).unwrap();
+        assert_eq!((seen.seq, seen.topline, seen.curline), (1, 10, 12));
+        assert_eq!(state.viewport_since(seen.seq), None);
+
+        // The same values again (reopening a file after :q) are a new viewport
+        state.publish_viewport(viewport);
+        assert_eq!(state.viewport_since(seen.seq).map(|v| v.seq), Some(2));
+
+        // A buffer switch skips what was sent before it, not what follows
+        let before_switch = state.viewport.seq;
+        assert_eq!(state.viewport_since(before_switch), None);
+        state.publish_viewport(Viewport {
+            topline: 0,
+            curline: 0,
+            ..viewport
+        });
+        assert_eq!(
+            state
+                .viewport_since(before_switch)
+                .map(|v| (v.seq, v.topline)),
+            Some((3, 0))
+        );
+    }
+}
@@ -2,10 +2,15 @@ mod client;
 mod events;
 mod handler;
 
-pub use client::NeovimClient;
+pub use client::{
+    parse_line_matches, parse_matches, parse_search_state, sweep_orphans, BufferEntry,
+    NeovimClient, RegisterEntry, RpcRequest, CHUNK_CANCELLED,
+};
 #[allow(unused_imports)]
 pub use client::{IndentOptions, SwitchBufferResult};
-pub use client::{TIMEOUT_RECOVERY_THRESHOLD, TIMEOUT_RECOVERY_WINDOW_SECS};
+pub use client::{
+    NEOVIM_REQUIRED_VERSION, TIMEOUT_RECOVERY_THRESHOLD, TIMEOUT_RECOVERY_WINDOW_SECS,
+};
 #[allow(unused_imports)]
-pub use events::{ParseError, RedrawEvent};
-pub use handler::{BufEvent, NeovimHandler, NeovimState};
+pub use events::{AutocmdEvent, ParseError, RedrawEvent};
+pub use handler::{BufEvent, NeovimHandler, NeovimMessage, NeovimState, Viewport, YankEvent};
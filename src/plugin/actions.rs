@@ -7,13 +7,15 @@
 //! The #[func] wrappers are defined in mod.rs's #[godot_api] block because
 //! godot-rs only allows one #[godot_api] impl block per struct.
 
+use super::motions::SubwordMotion;
+use super::operator::MotionKind;
 use super::GodotNeovimPlugin;
 use godot::prelude::*;
 
 /// Helper macro to record a key to the macro buffer if recording
 macro_rules! record_macro {
     ($self:expr, $key:expr) => {
-        if $self.recording_macro.is_some() && !$self.playing_macro {
+        if $self.recording_macro.is_some() {
             $self.macro_buffer.push($key.to_string());
         }
     };
@@ -46,6 +48,18 @@ impl GodotNeovimPlugin {
         self.send_keys("<C-r>");
     }
 
+    /// Go to the older text state, also across undo branches (g-)
+    pub(super) fn action_undo_earlier_impl(&mut self) {
+        record_macro!(self, "g-");
+        self.send_keys("g-");
+    }
+
+    /// Go to the newer text state, also across undo branches (g+)
+    pub(super) fn action_undo_later_impl(&mut self) {
+        record_macro!(self, "g+");
+        self.send_keys("g+");
+    }
+
     // =========================================================================
     // Page / Scroll navigation
     // =========================================================================
@@ -53,7 +67,6 @@ impl GodotNeovimPlugin {
     /// Page up (Ctrl+B)
     pub(super) fn action_page_up_impl(&mut self) {
         self.cancel_pending_operator();
-        self.pending_page_up_correction = true;
         record_macro!(self, "<C-b>");
         self.send_keys("<C-b>");
     }
@@ -83,14 +96,18 @@ impl GodotNeovimPlugin {
     pub(super) fn action_scroll_viewport_up_impl(&mut self) {
         self.cancel_pending_operator();
         record_macro!(self, "<C-y>");
-        self.scroll_viewport_up();
+        for _ in 0..self.get_and_clear_count() {
+            self.scroll_viewport_up();
+        }
     }
 
     /// Scroll viewport down by one line (Ctrl+E)
     pub(super) fn action_scroll_viewport_down_impl(&mut self) {
         self.cancel_pending_operator();
         record_macro!(self, "<C-e>");
-        self.scroll_viewport_down();
+        for _ in 0..self.get_and_clear_count() {
+            self.scroll_viewport_down();
+        }
     }
 
     // =========================================================================
@@ -164,6 +181,21 @@ impl GodotNeovimPlugin {
         self.search_word("#");
     }
 
+    /// Jump to two characters on screen (s{char}{char})
+    pub(super) fn action_sneak_impl(&mut self) {
+        self.start_sneak();
+    }
+
+    /// Search word under cursor forward, also inside other words (g*)
+    pub(super) fn action_search_partial_word_forward_impl(&mut self) {
+        self.search_word("g*");
+    }
+
+    /// Search word under cursor backward, also inside other words (g#)
+    pub(super) fn action_search_partial_word_backward_impl(&mut self) {
+        self.search_word("g#");
+    }
+
     /// Search next forward (n)
     pub(super) fn action_search_next_impl(&mut self) {
         self.search_next(true);
@@ -178,10 +210,40 @@ impl GodotNeovimPlugin {
     // Go to definition / file / URL
     // =========================================================================
 
-    /// Go to definition (gd) - uses Godot LSP
+    /// Go to definition (gd) - LSP, then tags, then Godot's symbol lookup
     pub(super) fn action_goto_definition_impl(&mut self) {
         self.add_to_jump_list();
-        self.go_to_definition_lsp();
+        self.go_to_definition();
+    }
+
+    /// Go to declaration in the current file (gD)
+    pub(super) fn action_goto_declaration_impl(&mut self) {
+        self.go_to_declaration();
+    }
+
+    /// Show the diagnostics of the caret line in a float (`<leader>e`)
+    pub(super) fn action_show_line_diagnostics_impl(&mut self) {
+        self.show_line_diagnostics();
+    }
+
+    /// Code actions at the cursor or selection in the picker (`<leader>ca`) - uses Godot LSP
+    pub(super) fn action_code_actions_impl(&mut self) {
+        self.lsp_code_actions();
+    }
+
+    /// Outline of the current script's symbols in the picker - uses Godot LSP
+    pub(super) fn action_document_symbols_impl(&mut self) {
+        self.show_symbols_picker();
+    }
+
+    /// Find references to symbol under cursor (grr) - uses Godot LSP
+    pub(super) fn action_lsp_references_impl(&mut self) {
+        self.lsp_references();
+    }
+
+    /// Rename symbol under cursor (gR) - uses Godot LSP
+    pub(super) fn action_lsp_rename_impl(&mut self) {
+        self.lsp_rename_prompt();
     }
 
     /// Go to file under cursor (gf)
@@ -208,6 +270,20 @@ impl GodotNeovimPlugin {
         self.prev_script_tab();
     }
 
+    // =========================================================================
+    // Finder
+    // =========================================================================
+
+    /// Fuzzy find a project file (<leader>ff)
+    pub(super) fn action_find_files_impl(&mut self) {
+        self.show_file_finder();
+    }
+
+    /// Search the project as the pattern is typed (<leader>fg)
+    pub(super) fn action_live_grep_impl(&mut self) {
+        self.show_live_grep();
+    }
+
     // =========================================================================
     // Visual mode
     // =========================================================================
@@ -234,13 +310,21 @@ impl GodotNeovimPlugin {
     /// Move down by display line (gj)
     pub(super) fn action_display_line_down_impl(&mut self) {
         record_macro!(self, "gj");
-        self.move_display_line_down();
+        self.run_local_motion(MotionKind::Exclusive, |this| {
+            for _ in 0..this.get_and_clear_count() {
+                this.move_display_line_down();
+            }
+        });
     }
 
     /// Move up by display line (gk)
     pub(super) fn action_display_line_up_impl(&mut self) {
         record_macro!(self, "gk");
-        self.move_display_line_up();
+        self.run_local_motion(MotionKind::Exclusive, |this| {
+            for _ in 0..this.get_and_clear_count() {
+                this.move_display_line_up();
+            }
+        });
     }
 
     /// Insert at column 0 (gI)
@@ -281,26 +365,64 @@ impl GodotNeovimPlugin {
     /// Move to end of previous word (ge)
     pub(super) fn action_word_end_backward_impl(&mut self) {
         record_macro!(self, "ge");
-        self.move_to_word_end_backward();
+        if self.should_preview_motion() {
+            self.move_to_word_end_backward();
+        }
         self.send_keys("ge");
     }
 
+    /// Select word under cursor, then add the next occurrence as a cursor (<C-n>)
+    pub(super) fn action_multicursor_next_impl(&mut self) {
+        self.cancel_pending_operator();
+        self.multicursor_add_next();
+    }
+
+    /// Move to start of next sub-word (<A-w>)
+    pub(super) fn action_subword_forward_impl(&mut self) {
+        record_macro!(self, "<A-w>");
+        self.run_local_motion(MotionKind::Exclusive, |this| {
+            let count = this.get_and_clear_count();
+            this.move_by_subword(SubwordMotion::Forward, count);
+        });
+    }
+
+    /// Move to start of previous sub-word (<A-b>)
+    pub(super) fn action_subword_backward_impl(&mut self) {
+        record_macro!(self, "<A-b>");
+        self.run_local_motion(MotionKind::Exclusive, |this| {
+            let count = this.get_and_clear_count();
+            this.move_by_subword(SubwordMotion::Backward, count);
+        });
+    }
+
+    /// Move to end of next sub-word (<A-e>)
+    pub(super) fn action_subword_end_impl(&mut self) {
+        record_macro!(self, "<A-e>");
+        self.run_local_motion(MotionKind::Inclusive, |this| {
+            let count = this.get_and_clear_count();
+            this.move_by_subword(SubwordMotion::EndForward, count);
+        });
+    }
+
     /// Move to start of display line (g0)
     pub(super) fn action_display_line_start_impl(&mut self) {
         record_macro!(self, "g0");
-        self.move_to_display_line_start();
+        self.run_local_motion(MotionKind::Exclusive, Self::move_to_display_line_start);
     }
 
     /// Move to end of display line (g$)
     pub(super) fn action_display_line_end_impl(&mut self) {
         record_macro!(self, "g$");
-        self.move_to_display_line_end();
+        self.run_local_motion(MotionKind::Inclusive, Self::move_to_display_line_end);
     }
 
     /// Move to first non-blank of display line (g^)
     pub(super) fn action_display_line_first_non_blank_impl(&mut self) {
         record_macro!(self, "g^");
-        self.move_to_display_line_first_non_blank();
+        self.run_local_motion(
+            MotionKind::Exclusive,
+            Self::move_to_display_line_first_non_blank,
+        );
     }
 
     // =========================================================================
@@ -370,9 +492,9 @@ impl GodotNeovimPlugin {
     // Save / Close (ZZ, ZQ)
     // =========================================================================
 
-    /// Save and close (ZZ / :wq)
+    /// Save if modified and close (ZZ / :x)
     pub(super) fn action_save_and_close_impl(&mut self) {
-        self.cmd_save_and_close();
+        self.cmd_update_and_close();
     }
 
     /// Close without saving (ZQ / :q!)
@@ -397,6 +519,7 @@ impl GodotNeovimPlugin {
     /// Check if there is a pending operation (f/t/r/m/q/@/")
     pub(super) fn is_pending_operation_impl(&self) -> bool {
         self.pending_char_op.is_some()
+            || self.pending_surround.is_some()
             || self.pending_mark_op.is_some()
             || self.pending_macro_op.is_some()
             || self.selected_register == Some('\0')
@@ -0,0 +1,124 @@
+//! Auto-save ('Auto Save' setting)
+//!
+//! Modified scripts are saved after leaving Insert mode (once the Auto Save Delay
+//! has passed without typing again) and/or when the CodeEdit loses focus. A save
+//! only happens while Godot's editor is dirty (version != saved version), and the
+//! save itself tags the saved version, so the encoding restore and other work on
+//! `resource_saved` cannot trigger another save.
+
+use super::{EditorType, GodotNeovimPlugin};
+use crate::settings;
+use godot::classes::{CodeEdit, ResourceLoader, Script};
+use godot::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Whether the CodeEdit has changes since it was last saved
+pub(super) fn is_dirty(editor: &Gd<CodeEdit>) -> bool {
+    editor.get_version() != editor.get_saved_version()
+}
+
+impl GodotNeovimPlugin {
+    /// Schedule the Insert mode exit save (called when leaving Insert mode)
+    pub(super) fn schedule_auto_save(&mut self) {
+        if !settings::get_auto_save().on_insert_leave() {
+            return;
+        }
+        let delay = Duration::from_millis(settings::get_auto_save_delay());
+        self.auto_save_due = Some(Instant::now() + delay);
+    }
+
+    /// Save once the Insert mode exit delay has passed (called every frame)
+    pub(super) fn poll_auto_save(&mut self) {
+        let Some(due) = self.auto_save_due else {
+            return;
+        };
+        if Instant::now() < due {
+            return;
+        }
+        self.auto_save_due = None;
+
+        // Typing again postpones the save to the next Insert mode exit
+        if self.is_insert_mode() || self.is_replace_mode() {
+            return;
+        }
+        self.auto_save_current("insert leave");
+    }
+
+    /// Save when the CodeEdit loses focus (focus_exited signal)
+    pub(super) fn auto_save_on_focus_lost(&mut self) {
+        if !settings::get_auto_save().on_focus_lost() {
+            return;
+        }
+        self.auto_save_due = None;
+        self.auto_save_current("focus lost");
+    }
+
+    /// Save the current CodeEdit if it is dirty
+    fn auto_save_current(&mut self, reason: &str) {
+        // Shaders and external CodeEdits are not saved through the ScriptEditor
+        if !matches!(
+            self.current_editor_type,
+            EditorType::Script | EditorType::TextFile
+        ) {
+            return;
+        }
+        let Some(editor) = self.current_editor.clone() else {
+            return;
+        };
+        if !editor.is_instance_valid() || !is_dirty(&editor) {
+            return;
+        }
+
+        // Switching tabs moves the focus away after the ScriptEditor already shows
+        // the next script, so its File menu would save the wrong one
+        let is_shown = self
+            .get_script_editor_code_edit_via_api()
+            .is_some_and(|shown| shown == editor);
+        if !is_shown {
+            if self.save_script_resource(editor) {
+                crate::verbose_print!(
+                    "[godot-neovim] Auto save ({}): {}",
+                    reason,
+                    self.current_script_path
+                );
+            }
+            return;
+        }
+        self.cmd_save();
+        crate::verbose_print!(
+            "[godot-neovim] Auto save ({}): {}",
+            reason,
+            self.current_script_path
+        );
+
+        // Neovim's buffer is saved too (the BufWriteCmd of :w does the same)
+        // Not after a tab switch: its modified event would clear the next script's dirty flag
+        if let Some(neovim) = self.get_current_neovim() {
+            if let Ok(client) = neovim.try_lock() {
+                let _ = client.command("setlocal nomodified");
+            }
+        }
+    }
+
+    /// Save the script of a CodeEdit that is no longer shown via ResourceSaver
+    fn save_script_resource(&self, editor: Gd<CodeEdit>) -> bool {
+        let path = self.current_script_path.clone();
+        if self.current_editor_type != EditorType::Script || path.is_empty() {
+            return false;
+        }
+        // Open scripts are cached, so this is the ScriptEditor's instance
+        let Some(mut script) = ResourceLoader::singleton()
+            .load(&path)
+            .and_then(|resource| resource.try_cast::<Script>().ok())
+        else {
+            return false;
+        };
+        match Self::save_script_from_editor(&mut script, editor) {
+            Ok(_) => true,
+            Err(e) => {
+                godot_warn!("[godot-neovim] Auto save: {}", e);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Autocmds forwarded by Neovim (autocmd bridge)
+//!
+//! The Lua plugin forwards the autocmds listed in integration.lua
+//! (BRIDGED_AUTOCMDS) as godot_autocmd notifications; NeovimHandler parses them
+//! into typed events that arrive here with the buffer events. To react to another
+//! event, add it to the list and give it a variant in AutocmdEvent.
+
+use super::GodotNeovimPlugin;
+use crate::neovim::AutocmdEvent;
+
+impl GodotNeovimPlugin {
+    /// Handle an autocmd forwarded by Neovim
+    pub(super) fn on_autocmd(&mut self, event: &AutocmdEvent) {
+        match event {
+            AutocmdEvent::InsertLeave { buf } => {
+                crate::verbose_print!("[godot-neovim] InsertLeave: buf={}", buf);
+            }
+            AutocmdEvent::CursorHold { buf, line, col } => {
+                crate::verbose_print!("[godot-neovim] CursorHold: buf={} ({}, {})", buf, line, col);
+                self.on_cursor_hold(*line, *col);
+            }
+            AutocmdEvent::BufWritePost { buf, file } => {
+                crate::verbose_print!("[godot-neovim] BufWritePost: buf={}, file={}", buf, file);
+            }
+            AutocmdEvent::Unknown(name) => {
+                crate::verbose_print!("[godot-neovim] Autocmd without handler: {}", name);
+            }
+        }
+    }
+}
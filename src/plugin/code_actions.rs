@@ -0,0 +1,184 @@
+//! LSP code actions (`<leader>ca`): quick fixes and refactorings at the cursor or
+//! the Visual selection, listed in the picker
+//!
+//! The diagnostics published for the range are sent along, so the server can offer
+//! their fixes. A picked action's WorkspaceEdit is applied like a rename: the
+//! current buffer through Neovim (the changes come back through the sync manager),
+//! other open scripts directly. Its command, if any, is then run by the server.
+
+use super::picker::{PickerItem, PickerSource};
+use super::GodotNeovimPlugin;
+use godot::prelude::*;
+use lsp_types::{CodeActionOrCommand, Diagnostic, Position, Range};
+
+/// Whether two ranges share a line (diagnostics on the lines of the request)
+fn lines_overlap(a: &Range, b: &Range) -> bool {
+    a.start.line <= b.end.line && b.start.line <= a.end.line
+}
+
+/// Title of an action, with its kind (e.g. "Remove unused variable (quickfix)")
+fn action_label(action: &CodeActionOrCommand) -> String {
+    match action {
+        CodeActionOrCommand::Command(command) => command.title.clone(),
+        CodeActionOrCommand::CodeAction(action) => {
+            let preferred = if action.is_preferred == Some(true) {
+                "* "
+            } else {
+                ""
+            };
+            match &action.kind {
+                Some(kind) if !kind.as_str().is_empty() => {
+                    format!("{}{} ({})", preferred, action.title, kind.as_str())
+                }
+                _ => format!("{}{}", preferred, action.title),
+            }
+        }
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// `<leader>ca` - Request the code actions at the cursor (or the selection) and
+    /// list them in the picker
+    pub(super) fn lsp_code_actions(&mut self) {
+        let Some(editor) = self.current_editor.as_ref() else {
+            return;
+        };
+        let range = if self.is_in_visual_mode() && editor.has_selection() {
+            Range {
+                start: Position::new(
+                    editor.get_selection_from_line() as u32,
+                    editor.get_selection_from_column() as u32,
+                ),
+                end: Position::new(
+                    editor.get_selection_to_line() as u32,
+                    editor.get_selection_to_column() as u32,
+                ),
+            }
+        } else {
+            let caret = Position::new(
+                editor.get_caret_line() as u32,
+                editor.get_caret_column() as u32,
+            );
+            Range::new(caret, caret)
+        };
+
+        let Some((lsp, uri)) = self.prepare_lsp_request("ca") else {
+            return;
+        };
+
+        let current = self.current_script_abs_path().replace('\\', "/");
+        let diagnostics: Vec<Diagnostic> = lsp
+            .diagnostics()
+            .into_iter()
+            .find(|(uri, _)| Self::uri_to_file_path(uri).replace('\\', "/") == current)
+            .map(|(_, diagnostics)| diagnostics)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|diagnostic| lines_overlap(&diagnostic.range, &range))
+            .collect();
+
+        crate::verbose_print!(
+            "[godot-neovim] ca: Requesting code actions at {}:{}:{} ({} diagnostic(s))",
+            uri,
+            range.start.line,
+            range.start.character,
+            diagnostics.len()
+        );
+
+        let actions = match lsp.code_actions(&uri, range, diagnostics) {
+            Ok(actions) => actions,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] ca: LSP error: {}", e);
+                self.show_status_message(&format!("LSP error: {}", e));
+                return;
+            }
+        };
+        if actions.is_empty() {
+            self.show_status_message("No code actions available");
+            return;
+        }
+
+        let items: Vec<PickerItem> = actions
+            .iter()
+            .map(|action| PickerItem {
+                label: action_label(action),
+                path: String::new(),
+                position: None,
+            })
+            .collect();
+        self.show_picker_with_source(
+            &format!("Code Actions ({})", items.len()),
+            items,
+            PickerSource::CodeActions,
+        );
+        self.code_actions = actions;
+    }
+
+    /// Apply a code action picked in the picker: its edit, then its command
+    pub(super) fn apply_code_action(&mut self, action: CodeActionOrCommand) {
+        let (title, edit, command) = match action {
+            CodeActionOrCommand::Command(command) => (command.title.clone(), None, Some(command)),
+            CodeActionOrCommand::CodeAction(action) => (action.title, action.edit, action.command),
+        };
+        if edit.is_none() && command.is_none() {
+            self.show_status_message(&format!("Code action has nothing to apply: {}", title));
+            return;
+        }
+
+        let mut message = format!("Applied: {}", title);
+        if let Some(edit) = edit {
+            let (files, edits, skipped) = self.apply_workspace_edit(edit, "Code action");
+            if skipped > 0 {
+                godot_warn!(
+                    "[godot-neovim] Code action - {} file(s) not open in the editor were skipped",
+                    skipped
+                );
+            }
+            message = format!(
+                "Applied: {} ({} change(s) in {} file(s))",
+                title, edits, files
+            );
+        }
+
+        if let Some(command) = command {
+            let Some(lsp) = self.godot_lsp.clone() else {
+                return;
+            };
+            if let Err(e) = lsp.execute_command(&command) {
+                crate::verbose_print!("[godot-neovim] ca: executeCommand failed: {}", e);
+                self.show_status_message(&format!("LSP error: {}", e));
+                return;
+            }
+        }
+        crate::verbose_print!("[godot-neovim] ca: {}", message);
+        self.show_status_message(&message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::{CodeAction, CodeActionKind, Command};
+
+    #[test]
+    fn test_action_label_and_overlap() {
+        let fix = CodeActionOrCommand::CodeAction(CodeAction {
+            title: "Remove unused variable".to_string(),
+            kind: Some(CodeActionKind::QUICKFIX),
+            is_preferred: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(action_label(&fix), "* Remove unused variable (quickfix)");
+        let command = CodeActionOrCommand::Command(Command {
+            title: "Organize".to_string(),
+            command: "organize".to_string(),
+            arguments: None,
+        });
+        assert_eq!(action_label(&command), "Organize");
+
+        let range = |from, to| Range::new(Position::new(from, 4), Position::new(to, 0));
+        assert!(lines_overlap(&range(3, 3), &range(1, 3)));
+        assert!(lines_overlap(&range(2, 5), &range(4, 4)));
+        assert!(!lines_overlap(&range(2, 2), &range(3, 6)));
+    }
+}
@@ -0,0 +1,224 @@
+//! :bd[elete][!] [N|path] - Close a script tab and wipe its Neovim buffer
+//!
+//! Without an argument it is :q for the current script. A buffer number (as in
+//! :ls) or a path (`res://`, relative to the project or a file name) closes another
+//! script: Godot can only close the current tab, so the script is shown, its tab
+//! closed, and the script that was current is shown again over the next frames
+//! (see poll_buffer_delete). on_script_close wipes the Neovim buffer, so :ls and the
+//! buffer picker no longer list it.
+
+use super::super::auto_save::is_dirty;
+use super::super::{EditorType, GodotNeovimPlugin};
+use crate::neovim::BufferEntry;
+use godot::classes::{EditorInterface, ProjectSettings, Script};
+use godot::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the script tab to show up before giving up
+const BUFFER_DELETE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// :bd of a script other than the current one, carried out over the next frames
+pub(in crate::plugin) struct PendingBufferDelete {
+    /// res:// path of the script to close
+    path: String,
+    /// Script that was current (shown again once the tab is closed)
+    return_to: Option<Gd<Script>>,
+    /// Whether the tab close was triggered
+    closing: bool,
+    /// When to give up (the script never became current)
+    deadline: Instant,
+}
+
+/// The buffer named by a :bd argument: a buffer number, or a path matching one
+/// buffer (`res://` path, path relative to the project, or file name)
+fn find_buffer(buffers: Vec<BufferEntry>, arg: &str) -> Result<BufferEntry, String> {
+    if let Ok(number) = arg.parse::<i64>() {
+        return buffers
+            .into_iter()
+            .find(|buffer| buffer.number == number)
+            .ok_or_else(|| format!("E516: No buffers were deleted: bd {}", arg));
+    }
+
+    let wanted = arg.replace('\\', "/");
+    let mut matches: Vec<BufferEntry> = buffers
+        .into_iter()
+        .filter(|buffer| {
+            let file = buffer.file.replace('\\', "/");
+            let res_path = ProjectSettings::singleton()
+                .localize_path(&file)
+                .to_string();
+            file == wanted
+                || res_path == wanted
+                || res_path.strip_prefix("res://") == Some(wanted.as_str())
+                || res_path.ends_with(&format!("/{}", wanted))
+        })
+        .collect();
+    match matches.len() {
+        0 => Err(format!("E94: No matching buffer for {}", arg)),
+        1 => Ok(matches.remove(0)),
+        _ => Err(format!("E93: More than one match for {}", arg)),
+    }
+}
+
+/// E89 for a modified buffer (its number, when known)
+fn no_write_message(number: Option<i64>) -> String {
+    match number {
+        Some(number) => format!(
+            "E89: No write since last change for buffer {} (add ! to override)",
+            number
+        ),
+        None => "E89: No write since last change (add ! to override)".to_string(),
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// :bd[!] [N|path] - Close the script of a buffer (the current one by default)
+    /// A modified script is only closed with `!`, which discards its changes.
+    pub(in crate::plugin) fn cmd_buffer_delete(&mut self, arg: &str, force: bool) {
+        let current = self.current_script_abs_path().replace('\\', "/");
+        if !arg.is_empty() {
+            let Some(buffers) = self.list_buffers() else {
+                return;
+            };
+            let buffer = match find_buffer(buffers, arg) {
+                Ok(buffer) => buffer,
+                Err(message) => {
+                    self.show_status_message(&message);
+                    return;
+                }
+            };
+            if buffer.file.replace('\\', "/") != current {
+                self.delete_other_buffer(buffer, force);
+                return;
+            }
+        }
+
+        // The current script
+        if force {
+            self.cmd_close_discard();
+            return;
+        }
+        let modified = self.current_editor_type != EditorType::Unknown
+            && self
+                .current_editor
+                .as_ref()
+                .is_some_and(|editor| editor.is_instance_valid() && is_dirty(editor));
+        if modified {
+            let number = self.list_buffers().and_then(|buffers| {
+                buffers
+                    .into_iter()
+                    .find(|buffer| buffer.file.replace('\\', "/") == current)
+                    .map(|buffer| buffer.number)
+            });
+            self.show_status_message(&no_write_message(number));
+            return;
+        }
+        self.cmd_close();
+    }
+
+    /// :bd of another script: show it, then close it (see poll_buffer_delete)
+    fn delete_other_buffer(&mut self, buffer: BufferEntry, force: bool) {
+        let abs_path = buffer.file.replace('\\', "/");
+        if self.current_editor_type != EditorType::Script {
+            self.show_status_message(
+                "E516: No buffers were deleted: only the current tab can be closed here",
+            );
+            return;
+        }
+
+        let res_path = ProjectSettings::singleton()
+            .localize_path(&abs_path)
+            .to_string();
+        let mut editor = EditorInterface::singleton();
+        let Some(script_editor) = editor.get_script_editor() else {
+            return;
+        };
+        let open_scripts = script_editor.get_open_scripts();
+        let script = (0..open_scripts.len())
+            .filter_map(|i| open_scripts.get(i))
+            .find(|script| script.get_path().to_string() == res_path);
+        let Some(mut script) = script else {
+            // A buffer Neovim opened itself (e.g. a jump): there is no tab to close
+            crate::verbose_print!(
+                "[godot-neovim] :bd {} - Not open in Godot, wiping the buffer only",
+                buffer.number
+            );
+            self.delete_neovim_buffer(&abs_path, EditorType::Script);
+            return;
+        };
+
+        let code_edit = Self::find_open_script_code_edit(&abs_path);
+        let modified = code_edit.as_ref().map_or(buffer.modified, is_dirty);
+        if modified {
+            if !force {
+                self.show_status_message(&no_write_message(Some(buffer.number)));
+                return;
+            }
+            // Discard the changes like ZQ, so closing the tab does not ask to save
+            let Some(mut code_edit) = code_edit else {
+                self.show_status_message(&format!(
+                    "E516: No buffers were deleted: {} is modified",
+                    res_path
+                ));
+                return;
+            };
+            let Some(text) = self.read_file_decoded(&res_path) else {
+                self.show_status_message(&format!("E484: Can't open file {}", res_path));
+                return;
+            };
+            code_edit.set_text(&text);
+            code_edit.tag_saved_version();
+            script.set_source_code(&text);
+        }
+
+        crate::verbose_print!(
+            "[godot-neovim] :bd {} - Closing {} (not current)",
+            buffer.number,
+            res_path
+        );
+        self.pending_buffer_delete = Some(PendingBufferDelete {
+            path: res_path,
+            return_to: script_editor.get_current_script(),
+            closing: false,
+            deadline: Instant::now() + BUFFER_DELETE_TIMEOUT,
+        });
+        editor.call_deferred("edit_script", &[script.to_variant()]);
+    }
+
+    /// Carry out a :bd of another script (called every frame while pending):
+    /// close its tab once it is current, then go back to the previous script
+    pub(in crate::plugin) fn poll_buffer_delete(&mut self) {
+        let Some(pending) = self.pending_buffer_delete.as_mut() else {
+            return;
+        };
+        if Instant::now() > pending.deadline {
+            crate::verbose_print!("[godot-neovim] :bd - {} was not closed", pending.path);
+            self.pending_buffer_delete = None;
+            return;
+        }
+
+        let is_current = self.current_script_path == pending.path;
+        if !pending.closing {
+            if is_current {
+                pending.closing = true;
+                self.cmd_close();
+            }
+            return;
+        }
+        if is_current {
+            // The tab is still closing
+            return;
+        }
+
+        let Some(pending) = self.pending_buffer_delete.take() else {
+            return;
+        };
+        crate::verbose_print!("[godot-neovim] :bd - Closed {}", pending.path);
+        if let Some(script) = pending
+            .return_to
+            .filter(|script| script.is_instance_valid())
+        {
+            EditorInterface::singleton().call_deferred("edit_script", &[script.to_variant()]);
+        }
+    }
+}
@@ -1,8 +1,11 @@
-//! Buffer/tab navigation: :bn, :bp, gt, gT, :{n}
+//! Buffer/tab navigation: :bn, :bp, :ls, :b, gt, gT, :{n}
 
+use super::super::matcher;
+use super::super::picker::PickerItem;
 use super::super::{EditorType, GodotNeovimPlugin};
 use super::simulate_ctrl_f;
-use godot::classes::{CodeEdit, EditorInterface, TabBar};
+use crate::neovim::{BufferEntry, RpcRequest};
+use godot::classes::{CodeEdit, EditorInterface, ProjectSettings, TabBar};
 use godot::prelude::*;
 
 impl GodotNeovimPlugin {
@@ -232,14 +235,170 @@ impl GodotNeovimPlugin {
         self.prev_script_tab();
     }
 
+    /// Neovim's buffers for Godot scripts (None if Neovim is not available)
+    pub(in crate::plugin) fn list_buffers(&self) -> Option<Vec<BufferEntry>> {
+        let Some(neovim) = self.get_current_neovim() else {
+            godot_warn!("[godot-neovim] Buffers: Neovim not connected");
+            return None;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            godot_warn!("[godot-neovim] Buffers: Neovim is busy");
+            return None;
+        };
+        match client.list_buffers() {
+            Ok(buffers) => Some(buffers),
+            Err(e) => {
+                godot_warn!("[godot-neovim] Failed to list buffers: {}", e);
+                None
+            }
+        }
+    }
+
+    /// :ls - Show Neovim's buffers in the picker
+    pub(in crate::plugin) fn show_buffers_picker(&mut self) {
+        let Some(buffers) = self.list_buffers() else {
+            return;
+        };
+        if buffers.is_empty() {
+            self.show_status_message("No buffers");
+            return;
+        }
+        self.show_buffer_items("Buffers", buffers);
+    }
+
+    /// Show buffers in the picker, with the flags of :ls
+    /// (`%` current, `#` alternate, `+` modified)
+    fn show_buffer_items(&mut self, title: &str, buffers: Vec<BufferEntry>) {
+        let current = self.current_script_abs_path().replace('\\', "/");
+        let items = buffers
+            .into_iter()
+            .map(|buffer| {
+                let path = buffer.file.replace('\\', "/");
+                let flag = if path == current {
+                    '%'
+                } else if buffer.alternate {
+                    '#'
+                } else {
+                    ' '
+                };
+                let modified = if buffer.modified { '+' } else { ' ' };
+                let display_path = ProjectSettings::singleton()
+                    .localize_path(&path)
+                    .to_string();
+                PickerItem {
+                    label: format!(
+                        "{:>3} {}{}  {}  line {}",
+                        buffer.number, flag, modified, display_path, buffer.line
+                    ),
+                    path,
+                    position: Some((buffer.line as i32 - 1, buffer.col as i32)),
+                }
+            })
+            .collect();
+        self.show_picker(title, items);
+    }
+
+    /// :b {N|#|partial} - Switch to a buffer by number, the alternate buffer, or a
+    /// fuzzy match of its path (several matches open the picker with them)
+    /// `:b` alone shows all buffers.
+    pub(in crate::plugin) fn cmd_buffer(&mut self, arg: &str) {
+        if arg.is_empty() {
+            self.show_buffers_picker();
+            return;
+        }
+        let Some(buffers) = self.list_buffers() else {
+            return;
+        };
+
+        let target = if let Ok(number) = arg.parse::<i64>() {
+            buffers.into_iter().find(|buffer| buffer.number == number)
+        } else if arg == "#" {
+            buffers.into_iter().find(|buffer| buffer.alternate)
+        } else {
+            let mut matches: Vec<(i64, BufferEntry)> = buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let display_path = ProjectSettings::singleton()
+                        .localize_path(&buffer.file)
+                        .to_string();
+                    matcher::fuzzy_score(&display_path, arg).map(|score| (score, buffer))
+                })
+                .collect();
+            if matches.len() > 1 {
+                // Best matches first
+                matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+                let buffers = matches.into_iter().map(|(_, buffer)| buffer).collect();
+                self.show_buffer_items(&format!("Buffers matching '{}'", arg), buffers);
+                return;
+            }
+            matches.pop().map(|(_, buffer)| buffer)
+        };
+
+        let Some(buffer) = target else {
+            let message = if arg.parse::<i64>().is_ok() {
+                format!("E86: Buffer {} does not exist", arg)
+            } else if arg == "#" {
+                "E23: No alternate file".to_string()
+            } else {
+                format!("E94: No matching buffer for {}", arg)
+            };
+            self.show_status_message(&message);
+            return;
+        };
+
+        crate::verbose_print!(
+            "[godot-neovim] :b {} -> buffer {} ({})",
+            arg,
+            buffer.number,
+            buffer.file
+        );
+        self.open_location(&buffer.file, buffer.line as i32 - 1, buffer.col as i32);
+    }
+
     /// Sync Godot script tab to match Neovim's current buffer
-    /// Called when Neovim switches buffer (e.g., via Ctrl+O/Ctrl+I jump)
+    /// Called when Neovim switches buffer (e.g., via Ctrl+O/Ctrl+I jump): the
+    /// script is switched to (or opened) at Neovim's cursor once Neovim reports
+    /// it (after the jump's keys; see process_rpc_responses)
     pub(crate) fn sync_godot_script_tab(&mut self, neovim_path: &str) {
+        // A file opened from Godot (gf, gd, picker) is on its way
+        if self.pending_file_path.is_some() {
+            return;
+        }
+
+        let requested = self.get_current_neovim().is_some_and(|neovim| {
+            neovim.try_lock().is_ok_and(|client| {
+                client.request_lua(
+                    RpcRequest::BufEnterCursor(neovim_path.to_string()),
+                    "return { vim.fn.line('.'), vim.fn.charcol('.') - 1 }",
+                    vec![],
+                )
+            })
+        });
+        if !requested {
+            crate::verbose_print!("[godot-neovim] BufEnter: Could not request the cursor");
+            self.switch_godot_script_tab(neovim_path, None);
+        }
+    }
+
+    /// Switch to (or open) the script of Neovim's buffer, at `cursor` (line,
+    /// character column, 0-indexed) if known
+    pub(crate) fn switch_godot_script_tab(
+        &mut self,
+        neovim_path: &str,
+        cursor: Option<(i32, i32)>,
+    ) {
+        if self.pending_file_path.is_some() {
+            return;
+        }
+
         let mut editor = EditorInterface::singleton();
         let Some(script_editor) = editor.get_script_editor() else {
             return;
         };
 
+        // Normalize neovim path (ensure forward slashes)
+        let neovim_normalized = neovim_path.replace('\\', "/");
+
         // Check if current Godot script already matches
         if let Some(current_script) = script_editor.get_current_script() {
             let current_res_path = current_script.get_path().to_string();
@@ -248,19 +407,15 @@ impl GodotNeovimPlugin {
                 godot::classes::ProjectSettings::singleton().globalize_path(&current_res_path);
             let current_abs_str = current_abs_path.to_string().replace('\\', "/");
 
-            // Normalize neovim path (ensure forward slashes)
-            let neovim_normalized = neovim_path.replace('\\', "/");
-
             if current_abs_str == neovim_normalized {
                 // Already on the correct script
                 return;
             }
         }
 
+        // Godot would restore its own caret: move it to Neovim's cursor
         // Find and switch to the script matching neovim_path
         let open_scripts = script_editor.get_open_scripts();
-        let neovim_normalized = neovim_path.replace('\\', "/");
-
         for i in 0..open_scripts.len() {
             if let Some(script) = open_scripts.get(i) {
                 let res_path = script.get_path().to_string();
@@ -273,6 +428,7 @@ impl GodotNeovimPlugin {
                         "[godot-neovim] BufEnter: Switching Godot tab to {}",
                         res_path
                     );
+                    self.pending_file_cursor = cursor.map(|(line, col)| (res_path, line, col));
                     // Use call_deferred to avoid issues during event processing
                     editor.call_deferred("edit_script", &[script.to_variant()]);
                     return;
@@ -280,10 +436,30 @@ impl GodotNeovimPlugin {
             }
         }
 
-        crate::verbose_print!(
-            "[godot-neovim] BufEnter: No matching Godot script for {}",
-            neovim_path
-        );
+        // Not open in Godot: open the script (deferred like gf)
+        let res_path = godot::classes::ProjectSettings::singleton()
+            .localize_path(&neovim_normalized)
+            .to_string();
+        if !res_path.starts_with("res://") {
+            crate::verbose_print!(
+                "[godot-neovim] BufEnter: {} is outside the project",
+                neovim_path
+            );
+            return;
+        }
+
+        crate::verbose_print!("[godot-neovim] BufEnter: Opening {}", res_path);
+        self.pending_file_path = Some(res_path.clone());
+        self.pending_file_cursor = cursor.map(|(line, col)| (res_path, line, col));
+    }
+
+    /// Position `{ line, charcol }` (1-indexed line, 0-indexed character column)
+    /// as (line, character column), 0-indexed
+    pub(crate) fn parse_char_position(value: &rmpv::Value) -> Option<(i32, i32)> {
+        let position = value.as_array()?;
+        let line = position.first()?.as_i64()?;
+        let col = position.get(1)?.as_i64()?;
+        Some(((line - 1).max(0) as i32, col.max(0) as i32))
     }
 
     /// Find CodeEdit recursively within a control hierarchy
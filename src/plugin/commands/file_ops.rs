@@ -1,9 +1,14 @@
-//! File operations: :w, :wa, :q, :qa, :e, :e!, ZZ, ZQ
+//! File operations: :w, :w {file}, :saveas, :wa, :xa, :q, :q!, :qa, :wq, :x, :e, :e!, ZZ, ZQ
 //! Also handles forwarding Ex commands to Neovim
 
+use super::super::auto_save::is_dirty;
 use super::super::{EditorType, GodotNeovimPlugin};
 use super::simulate_ctrl_w;
-use godot::classes::{EditorInterface, MenuButton, Node, PopupMenu};
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{
+    CodeEdit, EditorInterface, FileAccess, MenuButton, Node, PopupMenu, ProjectSettings,
+    ResourceSaver, Script,
+};
 use godot::prelude::*;
 
 /// ScriptEditor File menu IDs (from Godot source: editor/script_editor.cpp)
@@ -87,48 +92,23 @@ impl GodotNeovimPlugin {
         let full_cmd = format!(":{}", cmd);
         crate::verbose_print!("[godot-neovim] Forwarding to Neovim: {}", full_cmd);
 
-        // For :set commands with ?, get the option value directly via Neovim API
-        if cmd.starts_with("set ") && cmd.contains('?') {
-            // Extract option name from "set optname?" format
-            let option_name = cmd
-                .strip_prefix("set ")
-                .and_then(|s| s.strip_suffix('?'))
-                .unwrap_or("");
-
-            if !option_name.is_empty() {
-                // Use nvim_get_option_value API for reliable option retrieval
-                // Use buf=0 to get the current buffer's option (for buffer-local options like filetype)
-                let lua_cmd = format!(
-                    "return vim.api.nvim_get_option_value('{}', {{ buf = 0 }})",
-                    option_name
-                );
-                crate::verbose_print!("[godot-neovim] Querying option: {}", lua_cmd);
-                match client.execute_lua_with_result(&lua_cmd) {
-                    Ok(result) => {
-                        let value_str = if result.is_str() {
-                            result.as_str().unwrap_or("").to_string()
-                        } else if result.is_bool() {
-                            result.as_bool().map_or("".to_string(), |b| b.to_string())
-                        } else if result.is_i64() {
-                            result.as_i64().map_or("".to_string(), |n| n.to_string())
-                        } else {
-                            format!("{:?}", result)
-                        };
-                        crate::verbose_print!("[godot-neovim] Option result: {:?}", result);
-                        godot_print!("[godot-neovim] {}={}", option_name, value_str);
-                    }
-                    Err(e) => {
-                        godot_warn!(
-                            "[godot-neovim] Failed to get option '{}': {}",
-                            option_name,
-                            e
-                        );
-                    }
-                }
-            }
-        } else if let Err(e) = client.command(&full_cmd) {
+        if let Err(e) = client.command(&full_cmd) {
+            godot_warn!("[godot-neovim] Neovim command failed: {}", e);
+        }
+    }
+
+    /// Forward a command that can change many lines (:s, :g, :sort, :1,5d, ...)
+    /// to Neovim as an edit transaction, so it is a single undo step on both sides
+    pub(in crate::plugin) fn cmd_forward_as_transaction(&mut self, cmd: &str) {
+        crate::verbose_print!(
+            "[godot-neovim] Forwarding to Neovim as transaction: :{}",
+            cmd
+        );
+        self.begin_edit_transaction();
+        if let Err(e) = self.transaction_command(cmd) {
             godot_warn!("[godot-neovim] Neovim command failed: {}", e);
         }
+        self.commit_edit_transaction();
     }
 
     /// :e[dit] {file} - Open a file in the script editor
@@ -182,12 +162,17 @@ impl GodotNeovimPlugin {
         let resource = godot::classes::ResourceLoader::singleton().load(&path);
         if let Some(res) = resource {
             // Try to cast to Script
-            if let Ok(script) = res.try_cast::<godot::classes::Script>() {
-                // Use edit_script to open the script
-                editor.edit_script(&script);
-                crate::verbose_print!("[godot-neovim] :e - Opened script: {}", path);
-            } else {
-                godot_warn!("[godot-neovim] :e - Not a script file: {}", path);
+            match res.try_cast::<godot::classes::Script>() {
+                Ok(script) => {
+                    // Use edit_script to open the script
+                    editor.edit_script(&script);
+                    crate::verbose_print!("[godot-neovim] :e - Opened script: {}", path);
+                }
+                Err(res) => {
+                    // Shaders open in the shader editor, other resources in the Inspector
+                    editor.edit_resource(&res);
+                    crate::verbose_print!("[godot-neovim] :e - Opened resource: {}", path);
+                }
             }
         } else {
             godot_warn!("[godot-neovim] :e - File not found: {}", path);
@@ -217,143 +202,248 @@ impl GodotNeovimPlugin {
         }
     }
 
-    /// :wa/:wall - Save all open scripts via ScriptEditor's File menu
-    /// This triggers Godot's internal save_all processing, including EditorPlugin hooks
-    pub(in crate::plugin) fn cmd_save_all(&self) {
+    /// :w {file} / :saveas {file} - Write the current buffer to another file
+    /// :w keeps editing the current script (the file is a copy), :saveas opens
+    /// the new file like Vim renames the buffer (the original script stays open).
+    /// An existing file is only overwritten with `!`.
+    pub(in crate::plugin) fn cmd_write_to(&mut self, file_path: &str, bang: bool, saveas: bool) {
+        let tag = if saveas { ":saveas" } else { ":w" };
+        if file_path.is_empty() {
+            self.show_status_message(&format!("{}: Argument required", tag));
+            return;
+        }
+        if !matches!(
+            self.current_editor_type,
+            EditorType::Script | EditorType::TextFile
+        ) {
+            crate::verbose_print!("[godot-neovim] {} - Not a script, nothing to write", tag);
+            return;
+        }
+        let Some(ref editor) = self.current_editor else {
+            crate::verbose_print!("[godot-neovim] {} - No current editor", tag);
+            return;
+        };
+        let text = editor.get_text();
+
+        // Relative paths are relative to res:// (like :e)
+        let path = if file_path.starts_with("res://") {
+            file_path.to_string()
+        } else {
+            let localized = ProjectSettings::singleton()
+                .localize_path(file_path)
+                .to_string();
+            if localized.starts_with("res://") {
+                localized
+            } else {
+                format!("res://{}", file_path)
+            }
+        };
+        if path == self.current_script_path {
+            self.cmd_save();
+            return;
+        }
+
+        let existed = FileAccess::file_exists(&path);
+        if existed && !bang {
+            self.show_status_message(&format!("E13: File exists (add ! to override): {}", path));
+            return;
+        }
+        let Some(mut file) = FileAccess::open(&path, ModeFlags::WRITE) else {
+            let error = FileAccess::get_open_error();
+            godot_warn!(
+                "[godot-neovim] {} - Cannot write {}: {:?}",
+                tag,
+                path,
+                error
+            );
+            self.show_status_message(&format!("E212: Can't open file for writing: {}", path));
+            return;
+        };
+        file.store_string(&text);
+        file.close();
+
+        // Let the FileSystem dock and ResourceLoader see the new file
+        if let Some(mut file_system) = EditorInterface::singleton().get_resource_filesystem() {
+            if existed {
+                file_system.update_file(&path);
+            } else {
+                file_system.scan();
+            }
+        }
+
+        let line_count = text.to_string().lines().count();
+        self.show_status_message(&format!("\"{}\" {}L written", path, line_count));
+        crate::verbose_print!("[godot-neovim] {} - Wrote {}", tag, path);
+
+        if saveas {
+            // Defer file open to avoid borrow conflict with on_script_changed
+            self.pending_file_path = Some(path);
+        }
+    }
+
+    /// :wa/:wall - Save all modified open scripts via ResourceSaver
+    /// Each script is saved on its own, so a failure is reported for that file
+    /// and the others are still saved. The saves are done before returning,
+    /// so :xa can close the tabs right after without a save prompt.
+    pub(in crate::plugin) fn cmd_save_all(&mut self) {
         if self.current_editor_type == EditorType::Unknown {
             crate::verbose_print!("[godot-neovim] :wa - External CodeEdit, no files to save");
             return;
         }
 
-        if emit_file_menu_signal(file_menu::SAVE_ALL) {
-            crate::verbose_print!(
-                "[godot-neovim] :wa - emit_signal(id_pressed, {})",
-                file_menu::SAVE_ALL
-            );
-        } else {
-            godot_warn!("[godot-neovim] :wa - Could not find File menu in ScriptEditor");
+        let Some(script_editor) = EditorInterface::singleton().get_script_editor() else {
+            return;
+        };
+        let open_scripts = script_editor.get_open_scripts();
+        let open_editors = script_editor.get_open_script_editors();
+
+        // get_open_scripts() and get_open_script_editors() share the same tab order
+        // only when no non-script documents (text files, help pages) are open:
+        // let Godot's Save All match them up
+        if open_scripts.len() != open_editors.len() {
+            if emit_file_menu_signal(file_menu::SAVE_ALL) {
+                crate::verbose_print!(
+                    "[godot-neovim] :wa - emit_signal(id_pressed, {})",
+                    file_menu::SAVE_ALL
+                );
+            } else {
+                godot_warn!("[godot-neovim] :wa - Could not find File menu in ScriptEditor");
+            }
+            return;
         }
+
+        let mut saved = 0;
+        let mut failed = 0;
+        let mut current_saved = false;
+        for i in 0..open_scripts.len() {
+            let (Some(mut script), Some(code_edit)) = (
+                open_scripts.get(i),
+                open_editors
+                    .get(i)
+                    .and_then(|editor| editor.get_base_editor())
+                    .and_then(|control| control.try_cast::<CodeEdit>().ok()),
+            ) else {
+                continue;
+            };
+            if code_edit.get_version() == code_edit.get_saved_version() {
+                continue;
+            }
+            match Self::save_script_from_editor(&mut script, code_edit.clone()) {
+                Ok(path) => {
+                    saved += 1;
+                    current_saved |= self.current_editor.as_ref() == Some(&code_edit);
+                    crate::verbose_print!("[godot-neovim] :wa - Saved {}", path);
+                }
+                Err(e) => {
+                    failed += 1;
+                    godot_warn!("[godot-neovim] :wa - {}", e);
+                }
+            }
+        }
+
+        // Neovim's buffer is saved too (the BufWriteCmd of :w does the same)
+        if current_saved {
+            if let Some(neovim) = self.get_current_neovim() {
+                if let Ok(client) = neovim.try_lock() {
+                    let _ = client.command("setlocal nomodified");
+                }
+            }
+        }
+
+        let message = match (saved, failed) {
+            (0, 0) => "No modified scripts".to_string(),
+            (saved, 0) => format!("{} script(s) written", saved),
+            (saved, failed) => format!(
+                "{} script(s) written, {} failed (see Output)",
+                saved, failed
+            ),
+        };
+        self.show_status_message(&message);
+    }
+
+    /// Write the text of a script's CodeEdit to its file and mark it saved
+    /// Returns the path, or an error message naming the script
+    pub(in crate::plugin) fn save_script_from_editor(
+        script: &mut Gd<Script>,
+        mut code_edit: Gd<CodeEdit>,
+    ) -> Result<String, String> {
+        let path = script.get_path().to_string();
+        if path.is_empty() {
+            return Err("Script has no file yet, save it with Save As".to_string());
+        }
+        // Built-in scripts are saved with their scene
+        if path.contains("::") {
+            return Err(format!("{}: Built-in script, save its scene instead", path));
+        }
+
+        script.set_source_code(&code_edit.get_text());
+        let result = ResourceSaver::singleton()
+            .save_ex(&*script)
+            .path(&path)
+            .done();
+        if result != godot::global::Error::OK {
+            return Err(format!("{}: Failed to save ({:?})", path, result));
+        }
+        code_edit.tag_saved_version();
+        Ok(path)
     }
 
     /// :e!/:edit! - Reload current file from disk (discard changes)
-    /// Uses Neovim Master design: call Lua reload_buffer to reload and re-attach
+    /// The file is read in its own encoding; the CodeEdit and the Neovim buffer
+    /// get the disk content as a fresh baseline (no undo history, not modified),
+    /// and the Script resource is updated so reopening does not bring back the
+    /// discarded text (like ZQ, without closing the tab).
     pub(in crate::plugin) fn cmd_reload(&mut self) {
         if self.current_editor_type == EditorType::Unknown {
             crate::verbose_print!("[godot-neovim] :e! - External CodeEdit, nothing to reload");
             return;
         }
 
-        let neovim_ref = match self.current_editor_type {
-            EditorType::Shader => self.shader_neovim.as_ref(),
-            _ => self.script_neovim.as_ref(),
-        };
-        let Some(neovim) = neovim_ref else {
-            godot_warn!("[godot-neovim] :e! - Neovim not connected");
+        let path = self.current_script_path.clone();
+        let Some(text) = self.read_file_decoded(&path) else {
+            godot_warn!("[godot-neovim] :e! - Cannot read {}", path);
+            self.show_status_message(&format!("E484: Can't open file {}", path));
             return;
         };
 
-        let Ok(client) = neovim.try_lock() else {
-            godot_warn!("[godot-neovim] :e! - Failed to lock Neovim");
+        let Some(ref mut code_edit) = self.current_editor else {
             return;
         };
-
-        // Call Lua reload_buffer function which:
-        // 1. Executes :e! to reload from disk
-        // 2. Re-attaches for notifications
-        // 3. Returns the new buffer content
-        match client.execute_lua_with_result("return _G.godot_neovim.reload_buffer()") {
-            Ok(result) => {
-                // Parse result: { lines = [...], tick = number, attached = bool, cursor = {row, col} }
-                if let rmpv::Value::Map(map) = result {
-                    let mut lines: Vec<String> = Vec::new();
-                    let mut tick: i64 = 0;
-                    let mut cursor: Option<(i64, i64)> = None;
-
-                    for (key, value) in map {
-                        if let rmpv::Value::String(k) = key {
-                            match k.as_str() {
-                                Some("lines") => {
-                                    if let rmpv::Value::Array(arr) = value {
-                                        lines = arr
-                                            .into_iter()
-                                            .filter_map(|v| {
-                                                if let rmpv::Value::String(s) = v {
-                                                    s.into_str()
-                                                } else {
-                                                    None
-                                                }
-                                            })
-                                            .collect();
-                                    }
-                                }
-                                Some("tick") => {
-                                    if let rmpv::Value::Integer(i) = value {
-                                        tick = i.as_i64().unwrap_or(0);
-                                    }
-                                }
-                                Some("cursor") => {
-                                    // cursor is {row, col} - row is 1-indexed from Neovim
-                                    if let rmpv::Value::Array(arr) = value {
-                                        if arr.len() >= 2 {
-                                            let row = arr[0].as_i64().unwrap_or(1);
-                                            let col = arr[1].as_i64().unwrap_or(0);
-                                            cursor = Some((row, col));
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-
-                    // Apply the reloaded content from Neovim to Godot CodeEdit
-                    if let Some(ref mut code_edit) = self.current_editor {
-                        let line_count = lines.len() as i32;
-                        let text = lines.join("\n");
-                        code_edit.set_text(&text);
-                        code_edit.tag_saved_version();
-
-                        // Apply cursor position (convert from 1-indexed to 0-indexed line)
-                        if let Some((row, col)) = cursor {
-                            let line = (row - 1).max(0) as i32;
-                            // Convert byte column from Neovim to character column for Godot
-                            let line_text = code_edit.get_line(line).to_string();
-                            let char_col = Self::byte_col_to_char_col(&line_text, col as i32);
-                            code_edit.set_caret_line(line);
-                            code_edit.set_caret_column(char_col);
-                            // Update last_synced_cursor so the deferred caret_changed (emitted
-                            // via call_deferred by TextEdit) matches and is skipped in
-                            // on_caret_changed, preventing a redundant sync back to Neovim.
-                            self.last_synced_cursor = (line as i64, char_col as i64);
-                            crate::verbose_print!(
-                                "[godot-neovim] :e! - Set cursor to line={}, col={} (byte_col={})",
-                                line,
-                                char_col,
-                                col
-                            );
-                        }
-
-                        // Update sync manager with new tick and line count
-                        self.sync_manager.set_initial_sync_tick(tick);
-                        self.sync_manager.set_line_count(line_count);
-
-                        crate::verbose_print!(
-                            "[godot-neovim] :e! - Reloaded {} lines, tick={}",
-                            line_count,
-                            tick
-                        );
-                    }
-                    // Note: (*) marker may still show until tab switch
-                    // This is a Godot limitation - set_text() marks as modified
+        let (caret_line, caret_column) = (code_edit.get_caret_line(), code_edit.get_caret_column());
+        code_edit.set_text(&text);
+        code_edit.clear_undo_history();
+        code_edit.tag_saved_version();
+
+        // Keep the caret where it was, within the reloaded text
+        let line = caret_line.min(code_edit.get_line_count() - 1);
+        let column = caret_column.min(code_edit.get_line(line).len() as i32);
+        code_edit.set_caret_line(line);
+        code_edit.set_caret_column(column);
+        // The deferred caret_changed (emitted via call_deferred by TextEdit) matches
+        // and is skipped in on_caret_changed; the cursor is synced below
+        self.last_synced_cursor = (line as i64, column as i64);
+        let line_count = code_edit.get_line_count();
+
+        if self.current_editor_type == EditorType::Script {
+            if let Some(script_editor) = EditorInterface::singleton().get_script_editor() {
+                if let Some(mut current_script) = script_editor.get_current_script() {
+                    current_script.set_source_code(&text);
                 }
             }
-            Err(e) => {
-                godot_warn!("[godot-neovim] :e! - Lua call failed: {}", e);
-            }
         }
+
+        self.sync_buffer_to_neovim_reset_undo();
+        self.sync_cursor_to_neovim();
+
+        self.show_status_message(&format!("\"{}\" {}L reloaded", path, line_count));
+        crate::verbose_print!(
+            "[godot-neovim] :e! - Reloaded {} lines from {}",
+            line_count,
+            path
+        );
     }
 
-    /// ZZ/:wq - Save and close via ScriptEditor's File menu
+    /// :wq - Save and close via ScriptEditor's File menu
     /// This triggers Godot's internal save processing, including EditorPlugin hooks
     /// Close is deferred to next frame via pending_close_after_save flag
     pub(in crate::plugin) fn cmd_save_and_close(&mut self) {
@@ -384,12 +474,44 @@ impl GodotNeovimPlugin {
         crate::verbose_print!("[godot-neovim] :wq/ZZ - Close scheduled for next frame");
     }
 
-    /// :q - Close the current script tab by simulating Ctrl+W
+    /// Whether the current script has changes since it was last saved
+    /// (an external CodeEdit is only detached, so it never counts as modified)
+    fn current_editor_is_dirty(&self) -> bool {
+        self.current_editor_type != EditorType::Unknown
+            && self
+                .current_editor
+                .as_ref()
+                .is_some_and(|editor| editor.is_instance_valid() && is_dirty(editor))
+    }
+
+    /// :q - Close the current script tab, unless it has unsaved changes (E37)
+    /// Godot's own "save changes?" dialog is never reached: :q! discards, :w/:x save
+    pub(in crate::plugin) fn cmd_quit(&mut self) {
+        if self.current_editor_is_dirty() {
+            crate::verbose_print!("[godot-neovim] :q - Script is modified, not closing");
+            self.show_status_message("E37: No write since last change (add ! to override)");
+            return;
+        }
+        self.cmd_close();
+    }
+
+    /// ZZ/:x - Save only if the script is modified, then close
+    pub(in crate::plugin) fn cmd_update_and_close(&mut self) {
+        if self.current_editor_is_dirty() {
+            self.cmd_save_and_close();
+        } else {
+            crate::verbose_print!("[godot-neovim] :x/ZZ - Not modified, closing without save");
+            self.cmd_close();
+        }
+    }
+
+    /// Close the current script tab by simulating Ctrl+W (no modified check)
     pub(in crate::plugin) fn cmd_close(&mut self) {
         // Disconnect from signals BEFORE closing to avoid
         // accessing freed CodeEdit instance
         self.disconnect_caret_changed_signal();
         self.disconnect_resized_signal();
+        self.disconnect_focus_signal();
 
         if self.current_editor_type == EditorType::Unknown {
             // Delete scratch buffer before clearing state to avoid buffer leak
@@ -462,6 +584,7 @@ impl GodotNeovimPlugin {
         // to avoid accessing freed CodeEdit instance
         self.disconnect_caret_changed_signal();
         self.disconnect_resized_signal();
+        self.disconnect_focus_signal();
         self.disconnect_gui_input_signal();
 
         // Delete shader buffer from Neovim before closing
@@ -491,11 +614,12 @@ impl GodotNeovimPlugin {
         crate::verbose_print!("[godot-neovim] :q - Shader tab close triggered (Ctrl+W)");
     }
 
-    /// ZQ - Close without saving (discard changes)
+    /// ZQ/:q! - Close without saving (discard changes)
     pub(in crate::plugin) fn cmd_close_discard(&mut self) {
         // Disconnect from signals BEFORE closing
         self.disconnect_caret_changed_signal();
         self.disconnect_resized_signal();
+        self.disconnect_focus_signal();
 
         if self.current_editor_type == EditorType::Unknown {
             // Delete scratch buffer before clearing state to avoid buffer leak
@@ -607,6 +731,7 @@ impl GodotNeovimPlugin {
         // Disconnect from signals BEFORE closing
         self.disconnect_caret_changed_signal();
         self.disconnect_resized_signal();
+        self.disconnect_focus_signal();
 
         // Clear current editor reference since it will be freed
         self.current_editor = None;
@@ -0,0 +1,30 @@
+//! Formatting: :[range]format runs the formatter (gdformat) in Neovim
+
+use super::super::GodotNeovimPlugin;
+
+impl GodotNeovimPlugin {
+    /// :[range]format - Format the range with the formatter, the whole script by default
+    /// (gq{motion} uses the same formatter through Neovim's 'formatexpr')
+    pub(in crate::plugin) fn cmd_format(&mut self, range: &str) {
+        self.begin_edit_transaction();
+        let result = self.transaction_lua(
+            "return _G.godot_neovim.format.format_range(...)",
+            vec![rmpv::Value::from(range)],
+        );
+        self.commit_edit_transaction();
+
+        match result {
+            Ok(rmpv::Value::String(error)) => {
+                let error = error.as_str().unwrap_or_default();
+                crate::verbose_print!("[godot-neovim] :{}format - {}", range, error);
+                self.command_output = Some(error.to_string());
+            }
+            Ok(_) => {
+                crate::verbose_print!("[godot-neovim] :{}format - Formatted", range);
+            }
+            Err(e) => {
+                self.command_output = Some(format!(":format - {}", e));
+            }
+        }
+    }
+}
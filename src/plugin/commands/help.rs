@@ -1,4 +1,4 @@
-//! Help and documentation: :help, :version, K
+//! Help and documentation: :help, :version, K (help tab mode; hover float is in lsp.rs)
 
 use super::super::{GodotNeovimPlugin, HelpMemberType, HelpQuery};
 use godot::classes::ProjectSettings;
@@ -25,6 +25,12 @@ impl GodotNeovimPlugin {
     /// Note: Actual goto_help() call is deferred to process() to avoid borrow conflicts
     /// (goto_help triggers editor_script_changed signal synchronously)
     pub(in crate::plugin) fn open_documentation(&mut self) {
+        // Hover mode: show LSP hover text in a float instead of opening the help tab
+        if crate::settings::get_k_behavior() == crate::settings::KBehavior::Hover {
+            self.lsp_hover();
+            return;
+        }
+
         let Some(ref editor) = self.current_editor else {
             return;
         };
@@ -143,12 +149,11 @@ impl GodotNeovimPlugin {
         }
     }
 
-    /// Parse LSP hover response to extract class/member information for goto_help()
-    fn parse_hover_for_help(hover: &lsp_types::Hover, word: &str) -> Option<HelpQuery> {
+    /// Extract LSP hover contents as a single (markdown) string
+    pub(in crate::plugin) fn hover_contents_to_string(hover: &lsp_types::Hover) -> String {
         use lsp_types::{HoverContents, MarkedString, MarkupContent};
 
-        // Extract the hover content as a string
-        let content = match &hover.contents {
+        match &hover.contents {
             HoverContents::Scalar(marked) => match marked {
                 MarkedString::String(s) => s.clone(),
                 MarkedString::LanguageString(ls) => ls.value.clone(),
@@ -162,7 +167,12 @@ impl GodotNeovimPlugin {
                 .collect::<Vec<_>>()
                 .join("\n"),
             HoverContents::Markup(MarkupContent { value, .. }) => value.clone(),
-        };
+        }
+    }
+
+    /// Parse LSP hover response to extract class/member information for goto_help()
+    fn parse_hover_for_help(hover: &lsp_types::Hover, word: &str) -> Option<HelpQuery> {
+        let content = Self::hover_contents_to_string(hover);
 
         crate::verbose_print!("[godot-neovim] K: Parsing hover content: {}", content);
 
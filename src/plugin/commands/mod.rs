@@ -4,18 +4,29 @@
 //! - mode: Command-line mode management (open/close, history)
 //! - file_ops: File operations (:w, :q, :e, etc.)
 //! - buffer_nav: Buffer/tab navigation (:bn, :bp, gt, gT)
+//! - buffer_delete: Closing a script by buffer number or path (:bd)
 //! - info: Information display (:marks, :registers, :jumps, :ls)
 //! - help: Help and documentation (:help, :version, K)
+//! - preview: Live substitute preview while typing :s ('inccommand')
+//! - run: Running the project (:run, :stop)
+//! - format: Formatting with gdformat (:format)
 
 use godot::classes::{Input, InputEventKey};
 use godot::global::Key;
 use godot::prelude::*;
 
+mod buffer_delete;
 mod buffer_nav;
 mod file_ops;
+mod format;
 mod help;
 mod info;
 mod mode;
+mod preview;
+mod run;
+
+pub(super) use buffer_delete::PendingBufferDelete;
+pub(super) use preview::SubstitutePreview;
 
 /// Simulate a key press and release with optional modifiers
 /// This triggers Godot's internal shortcut handling
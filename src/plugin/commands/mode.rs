@@ -11,17 +11,41 @@ impl GodotNeovimPlugin {
         self.command_mode = true;
         self.command_buffer = ":".to_string();
 
+        // From visual mode: operate on the selected lines like Vim (:'<,'>)
+        // The selection stays visible until the command is executed or cancelled
+        self.command_from_visual = self.is_in_visual_mode();
+        if self.command_from_visual {
+            self.command_buffer.push_str("'<,'>");
+        }
+
         // Show command in mode label with yellow color
         let label = match self.current_editor_type {
             EditorType::Shader => self.shader_mode_label.as_mut(),
             _ => self.mode_label.as_mut(),
         };
         if let Some(label) = label {
-            label.set_text(":");
+            label.set_text(&self.command_buffer);
             Self::set_command_mode_color(label);
         }
     }
 
+    /// Leave visual mode in Neovim before running a command opened from it
+    /// Exiting visual mode sets the '< and '> marks used by the prefilled range
+    fn leave_visual_for_command(&mut self) {
+        if !std::mem::take(&mut self.command_from_visual) {
+            return;
+        }
+        if let Some(neovim) = self.get_current_neovim() {
+            if let Ok(client) = neovim.try_lock() {
+                // Runs after the visual-mode keys still in the input queue
+                if let Err(e) = client.command("exe \"normal! \\<Esc>\"") {
+                    godot_warn!("[godot-neovim] Failed to leave visual mode: {}", e);
+                }
+            }
+        }
+        self.clear_visual_selection();
+    }
+
     /// Set yellow color for command mode
     fn set_command_mode_color(label: &mut Gd<Label>) {
         label.add_theme_color_override("font_color", Color::from_rgb(1.0, 1.0, 0.4));
@@ -31,6 +55,15 @@ impl GodotNeovimPlugin {
     pub(in crate::plugin) fn close_command_line(&mut self) {
         self.command_mode = false;
         self.command_buffer.clear();
+        self.close_wildmenu();
+
+        // Cancelled from visual mode: back to normal mode like Vim
+        self.leave_visual_for_command();
+
+        // Cancelled with a substitute preview shown: restore the original lines
+        if self.restore_substitute_preview() {
+            self.refresh_hlsearch();
+        }
 
         // Restore mode display (unless showing version)
         if !self.show_version {
@@ -126,14 +159,177 @@ impl GodotNeovimPlugin {
             Some('\'') => true,
             // Relative: :+1d, :-1d
             Some('+') | Some('-') => true,
+            // Whole file: :%s/old/new/g, :%d
+            Some('%') => true,
             _ => false,
         }
     }
 
+    /// Check if a command (without range) is :substitute or a repeat of it
+    /// Any non-alphanumeric delimiter is accepted (:s/a/b/, :s#a#b#, :substitute|a|b|)
+    fn is_substitute_command(cmd: &str) -> bool {
+        if matches!(cmd, "&" | "&&" | "~") || cmd.starts_with("& ") {
+            return true;
+        }
+        let name_len = cmd.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        let (name, rest) = cmd.split_at(name_len);
+        if name.is_empty() || !"substitute".starts_with(name) {
+            return false;
+        }
+        // :s alone repeats the last substitute; otherwise a delimiter must follow
+        rest.is_empty()
+            || rest
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_alphanumeric() && !c.is_whitespace() && c != '"' && c != '|')
+    }
+
+    /// Check if a command (without range) is :global or :vglobal
+    /// Accepts :g/pat/cmd, :g!/pat/cmd, :global/pat/cmd, :v/pat/cmd, :vglobal#pat#cmd
+    fn is_global_command(cmd: &str) -> bool {
+        let name_len = cmd.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        let (name, rest) = cmd.split_at(name_len);
+        let is_global = !name.is_empty() && "global".starts_with(name);
+        let is_vglobal = !name.is_empty() && "vglobal".starts_with(name);
+        if !is_global && !is_vglobal {
+            return false;
+        }
+        // :g! is the same as :v
+        let rest = if is_global {
+            rest.strip_prefix('!').unwrap_or(rest)
+        } else {
+            rest
+        };
+        // Same delimiter rules as Vim: not alphanumeric, '"', '|' or whitespace
+        rest.chars()
+            .next()
+            .is_some_and(|c| !c.is_alphanumeric() && !c.is_whitespace() && c != '"' && c != '|')
+    }
+
+    /// Check if a command (without range) is a line-wise Ex command run by Neovim:
+    /// :sor[t][!] [flags], :m[ove] {addr}, :t / :co[py] {addr}, :norm[al][!] {keys}
+    fn is_line_command(cmd: &str) -> bool {
+        let name_len = cmd.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        let (name, rest) = cmd.split_at(name_len);
+        // The name is the whole alphabetic run, so :m0 / :t. / :normal! dd split correctly
+        let abbreviates =
+            |full: &str, min_len: usize| name.len() >= min_len && full.starts_with(name);
+        if abbreviates("sort", 3) || abbreviates("normal", 4) {
+            return true;
+        }
+        // :m / :t / :co need a destination address
+        (abbreviates("move", 1) || abbreviates("copy", 2) || name == "t") && !rest.trim().is_empty()
+    }
+
+    /// Range of :[range]format ("" for the whole file), None for other commands
+    /// Only a bare line range followed by the command name counts (not e.g.
+    /// `:%s/fmt/format`, a substitute without its closing delimiter)
+    fn format_command_range(cmd: &str) -> Option<&str> {
+        let range_len = if Self::has_line_range(cmd) {
+            Self::line_range_len(cmd)
+        } else {
+            0
+        };
+        let (range, rest) = cmd.split_at(range_len);
+        Self::ex_command_arg(rest, "format", 6)
+            .filter(|arg| arg.is_empty())
+            .map(|_| range.trim_end())
+    }
+
+    /// Length in bytes of the line range a command starts with
+    /// (numbers, `.`, `$`, `%`, marks, offsets and separators; not /pattern/)
+    fn line_range_len(cmd: &str) -> usize {
+        let mut chars = cmd.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                // Mark: the next character is its name
+                '\'' => {
+                    if chars.next().is_none() {
+                        return cmd.len();
+                    }
+                }
+                c if c.is_ascii_digit() || ".$%,;+- ".contains(c) => {}
+                _ => return i,
+            }
+        }
+        cmd.len()
+    }
+
+    /// Argument of :b[uffer] {arg}, None for other commands
+    fn buffer_command_arg(cmd: &str) -> Option<&str> {
+        let name_len = cmd.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        let (name, rest) = cmd.split_at(name_len);
+        if name.is_empty() || !"buffer".starts_with(name) {
+            return None;
+        }
+        // :b 3, :b3, :b# (but not :b3x)
+        if rest.is_empty()
+            || rest.starts_with(' ')
+            || rest == "#"
+            || rest.chars().all(|c| c.is_ascii_digit())
+        {
+            Some(rest.trim())
+        } else {
+            None
+        }
+    }
+
+    /// Argument and bang of :bd[elete][!] [N|path], None for other commands
+    fn buffer_delete_command_arg(cmd: &str) -> Option<(&str, bool)> {
+        let name_len = cmd.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        let (name, rest) = cmd.split_at(name_len);
+        if name.len() < 2 || !"bdelete".starts_with(name) {
+            return None;
+        }
+        let (rest, force) = match rest.strip_prefix('!') {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+        // :bd, :bd 3, :bd3, :bd! res://player.gd
+        (rest.is_empty() || rest.starts_with(' ') || rest.chars().all(|c| c.is_ascii_digit()))
+            .then(|| (rest.trim(), force))
+    }
+
+    /// Argument of the Ex command `full` (abbreviated to at least `min_len`
+    /// characters), None for other commands
+    fn ex_command_arg<'a>(cmd: &'a str, full: &str, min_len: usize) -> Option<&'a str> {
+        let name_len = cmd.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        let (name, rest) = cmd.split_at(name_len);
+        if name.len() < min_len || !full.starts_with(name) {
+            return None;
+        }
+        (rest.is_empty() || rest.starts_with(' ') || rest.chars().all(|c| c.is_ascii_digit()))
+            .then(|| rest.trim())
+    }
+
+    /// Arguments of `:w[rite][!] {file}` and `:sav[eas][!] {file}`:
+    /// (file, bang, saveas), None for other commands and :w without a file
+    fn write_command_arg(cmd: &str) -> Option<(&str, bool, bool)> {
+        let (name, file) = cmd
+            .split_once(' ')
+            .map_or((cmd, ""), |(name, file)| (name, file.trim()));
+        let (name, bang) = name
+            .strip_suffix('!')
+            .map_or((name, false), |name| (name, true));
+        if name.len() >= 3 && "saveas".starts_with(name) {
+            Some((file, bang, true))
+        } else if !name.is_empty() && "write".starts_with(name) && !file.is_empty() {
+            Some((file, bang, false))
+        } else {
+            None
+        }
+    }
+
     /// Execute the current command
     pub(in crate::plugin) fn execute_command(&mut self) {
         let command = self.command_buffer.clone();
 
+        // The preview must be undone before Neovim applies the real change
+        self.restore_substitute_preview();
+
+        // Set the '< and '> marks before a visual range is resolved by Neovim
+        self.leave_visual_for_command();
+
         // Remove the leading ':'
         let cmd = command.strip_prefix(':').unwrap_or(&command).trim();
 
@@ -151,37 +347,51 @@ impl GodotNeovimPlugin {
         crate::verbose_print!("[godot-neovim] Executing command: {}", cmd);
 
         match cmd {
-            "w" => self.cmd_save(),
-            "q" => self.cmd_close(),
-            "q!" => self.cmd_close_discard(),
+            "w" | "w!" | "write" | "write!" => self.cmd_save(),
+            "q" | "quit" => self.cmd_quit(),
+            "q!" | "quit!" => self.cmd_close_discard(),
             "qa" | "qall" => self.cmd_close_all(),
             "qa!" | "qall!" => self.cmd_close_all(),
-            "wq" | "x" => self.cmd_save_and_close(),
-            "wq!" | "x!" => self.cmd_save_and_close(),
+            "wq" | "wq!" => self.cmd_save_and_close(),
+            "x" | "x!" | "xit" | "xit!" | "exit" | "exit!" => self.cmd_update_and_close(),
             "wa" | "wall" => self.cmd_save_all(),
             "wqa" | "wqall" | "xa" | "xall" | "wqa!" | "wqall!" | "xa!" | "xall!" => {
                 self.cmd_save_all();
                 self.cmd_close_all();
             }
             "e!" | "edit!" => self.cmd_reload(),
+            // :nohlsearch - Neovim clears v:hlsearch, the refresh below removes the highlights
+            "noh" | "nohl" | "nohlsearch" => self.cmd_forward_to_neovim("nohlsearch"),
             _ => {
                 // Check for :{number} - jump to line (must check before has_line_range)
                 // Pure numbers like "100" should use G motion for proper jump list support
                 if let Ok(line_num) = cmd.parse::<i32>() {
                     self.cmd_goto_line(line_num);
                 }
+                // :[range]format - run the formatter (gdformat) on the range
+                else if let Some(range) = Self::format_command_range(cmd) {
+                    self.cmd_format(range);
+                }
                 // Check for line range commands (e.g., :1,5d, :.,$s/old/new/g)
                 // Forward to Neovim for processing (Neovim Master design)
                 else if Self::has_line_range(cmd) {
-                    self.cmd_forward_to_neovim(cmd);
+                    self.cmd_forward_as_transaction(cmd);
+                }
+                // :w {file} - write a copy, :saveas {file} - write and open it
+                else if let Some((file, bang, saveas)) = Self::write_command_arg(cmd) {
+                    self.cmd_write_to(file, bang, saveas);
                 }
                 // Check for :marks - show marks
                 else if cmd == "marks" {
                     self.cmd_show_marks();
                 }
-                // Check for :registers or :reg - show registers
-                else if cmd == "registers" || cmd == "reg" {
-                    self.cmd_show_registers();
+                // Check for :registers or :reg [names] - show registers
+                else if matches!(
+                    cmd.split_once(' ').map_or(cmd, |(name, _)| name),
+                    "registers" | "reg" | "display" | "di"
+                ) {
+                    let names = cmd.split_once(' ').map_or("", |(_, names)| names);
+                    self.cmd_show_registers(names);
                 }
                 // Check for :jumps - show jump list
                 else if cmd == "jumps" || cmd == "ju" {
@@ -213,46 +423,116 @@ impl GodotNeovimPlugin {
                     }
                 }
                 // Commands forwarded to Neovim for proper undo/register integration
-                // (Neovim Master design - see DESIGN_V2.md):
-                // - :%s/old/new/g, :s/old/new/g (substitute)
-                // - :g/pattern/cmd (global)
-                // - :sort
-                // - :t{line} (copy line)
-                // - :m{line} (move line)
-                else if cmd.starts_with("%s/")
-                    || cmd.starts_with("s/")
-                    || cmd.starts_with("g/")
-                    || cmd == "sort"
-                    || cmd.starts_with("sort ")
-                    || (cmd.starts_with("t") && cmd.len() > 1)
-                    || (cmd.starts_with("m") && cmd.len() > 1)
+                // (Neovim Master design - see DESIGN_V2.md), each a single undo step:
+                // - :s/old/new/g, :s#old#new#, :&&, :~ (substitute, regex and flags by Neovim)
+                // - :g/pattern/cmd, :g!/pattern/cmd, :v/pattern/cmd (global)
+                // - :sort [u][i][n] (sort lines)
+                // - :t{addr}, :co[py] {addr} (copy lines)
+                // - :m[ove] {addr} (move lines)
+                // - :norm[al][!] {keys} (run Normal mode keys)
+                else if Self::is_substitute_command(cmd)
+                    || Self::is_global_command(cmd)
+                    || Self::is_line_command(cmd)
                 {
-                    self.cmd_forward_to_neovim(cmd);
+                    self.cmd_forward_as_transaction(cmd);
+                }
+                // :rename {new_name} - LSP rename (gR prefills this)
+                else if cmd == "rename" || cmd.starts_with("rename ") {
+                    let new_name = cmd.strip_prefix("rename").unwrap_or("").trim();
+                    self.cmd_lsp_rename(new_name);
+                }
+                // :undotree - pick a state of Neovim's undo tree to restore
+                else if Self::ex_command_arg(cmd, "undotree", 5) == Some("") {
+                    self.show_undotree_picker();
+                }
+                // :symbols - outline of the current script (LSP document symbols)
+                else if Self::ex_command_arg(cmd, "symbols", 3) == Some("") {
+                    self.show_symbols_picker();
+                }
+                // :wsymbols [query] - symbols of the whole project (LSP workspace symbols)
+                else if let Some(query) = Self::ex_command_arg(cmd, "wsymbols", 4) {
+                    self.cmd_workspace_symbols(query);
                 }
                 // Buffer navigation commands
                 else if cmd == "bn" || cmd == "bnext" {
                     self.cmd_buffer_next();
                 } else if cmd == "bp" || cmd == "bprev" || cmd == "bprevious" {
                     self.cmd_buffer_prev();
-                } else if cmd == "bd" || cmd == "bdelete" {
-                    self.cmd_close();
+                } else if let Some((arg, force)) = Self::buffer_delete_command_arg(cmd) {
+                    self.cmd_buffer_delete(arg, force);
                 } else if cmd == "ls" || cmd == "buffers" {
                     self.cmd_list_buffers();
+                } else if let Some(arg) = Self::buffer_command_arg(cmd) {
+                    self.cmd_buffer(arg);
+                }
+                // :gr[ep] {pattern} - search the project's GDScript files
+                else if let Some(pattern) = Self::ex_command_arg(cmd, "grep", 2) {
+                    self.cmd_grep(pattern);
+                }
+                // Quickfix list (results of :grep)
+                else if Self::ex_command_arg(cmd, "cnext", 2) == Some("") {
+                    self.cmd_quickfix_step(1);
+                } else if Self::ex_command_arg(cmd, "cprevious", 2) == Some("")
+                    || Self::ex_command_arg(cmd, "cNext", 2) == Some("")
+                {
+                    self.cmd_quickfix_step(-1);
+                } else if Self::ex_command_arg(cmd, "cfirst", 4) == Some("")
+                    || Self::ex_command_arg(cmd, "crewind", 2) == Some("")
+                {
+                    self.cmd_quickfix_goto(Some(1));
+                } else if Self::ex_command_arg(cmd, "clast", 3) == Some("") {
+                    self.cmd_quickfix_goto(Some(usize::MAX));
+                } else if let Some(number) = Self::ex_command_arg(cmd, "cc", 2) {
+                    self.cmd_quickfix_goto(number.parse().ok());
+                } else if Self::ex_command_arg(cmd, "copen", 4) == Some("")
+                    || Self::ex_command_arg(cmd, "cwindow", 2) == Some("")
+                {
+                    self.show_quickfix_picker();
                 }
                 // :help - open GodotNeovim help
                 else if cmd == "help" || cmd == "h" {
                     self.cmd_help();
                 }
+                // :checkhealth - diagnostics report
+                else if Self::ex_command_arg(cmd, "checkhealth", 3) == Some("") {
+                    self.cmd_checkhealth();
+                }
                 // :version - show version in status label
                 else if cmd == "version" || cmd == "ver" {
                     self.cmd_version();
                 }
-                // :set - forward to Neovim (e.g., :set filetype?, :set number)
-                // User-defined commands (start with uppercase) are also handled by Neovim
+                // :setlocal - wrap/number/list are per-script CodeEdit options
+                else if cmd == "setlocal"
+                    || cmd == "setl"
+                    || cmd.starts_with("setlocal ")
+                    || cmd.starts_with("setl ")
+                {
+                    let args = cmd.split_once(' ').map_or("", |(_, args)| args);
+                    self.cmd_setlocal(args);
+                }
+                // :set - query Neovim options, change the safe subset (e.g., :set ic?, :set so=5)
                 else if cmd == "set"
+                    || cmd == "se"
                     || cmd.starts_with("set ")
-                    || cmd.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+                    || cmd.starts_with("se ")
                 {
+                    let args = cmd.split_once(' ').map_or("", |(_, args)| args);
+                    self.cmd_set(args);
+                }
+                // :run [scene|{file}], :mak[e] - play the project, :stop - stop it
+                else if let Some(arg) = Self::ex_command_arg(cmd, "run", 3) {
+                    self.cmd_run(arg);
+                } else if Self::ex_command_arg(cmd, "make", 3) == Some("") {
+                    self.cmd_run("");
+                } else if Self::ex_command_arg(cmd, "stop", 4) == Some("") {
+                    self.cmd_stop();
+                }
+                // :PluginReloadConfig - restart Neovim with the current config settings
+                else if cmd == "PluginReloadConfig" {
+                    self.cmd_reload_config();
+                }
+                // User-defined commands (start with uppercase) are handled by Neovim
+                else if cmd.chars().next().is_some_and(|c| c.is_ascii_uppercase()) {
                     self.cmd_forward_to_neovim(cmd);
                 } else {
                     godot_warn!("[godot-neovim] Unknown command: {}", cmd);
@@ -261,5 +541,40 @@ impl GodotNeovimPlugin {
         }
 
         self.close_command_line();
+
+        // Show command output (e.g., :set ic?) after the command line is closed
+        if let Some(output) = self.command_output.take() {
+            self.show_status_message(&output);
+        }
+
+        // Ex commands can change the search pattern or 'hlsearch' state (:s, :noh, :set hls)
+        self.refresh_hlsearch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GodotNeovimPlugin;
+
+    #[test]
+    fn test_format_command_range() {
+        assert_eq!(GodotNeovimPlugin::format_command_range("format"), Some(""));
+        assert_eq!(
+            GodotNeovimPlugin::format_command_range("'<,'>format"),
+            Some("'<,'>")
+        );
+        assert_eq!(
+            GodotNeovimPlugin::format_command_range("10,20 format"),
+            Some("10,20")
+        );
+        assert_eq!(
+            GodotNeovimPlugin::format_command_range("%s/fmt/format"),
+            None
+        );
+        assert_eq!(
+            GodotNeovimPlugin::format_command_range("%s/a/b/format"),
+            None
+        );
+        assert_eq!(GodotNeovimPlugin::format_command_range("format x"), None);
     }
 }
@@ -0,0 +1,128 @@
+//! Substitute preview ('inccommand'): live preview of :s while it is typed
+//!
+//! Neovim computes the substituted lines without modifying its buffer. The
+//! preview only edits the Godot CodeEdit and keeps the original lines, which are
+//! always restored before the command is executed or cancelled, so the real
+//! change still arrives from Neovim via buffer events (Neovim Master design).
+
+use super::super::highlight::HighlightRange;
+use super::super::GodotNeovimPlugin;
+
+/// Maximum number of lines changed by a preview
+const MAX_PREVIEW_LINES: i64 = 1000;
+
+/// State of an active substitute preview
+#[derive(Debug, Default)]
+pub(in crate::plugin) struct SubstitutePreview {
+    /// Original text of the lines replaced in the CodeEdit
+    original_lines: Vec<(i32, String)>,
+    /// Whether the editor was at its saved version before the preview
+    was_saved: bool,
+}
+
+impl GodotNeovimPlugin {
+    /// Update the preview for the current command line (called after each edit)
+    pub(in crate::plugin) fn update_substitute_preview(&mut self) {
+        let cmd = self
+            .command_buffer
+            .strip_prefix(':')
+            .unwrap_or("")
+            .to_string();
+
+        let preview = if cmd.trim().is_empty() {
+            None
+        } else {
+            let Some(neovim) = self.get_current_neovim() else {
+                return;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                crate::verbose_print!("[godot-neovim] Mutex busy, skipping substitute preview");
+                return;
+            };
+            match client.substitute_preview(&cmd, MAX_PREVIEW_LINES) {
+                Ok(preview) => preview,
+                Err(e) => {
+                    crate::verbose_print!("[godot-neovim] Substitute preview failed: {}", e);
+                    None
+                }
+            }
+        };
+
+        let had_preview = self.restore_substitute_preview();
+        let Some(lines) = preview else {
+            // No longer a :substitute command - back to the 'hlsearch' matches
+            if had_preview {
+                self.refresh_hlsearch();
+            }
+            return;
+        };
+
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        let mut state = SubstitutePreview {
+            original_lines: Vec::new(),
+            was_saved: editor.get_version() == editor.get_saved_version(),
+        };
+        let mut ranges = Vec::new();
+        let mut new_lines = Vec::new();
+        for (line, text, start_col, end_col) in lines {
+            let line = line as i32;
+            if line < 0 || line >= editor.get_line_count() {
+                continue;
+            }
+            let current = editor.get_line(line).to_string();
+            if current != text && !state.original_lines.iter().any(|(l, _)| *l == line) {
+                state.original_lines.push((line, current));
+                new_lines.push((line, text));
+            }
+            ranges.push(HighlightRange {
+                line,
+                start_col: start_col as i32,
+                end_col: end_col as i32,
+            });
+        }
+
+        self.set_preview_lines(&new_lines);
+        self.substitute_preview = Some(state);
+        self.set_search_highlights(ranges);
+    }
+
+    /// Restore the lines changed by the preview. Returns true if a preview was active.
+    /// Highlights are left to the caller (refresh_hlsearch).
+    pub(in crate::plugin) fn restore_substitute_preview(&mut self) -> bool {
+        let Some(state) = self.substitute_preview.take() else {
+            return false;
+        };
+        self.set_preview_lines(&state.original_lines);
+
+        // The preview round trip leaves the text unchanged - keep the saved state
+        if state.was_saved && !state.original_lines.is_empty() {
+            if let Some(ref mut editor) = self.current_editor {
+                editor.tag_saved_version();
+            }
+        }
+        true
+    }
+
+    /// Replace lines in the CodeEdit only (not synced to Neovim), keeping the caret
+    fn set_preview_lines(&mut self, lines: &[(i32, String)]) {
+        if lines.is_empty() {
+            return;
+        }
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+
+        self.syncing_from_grid = true;
+        let caret = (editor.get_caret_line(), editor.get_caret_column());
+        for (line, text) in lines {
+            if *line < editor.get_line_count() {
+                editor.set_line(*line, text);
+            }
+        }
+        editor.set_caret_line(caret.0);
+        editor.set_caret_column(caret.1);
+        self.syncing_from_grid = false;
+    }
+}
@@ -0,0 +1,76 @@
+//! Running the project: :run, :run scene, :run {scene}, :make, :stop
+
+use super::super::GodotNeovimPlugin;
+use godot::classes::{EditorInterface, ResourceLoader};
+use godot::prelude::*;
+
+impl GodotNeovimPlugin {
+    /// :run - Play the main scene, :run scene (or :run %) the scene being edited,
+    /// :run {file} a scene file (res:// or relative to the project)
+    pub(in crate::plugin) fn cmd_run(&mut self, arg: &str) {
+        let mut editor = EditorInterface::singleton();
+        match arg {
+            "" => {
+                editor.play_main_scene();
+                crate::verbose_print!("[godot-neovim] :run - Playing the main scene");
+            }
+            "scene" | "%" => {
+                let scene = editor
+                    .get_edited_scene_root()
+                    .map(|root| root.get_scene_file_path().to_string())
+                    .unwrap_or_default();
+                if scene.is_empty() {
+                    self.command_output = Some(":run scene - No scene is being edited".to_string());
+                    return;
+                }
+                editor.play_current_scene();
+                crate::verbose_print!("[godot-neovim] :run scene - Playing {}", scene);
+            }
+            _ => {
+                let path = if arg.starts_with("res://") {
+                    arg.to_string()
+                } else {
+                    format!("res://{}", arg.trim_start_matches("./"))
+                };
+                if !path.ends_with(".tscn") && !path.ends_with(".scn") {
+                    self.command_output = Some(format!("E475: Invalid argument: {}", arg));
+                    return;
+                }
+                if !ResourceLoader::singleton().exists(&path) {
+                    self.command_output = Some(format!("E484: Can't open file {}", path));
+                    return;
+                }
+                editor.play_custom_scene(&path);
+                crate::verbose_print!("[godot-neovim] :run - Playing {}", path);
+            }
+        }
+    }
+
+    /// :stop - Stop the running project
+    pub(in crate::plugin) fn cmd_stop(&mut self) {
+        let mut editor = EditorInterface::singleton();
+        if !editor.is_playing_scene() {
+            self.command_output = Some(":stop - Project is not running".to_string());
+            return;
+        }
+        editor.stop_playing_scene();
+        crate::verbose_print!("[godot-neovim] :stop - Stopped the project");
+    }
+
+    /// Refresh the mode label when the project starts or stops (called every frame)
+    pub(in crate::plugin) fn poll_run_state(&mut self) {
+        let running = EditorInterface::singleton().is_playing_scene();
+        if running == self.project_running {
+            return;
+        }
+        self.project_running = running;
+        crate::verbose_print!("[godot-neovim] Project running: {}", running);
+
+        // The command line and :version use the label for other text
+        if self.command_mode || self.search_mode || self.show_version {
+            return;
+        }
+        let display_cursor = (self.current_cursor.0 + 1, self.current_cursor.1);
+        self.update_mode_display_with_cursor(&self.current_mode.clone(), Some(display_cursor));
+    }
+}
@@ -0,0 +1,66 @@
+//! Comment toggle operator (gc{motion}, gcc, Visual gc)
+//!
+//! `gc` makes Neovim's `g@` the comment operator (comment.lua) and hands it the
+//! motion, so the toggle is a single undo step synced back through buffer events
+//! and `.` repeats it.
+
+use super::text_object::is_text_object_prefix;
+use super::{EditorType, GodotNeovimPlugin};
+
+impl GodotNeovimPlugin {
+    /// Line comment leader of the current file
+    fn comment_leader(&self) -> &'static str {
+        match self.current_editor_type {
+            EditorType::Shader => "//",
+            _ => "#",
+        }
+    }
+
+    /// Keys that make `g@` the comment operator
+    fn comment_operator_keys(&self) -> String {
+        format!(
+            "<Cmd>lua require('godot_neovim').comment_operator('{}')<CR>",
+            self.comment_leader()
+        )
+    }
+
+    /// `gc` typed: toggle the Visual selection, or wait for the motion (`c`: line)
+    pub(super) fn start_comment_operator(&mut self) {
+        if !self.is_in_visual_mode() {
+            self.set_last_key("gc");
+            return;
+        }
+
+        self.clear_last_key();
+        self.take_count();
+        let keys = format!("{}g@", self.comment_operator_keys());
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(keys.clone());
+        }
+        self.send_keys(&keys);
+    }
+
+    /// Key after `gc`: `c` toggles the current line(s), anything else starts the
+    /// motion (the rest of it is forwarded to Neovim as usual)
+    pub(super) fn finish_comment_operator(&mut self, key: &str) {
+        self.clear_last_key();
+        // The count goes to g@, after the <Cmd> that sets it up
+        let count = self
+            .take_count()
+            .map(|count| count.to_string())
+            .unwrap_or_default();
+        if is_text_object_prefix(key) {
+            let operator = format!("{}g@", self.comment_operator_keys());
+            self.start_text_object(operator, count, key);
+            return;
+        }
+        let motion = if key == "c" { "_" } else { key };
+        let keys = format!("{}{}g@{}", self.comment_operator_keys(), count, motion);
+        crate::verbose_print!("[godot-neovim] gc{}", key);
+
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(keys.clone());
+        }
+        self.send_keys(&keys);
+    }
+}
@@ -0,0 +1,293 @@
+//! Go to definition (gd) and go to declaration (gD)
+//!
+//! gd tries, in order: Godot's LSP, the tags files of Neovim's 'tags' option, and
+//! Godot's own symbol lookup (what Ctrl+Click runs, which also opens the class
+//! reference for engine symbols). The symbol lookup gives no result back, so the
+//! caret is checked a moment later: if nothing moved, the symbol is reported as
+//! not found together with the reason the LSP could not be used.
+//!
+//! gD stays in the current file: the first declaration of the word (`var`,
+//! `func`, `const`, ...), else its first occurrence outside a comment.
+
+use super::GodotNeovimPlugin;
+use godot::classes::CodeEdit;
+use godot::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Time to wait for Godot's symbol lookup before reporting that nothing was found
+const SYMBOL_LOOKUP_WAIT: Duration = Duration::from_millis(300);
+
+/// Keywords of GDScript declarations (the name follows the keyword)
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "var",
+    "const",
+    "func",
+    "signal",
+    "enum",
+    "class",
+    "class_name",
+    "static var",
+    "static func",
+];
+
+/// Godot's symbol lookup started by gd, checked once SYMBOL_LOOKUP_WAIT has passed
+pub(super) struct SymbolLookup {
+    /// CodeEdit the lookup started in
+    editor: Gd<CodeEdit>,
+    /// Caret (line, column) when the lookup started
+    caret: (i32, i32),
+    /// The symbol
+    word: String,
+    /// Why the LSP was not used (None: it found nothing)
+    lsp_error: Option<String>,
+    started: Instant,
+}
+
+/// Whether `c` can be part of an identifier
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Character column of `word` as a whole word in `text` (from `from`)
+fn find_word(text: &str, word: &str, from: usize) -> Option<usize> {
+    let chars: Vec<char> = text.chars().collect();
+    let word: Vec<char> = word.chars().collect();
+    (from..chars.len()).find(|&col| {
+        chars[col..].starts_with(&word)
+            && (col == 0 || !is_word_char(chars[col - 1]))
+            && chars
+                .get(col + word.len())
+                .is_none_or(|&c| !is_word_char(c))
+    })
+}
+
+/// Column where a `#` comment starts in a line (not inside a string)
+fn comment_start(text: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (col, c) in text.chars().enumerate() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return Some(col),
+            None => {}
+        }
+    }
+    None
+}
+
+/// (line, column) of the declaration of `word` for gD: the first line declaring
+/// it, else its first occurrence outside a comment
+fn find_declaration(lines: &[String], word: &str) -> Option<(usize, usize)> {
+    let code = |text: &str| -> String {
+        let end = comment_start(text).unwrap_or(usize::MAX);
+        text.chars().take(end).collect()
+    };
+
+    for (line, text) in lines.iter().enumerate() {
+        let code = code(text);
+        let trimmed = code.trim_start();
+        let indent = code.chars().count() - trimmed.chars().count();
+        let trimmed = trimmed.strip_prefix('@').map_or(trimmed, |annotated| {
+            // @export var x, @onready var y
+            annotated
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, rest)| rest.trim_start())
+        });
+        for keyword in DECLARATION_KEYWORDS {
+            let Some(rest) = trimmed.strip_prefix(keyword) else {
+                continue;
+            };
+            if !rest.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let name = rest.trim_start();
+            let name_end = name.find(|c: char| !is_word_char(c)).unwrap_or(name.len());
+            if &name[..name_end] == word {
+                let col = find_word(&code, word, indent)?;
+                return Some((line, col));
+            }
+        }
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .find_map(|(line, text)| find_word(&code(text), word, 0).map(|col| (line, col)))
+}
+
+impl GodotNeovimPlugin {
+    /// gd - Go to the definition: LSP, then tags, then Godot's symbol lookup
+    pub(super) fn go_to_definition(&mut self) {
+        let lsp_error = match self.go_to_definition_lsp() {
+            Ok(true) => return,
+            Ok(false) => None,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] gd: LSP not used: {}", e);
+                Some(e)
+            }
+        };
+
+        let Some(word) = self.word_under_cursor() else {
+            self.show_status_message("gd: No symbol under cursor");
+            return;
+        };
+
+        if self.go_to_tag(&word) {
+            return;
+        }
+        self.start_symbol_lookup(word, lsp_error);
+    }
+
+    /// Jump to the tag of `word` in Neovim's tags files
+    fn go_to_tag(&mut self, word: &str) -> bool {
+        let tag = {
+            let Some(neovim) = self.get_current_neovim() else {
+                return false;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                return false;
+            };
+            client.find_tag(word)
+        };
+        match tag {
+            Ok(Some(tag)) => {
+                crate::verbose_print!(
+                    "[godot-neovim] gd: Tag {} at {}:{}",
+                    word,
+                    tag.file,
+                    tag.line
+                );
+                self.open_location(&tag.file, (tag.line - 1) as i32, tag.col as i32);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] gd: Tag lookup failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Run Godot's symbol lookup (Ctrl+Click) on the word under the caret
+    fn start_symbol_lookup(&mut self, word: String, lsp_error: Option<String>) {
+        let Some(mut editor) = self.current_editor.clone() else {
+            return;
+        };
+        let caret = (editor.get_caret_line(), editor.get_caret_column());
+
+        // Deferred: the lookup can switch scripts, which calls back into the plugin
+        editor.call_deferred(
+            "emit_signal",
+            &[
+                "symbol_lookup".to_variant(),
+                word.to_variant(),
+                caret.0.to_variant(),
+                caret.1.to_variant(),
+            ],
+        );
+        crate::verbose_print!("[godot-neovim] gd: Godot symbol lookup: {}", word);
+
+        self.symbol_lookup = Some(SymbolLookup {
+            editor,
+            caret,
+            word,
+            lsp_error,
+            started: Instant::now(),
+        });
+    }
+
+    /// Report a symbol lookup that went nowhere (called every frame)
+    pub(super) fn poll_symbol_lookup(&mut self) {
+        let Some(started) = self.symbol_lookup.as_ref().map(|lookup| lookup.started) else {
+            return;
+        };
+        if started.elapsed() < SYMBOL_LOOKUP_WAIT {
+            return;
+        }
+        let Some(lookup) = self.symbol_lookup.take() else {
+            return;
+        };
+        if !lookup.editor.is_instance_valid() {
+            return;
+        }
+
+        // A jump moves the caret or shows another script (or the class reference)
+        let still_shown = self
+            .get_script_editor_code_edit_via_api()
+            .is_some_and(|shown| shown == lookup.editor);
+        let caret = (
+            lookup.editor.get_caret_line(),
+            lookup.editor.get_caret_column(),
+        );
+        if !still_shown || caret != lookup.caret {
+            return;
+        }
+
+        let message = match lookup.lsp_error {
+            Some(e) => format!("gd: '{}' not found (LSP: {})", lookup.word, e),
+            None => format!("gd: '{}' not found", lookup.word),
+        };
+        self.show_status_message(&message);
+    }
+
+    /// gD - Go to the declaration of the word under the cursor in the current file
+    pub(super) fn go_to_declaration(&mut self) {
+        let Some(word) = self.word_under_cursor() else {
+            self.show_status_message("gD: No symbol under cursor");
+            return;
+        };
+        let Some(editor) = self.current_editor.as_ref() else {
+            return;
+        };
+        let lines: Vec<String> = (0..editor.get_line_count())
+            .map(|line| editor.get_line(line).to_string())
+            .collect();
+
+        let Some((line, col)) = find_declaration(&lines, &word) else {
+            self.show_status_message(&format!("gD: '{}' not found", word));
+            return;
+        };
+        crate::verbose_print!("[godot-neovim] gD: {} at {}:{}", word, line + 1, col);
+        let path = self.current_script_abs_path();
+        self.jump_to_location(&path, line as i32, col as i32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_declaration() {
+        let lines: Vec<String> = [
+            "# speed is in pixels per second",
+            "extends Node2D",
+            "",
+            "@export var speed := 200.0",
+            "var speed_scale = 1.0",
+            "",
+            "func move(delta):",
+            "\tposition.x += speed * delta",
+            "\tvar step = \"#speed\"",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        assert_eq!(find_declaration(&lines, "speed"), Some((3, 12)));
+        assert_eq!(find_declaration(&lines, "move"), Some((6, 5)));
+        assert_eq!(find_declaration(&lines, "step"), Some((8, 5)));
+        // Not declared in the file: the first use outside a comment
+        assert_eq!(find_declaration(&lines, "position"), Some((7, 1)));
+        assert_eq!(find_declaration(&lines, "missing"), None);
+    }
+}
@@ -0,0 +1,573 @@
+//! LSP diagnostics: gutter icons, end-of-line virtual text, ]d/[d and the
+//! diagnostic float (`<leader>e`)
+//!
+//! Godot's LSP publishes the errors and warnings of a script after it receives the
+//! script's text (textDocument/publishDiagnostics). The text is sent whenever the
+//! buffer changed, except in Insert mode (like Neovim's `update_in_insert = false`)
+//! and in large-file mode, and the connection is polled for published diagnostics a few times a second.
+//! The LSP is connected quietly: without 'Use Thread' or a running server, nothing
+//! is shown and the connection is retried now and then.
+//!
+//! Icons are drawn by a custom gutter (added only while the script has
+//! diagnostics, like `signcolumn=auto`) and the messages by a Control over the
+//! CodeEdit, as for the search highlights.
+
+use super::editor::gutter_index;
+use super::float::FloatPlacement;
+use super::{EditorType, GodotNeovimPlugin};
+use crate::lsp::GodotLspClient;
+use crate::settings;
+use godot::classes::control::{LayoutPreset, MouseFilter};
+use godot::classes::text_edit::GutterType;
+use godot::classes::{CodeEdit, Control, EditorInterface, Texture2D};
+use godot::prelude::*;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use std::time::{Duration, Instant};
+
+/// Time between two polls of the LSP connection
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Time before connecting to the LSP again after a failed attempt
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Name of the diagnostics gutter
+const GUTTER_NAME: &str = "godot_neovim_diagnostics";
+
+/// Name of CodeEdit's breakpoint/bookmark gutter (the diagnostics one follows it)
+const MAIN_GUTTER: &str = "main_gutter";
+
+/// Prefix of the virtual text (like Neovim's default)
+const VIRTUAL_TEXT_PREFIX: &str = "■ ";
+
+/// Space between the end of a line and its virtual text, in characters
+const VIRTUAL_TEXT_GAP: f32 = 2.0;
+
+/// A diagnostic of the current script
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct LineDiagnostic {
+    /// Start of the range (0-based line and column)
+    line: i32,
+    col: i32,
+    severity: DiagnosticSeverity,
+    message: String,
+    /// Who reported it and its code (e.g. "gdscript(UNUSED_VARIABLE)")
+    source: Option<String>,
+}
+
+/// Connection and sync state of the diagnostics polling
+#[derive(Default)]
+pub(super) struct DiagnosticsState {
+    /// Last poll of the LSP connection
+    polled: Option<Instant>,
+    /// Script and changedtick whose text was sent last
+    sent: Option<(String, i64)>,
+    /// Last failed connection attempt
+    connect_failed: Option<Instant>,
+}
+
+/// Order of severities, most severe first (a missing severity counts as an error)
+fn severity_rank(severity: DiagnosticSeverity) -> u8 {
+    match severity {
+        DiagnosticSeverity::ERROR => 0,
+        DiagnosticSeverity::WARNING => 1,
+        DiagnosticSeverity::INFORMATION => 2,
+        _ => 3,
+    }
+}
+
+fn severity_label(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "Error",
+        DiagnosticSeverity::WARNING => "Warning",
+        DiagnosticSeverity::INFORMATION => "Info",
+        _ => "Hint",
+    }
+}
+
+/// Color of a severity from the editor theme
+fn severity_color(severity: DiagnosticSeverity) -> Color {
+    let name = match severity {
+        DiagnosticSeverity::ERROR => "error_color",
+        DiagnosticSeverity::WARNING => "warning_color",
+        _ => "font_placeholder_color",
+    };
+    EditorInterface::singleton()
+        .get_editor_theme()
+        .map(|theme| theme.get_color(name, "Editor"))
+        .unwrap_or(Color::GRAY)
+}
+
+/// Gutter icon of a severity from the editor theme
+fn severity_icon(severity: DiagnosticSeverity) -> Option<Gd<Texture2D>> {
+    let name = match severity {
+        DiagnosticSeverity::ERROR => "StatusError",
+        DiagnosticSeverity::WARNING => "StatusWarning",
+        _ => "NodeInfo",
+    };
+    EditorInterface::singleton()
+        .get_editor_theme()?
+        .get_icon(name, "EditorIcons")
+}
+
+/// Diagnostics of a publishDiagnostics notification, ordered by position
+fn line_diagnostics(diagnostics: Vec<Diagnostic>) -> Vec<LineDiagnostic> {
+    let mut lines: Vec<LineDiagnostic> = diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let code = diagnostic.code.map(|code| match code {
+                NumberOrString::Number(number) => number.to_string(),
+                NumberOrString::String(text) => text,
+            });
+            let source = match (diagnostic.source, code) {
+                (Some(source), Some(code)) => Some(format!("{}({})", source, code)),
+                (source, code) => source.or(code),
+            };
+            LineDiagnostic {
+                line: diagnostic.range.start.line as i32,
+                col: diagnostic.range.start.character as i32,
+                severity: diagnostic.severity.unwrap_or(DiagnosticSeverity::ERROR),
+                message: diagnostic.message.trim().to_string(),
+                source,
+            }
+        })
+        .collect();
+    lines.sort_by_key(|diagnostic| {
+        (
+            diagnostic.line,
+            diagnostic.col,
+            severity_rank(diagnostic.severity),
+        )
+    });
+    lines
+}
+
+/// Most severe diagnostic of a line
+fn worst_on_line(diagnostics: &[LineDiagnostic], line: i32) -> Option<&LineDiagnostic> {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.line == line)
+        .min_by_key(|diagnostic| severity_rank(diagnostic.severity))
+}
+
+/// Index of the `count`th diagnostic after (or before) the caret, wrapping around
+/// the end of the script like vim.diagnostic.jump()
+fn step_diagnostic(
+    diagnostics: &[LineDiagnostic],
+    caret: (i32, i32),
+    forward: bool,
+    count: i32,
+) -> Option<usize> {
+    if diagnostics.is_empty() {
+        return None;
+    }
+    let len = diagnostics.len() as i64;
+    // Diagnostics strictly before the caret
+    let before = diagnostics.partition_point(|d| (d.line, d.col) < caret) as i64;
+    let at_caret = diagnostics
+        .get(before as usize)
+        .is_some_and(|d| (d.line, d.col) == caret) as i64;
+    let count = count.max(1) as i64;
+    let index = if forward {
+        before + at_caret + count - 1
+    } else {
+        before - count
+    };
+    Some(index.rem_euclid(len) as usize)
+}
+
+impl GodotNeovimPlugin {
+    /// Send the script's text to the LSP when it changed and pick up the
+    /// diagnostics it published (called every frame, polls every POLL_INTERVAL)
+    pub(super) fn poll_diagnostics(&mut self) {
+        if self
+            .diagnostics_state
+            .polled
+            .is_some_and(|polled| polled.elapsed() < POLL_INTERVAL)
+        {
+            return;
+        }
+        self.diagnostics_state.polled = Some(Instant::now());
+
+        if !settings::get_diagnostics() {
+            self.set_diagnostics(Vec::new());
+            return;
+        }
+        let Some(lsp) = self.godot_lsp.clone() else {
+            return;
+        };
+        if self.current_editor_type != EditorType::Script || self.current_script_path.is_empty() {
+            return;
+        }
+        if !self.connect_lsp_quietly(&lsp) {
+            return;
+        }
+
+        let abs_path = self.current_script_abs_path();
+        let uri = Self::path_to_uri(&abs_path);
+        let sync_key = (
+            self.current_script_path.clone(),
+            self.sync_manager.get_changedtick(),
+        );
+        // Not in large-file mode: every change would send the whole text again
+        let typing = self.is_insert_mode() || self.is_replace_mode();
+        if !typing
+            && !self.is_large_file()
+            && self.diagnostics_state.sent.as_ref() != Some(&sync_key)
+        {
+            let text = self
+                .current_editor
+                .as_ref()
+                .map(|editor| editor.get_text().to_string())
+                .unwrap_or_default();
+            if let Err(e) = lsp.did_open(&uri, &text) {
+                crate::verbose_print!("[godot-neovim] Diagnostics: didOpen failed: {}", e);
+            }
+            self.diagnostics_state.sent = Some(sync_key);
+        }
+
+        if let Err(e) = lsp.poll_notifications() {
+            crate::verbose_print!("[godot-neovim] Diagnostics: {}", e);
+            lsp.disconnect();
+            self.diagnostics_state.sent = None;
+            return;
+        }
+
+        let current = abs_path.replace('\\', "/");
+        let published = lsp
+            .diagnostics()
+            .into_iter()
+            .find(|(uri, _)| Self::uri_to_file_path(uri).replace('\\', "/") == current)
+            .map(|(_, diagnostics)| line_diagnostics(diagnostics))
+            .unwrap_or_default();
+        self.set_diagnostics(published);
+    }
+
+    /// Connect and initialize the LSP without status messages
+    /// A failed attempt is retried after RECONNECT_INTERVAL
+    fn connect_lsp_quietly(&mut self, lsp: &GodotLspClient) -> bool {
+        if lsp.is_connected() && lsp.is_initialized() {
+            return true;
+        }
+        if self
+            .diagnostics_state
+            .connect_failed
+            .is_some_and(|failed| failed.elapsed() < RECONNECT_INTERVAL)
+        {
+            return false;
+        }
+
+        let result = if lsp.is_connected() {
+            Ok(())
+        } else {
+            lsp.connect(6005)
+        }
+        .and_then(|_| lsp.initialize(&Self::project_root_uri()));
+        if let Err(e) = result {
+            crate::verbose_print!("[godot-neovim] Diagnostics: LSP not available: {}", e);
+            self.diagnostics_state.connect_failed = Some(Instant::now());
+            return false;
+        }
+        self.diagnostics_state.connect_failed = None;
+        self.diagnostics_state.sent = None;
+        crate::verbose_print!("[godot-neovim] Diagnostics: Connected to LSP");
+        true
+    }
+
+    /// Replace the diagnostics of the current script and redraw them
+    fn set_diagnostics(&mut self, diagnostics: Vec<LineDiagnostic>) {
+        if diagnostics == self.diagnostics {
+            return;
+        }
+        crate::verbose_print!(
+            "[godot-neovim] Diagnostics: {} in {}",
+            diagnostics.len(),
+            self.current_script_path
+        );
+        self.diagnostics = diagnostics;
+        self.render_diagnostics();
+    }
+
+    /// Add or remove the gutter and the virtual text overlay of the current editor
+    fn render_diagnostics(&mut self) {
+        let Some(mut editor) = self.current_editor.clone() else {
+            return;
+        };
+        if !editor.is_instance_valid() {
+            return;
+        }
+
+        if self.diagnostics.is_empty() {
+            if let Some(index) = gutter_index(&editor, GUTTER_NAME) {
+                editor.remove_gutter(index);
+            }
+            self.free_diagnostics_overlay();
+            return;
+        }
+
+        if gutter_index(&editor, GUTTER_NAME).is_none() {
+            let index = gutter_index(&editor, MAIN_GUTTER).map_or(0, |main| main + 1);
+            editor.add_gutter_ex().at(index).done();
+            editor.set_gutter_name(index, GUTTER_NAME);
+            editor.set_gutter_type(index, GutterType::CUSTOM);
+            let width = editor.get_line_height();
+            editor.set_gutter_width(index, width);
+            let callable = self
+                .base()
+                .callable("on_diagnostics_gutter_draw")
+                .bind(&[editor.to_variant()]);
+            editor.set_gutter_custom_draw(index, &callable);
+        }
+        editor.queue_redraw();
+
+        let overlay_valid = self.diagnostics_overlay.as_ref().is_some_and(|overlay| {
+            overlay.is_instance_valid()
+                && overlay
+                    .get_parent()
+                    .is_some_and(|parent| parent.instance_id() == editor.instance_id())
+        });
+        if !overlay_valid {
+            self.free_diagnostics_overlay();
+            let mut overlay = Control::new_alloc();
+            overlay.set_name("GodotNeovimDiagnosticsOverlay");
+            overlay.set_mouse_filter(MouseFilter::IGNORE);
+            overlay.set_clip_contents(true);
+            overlay.set_anchors_and_offsets_preset(LayoutPreset::FULL_RECT);
+            overlay.connect("draw", &self.base().callable("on_diagnostics_overlay_draw"));
+            editor.add_child(&overlay);
+
+            // Redraw the virtual text whenever the editor redraws (scroll, edits, resize)
+            let editor_draw = self.base().callable("on_diagnostics_editor_draw");
+            if !editor.is_connected("draw", &editor_draw) {
+                editor.connect("draw", &editor_draw);
+            }
+            self.diagnostics_overlay = Some(overlay);
+        }
+        self.redraw_diagnostics_overlay();
+    }
+
+    fn free_diagnostics_overlay(&mut self) {
+        if let Some(mut overlay) = self.diagnostics_overlay.take() {
+            if overlay.is_instance_valid() {
+                overlay.queue_free();
+            }
+        }
+    }
+
+    /// Request a redraw of the virtual text (the CodeEdit redrew)
+    pub(super) fn redraw_diagnostics_overlay(&mut self) {
+        if let Some(ref mut overlay) = self.diagnostics_overlay {
+            if overlay.is_instance_valid() {
+                overlay.queue_redraw();
+            }
+        }
+    }
+
+    /// Remove the diagnostics of the current editor (script change, deactivation)
+    /// The next poll shows the ones of the new script.
+    pub(super) fn clear_diagnostics_view(&mut self) {
+        self.diagnostics.clear();
+        self.render_diagnostics();
+        self.diagnostics_state.polled = None;
+    }
+
+    /// Draw the icon of one line (custom draw callback of the gutter)
+    pub(super) fn draw_diagnostics_gutter(
+        &self,
+        editor: &mut Gd<CodeEdit>,
+        line: i32,
+        rect: Rect2,
+    ) {
+        // The gutter of a script that is no longer current is removed on the switch
+        if self.current_editor.as_ref() != Some(editor) {
+            return;
+        }
+        let Some(diagnostic) = worst_on_line(&self.diagnostics, line) else {
+            return;
+        };
+        let Some(icon) = severity_icon(diagnostic.severity) else {
+            return;
+        };
+        let size = rect.size.x.min(rect.size.y) * 0.75;
+        let icon_rect = Rect2::new(
+            rect.position + (rect.size - Vector2::new(size, size)) / 2.0,
+            Vector2::new(size, size),
+        );
+        editor.draw_texture_rect(&icon, icon_rect, false);
+    }
+
+    /// Draw the message of each visible line after its text (overlay's draw signal)
+    pub(super) fn draw_diagnostics_overlay(&mut self) {
+        let Some(overlay) = self.diagnostics_overlay.clone() else {
+            return;
+        };
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        if !overlay.is_instance_valid() || !editor.is_instance_valid() {
+            return;
+        }
+        let Some(font) = editor.get_theme_font("font") else {
+            return;
+        };
+        let font_size = editor.get_theme_font_size("font_size");
+        let ascent = font.get_ascent_ex().font_size(font_size).done();
+        let height = font.get_height_ex().font_size(font_size).done();
+        let gap = font.get_string_size_ex(" ").font_size(font_size).done().x * VIRTUAL_TEXT_GAP;
+
+        let first_line = editor.get_first_visible_line();
+        let last_line = editor.get_last_full_visible_line() + 1;
+        let mut line = None;
+        for diagnostic in &self.diagnostics {
+            if diagnostic.line < first_line || diagnostic.line > last_line {
+                continue;
+            }
+            // One message per line: the most severe one
+            if line == Some(diagnostic.line) {
+                continue;
+            }
+            line = Some(diagnostic.line);
+            let Some(shown) = worst_on_line(&self.diagnostics, diagnostic.line) else {
+                continue;
+            };
+
+            let line_len = editor.get_line(shown.line).len() as i32;
+            let end = editor.get_rect_at_line_column(shown.line, line_len);
+            if end.position.x < 0 || end.position.y < 0 {
+                continue;
+            }
+            let first_line_of_message = shown.message.lines().next().unwrap_or_default();
+            let text = format!("{}{}", VIRTUAL_TEXT_PREFIX, first_line_of_message);
+            let position = Vector2::new(
+                end.position.x as f32 + gap,
+                end.position.y as f32 + (end.size.y as f32 - height) / 2.0 + ascent,
+            );
+            let mut color = severity_color(shown.severity);
+            color.a = 0.8;
+            overlay
+                .draw_string_ex(&font, position, &text)
+                .font_size(font_size)
+                .modulate(color)
+                .done();
+        }
+    }
+
+    /// ]d / [d - Move to the next / previous diagnostic (wraps around)
+    pub(super) fn move_to_diagnostic(&mut self, forward: bool) {
+        let count = self.get_and_clear_count();
+        let Some(editor) = self.current_editor.as_ref() else {
+            return;
+        };
+        let caret = (editor.get_caret_line(), editor.get_caret_column());
+        let Some(index) = step_diagnostic(&self.diagnostics, caret, forward, count) else {
+            self.show_status_message("No diagnostics");
+            return;
+        };
+
+        let diagnostic = self.diagnostics[index].clone();
+        if let Some(ref mut editor) = self.current_editor {
+            editor.set_caret_line(diagnostic.line);
+            editor.set_caret_column(diagnostic.col);
+            editor.adjust_viewport_to_caret();
+        }
+        self.sync_cursor_to_neovim();
+        self.show_status_message(&format!(
+            "[{}/{}] {}: {}",
+            index + 1,
+            self.diagnostics.len(),
+            severity_label(diagnostic.severity),
+            diagnostic.message.lines().next().unwrap_or_default()
+        ));
+    }
+
+    /// Whether there are diagnostics on a line (0-based)
+    pub(super) fn has_line_diagnostics(&self, line: i32) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.line == line)
+    }
+
+    /// `<leader>e` - Show the full messages of the caret line in a float
+    pub(super) fn show_line_diagnostics(&mut self) {
+        let Some(line) = self.current_editor.as_ref().map(|e| e.get_caret_line()) else {
+            return;
+        };
+        let entries: Vec<String> = self
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.line == line)
+            .map(|diagnostic| {
+                let color = severity_color(diagnostic.severity).to_html();
+                let source = diagnostic
+                    .source
+                    .as_ref()
+                    .map(|source| {
+                        format!(" [color=#{}]{}[/color]", color, source.replace('[', "[lb]"))
+                    })
+                    .unwrap_or_default();
+                format!(
+                    "[color=#{}]{}[/color]: {}{}",
+                    color,
+                    severity_label(diagnostic.severity),
+                    diagnostic.message.replace('[', "[lb]"),
+                    source
+                )
+            })
+            .collect();
+        if entries.is_empty() {
+            self.show_status_message("No diagnostics on this line");
+            return;
+        }
+
+        self.close_hover_float();
+        self.hover_float =
+            self.create_editor_float(&entries.join("\n"), FloatPlacement::BelowCaret);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_diagnostic() {
+        let diagnostics: Vec<Diagnostic> = serde_json::from_value(serde_json::json!([
+            {
+                "range": { "start": { "line": 9, "character": 4 }, "end": { "line": 9, "character": 8 } },
+                "severity": 2,
+                "source": "gdscript",
+                "code": "UNUSED_VARIABLE",
+                "message": "The local variable \"x\" is declared but never used.",
+            },
+            {
+                "range": { "start": { "line": 2, "character": 0 }, "end": { "line": 2, "character": 5 } },
+                "severity": 1,
+                "message": "Identifier \"foo\" not declared in the current scope.",
+            },
+            {
+                "range": { "start": { "line": 9, "character": 0 }, "end": { "line": 9, "character": 1 } },
+                "message": "Unexpected indent.",
+            },
+        ]))
+        .unwrap();
+        let diagnostics = line_diagnostics(diagnostics);
+
+        let positions: Vec<(i32, i32)> = diagnostics.iter().map(|d| (d.line, d.col)).collect();
+        assert_eq!(positions, [(2, 0), (9, 0), (9, 4)]);
+        assert_eq!(
+            diagnostics[2].source.as_deref(),
+            Some("gdscript(UNUSED_VARIABLE)")
+        );
+        // No severity: an error, which wins on its line
+        assert_eq!(worst_on_line(&diagnostics, 9).map(|d| d.col), Some(0));
+
+        assert_eq!(step_diagnostic(&diagnostics, (0, 0), true, 1), Some(0));
+        // On a diagnostic: the next one
+        assert_eq!(step_diagnostic(&diagnostics, (2, 0), true, 1), Some(1));
+        assert_eq!(step_diagnostic(&diagnostics, (5, 0), true, 2), Some(2));
+        // Wraps around the end and the start of the script
+        assert_eq!(step_diagnostic(&diagnostics, (9, 4), true, 1), Some(0));
+        assert_eq!(step_diagnostic(&diagnostics, (2, 0), false, 1), Some(2));
+        assert_eq!(step_diagnostic(&diagnostics, (9, 2), false, 1), Some(1));
+        assert_eq!(step_diagnostic(&[], (0, 0), true, 1), None);
+    }
+}
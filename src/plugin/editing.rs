@@ -18,23 +18,23 @@ impl GodotNeovimPlugin {
         crate::verbose_print!("[godot-neovim] R: Entered replace mode");
     }
 
-    /// Go to definition using LSP (gd command)
+    /// Go to definition using LSP (first step of gd)
+    /// Returns whether the LSP found a definition (Err: the LSP is not available)
     ///
     /// Note: This uses Godot's built-in LSP (port 6005) instead of Neovim LSP.
     /// Rationale: Similar to vscode-neovim which uses IDE's LSP.
     /// - neovim_clean=true by default, so user's Neovim LSP config is not loaded
     /// - Godot LSP is always available without additional user configuration
     /// - File jumping across files is handled by Godot's editor
-    pub(super) fn go_to_definition_lsp(&mut self) {
+    pub(super) fn go_to_definition_lsp(&mut self) -> Result<bool, String> {
         use godot::classes::ProjectSettings;
 
         let Some(ref lsp) = self.godot_lsp else {
-            self.show_status_message("gd: Enable 'Use Thread' in Editor Settings");
-            return;
+            return Err("Enable 'Use Thread' in Editor Settings".to_string());
         };
 
         let Some(ref editor) = self.current_editor else {
-            return;
+            return Ok(false);
         };
 
         // Get current position and buffer content
@@ -79,8 +79,7 @@ impl GodotNeovimPlugin {
         // Ensure connected
         if !lsp.is_connected() {
             if let Err(e) = lsp.connect(6005) {
-                self.show_status_message(&format!("LSP connect failed: {}", e));
-                return;
+                return Err(format!("LSP connect failed: {}", e));
             }
             crate::verbose_print!("[godot-neovim] gd: Connected to LSP");
         }
@@ -88,8 +87,7 @@ impl GodotNeovimPlugin {
         // Ensure initialized
         if !lsp.is_initialized() {
             if let Err(e) = lsp.initialize(&root_uri) {
-                self.show_status_message(&format!("LSP init failed: {}", e));
-                return;
+                return Err(format!("LSP init failed: {}", e));
             }
             crate::verbose_print!("[godot-neovim] gd: LSP initialized");
         }
@@ -153,17 +151,22 @@ impl GodotNeovimPlugin {
                     );
 
                     // Queue file open with position
-                    self.pending_file_path = Some(res_path);
-                    // TODO: Also store line/col for after file opens
+                    self.pending_file_path = Some(res_path.clone());
+                    self.pending_file_cursor = Some((
+                        res_path,
+                        (target_line - 1).max(0) as i32,
+                        target_col.max(0) as i32,
+                    ));
                 }
+                Ok(true)
             }
             Ok(None) => {
                 crate::verbose_print!("[godot-neovim] gd: No definition found");
-                self.show_status_message("Definition not found");
+                Ok(false)
             }
             Err(e) => {
                 crate::verbose_print!("[godot-neovim] gd: LSP error: {}", e);
-                self.show_status_message(&format!("LSP error: {}", e));
+                Err(format!("LSP error: {}", e))
             }
         }
     }
@@ -456,7 +459,7 @@ impl GodotNeovimPlugin {
     /// Handles URL decoding and platform differences:
     /// - Unix: file:///path -> /path
     /// - Windows: file:///C:/path -> C:/path
-    fn uri_to_file_path(uri: &str) -> String {
+    pub(super) fn uri_to_file_path(uri: &str) -> String {
         // First, URL decode the entire URI to handle %3A etc.
         let decoded_uri = Self::url_decode(uri);
 
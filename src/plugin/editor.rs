@@ -4,6 +4,12 @@ use super::{EditorType, GodotNeovimPlugin};
 use godot::classes::{CodeEdit, Control, EditorInterface, Resource, ScriptEditorBase, Window};
 use godot::prelude::*;
 
+/// Index of the gutter named `name` (gutters added by the plugin are found by name,
+/// as Godot's own gutters and other plugins may shift their index)
+pub(super) fn gutter_index(editor: &Gd<CodeEdit>, name: &str) -> Option<i32> {
+    (0..editor.get_gutter_count()).find(|&i| editor.get_gutter_name(i) == name)
+}
+
 impl GodotNeovimPlugin {
     /// Check if current CodeEdit is in a float window and connect to gui_input signal
     pub(super) fn update_float_window_connection(&mut self) {
@@ -71,9 +77,18 @@ impl GodotNeovimPlugin {
             return;
         };
 
+        // Buffers are named by absolute path (on_script_close passes res:// paths)
+        let abs_path = if path.starts_with("res://") {
+            godot::classes::ProjectSettings::singleton()
+                .globalize_path(path)
+                .to_string()
+        } else {
+            path.to_string()
+        };
+
         // Use bwipeout to completely remove buffer (including undo history)
         // This matches vscode-neovim's behavior with force=true
-        let cmd = format!("silent! bwipeout! {}", path);
+        let cmd = format!("silent! bwipeout! {}", abs_path);
         if let Err(e) = client.command(&cmd) {
             crate::verbose_print!("[godot-neovim] Failed to delete buffer {}: {}", path, e);
         } else {
@@ -87,6 +102,10 @@ impl GodotNeovimPlugin {
 
     /// Trigger script change handling via deferred call
     pub(super) fn handle_script_changed(&mut self) {
+        // The carets and snippet fields belong to the editor being left
+        self.exit_multicursor();
+        self.snippet_session = None;
+
         // Increment switch ID and store as pending
         // This allows detecting and skipping stale deferred operations
         // when rapid tab switching occurs (ref: vscode-neovim commit 0520846)
@@ -201,7 +220,7 @@ impl GodotNeovimPlugin {
 
     /// Get CodeEdit from ScriptEditor using public API (stable method)
     /// Uses: ScriptEditor.get_current_editor() -> ScriptEditorBase.get_base_editor() -> CodeEdit
-    fn get_script_editor_code_edit_via_api(&self) -> Option<Gd<CodeEdit>> {
+    pub(super) fn get_script_editor_code_edit_via_api(&self) -> Option<Gd<CodeEdit>> {
         let editor = EditorInterface::singleton();
         let script_editor = editor.get_script_editor()?;
 
@@ -342,6 +361,7 @@ impl GodotNeovimPlugin {
         if self.current_editor.is_some() {
             self.connect_caret_changed_signal();
             self.connect_resized_signal();
+            self.connect_focus_signal();
             self.update_float_window_connection();
 
             // Clear any restored selection and disable selecting
@@ -782,6 +802,7 @@ impl GodotNeovimPlugin {
 
                 self.connect_caret_changed_signal();
                 self.connect_resized_signal();
+                self.connect_focus_signal();
                 self.update_float_window_connection();
                 return true;
             }
@@ -807,6 +828,7 @@ impl GodotNeovimPlugin {
                     self.current_editor_type = EditorType::Script;
                     self.connect_caret_changed_signal();
                     self.connect_resized_signal();
+                    self.connect_focus_signal();
                     self.reposition_mode_label();
 
                     if type_changed {
@@ -832,6 +854,7 @@ impl GodotNeovimPlugin {
                     self.current_script_path = format!("godot-neovim://external/{}", instance_id);
                     self.connect_caret_changed_signal();
                     self.connect_resized_signal();
+                    self.connect_focus_signal();
                     self.handle_script_changed();
                 }
             }
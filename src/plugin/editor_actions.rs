@@ -0,0 +1,138 @@
+//! Godot editor actions for the leader mappings (`<leader>rp`, `<leader>tb`, ...)
+//!
+//! Running the project goes through EditorInterface. The bottom panel, the
+//! FileSystem dock and the help search have no API of their own, so they are
+//! reached through the editor's controls (the panel next to the Neovim status
+//! dock, the dock's tab, the ScriptEditor's Search Help button).
+
+use super::window::dock_focus_control;
+use super::GodotNeovimPlugin;
+use godot::classes::control::SizeFlags;
+use godot::classes::{Button, Control, EditorInterface, Node, TabContainer};
+use godot::prelude::*;
+
+/// Text of the ScriptEditor's Search Help button (editor/script/script_editor_plugin.cpp)
+const SEARCH_HELP_TEXT: &str = "Search Help";
+
+/// First Button under `node` with a text (not a MenuButton)
+fn find_button_by_text(node: Gd<Node>, text: &str) -> Option<Gd<Button>> {
+    if let Ok(button) = node.clone().try_cast::<Button>() {
+        if !button.is_class("MenuButton") && button.get_text() == text {
+            return Some(button);
+        }
+    }
+    node.get_children()
+        .iter_shared()
+        .find_map(|child| find_button_by_text(child, text))
+}
+
+impl GodotNeovimPlugin {
+    /// Run the project's main scene (F5)
+    pub(super) fn action_run_project_impl(&mut self) {
+        EditorInterface::singleton().play_main_scene();
+        crate::verbose_print!("[godot-neovim] Editor action: run project");
+    }
+
+    /// Run the scene being edited (F6)
+    pub(super) fn action_run_current_scene_impl(&mut self) {
+        EditorInterface::singleton().play_current_scene();
+        crate::verbose_print!("[godot-neovim] Editor action: run current scene");
+    }
+
+    /// Stop the running project (F8)
+    pub(super) fn action_stop_project_impl(&mut self) {
+        let mut editor = EditorInterface::singleton();
+        if !editor.is_playing_scene() {
+            self.show_status_message("Project is not running");
+            return;
+        }
+        editor.stop_playing_scene();
+        crate::verbose_print!("[godot-neovim] Editor action: stop project");
+    }
+
+    /// Hide the bottom panel, or show the item it showed before
+    pub(super) fn action_toggle_bottom_panel_impl(&mut self) {
+        let items = self.bottom_panel_items();
+        if let Some(shown) = items.iter().find(|item| item.is_visible()) {
+            self.last_bottom_panel_item = Some(shown.clone());
+            self.base_mut().hide_bottom_panel();
+            crate::verbose_print!("[godot-neovim] Editor action: hide bottom panel");
+            return;
+        }
+
+        let item = self
+            .last_bottom_panel_item
+            .take()
+            .filter(|item| item.is_instance_valid())
+            .or_else(|| items.first().cloned());
+        let Some(item) = item else {
+            godot_warn!("[godot-neovim] Toggle bottom panel: bottom panel not found");
+            return;
+        };
+        self.base_mut().make_bottom_panel_item_visible(&item);
+        crate::verbose_print!(
+            "[godot-neovim] Editor action: show bottom panel ({})",
+            item.get_name()
+        );
+    }
+
+    /// Items of the bottom panel: the Neovim status dock and its siblings
+    fn bottom_panel_items(&self) -> Vec<Gd<Control>> {
+        let Some(parent) = self
+            .status_dock
+            .as_ref()
+            .filter(|dock| dock.is_instance_valid())
+            .and_then(|dock| dock.get_parent())
+        else {
+            return Vec::new();
+        };
+        parent
+            .get_children()
+            .iter_shared()
+            .filter_map(|child| child.try_cast::<Control>().ok())
+            // Items fill the panel vertically; the bar of item buttons does not
+            .filter(|control| control.get_v_size_flags().is_set(SizeFlags::EXPAND))
+            .collect()
+    }
+
+    /// Show the FileSystem dock and focus its file tree
+    pub(super) fn action_focus_filesystem_impl(&mut self) {
+        let Some(dock) = EditorInterface::singleton().get_file_system_dock() else {
+            return;
+        };
+        let dock: Gd<Control> = dock.upcast();
+
+        // A dock sharing its slot with other docks is a tab of a TabContainer
+        if let Some(mut tabs) = dock
+            .get_parent()
+            .and_then(|parent| parent.try_cast::<TabContainer>().ok())
+        {
+            let index = tabs.get_tab_idx_from_control(&dock);
+            if index >= 0 {
+                tabs.set_current_tab(index);
+            }
+        }
+
+        let Some(mut focus) = dock_focus_control(&dock) else {
+            self.show_status_message("FileSystem dock is hidden");
+            return;
+        };
+        focus.grab_focus();
+        crate::verbose_print!("[godot-neovim] Editor action: focus FileSystem dock");
+    }
+
+    /// Open the editor's help search (like F1)
+    pub(super) fn action_search_help_impl(&mut self) {
+        let button = EditorInterface::singleton()
+            .get_script_editor()
+            .and_then(|script_editor| {
+                find_button_by_text(script_editor.upcast(), SEARCH_HELP_TEXT)
+            });
+        let Some(mut button) = button else {
+            godot_warn!("[godot-neovim] Search help: Search Help button not found in ScriptEditor");
+            return;
+        };
+        button.call_deferred("emit_signal", &["pressed".to_variant()]);
+        crate::verbose_print!("[godot-neovim] Editor action: search help");
+    }
+}
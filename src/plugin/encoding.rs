@@ -0,0 +1,340 @@
+//! File encoding detection for scripts that are not plain UTF-8
+//!
+//! Godot reads and writes scripts as UTF-8 with LF line endings, so Latin-1 files
+//! arrive garbled, and byte order marks and CRLF line endings are lost. The
+//! encoding and line ending are detected from the file on disk when it is opened;
+//! the editor and the Neovim buffer get the decoded text with LF line endings, and
+//! the file Godot saved is converted back to its original format afterwards.
+
+use super::{EditorType, GodotNeovimPlugin};
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+
+/// Encoding of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FileEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+/// Line ending of a file on disk (Vim's 'fileformat': unix or dos)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    /// Line ending of the first line of `text` (LF for a single line)
+    fn detect(text: &str) -> Self {
+        match text.find('\n') {
+            Some(end) if text[..end].ends_with('\r') => Self::Crlf,
+            _ => Self::Lf,
+        }
+    }
+
+    /// Apply this line ending to text with LF (or mixed) line endings
+    fn apply(self, text: &str) -> String {
+        let text = text.replace("\r\n", "\n");
+        match self {
+            Self::Lf => text,
+            Self::Crlf => text.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Encoding and line ending of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FileFormat {
+    pub(super) encoding: FileEncoding,
+    pub(super) line_ending: LineEnding,
+}
+
+impl FileFormat {
+    /// Detect the format of file content and decode it to text with LF line endings
+    fn decode(bytes: &[u8]) -> (Self, String) {
+        let encoding = FileEncoding::detect(bytes);
+        let text = encoding.decode(bytes);
+        let format = Self {
+            encoding,
+            line_ending: LineEnding::detect(&text),
+        };
+        (format, LineEnding::Lf.apply(&text))
+    }
+
+    /// Whether Godot writes files in this format itself (UTF-8 with LF)
+    fn is_godot_default(self) -> bool {
+        self.encoding == FileEncoding::Utf8 && self.line_ending == LineEnding::Lf
+    }
+
+    /// Name for the status line: the encoding, with `dos` for CRLF line endings
+    fn name(self) -> String {
+        match self.line_ending {
+            LineEnding::Lf => self.encoding.name().to_string(),
+            LineEnding::Crlf => format!("{}, dos", self.encoding.name()),
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Character written for characters the target encoding cannot represent
+const REPLACEMENT: u8 = b'?';
+
+impl FileEncoding {
+    /// Name as used by Neovim's 'fileencoding' (with `bomb` noted)
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf8Bom => "utf-8 (bom)",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16",
+            Self::Latin1 => "latin1",
+        }
+    }
+
+    /// Detect the encoding from a byte order mark, falling back to Latin-1 for
+    /// bytes that are not valid UTF-8 (every byte sequence is valid Latin-1)
+    pub(super) fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            Self::Utf8Bom
+        } else if bytes.starts_with(UTF16_LE_BOM) {
+            Self::Utf16Le
+        } else if bytes.starts_with(UTF16_BE_BOM) {
+            Self::Utf16Be
+        } else if std::str::from_utf8(bytes).is_ok() {
+            Self::Utf8
+        } else {
+            Self::Latin1
+        }
+    }
+
+    /// Decode file content (the byte order mark is not part of the text)
+    pub(super) fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Utf8Bom => String::from_utf8_lossy(&bytes[UTF8_BOM.len()..]).into_owned(),
+            Self::Utf16Le | Self::Utf16Be => {
+                let units: Vec<u16> = bytes[UTF16_LE_BOM.len()..]
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        let pair = [pair[0], pair[1]];
+                        if self == Self::Utf16Le {
+                            u16::from_le_bytes(pair)
+                        } else {
+                            u16::from_be_bytes(pair)
+                        }
+                    })
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            Self::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+        }
+    }
+
+    /// Encode text for writing, with the byte order mark if the encoding has one.
+    /// Returns the bytes and whether characters had to be replaced (lossy).
+    pub(super) fn encode(self, text: &str) -> (Vec<u8>, bool) {
+        match self {
+            Self::Utf8 => (text.as_bytes().to_vec(), false),
+            Self::Utf8Bom => {
+                let mut bytes = UTF8_BOM.to_vec();
+                bytes.extend_from_slice(text.as_bytes());
+                (bytes, false)
+            }
+            Self::Utf16Le => {
+                let mut bytes = UTF16_LE_BOM.to_vec();
+                bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+                (bytes, false)
+            }
+            Self::Utf16Be => {
+                let mut bytes = UTF16_BE_BOM.to_vec();
+                bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+                (bytes, false)
+            }
+            Self::Latin1 => {
+                let mut lossy = false;
+                let bytes = text
+                    .chars()
+                    .map(|c| {
+                        u8::try_from(u32::from(c)).unwrap_or_else(|_| {
+                            lossy = true;
+                            REPLACEMENT
+                        })
+                    })
+                    .collect();
+                (bytes, lossy)
+            }
+        }
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Read a file as text in its detected encoding (for :e!)
+    /// The format is recorded, so the next save converts back to it
+    pub(super) fn read_file_decoded(&mut self, path: &str) -> Option<String> {
+        let abs_path = ProjectSettings::singleton()
+            .globalize_path(path)
+            .to_string();
+        let bytes = std::fs::read(&abs_path).ok()?;
+        let (format, text) = FileFormat::decode(&bytes);
+        self.file_formats.insert(path.to_string(), format);
+        Some(text)
+    }
+
+    /// Detect the format of the current file when it is first opened.
+    /// Returns the decoded text if the editor shows something else (garbled by
+    /// Godot's UTF-8 reader); the editor is updated and the text must be used for
+    /// the Neovim buffer.
+    pub(super) fn decode_file_on_open(&mut self, editor_text: &str) -> Option<String> {
+        if self.current_editor_type == EditorType::Unknown
+            || self.current_script_path.is_empty()
+            || self.file_formats.contains_key(&self.current_script_path)
+        {
+            return None;
+        }
+
+        let path = self.current_script_path.clone();
+        let abs_path = ProjectSettings::singleton()
+            .globalize_path(&path)
+            .to_string();
+        let bytes = std::fs::read(&abs_path).ok()?;
+        let (format, decoded) = FileFormat::decode(&bytes);
+        self.file_formats.insert(path.clone(), format);
+        if format.is_godot_default() {
+            return None;
+        }
+
+        crate::verbose_print!("[godot-neovim] {}: fileformat={}", path, format.name());
+        if format.encoding == FileEncoding::Utf8 || decoded == editor_text {
+            return None;
+        }
+
+        let editor = self.current_editor.as_mut()?;
+        editor.set_text(&decoded);
+        editor.tag_saved_version();
+        self.show_status_message(&format!("[{}] converted to utf-8", format.encoding.name()));
+        Some(decoded)
+    }
+
+    /// Convert a file Godot just saved (always UTF-8 with LF line endings) back to
+    /// the encoding and line ending it had on disk, warning when characters could
+    /// not be represented
+    pub(super) fn restore_file_format(&mut self, path: &str) {
+        let Some(&format) = self.file_formats.get(path) else {
+            return;
+        };
+        if format.is_godot_default() {
+            return;
+        }
+
+        let abs_path = ProjectSettings::singleton()
+            .globalize_path(path)
+            .to_string();
+        let Ok(bytes) = std::fs::read(&abs_path) else {
+            return;
+        };
+        // Not written by Godot (already converted to another encoding)
+        let Ok(text) = std::str::from_utf8(&bytes) else {
+            return;
+        };
+        let text = format
+            .line_ending
+            .apply(text.trim_start_matches('\u{feff}'));
+        let (converted, lossy) = format.encoding.encode(&text);
+        if converted == bytes {
+            return;
+        }
+        let encoding = format.encoding;
+
+        // Keep the modification time Godot recorded, so the editor does not
+        // report the file as changed outside Godot
+        let modified = std::fs::metadata(&abs_path).and_then(|m| m.modified()).ok();
+        if let Err(e) = std::fs::write(&abs_path, &converted) {
+            godot_warn!(
+                "[godot-neovim] {}: Failed to write as {}: {}",
+                path,
+                encoding.name(),
+                e
+            );
+            return;
+        }
+        if let Some(modified) = modified {
+            let _ = std::fs::File::options()
+                .write(true)
+                .open(&abs_path)
+                .and_then(|file| file.set_modified(modified));
+        }
+
+        crate::verbose_print!("[godot-neovim] {}: Saved as {}", path, format.name());
+        if lossy {
+            godot_warn!(
+                "[godot-neovim] {}: Characters not representable in {} were replaced with '?'",
+                path,
+                encoding.name()
+            );
+            self.show_status_message(&format!(
+                "W: [{}] characters replaced with '?'",
+                encoding.name()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_round_trip() {
+        let latin1 = b"# caf\xe9\nvar x = 1\n";
+        assert_eq!(FileEncoding::detect(latin1), FileEncoding::Latin1);
+        let text = FileEncoding::Latin1.decode(latin1);
+        assert_eq!(text, "# café\nvar x = 1\n");
+        assert_eq!(FileEncoding::Latin1.encode(&text), (latin1.to_vec(), false));
+
+        let bom = b"\xef\xbb\xbfextends Node\n";
+        assert_eq!(FileEncoding::detect(bom), FileEncoding::Utf8Bom);
+        let text = FileEncoding::Utf8Bom.decode(bom);
+        assert_eq!(text, "extends Node\n");
+        assert_eq!(FileEncoding::Utf8Bom.encode(&text), (bom.to_vec(), false));
+
+        let utf16 = b"\xff\xfea\x00\xe9\x00";
+        assert_eq!(FileEncoding::detect(utf16), FileEncoding::Utf16Le);
+        let text = FileEncoding::Utf16Le.decode(utf16);
+        assert_eq!(text, "a\u{e9}");
+        assert_eq!(FileEncoding::Utf16Le.encode(&text), (utf16.to_vec(), false));
+
+        assert_eq!(
+            FileEncoding::detect("# café\n".as_bytes()),
+            FileEncoding::Utf8
+        );
+    }
+
+    #[test]
+    fn test_crlf_line_ending_round_trip() {
+        let crlf = b"extends Node\r\n\r\nvar x = 1\r\n";
+        let (format, text) = FileFormat::decode(crlf);
+        assert_eq!(format.line_ending, LineEnding::Crlf);
+        assert_eq!(format.encoding, FileEncoding::Utf8);
+        assert_eq!(text, "extends Node\n\nvar x = 1\n");
+        let saved = format.line_ending.apply(&text);
+        assert_eq!(format.encoding.encode(&saved).0, crlf.to_vec());
+
+        let (format, _) = FileFormat::decode(b"extends Node\nvar s = \"\r\n\"\n");
+        assert_eq!(format.line_ending, LineEnding::Lf);
+        assert!(format.is_godot_default());
+    }
+
+    #[test]
+    fn test_latin1_encode_reports_lossy() {
+        let (bytes, lossy) = FileEncoding::Latin1.encode("a → b");
+        assert_eq!(bytes, b"a ? b");
+        assert!(lossy);
+    }
+}
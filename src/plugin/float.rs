@@ -0,0 +1,87 @@
+//! Floating text panels drawn over the CodeEdit (LSP hover, signature help, the
+//! command-line wildmenu)
+//!
+//! Floats are children of the CodeEdit (not popup windows), so keyboard focus
+//! stays in the editor and typing continues normally while a float is shown.
+
+use super::GodotNeovimPlugin;
+use godot::classes::control::GrowDirection;
+use godot::classes::{PanelContainer, RichTextLabel};
+use godot::prelude::*;
+
+/// Width of a float in pixels
+const FLOAT_WIDTH: f32 = 480.0;
+
+/// Where a float is placed in the editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum FloatPlacement {
+    /// Below the caret line (hover)
+    BelowCaret,
+    /// Above the caret line, growing upward (signature help while typing)
+    AboveCaret,
+    /// At the bottom left of the editor, growing upward (command-line completion)
+    BottomLeft,
+}
+
+impl GodotNeovimPlugin {
+    /// Create a float showing `bbcode` next to the caret of the current editor
+    pub(super) fn create_editor_float(
+        &self,
+        bbcode: &str,
+        placement: FloatPlacement,
+    ) -> Option<Gd<PanelContainer>> {
+        let mut editor = self.current_editor.clone()?;
+        if !editor.is_instance_valid() {
+            return None;
+        }
+
+        let mut label = RichTextLabel::new_alloc();
+        label.set_use_bbcode(true);
+        label.set_fit_content(true);
+        label.set_selection_enabled(true);
+        label.set_custom_minimum_size(Vector2::new(FLOAT_WIDTH, 0.0));
+        label.set_text(bbcode);
+
+        let mut panel = PanelContainer::new_alloc();
+        panel.add_child(&label);
+        editor.add_child(&panel);
+
+        // Keep the float inside the editor horizontally
+        let caret_pos = editor.get_caret_draw_pos();
+        let line_height = editor.get_line_height() as f32;
+        let editor_size = editor.get_size();
+        let x = caret_pos
+            .x
+            .min(editor_size.x - FLOAT_WIDTH - line_height)
+            .max(0.0);
+
+        match placement {
+            FloatPlacement::BelowCaret => {
+                panel.set_position(Vector2::new(x, caret_pos.y + line_height * 0.25));
+            }
+            FloatPlacement::AboveCaret => {
+                // Anchor the bottom edge at the top of the caret line; the panel grows
+                // upward once the label's content size is known
+                panel.set_v_grow_direction(GrowDirection::BEGIN);
+                panel.set_position(Vector2::new(x, (caret_pos.y - line_height).max(0.0)));
+            }
+            FloatPlacement::BottomLeft => {
+                panel.set_v_grow_direction(GrowDirection::BEGIN);
+                panel.set_position(Vector2::new(0.0, editor_size.y));
+            }
+        }
+
+        Some(panel)
+    }
+
+    /// Free a float. Returns true if there was a float to free.
+    pub(super) fn free_editor_float(float: Option<Gd<PanelContainer>>) -> bool {
+        let Some(mut panel) = float else {
+            return false;
+        };
+        if panel.is_instance_valid() {
+            panel.queue_free();
+        }
+        true
+    }
+}
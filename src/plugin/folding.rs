@@ -0,0 +1,61 @@
+//! z commands and folding
+//!
+//! `z` is held until the next key instead of going to Neovim, whose folds are
+//! never shown. Fold commands act on the CodeEdit's folds (za, zo, zc, zR, zM),
+//! and Visual `zf` wraps the selected lines in a code region (`#region` ...
+//! `#endregion`), the only folds Godot can create on arbitrary lines. Other z
+//! commands (zz, zt, zb, zh, ...) are sent to Neovim as a whole.
+
+use super::GodotNeovimPlugin;
+
+impl GodotNeovimPlugin {
+    /// Key after `z`: a fold command, or the whole command for Neovim
+    pub(super) fn finish_z_command(&mut self, keys: &str) {
+        self.clear_last_key();
+        match keys {
+            "a" => self.toggle_fold(),
+            "o" => self.unfold_current_line(),
+            "c" => self.fold_current_line(),
+            "R" => self.unfold_all(),
+            "M" => self.fold_all(),
+            "f" if self.is_in_visual_mode() => self.create_fold_region(),
+            _ => self.action_send_keys_impl(&format!("z{}", keys)),
+        }
+    }
+
+    /// Wrap the lines of the Visual selection in a folded code region (Visual zf)
+    fn create_fold_region(&mut self) {
+        let selection = {
+            let Some(neovim) = self.get_current_neovim() else {
+                return;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                return;
+            };
+            client.get_visual_selection()
+        };
+        let Some(((start_line, _), (end_line, _))) = selection else {
+            return;
+        };
+        let from_line = start_line.min(end_line) as i32;
+        let to_line = start_line.max(end_line) as i32;
+
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        // create_code_region works on whole lines of the selection
+        let to_column = editor.get_line(to_line).chars().len() as i32;
+        editor.select(from_line, 0, to_line, to_column);
+        editor.create_code_region();
+
+        // The region lines are Godot's edit: sync them back as one undo step
+        self.send_keys("<Esc>");
+        self.sync_buffer_to_neovim_keep_undo();
+        self.sync_cursor_to_neovim();
+        crate::verbose_print!(
+            "[godot-neovim] zf: Created code region for lines {}-{}",
+            from_line + 1,
+            to_line + 1
+        );
+    }
+}
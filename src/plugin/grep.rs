@@ -0,0 +1,158 @@
+//! Project grep backend: regex search of the project's GDScript files
+//!
+//! The project is walked and searched on a background thread. Matches are sent
+//! back through a channel in batches per file and polled from `process()`, so a
+//! large project never blocks the editor. Dropping the GrepSearch stops the search.
+
+use super::project_files::project_files;
+use regex::{Regex, RegexBuilder};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// Extensions of the files searched by :grep
+const GREP_EXTENSIONS: &[&str] = &["gd"];
+
+/// Maximum number of matches of one search (the rest is not reported)
+const MAX_MATCHES: usize = 5000;
+
+/// A line matching the pattern
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct GrepMatch {
+    /// Absolute path of the file
+    pub path: String,
+    /// Line (0-indexed)
+    pub line: i32,
+    /// Column of the match start (0-indexed, character position)
+    pub col: i32,
+    /// Text of the line
+    pub text: String,
+}
+
+/// Result of polling a search
+#[derive(Debug, Default)]
+pub(super) struct GrepUpdate {
+    /// Newly found matches (after the previous ones)
+    pub matches: Vec<GrepMatch>,
+    /// The search is complete (no more updates)
+    pub done: bool,
+    /// The search stopped at MAX_MATCHES
+    pub truncated: bool,
+}
+
+/// Message from the search thread
+enum GrepEvent {
+    /// Matches of one file
+    Matches(Vec<GrepMatch>),
+    /// Search finished; true if it stopped at MAX_MATCHES
+    Done(bool),
+}
+
+/// A running search for one pattern
+pub(super) struct GrepSearch {
+    receiver: Receiver<GrepEvent>,
+}
+
+impl GrepSearch {
+    /// Drain the events received so far (never blocks)
+    pub(super) fn poll(&mut self) -> GrepUpdate {
+        let mut update = GrepUpdate::default();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(GrepEvent::Matches(matches)) => update.matches.extend(matches),
+                Ok(GrepEvent::Done(truncated)) => {
+                    update.done = true;
+                    update.truncated = truncated;
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    update.done = true;
+                    break;
+                }
+            }
+        }
+        update
+    }
+}
+
+/// Compile a grep pattern (Rust regex syntax), ignoring case unless it has an
+/// uppercase character (like 'smartcase')
+pub(super) fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!pattern.chars().any(char::is_uppercase))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Start searching the GDScript files under `root` (absolute, forward slashes)
+pub(super) fn spawn_grep(root: &str, pattern: &str) -> Result<GrepSearch, String> {
+    let regex = compile_pattern(pattern)?;
+    let root = root.to_string();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut count = 0;
+        for file in project_files(Path::new(&root), GREP_EXTENSIONS) {
+            let path = format!("{}/{}", root, file);
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let mut matches = search_text(&regex, &path, &text);
+            if matches.is_empty() {
+                continue;
+            }
+            matches.truncate(MAX_MATCHES - count);
+            count += matches.len();
+            if sender.send(GrepEvent::Matches(matches)).is_err() {
+                // Receiver dropped: the search was replaced or cancelled
+                return;
+            }
+            if count >= MAX_MATCHES {
+                let _ = sender.send(GrepEvent::Done(true));
+                return;
+            }
+        }
+        let _ = sender.send(GrepEvent::Done(false));
+    });
+
+    Ok(GrepSearch { receiver })
+}
+
+/// Lines of `text` matching `regex` (first match of each line)
+fn search_text(regex: &Regex, path: &str, text: &str) -> Vec<GrepMatch> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, line_text)| {
+            let line_text = line_text.trim_end_matches('\r');
+            let found = regex.find(line_text)?;
+            Some(GrepMatch {
+                path: path.to_string(),
+                line: line as i32,
+                col: line_text[..found.start()].chars().count() as i32,
+                text: line_text.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_text_reports_lines_and_char_columns() {
+        let regex = compile_pattern("speed").unwrap();
+        let text = "var speed = 1\r\nfunc _ready():\n\t# 速度 Speed\n";
+        let matches = search_text(&regex, "/p/a.gd", text);
+        assert_eq!(matches.len(), 2);
+        assert_eq!((matches[0].line, matches[0].col), (0, 4));
+        assert_eq!(matches[0].text, "var speed = 1");
+        // Smartcase: a lowercase pattern ignores case; columns count characters
+        assert_eq!((matches[1].line, matches[1].col), (2, 6));
+    }
+
+    #[test]
+    fn test_compile_pattern_smartcase() {
+        assert!(!compile_pattern("Speed").unwrap().is_match("speed"));
+        assert!(compile_pattern("(").is_err());
+    }
+}
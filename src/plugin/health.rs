@@ -0,0 +1,443 @@
+//! Installation self-check run at plugin activation, and :checkhealth
+//!
+//! Verifies that the loaded library, the addon files (plugin.cfg, Lua modules) and
+//! the .gdextension library entries for this platform belong together. A
+//! half-updated addon folder or a missing/misnamed platform entry is reported with
+//! an actionable message instead of surfacing later as Lua errors from Neovim or a
+//! dlopen failure on the next editor start.
+//!
+//! :checkhealth adds the runtime state (Neovim executable and version, RPC
+//! latency, Lua plugin, buffer sync, LSP) and shows the report in a dialog, from
+//! which it can be copied into a bug report.
+
+use super::{EditorType, GodotNeovimPlugin};
+use crate::neovim::NEOVIM_REQUIRED_VERSION;
+use crate::settings::{self, ValidationResult};
+use godot::classes::{
+    AcceptDialog, ConfigFile, DisplayServer, EditorInterface, Engine, FileAccess, Os,
+    ProjectSettings, TextEdit,
+};
+use godot::global::Error;
+use godot::prelude::*;
+use rmpv::Value;
+use std::time::{Duration, Instant};
+
+/// Version of the loaded library (Cargo.toml; plugin.cfg and the Lua modules must match)
+const LIBRARY_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const PLUGIN_CFG_PATH: &str = "res://addons/godot-neovim/plugin.cfg";
+const GDEXTENSION_PATH: &str = "res://addons/godot-neovim/godot-neovim.gdextension";
+const LUA_INIT_PATH: &str = "res://addons/godot-neovim/lua/godot_neovim/init.lua";
+
+/// Architecture feature tags Godot understands in .gdextension library entries
+const ARCHITECTURE_TAGS: &[&str] = &[
+    "x86_64",
+    "x86_32",
+    "arm64",
+    "arm32",
+    "rv64",
+    "ppc64",
+    "wasm32",
+    "universal",
+];
+
+/// Other feature tags used in library entries
+const BUILD_TAGS: &[&str] = &["debug", "release", "editor", "template", "double", "single"];
+
+/// RPC round trips slower than this are reported (half the blocking RPC timeout)
+const SLOW_RPC: Duration = Duration::from_millis(50);
+
+/// Neovim state for :checkhealth: { major, minor, patch, Lua plugin version ('' for
+/// the embedded fallback), current buffer attached, changedtick, line count }
+const HEALTH_QUERY_LUA: &str = r#"
+local v = vim.version()
+local buf = vim.api.nvim_get_current_buf()
+local ok, core = pcall(require, 'godot_neovim.core')
+return {
+    v.major, v.minor, v.patch,
+    (_G.godot_neovim or {}).VERSION or '',
+    ok and core._attached_buffers[buf] == true,
+    vim.api.nvim_buf_get_changedtick(buf),
+    vim.api.nvim_buf_line_count(buf),
+}
+"#;
+
+/// Run all checks. Returns one actionable message per problem (empty if all is well).
+pub(super) fn check_installation() -> Vec<String> {
+    let mut problems = Vec::new();
+    check_addon_versions(&mut problems);
+    check_library_entries(&mut problems);
+    problems
+}
+
+/// plugin.cfg and the Lua modules must come from the same release as the library
+fn check_addon_versions(problems: &mut Vec<String>) {
+    let mut plugin_cfg = ConfigFile::new_gd();
+    if plugin_cfg.load(PLUGIN_CFG_PATH) == Error::OK {
+        let version = plugin_cfg
+            .get_value("plugin", "version")
+            .try_to::<GString>()
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        if version != LIBRARY_VERSION {
+            problems.push(format!(
+                "plugin.cfg is v{} but the loaded library is v{}. Replace the whole addons/godot-neovim folder with one release and restart the editor.",
+                version, LIBRARY_VERSION
+            ));
+        }
+    } else {
+        problems.push(format!("Cannot read {}", PLUGIN_CFG_PATH));
+    }
+
+    if !FileAccess::file_exists(LUA_INIT_PATH) {
+        problems.push(format!(
+            "Lua modules not found ({}). Neovim integration will fall back to minimal defaults; reinstall the addon.",
+            LUA_INIT_PATH
+        ));
+        return;
+    }
+    let lua_init = FileAccess::get_file_as_string(LUA_INIT_PATH).to_string();
+    match lua_version(&lua_init) {
+        Some(version) if version == LIBRARY_VERSION => {}
+        Some(version) => problems.push(format!(
+            "Lua modules are v{} but the loaded library is v{}. Replace the whole addons/godot-neovim folder with one release and restart the editor.",
+            version, LIBRARY_VERSION
+        )),
+        None => problems.push(format!(
+            "Lua modules have no version (older than v{}). Replace the whole addons/godot-neovim folder with one release and restart the editor.",
+            LIBRARY_VERSION
+        )),
+    }
+}
+
+/// Version declared in init.lua (`M.VERSION = '1.0.2'`)
+fn lua_version(source: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("M.VERSION")?.trim_start();
+        let value = value.strip_prefix('=')?.trim();
+        Some(value.trim_matches(|c| c == '\'' || c == '"').to_string())
+    })
+}
+
+/// The .gdextension must have existing debug and release libraries for this platform
+/// and must not use feature tags Godot does not know (e.g. `aarch64` instead of `arm64`)
+fn check_library_entries(problems: &mut Vec<String>) {
+    let mut gdextension = ConfigFile::new_gd();
+    if gdextension.load(GDEXTENSION_PATH) != Error::OK {
+        problems.push(format!("Cannot read {}", GDEXTENSION_PATH));
+        return;
+    }
+    if !gdextension.has_section("libraries") {
+        problems.push(format!("{} has no [libraries] section", GDEXTENSION_PATH));
+        return;
+    }
+
+    let platform = Os::singleton().get_name().to_string().to_lowercase();
+    let arch = Engine::singleton().get_architecture_name().to_string();
+    let keys: Vec<String> = gdextension
+        .get_section_keys("libraries")
+        .as_slice()
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+
+    for key in &keys {
+        let mut tags = key.split('.');
+        if tags.next() != Some(platform.as_str()) {
+            continue;
+        }
+        for tag in tags {
+            if !ARCHITECTURE_TAGS.contains(&tag) && !BUILD_TAGS.contains(&tag) {
+                problems.push(format!(
+                    "Library entry '{}' in {} uses unknown feature tag '{}' (architectures: {}). Godot will never load it.",
+                    key,
+                    GDEXTENSION_PATH,
+                    tag,
+                    ARCHITECTURE_TAGS.join(", ")
+                ));
+            }
+        }
+    }
+
+    for build in ["debug", "release"] {
+        let features = [platform.as_str(), build, "editor", arch.as_str()];
+        let entry = keys.iter().find(|key| {
+            key.split('.').next() == Some(platform.as_str())
+                && key.split('.').all(|tag| features.contains(&tag))
+        });
+        let Some(key) = entry else {
+            problems.push(format!(
+                "{} has no library entry for this editor ({}.{}.editor.{}). Add one to [libraries].",
+                GDEXTENSION_PATH, platform, build, arch
+            ));
+            continue;
+        };
+
+        let path = gdextension
+            .get_value("libraries", key)
+            .try_to::<GString>()
+            .map(|p| p.to_string())
+            .unwrap_or_default();
+        let absolute = ProjectSettings::singleton()
+            .globalize_path(&path)
+            .to_string();
+        if !FileAccess::file_exists(&path) && !std::path::Path::new(&absolute).exists() {
+            problems.push(format!(
+                "Library for '{}' not found: {}. Download the release for {} {} or fix the path.",
+                key, path, platform, arch
+            ));
+        }
+    }
+}
+
+/// :checkhealth report, formatted like Neovim's
+#[derive(Debug, Default)]
+struct HealthReport {
+    text: String,
+    /// Number of warnings and errors
+    problems: usize,
+}
+
+impl HealthReport {
+    fn section(&mut self, title: &str) {
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text
+            .push_str(&format!("{}\n{}\n", title, "-".repeat(title.len())));
+    }
+
+    fn ok(&mut self, message: &str) {
+        self.text.push_str(&format!("- OK {}\n", message));
+    }
+
+    fn warn(&mut self, message: &str) {
+        self.problems += 1;
+        self.text.push_str(&format!("- WARNING {}\n", message));
+    }
+
+    fn error(&mut self, message: &str) {
+        self.problems += 1;
+        self.text.push_str(&format!("- ERROR {}\n", message));
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// :checkhealth - run the diagnostics and show the report
+    pub(super) fn cmd_checkhealth(&mut self) {
+        let mut report = HealthReport::default();
+
+        report.section("Installation");
+        let problems = check_installation();
+        if problems.is_empty() {
+            report.ok(&format!(
+                "Library, plugin.cfg and Lua modules are v{}",
+                LIBRARY_VERSION
+            ));
+        }
+        for problem in &problems {
+            report.error(problem);
+        }
+
+        report.section("Neovim executable");
+        let path = settings::get_neovim_path();
+        match settings::validate_neovim_path(&path) {
+            ValidationResult::Valid { version } => report.ok(&format!("{} ({})", path, version)),
+            ValidationResult::NotFound => report.error(&format!(
+                "Neovim not found: '{}'. Set Editor Settings > Godot Neovim > Neovim Executable Path.",
+                path
+            )),
+            ValidationResult::NotExecutable => {
+                report.error(&format!("'{}' is not executable", path))
+            }
+            ValidationResult::InvalidVersion { error } => {
+                report.error(&format!("'{} --version' failed: {}", path, error))
+            }
+        }
+
+        self.check_neovim_health(&mut report);
+
+        report.section("Godot language server");
+        if self.godot_lsp.is_some() {
+            report.ok("Network > Language Server > Use Thread is enabled");
+        } else {
+            report.warn("Network > Language Server > Use Thread is disabled: LSP features (hover, references, rename, signature help) are unavailable. Enable it and restart the editor.");
+        }
+
+        let summary = if report.problems == 0 {
+            "checkhealth: OK".to_string()
+        } else {
+            format!("checkhealth: {} problem(s)", report.problems)
+        };
+        crate::verbose_print!("[godot-neovim] {}\n{}", summary, report.text);
+        self.show_health_report(report.text);
+        self.show_status_message(&summary);
+    }
+
+    /// Connection, version, Lua plugin and buffer sync of the current Neovim
+    fn check_neovim_health(&mut self, report: &mut HealthReport) {
+        report.section("Neovim connection");
+        let result = {
+            let Some(neovim) = self.get_current_neovim() else {
+                report.error(
+                    "Not connected to Neovim. Check the executable path and restart the editor.",
+                );
+                return;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                report.warn("Neovim client is busy. Run :checkhealth again.");
+                return;
+            };
+            match client.remote_address() {
+                Some(address) => report.ok(&format!("Attached to Neovim at {}", address)),
+                None if self.current_editor_type != EditorType::Shader
+                    && !settings::get_connection_address().is_empty() =>
+                {
+                    report.warn(&format!(
+                        "Could not attach to Neovim at {}: using an embedded Neovim (see Output)",
+                        settings::get_connection_address()
+                    ))
+                }
+                None => {}
+            }
+            let start = Instant::now();
+            client
+                .execute_lua_with_result(HEALTH_QUERY_LUA)
+                .map(|value| (value, start.elapsed()))
+        };
+        let (fields, latency) = match result {
+            Ok((Value::Array(fields), latency)) if fields.len() == 7 => (fields, latency),
+            Ok(_) => {
+                report.error("Unexpected response from Neovim");
+                return;
+            }
+            Err(e) => {
+                report.error(&format!("Neovim did not respond: {}", e));
+                return;
+            }
+        };
+        // Changes notified before the response are applied before comparing the buffer
+        self.process_neovim_updates();
+
+        let number = |i: usize| fields[i].as_i64().unwrap_or(-1);
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        if latency < SLOW_RPC {
+            report.ok(&format!("RPC round trip: {:.1} ms", latency_ms));
+        } else {
+            report.warn(&format!(
+                "RPC round trip: {:.1} ms (slow; commands time out after {} ms)",
+                latency_ms,
+                SLOW_RPC.as_millis() * 2
+            ));
+        }
+
+        let version = (number(0), number(1), number(2));
+        let (req_major, req_minor, req_patch) = NEOVIM_REQUIRED_VERSION;
+        let required = (req_major as i64, req_minor as i64, req_patch as i64);
+        let version_text = format!("{}.{}.{}", version.0, version.1, version.2);
+        if version >= required {
+            report.ok(&format!("Neovim v{}", version_text));
+        } else {
+            report.error(&format!(
+                "Neovim v{} is below the required v{}.{}.{}",
+                version_text, req_major, req_minor, req_patch
+            ));
+        }
+
+        let lua_version = fields[3].as_str().unwrap_or_default();
+        let external = !lua_version.is_empty();
+        if !external {
+            report.warn("Lua plugin not loaded: using the embedded fallback (most commands are unavailable). Check the addons/godot-neovim/lua folder.");
+        } else if lua_version == LIBRARY_VERSION {
+            report.ok(&format!("Lua plugin v{}", lua_version));
+        } else {
+            report.error(&format!(
+                "Lua plugin is v{} but the library is v{}. Restart the editor after updating the addon.",
+                lua_version, LIBRARY_VERSION
+            ));
+        }
+
+        report.section("Current buffer");
+        if self.current_script_path.is_empty() {
+            report.ok("No script open");
+            return;
+        }
+        let nvim_attached = fields[4].as_bool().unwrap_or(false);
+        if self.sync_manager.is_attached() && (nvim_attached || !external) {
+            report.ok(&format!("{} is attached", self.current_script_path));
+        } else {
+            report.error(&format!(
+                "{} is not attached: changes made in Neovim do not reach Godot. Close and reopen the script.",
+                self.current_script_path
+            ));
+        }
+
+        let (nvim_tick, nvim_lines) = (number(5), number(6));
+        let synced_tick = self.sync_manager.get_changedtick();
+        let synced_lines = self.sync_manager.get_line_count() as i64;
+        if nvim_lines != synced_lines {
+            report.error(&format!(
+                "Line count differs: Neovim {}, Godot {}. Close and reopen the script.",
+                nvim_lines, synced_lines
+            ));
+        } else if nvim_tick != synced_tick {
+            report.warn(&format!(
+                "changedtick differs: Neovim {}, last synced {}",
+                nvim_tick, synced_tick
+            ));
+        } else {
+            report.ok(&format!(
+                "changedtick {} and {} line(s) in sync",
+                nvim_tick, nvim_lines
+            ));
+        }
+    }
+
+    /// Show the :checkhealth report in a dialog (replacing a previous one)
+    fn show_health_report(&mut self, text: String) {
+        self.close_health_dialog();
+
+        let mut dialog = AcceptDialog::new_alloc();
+        dialog.set_title("godot-neovim: checkhealth");
+        dialog
+            .add_button_ex("Copy to Clipboard")
+            .right(false)
+            .action("copy")
+            .done();
+
+        let mut report_view = TextEdit::new_alloc();
+        report_view.set_editable(false);
+        report_view.set_text(&text);
+        report_view.set_custom_minimum_size(Vector2::new(720.0, 420.0));
+        dialog.add_child(&report_view);
+
+        let callable_closed = self.base().callable("on_health_dialog_closed");
+        let callable_custom = self.base().callable("on_health_dialog_action");
+        dialog.connect("confirmed", &callable_closed);
+        dialog.connect("canceled", &callable_closed);
+        dialog.connect("custom_action", &callable_custom);
+
+        if let Some(mut base_control) = EditorInterface::singleton().get_base_control() {
+            base_control.add_child(&dialog);
+            dialog.popup_centered();
+        }
+
+        self.health_report = text;
+        self.health_dialog = Some(dialog);
+    }
+
+    /// Copy the :checkhealth report to the clipboard
+    pub(super) fn copy_health_report(&mut self) {
+        DisplayServer::singleton().clipboard_set(&self.health_report);
+        self.show_status_message("checkhealth: Report copied to the clipboard");
+    }
+
+    /// Free the :checkhealth dialog
+    pub(super) fn close_health_dialog(&mut self) {
+        if let Some(mut dialog) = self.health_dialog.take() {
+            if dialog.is_instance_valid() {
+                dialog.queue_free();
+            }
+        }
+    }
+}
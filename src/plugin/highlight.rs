@@ -0,0 +1,316 @@
+//! Highlight overlay: colored ranges drawn over the CodeEdit text
+//! (incremental search and 'hlsearch' matches, clever-f targets, sneak labels,
+//! highlight on yank)
+//!
+//! CodeEdit has no API for arbitrary range highlighting, so a transparent
+//! Control is added as a child of the CodeEdit and draws rectangles for each
+//! range. It is redrawn whenever the CodeEdit redraws (scroll, edits, resize).
+
+use super::sneak::SneakState;
+use super::GodotNeovimPlugin;
+use crate::neovim::YankEvent;
+use crate::settings;
+use godot::classes::control::{LayoutPreset, MouseFilter};
+use godot::classes::Control;
+use godot::prelude::*;
+
+/// A highlighted range on a single line (character columns, end exclusive)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct HighlightRange {
+    /// Line (0-indexed)
+    pub line: i32,
+    /// Start column (0-indexed, character position)
+    pub start_col: i32,
+    /// End column (exclusive)
+    pub end_col: i32,
+}
+
+impl GodotNeovimPlugin {
+    /// Get the overlay for the current editor, creating it if needed
+    fn ensure_highlight_overlay(&mut self) -> Option<Gd<Control>> {
+        let mut editor = self.current_editor.clone()?;
+        if !editor.is_instance_valid() {
+            return None;
+        }
+
+        // Reuse the overlay only if it belongs to the current editor
+        if let Some(ref overlay) = self.highlight_overlay {
+            if overlay.is_instance_valid()
+                && overlay
+                    .get_parent()
+                    .is_some_and(|parent| parent.instance_id() == editor.instance_id())
+            {
+                return Some(overlay.clone());
+            }
+        }
+        self.free_highlight_overlay();
+
+        let mut overlay = Control::new_alloc();
+        overlay.set_name("GodotNeovimHighlightOverlay");
+        overlay.set_mouse_filter(MouseFilter::IGNORE);
+        overlay.set_anchors_and_offsets_preset(LayoutPreset::FULL_RECT);
+        overlay.connect("draw", &self.base().callable("on_highlight_overlay_draw"));
+        editor.add_child(&overlay);
+
+        // Redraw the overlay whenever the editor redraws (scroll, edits, resize)
+        let editor_draw = self.base().callable("on_highlight_editor_draw");
+        if !editor.is_connected("draw", &editor_draw) {
+            editor.connect("draw", &editor_draw);
+        }
+
+        self.highlight_overlay = Some(overlay.clone());
+        Some(overlay)
+    }
+
+    /// Free the overlay (e.g. when the editor changes)
+    pub(super) fn free_highlight_overlay(&mut self) {
+        if let Some(mut overlay) = self.highlight_overlay.take() {
+            if overlay.is_instance_valid() {
+                overlay.queue_free();
+            }
+        }
+    }
+
+    /// Replace the search match highlights
+    pub(super) fn set_search_highlights(&mut self, ranges: Vec<HighlightRange>) {
+        self.search_highlights = ranges;
+        self.redraw_highlight_overlay();
+    }
+
+    /// Remove all search match highlights
+    pub(super) fn clear_search_highlights(&mut self) {
+        if self.search_highlights.is_empty() {
+            return;
+        }
+        self.search_highlights.clear();
+        self.redraw_highlight_overlay();
+    }
+
+    /// Briefly highlight the region of a yank (like vim.hl.on_yank)
+    pub(super) fn flash_yank_highlight(&mut self, yank: &YankEvent) {
+        let duration_ms = settings::get_yank_highlight_duration();
+        if duration_ms == 0 || yank.operator != "y" {
+            return;
+        }
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        if !editor.is_instance_valid() {
+            return;
+        }
+
+        let line_count = editor.get_line_count();
+        let (start_line, start_col) = (yank.start.0 as i32, yank.start.1 as i32);
+        let (end_line, end_col) = (yank.end.0 as i32, yank.end.1 as i32);
+        if start_line < 0 || end_line < start_line || start_line >= line_count {
+            return;
+        }
+
+        let mut ranges = Vec::new();
+        for line in start_line..=end_line.min(line_count - 1) {
+            let line_len = editor.get_line(line).to_string().chars().count() as i32;
+            let (from, to) = if yank.regtype == "V" {
+                (0, line_len)
+            } else if yank.regtype.starts_with('\u{16}') {
+                // Blockwise: same columns on every line
+                (start_col.min(end_col), start_col.max(end_col) + 1)
+            } else {
+                // Charwise: end column is inclusive
+                let from = if line == start_line { start_col } else { 0 };
+                let to = if line == end_line {
+                    end_col + 1
+                } else {
+                    line_len
+                };
+                (from, to)
+            };
+            ranges.push(HighlightRange {
+                line,
+                start_col: from.min(line_len),
+                end_col: to.min(line_len),
+            });
+        }
+
+        self.yank_highlights = ranges;
+        self.yank_highlight_id += 1;
+        self.redraw_highlight_overlay();
+
+        // Clear after the configured duration (a newer yank invalidates older timers)
+        let id = self.yank_highlight_id;
+        let callable = self
+            .base()
+            .callable("on_yank_highlight_timeout")
+            .bind(&[id.to_variant()]);
+        if let Some(mut timer) = self
+            .base()
+            .get_tree()
+            .create_timer(duration_ms as f64 / 1000.0)
+        {
+            timer.connect("timeout", &callable);
+        }
+    }
+
+    /// Remove the yank highlight if it is still the one started with `id`
+    pub(super) fn clear_yank_highlight(&mut self, id: i64) {
+        if id != self.yank_highlight_id || self.yank_highlights.is_empty() {
+            return;
+        }
+        self.yank_highlights.clear();
+        self.redraw_highlight_overlay();
+    }
+
+    /// Request a redraw of the overlay (creates it if there is something to draw)
+    pub(super) fn redraw_highlight_overlay(&mut self) {
+        let overlay = if self.search_highlights.is_empty()
+            && self.find_char_highlights.is_empty()
+            && self.yank_highlights.is_empty()
+            && !matches!(self.pending_sneak, Some(SneakState::Labels(_)))
+        {
+            self.highlight_overlay.clone()
+        } else {
+            self.ensure_highlight_overlay()
+        };
+        if let Some(mut overlay) = overlay {
+            if overlay.is_instance_valid() {
+                overlay.queue_redraw();
+            }
+        }
+    }
+
+    /// Draw all highlight ranges (called from the overlay's draw signal)
+    pub(super) fn draw_highlight_overlay(&mut self) {
+        let Some(mut overlay) = self.highlight_overlay.clone() else {
+            return;
+        };
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        if !overlay.is_instance_valid() || !editor.is_instance_valid() {
+            return;
+        }
+
+        let fill = editor.get_theme_color("search_result_color");
+        let border = editor.get_theme_color("search_result_border_color");
+
+        // Only ranges on visible lines can produce rects
+        let first_line = editor.get_first_visible_line();
+        let last_line = editor.get_last_full_visible_line() + 1;
+
+        // Search highlights are ordered by line: skip straight to the visible ones
+        let start = self
+            .search_highlights
+            .partition_point(|r| r.line < first_line);
+        for range in self.search_highlights[start..]
+            .iter()
+            .take_while(|r| r.line <= last_line)
+        {
+            for rect in Self::highlight_rects(editor, range) {
+                overlay.draw_rect(rect, fill);
+                overlay
+                    .draw_rect_ex(rect, border)
+                    .filled(false)
+                    .width(1.0)
+                    .done();
+            }
+        }
+
+        let find_char_color = editor.get_theme_color("word_highlighted_color");
+        for range in self
+            .find_char_highlights
+            .iter()
+            .filter(|r| r.line >= first_line && r.line <= last_line)
+        {
+            for rect in Self::highlight_rects(editor, range) {
+                overlay.draw_rect(rect, find_char_color);
+            }
+        }
+
+        let yank_color = settings::get_yank_highlight_color();
+        for range in self
+            .yank_highlights
+            .iter()
+            .filter(|r| r.line >= first_line && r.line <= last_line)
+        {
+            for rect in Self::highlight_rects(editor, range) {
+                overlay.draw_rect(rect, yank_color);
+            }
+        }
+
+        // Sneak labels: the label over the first character of each target
+        if let Some(SneakState::Labels(ref targets)) = self.pending_sneak {
+            let font = editor.get_theme_font("font");
+            let font_size = editor.get_theme_font_size("font_size");
+            let label_color = editor.get_theme_color("caret_color");
+            let text_color = editor.get_theme_color("background_color");
+            for target in targets
+                .iter()
+                .filter(|t| t.line >= first_line && t.line <= last_line)
+            {
+                let r = editor.get_rect_at_line_column(target.line, target.col);
+                if r.position.x < 0 || r.position.y < 0 {
+                    continue;
+                }
+                let rect = Rect2::new(
+                    Vector2::new(r.position.x as f32, r.position.y as f32),
+                    Vector2::new(r.size.x as f32, r.size.y as f32),
+                );
+                overlay.draw_rect(rect, label_color);
+                if let Some(ref font) = font {
+                    // Vertically centered in the line
+                    let ascent = font.get_ascent_ex().font_size(font_size).done();
+                    let height = font.get_height_ex().font_size(font_size).done();
+                    let baseline = rect.position.y + (rect.size.y - height) / 2.0 + ascent;
+                    overlay
+                        .draw_string_ex(
+                            font,
+                            Vector2::new(rect.position.x, baseline),
+                            &target.label.to_string(),
+                        )
+                        .font_size(font_size)
+                        .modulate(text_color)
+                        .done();
+                }
+            }
+        }
+    }
+
+    /// Compute on-screen rectangles for a range (one per visual row for wrapped lines)
+    fn highlight_rects(
+        editor: &Gd<godot::classes::CodeEdit>,
+        range: &HighlightRange,
+    ) -> Vec<Rect2> {
+        let mut rects: Vec<Rect2> = Vec::new();
+
+        // Empty match: thin bar at the match position
+        if range.end_col <= range.start_col {
+            let r = editor.get_rect_at_line_column(range.line, range.start_col);
+            if r.position.x >= 0 && r.position.y >= 0 {
+                rects.push(Rect2::new(
+                    Vector2::new(r.position.x as f32, r.position.y as f32),
+                    Vector2::new(2.0, r.size.y as f32),
+                ));
+            }
+            return rects;
+        }
+
+        // Merge per-character rects that share a visual row
+        for col in range.start_col..range.end_col {
+            let r = editor.get_rect_at_line_column(range.line, col);
+            if r.position.x < 0 || r.position.y < 0 {
+                continue;
+            }
+            let rect = Rect2::new(
+                Vector2::new(r.position.x as f32, r.position.y as f32),
+                Vector2::new(r.size.x as f32, r.size.y as f32),
+            );
+            match rects.last_mut() {
+                Some(last) if last.position.y == rect.position.y => {
+                    *last = last.merge(rect);
+                }
+                _ => rects.push(rect),
+            }
+        }
+
+        rects
+    }
+}
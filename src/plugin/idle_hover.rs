@@ -0,0 +1,68 @@
+//! Idle hover: when the caret rests in Normal mode, show the diagnostics of its
+//! line or the documentation of the symbol under it
+//!
+//! Resting is Neovim's CursorHold (forwarded by the autocmd bridge), with
+//! 'updatetime' set to the Idle Hover Delay setting. The float is the hover float,
+//! so like K's it goes away on the next key.
+
+use super::float::FloatPlacement;
+use super::{EditorType, GodotNeovimPlugin};
+use crate::settings;
+
+impl GodotNeovimPlugin {
+    /// CursorHold at (line, col): show the idle hover float if the caret is still there
+    pub(super) fn on_cursor_hold(&mut self, line: i64, col: i64) {
+        if !settings::get_idle_hover()
+            || !matches!(self.current_mode.as_str(), "n" | "normal")
+            || !self.last_key.is_empty()
+            || self.hover_float.is_some()
+        {
+            return;
+        }
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        // The caret moved in Godot after Neovim's cursor rested
+        if (
+            editor.get_caret_line() as i64,
+            editor.get_caret_column() as i64,
+        ) != (line, col)
+        {
+            return;
+        }
+
+        if self.has_line_diagnostics(line as i32) {
+            self.show_line_diagnostics();
+        } else {
+            self.show_idle_documentation(line as u32, col as u32);
+        }
+    }
+
+    /// Show the LSP hover text of the symbol at (line, col), silently doing nothing
+    /// without documentation (unlike K)
+    fn show_idle_documentation(&mut self, line: u32, col: u32) {
+        if self.godot_lsp.is_none() || self.current_editor_type != EditorType::Script {
+            return;
+        }
+        let Some((lsp, uri)) = self.prepare_lsp_request("Idle hover") else {
+            return;
+        };
+
+        let content = match lsp.hover(&uri, line, col) {
+            Ok(Some(hover)) => Self::hover_contents_to_string(&hover),
+            Ok(None) => return,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Idle hover: LSP error: {}", e);
+                return;
+            }
+        };
+        if content.trim().is_empty() {
+            return;
+        }
+
+        self.hover_float = self.create_editor_float(
+            &Self::markdown_to_bbcode(&content),
+            FloatPlacement::BelowCaret,
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Input method (IME) support in Insert mode
+//!
+//! Insert mode text is typed in Godot, where an IME composes it: the text being
+//! composed is not in the CodeEdit yet. Escape during a composition belongs to the
+//! IME (it commits or cancels the text), so leaving Insert mode waits until the
+//! composition has ended; syncing the buffer to Neovim earlier would lose the
+//! composed text, or insert it again after the exit.
+//!
+//! With the IME Auto Off setting, the IME is turned off while not in Insert mode,
+//! so Normal mode keys reach Vim uncomposed, and turned on again in Insert mode.
+
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::DisplayServer;
+use godot::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How long leaving Insert mode waits for the IME to end its composition before
+/// committing the composed text itself
+const IME_EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl GodotNeovimPlugin {
+    /// Escape while the IME is composing: leave Insert mode once the composition has
+    /// ended (see poll_ime_insert_exit). Returns false if there is no composition.
+    pub(super) fn defer_insert_exit_for_ime(&mut self) -> bool {
+        let composing = self
+            .current_editor
+            .as_ref()
+            .is_some_and(|editor| editor.is_instance_valid() && editor.has_ime_text());
+        if !composing {
+            return false;
+        }
+        // The key is not marked as handled: the IME takes it
+        if self.ime_exit_requested.is_none() {
+            crate::verbose_print!("[godot-neovim] IME composing - Insert mode exit deferred");
+            self.ime_exit_requested = Some(Instant::now());
+        }
+        true
+    }
+
+    /// Leave Insert mode once the IME composition that deferred the exit has ended
+    /// (called every frame while an exit is deferred)
+    pub(super) fn poll_ime_insert_exit(&mut self) {
+        let Some(requested) = self.ime_exit_requested else {
+            return;
+        };
+        if !self.is_insert_mode() {
+            self.ime_exit_requested = None;
+            return;
+        }
+
+        if let Some(ref mut editor) = self.current_editor {
+            if editor.is_instance_valid() && editor.has_ime_text() {
+                if requested.elapsed() < IME_EXIT_TIMEOUT {
+                    return;
+                }
+                // The IME kept composing: commit the text, like Enter would
+                crate::verbose_print!("[godot-neovim] IME still composing - committing");
+                editor.apply_ime();
+            }
+        }
+
+        self.ime_exit_requested = None;
+        crate::verbose_print!("[godot-neovim] IME composition ended - leaving Insert mode");
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push("<Esc>".to_string());
+        }
+        self.send_escape();
+    }
+
+    /// IME Auto Off: turn the IME of the editor's window on in Insert mode, off otherwise
+    pub(super) fn set_ime_for_insert_mode(&self, insert: bool) {
+        if !settings::get_ime_auto_off() {
+            return;
+        }
+        let window_id = self
+            .current_editor
+            .as_ref()
+            .filter(|editor| editor.is_instance_valid())
+            .and_then(|editor| editor.get_window())
+            .map_or(DisplayServer::MAIN_WINDOW_ID, |window| {
+                window.get_window_id()
+            });
+        DisplayServer::singleton()
+            .window_set_ime_active_ex(insert)
+            .window_id(window_id)
+            .done();
+        crate::verbose_print!(
+            "[godot-neovim] IME {} (window {})",
+            if insert { "on" } else { "off" },
+            window_id
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Indent detection: tabs or spaces and the indent width of the opened script
+//!
+//! Godot indents every script like Editor Settings > Text Editor > Behavior >
+//! Indent says. A script indented otherwise (e.g. 4 spaces from an external
+//! editor) gets its own indentation on the CodeEdit and in the Neovim buffer
+//! ('expandtab', 'shiftwidth', 'tabstop') each time it is shown, so `>>`, `o`/`O`
+//! and Insert mode keep its alignment.
+
+use super::GodotNeovimPlugin;
+use crate::neovim::IndentOptions;
+use crate::settings;
+use godot::prelude::*;
+
+/// Lines looked at (indentation is consistent enough at the top of a script)
+const DETECT_LINES: usize = 2000;
+
+/// Indent widths accepted for space indentation
+const SPACE_WIDTHS: std::ops::RangeInclusive<usize> = 2..=8;
+
+/// Indentation used by `lines`: tabs (with the width of `fallback`) or spaces
+/// (with the most common indent step). None when nothing is indented.
+pub(super) fn detect_indent(lines: &[String], fallback: IndentOptions) -> Option<IndentOptions> {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    // Occurrences of each indent step between consecutive space-indented lines
+    let mut steps = [0usize; 9];
+    let mut previous = 0;
+
+    for line in lines.iter().take(DETECT_LINES) {
+        let code = line.trim_start();
+        // Blank lines and comments (often aligned freely) say nothing
+        if code.is_empty() || code.starts_with('#') {
+            continue;
+        }
+        let lead = &line[..line.len() - code.len()];
+        if lead.starts_with('\t') {
+            tab_lines += 1;
+            previous = 0;
+            continue;
+        }
+        let spaces = lead.len();
+        if lead.bytes().any(|b| b != b' ') {
+            continue;
+        }
+        if spaces > previous && SPACE_WIDTHS.contains(&(spaces - previous)) {
+            steps[spaces - previous] += 1;
+            space_lines += 1;
+        } else if spaces > 0 {
+            space_lines += 1;
+        }
+        previous = spaces;
+    }
+
+    if tab_lines == 0 && space_lines == 0 {
+        return None;
+    }
+    if tab_lines >= space_lines {
+        return Some(IndentOptions {
+            use_spaces: false,
+            indent_size: fallback.indent_size,
+        });
+    }
+    // The most common step, the smaller one on a tie
+    let width = SPACE_WIDTHS
+        .rev()
+        .max_by_key(|&width| steps[width])
+        .filter(|&width| steps[width] > 0)?;
+    Some(IndentOptions {
+        use_spaces: true,
+        indent_size: width as i32,
+    })
+}
+
+impl GodotNeovimPlugin {
+    /// Indentation for the current script: detected from `lines` (and set on the
+    /// CodeEdit) when the Detect Indent setting is on, else `fallback`
+    pub(super) fn script_indent(
+        &mut self,
+        lines: &[String],
+        fallback: IndentOptions,
+    ) -> IndentOptions {
+        if !settings::get_detect_indent() {
+            return fallback;
+        }
+        let Some(indent) = detect_indent(lines, fallback) else {
+            return fallback;
+        };
+        if indent.use_spaces != fallback.use_spaces || indent.indent_size != fallback.indent_size {
+            crate::verbose_print!(
+                "[godot-neovim] Detected indent: {} (size {})",
+                if indent.use_spaces { "spaces" } else { "tabs" },
+                indent.indent_size
+            );
+        }
+        if let Some(ref mut editor) = self.current_editor {
+            // Deferred: Godot applies its own indent settings to the CodeEdit after
+            // settings_changed
+            editor.call_deferred("set_indent_using_spaces", &[indent.use_spaces.to_variant()]);
+            editor.call_deferred("set_indent_size", &[indent.indent_size.to_variant()]);
+        }
+        indent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_detect_indent() {
+        let fallback = IndentOptions {
+            use_spaces: false,
+            indent_size: 4,
+        };
+        let detect = |text: &str| {
+            detect_indent(&lines(text), fallback)
+                .map(|indent| (indent.use_spaces, indent.indent_size))
+        };
+
+        assert_eq!(
+            detect("extends Node\n\nfunc _ready():\n\tpass\n"),
+            Some((false, 4))
+        );
+        assert_eq!(
+            detect("func f(x):\n    if x:\n        return 1\n    return 0\n"),
+            Some((true, 4))
+        );
+        assert_eq!(
+            detect("func f(x):\n  if x:\n    return 1\n  # comment\n  return 0\n"),
+            Some((true, 2))
+        );
+        // Nested blocks indented with tabs
+        assert_eq!(
+            detect("func f():\n\tvar a = [\n\t\t1,\n\t]\n\tif a:\n\t\tpass\n"),
+            Some((false, 4))
+        );
+        assert_eq!(detect("extends Node\nvar x = 1\n"), None);
+    }
+}
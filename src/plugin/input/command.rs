@@ -12,25 +12,37 @@ impl GodotNeovimPlugin {
         let keycode = key_event.get_keycode();
         let ctrl_pressed = key_event.is_ctrl_pressed();
 
+        // Any key but Tab keeps the completed text and closes the wildmenu
+        if keycode != Key::TAB {
+            self.close_wildmenu();
+        }
+
         // Escape or Ctrl+[ closes command line
         if keycode == Key::ESCAPE || (ctrl_pressed && keycode == Key::BRACKETLEFT) {
             self.close_command_line();
         } else if keycode == Key::ENTER {
             self.execute_command();
+        } else if keycode == Key::TAB {
+            // Complete command names and file/buffer paths (Shift+Tab: previous)
+            self.complete_command_line(key_event.is_shift_pressed());
+            self.command_history_index = None;
         } else if keycode == Key::BACKSPACE {
             // Remove last character (but keep the ':')
             if self.command_buffer.len() > 1 {
                 self.command_buffer.pop();
                 self.update_command_display();
+                self.update_substitute_preview();
             }
             // Reset history browsing when editing
             self.command_history_index = None;
         } else if keycode == Key::UP {
             // Browse command history (older)
             self.command_history_up();
+            self.update_substitute_preview();
         } else if keycode == Key::DOWN {
             // Browse command history (newer)
             self.command_history_down();
+            self.update_substitute_preview();
         } else {
             // Append character to command buffer
             let unicode = key_event.get_unicode();
@@ -38,6 +50,7 @@ impl GodotNeovimPlugin {
                 if let Some(c) = char::from_u32(unicode) {
                     self.command_buffer.push(c);
                     self.update_command_display();
+                    self.update_substitute_preview();
                     // Reset history browsing when typing
                     self.command_history_index = None;
                 }
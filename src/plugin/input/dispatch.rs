@@ -4,8 +4,10 @@
 //! Instead of directly calling action_*_impl(), returns a VarDictionary
 //! telling GDScript what key was resolved and whether to dispatch it.
 
+use super::super::layout::is_command_chord;
+use super::super::leader::{LeaderKey, LEADER_PREFIX};
+use super::super::window::WINDOW_PREFIX;
 use super::super::GodotNeovimPlugin;
-use godot::classes::Input;
 use godot::global::Key;
 use godot::prelude::*;
 
@@ -32,7 +34,9 @@ impl GodotNeovimPlugin {
     }
 
     /// Create a result dict indicating the key should be dispatched to GDScript keymap
-    fn dispatch_key(&self, resolved_key: &str) -> VarDictionary {
+    /// The typed count goes with the key to the (deferred) action
+    fn dispatch_key(&mut self, resolved_key: &str) -> VarDictionary {
+        self.dispatch_count();
         self.mark_input_handled();
         let mut dict = VarDictionary::new();
         dict.set(KEY_NEEDS_DISPATCH, true);
@@ -64,7 +68,7 @@ impl GodotNeovimPlugin {
         // (Mode routing is done by input() before calling this)
         // =====================================================================
         let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
 
         // ----- Ctrl+/ (toggle comment) → pass through to Godot -----
         if key_event.is_command_or_control_pressed() && keycode == Key::SLASH {
@@ -79,11 +83,16 @@ impl GodotNeovimPlugin {
             }
         }
 
-        // ----- 'o' in visual mode: toggle selection direction (internal) -----
+        // ----- Alt+key combinations (sub-word motions) → dispatch to GDScript keymap -----
+        if let Some(resolved) = self.resolve_alt_key(key_event) {
+            return self.dispatch_key(&resolved);
+        }
+
+        // ----- 'o' in visual mode: toggle selection direction (internal, not zo) -----
         if Self::is_visual_mode(&self.current_mode)
-            && keycode == Key::O
-            && !key_event.is_ctrl_pressed()
-            && !key_event.is_shift_pressed()
+            && self.last_key != "z"
+            && vim_char == Some('o')
+            && !is_command_chord(key_event)
         {
             self.send_keys("o");
             if self.current_mode == "v" {
@@ -95,27 +104,45 @@ impl GodotNeovimPlugin {
             return self.dispatch_handled();
         }
 
+        // ----- Surround (ys, cs, ds): s after the y, c or d operator -----
+        if self.try_start_surround(key_event) {
+            return self.dispatch_handled();
+        }
+
+        // ----- Exchange (cx): x after the c operator -----
+        if self.try_start_exchange(key_event) {
+            return self.dispatch_handled();
+        }
+
+        // ----- Register-aware operations (before g-prefix resolution for "agp) -----
+        if self.handle_register_key(key_event) {
+            return self.dispatch_handled();
+        }
+
         // ----- Pending prefix resolution -----
         // Must check before single-key handling to resolve g+key, [+key, etc.
         if let Some(result) = self.resolve_pending_prefix(key_event) {
             return result;
         }
 
-        // ----- Register-aware operations -----
-        if let Some(result) = self.handle_register_operations(key_event) {
-            return result;
-        }
-
         // ----- Count prefix (digits) -----
-        if let Some(c) = unicode_char {
+        // Held until the command: Neovim gets it as a key prefix (send_keys),
+        // local commands take it with get_and_clear_count()
+        if let Some(c) = vim_char {
             if c.is_ascii_digit() && (c != '0' || !self.count_buffer.is_empty()) {
                 self.count_buffer.push(c);
-                self.send_keys(&c.to_string());
                 self.last_key_time = Some(std::time::Instant::now());
                 return self.dispatch_handled();
             }
         }
 
+        // ----- Leader key (starts a <leader> mapping) -----
+        if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+            if self.try_start_leader(&keys) {
+                return self.dispatch_handled();
+            }
+        }
+
         // ----- Side-effect keys (handled internally) -----
         if let Some(result) = self.handle_side_effect_keys(key_event) {
             return result;
@@ -131,7 +158,7 @@ impl GodotNeovimPlugin {
             return result;
         }
 
-        // ----- Prefix key accumulation (g, [, ], Z) -----
+        // ----- Prefix key accumulation (g, [, ], z, Z) -----
         if let Some(result) = self.handle_prefix_key_accumulation(key_event) {
             return result;
         }
@@ -144,8 +171,8 @@ impl GodotNeovimPlugin {
         }
 
         // ----- Visual mode type tracking -----
-        if keycode == Key::V && !key_event.is_ctrl_pressed() {
-            if key_event.is_shift_pressed() {
+        if matches!(vim_char, Some('v' | 'V')) && !is_command_chord(key_event) {
+            if vim_char == Some('V') {
                 self.visual_mode_type = 'V';
             } else {
                 self.visual_mode_type = 'v';
@@ -157,20 +184,14 @@ impl GodotNeovimPlugin {
         // =====================================================================
         if let Some(keys) = self.key_event_to_nvim_string(key_event) {
             // Record key for macro if recording
-            if self.recording_macro.is_some() && !self.playing_macro {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push(keys.clone());
             }
 
-            // Handle scroll commands (zz, zt, zb) after sending key
-            let completed = self.send_keys(&keys);
-            let scroll_handled = if completed {
-                self.handle_scroll_command(&keys)
-            } else {
-                false
-            };
+            self.send_keys(&keys);
 
             // Track last key for sequence detection
-            if !scroll_handled && !self.is_insert_mode() && !self.is_replace_mode() {
+            if !self.is_insert_mode() && !self.is_replace_mode() {
                 if self.is_in_visual_mode() {
                     if keys == "i" || keys == "a" {
                         self.set_last_key(keys);
@@ -204,6 +225,7 @@ impl GodotNeovimPlugin {
             | Key::O
             | Key::I
             | Key::G
+            | Key::N
             | Key::R => {
                 let ch = match keycode {
                     Key::B => 'b',
@@ -217,6 +239,7 @@ impl GodotNeovimPlugin {
                     Key::O => 'o',
                     Key::I => 'i',
                     Key::G => 'g',
+                    Key::N => 'n',
                     Key::R => 'r',
                     _ => unreachable!(),
                 };
@@ -226,6 +249,26 @@ impl GodotNeovimPlugin {
         }
     }
 
+    // =====================================================================
+    // Helper: Resolve Alt+key to Neovim notation for GDScript dispatch
+    // Only keys with a default action (sub-word motions); other Alt combinations
+    // fall through and are sent to Neovim as <A-x>
+    // =====================================================================
+    fn resolve_alt_key(&self, key_event: &Gd<godot::classes::InputEventKey>) -> Option<String> {
+        if !key_event.is_alt_pressed()
+            || key_event.is_ctrl_pressed()
+            || key_event.is_shift_pressed()
+        {
+            return None;
+        }
+        match key_event.get_keycode() {
+            Key::W => Some("<A-w>".to_string()),
+            Key::B => Some("<A-b>".to_string()),
+            Key::E => Some("<A-e>".to_string()),
+            _ => None,
+        }
+    }
+
     // =====================================================================
     // Helper: Resolve dispatchable single keys (/, ?, :, n, N, *, #, u, K)
     // These open Godot-side UI (search, command line) or call LSP (goto def,
@@ -236,49 +279,47 @@ impl GodotNeovimPlugin {
         &self,
         key_event: &Gd<godot::classes::InputEventKey>,
     ) -> Option<String> {
-        let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
         let ctrl = key_event.is_ctrl_pressed();
-        let shift = key_event.is_shift_pressed();
 
         if ctrl {
             return None;
         }
 
         // '/' - forward search
-        if unicode_char == Some('/') {
+        if vim_char == Some('/') {
             return Some("/".to_string());
         }
         // '?' - backward search
-        if unicode_char == Some('?') {
+        if vim_char == Some('?') {
             return Some("?".to_string());
         }
         // ':' - command line
-        if unicode_char == Some(':') {
+        if vim_char == Some(':') {
             return Some(":".to_string());
         }
         // '*' - search word forward
-        if unicode_char == Some('*') {
+        if vim_char == Some('*') {
             return Some("*".to_string());
         }
         // '#' - search word backward
-        if unicode_char == Some('#') {
+        if vim_char == Some('#') {
             return Some("#".to_string());
         }
         // 'n' - search next (not Shift)
-        if keycode == Key::N && !shift {
+        if vim_char == Some('n') {
             return Some("n".to_string());
         }
         // 'N' - search previous (Shift+N)
-        if keycode == Key::N && shift {
+        if vim_char == Some('N') {
             return Some("N".to_string());
         }
         // 'u' - undo (not Shift, not after 'g' prefix which is gu = lowercase operator)
-        if keycode == Key::U && !shift && self.last_key != "g" {
+        if vim_char == Some('u') && self.last_key != "g" {
             return Some("u".to_string());
         }
         // 'K' - documentation (Shift+K, not after 'g' prefix which is gK)
-        if keycode == Key::K && shift && self.last_key != "g" {
+        if vim_char == Some('K') && self.last_key != "g" {
             return Some("K".to_string());
         }
 
@@ -286,68 +327,111 @@ impl GodotNeovimPlugin {
     }
 
     // =====================================================================
-    // Helper: Resolve pending prefix keys (g, [, ], Z, >, <)
+    // Helper: Resolve pending prefix keys (g, z, [, ], Z, >, <)
     // =====================================================================
     fn resolve_pending_prefix(
         &mut self,
         key_event: &Gd<godot::classes::InputEventKey>,
     ) -> Option<VarDictionary> {
-        let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
+
+        // --- Leader mappings (<leader>ff) ---
+        if self.last_key.starts_with(LEADER_PREFIX) {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                return Some(match self.feed_leader_key(&keys) {
+                    LeaderKey::Mapped(resolved) => self.dispatch_key(&resolved),
+                    LeaderKey::Pending | LeaderKey::Unmapped => self.dispatch_handled(),
+                });
+            }
+            // Modifier-only key - don't clear prefix
+            return Some(self.dispatch_handled());
+        }
 
         // --- g-prefix resolution ---
         if self.last_key == "g" {
             if let Some(keys) = self.key_event_to_nvim_string(key_event) {
                 let resolved = format!("g{}", keys);
                 self.clear_last_key();
+                // gc waits for its motion (or toggles the Visual selection)
+                if keys == "c" {
+                    self.start_comment_operator();
+                    return Some(self.dispatch_handled());
+                }
+                // gr waits for its motion (grr: LSP references), unless gr is
+                // references itself
+                if keys == "r" && Self::gr_is_replace_operator() {
+                    self.start_replace_operator('"');
+                    return Some(self.dispatch_handled());
+                }
+                // gn/gN select the next match charwise
+                if keys == "n" || keys == "N" {
+                    self.visual_mode_type = 'v';
+                }
+                // g* / g# search the word under the cursor without 'smartcase'
+                if keys == "*" || keys == "#" {
+                    self.search_smartcase = false;
+                }
                 return Some(self.dispatch_key(&resolved));
             }
             // Modifier-only key - don't clear prefix
             return Some(self.dispatch_handled());
         }
 
+        // --- z-prefix resolution (folds are Godot's) ---
+        if self.last_key == "z" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                self.finish_z_command(&keys);
+            }
+            // Modifier-only key - don't clear prefix
+            return Some(self.dispatch_handled());
+        }
+
         // --- [-prefix resolution ---
         if self.last_key == "[" {
             // [[ - use keycode for keyboard layout independence
-            if keycode == Key::BRACKETLEFT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some('[') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 self.send_keys("[[");
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("[[".to_string());
                 }
                 return Some(self.dispatch_handled());
             }
             // [] - use keycode
-            if keycode == Key::BRACKETRIGHT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some(']') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 self.send_keys("[]");
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("[]".to_string());
                 }
                 return Some(self.dispatch_handled());
             }
             // [p
-            if keycode == Key::P && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+            if vim_char == Some('p') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 self.send_keys("[p");
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("[p".to_string());
                 }
                 return Some(self.dispatch_handled());
             }
-            match unicode_char {
+            match vim_char {
+                Some('f') => {
+                    self.clear_last_key();
+                    self.move_to_function(false);
+                    return Some(self.dispatch_handled());
+                }
+                Some('d') => {
+                    self.clear_last_key();
+                    self.move_to_diagnostic(false);
+                    return Some(self.dispatch_handled());
+                }
                 Some('{') | Some('(') | Some('m') => {
-                    let ch = unicode_char.unwrap();
+                    let ch = vim_char.unwrap();
                     let cmd = format!("[{}", ch);
                     self.clear_last_key();
                     self.send_keys(&cmd);
-                    if self.recording_macro.is_some() && !self.playing_macro {
+                    if self.recording_macro.is_some() {
                         self.macro_buffer.push(cmd);
                     }
                     return Some(self.dispatch_handled());
@@ -366,45 +450,49 @@ impl GodotNeovimPlugin {
         // --- ]-prefix resolution ---
         if self.last_key == "]" {
             // ]] - use keycode
-            if keycode == Key::BRACKETRIGHT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some(']') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 self.send_keys("]]");
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("]]".to_string());
                 }
                 return Some(self.dispatch_handled());
             }
             // ][ - use keycode
-            if keycode == Key::BRACKETLEFT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some('[') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 self.send_keys("][");
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("][".to_string());
                 }
                 return Some(self.dispatch_handled());
             }
             // ]p
-            if keycode == Key::P && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+            if vim_char == Some('p') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 self.send_keys("]p");
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("]p".to_string());
                 }
                 return Some(self.dispatch_handled());
             }
-            match unicode_char {
+            match vim_char {
+                Some('f') => {
+                    self.clear_last_key();
+                    self.move_to_function(true);
+                    return Some(self.dispatch_handled());
+                }
+                Some('d') => {
+                    self.clear_last_key();
+                    self.move_to_diagnostic(true);
+                    return Some(self.dispatch_handled());
+                }
                 Some('}') | Some(')') | Some('m') => {
-                    let ch = unicode_char.unwrap();
+                    let ch = vim_char.unwrap();
                     let cmd = format!("]{}", ch);
                     self.clear_last_key();
                     self.send_keys(&cmd);
-                    if self.recording_macro.is_some() && !self.playing_macro {
+                    if self.recording_macro.is_some() {
                         self.macro_buffer.push(cmd);
                     }
                     return Some(self.dispatch_handled());
@@ -420,11 +508,11 @@ impl GodotNeovimPlugin {
 
         // --- Z-prefix resolution ---
         if self.last_key == "Z" {
-            if keycode == Key::Z && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+            if vim_char == Some('Z') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 return Some(self.dispatch_key("ZZ"));
             }
-            if keycode == Key::Q && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+            if vim_char == Some('Q') && !is_command_chord(key_event) {
                 self.clear_last_key();
                 return Some(self.dispatch_key("ZQ"));
             }
@@ -434,15 +522,19 @@ impl GodotNeovimPlugin {
 
         // --- >-prefix resolution ---
         if self.last_key == ">" {
-            if let Some(ch) = unicode_char {
+            if let Some(ch) = vim_char {
                 if ch == '>' {
                     // >> indent
                     self.send_keys(">>");
                     self.clear_last_key();
-                    if self.recording_macro.is_some() && !self.playing_macro {
+                    if self.recording_macro.is_some() {
                         self.macro_buffer.push(">>".to_string());
                     }
                     return Some(self.dispatch_handled());
+                } else if ch == '/' || ch == '?' {
+                    // > + search motion: the pattern is typed in search mode
+                    self.open_operator_search(">", ch == '/');
+                    return Some(self.dispatch_handled());
                 } else {
                     // > + motion
                     self.send_keys(&format!(">{}", ch));
@@ -454,15 +546,19 @@ impl GodotNeovimPlugin {
 
         // --- <-prefix resolution ---
         if self.last_key == "<" {
-            if let Some(ch) = unicode_char {
+            if let Some(ch) = vim_char {
                 if ch == '<' {
                     // << unindent
                     self.send_keys("<LT><LT>");
                     self.clear_last_key();
-                    if self.recording_macro.is_some() && !self.playing_macro {
+                    if self.recording_macro.is_some() {
                         self.macro_buffer.push("<<".to_string());
                     }
                     return Some(self.dispatch_handled());
+                } else if ch == '/' || ch == '?' {
+                    // < + search motion: the pattern is typed in search mode
+                    self.open_operator_search("<LT>", ch == '/');
+                    return Some(self.dispatch_handled());
                 } else {
                     // < + motion
                     self.send_keys(&format!("<LT>{}", ch));
@@ -472,169 +568,58 @@ impl GodotNeovimPlugin {
             }
         }
 
-        // --- gq-prefix resolution ---
-        if self.last_key == "gq" {
-            if keycode == Key::Q && !key_event.is_shift_pressed() {
-                self.send_keys("gqq");
-                self.clear_last_key();
-                if self.recording_macro.is_some() && !self.playing_macro {
-                    self.macro_buffer.push("gqq".to_string());
-                }
-                return Some(self.dispatch_handled());
-            }
-            // Other key after gq: gq + motion
+        // --- gc-prefix resolution (comment operator motion, gcc) ---
+        if self.last_key == "gc" {
             if let Some(keys) = self.key_event_to_nvim_string(key_event) {
-                self.send_keys(&format!("gq{}", keys));
-                self.clear_last_key();
+                self.finish_comment_operator(&keys);
                 return Some(self.dispatch_handled());
             }
-        }
-
-        None
-    }
-
-    // =====================================================================
-    // Helper: Handle register-aware operations ("ayy, "ap, etc.)
-    // =====================================================================
-    fn handle_register_operations(
-        &mut self,
-        key_event: &Gd<godot::classes::InputEventKey>,
-    ) -> Option<VarDictionary> {
-        let reg = self.selected_register?;
-        if reg == '\0' {
-            return None; // Waiting for register char - handled by mode_handler
-        }
-
-        let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
-
-        // Count prefix within register context
-        if let Some(c) = unicode_char {
-            if c.is_ascii_digit() && (c != '0' || !self.count_buffer.is_empty()) {
-                self.count_buffer.push(c);
-                return Some(self.dispatch_handled());
-            }
-        }
-
-        // yy - yank line to register
-        if keycode == Key::Y && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            if self.last_key == "y" {
-                let count = self.get_and_clear_count();
-                let count_str = if count > 1 {
-                    count.to_string()
-                } else {
-                    String::new()
-                };
-                self.send_keys(&format!("\"{}{}yy", reg, count_str));
-                self.selected_register = None;
-                self.clear_last_key();
-                return Some(self.dispatch_handled());
-            } else {
-                self.set_last_key("y");
-                return Some(self.dispatch_handled());
-            }
-        }
-
-        // p - paste from register
-        if keycode == Key::P && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            self.send_keys(&format!("\"{}p", reg));
-            self.selected_register = None;
-            self.count_buffer.clear();
-            return Some(self.dispatch_handled());
-        }
-
-        // P - paste before from register
-        if keycode == Key::P && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            self.send_keys(&format!("\"{}P", reg));
-            self.selected_register = None;
-            self.count_buffer.clear();
+            // Modifier-only key - don't clear prefix
             return Some(self.dispatch_handled());
         }
 
-        // dd - delete line to register
-        if keycode == Key::D && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            if self.last_key == "d" {
-                let count = self.get_and_clear_count();
-                let count_str = if count > 1 {
-                    count.to_string()
-                } else {
-                    String::new()
-                };
-                self.send_keys(&format!("\"{}{}dd", reg, count_str));
-                self.selected_register = None;
-                self.clear_last_key();
-                return Some(self.dispatch_handled());
-            } else {
-                self.set_last_key("d");
+        // --- gr-prefix resolution (replace with register motion, grr) ---
+        if self.last_key == "gr" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                if keys == "r" {
+                    self.clear_last_key();
+                    return Some(self.dispatch_key("grr"));
+                }
+                self.finish_replace_operator(&keys);
                 return Some(self.dispatch_handled());
             }
+            // Modifier-only key - don't clear prefix
+            return Some(self.dispatch_handled());
         }
 
-        // cc - change line to register
-        if keycode == Key::C && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            if self.last_key == "c" {
-                let count = self.get_and_clear_count();
-                let count_str = if count > 1 {
-                    count.to_string()
-                } else {
-                    String::new()
-                };
-                self.send_keys(&format!("\"{}{}cc", reg, count_str));
-                self.selected_register = None;
-                self.clear_last_key();
-                return Some(self.dispatch_handled());
-            } else {
-                self.set_last_key("c");
+        // --- cx-prefix resolution (exchange motion, cxx, cxc) ---
+        if self.last_key == "cx" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                self.finish_exchange(&keys);
                 return Some(self.dispatch_handled());
             }
+            // Modifier-only key - don't clear prefix
+            return Some(self.dispatch_handled());
         }
 
-        // Operator + motion with register (y/d/c + motion)
-        if let Some(keys) = self.key_event_to_nvim_string(key_event) {
-            if self.last_key == "y" && keycode != Key::Y {
-                let count = self.get_and_clear_count();
-                let count_str = if count > 1 {
-                    count.to_string()
-                } else {
-                    String::new()
-                };
-                self.send_keys(&format!("\"{}{}y{}", reg, count_str, keys));
-                self.selected_register = None;
-                self.clear_last_key();
-                return Some(self.dispatch_handled());
-            }
-            if self.last_key == "d" && keycode != Key::D {
-                let count = self.get_and_clear_count();
-                let count_str = if count > 1 {
-                    count.to_string()
-                } else {
-                    String::new()
-                };
-                self.send_keys(&format!("\"{}{}d{}", reg, count_str, keys));
-                self.selected_register = None;
+        // --- gq-prefix resolution ---
+        if self.last_key == "gq" {
+            if vim_char == Some('q') {
+                self.send_keys("gqq");
                 self.clear_last_key();
+                if self.recording_macro.is_some() {
+                    self.macro_buffer.push("gqq".to_string());
+                }
                 return Some(self.dispatch_handled());
             }
-            if self.last_key == "c" && keycode != Key::C {
-                let count = self.get_and_clear_count();
-                let count_str = if count > 1 {
-                    count.to_string()
-                } else {
-                    String::new()
-                };
-                self.send_keys(&format!("\"{}{}c{}", reg, count_str, keys));
-                self.selected_register = None;
+            // Other key after gq: gq + motion
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                self.send_keys(&format!("gq{}", keys));
                 self.clear_last_key();
                 return Some(self.dispatch_handled());
             }
         }
 
-        // Other keys cancel register selection
-        if keycode != Key::Y && keycode != Key::D && keycode != Key::C {
-            self.selected_register = None;
-            self.count_buffer.clear();
-        }
-
         None
     }
 
@@ -645,89 +630,71 @@ impl GodotNeovimPlugin {
         &mut self,
         key_event: &Gd<godot::classes::InputEventKey>,
     ) -> Option<VarDictionary> {
-        let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
 
         // ';' - repeat find char same direction
-        if keycode == Key::SEMICOLON && !key_event.is_shift_pressed() {
-            self.repeat_find_char(true);
-            self.send_keys(";");
-            if self.recording_macro.is_some() && !self.playing_macro {
-                self.macro_buffer.push(";".to_string());
-            }
+        if vim_char == Some(';') {
+            self.repeat_find(true);
             return Some(self.dispatch_handled());
         }
 
         // ',' - repeat find char opposite direction
-        if keycode == Key::COMMA && !key_event.is_shift_pressed() {
-            self.repeat_find_char(false);
-            self.send_keys(",");
-            if self.recording_macro.is_some() && !self.playing_macro {
-                self.macro_buffer.push(",".to_string());
-            }
+        if vim_char == Some(',') {
+            self.repeat_find(false);
             return Some(self.dispatch_handled());
         }
 
         // '%' - matching bracket
-        if unicode_char == Some('%') {
-            self.jump_to_matching_bracket();
+        if vim_char == Some('%') {
+            // N% jumps to N percent of the file; only Neovim handles that
+            if self.count_buffer.is_empty() && self.should_preview_motion() {
+                self.jump_to_matching_bracket();
+            }
             self.send_keys("%");
-            if self.recording_macro.is_some() && !self.playing_macro {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("%".to_string());
             }
             return Some(self.dispatch_handled());
         }
 
         // '0' - go to start of line (only when not part of a count, not after g)
-        if unicode_char == Some('0') && !key_event.is_ctrl_pressed() && self.last_key != "g" {
+        if vim_char == Some('0') && !is_command_chord(key_event) && self.last_key != "g" {
             self.move_to_line_start();
             self.send_keys("0");
             return Some(self.dispatch_handled());
         }
 
         // '^' - go to first non-blank (not after g)
-        if unicode_char == Some('^') && self.last_key != "g" {
+        if vim_char == Some('^') && self.last_key != "g" {
             self.move_to_first_non_blank();
             self.send_keys("^");
             return Some(self.dispatch_handled());
         }
 
         // '$' - go to end of line (not after g)
-        if unicode_char == Some('$') && self.last_key != "g" {
+        if vim_char == Some('$') && self.last_key != "g" {
             self.move_to_line_end();
             self.send_keys("$");
             return Some(self.dispatch_handled());
         }
 
         // 'J' - join lines (not after g - that's gJ)
-        if keycode == Key::J
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && self.last_key != "g"
-        {
+        if vim_char == Some('J') && !is_command_chord(key_event) && self.last_key != "g" {
             self.send_keys("J");
             return Some(self.dispatch_handled());
         }
 
         // H/M/L - viewport-relative movement
-        if !key_event.is_ctrl_pressed()
-            && !key_event.is_alt_pressed()
-            && (keycode == Key::H || keycode == Key::M || keycode == Key::L)
-            && key_event.is_shift_pressed()
-        {
-            let key_str = match keycode {
-                Key::H => "H",
-                Key::M => "M",
-                Key::L => "L",
-                _ => unreachable!(),
-            };
-            self.send_keys(key_str);
+        if matches!(vim_char, Some('H' | 'M' | 'L')) && !is_command_chord(key_event) {
+            if let Some(ch) = vim_char {
+                self.send_keys(&ch.to_string());
+            }
             return Some(self.dispatch_handled());
         }
 
         // 'R' - enter replace mode
-        if keycode == Key::R && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            if self.recording_macro.is_some() && !self.playing_macro {
+        if vim_char == Some('R') && !is_command_chord(key_event) {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("R".to_string());
             }
             self.enter_replace_mode();
@@ -744,13 +711,11 @@ impl GodotNeovimPlugin {
         &mut self,
         key_event: &Gd<godot::classes::InputEventKey>,
     ) -> Option<VarDictionary> {
-        let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
 
         // 'f' - find char forward (not after g/i/a prefix)
-        if keycode == Key::F
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('f')
+            && !is_command_chord(key_event)
             && self.last_key != "g"
             && self.last_key != "i"
             && self.last_key != "a"
@@ -761,9 +726,8 @@ impl GodotNeovimPlugin {
         }
 
         // 'F' - find char backward (not after i/a prefix)
-        if keycode == Key::F
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('F')
+            && !is_command_chord(key_event)
             && self.last_key != "i"
             && self.last_key != "a"
         {
@@ -773,9 +737,8 @@ impl GodotNeovimPlugin {
         }
 
         // 't' - till char forward (not after g/z/i/a prefix)
-        if keycode == Key::T
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('t')
+            && !is_command_chord(key_event)
             && self.last_key != "g"
             && self.last_key != "z"
             && self.last_key != "i"
@@ -787,9 +750,8 @@ impl GodotNeovimPlugin {
         }
 
         // 'T' - till char backward (not after g/i/a prefix)
-        if keycode == Key::T
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('T')
+            && !is_command_chord(key_event)
             && self.last_key != "g"
             && self.last_key != "i"
             && self.last_key != "a"
@@ -800,22 +762,22 @@ impl GodotNeovimPlugin {
         }
 
         // 'r' - replace char
-        if keycode == Key::R && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('r') && !is_command_chord(key_event) {
             self.clear_pending_input_states();
             self.pending_char_op = Some('r');
             return Some(self.dispatch_handled());
         }
 
         // 'm' - set mark
-        if keycode == Key::M && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('m') && !is_command_chord(key_event) {
             self.clear_pending_input_states();
             self.pending_mark_op = Some('m');
             return Some(self.dispatch_handled());
         }
 
         // '\'' - jump to mark line (not in operator-pending or visual mode)
-        if unicode_char == Some('\'')
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('\'')
+            && !is_command_chord(key_event)
             && self.current_mode != "operator"
             && !Self::is_visual_mode(&self.current_mode)
         {
@@ -825,8 +787,8 @@ impl GodotNeovimPlugin {
         }
 
         // '`' - jump to mark position (not in operator-pending or visual mode)
-        if unicode_char == Some('`')
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('`')
+            && !is_command_chord(key_event)
             && self.current_mode != "operator"
             && !Self::is_visual_mode(&self.current_mode)
         {
@@ -835,15 +797,8 @@ impl GodotNeovimPlugin {
             return Some(self.dispatch_handled());
         }
 
-        // 'q' - macro recording start/stop (not after g, not with AltGr)
-        let input = Input::singleton();
-        let is_altgr_held = input.is_key_pressed(Key::CTRL) && input.is_key_pressed(Key::ALT);
-        if keycode == Key::Q
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && !is_altgr_held
-            && self.last_key != "g"
-        {
+        // 'q' - macro recording start/stop (not after g; AltGr+Q types '@' on German keyboards)
+        if vim_char == Some('q') && !is_command_chord(key_event) && self.last_key != "g" {
             if self.recording_macro.is_some() {
                 self.stop_macro_recording();
             } else {
@@ -854,15 +809,15 @@ impl GodotNeovimPlugin {
         }
 
         // '@' - macro playback
-        if unicode_char == Some('@') && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('@') && !is_command_chord(key_event) {
             self.clear_pending_input_states();
             self.pending_macro_op = Some('@');
             return Some(self.dispatch_handled());
         }
 
         // '"' - register selection (not in operator-pending or visual mode)
-        if unicode_char == Some('"')
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('"')
+            && !is_command_chord(key_event)
             && self.current_mode != "operator"
             && !Self::is_visual_mode(&self.current_mode)
         {
@@ -872,6 +827,15 @@ impl GodotNeovimPlugin {
             return Some(self.dispatch_handled());
         }
 
+        // '"' in Visual mode is forwarded to Neovim ("+p on the selection): load the
+        // OS clipboard first so the paste sees it
+        if vim_char == Some('"')
+            && !is_command_chord(key_event)
+            && Self::is_visual_mode(&self.current_mode)
+        {
+            self.load_clipboard_registers();
+        }
+
         None
     }
 
@@ -882,14 +846,14 @@ impl GodotNeovimPlugin {
         &mut self,
         key_event: &Gd<godot::classes::InputEventKey>,
     ) -> Option<VarDictionary> {
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
 
         // '>' - indent operator
-        if unicode_char == Some('>') {
+        if vim_char == Some('>') {
             if self.last_key == ">" {
                 self.send_keys(">>");
                 self.clear_last_key();
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push(">>".to_string());
                 }
             } else {
@@ -899,11 +863,11 @@ impl GodotNeovimPlugin {
         }
 
         // '<' - unindent operator
-        if unicode_char == Some('<') {
+        if vim_char == Some('<') {
             if self.last_key == "<" {
                 self.send_keys("<LT><LT>");
                 self.clear_last_key();
-                if self.recording_macro.is_some() && !self.playing_macro {
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push("<<".to_string());
                 }
             } else {
@@ -916,29 +880,24 @@ impl GodotNeovimPlugin {
     }
 
     // =====================================================================
-    // Helper: Prefix key accumulation (g, [, ], Z)
+    // Helper: Prefix key accumulation (g, [, ], z, Z, Ctrl+W)
     // =====================================================================
     fn handle_prefix_key_accumulation(
         &mut self,
         key_event: &Gd<godot::classes::InputEventKey>,
     ) -> Option<VarDictionary> {
         let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
 
         // 'g' prefix (not after another g - allow gg)
-        if unicode_char == Some('g')
-            && !key_event.is_ctrl_pressed()
-            && !key_event.is_shift_pressed()
-            && self.last_key != "g"
-        {
+        if vim_char == Some('g') && !is_command_chord(key_event) && self.last_key != "g" {
             self.set_last_key("g");
             return Some(self.dispatch_handled());
         }
 
         // '[' prefix (not after [ or ])
-        if keycode == Key::BRACKETLEFT
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('[')
+            && !is_command_chord(key_event)
             && self.last_key != "["
             && self.last_key != "]"
         {
@@ -947,9 +906,8 @@ impl GodotNeovimPlugin {
         }
 
         // ']' prefix (not after [ or ])
-        if keycode == Key::BRACKETRIGHT
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some(']')
+            && !is_command_chord(key_event)
             && self.last_key != "["
             && self.last_key != "]"
         {
@@ -957,12 +915,26 @@ impl GodotNeovimPlugin {
             return Some(self.dispatch_handled());
         }
 
-        // 'Z' prefix (Shift+Z)
-        if keycode == Key::Z
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && self.last_key != "Z"
+        // 'z' prefix (not in operator-pending mode)
+        if vim_char == Some('z') && !is_command_chord(key_event) && self.current_mode != "operator"
         {
+            self.set_last_key("z");
+            return Some(self.dispatch_handled());
+        }
+
+        // Ctrl+W prefix: the next key is a window command (dock focus, see window.rs)
+        if keycode == Key::W
+            && key_event.is_ctrl_pressed()
+            && !key_event.is_shift_pressed()
+            && self.last_key.is_empty()
+            && matches!(self.current_mode.as_str(), "n" | "normal")
+        {
+            self.set_last_key(WINDOW_PREFIX);
+            return Some(self.dispatch_handled());
+        }
+
+        // 'Z' prefix (Shift+Z)
+        if vim_char == Some('Z') && !is_command_chord(key_event) && self.last_key != "Z" {
             self.set_last_key("Z");
             return Some(self.dispatch_handled());
         }
@@ -1,6 +1,7 @@
 //! Insert mode input handling
 
 use super::super::GodotNeovimPlugin;
+use crate::settings::{self, InsertNavigationPolicy};
 use godot::global::Key;
 use godot::prelude::*;
 
@@ -15,8 +16,13 @@ impl GodotNeovimPlugin {
             key_event.is_ctrl_pressed() && key_event.get_keycode() == Key::BRACKETLEFT;
 
         if is_escape || is_ctrl_bracket {
+            // During an IME composition the key ends the composition first
+            if self.defer_insert_exit_for_ime() {
+                return;
+            }
             // Record <Esc> to macro buffer before send_escape
-            if self.recording_macro.is_some() && !self.playing_macro {
+            // (send_escape flushes the Godot-side insert keys to the recording)
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("<Esc>".to_string());
             }
             self.send_escape();
@@ -30,7 +36,7 @@ impl GodotNeovimPlugin {
         let is_ctrl_b = key_event.is_ctrl_pressed() && key_event.get_keycode() == Key::B;
         if is_ctrl_b {
             // Record <Esc> and <C-v> to macro buffer
-            if self.recording_macro.is_some() && !self.playing_macro {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("<Esc>".to_string());
                 self.macro_buffer.push("<C-v>".to_string());
             }
@@ -47,6 +53,28 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Navigation keys (arrows, Home/End and Godot's word/document motions):
+        // Godot always moves its own caret, Neovim is optionally told about the
+        // move (see InsertNavigationPolicy)
+        if let Some(nav_key) = Self::insert_navigation_key(
+            key_event.get_keycode(),
+            key_event.is_ctrl_pressed(),
+            key_event.is_alt_pressed(),
+            key_event.is_meta_pressed(),
+            key_event.is_shift_pressed(),
+        ) {
+            if settings::get_insert_navigation_policy() == InsertNavigationPolicy::Forward {
+                if self.recording_macro.is_some() {
+                    self.macro_buffer.push(nav_key.to_string());
+                    self.flush_macro_insert_keys();
+                }
+                self.forward_insert_navigation_key(nav_key);
+            } else {
+                self.record_insert_key(nav_key);
+            }
+            return;
+        }
+
         // Ctrl/Alt modified keys are sent to Neovim for Vim insert mode commands
         // (Ctrl+w, Ctrl+u, Ctrl+r, Ctrl+o, etc.)
         // IMPORTANT: Only send actual Vim commands (<C-...>, <A-...>), not plain characters
@@ -59,9 +87,10 @@ impl GodotNeovimPlugin {
             // Only send if it's an actual Vim command notation (starts with <)
             // Plain characters (including CJK) should be handled by Godot
             if !nvim_key.is_empty() && nvim_key.starts_with('<') {
-                // Record to macro buffer
-                if self.recording_macro.is_some() && !self.playing_macro {
+                // Record to macro buffer (Neovim records the key itself)
+                if self.recording_macro.is_some() {
                     self.macro_buffer.push(nvim_key.clone());
+                    self.flush_macro_insert_keys();
                 }
                 self.send_keys(&nvim_key);
                 if let Some(mut viewport) = self.base().get_viewport() {
@@ -71,38 +100,183 @@ impl GodotNeovimPlugin {
             return;
         }
 
-        // Record keys to macro buffer if recording
-        if self.recording_macro.is_some() && !self.playing_macro {
-            let keycode = key_event.get_keycode();
-            // Special keys
-            match keycode {
-                Key::BACKSPACE => {
-                    self.macro_buffer.push("<BS>".to_string());
-                    return;
-                }
-                Key::ENTER => {
-                    self.macro_buffer.push("<CR>".to_string());
-                    return;
-                }
-                Key::DELETE => {
-                    self.macro_buffer.push("<Del>".to_string());
-                    return;
-                }
-                Key::TAB => {
-                    self.macro_buffer.push("<Tab>".to_string());
-                    return;
-                }
-                _ => {}
+        // Enter inside a comment: continue the comment leader (Godot-side 'formatoptions' r)
+        if key_event.get_keycode() == Key::ENTER
+            && !key_event.is_shift_pressed()
+            && settings::get_comment_continuation().on_enter()
+            && self.continue_comment_on_enter()
+        {
+            self.record_insert_key("<CR>");
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
             }
-            // Normal characters
-            let unicode = key_event.get_unicode();
-            if unicode > 0 {
-                if let Some(c) = char::from_u32(unicode) {
-                    self.macro_buffer.push(c.to_string());
-                }
+            return;
+        }
+
+        // Tab: expand a snippet or move between its fields (Shift+Tab: back)
+        if key_event.get_keycode() == Key::TAB
+            && self.handle_snippet_tab(key_event.is_shift_pressed())
+        {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Signature help: refresh after `(` / `,` is inserted, close after `)`
+        match char::from_u32(key_event.get_unicode()) {
+            Some('(') | Some(',') => {
+                self.base_mut().call_deferred("update_signature_help", &[]);
+            }
+            Some(')') => self.close_signature_help(),
+            _ => {}
+        }
+
+        // Record keys typed in Godot for the macro being recorded
+        if self.recording_macro.is_some() {
+            let key = match key_event.get_keycode() {
+                Key::BACKSPACE => Some("<BS>".to_string()),
+                Key::ENTER => Some("<CR>".to_string()),
+                Key::DELETE => Some("<Del>".to_string()),
+                Key::TAB => Some("<Tab>".to_string()),
+                _ => char::from_u32(key_event.get_unicode())
+                    .filter(|_| key_event.get_unicode() > 0)
+                    .map(|c| c.to_string()),
+            };
+            if let Some(key) = key {
+                self.record_insert_key(&key);
             }
         }
 
         // Normal character input: let Godot handle it (IME/autocomplete support)
     }
+
+    /// Insert a newline followed by the indent and comment leader of the current line
+    /// Only when the caret is inside a line comment (not in a string, and after the
+    /// leader). Returns false to let Godot handle Enter normally.
+    fn continue_comment_on_enter(&mut self) -> bool {
+        let Some(ref mut editor) = self.current_editor else {
+            return false;
+        };
+        if editor.has_selection() || editor.get_caret_count() > 1 {
+            return false;
+        }
+        let line = editor.get_caret_line();
+        let col = editor.get_caret_column();
+        if editor.is_in_string_ex(line).column(col).done() != -1 {
+            return false;
+        }
+        let delimiter = editor.is_in_comment_ex(line).column(col).done();
+        if delimiter == -1 {
+            return false;
+        }
+        let start_key = editor.get_delimiter_start_key(delimiter).to_string();
+
+        // The line must start with the leader (a trailing comment after code is not continued)
+        let text = editor.get_line(line).to_string();
+        let indent: String = text.chars().take_while(|c| c.is_whitespace()).collect();
+        let body = &text[indent.len()..];
+        if start_key.is_empty() || !body.starts_with(start_key.as_str()) {
+            return false;
+        }
+        // Repeated leader characters (##) and the space after them are part of the leader
+        let leader_char = start_key.chars().next().unwrap_or('#');
+        let leader_len = body.chars().take_while(|&c| c == leader_char).count();
+        let space_len = body
+            .chars()
+            .skip(leader_len)
+            .take_while(|c| *c == ' ')
+            .count();
+        let leader: String = body.chars().take(leader_len + space_len).collect();
+        if (col as usize) < indent.chars().count() + leader_len {
+            return false;
+        }
+
+        editor.insert_text_at_caret(&format!("\n{}{}", indent, leader));
+        crate::verbose_print!("[godot-neovim] Continued comment leader '{}'", leader);
+        true
+    }
+
+    /// Map an Insert mode navigation key to Neovim notation
+    /// Chords are mapped only when they are Godot's own caret motions (by word,
+    /// to the line or document ends); other chords, and Shift (selection), are
+    /// not navigation.
+    fn insert_navigation_key(
+        keycode: Key,
+        ctrl: bool,
+        alt: bool,
+        meta: bool,
+        shift: bool,
+    ) -> Option<&'static str> {
+        if shift {
+            return None;
+        }
+        let only =
+            |modifier: bool| modifier && [ctrl, alt, meta].iter().filter(|&&m| m).count() == 1;
+        let plain = !ctrl && !alt && !meta;
+        // macOS: Option moves by word, Command to the line and document ends
+        let (word, line_end, document_end) = if cfg!(target_os = "macos") {
+            (only(alt), only(meta), only(meta))
+        } else {
+            (only(ctrl), false, only(ctrl))
+        };
+        match keycode {
+            Key::LEFT if plain => Some("<Left>"),
+            Key::RIGHT if plain => Some("<Right>"),
+            Key::UP if plain => Some("<Up>"),
+            Key::DOWN if plain => Some("<Down>"),
+            Key::HOME if plain => Some("<Home>"),
+            Key::END if plain => Some("<End>"),
+            Key::LEFT if word => Some("<C-Left>"),
+            Key::RIGHT if word => Some("<C-Right>"),
+            Key::LEFT if line_end => Some("<Home>"),
+            Key::RIGHT if line_end => Some("<End>"),
+            Key::HOME if document_end => Some("<C-Home>"),
+            Key::END if document_end => Some("<C-End>"),
+            Key::UP if line_end => Some("<C-Home>"),
+            Key::DOWN if line_end => Some("<C-End>"),
+            _ => None,
+        }
+    }
+
+    /// Forward a navigation key to Neovim while staying in Insert mode
+    /// Neovim hasn't seen the text typed in Godot yet, so sync buffer and cursor first;
+    /// the key then ends the current insert in Neovim and starts a new one at the
+    /// new position (keeps `.` repeat, undo blocks and gi in line with native Vim)
+    fn forward_insert_navigation_key(&mut self, nvim_key: &str) {
+        self.sync_buffer_to_neovim_keep_undo();
+        self.sync_cursor_to_neovim();
+        self.send_keys(nvim_key);
+        crate::verbose_print!(
+            "[godot-neovim] Insert navigation forwarded to Neovim: {}",
+            nvim_key
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_navigation_key() {
+        let key = |keycode, ctrl, alt, meta, shift| {
+            GodotNeovimPlugin::insert_navigation_key(keycode, ctrl, alt, meta, shift)
+        };
+        assert_eq!(key(Key::LEFT, false, false, false, false), Some("<Left>"));
+        assert_eq!(key(Key::END, false, false, false, false), Some("<End>"));
+        // Selection and chords that are not caret motions
+        assert_eq!(key(Key::LEFT, false, false, false, true), None);
+        assert_eq!(key(Key::UP, false, true, false, false), None);
+        assert_eq!(key(Key::LEFT, true, true, false, false), None);
+        if cfg!(target_os = "macos") {
+            assert_eq!(key(Key::LEFT, false, true, false, false), Some("<C-Left>"));
+            assert_eq!(key(Key::RIGHT, false, false, true, false), Some("<End>"));
+            assert_eq!(key(Key::LEFT, true, false, false, false), None);
+        } else {
+            assert_eq!(key(Key::LEFT, true, false, false, false), Some("<C-Left>"));
+            assert_eq!(key(Key::HOME, true, false, false, false), Some("<C-Home>"));
+            assert_eq!(key(Key::LEFT, false, false, true, false), None);
+        }
+    }
 }
@@ -3,8 +3,9 @@
 //! This is the largest input handler, handling all normal mode key sequences
 //! including g-prefix commands, [/] bracket commands, z-commands, etc.
 
+use super::super::layout::is_command_chord;
+use super::super::window::WINDOW_PREFIX;
 use super::super::GodotNeovimPlugin;
-use godot::classes::Input;
 use godot::global::Key;
 use godot::prelude::*;
 
@@ -14,7 +15,80 @@ impl GodotNeovimPlugin {
         key_event: &Gd<godot::classes::InputEventKey>,
     ) {
         let keycode = key_event.get_keycode();
-        let unicode_char = char::from_u32(key_event.get_unicode());
+        let vim_char = self.vim_key(key_event);
+
+        // Key after gc: the motion of the comment operator (or c for gcc)
+        if self.last_key == "gc" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                self.finish_comment_operator(&keys);
+            }
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Key after gr: the motion of the replace operator (r for grr: references)
+        if self.last_key == "gr" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                if keys == "r" {
+                    self.clear_last_key();
+                    self.action_lsp_references_impl();
+                } else {
+                    self.finish_replace_operator(&keys);
+                }
+            }
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Key after cx: the motion of the exchange operator (x: line, c: clear)
+        if self.last_key == "cx" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                self.finish_exchange(&keys);
+            }
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Key after z: fold commands are Godot's, the rest goes to Neovim (zz, zt, ...)
+        if self.last_key == "z" {
+            if let Some(keys) = self.key_event_to_nvim_string(key_event) {
+                self.finish_z_command(&keys);
+            }
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // 'z' prefix: wait for the second key (not in operator-pending mode)
+        if vim_char == Some('z') && !is_command_chord(key_event) && self.current_mode != "operator"
+        {
+            self.set_last_key("z");
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Ctrl+W prefix: the next key is a window command (dock focus, see window.rs)
+        if key_event.is_ctrl_pressed()
+            && keycode == Key::W
+            && !key_event.is_shift_pressed()
+            && self.last_key.is_empty()
+            && matches!(self.current_mode.as_str(), "n" | "normal")
+        {
+            self.set_last_key(WINDOW_PREFIX);
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
 
         // Handle Ctrl+B: visual block in visual mode, page up in normal mode
         if key_event.is_ctrl_pressed() && keycode == Key::B {
@@ -31,9 +105,8 @@ impl GodotNeovimPlugin {
 
         // Handle 'o' in visual mode: toggle selection direction
         if Self::is_visual_mode(&self.current_mode)
-            && keycode == Key::O
-            && !key_event.is_ctrl_pressed()
-            && !key_event.is_shift_pressed()
+            && vim_char == Some('o')
+            && !is_command_chord(key_event)
         {
             // Send 'o' to Neovim to toggle selection direction
             self.send_keys("o");
@@ -115,6 +188,15 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Handle Ctrl+N for multicursor (select word / add next occurrence)
+        if key_event.is_ctrl_pressed() && keycode == Key::N {
+            self.action_multicursor_next_impl();
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
         // Handle Ctrl+G for file info
         if key_event.is_ctrl_pressed() && keycode == Key::G {
             self.action_show_file_info_impl();
@@ -125,7 +207,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '/' for forward search mode
-        if unicode_char == Some('/') && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('/') && !is_command_chord(key_event) {
             self.action_open_search_forward_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -134,7 +216,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '?' for backward search mode
-        if unicode_char == Some('?') && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('?') && !is_command_chord(key_event) {
             self.action_open_search_backward_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -142,8 +224,25 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Handle Alt+w/b/e for sub-word motions
+        if key_event.is_alt_pressed()
+            && !key_event.is_ctrl_pressed()
+            && !key_event.is_shift_pressed()
+            && matches!(keycode, Key::W | Key::B | Key::E)
+        {
+            match keycode {
+                Key::W => self.action_subword_forward_impl(),
+                Key::B => self.action_subword_backward_impl(),
+                _ => self.action_subword_end_impl(),
+            }
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
         // Handle ':' for command-line mode
-        if unicode_char == Some(':') {
+        if vim_char == Some(':') {
             self.action_open_command_line_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -152,7 +251,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '*' for search forward word under cursor
-        if unicode_char == Some('*') {
+        if vim_char == Some('*') {
             self.action_search_word_forward_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -161,7 +260,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '#' for search backward word under cursor
-        if unicode_char == Some('#') {
+        if vim_char == Some('#') {
             self.action_search_word_backward_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -170,7 +269,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'n' for repeat search forward
-        if keycode == Key::N && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('n') && !is_command_chord(key_event) {
             self.action_search_next_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -179,7 +278,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'N' for repeat search backward
-        if keycode == Key::N && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('N') && !is_command_chord(key_event) {
             self.action_search_prev_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -188,11 +287,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'u' for undo (but not after 'g' - that's 'gu' for lowercase)
-        if keycode == Key::U
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && self.last_key != "g"
-        {
+        if vim_char == Some('u') && !is_command_chord(key_event) && self.last_key != "g" {
             self.action_undo_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -211,9 +306,8 @@ impl GodotNeovimPlugin {
 
         // Handle 'f' for find char forward (but not after 'g' - that's 'gf' for go to file,
         // and not after 'i'/'a' - that's text object selection like 'vif')
-        if keycode == Key::F
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('f')
+            && !is_command_chord(key_event)
             && self.last_key != "g"
             && self.last_key != "i"
             && self.last_key != "a"
@@ -227,9 +321,8 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'F' for find char backward (not after 'i'/'a' - text object selection)
-        if keycode == Key::F
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('F')
+            && !is_command_chord(key_event)
             && self.last_key != "i"
             && self.last_key != "a"
         {
@@ -244,9 +337,8 @@ impl GodotNeovimPlugin {
         // Handle 't' for till char forward (but not after 'g' - that's gt for tab navigation,
         // not after 'z' - that's zt for scroll cursor to top,
         // and not after 'i'/'a' - that's text object selection like 'vit')
-        if keycode == Key::T
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('t')
+            && !is_command_chord(key_event)
             && self.last_key != "g"
             && self.last_key != "z"
             && self.last_key != "i"
@@ -262,9 +354,8 @@ impl GodotNeovimPlugin {
 
         // Handle 'T' for till char backward (but not after 'g' - that's gT for tab navigation,
         // and not after 'i'/'a' - text object selection)
-        if keycode == Key::T
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('T')
+            && !is_command_chord(key_event)
             && self.last_key != "g"
             && self.last_key != "i"
             && self.last_key != "a"
@@ -278,13 +369,8 @@ impl GodotNeovimPlugin {
         }
 
         // Handle ';' for repeat find char same direction
-        if keycode == Key::SEMICOLON && !key_event.is_shift_pressed() {
-            self.repeat_find_char(true);
-            self.send_keys(";");
-            // Record to local macro buffer (early return skips normal recording)
-            if self.recording_macro.is_some() && !self.playing_macro {
-                self.macro_buffer.push(";".to_string());
-            }
+        if vim_char == Some(';') {
+            self.repeat_find(true);
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
             }
@@ -292,13 +378,8 @@ impl GodotNeovimPlugin {
         }
 
         // Handle ',' for repeat find char opposite direction
-        if keycode == Key::COMMA && !key_event.is_shift_pressed() {
-            self.repeat_find_char(false);
-            self.send_keys(",");
-            // Record to local macro buffer (early return skips normal recording)
-            if self.recording_macro.is_some() && !self.playing_macro {
-                self.macro_buffer.push(",".to_string());
-            }
+        if vim_char == Some(',') {
+            self.repeat_find(false);
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
             }
@@ -306,11 +387,14 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '%' for matching bracket
-        if unicode_char == Some('%') {
-            self.jump_to_matching_bracket();
+        if vim_char == Some('%') {
+            // N% jumps to N percent of the file; only Neovim handles that
+            if self.count_buffer.is_empty() && self.should_preview_motion() {
+                self.jump_to_matching_bracket();
+            }
             self.send_keys("%");
             // Record to local macro buffer (early return skips normal recording)
-            if self.recording_macro.is_some() && !self.playing_macro {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("%".to_string());
             }
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -320,11 +404,11 @@ impl GodotNeovimPlugin {
         }
 
         // Handle count prefix (1-9, or 0 if count_buffer not empty)
-        // This tracks the count locally while also sending to Neovim
-        if let Some(c) = unicode_char {
+        // Held until the command: Neovim gets it as a key prefix (send_keys),
+        // local commands take it with get_and_clear_count()
+        if let Some(c) = vim_char {
             if c.is_ascii_digit() && (c != '0' || !self.count_buffer.is_empty()) {
                 self.count_buffer.push(c);
-                self.send_keys(&c.to_string());
                 // Reset timeout to prevent <Esc> being sent during count input
                 self.last_key_time = Some(std::time::Instant::now());
                 if let Some(mut viewport) = self.base().get_viewport() {
@@ -336,7 +420,7 @@ impl GodotNeovimPlugin {
 
         // Handle '0' for go to start of line (only when not part of a count)
         // Skip if last_key is "g" (g0 is handled separately for display line)
-        if unicode_char == Some('0') && !key_event.is_ctrl_pressed() && self.last_key != "g" {
+        if vim_char == Some('0') && !is_command_chord(key_event) && self.last_key != "g" {
             self.move_to_line_start();
             self.send_keys("0"); // Also send to Neovim
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -347,7 +431,7 @@ impl GodotNeovimPlugin {
 
         // Handle '^' for go to first non-blank
         // Skip if last_key is "g" (g^ is handled separately for display line)
-        if unicode_char == Some('^') && self.last_key != "g" {
+        if vim_char == Some('^') && self.last_key != "g" {
             self.move_to_first_non_blank();
             self.send_keys("^"); // Also send to Neovim
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -358,7 +442,7 @@ impl GodotNeovimPlugin {
 
         // Handle '$' for go to end of line
         // Skip if last_key is "g" (g$ is handled separately for display line)
-        if unicode_char == Some('$') && self.last_key != "g" {
+        if vim_char == Some('$') && self.last_key != "g" {
             self.move_to_line_end();
             self.send_keys("$"); // Also send to Neovim
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -369,7 +453,7 @@ impl GodotNeovimPlugin {
 
         // Handle '{' for previous paragraph (send to Neovim for proper cursor positioning)
         // Skip if last_key is '[' or ']' - these are [{ / ]{ commands handled later
-        if unicode_char == Some('{') && self.last_key != "[" && self.last_key != "]" {
+        if vim_char == Some('{') && self.last_key != "[" && self.last_key != "]" {
             self.send_keys("{");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -379,7 +463,7 @@ impl GodotNeovimPlugin {
 
         // Handle '}' for next paragraph (send to Neovim for proper cursor positioning)
         // Skip if last_key is '[' or ']' - these are [} / ]} commands handled later
-        if unicode_char == Some('}') && self.last_key != "[" && self.last_key != "]" {
+        if vim_char == Some('}') && self.last_key != "[" && self.last_key != "]" {
             self.send_keys("}");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -387,13 +471,14 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Handle 'x' after the c operator: exchange (cx)
+        if self.try_start_exchange(key_event) {
+            return;
+        }
+
         // Handle 'x' for delete char under cursor (but not after 'g' - that's 'gx' for open URL)
         // Neovim Master: send to Neovim only, reflect via nvim_buf_lines_event
-        if keycode == Key::X
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && self.last_key != "g"
-        {
+        if vim_char == Some('x') && !is_command_chord(key_event) && self.last_key != "g" {
             self.send_keys("x");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -403,7 +488,7 @@ impl GodotNeovimPlugin {
 
         // Handle 'X' for delete char before cursor
         // Neovim Master: send to Neovim only
-        if keycode == Key::X && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('X') && !is_command_chord(key_event) {
             self.send_keys("X");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -413,7 +498,7 @@ impl GodotNeovimPlugin {
 
         // Handle 'Y' for yank to end of line
         // Neovim Master: send to Neovim only
-        if keycode == Key::Y && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('Y') && !is_command_chord(key_event) {
             self.send_keys("Y");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -423,7 +508,7 @@ impl GodotNeovimPlugin {
 
         // Handle 'D' for delete to end of line
         // Neovim Master: send to Neovim only, reflect via nvim_buf_lines_event
-        if keycode == Key::D && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('D') && !is_command_chord(key_event) {
             self.send_keys("D");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -433,7 +518,7 @@ impl GodotNeovimPlugin {
 
         // Handle 'C' for change to end of line
         // Neovim Master: send to Neovim only
-        if keycode == Key::C && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('C') && !is_command_chord(key_event) {
             self.send_keys("C");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -441,9 +526,14 @@ impl GodotNeovimPlugin {
             return;
         }
 
-        // Handle 's' for substitute char (delete char and enter insert mode)
-        if keycode == Key::S && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            self.action_send_keys_impl("s");
+        // Handle 's' after the y, c or d operator: surround (ys, cs, ds)
+        if self.try_start_surround(key_event) {
+            return;
+        }
+
+        // Handle 's' for sneak (or substitute char with the Sneak setting off)
+        if vim_char == Some('s') && !is_command_chord(key_event) {
+            self.action_sneak_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
             }
@@ -451,7 +541,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'S' for substitute line (delete line content and enter insert mode)
-        if keycode == Key::S && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('S') && !is_command_chord(key_event) {
             self.action_send_keys_impl("S");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -463,8 +553,8 @@ impl GodotNeovimPlugin {
         // This allows 'ci(', 'cw', 'cc', etc. to work correctly
         // Neovim handles operator-pending mode and text objects natively
 
-        // Handle 'r' for replace char
-        if keycode == Key::R && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        // Handle 'r' for replace char (but not after 'g' - that's 'gr')
+        if vim_char == Some('r') && !is_command_chord(key_event) && self.last_key != "g" {
             self.clear_pending_input_states();
             self.pending_char_op = Some('r');
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -474,8 +564,9 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'R' for replace mode (continuous overwrite)
-        if keycode == Key::R && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
-            if self.recording_macro.is_some() && !self.playing_macro {
+        // (but not after 'g' - that's 'gR' for LSP rename)
+        if vim_char == Some('R') && !is_command_chord(key_event) && self.last_key != "g" {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("R".to_string());
             }
             self.enter_replace_mode();
@@ -486,7 +577,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '~' for toggle case
-        if unicode_char == Some('~') {
+        if vim_char == Some('~') {
             self.action_send_keys_impl("~");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -495,7 +586,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'm' for set mark
-        if keycode == Key::M && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('m') && !is_command_chord(key_event) {
             self.clear_pending_input_states();
             self.pending_mark_op = Some('m');
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -507,8 +598,8 @@ impl GodotNeovimPlugin {
         // Handle '\'' (single quote) for jump to mark line
         // Skip if in operator-pending mode (e.g., ci' should send ' to Neovim as text object)
         // Skip if in visual mode (e.g., vi' should select inside quotes)
-        if unicode_char == Some('\'')
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('\'')
+            && !is_command_chord(key_event)
             && self.current_mode != "operator"
             && !Self::is_visual_mode(&self.current_mode)
         {
@@ -523,8 +614,8 @@ impl GodotNeovimPlugin {
         // Handle '`' (backtick) for jump to mark position
         // Skip if in operator-pending mode (e.g., ci` should send ` to Neovim as text object)
         // Skip if in visual mode (e.g., vi` should select inside backticks)
-        if unicode_char == Some('`')
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('`')
+            && !is_command_chord(key_event)
             && self.current_mode != "operator"
             && !Self::is_visual_mode(&self.current_mode)
         {
@@ -537,17 +628,8 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'q' for macro recording (start/stop) - but not after 'g' (that's gq for format)
-        // Also skip if AltGr is pressed (Ctrl+Alt on German keyboards for '@')
-        // Note: Use Input::singleton() to check actual key state because AltGr sends
-        // separate Ctrl and Alt events, and key_event modifiers may not be set correctly
-        let input = Input::singleton();
-        let is_altgr_held = input.is_key_pressed(Key::CTRL) && input.is_key_pressed(Key::ALT);
-        if keycode == Key::Q
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && !is_altgr_held
-            && self.last_key != "g"
-        {
+        // AltGr+Q types '@' on German keyboards, so it is not 'q' here
+        if vim_char == Some('q') && !is_command_chord(key_event) && self.last_key != "g" {
             if self.recording_macro.is_some() {
                 // Stop recording
                 self.stop_macro_recording();
@@ -563,7 +645,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '@' for macro playback
-        if unicode_char == Some('@') && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('@') && !is_command_chord(key_event) {
             self.clear_pending_input_states();
             self.pending_macro_op = Some('@');
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -575,8 +657,8 @@ impl GodotNeovimPlugin {
         // Handle '"' for register selection
         // Skip if in operator-pending mode (e.g., ci" should send " to Neovim as text object)
         // Skip if in visual mode (e.g., vi" should select inside quotes)
-        if unicode_char == Some('"')
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('"')
+            && !is_command_chord(key_event)
             && self.current_mode != "operator"
             && !Self::is_visual_mode(&self.current_mode)
         {
@@ -591,10 +673,27 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // '"' in Visual mode is forwarded to Neovim ("+p on the selection): load the
+        // OS clipboard first so the paste sees it
+        if vim_char == Some('"')
+            && !is_command_chord(key_event)
+            && Self::is_visual_mode(&self.current_mode)
+        {
+            self.load_clipboard_registers();
+        }
+
         // Handle '>' operator with motion/text object (>iw, >i{, >aw, etc.)
         // When '>' is pending and next key is not '>', send '>' + key to Neovim
         if self.last_key == ">" {
-            if let Some(ch) = unicode_char {
+            if let Some(ch) = vim_char {
+                if ch == '/' || ch == '?' {
+                    // Search motion: the pattern is typed in search mode
+                    self.open_operator_search(">", ch == '/');
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return;
+                }
                 if ch != '>' {
                     self.send_keys(&format!(">{}", ch));
                     self.clear_last_key();
@@ -610,7 +709,15 @@ impl GodotNeovimPlugin {
         // When '<' is pending and next key is not '<', send '<LT>' + key to Neovim
         // Use <LT> because nvim_input interprets < as special key sequence start
         if self.last_key == "<" {
-            if let Some(ch) = unicode_char {
+            if let Some(ch) = vim_char {
+                if ch == '/' || ch == '?' {
+                    // Search motion: the pattern is typed in search mode
+                    self.open_operator_search("<LT>", ch == '/');
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return;
+                }
                 if ch != '<' {
                     self.send_keys(&format!("<LT>{}", ch));
                     self.clear_last_key();
@@ -625,7 +732,7 @@ impl GodotNeovimPlugin {
         // Handle '>>' for indent (first '>' sets pending, second '>' executes)
         // Handle '<<' for unindent (first '<' sets pending, second '<' executes)
         // Neovim Master: send to Neovim for proper undo/register integration
-        if unicode_char == Some('>') {
+        if vim_char == Some('>') {
             if self.last_key == ">" {
                 self.send_keys(">>");
                 self.clear_last_key();
@@ -638,7 +745,7 @@ impl GodotNeovimPlugin {
             return;
         }
 
-        if unicode_char == Some('<') {
+        if vim_char == Some('<') {
             if self.last_key == "<" {
                 // Use <LT><LT> because nvim_input interprets < as special key sequence start
                 self.send_keys("<LT><LT>");
@@ -655,11 +762,7 @@ impl GodotNeovimPlugin {
         // Handle 'g' prefix - don't send to Neovim yet, wait for next key
         // (like '[' and ']' prefixes)
         // Note: Skip if last_key is already "g" to allow 'gg' to be processed
-        if unicode_char == Some('g')
-            && !key_event.is_ctrl_pressed()
-            && !key_event.is_shift_pressed()
-            && self.last_key != "g"
-        {
+        if vim_char == Some('g') && !is_command_chord(key_event) && self.last_key != "g" {
             self.set_last_key("g");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -670,9 +773,8 @@ impl GodotNeovimPlugin {
         // Handle '[' prefix - don't send to Neovim yet, wait for next key
         // Use keycode for keyboard layout independence (JP keyboard may have different unicode)
         // Skip if last_key is already '[' or ']' (to allow [[, ]], [], ][ sequences)
-        if keycode == Key::BRACKETLEFT
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some('[')
+            && !is_command_chord(key_event)
             && self.last_key != "["
             && self.last_key != "]"
         {
@@ -686,9 +788,8 @@ impl GodotNeovimPlugin {
         // Handle ']' prefix - don't send to Neovim yet, wait for next key
         // Use keycode for keyboard layout independence (JP keyboard may have different unicode)
         // Skip if last_key is already '[' or ']' (to allow [[, ]], [], ][ sequences)
-        if keycode == Key::BRACKETRIGHT
-            && !key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
+        if vim_char == Some(']')
+            && !is_command_chord(key_event)
             && self.last_key != "["
             && self.last_key != "]"
         {
@@ -701,7 +802,7 @@ impl GodotNeovimPlugin {
 
         // Handle p after [ or ]
         // Neovim Master: send to Neovim for proper undo/register integration
-        if keycode == Key::P && !key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('p') && !is_command_chord(key_event) {
             if self.last_key == "[" {
                 self.send_keys("[p");
                 self.clear_last_key();
@@ -720,7 +821,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle '?' for backward search
-        if unicode_char == Some('?') && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('?') && !is_command_chord(key_event) {
             self.start_search_backward();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -729,7 +830,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle 'K' for documentation lookup
-        if keycode == Key::K && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('K') && !is_command_chord(key_event) {
             self.action_open_documentation_impl();
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -741,10 +842,7 @@ impl GodotNeovimPlugin {
         // Use keycode for keyboard layout independence (JP keyboard support)
         if self.last_key == "[" {
             // [[ - jump to previous '{' at start of line (send to Neovim)
-            if keycode == Key::BRACKETLEFT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some('[') && !is_command_chord(key_event) {
                 self.send_keys("[[");
                 self.clear_last_key();
                 if let Some(mut viewport) = self.base().get_viewport() {
@@ -753,10 +851,7 @@ impl GodotNeovimPlugin {
                 return;
             }
             // [] - jump to previous '}' at start of line (send to Neovim)
-            if keycode == Key::BRACKETRIGHT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some(']') && !is_command_chord(key_event) {
                 self.send_keys("[]");
                 self.clear_last_key();
                 if let Some(mut viewport) = self.base().get_viewport() {
@@ -764,7 +859,25 @@ impl GodotNeovimPlugin {
                 }
                 return;
             }
-            match unicode_char {
+            match vim_char {
+                Some('f') => {
+                    // [f - function start from the LSP symbols (or the func lines)
+                    self.clear_last_key();
+                    self.move_to_function(false);
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return;
+                }
+                Some('d') => {
+                    // [d - LSP diagnostic
+                    self.clear_last_key();
+                    self.move_to_diagnostic(false);
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return;
+                }
                 Some('{') => {
                     // Neovim Master: send to Neovim for proper jumplist support
                     self.send_keys("[{");
@@ -806,10 +919,7 @@ impl GodotNeovimPlugin {
         // Use keycode for keyboard layout independence (JP keyboard support)
         if self.last_key == "]" {
             // ]] - jump to next '{' at start of line (send to Neovim)
-            if keycode == Key::BRACKETRIGHT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some(']') && !is_command_chord(key_event) {
                 self.send_keys("]]");
                 self.clear_last_key();
                 if let Some(mut viewport) = self.base().get_viewport() {
@@ -818,10 +928,7 @@ impl GodotNeovimPlugin {
                 return;
             }
             // ][ - jump to next '}' at start of line (send to Neovim)
-            if keycode == Key::BRACKETLEFT
-                && !key_event.is_shift_pressed()
-                && !key_event.is_ctrl_pressed()
-            {
+            if vim_char == Some('[') && !is_command_chord(key_event) {
                 self.send_keys("][");
                 self.clear_last_key();
                 if let Some(mut viewport) = self.base().get_viewport() {
@@ -829,7 +936,25 @@ impl GodotNeovimPlugin {
                 }
                 return;
             }
-            match unicode_char {
+            match vim_char {
+                Some('f') => {
+                    // ]f - function start from the LSP symbols (or the func lines)
+                    self.clear_last_key();
+                    self.move_to_function(true);
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return;
+                }
+                Some('d') => {
+                    // ]d - LSP diagnostic
+                    self.clear_last_key();
+                    self.move_to_diagnostic(true);
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return;
+                }
                 Some('}') => {
                     // Neovim Master: send to Neovim for proper jumplist support
                     self.send_keys("]}");
@@ -869,7 +994,7 @@ impl GodotNeovimPlugin {
 
         // Handle gqq (format current line)
         // Neovim Master: send to Neovim for proper undo/register integration
-        if self.last_key == "gq" && keycode == Key::Q && !key_event.is_shift_pressed() {
+        if self.last_key == "gq" && vim_char == Some('q') {
             self.send_keys("gqq");
             self.clear_last_key();
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -881,11 +1006,7 @@ impl GodotNeovimPlugin {
         // Handle 'J' for join lines - send to Neovim (Neovim Master design)
         // Neovim will process the join and send buffer changes via nvim_buf_lines_event
         // Note: Skip if last_key is "g" to allow 'gJ' to be processed in g-prefix block
-        if keycode == Key::J
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && self.last_key != "g"
-        {
+        if vim_char == Some('J') && !is_command_chord(key_event) && self.last_key != "g" {
             self.send_keys("J");
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -912,28 +1033,15 @@ impl GodotNeovimPlugin {
         }
 
         // Handle H/M/L based on Godot's visible area (not Neovim's)
-        if !key_event.is_ctrl_pressed()
-            && !key_event.is_alt_pressed()
-            && (keycode == Key::H || keycode == Key::M || keycode == Key::L)
-            && key_event.is_shift_pressed()
-        {
+        if matches!(vim_char, Some('H' | 'M' | 'L')) && !is_command_chord(key_event) {
             // H/M/L are valid motions in all contexts:
             // - Normal mode: move cursor
             // - Visual mode: extend selection
             // - Operator-pending mode (d, c, y + H/M/L): complete the operation
             // Do NOT cancel pending operator - let Neovim handle it
             // Shift+h/m/l = H/M/L (uppercase) - send to Neovim for viewport-aware handling
-            match keycode {
-                Key::H => {
-                    self.send_keys("H");
-                }
-                Key::M => {
-                    self.send_keys("M");
-                }
-                Key::L => {
-                    self.send_keys("L");
-                }
-                _ => {}
+            if let Some(ch) = vim_char {
+                self.send_keys(&ch.to_string());
             }
             if let Some(mut viewport) = self.base().get_viewport() {
                 viewport.set_input_as_handled();
@@ -942,9 +1050,9 @@ impl GodotNeovimPlugin {
         }
 
         // Handle Z-prefixed commands (ZZ, ZQ)
-        if keycode == Key::Z && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed() {
+        if vim_char == Some('Z') && !is_command_chord(key_event) {
             if self.last_key == "Z" {
-                // Second Z - this is ZZ (save and close)
+                // Second Z - this is ZZ (save if modified and close)
                 self.action_save_and_close_impl();
                 self.clear_last_key();
             } else {
@@ -958,11 +1066,7 @@ impl GodotNeovimPlugin {
         }
 
         // Handle ZQ (Z then Q) - close without saving
-        if keycode == Key::Q
-            && key_event.is_shift_pressed()
-            && !key_event.is_ctrl_pressed()
-            && self.last_key == "Z"
-        {
+        if vim_char == Some('Q') && !is_command_chord(key_event) && self.last_key == "Z" {
             self.action_close_discard_impl();
             self.clear_last_key();
             if let Some(mut viewport) = self.base().get_viewport() {
@@ -976,207 +1080,18 @@ impl GodotNeovimPlugin {
             self.clear_last_key();
         }
 
-        // Handle register-aware yy (yank line)
-        if let Some(reg) = self.selected_register {
-            if reg != '\0' {
-                // Handle count prefix (digits 1-9, or 0 if count_buffer not empty)
-                if let Some(c) = unicode_char {
-                    if c.is_ascii_digit() && (c != '0' || !self.count_buffer.is_empty()) {
-                        self.count_buffer.push(c);
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                }
-
-                // Register is selected, check for yy
-                // Neovim Master: send to Neovim for proper undo/register integration
-                if keycode == Key::Y
-                    && !key_event.is_shift_pressed()
-                    && !key_event.is_ctrl_pressed()
-                {
-                    if self.last_key == "y" {
-                        // yy - yank current line(s) to register
-                        let count = self.get_and_clear_count();
-                        let count_str = if count > 1 {
-                            count.to_string()
-                        } else {
-                            String::new()
-                        };
-                        self.send_keys(&format!("\"{}{}yy", reg, count_str));
-                        self.selected_register = None;
-                        self.clear_last_key();
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    } else {
-                        // First y - wait for second
-                        self.set_last_key("y");
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                }
-
-                // Handle register-aware p (paste)
-                // Neovim Master: send to Neovim for proper undo/register integration
-                if keycode == Key::P
-                    && !key_event.is_shift_pressed()
-                    && !key_event.is_ctrl_pressed()
-                {
-                    self.send_keys(&format!("\"{}p", reg));
-                    self.selected_register = None;
-                    self.count_buffer.clear();
-                    if let Some(mut viewport) = self.base().get_viewport() {
-                        viewport.set_input_as_handled();
-                    }
-                    return;
-                }
-
-                // Handle register-aware P (paste before)
-                // Neovim Master: send to Neovim for proper undo/register integration
-                if keycode == Key::P && key_event.is_shift_pressed() && !key_event.is_ctrl_pressed()
-                {
-                    self.send_keys(&format!("\"{}P", reg));
-                    self.selected_register = None;
-                    self.count_buffer.clear();
-                    if let Some(mut viewport) = self.base().get_viewport() {
-                        viewport.set_input_as_handled();
-                    }
-                    return;
-                }
-
-                // Handle register-aware dd (delete line and yank)
-                // Neovim Master: send to Neovim for proper undo/register integration
-                if keycode == Key::D
-                    && !key_event.is_shift_pressed()
-                    && !key_event.is_ctrl_pressed()
-                {
-                    if self.last_key == "d" {
-                        // dd - delete line(s) and store in register
-                        let count = self.get_and_clear_count();
-                        let count_str = if count > 1 {
-                            count.to_string()
-                        } else {
-                            String::new()
-                        };
-                        self.send_keys(&format!("\"{}{}dd", reg, count_str));
-                        self.selected_register = None;
-                        self.clear_last_key();
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    } else {
-                        // First d - wait for second or motion
-                        self.set_last_key("d");
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                }
-
-                // Handle register-aware cc (change line)
-                // Neovim Master: send to Neovim for proper undo/register integration
-                if keycode == Key::C
-                    && !key_event.is_shift_pressed()
-                    && !key_event.is_ctrl_pressed()
-                {
-                    if self.last_key == "c" {
-                        // cc - change line(s) and store in register
-                        let count = self.get_and_clear_count();
-                        let count_str = if count > 1 {
-                            count.to_string()
-                        } else {
-                            String::new()
-                        };
-                        self.send_keys(&format!("\"{}{}cc", reg, count_str));
-                        self.selected_register = None;
-                        self.clear_last_key();
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    } else {
-                        // First c - wait for second or motion
-                        self.set_last_key("c");
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                }
-
-                // Handle operator + motion/text object with register (e.g., "adi(, "ayi(, "aci()
-                // When last_key is an operator (y/d) and current key is a motion/text object,
-                // send the full command to Neovim
-                if let Some(keys) = self.key_event_to_nvim_string(key_event) {
-                    if self.last_key == "y" && keycode != Key::Y {
-                        // y + motion (e.g., yi(, yw, y$)
-                        let count = self.get_and_clear_count();
-                        let count_str = if count > 1 {
-                            count.to_string()
-                        } else {
-                            String::new()
-                        };
-                        self.send_keys(&format!("\"{}{}y{}", reg, count_str, keys));
-                        self.selected_register = None;
-                        self.clear_last_key();
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                    if self.last_key == "d" && keycode != Key::D {
-                        // d + motion (e.g., di(, dw, d$)
-                        let count = self.get_and_clear_count();
-                        let count_str = if count > 1 {
-                            count.to_string()
-                        } else {
-                            String::new()
-                        };
-                        self.send_keys(&format!("\"{}{}d{}", reg, count_str, keys));
-                        self.selected_register = None;
-                        self.clear_last_key();
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                    if self.last_key == "c" && keycode != Key::C {
-                        // c + motion (e.g., ci(, cw, c$)
-                        let count = self.get_and_clear_count();
-                        let count_str = if count > 1 {
-                            count.to_string()
-                        } else {
-                            String::new()
-                        };
-                        self.send_keys(&format!("\"{}{}c{}", reg, count_str, keys));
-                        self.selected_register = None;
-                        self.clear_last_key();
-                        if let Some(mut viewport) = self.base().get_viewport() {
-                            viewport.set_input_as_handled();
-                        }
-                        return;
-                    }
-                }
-
-                // Other keys cancel register selection
-                if keycode != Key::Y && keycode != Key::D && keycode != Key::C {
-                    self.selected_register = None;
-                    self.count_buffer.clear();
-                }
+        // Register-aware commands ("ayy, "ap, "adw): sent to Neovim with the register
+        if self.handle_register_key(key_event) {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
             }
+            return;
         }
 
         // Track visual mode type when entering visual mode
         // Neovim returns "visual" for all visual modes, so we track the key pressed
-        if keycode == Key::V && !key_event.is_ctrl_pressed() {
-            if key_event.is_shift_pressed() {
+        if matches!(vim_char, Some('v' | 'V')) && !is_command_chord(key_event) {
+            if vim_char == Some('V') {
                 // V (shift+V) - visual line mode
                 self.visual_mode_type = 'V';
             } else {
@@ -1190,6 +1105,19 @@ impl GodotNeovimPlugin {
             // Intercept g-prefix commands
             // Note: 'g' is NOT sent to Neovim when typed - we wait for the second key
             // and send the full command (like 'ge', 'gj', etc.) or 'g' + second key for unhandled commands
+            if self.last_key == "g"
+                && (keys == "c" || (keys == "r" && Self::gr_is_replace_operator()))
+            {
+                if keys == "c" {
+                    self.start_comment_operator();
+                } else {
+                    self.start_replace_operator('"');
+                }
+                if let Some(mut viewport) = self.base().get_viewport() {
+                    viewport.set_input_as_handled();
+                }
+                return;
+            }
             if self.last_key == "g" {
                 let handled = match keys.as_str() {
                     "x" => {
@@ -1204,6 +1132,18 @@ impl GodotNeovimPlugin {
                         self.action_goto_definition_impl();
                         true
                     }
+                    "D" => {
+                        self.action_goto_declaration_impl();
+                        true
+                    }
+                    "r" => {
+                        self.action_lsp_references_impl();
+                        true
+                    }
+                    "R" => {
+                        self.action_lsp_rename_impl();
+                        true
+                    }
                     "I" => {
                         self.action_insert_at_column_zero_impl();
                         true
@@ -1268,6 +1208,28 @@ impl GodotNeovimPlugin {
                         self.action_display_line_first_non_blank_impl();
                         true
                     }
+                    "-" => {
+                        self.action_undo_earlier_impl();
+                        true
+                    }
+                    "+" => {
+                        self.action_undo_later_impl();
+                        true
+                    }
+                    "*" => {
+                        self.action_search_partial_word_forward_impl();
+                        true
+                    }
+                    "#" => {
+                        self.action_search_partial_word_backward_impl();
+                        true
+                    }
+                    "n" | "N" => {
+                        // gn/gN select the next match charwise (or act as an operator's motion)
+                        self.visual_mode_type = 'v';
+                        self.action_send_keys_impl(&format!("g{}", keys));
+                        true
+                    }
                     _ => {
                         // Unhandled g-command: send 'g' + second key to Neovim
                         // (e.g., gg, g_, etc.)
@@ -1286,30 +1248,22 @@ impl GodotNeovimPlugin {
             }
 
             // Record key for macro if recording (and not playing back)
-            if self.recording_macro.is_some() && !self.playing_macro {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push(keys.clone());
             }
 
             let completed = self.send_keys(&keys);
 
-            // Handle scroll commands (zz, zt, zb) only if command completed
-            let scroll_handled = if completed {
-                self.handle_scroll_command(&keys)
-            } else {
-                false
-            };
-
             // Handle gq (format operator) - needs to wait for motion
             if completed && self.last_key == "g" && keys == "q" {
                 self.set_last_key("gq");
                 // Don't return - let normal key handling continue for motion
             }
 
-            // Track last key for sequence detection, unless:
-            // - scroll command was handled, or
-            // - we entered insert/replace mode (no sequence expected in those modes)
+            // Track last key for sequence detection, unless we entered
+            // insert/replace mode (no sequence expected in those modes)
             // Note: In visual mode, we still track 'i' and 'a' for text object selection (vit, vat, etc.)
-            if !scroll_handled && !self.is_insert_mode() && !self.is_replace_mode() {
+            if !self.is_insert_mode() && !self.is_replace_mode() {
                 // In visual mode, only track 'i' and 'a' for text object prefix
                 if self.is_in_visual_mode() {
                     if keys == "i" || keys == "a" {
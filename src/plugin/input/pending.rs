@@ -1,5 +1,8 @@
 //! Pending operation handlers (f/t/r, marks, macros, registers)
 
+use super::super::layout::{self, is_command_chord};
+use super::super::marks::{is_jumpable_mark, is_settable_mark};
+use super::super::registers::is_valid_register;
 use super::super::GodotNeovimPlugin;
 use godot::global::Key;
 use godot::prelude::*;
@@ -25,13 +28,10 @@ impl GodotNeovimPlugin {
             return false;
         }
 
-        // Cancel on Escape or any modifier key combination (Ctrl+X, Alt+X, etc.)
-        if keycode == Key::ESCAPE
-            || key_event.is_ctrl_pressed()
-            || key_event.is_alt_pressed()
-            || key_event.is_meta_pressed()
-        {
+        // Cancel on Escape or any modifier key combination (Ctrl+X, Alt+X, etc.; AltGr types)
+        if keycode == Key::ESCAPE || is_command_chord(key_event) {
             self.pending_char_op = None;
+            self.pending_dead_key = None;
             crate::verbose_print!(
                 "[godot-neovim] Cancelled pending char op '{}' due to modifier/escape",
                 op
@@ -40,31 +40,63 @@ impl GodotNeovimPlugin {
             return false;
         }
 
-        // Get the character
+        // Dead key: the composed character comes with the next key
+        if layout::is_dead_key(key_event) {
+            crate::verbose_print!("[godot-neovim] {}: Dead key, waiting for the character", op);
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return true;
+        }
+
+        // Get the character (any Unicode scalar, e.g. é typed with a dead key)
         let unicode = key_event.get_unicode();
         if unicode > 0 {
             if let Some(c) = char::from_u32(unicode) {
+                // Accent reported on its own: compose it with the next character
+                if self.pending_dead_key.is_none() && layout::dead_key_mark(c).is_some() {
+                    self.pending_dead_key = Some(c);
+                    if let Some(mut viewport) = self.base().get_viewport() {
+                        viewport.set_input_as_handled();
+                    }
+                    return true;
+                }
+                let c = match self.pending_dead_key.take() {
+                    Some(accent) => layout::compose_dead_key(accent, c),
+                    None => c,
+                };
+
                 self.pending_char_op = None;
-                // Build the key sequence for f/F/t/T
+                // Build the key sequence for f/F/t/T ('<' would start a key notation)
+                let target = if c == '<' {
+                    "<lt>".to_string()
+                } else {
+                    c.to_string()
+                };
                 let keys = match op {
-                    'f' | 'F' | 't' | 'T' | 'r' => Some(format!("{}{}", op, c)),
+                    'f' | 'F' | 't' | 'T' | 'r' => Some(format!("{}{}", op, target)),
                     _ => None,
                 };
 
-                match op {
-                    'f' => self.find_char_forward(c, false),
-                    'F' => self.find_char_backward(c, false),
-                    't' => self.find_char_forward(c, true),
-                    'T' => self.find_char_backward(c, true),
+                // The count stays pending for send_keys, which prefixes it
+                let count = self.peek_count();
+                let moved = match op {
+                    'f' => self.find_char(c, true, false, count),
+                    'F' => self.find_char(c, false, false, count),
+                    't' => self.find_char(c, true, true, count),
+                    'T' => self.find_char(c, false, true, count),
                     // 'r' is sent to Neovim via keys above (Neovim Master design)
-                    _ => {}
-                }
+                    _ => false,
+                };
 
-                // Send to Neovim and record to local macro buffer
+                // Send to Neovim (unless found on another line) and record to local
+                // macro buffer
                 if let Some(keys) = keys {
-                    self.send_keys(&keys);
+                    if !moved {
+                        self.send_keys(&keys);
+                    }
                     // Record to local macro buffer (early return skips normal recording)
-                    if self.recording_macro.is_some() && !self.playing_macro {
+                    if self.recording_macro.is_some() {
                         self.macro_buffer.push(keys);
                     }
                 }
@@ -77,6 +109,7 @@ impl GodotNeovimPlugin {
 
         // Non-printable key pressed - cancel the pending operation
         self.pending_char_op = None;
+        self.pending_dead_key = None;
         crate::verbose_print!(
             "[godot-neovim] Cancelled pending char op '{}' due to non-printable key",
             op
@@ -103,11 +136,7 @@ impl GodotNeovimPlugin {
         }
 
         // Cancel on Escape or any modifier key combination
-        if keycode == Key::ESCAPE
-            || key_event.is_ctrl_pressed()
-            || key_event.is_alt_pressed()
-            || key_event.is_meta_pressed()
-        {
+        if keycode == Key::ESCAPE || is_command_chord(key_event) {
             self.pending_mark_op = None;
             crate::verbose_print!(
                 "[godot-neovim] Cancelled pending mark op '{}' due to modifier/escape",
@@ -117,11 +146,16 @@ impl GodotNeovimPlugin {
             return false;
         }
 
-        // Get the character (must be a-z for marks)
+        // Get the character (a-z buffer marks, A-Z file marks, special marks)
         let unicode = key_event.get_unicode();
         if unicode > 0 {
             if let Some(c) = char::from_u32(unicode) {
-                if c.is_ascii_lowercase() {
+                let is_valid_mark = if op == 'm' {
+                    is_settable_mark(c)
+                } else {
+                    is_jumpable_mark(c)
+                };
+                if is_valid_mark {
                     self.pending_mark_op = None;
                     match op {
                         'm' => self.set_mark(c),
@@ -134,7 +168,7 @@ impl GodotNeovimPlugin {
                     }
                     return true;
                 }
-                // Non a-z character - cancel and let it be processed normally
+                // Not a mark - cancel and let it be processed normally
                 self.pending_mark_op = None;
                 crate::verbose_print!(
                     "[godot-neovim] Cancelled pending mark op '{}' - invalid mark char '{}'",
@@ -173,11 +207,7 @@ impl GodotNeovimPlugin {
         }
 
         // Cancel on Escape or any modifier key combination
-        if keycode == Key::ESCAPE
-            || key_event.is_ctrl_pressed()
-            || key_event.is_alt_pressed()
-            || key_event.is_meta_pressed()
-        {
+        if keycode == Key::ESCAPE || is_command_chord(key_event) {
             self.pending_macro_op = None;
             crate::verbose_print!(
                 "[godot-neovim] Cancelled pending macro op '{}' due to modifier/escape",
@@ -194,9 +224,11 @@ impl GodotNeovimPlugin {
                 self.pending_macro_op = None;
                 match op {
                     'q' => {
-                        // Start recording if a-z
+                        // Start recording if a-z, q/ and q? list the search history
                         if c.is_ascii_lowercase() {
                             self.start_macro_recording(c);
+                        } else if c == '/' || c == '?' {
+                            self.show_search_history_picker(c == '/');
                         } else {
                             crate::verbose_print!(
                                 "[godot-neovim] Macro recording cancelled - invalid register '{}'",
@@ -258,18 +290,11 @@ impl GodotNeovimPlugin {
             return true;
         }
 
-        // Get the character
-        // Valid registers: a-z (named), " (unnamed), + and * (clipboard), _ (black hole), 0 (yank)
+        // Get the character (any register Neovim knows except the expression register)
         let unicode = key_event.get_unicode();
         if unicode > 0 {
             if let Some(c) = char::from_u32(unicode) {
-                let is_valid_register = c.is_ascii_lowercase()
-                    || c == '"'
-                    || c == '+'
-                    || c == '*'
-                    || c == '_'
-                    || c == '0';
-                if is_valid_register {
+                if is_valid_register(c) {
                     self.selected_register = Some(c);
                     crate::verbose_print!("[godot-neovim] \"{}: Register selected", c);
                     if let Some(mut viewport) = self.base().get_viewport() {
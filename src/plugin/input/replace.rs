@@ -1,4 +1,10 @@
 //! Replace mode input handling
+//!
+//! Replace mode is routed entirely through Neovim (vscode-neovim model):
+//! every key is sent to Neovim and the overwritten text comes back via
+//! buffer events. This keeps R semantics exact - <BS> restores the original
+//! characters, counts repeat the replacement on <Esc> (5Rfoo<Esc>), undo is a
+//! single change, and multi-byte characters are handled by Neovim.
 
 use super::super::GodotNeovimPlugin;
 use godot::global::Key;
@@ -16,7 +22,7 @@ impl GodotNeovimPlugin {
 
         if is_escape || is_ctrl_bracket {
             // Record <Esc> to macro buffer before send_escape
-            if self.recording_macro.is_some() && !self.playing_macro {
+            if self.recording_macro.is_some() {
                 self.macro_buffer.push("<Esc>".to_string());
             }
             self.send_escape();
@@ -26,74 +32,35 @@ impl GodotNeovimPlugin {
             return;
         }
 
-        // Ctrl/Alt modified keys are sent to Neovim
-        // IMPORTANT: Only send actual Vim commands (<C-...>, <A-...>), not plain characters
+        let mut nvim_key = self.key_event_to_nvim_notation(key_event);
+
         // IME like CorvusSKK may report composed characters with ctrl modifier still set
+        // Only forward Ctrl/Alt keys that map to actual Vim commands (<C-...>, <A-...>)
         let ctrl = key_event.is_ctrl_pressed();
         let alt = key_event.is_alt_pressed();
-        if ctrl || alt {
-            let nvim_key = self.key_event_to_nvim_notation(key_event);
-            // Only send if it's an actual Vim command notation (starts with <)
-            if !nvim_key.is_empty() && nvim_key.starts_with('<') {
-                // Record to macro buffer
-                if self.recording_macro.is_some() && !self.playing_macro {
-                    self.macro_buffer.push(nvim_key.clone());
-                }
-                self.send_keys(&nvim_key);
-                if let Some(mut viewport) = self.base().get_viewport() {
-                    viewport.set_input_as_handled();
-                }
-            }
+        if (ctrl || alt) && !nvim_key.starts_with('<') {
             return;
         }
 
-        // Record keys to macro buffer if recording
-        if self.recording_macro.is_some() && !self.playing_macro {
-            let keycode = key_event.get_keycode();
-            // Special keys
-            match keycode {
-                Key::BACKSPACE => {
-                    self.macro_buffer.push("<BS>".to_string());
-                }
-                Key::ENTER => {
-                    self.macro_buffer.push("<CR>".to_string());
-                }
-                Key::DELETE => {
-                    self.macro_buffer.push("<Del>".to_string());
-                }
-                Key::TAB => {
-                    self.macro_buffer.push("<Tab>".to_string());
-                }
-                _ => {
-                    // Normal characters
-                    let unicode = key_event.get_unicode();
-                    if unicode > 0 {
-                        if let Some(c) = char::from_u32(unicode) {
-                            self.macro_buffer.push(c.to_string());
-                        }
-                    }
-                }
-            }
+        // Modifier-only keys and unmapped keys (e.g. F1-F12) are left to Godot
+        if nvim_key.is_empty() {
+            return;
         }
 
-        // In replace mode, we need to delete the character under cursor
-        // before letting Godot insert the new character
-        // This simulates overwrite behavior
-        let unicode = key_event.get_unicode();
-        if unicode > 0 {
-            if let Some(ref mut editor) = self.current_editor {
-                let line = editor.get_caret_line();
-                let col = editor.get_caret_column();
-                let line_text: String = editor.get_line(line).to_string();
+        // A literal '<' must be escaped so Neovim doesn't parse it as key notation
+        if nvim_key == "<" {
+            nvim_key = "<LT>".to_string();
+        }
 
-                // Only delete if we're not at end of line
-                if (col as usize) < line_text.chars().count() {
-                    // Delete character at cursor
-                    editor.select(line, col, line, col + 1);
-                    editor.delete_selection();
-                }
-            }
+        // Record keys to macro buffer if recording
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(nvim_key.clone());
+        }
+
+        // Neovim performs the overwrite; Godot must not type the character itself
+        self.send_keys(&nvim_key);
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
         }
-        // Let Godot insert the character
     }
 }
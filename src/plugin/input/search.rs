@@ -12,14 +12,19 @@ impl GodotNeovimPlugin {
         let keycode = key_event.get_keycode();
 
         if keycode == Key::ESCAPE {
-            self.close_search_mode();
+            self.cancel_search_mode();
         } else if keycode == Key::ENTER {
             self.execute_search();
+        } else if keycode == Key::UP {
+            self.search_history_up();
+        } else if keycode == Key::DOWN {
+            self.search_history_down();
         } else if keycode == Key::BACKSPACE {
             // Remove last character (but keep the '/' or '?')
             if self.search_buffer.len() > 1 {
                 self.search_buffer.pop();
                 self.update_search_display();
+                self.update_incremental_search();
             }
         } else {
             // Append character to search buffer
@@ -28,6 +33,7 @@ impl GodotNeovimPlugin {
                 if let Some(c) = char::from_u32(unicode) {
                     self.search_buffer.push(c);
                     self.update_search_display();
+                    self.update_incremental_search();
                 }
             }
         }
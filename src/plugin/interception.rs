@@ -0,0 +1,143 @@
+//! Key interception table (Key Interception setting)
+//!
+//! The setting maps keys in Neovim notation to whether the plugin intercepts
+//! them (`true`) or leaves them to Godot (`false`), e.g. `{"<C-d>": false}` keeps
+//! Godot's multi-caret selection. A `n:`, `i:` or `v:` prefix limits an entry to
+//! Normal, Insert (and Replace) or Visual mode, and wins over an entry without
+//! one. The table is consulted before any of the plugin's key handlers; keys
+//! not in it keep their built-in behavior.
+
+use super::user_mappings::parse_keys;
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+
+/// An entry of the Key Interception setting
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct InterceptionRule {
+    /// Mode the entry is limited to (`n`, `i`, `v`), None for all modes
+    mode: Option<char>,
+    /// Normalized key (see parse_keys)
+    key: String,
+    /// Whether the plugin handles the key (false: Godot does)
+    intercept: bool,
+}
+
+/// Parse an entry (`<C-d>`, `n:<C-f>`); None if it is not a single key
+fn parse_rule(entry: &str, intercept: bool) -> Option<InterceptionRule> {
+    let (mode, key) = match entry.split_once(':') {
+        Some((mode @ ("n" | "i" | "v"), key)) if !key.is_empty() => (mode.chars().next(), key),
+        _ => (None, entry),
+    };
+    let mut keys = parse_keys(key, "");
+    if keys.len() != 1 {
+        return None;
+    }
+    Some(InterceptionRule {
+        mode,
+        key: keys.remove(0),
+        intercept,
+    })
+}
+
+/// Whether the plugin intercepts `key` in `mode` (None: not in the table)
+fn lookup(rules: &[InterceptionRule], mode: char, key: &str) -> Option<bool> {
+    let find = |mode: Option<char>| {
+        rules
+            .iter()
+            .find(|rule| rule.mode == mode && rule.key == key)
+            .map(|rule| rule.intercept)
+    };
+    find(Some(mode)).or_else(|| find(None))
+}
+
+impl GodotNeovimPlugin {
+    /// Read the Key Interception setting (at activation and when the settings change)
+    pub(super) fn refresh_key_interception(&mut self) {
+        self.key_interception = settings::get_key_interception()
+            .into_iter()
+            .filter_map(|(entry, intercept)| {
+                let rule = parse_rule(&entry, intercept);
+                if rule.is_none() {
+                    godot_warn!(
+                        "[godot-neovim] Key Interception: '{}' is not a single key, ignored",
+                        entry
+                    );
+                }
+                rule
+            })
+            .collect();
+    }
+
+    /// Whether the Key Interception setting leaves the key to Godot in the current mode
+    /// The command line and search prompt of the plugin always get their keys.
+    pub(super) fn is_key_left_to_godot(&self, key_event: &Gd<InputEventKey>) -> bool {
+        if self.key_interception.is_empty() || self.command_mode || self.search_mode {
+            return false;
+        }
+        let Some(key) = self.interception_key(key_event) else {
+            return false;
+        };
+        let mode = if self.is_insert_mode() || self.is_replace_mode() {
+            'i'
+        } else if self.is_in_visual_mode() {
+            'v'
+        } else {
+            'n'
+        };
+        lookup(&self.key_interception, mode, &key) == Some(false)
+    }
+
+    /// Normalized key of an event for the table
+    /// Ctrl/Alt+letter is taken from the keycode (the event may have no unicode).
+    fn interception_key(&self, key_event: &Gd<InputEventKey>) -> Option<String> {
+        let keycode = key_event.get_keycode();
+        let ctrl = key_event.is_ctrl_pressed();
+        let alt = key_event.is_alt_pressed();
+        let notation = if (ctrl || alt) && (Key::A.ord()..=Key::Z.ord()).contains(&keycode.ord()) {
+            let mut mods = String::new();
+            for (pressed, flag) in [
+                (ctrl, "C-"),
+                (alt, "A-"),
+                (key_event.is_shift_pressed(), "S-"),
+            ] {
+                if pressed {
+                    mods.push_str(flag);
+                }
+            }
+            let letter = char::from_u32(keycode.ord() as u32)?.to_ascii_lowercase();
+            format!("<{}{}>", mods, letter)
+        } else {
+            self.key_event_to_nvim_string(key_event)?
+        };
+        parse_keys(&notation, "").into_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interception_rules() {
+        let rules: Vec<InterceptionRule> = [
+            ("<C-D>", false),
+            ("n:<C-f>", false),
+            ("i:<C-d>", true),
+            ("<leader>x", false),
+            (":", false),
+        ]
+        .into_iter()
+        .filter_map(|(entry, intercept)| parse_rule(entry, intercept))
+        .collect();
+        assert_eq!(rules.len(), 4);
+
+        assert_eq!(lookup(&rules, 'n', "<C-d>"), Some(false));
+        assert_eq!(lookup(&rules, 'i', "<C-d>"), Some(true));
+        assert_eq!(lookup(&rules, 'n', "<C-f>"), Some(false));
+        assert_eq!(lookup(&rules, 'i', "<C-f>"), None);
+        assert_eq!(lookup(&rules, 'v', ":"), Some(false));
+    }
+}
@@ -1,5 +1,6 @@
 //! Key conversion utilities
 
+use super::layout;
 use super::GodotNeovimPlugin;
 use godot::classes::InputEventKey;
 use godot::global::Key;
@@ -9,8 +10,10 @@ impl GodotNeovimPlugin {
     /// Convert Godot key event to Neovim key string
     pub(super) fn key_event_to_nvim_string(&self, event: &Gd<InputEventKey>) -> Option<String> {
         let keycode = event.get_keycode();
-        let ctrl = event.is_ctrl_pressed();
-        let alt = event.is_alt_pressed();
+        // A character typed with AltGr (Ctrl+Alt) is not a chord
+        let altgr = layout::is_altgr_char(event);
+        let ctrl = event.is_ctrl_pressed() && !altgr;
+        let alt = event.is_alt_pressed() && !altgr;
         let shift = event.is_shift_pressed();
 
         // Ctrl+[ is equivalent to Escape (terminal standard)
@@ -36,6 +39,10 @@ impl GodotNeovimPlugin {
             // Note: F1-F12 are intentionally NOT mapped - let Godot handle them
             // F1 opens :help in Neovim which causes freezes
             Key::SPACE => " ".to_string(),
+            _ if !ctrl && !alt => {
+                // Typed character, by keyboard layout and Key Layout Overrides
+                self.vim_key(event)?.to_string()
+            }
             _ => {
                 // Get unicode character
                 let unicode = event.get_unicode();
@@ -151,13 +158,91 @@ impl GodotNeovimPlugin {
         String::new()
     }
 
-    /// Get and clear the count buffer, returning 1 if empty
+    /// Get and clear the count of the current command, returning 1 if none
     pub(super) fn get_and_clear_count(&mut self) -> i32 {
-        if self.count_buffer.is_empty() {
-            return 1;
-        }
-        let count = self.count_buffer.parse::<i32>().unwrap_or(1).max(1);
+        self.take_count().unwrap_or(1)
+    }
+
+    /// Count of the current command without consuming it (1 if none)
+    pub(super) fn peek_count(&self) -> i32 {
+        Self::combine_counts(&self.count_buffer, &self.dispatched_count).unwrap_or(1)
+    }
+
+    /// Take the count of the current command (None if no count was typed)
+    /// Counts are held here until a command uses them: commands sent to Neovim get
+    /// the count as a key prefix (see send_keys), local commands take it directly.
+    pub(super) fn take_count(&mut self) -> Option<i32> {
+        let count = Self::combine_counts(&self.count_buffer, &self.dispatched_count);
         self.count_buffer.clear();
+        self.dispatched_count.clear();
         count
     }
+
+    /// Hand the typed count over to a key dispatched to the GDScript keymap
+    /// The action runs deferred; the count expires with the next key (see
+    /// resolve_count_before_key) so it never leaks into a later command.
+    pub(super) fn dispatch_count(&mut self) {
+        if !self.count_buffer.is_empty() {
+            self.dispatched_count = std::mem::take(&mut self.count_buffer);
+        }
+    }
+
+    /// Count resolution, before a Normal/Visual mode key is handled.
+    /// A count handed to the previous dispatched action has been used (or ignored)
+    /// by now and must not apply to this key. Returns the pending count.
+    pub(super) fn resolve_count_before_key(&mut self) -> String {
+        self.dispatched_count.clear();
+        self.count_buffer.clone()
+    }
+
+    /// Count resolution, after a Normal/Visual mode key was handled.
+    /// Every command takes the count when it runs (send_keys prefixes it for Neovim,
+    /// local handlers call get_and_clear_count). A count left untouched by a key
+    /// that completed a command was ignored by it and is dropped here, so it cannot
+    /// apply to the next command.
+    pub(super) fn resolve_count_after_key(
+        &mut self,
+        key_event: &Gd<InputEventKey>,
+        count_before: &str,
+    ) {
+        if self.count_buffer.is_empty() || self.count_buffer != count_before {
+            return;
+        }
+        // Modifier presses come before the key they modify (3 Shift+G)
+        if matches!(
+            key_event.get_keycode(),
+            Key::SHIFT | Key::CTRL | Key::ALT | Key::META | Key::CAPSLOCK | Key::NUMLOCK
+        ) {
+            return;
+        }
+        // The command is not complete yet (3g_, "a3p, 3fx)
+        if !self.last_key.is_empty()
+            || self.selected_register.is_some()
+            || self.pending_char_op.is_some()
+            || self.pending_mark_op.is_some()
+            || self.pending_macro_op.is_some()
+        {
+            return;
+        }
+        crate::verbose_print!(
+            "[godot-neovim] Count {} not used by the command",
+            self.count_buffer
+        );
+        self.count_buffer.clear();
+    }
+
+    /// Counts multiply, like Vim's 2"a3p
+    fn combine_counts(typed: &str, dispatched: &str) -> Option<i32> {
+        let typed = typed.parse::<i32>().ok();
+        let dispatched = dispatched.parse::<i32>().ok();
+        if typed.is_none() && dispatched.is_none() {
+            return None;
+        }
+        Some(
+            typed
+                .unwrap_or(1)
+                .saturating_mul(dispatched.unwrap_or(1))
+                .max(1),
+        )
+    }
 }
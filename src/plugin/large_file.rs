@@ -0,0 +1,191 @@
+//! Large-file mode: scripts with more lines than the Large File Lines setting
+//!
+//! Opening such a script sends only the first chunk of lines with the buffer
+//! switch. The rest is appended in chunks through the input queue, so the RPC
+//! runs on the worker threads while the editor stays responsive; keys typed
+//! meanwhile are queued behind the chunks. If a chunk fails, the remaining ones
+//! are cancelled and, once the last one has answered, the whole text is sent
+//! again from the editor (see on_buffer_chunk). Expensive per-update features are
+//! reduced: the visual selection is polled from Neovim at most every
+//! VISUAL_POLL_INTERVAL, and the hlsearch overlay is not drawn.
+
+use super::GodotNeovimPlugin;
+use crate::neovim::{NeovimClient, RpcRequest, CHUNK_CANCELLED};
+use crate::settings;
+use godot::prelude::*;
+use rmpv::Value;
+use std::time::{Duration, Instant};
+
+/// Lines per RPC call when loading a large file
+const CHUNK_LINES: usize = 2000;
+
+/// Minimum interval between visual selection polls in large-file mode
+const VISUAL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Whether a script of `line_count` lines is opened in large-file mode
+pub(super) fn is_large(line_count: usize) -> bool {
+    let threshold = settings::get_large_file_lines();
+    threshold > 0 && line_count > threshold
+}
+
+/// Split the lines of a large file into the first chunk (sent with the buffer
+/// switch) and the chunks appended after it
+pub(super) fn split_chunks(mut lines: Vec<String>) -> (Vec<String>, Vec<Vec<String>>) {
+    if lines.len() <= CHUNK_LINES {
+        return (lines, Vec::new());
+    }
+    let rest = lines.split_off(CHUNK_LINES);
+    let chunks = rest.chunks(CHUNK_LINES).map(<[String]>::to_vec).collect();
+    (lines, chunks)
+}
+
+/// Queue the remaining chunks of a large file after the buffer switch
+/// (`path` is the script path the responses are reported for)
+/// Returns false if the input queue is not available.
+pub(super) fn queue_chunks(
+    client: &NeovimClient,
+    bufnr: i64,
+    path: &str,
+    chunks: Vec<Vec<String>>,
+) -> bool {
+    let count = chunks.len();
+    chunks.into_iter().enumerate().all(|(i, chunk)| {
+        let last = i + 1 == count;
+        let lines = Value::Array(chunk.into_iter().map(Value::from).collect());
+        client.request_lua(
+            RpcRequest::BufferChunk {
+                path: path.to_string(),
+                last,
+            },
+            "return _G.godot_neovim.buffer_append_lines(...)",
+            vec![Value::from(bufnr), lines, Value::from(last)],
+        )
+    })
+}
+
+impl GodotNeovimPlugin {
+    /// Whether the current script is in large-file mode
+    pub(super) fn is_large_file(&self) -> bool {
+        is_large(self.sync_manager.get_line_count().max(0) as usize)
+    }
+
+    /// Handle the response to a chunk of a large file
+    /// On the first failure the remaining chunks are cancelled: Neovim's buffer
+    /// would otherwise differ from the editor. Once the last chunk has answered
+    /// (Neovim runs requests in order), the whole text is sent again.
+    pub(super) fn on_buffer_chunk(
+        &mut self,
+        path: &str,
+        last: bool,
+        result: Result<Value, String>,
+    ) {
+        let tick = match result {
+            Ok(value) => value.as_i64().unwrap_or(-1),
+            Err(e) if e == CHUNK_CANCELLED => -1,
+            Err(e) => {
+                if self
+                    .large_file_resync
+                    .insert(path.to_string(), false)
+                    .is_none()
+                {
+                    godot_error!("[godot-neovim] Failed to load {}: {}", path, e);
+                    if !last {
+                        if let Some(client) = self.script_neovim.as_ref() {
+                            if let Ok(client) = client.try_lock() {
+                                client.cancel_buffer_chunks(path);
+                            }
+                        }
+                    }
+                }
+                -1
+            }
+        };
+        if !last {
+            return;
+        }
+
+        if let Some(answered) = self.large_file_resync.get_mut(path) {
+            *answered = true;
+            if self.current_script_path == path {
+                self.resync_large_file();
+            }
+            return;
+        }
+        crate::verbose_print!("[godot-neovim] Large file loaded: {} (tick={})", path, tick);
+
+        // The chunks were not notified: their changedtick is the new sync baseline
+        if tick >= 0 && self.current_script_path == path {
+            self.sync_manager.set_initial_sync_tick(tick);
+        }
+    }
+
+    /// Send the whole text of the current script to Neovim if its large-file
+    /// load failed and all its chunks have answered (called when the last one
+    /// does, and after switching back to a script that was not current then)
+    pub(super) fn resync_large_file(&mut self) {
+        if self.large_file_resync.get(&self.current_script_path) != Some(&true) {
+            return;
+        }
+        self.large_file_resync.remove(&self.current_script_path);
+        self.show_status_message("Large file failed to load in Neovim: sending it again");
+        self.sync_buffer_to_neovim_reset_undo();
+    }
+
+    /// Whether the visual selection may be polled from Neovim now
+    /// In large-file mode a skipped poll is retried by poll_deferred_visual_selection.
+    pub(super) fn visual_poll_due(&mut self) -> bool {
+        if !self.is_large_file() {
+            return true;
+        }
+        if self
+            .last_visual_poll
+            .is_some_and(|time| time.elapsed() < VISUAL_POLL_INTERVAL)
+        {
+            self.visual_poll_pending = true;
+            return false;
+        }
+        self.last_visual_poll = Some(Instant::now());
+        self.visual_poll_pending = false;
+        true
+    }
+
+    /// Apply a visual selection update skipped by visual_poll_due (called every frame)
+    pub(super) fn poll_deferred_visual_selection(&mut self) {
+        if !self.visual_poll_pending
+            || self
+                .last_visual_poll
+                .is_some_and(|time| time.elapsed() < VISUAL_POLL_INTERVAL)
+        {
+            return;
+        }
+        self.visual_poll_pending = false;
+        if !self.is_in_visual_mode() {
+            return;
+        }
+        self.syncing_from_grid = true;
+        if self.current_mode == "V" {
+            self.update_visual_line_selection();
+        } else {
+            self.update_visual_selection();
+        }
+        self.syncing_from_grid = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_chunks() {
+        let lines: Vec<String> = (0..CHUNK_LINES * 2 + 1).map(|i| i.to_string()).collect();
+        let (first, chunks) = split_chunks(lines);
+        assert_eq!(first.len(), CHUNK_LINES);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0][0], CHUNK_LINES.to_string());
+        assert_eq!(chunks[1], vec![(CHUNK_LINES * 2).to_string()]);
+
+        let (first, chunks) = split_chunks(vec!["a".to_string()]);
+        assert_eq!((first.len(), chunks.len()), (1, 0));
+    }
+}
@@ -0,0 +1,189 @@
+//! Keyboard layout independence: the Vim key typed by a key event
+//!
+//! Normal mode commands are matched by the character the key types, so `[`, `^`,
+//! `$` or `;` work wherever the layout puts them (AZERTY, QWERTZ, JIS), also when
+//! typed with AltGr (reported as Ctrl+Alt on Windows). A key that types no
+//! character falls back to its label in the current layout (keycode), then to its
+//! position on a US QWERTY keyboard (physical keycode). Ctrl chords keep using the
+//! label, like terminal Vim.
+//!
+//! The Key Layout Overrides setting maps typed characters to other Vim keys, e.g.
+//! `{"ö": "[", "ä": "]"}` on a German keyboard. Overrides apply to commands only:
+//! the character after f/t/r, a mark or a register name is taken as typed.
+//!
+//! Dead keys (´ then e types é) compose the target character of f/t/r: a dead key
+//! reported without a character waits for the composed one, and an accent reported
+//! on its own is combined with the next character here.
+
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+use std::collections::HashMap;
+
+/// Character of a printable ASCII keycode (Godot uses the ASCII code; letters are
+/// upper case), with Shift applied to letters
+fn keycode_char(code: i32, shift: bool) -> Option<char> {
+    let c = u8::try_from(code)
+        .ok()
+        .filter(|c| (b'!'..=b'~').contains(c))? as char;
+    Some(if c.is_ascii_alphabetic() && !shift {
+        c.to_ascii_lowercase()
+    } else {
+        c
+    })
+}
+
+/// Parse an entry of the Key Layout Overrides setting: one typed character to one
+/// Vim key
+fn parse_override(typed: &str, vim_key: &str) -> Option<(char, char)> {
+    let mut typed = typed.chars();
+    let mut vim_key = vim_key.chars();
+    match (typed.next(), typed.next(), vim_key.next(), vim_key.next()) {
+        (Some(from), None, Some(to), None) => Some((from, to)),
+        _ => None,
+    }
+}
+
+/// Precomposed characters of the common dead key accents: (combining mark, base
+/// characters, composed characters)
+const DEAD_KEY_COMPOSITIONS: &[(char, &str, &str)] = &[
+    ('\u{300}', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    ('\u{301}', "aeiouycnszAEIOUYCNSZ", "áéíóúýćńśźÁÉÍÓÚÝĆŃŚŹ"),
+    ('\u{302}', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    ('\u{303}', "anoANO", "ãñõÃÑÕ"),
+    ('\u{308}', "aeiouyAEIOUY", "äëïöüÿÄËÏÖÜŸ"),
+    ('\u{30a}', "auAU", "åůÅŮ"),
+    ('\u{30c}', "cdenrstzCDENRSTZ", "čďěňřšťžČĎĚŇŘŠŤŽ"),
+    ('\u{327}', "csCS", "çşÇŞ"),
+];
+
+/// Combining mark of an accent typed by a dead key: a combining mark itself, or a
+/// spacing accent that is not ASCII (`^`, `` ` `` and `~` are f/t/r targets)
+pub(super) fn dead_key_mark(c: char) -> Option<char> {
+    match c {
+        '\u{300}'..='\u{36f}' => Some(c),
+        '´' => Some('\u{301}'),
+        '¨' => Some('\u{308}'),
+        '˚' => Some('\u{30a}'),
+        'ˇ' => Some('\u{30c}'),
+        '¸' => Some('\u{327}'),
+        _ => None,
+    }
+}
+
+/// Character typed by a dead key `accent` followed by `base`: the precomposed
+/// character, the accent itself for Space, otherwise `base` (like most layouts)
+pub(super) fn compose_dead_key(accent: char, base: char) -> char {
+    let composed = dead_key_mark(accent).and_then(|mark| {
+        let (_, bases, composed) = DEAD_KEY_COMPOSITIONS
+            .iter()
+            .find(|(combining, _, _)| *combining == mark)?;
+        let index = bases.chars().position(|c| c == base)?;
+        composed.chars().nth(index)
+    });
+    match composed {
+        Some(c) => c,
+        None if base == ' ' => accent,
+        None => base,
+    }
+}
+
+/// Whether the key event is a dead key whose character comes with the next key
+/// event: a key that types nothing and is not a special key (Enter, arrows, ...)
+pub(super) fn is_dead_key(event: &Gd<InputEventKey>) -> bool {
+    let keycode = event.get_keycode();
+    event.get_unicode() == 0 && (keycode == Key::UNKNOWN || keycode.ord() & Key::SPECIAL.ord() == 0)
+}
+
+/// Whether the key event types a character with AltGr (Ctrl+Alt plus a character)
+pub(super) fn is_altgr_char(event: &Gd<InputEventKey>) -> bool {
+    event.is_ctrl_pressed() && event.is_alt_pressed() && event.get_unicode() >= 0x20
+}
+
+/// Whether the key event is a Ctrl/Alt/Meta chord (typing with AltGr is not)
+pub(super) fn is_command_chord(event: &Gd<InputEventKey>) -> bool {
+    (event.is_ctrl_pressed() || event.is_alt_pressed() || event.is_meta_pressed())
+        && !is_altgr_char(event)
+}
+
+/// The character typed by the key event, as Vim would see it
+pub(super) fn typed_char(event: &Gd<InputEventKey>) -> Option<char> {
+    let shift = event.is_shift_pressed();
+    let unicode = event.get_unicode();
+    if unicode >= 0x20 && unicode != 0x7f {
+        let c = char::from_u32(unicode)?;
+        // get_unicode may not include Shift for letters
+        return Some(if shift && c.is_ascii_lowercase() {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        });
+    }
+    let physical = event.get_physical_keycode();
+    keycode_char(event.get_keycode().ord(), shift).or_else(|| {
+        (physical != Key::NONE)
+            .then(|| keycode_char(physical.ord(), shift))
+            .flatten()
+    })
+}
+
+impl GodotNeovimPlugin {
+    /// Read the Key Layout Overrides setting (at activation and when the settings change)
+    pub(super) fn refresh_layout_overrides(&mut self) {
+        self.layout_overrides = settings::get_key_layout_overrides()
+            .into_iter()
+            .filter_map(|(typed, vim_key)| {
+                let entry = parse_override(&typed, &vim_key);
+                if entry.is_none() {
+                    godot_warn!(
+                        "[godot-neovim] Key Layout Overrides: '{}' -> '{}' is not one character to one key, ignored",
+                        typed,
+                        vim_key
+                    );
+                }
+                entry
+            })
+            .collect::<HashMap<char, char>>();
+    }
+
+    /// The Vim key of a Normal/Visual mode command typed by the key event (see
+    /// typed_char), after the Key Layout Overrides
+    pub(super) fn vim_key(&self, event: &Gd<InputEventKey>) -> Option<char> {
+        let c = typed_char(event)?;
+        Some(self.layout_overrides.get(&c).copied().unwrap_or(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keycode_char_and_overrides() {
+        assert_eq!(keycode_char('N' as i32, false), Some('n'));
+        assert_eq!(keycode_char('N' as i32, true), Some('N'));
+        assert_eq!(keycode_char('[' as i32, false), Some('['));
+        assert_eq!(keycode_char(';' as i32, true), Some(';'));
+        assert_eq!(keycode_char(' ' as i32, false), None);
+        assert_eq!(keycode_char(0x40_0000 | 0x2a, false), None);
+
+        assert_eq!(parse_override("ö", "["), Some(('ö', '[')));
+        assert_eq!(parse_override("ü", "<Esc>"), None);
+        assert_eq!(parse_override("", "]"), None);
+    }
+
+    #[test]
+    fn test_compose_dead_key() {
+        for (_, bases, composed) in DEAD_KEY_COMPOSITIONS {
+            assert_eq!(bases.chars().count(), composed.chars().count());
+        }
+        assert_eq!(compose_dead_key('´', 'e'), 'é');
+        assert_eq!(compose_dead_key('\u{308}', 'U'), 'Ü');
+        assert_eq!(compose_dead_key('ˇ', 's'), 'š');
+        assert_eq!(compose_dead_key('´', ' '), '´');
+        assert_eq!(compose_dead_key('´', 'x'), 'x');
+        assert_eq!(dead_key_mark('^'), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Leader key mappings (`<leader>ff` in the GDScript keymap)
+//!
+//! The leader key (Editor Settings, default `<Space>`) starts a sequence when the
+//! keymap has `<leader>` mappings. The keys typed after it are collected until
+//! they name a mapping, which is dispatched as `<leader>{keys}`. Keys that cannot
+//! become a mapping are sent to Neovim as typed, like an unmapped sequence in Vim.
+//!
+//! A sequence held for timeoutlen opens the leader palette instead of timing out:
+//! a which-key style list of the keys that can follow, with the action each one
+//! runs (or the number of mappings behind it). It stays open until a key is typed.
+
+use super::float::FloatPlacement;
+use super::user_mappings::parse_keys;
+use super::GodotNeovimPlugin;
+use crate::settings;
+use std::collections::BTreeMap;
+
+/// Prefix of leader mappings in the keymap (and of last_key while one is typed)
+pub(super) const LEADER_PREFIX: &str = "<leader>";
+
+/// Leader key from the settings as produced by key_event_to_nvim_string
+fn leader_key() -> String {
+    let key = settings::get_leader_key();
+    match key.to_ascii_lowercase().as_str() {
+        "<space>" => " ".to_string(),
+        "<bslash>" => "\\".to_string(),
+        _ => key,
+    }
+}
+
+/// Label of an action in the leader palette (`action_find_files` -> `find files`)
+fn action_label(action: &str) -> String {
+    action
+        .strip_prefix("action_")
+        .unwrap_or(action)
+        .replace('_', " ")
+}
+
+/// Entries of the leader palette after `typed`: the next key and what it does
+/// (the action's label, or the number of mappings when more keys follow)
+fn palette_entries(mappings: &BTreeMap<String, String>, typed: &str) -> Vec<(String, String)> {
+    let mut next: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (keys, action) in mappings {
+        let Some(rest) = keys.strip_prefix(typed) else {
+            continue;
+        };
+        let rest_keys = parse_keys(rest, "");
+        let Some(key) = rest_keys.first() else {
+            continue;
+        };
+        let entry = next.entry(key.clone()).or_default();
+        if rest_keys.len() == 1 {
+            entry.insert(0, action);
+        } else {
+            entry.push("");
+        }
+    }
+    next.into_iter()
+        .map(|(key, actions)| {
+            let label = match actions.as_slice() {
+                [action] if !action.is_empty() => action_label(action),
+                [_] => "+1 mapping".to_string(),
+                _ => format!("+{} mappings", actions.len()),
+            };
+            let key = if key == " " {
+                "<Space>".to_string()
+            } else {
+                key
+            };
+            (key, label)
+        })
+        .collect()
+}
+
+/// Outcome of a key typed after the leader
+pub(super) enum LeaderKey {
+    /// The keys so far start a mapping: wait for more
+    Pending,
+    /// A mapping was completed: dispatch `<leader>{keys}`
+    Mapped(String),
+    /// No mapping starts with the keys: they were sent to Neovim (Esc: dropped)
+    Unmapped,
+}
+
+impl GodotNeovimPlugin {
+    /// Leader key typed in Normal mode (with no other sequence pending) while the
+    /// keymap has leader mappings
+    pub(super) fn try_start_leader(&mut self, keys: &str) -> bool {
+        if self.leader_mappings.is_empty()
+            || !self.last_key.is_empty()
+            || self.current_mode != "n"
+            || keys != leader_key()
+        {
+            return false;
+        }
+        self.set_last_key(LEADER_PREFIX);
+        true
+    }
+
+    /// Key typed after the leader (last_key starts with `<leader>`)
+    pub(super) fn feed_leader_key(&mut self, keys: &str) -> LeaderKey {
+        let typed = format!("{}{}", &self.last_key[LEADER_PREFIX.len()..], keys);
+
+        // Esc cancels the sequence (the leader is not sent to Neovim)
+        if keys == "<Esc>" {
+            self.clear_last_key();
+            self.close_leader_palette();
+            return LeaderKey::Unmapped;
+        }
+
+        if self.leader_mappings.contains_key(&typed) {
+            self.clear_last_key();
+            self.close_leader_palette();
+            crate::verbose_print!("[godot-neovim] <leader>{}", typed);
+            return LeaderKey::Mapped(format!("{}{}", LEADER_PREFIX, typed));
+        }
+        if self
+            .leader_mappings
+            .keys()
+            .any(|mapping| mapping.starts_with(&typed))
+        {
+            self.set_last_key(format!("{}{}", LEADER_PREFIX, typed));
+            // An open palette follows the keys and keeps waiting for the next one
+            if self.leader_palette.is_some() {
+                self.show_leader_palette();
+            }
+            return LeaderKey::Pending;
+        }
+
+        self.clear_last_key();
+        self.close_leader_palette();
+        let keys = format!("{}{}", leader_key(), typed);
+        crate::verbose_print!("[godot-neovim] <leader>{}: Not mapped", typed);
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(keys.clone());
+        }
+        self.send_keys(&keys);
+        LeaderKey::Unmapped
+    }
+
+    /// Open the leader palette once a leader sequence has been held for timeoutlen,
+    /// and close it when the sequence ended some other way (called every frame)
+    pub(super) fn poll_leader_palette(&mut self) {
+        if !self.last_key.starts_with(LEADER_PREFIX) {
+            self.close_leader_palette();
+            return;
+        }
+        if self.leader_palette.is_some() {
+            return;
+        }
+        let held = self
+            .last_key_time
+            .is_some_and(|time| time.elapsed().as_millis() > settings::get_timeoutlen() as u128);
+        if held {
+            self.show_leader_palette();
+        }
+    }
+
+    /// Show the keys that can follow the typed leader sequence
+    fn show_leader_palette(&mut self) {
+        let typed = self.last_key[LEADER_PREFIX.len()..].to_string();
+        let entries = palette_entries(&self.leader_mappings, &typed);
+        let mut bbcode = format!(
+            "[b]{}{}[/b]",
+            settings::get_leader_key(),
+            typed.replace('[', "[lb]")
+        );
+        for (key, label) in &entries {
+            bbcode.push_str(&format!(
+                "\n[color=yellow]{}[/color]  {}",
+                key.replace('[', "[lb]"),
+                label
+            ));
+        }
+
+        Self::free_editor_float(self.leader_palette.take());
+        self.leader_palette = self.create_editor_float(&bbcode, FloatPlacement::BelowCaret);
+        // The palette waits for the next key: no timeout while it is open
+        self.last_key_time = None;
+    }
+
+    pub(super) fn close_leader_palette(&mut self) {
+        Self::free_editor_float(self.leader_palette.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_entries() {
+        let mappings: BTreeMap<String, String> = [
+            ("ff", "action_find_files"),
+            ("fg", "action_live_grep"),
+            ("e", "action_focus_filesystem"),
+            ("r<C-p>", "action_run_project"),
+        ]
+        .into_iter()
+        .map(|(keys, action)| (keys.to_string(), action.to_string()))
+        .collect();
+
+        assert_eq!(
+            palette_entries(&mappings, ""),
+            vec![
+                ("e".to_string(), "focus filesystem".to_string()),
+                ("f".to_string(), "+2 mappings".to_string()),
+                ("r".to_string(), "+1 mapping".to_string()),
+            ]
+        );
+        assert_eq!(
+            palette_entries(&mappings, "f"),
+            vec![
+                ("f".to_string(), "find files".to_string()),
+                ("g".to_string(), "live grep".to_string()),
+            ]
+        );
+        assert_eq!(
+            palette_entries(&mappings, "r"),
+            vec![("<C-p>".to_string(), "run project".to_string())]
+        );
+    }
+}
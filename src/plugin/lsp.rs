@@ -0,0 +1,742 @@
+//! LSP features backed by Godot's built-in language server: rename, references, hover,
+//! signature help
+//!
+//! Note: Like gd, these use Godot's LSP (port 6005) instead of Neovim LSP
+//! (see go_to_definition_lsp in editing.rs for the rationale)
+
+use super::float::FloatPlacement;
+use super::picker::PickerItem;
+use super::GodotNeovimPlugin;
+use crate::lsp::GodotLspClient;
+use godot::classes::{CodeEdit, EditorInterface, InputEventKey, ProjectSettings};
+use godot::global::Key;
+use godot::prelude::*;
+use lsp_types::{
+    DocumentChangeOperation, DocumentChanges, OneOf, ParameterLabel, SignatureHelp, TextEdit,
+    WorkspaceEdit,
+};
+use std::sync::Arc;
+
+/// Convert an LSP `character` offset (UTF-16 code units) into a character column of
+/// `line`, the unit Godot and the Neovim edit helper use
+fn utf16_to_char_col(line: &str, utf16: u32) -> i32 {
+    let mut units = 0;
+    let mut col = 0;
+    for c in line.chars() {
+        if units >= utf16 {
+            break;
+        }
+        units += c.len_utf16() as u32;
+        col += 1;
+    }
+    col
+}
+
+/// Convert a character column of `line` into an LSP `character` offset (UTF-16 code units)
+fn char_col_to_utf16(line: &str, col: i32) -> u32 {
+    line.chars()
+        .take(col.max(0) as usize)
+        .map(|c| c.len_utf16() as u32)
+        .sum()
+}
+
+impl GodotNeovimPlugin {
+    /// Convert an absolute file path to a file:// URI (handles Windows paths)
+    pub(super) fn path_to_uri(abs_path: &str) -> String {
+        if abs_path.starts_with('/') {
+            format!("file://{}", abs_path)
+        } else {
+            // Windows path: C:/... -> file:///C:/...
+            format!("file:///{}", abs_path.replace('\\', "/"))
+        }
+    }
+
+    /// Get absolute path of the current script
+    pub(super) fn current_script_abs_path(&self) -> String {
+        if self.current_script_path.starts_with("res://") {
+            ProjectSettings::singleton()
+                .globalize_path(&self.current_script_path)
+                .to_string()
+        } else {
+            self.current_script_path.clone()
+        }
+    }
+
+    /// file:// URI of the project folder (the LSP workspace)
+    pub(super) fn project_root_uri() -> String {
+        let project_root = ProjectSettings::singleton()
+            .globalize_path("res://")
+            .to_string();
+        Self::path_to_uri(&project_root)
+    }
+
+    /// Connect, initialize and send didOpen for the current script
+    /// Returns the LSP client and the current document URI, or None on failure
+    /// (failure reason is shown in the status line, prefixed with `tag`)
+    pub(super) fn prepare_lsp_request(
+        &mut self,
+        tag: &str,
+    ) -> Option<(Arc<GodotLspClient>, String)> {
+        let Some(lsp) = self.godot_lsp.clone() else {
+            self.show_status_message(&format!("{}: Enable 'Use Thread' in Editor Settings", tag));
+            return None;
+        };
+
+        let text = self.current_editor.as_ref()?.get_text().to_string();
+        let uri = Self::path_to_uri(&self.current_script_abs_path());
+
+        // Get project root for LSP initialization
+        let root_uri = Self::project_root_uri();
+
+        if !lsp.is_connected() {
+            if let Err(e) = lsp.connect(6005) {
+                self.show_status_message(&format!("LSP connect failed: {}", e));
+                return None;
+            }
+            crate::verbose_print!("[godot-neovim] {}: Connected to LSP", tag);
+        }
+
+        if !lsp.is_initialized() {
+            if let Err(e) = lsp.initialize(&root_uri) {
+                self.show_status_message(&format!("LSP init failed: {}", e));
+                return None;
+            }
+            crate::verbose_print!("[godot-neovim] {}: LSP initialized", tag);
+        }
+
+        // Send didOpen to ensure LSP knows about the latest buffer content
+        if let Err(e) = lsp.did_open(&uri, &text) {
+            crate::verbose_print!("[godot-neovim] {}: didOpen warning: {}", tag, e);
+        }
+
+        Some((lsp, uri))
+    }
+
+    /// Extract word under cursor (alphanumeric and underscore)
+    pub(super) fn word_under_cursor(&self) -> Option<String> {
+        let editor = self.current_editor.as_ref()?;
+        let line_text = editor.get_line(editor.get_caret_line()).to_string();
+        let col_idx = editor.get_caret_column() as usize;
+        let chars: Vec<char> = line_text.chars().collect();
+
+        if col_idx >= chars.len() {
+            return None;
+        }
+
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let mut start = col_idx;
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = col_idx;
+        while end < chars.len() && is_word_char(chars[end]) {
+            end += 1;
+        }
+
+        if start == end {
+            return None;
+        }
+        Some(chars[start..end].iter().collect())
+    }
+
+    /// Caret position of the current editor as an LSP (line, UTF-16 character) pair
+    fn caret_lsp_position(&self) -> Option<(u32, u32)> {
+        let editor = self.current_editor.as_ref()?;
+        let line = editor.get_caret_line();
+        let col = char_col_to_utf16(
+            &editor.get_line(line).to_string(),
+            editor.get_caret_column(),
+        );
+        Some((line as u32, col))
+    }
+
+    /// gR - Start LSP rename: open command line prefilled with `:rename <word>`
+    pub(super) fn lsp_rename_prompt(&mut self) {
+        let Some(word) = self.word_under_cursor() else {
+            self.show_status_message("gR: No symbol under cursor");
+            return;
+        };
+
+        self.open_command_line();
+        self.command_buffer = format!(":rename {}", word);
+        self.update_command_display();
+    }
+
+    /// :rename {new_name} - Rename symbol under cursor across open scripts
+    pub(in crate::plugin) fn cmd_lsp_rename(&mut self, new_name: &str) {
+        if new_name.is_empty() {
+            godot_warn!("[godot-neovim] :rename - E471: Argument required");
+            return;
+        }
+
+        let Some((line, col)) = self.caret_lsp_position() else {
+            return;
+        };
+
+        let Some((lsp, uri)) = self.prepare_lsp_request("rename") else {
+            return;
+        };
+
+        crate::verbose_print!(
+            "[godot-neovim] rename: Requesting rename at {}:{}:{} -> {}",
+            uri,
+            line,
+            col,
+            new_name
+        );
+
+        match lsp.rename(&uri, line, col, new_name) {
+            Ok(Some(edit)) => {
+                let (files, edits, skipped) = self.apply_workspace_edit(edit, ":rename");
+                if skipped > 0 {
+                    godot_warn!(
+                        "[godot-neovim] :rename - {} file(s) not open in the editor were skipped",
+                        skipped
+                    );
+                }
+                godot_print!(
+                    "[godot-neovim] :rename - Renamed to '{}': {} change(s) in {} file(s)",
+                    new_name,
+                    edits,
+                    files
+                );
+            }
+            Ok(None) => {
+                godot_warn!("[godot-neovim] :rename - Nothing to rename");
+            }
+            Err(e) => {
+                godot_warn!("[godot-neovim] :rename - LSP error: {}", e);
+            }
+        }
+    }
+
+    /// grr - Find references to the symbol under cursor and show them in the picker
+    pub(super) fn lsp_references(&mut self) {
+        let Some((line, col)) = self.caret_lsp_position() else {
+            return;
+        };
+
+        let Some((lsp, uri)) = self.prepare_lsp_request("grr") else {
+            return;
+        };
+
+        crate::verbose_print!(
+            "[godot-neovim] grr: Requesting references at {}:{}:{}",
+            uri,
+            line,
+            col
+        );
+
+        let locations = match lsp.references(&uri, line, col, true) {
+            Ok(locations) => locations,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] grr: LSP error: {}", e);
+                self.show_status_message(&format!("LSP error: {}", e));
+                return;
+            }
+        };
+
+        if locations.is_empty() {
+            self.show_status_message("No references found");
+            return;
+        }
+
+        let current_abs = self.current_script_abs_path().replace('\\', "/");
+        let mut file_cache: std::collections::HashMap<String, Vec<String>> =
+            std::collections::HashMap::new();
+        let mut items = Vec::with_capacity(locations.len());
+
+        for location in &locations {
+            let path = Self::uri_to_file_path(location.uri.as_str()).replace('\\', "/");
+            let line = location.range.start.line as i32;
+
+            // Line preview: current buffer from the editor, other files from disk
+            let preview = if path == current_abs {
+                self.current_editor
+                    .as_ref()
+                    .map(|e| e.get_line(line).to_string())
+                    .unwrap_or_default()
+            } else {
+                let lines = file_cache.entry(path.clone()).or_insert_with(|| {
+                    std::fs::read_to_string(&path)
+                        .map(|text| text.lines().map(String::from).collect())
+                        .unwrap_or_default()
+                });
+                lines.get(line as usize).cloned().unwrap_or_default()
+            };
+            let col = utf16_to_char_col(&preview, location.range.start.character);
+
+            let display_path = ProjectSettings::singleton()
+                .localize_path(&path)
+                .to_string();
+            items.push(PickerItem {
+                label: format!(
+                    "{}:{}:{}  {}",
+                    display_path,
+                    line + 1,
+                    col + 1,
+                    preview.trim()
+                ),
+                path,
+                position: Some((line, col)),
+            });
+        }
+
+        crate::verbose_print!("[godot-neovim] grr: {} reference(s) found", items.len());
+        if let [PickerItem {
+            path,
+            position: Some((line, col)),
+            ..
+        }] = items.as_slice()
+        {
+            self.jump_to_location(path, *line, *col);
+            return;
+        }
+        self.show_picker(&format!("References ({})", items.len()), items);
+    }
+
+    /// Flatten a WorkspaceEdit into (uri, edits) pairs
+    fn workspace_edit_to_changes(edit: WorkspaceEdit) -> Vec<(String, Vec<TextEdit>)> {
+        let mut changes: Vec<(String, Vec<TextEdit>)> = Vec::new();
+
+        if let Some(map) = edit.changes {
+            for (uri, edits) in map {
+                changes.push((uri.as_str().to_string(), edits));
+            }
+        }
+
+        let doc_edits = match edit.document_changes {
+            Some(DocumentChanges::Edits(edits)) => edits,
+            Some(DocumentChanges::Operations(ops)) => ops
+                .into_iter()
+                .filter_map(|op| match op {
+                    DocumentChangeOperation::Edit(edit) => Some(edit),
+                    // File create/rename/delete is not supported
+                    DocumentChangeOperation::Op(_) => None,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        for doc_edit in doc_edits {
+            let edits = doc_edit
+                .edits
+                .into_iter()
+                .map(|e| match e {
+                    OneOf::Left(edit) => edit,
+                    OneOf::Right(annotated) => annotated.text_edit,
+                })
+                .collect();
+            changes.push((doc_edit.text_document.uri.as_str().to_string(), edits));
+        }
+
+        changes
+    }
+
+    /// Apply a WorkspaceEdit to the current buffer (via Neovim) and other open scripts
+    /// (`tag` prefixes the warnings)
+    /// Returns (files changed, edits applied, files skipped because they are not open)
+    pub(super) fn apply_workspace_edit(
+        &mut self,
+        edit: WorkspaceEdit,
+        tag: &str,
+    ) -> (usize, usize, usize) {
+        let current_abs = self.current_script_abs_path().replace('\\', "/");
+        let mut files = 0;
+        let mut edit_count = 0;
+        let mut skipped = 0;
+
+        for (uri, mut edits) in Self::workspace_edit_to_changes(edit) {
+            if edits.is_empty() {
+                continue;
+            }
+            let path = Self::uri_to_file_path(&uri).replace('\\', "/");
+
+            // Apply bottom-up so earlier edits don't shift later positions
+            edits.sort_by(|a, b| {
+                (b.range.start.line, b.range.start.character)
+                    .cmp(&(a.range.start.line, a.range.start.character))
+            });
+
+            if path == current_abs || path == self.current_script_path {
+                let Some(editor) = self.current_editor.as_ref() else {
+                    continue;
+                };
+                let nvim_edits = Self::char_column_edits(editor, &edits);
+                // Current buffer: Neovim Master - apply in Neovim, changes come back
+                // through on_lines and the sync manager (marks both sides dirty)
+                self.begin_edit_transaction();
+                let result = match self.get_current_neovim() {
+                    Some(neovim) => match neovim.try_lock() {
+                        Ok(client) => client.apply_text_edits("", nvim_edits),
+                        Err(_) => Err("Neovim busy".to_string()),
+                    },
+                    None => Err("No neovim".to_string()),
+                };
+                self.commit_edit_transaction();
+                match result {
+                    Ok(true) => {
+                        files += 1;
+                        edit_count += edits.len();
+                    }
+                    Ok(false) => {
+                        godot_warn!(
+                            "[godot-neovim] {} - Current buffer not loaded in Neovim",
+                            tag
+                        );
+                    }
+                    Err(e) => {
+                        godot_warn!("[godot-neovim] {} - Failed to apply edits: {}", tag, e);
+                    }
+                }
+                continue;
+            }
+
+            // Other scripts: edit the open CodeEdit directly (Godot marks it dirty),
+            // then mirror the edits into Neovim's buffer if it has been loaded
+            let Some(mut code_edit) = Self::find_open_script_code_edit(&path) else {
+                crate::verbose_print!("[godot-neovim] {}: Skipping {} (not open)", tag, path);
+                skipped += 1;
+                continue;
+            };
+
+            let nvim_edits = Self::char_column_edits(&code_edit, &edits);
+            code_edit.begin_complex_operation();
+            for (sl, sc, el, ec, text) in &nvim_edits {
+                let (sl, sc, el, ec) = (*sl as i32, *sc as i32, *el as i32, *ec as i32);
+                if sl != el || sc != ec {
+                    code_edit.remove_text(sl, sc, el, ec);
+                }
+                if !text.is_empty() {
+                    code_edit.insert_text(text, sl, sc);
+                }
+            }
+            code_edit.end_complex_operation();
+
+            if let Some(neovim) = self.neovim_for(super::EditorType::Script) {
+                if let Ok(client) = neovim.try_lock() {
+                    if let Err(e) = client.apply_text_edits(&path, nvim_edits) {
+                        crate::verbose_print!(
+                            "[godot-neovim] {}: Failed to mirror edits to Neovim: {}",
+                            tag,
+                            e
+                        );
+                    }
+                }
+            }
+
+            files += 1;
+            edit_count += edits.len();
+        }
+
+        (files, edit_count, skipped)
+    }
+
+    /// Convert the edits' UTF-16 ranges into (start line, start col, end line, end col,
+    /// text) with character columns of the lines in `code_edit`
+    /// (all converted against the text before any edit is applied)
+    fn char_column_edits(
+        code_edit: &Gd<CodeEdit>,
+        edits: &[TextEdit],
+    ) -> Vec<(i64, i64, i64, i64, String)> {
+        let char_col = |line: u32, character: u32| {
+            let text = code_edit.get_line(line as i32).to_string();
+            utf16_to_char_col(&text, character) as i64
+        };
+        edits
+            .iter()
+            .map(|e| {
+                (
+                    e.range.start.line as i64,
+                    char_col(e.range.start.line, e.range.start.character),
+                    e.range.end.line as i64,
+                    char_col(e.range.end.line, e.range.end.character),
+                    e.new_text.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Find the CodeEdit of an open (non-current) script by absolute path
+    pub(super) fn find_open_script_code_edit(abs_path: &str) -> Option<Gd<CodeEdit>> {
+        let script_editor = EditorInterface::singleton().get_script_editor()?;
+        let open_scripts = script_editor.get_open_scripts();
+        let open_editors = script_editor.get_open_script_editors();
+
+        // get_open_scripts() and get_open_script_editors() share the same tab order
+        // only when no non-script documents (text files, help pages) are open
+        if open_scripts.len() != open_editors.len() {
+            crate::verbose_print!(
+                "[godot-neovim] rename: Script/editor count mismatch ({} vs {})",
+                open_scripts.len(),
+                open_editors.len()
+            );
+            return None;
+        }
+
+        for i in 0..open_scripts.len() {
+            let Some(script) = open_scripts.get(i) else {
+                continue;
+            };
+            let res_path = script.get_path().to_string();
+            let script_abs = ProjectSettings::singleton()
+                .globalize_path(&res_path)
+                .to_string()
+                .replace('\\', "/");
+            if script_abs == abs_path {
+                return open_editors
+                    .get(i)?
+                    .get_base_editor()?
+                    .try_cast::<CodeEdit>()
+                    .ok();
+            }
+        }
+
+        None
+    }
+
+    /// K (hover mode) - Show LSP hover text for the symbol under cursor in a float
+    pub(super) fn lsp_hover(&mut self) {
+        let Some((line, col)) = self
+            .current_editor
+            .as_ref()
+            .map(|e| (e.get_caret_line() as u32, e.get_caret_column() as u32))
+        else {
+            return;
+        };
+
+        let Some((lsp, uri)) = self.prepare_lsp_request("K") else {
+            return;
+        };
+
+        let content = match lsp.hover(&uri, line, col) {
+            Ok(Some(hover)) => Self::hover_contents_to_string(&hover),
+            Ok(None) => String::new(),
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] K: LSP hover error: {}", e);
+                self.show_status_message(&format!("LSP error: {}", e));
+                return;
+            }
+        };
+
+        if content.trim().is_empty() {
+            self.show_status_message("K: No hover information");
+            return;
+        }
+
+        self.close_hover_float();
+        self.hover_float = self.create_editor_float(
+            &Self::markdown_to_bbcode(&content),
+            FloatPlacement::BelowCaret,
+        );
+    }
+
+    /// Dismiss the hover float on key press
+    /// Returns true if the key should be consumed (Escape only closes the float)
+    pub(super) fn dismiss_hover_float(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        if !self.close_hover_float() {
+            return false;
+        }
+        key_event.get_keycode() == Key::ESCAPE
+    }
+
+    /// Close the hover float if shown. Returns true if a float was closed.
+    pub(super) fn close_hover_float(&mut self) -> bool {
+        Self::free_editor_float(self.hover_float.take())
+    }
+
+    /// Insert mode (after `(` or `,`) - Show signature help above the caret
+    /// with the active parameter highlighted
+    pub(super) fn lsp_signature_help(&mut self) {
+        if !self.is_insert_mode() {
+            return;
+        }
+
+        // Stay silent while typing when LSP is unavailable (no status message spam)
+        if self.godot_lsp.is_none() {
+            return;
+        }
+
+        let Some((line, col)) = self
+            .current_editor
+            .as_ref()
+            .map(|e| (e.get_caret_line() as u32, e.get_caret_column() as u32))
+        else {
+            return;
+        };
+
+        let Some((lsp, uri)) = self.prepare_lsp_request("Signature help") else {
+            return;
+        };
+
+        let bbcode = match lsp.signature_help(&uri, line, col) {
+            Ok(Some(help)) => Self::signature_help_to_bbcode(&help),
+            Ok(None) => None,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Signature help: LSP error: {}", e);
+                None
+            }
+        };
+
+        self.close_signature_help();
+        if let Some(bbcode) = bbcode {
+            self.signature_float = self.create_editor_float(&bbcode, FloatPlacement::AboveCaret);
+        }
+    }
+
+    /// Close the signature help float if shown
+    pub(super) fn close_signature_help(&mut self) {
+        Self::free_editor_float(self.signature_float.take());
+    }
+
+    /// Render the active signature as BBCode, with the active parameter in bold
+    fn signature_help_to_bbcode(help: &SignatureHelp) -> Option<String> {
+        let index = help.active_signature.unwrap_or(0) as usize;
+        let signature = help
+            .signatures
+            .get(index)
+            .or_else(|| help.signatures.first())?;
+
+        let label: Vec<char> = signature.label.chars().collect();
+        let active = signature
+            .active_parameter
+            .or(help.active_parameter)
+            .unwrap_or(0) as usize;
+
+        // Character range of the active parameter within the label
+        let range = signature
+            .parameters
+            .as_ref()
+            .and_then(|params| params.get(active))
+            .and_then(|param| match &param.label {
+                ParameterLabel::Simple(name) => {
+                    let byte_idx = signature.label.find(name.as_str())?;
+                    let start = signature.label[..byte_idx].chars().count();
+                    Some((start, start + name.chars().count()))
+                }
+                ParameterLabel::LabelOffsets([start, end]) => {
+                    Some((*start as usize, (*end as usize).min(label.len())))
+                }
+            });
+
+        let escape = |chars: &[char]| chars.iter().collect::<String>().replace('[', "[lb]");
+        let mut out = String::from("[code]");
+        match range {
+            Some((start, end)) if start <= end && end <= label.len() => {
+                out.push_str(&escape(&label[..start]));
+                out.push_str("[b][u]");
+                out.push_str(&escape(&label[start..end]));
+                out.push_str("[/u][/b]");
+                out.push_str(&escape(&label[end..]));
+            }
+            _ => out.push_str(&escape(&label)),
+        }
+        out.push_str("[/code]");
+
+        if help.signatures.len() > 1 {
+            out.push_str(&format!(" ({}/{})", index + 1, help.signatures.len()));
+        }
+
+        Some(out)
+    }
+
+    /// Convert the subset of markdown produced by Godot's LSP hover to BBCode
+    /// (code fences, inline code, bold, links, `___` separators)
+    pub(super) fn markdown_to_bbcode(markdown: &str) -> String {
+        let mut out = String::new();
+        let mut in_fence = false;
+
+        for line in markdown.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") {
+                out.push_str(if in_fence { "[/code]\n" } else { "[code]" });
+                in_fence = !in_fence;
+                continue;
+            }
+            if in_fence {
+                out.push_str(&line.replace('[', "[lb]"));
+                out.push('\n');
+                continue;
+            }
+            if trimmed == "___" || trimmed == "---" {
+                out.push('\n');
+                continue;
+            }
+            out.push_str(&Self::markdown_inline_to_bbcode(line));
+            out.push('\n');
+        }
+        if in_fence {
+            out.push_str("[/code]");
+        }
+
+        out.trim_end().to_string()
+    }
+
+    /// Convert inline markdown (`code`, **bold**, [text](url)) in one line to BBCode
+    fn markdown_inline_to_bbcode(line: &str) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut out = String::new();
+        let mut in_code = false;
+        let mut in_bold = false;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '`' {
+                out.push_str(if in_code { "[/code]" } else { "[code]" });
+                in_code = !in_code;
+            } else if in_code {
+                if c == '[' {
+                    out.push_str("[lb]");
+                } else {
+                    out.push(c);
+                }
+            } else if c == '*' && chars.get(i + 1) == Some(&'*') {
+                out.push_str(if in_bold { "[/b]" } else { "[b]" });
+                in_bold = !in_bold;
+                i += 1;
+            } else if c == '[' {
+                // [text](url) -> text
+                let rest: String = chars[i + 1..].iter().collect();
+                match rest.split_once("](") {
+                    Some((text, after)) if after.contains(')') && !text.contains('[') => {
+                        out.push_str(text);
+                        let url_len = after.find(')').unwrap_or(0);
+                        i += 1 + text.chars().count() + 2 + after[..url_len].chars().count();
+                    }
+                    _ => out.push_str("[lb]"),
+                }
+            } else {
+                out.push(c);
+            }
+            i += 1;
+        }
+        if in_code {
+            out.push_str("[/code]");
+        }
+        if in_bold {
+            out.push_str("[/b]");
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_char_col_conversion() {
+        // The emoji is a surrogate pair: 2 UTF-16 code units, 1 character
+        let line = "var s = \"😀\"; x";
+        let x_col = line.chars().position(|c| c == 'x').unwrap() as i32;
+        assert_eq!(x_col, 13);
+        assert_eq!(utf16_to_char_col(line, 14), x_col);
+        assert_eq!(char_col_to_utf16(line, x_col), 14);
+        // Before the emoji both units agree
+        assert_eq!(utf16_to_char_col(line, 4), 4);
+        assert_eq!(char_col_to_utf16(line, 4), 4);
+        // Past the end clamps to the line length
+        assert_eq!(utf16_to_char_col(line, 100), line.chars().count() as i32);
+    }
+}
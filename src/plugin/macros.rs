@@ -1,73 +1,117 @@
 //! Macro recording and playback
+//!
+//! Macros are recorded and replayed by Neovim (native `q`/`@` registers). Keys
+//! handled in Godot's insert mode never reach Neovim, so they are collected here
+//! and spliced into the register when recording stops (see core.lua macro_finish).
 
 use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::prelude::*;
+
+/// Maximum number of characters of recorded keys shown next to the recording indicator
+const MACRO_KEYS_DISPLAY_CHARS: usize = 30;
+
+/// Typed into the recording where Godot-side insert keys belong
+/// (must match M.MACRO_INSERT_MARKER in core.lua)
+const MACRO_INSERT_MARKER: &str = "<Cmd><CR>";
 
 impl GodotNeovimPlugin {
     /// Start recording a macro to the specified register
     pub(super) fn start_macro_recording(&mut self, register: char) {
+        self.send_keys(&format!("q{}", register));
         self.recording_macro = Some(register);
         self.macro_buffer.clear();
-        self.update_recording_label(Some(register));
+        self.macro_insert_keys.clear();
+        self.show_macro_keys = settings::get_show_macro_keys();
+        self.macro_keys_shown = 0;
+        self.update_recording_label(Some(register), "");
         crate::verbose_print!("[godot-neovim] q{}: Started recording macro", register);
     }
 
-    /// Stop recording the current macro and save it
+    /// Stop recording the current macro (Neovim writes the register)
     pub(super) fn stop_macro_recording(&mut self) {
         if let Some(register) = self.recording_macro.take() {
-            let keys = std::mem::take(&mut self.macro_buffer);
-            self.update_recording_label(None);
-            if !keys.is_empty() {
-                self.macros.insert(register, keys.clone());
-                crate::verbose_print!(
-                    "[godot-neovim] q: Stopped recording macro '{}' ({} keys)",
-                    register,
-                    keys.len()
-                );
-            } else {
-                crate::verbose_print!(
-                    "[godot-neovim] q: Stopped recording macro '{}' (empty)",
-                    register
-                );
-            }
+            self.flush_macro_insert_keys();
+            self.send_keys("q");
+            let count = std::mem::take(&mut self.macro_buffer).len();
+            self.update_recording_label(None, "");
+            crate::verbose_print!(
+                "[godot-neovim] q: Stopped recording macro '{}' ({} keys)",
+                register,
+                count
+            );
         }
     }
 
-    /// Play a macro from the specified register
-    pub(super) fn play_macro(&mut self, register: char) {
-        let Some(keys) = self.macros.get(&register).cloned() else {
-            crate::verbose_print!("[godot-neovim] @{}: Macro not recorded", register);
+    /// Record a key handled in Godot's insert mode (Neovim never sees it)
+    pub(super) fn record_insert_key(&mut self, key: &str) {
+        if self.recording_macro.is_none() {
             return;
-        };
+        }
+        self.macro_buffer.push(key.to_string());
+        let notation = if key == "<" { "<lt>" } else { key };
+        self.macro_insert_keys.push(notation.to_string());
+    }
 
-        if keys.is_empty() {
-            crate::verbose_print!("[godot-neovim] @{}: Macro is empty", register);
+    /// Hand the Godot-side insert keys to Neovim and type a marker into the
+    /// recording at this point. Must be called before any key of the same insert
+    /// session is sent to Neovim, so the keys stay in typing order.
+    pub(super) fn flush_macro_insert_keys(&mut self) {
+        if self.macro_insert_keys.is_empty() {
             return;
         }
-
-        self.last_macro = Some(register);
-        self.playing_macro = true;
-
-        crate::verbose_print!(
-            "[godot-neovim] @{}: Playing macro ({} keys)",
-            register,
-            keys.len()
-        );
-
-        // Play back each key
-        for key in &keys {
-            self.send_keys(key);
+        let keys = std::mem::take(&mut self.macro_insert_keys).concat();
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            godot_warn!("[godot-neovim] q: Neovim is busy, inserted text not recorded");
+            return;
+        };
+        // Blocking calls run after the keys already queued, so the inserted
+        // keys are recorded after them
+        if let Err(e) = client.execute_lua_with_args(
+            "_G.godot_neovim.macro_insert(...)",
+            vec![rmpv::Value::from(keys.as_str())],
+        ) {
+            godot_warn!("[godot-neovim] q: Failed to record inserted text: {}", e);
+            return;
         }
+        // Via the key channel: the marker must follow the keys already sent
+        client.send_key_via_channel(MACRO_INSERT_MARKER);
+        crate::verbose_print!("[godot-neovim] q: Recorded inserted keys '{}'", keys);
+    }
+
+    /// Show the keys recorded so far next to the recording indicator (like 'showcmd')
+    /// Only the tail is shown so the indicator stays small
+    pub(super) fn refresh_macro_keys_display(&mut self) {
+        let Some(register) = self.recording_macro else {
+            return;
+        };
+        self.macro_keys_shown = self.macro_buffer.len();
+        let keys = self.macro_buffer.concat();
+        let count = keys.chars().count();
+        let tail = if count > MACRO_KEYS_DISPLAY_CHARS {
+            let skipped: String = keys
+                .chars()
+                .skip(count - MACRO_KEYS_DISPLAY_CHARS)
+                .collect();
+            format!("…{}", skipped)
+        } else {
+            keys
+        };
+        self.update_recording_label(Some(register), &tail);
+    }
 
-        self.playing_macro = false;
+    /// Play a macro from the specified register (Neovim replays it)
+    pub(super) fn play_macro(&mut self, register: char) {
+        crate::verbose_print!("[godot-neovim] @{}: Playing macro", register);
+        self.send_keys(&format!("@{}", register));
     }
 
     /// Replay the last played macro (@@)
     pub(super) fn replay_last_macro(&mut self) {
-        if let Some(register) = self.last_macro {
-            crate::verbose_print!("[godot-neovim] @@: Replaying macro '{}'", register);
-            self.play_macro(register);
-        } else {
-            crate::verbose_print!("[godot-neovim] @@: No macro played yet");
-        }
+        crate::verbose_print!("[godot-neovim] @@: Replaying last macro");
+        self.send_keys("@@");
     }
 }
@@ -1,121 +1,165 @@
 //! Marks and jump list functionality
-
+//!
+//! Marks are Neovim's own marks (Neovim Master design): `m{char}`, `'{char}` and
+//! `` `{char} `` are sent to Neovim, so buffer marks survive buffer switches.
+//! File marks (`A`-`Z`) in another script open that script first and jump once
+//! its buffer is current in Neovim.
+//!
+//! The jump list is Neovim's too: jumps Neovim does not see (gd, pickers) add the
+//! position with `m'`. Ctrl+O / Ctrl+I into another script switch Godot to it
+//! when Neovim reports the buffer change (BufEnter).
+
+use super::picker::PickerItem;
 use super::GodotNeovimPlugin;
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+use std::collections::HashMap;
+
+/// Whether `m{c}` sets a mark (buffer marks, file marks and the settable special marks)
+pub(super) fn is_settable_mark(c: char) -> bool {
+    c.is_ascii_alphabetic() || "'`[]<>".contains(c)
+}
+
+/// Whether `'{c}` / `` `{c} `` jumps to a mark (settable marks and `.`, `^`, `"`)
+pub(super) fn is_jumpable_mark(c: char) -> bool {
+    is_settable_mark(c) || ".^\"".contains(c)
+}
 
 impl GodotNeovimPlugin {
-    /// Set a mark at current position (m{a-z})
+    /// Set a mark at current position (m{a-zA-Z})
     pub(super) fn set_mark(&mut self, mark: char) {
-        let Some(ref editor) = self.current_editor else {
-            return;
-        };
-
-        let line = editor.get_caret_line();
-        let col = editor.get_caret_column();
-        self.marks.insert(mark, (line, col));
-        crate::verbose_print!(
-            "[godot-neovim] m{}: Set mark at line {}, col {}",
-            mark,
-            line + 1,
-            col
-        );
+        self.send_keys(&format!("m{}", mark));
+        crate::verbose_print!("[godot-neovim] m{}: Set mark", mark);
     }
 
-    /// Jump to mark line ('{a-z})
+    /// Jump to mark line ('{a-zA-Z}): first non-blank character
     pub(super) fn jump_to_mark_line(&mut self, mark: char) {
-        // Add to jump list before jumping
-        self.add_to_jump_list();
-
-        let Some((line, _)) = self.marks.get(&mark).copied() else {
-            crate::verbose_print!("[godot-neovim] '{}: Mark not set", mark);
-            return;
-        };
-
-        let Some(ref mut editor) = self.current_editor else {
-            return;
-        };
-
-        let line_count = editor.get_line_count();
-        let target_line = line.min(line_count - 1);
-        editor.set_caret_line(target_line);
-
-        // Move to first non-blank character (Vim behavior for ')
-        let line_text = editor.get_line(target_line).to_string();
-        let first_non_blank = line_text
-            .chars()
-            .position(|c| !c.is_whitespace())
-            .unwrap_or(0);
-        editor.set_caret_column(first_non_blank as i32);
-
-        self.sync_cursor_to_neovim();
-        crate::verbose_print!(
-            "[godot-neovim] '{}: Jumped to line {}",
-            mark,
-            target_line + 1
-        );
+        self.jump_to_mark(mark, '\'');
     }
 
-    /// Jump to exact mark position (`{a-z})
+    /// Jump to exact mark position (`{a-zA-Z})
     pub(super) fn jump_to_mark_position(&mut self, mark: char) {
-        // Add to jump list before jumping
-        self.add_to_jump_list();
-
-        let Some((line, col)) = self.marks.get(&mark).copied() else {
-            crate::verbose_print!("[godot-neovim] `{}: Mark not set", mark);
-            return;
-        };
-
-        let Some(ref mut editor) = self.current_editor else {
-            return;
-        };
+        self.jump_to_mark(mark, '`');
+    }
 
-        let line_count = editor.get_line_count();
-        let target_line = line.min(line_count - 1);
-        editor.set_caret_line(target_line);
+    /// Send the mark jump to Neovim; file marks in another script open it first
+    fn jump_to_mark(&mut self, mark: char, jump: char) {
+        let keys = format!("{}{}", jump, mark);
+        if mark.is_ascii_uppercase() {
+            let Some(file) = self.file_mark_path(mark) else {
+                self.show_status_message(&format!("E20: Mark not set: {}", mark));
+                return;
+            };
+            let file = file.replace('\\', "/");
+            if file != self.current_script_abs_path().replace('\\', "/") {
+                let res_path = ProjectSettings::singleton()
+                    .localize_path(&file)
+                    .to_string();
+                crate::verbose_print!("[godot-neovim] {}: Opening {}", keys, res_path);
+                self.pending_file_path = Some(res_path.clone());
+                self.pending_mark_jump = Some((res_path, keys));
+                return;
+            }
+        }
 
-        // Use chars().count() for character count, not byte length
-        let line_text = editor.get_line(target_line).to_string();
-        let line_length = line_text.chars().count() as i32;
-        let target_col = col.min(line_length.max(0));
-        editor.set_caret_column(target_col);
+        self.send_keys(&keys);
+        crate::verbose_print!("[godot-neovim] {}: Jump to mark", keys);
+    }
 
-        self.sync_cursor_to_neovim();
-        crate::verbose_print!(
-            "[godot-neovim] `{}: Jumped to line {}, col {}",
-            mark,
-            target_line + 1,
-            target_col
-        );
+    /// File of a file mark (A-Z), None if the mark is not set
+    fn file_mark_path(&self, mark: char) -> Option<String> {
+        let neovim = self.get_current_neovim()?;
+        let Ok(client) = neovim.try_lock() else {
+            crate::verbose_print!("[godot-neovim] '{}: Mutex busy", mark);
+            return None;
+        };
+        match client.get_marks() {
+            Ok(marks) => marks
+                .into_iter()
+                .find(|entry| entry.name == mark && !entry.file.is_empty())
+                .map(|entry| entry.file),
+            Err(e) => {
+                godot_warn!("[godot-neovim] Failed to get marks: {}", e);
+                None
+            }
+        }
     }
 
-    /// Add current position to jump list
+    /// Add the current position to Neovim's jumplist before a jump Neovim does
+    /// not see (gd, gf, picker locations)
     pub(super) fn add_to_jump_list(&mut self) {
-        let Some(ref editor) = self.current_editor else {
+        // m' is a Normal mode command; Visual and operator-pending jumps are Neovim's
+        if self.is_in_visual_mode() || Self::is_operator_pending_mode(&self.current_mode) {
             return;
-        };
-
-        let line = editor.get_caret_line();
-        let col = editor.get_caret_column();
+        }
+        self.send_keys("m'");
+    }
 
-        // Don't add duplicate consecutive entries
-        if let Some(&last) = self.jump_list.last() {
-            if last == (line, col) {
+    /// :jumps - Show Neovim's jumplist in the picker (newest first)
+    pub(super) fn show_jumps_picker(&mut self) {
+        let (entries, current) = {
+            let Some(neovim) = self.get_current_neovim() else {
+                godot_warn!("[godot-neovim] :jumps - Neovim not connected");
+                return;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                godot_warn!("[godot-neovim] :jumps - Neovim is busy");
                 return;
+            };
+            match client.get_jumplist() {
+                Ok(jumplist) => jumplist,
+                Err(e) => {
+                    godot_warn!("[godot-neovim] :jumps - {}", e);
+                    return;
+                }
             }
-        }
+        };
 
-        // If we're not at the end of the list, truncate
-        if self.jump_list_pos < self.jump_list.len() {
-            self.jump_list.truncate(self.jump_list_pos);
+        let mut file_cache: HashMap<String, Vec<String>> = HashMap::new();
+        let mut items = Vec::with_capacity(entries.len());
+        for (i, entry) in entries.iter().enumerate().rev() {
+            if entry.file.is_empty() {
+                continue;
+            }
+            let path = entry.file.replace('\\', "/");
+            let line = entry.line as i32 - 1;
+
+            // Buffers that are not loaded: line preview from disk
+            let text = if entry.text.is_empty() {
+                let lines = file_cache.entry(path.clone()).or_insert_with(|| {
+                    std::fs::read_to_string(&path)
+                        .map(|text| text.lines().map(String::from).collect())
+                        .unwrap_or_default()
+                });
+                lines.get(line.max(0) as usize).cloned().unwrap_or_default()
+            } else {
+                entry.text.clone()
+            };
+            let col = Self::byte_col_to_char_col(&text, entry.col as i32);
+
+            // Distance from the current position, like the first column of :jumps
+            let distance = (i as i64 - current as i64).abs();
+            let display_path = ProjectSettings::singleton()
+                .localize_path(&path)
+                .to_string();
+            items.push(PickerItem {
+                label: format!(
+                    "{:>3}  {}:{}:{}  {}",
+                    distance,
+                    display_path,
+                    line + 1,
+                    col + 1,
+                    text.trim()
+                ),
+                path,
+                position: Some((line, col)),
+            });
         }
 
-        self.jump_list.push((line, col));
-        self.jump_list_pos = self.jump_list.len();
-
-        // Limit jump list size
-        const MAX_JUMP_LIST: usize = 100;
-        if self.jump_list.len() > MAX_JUMP_LIST {
-            self.jump_list.remove(0);
-            self.jump_list_pos = self.jump_list.len();
+        if items.is_empty() {
+            self.show_status_message("Jumplist is empty");
+            return;
         }
+        self.show_picker("Jumps", items);
     }
 }
@@ -0,0 +1,258 @@
+//! Picker matching backends: built-in fuzzy matcher or an external fzf process
+//!
+//! The external backend runs `fzf --filter {query}` on a background thread with the
+//! item labels on stdin. Matches are streamed back through a channel in batches and
+//! polled from `process()`, so large item lists never block the editor.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// Number of matches sent to the main thread at once
+const MATCH_BATCH_SIZE: usize = 256;
+
+/// fzf exit code for "no match" (not an error)
+const FZF_EXIT_NO_MATCH: i32 = 1;
+
+/// Message from the matcher thread
+#[derive(Debug)]
+enum MatcherEvent {
+    /// Indices of matching items, in fzf's ranking order
+    Matches(Vec<usize>),
+    /// fzf finished normally
+    Done,
+    /// fzf could not run or exited with an error
+    Failed(String),
+}
+
+/// Result of polling an external match
+#[derive(Debug, Default)]
+pub(super) struct MatchUpdate {
+    /// First update for this query (replaces the previous results)
+    pub first: bool,
+    /// Newly received matches (appended after the previous ones)
+    pub matches: Vec<usize>,
+    /// The match is complete (no more updates)
+    pub done: bool,
+    /// fzf failed - the caller should fall back to the built-in matcher
+    pub error: Option<String>,
+}
+
+/// A running external match for one query
+pub(super) struct ExternalMatch {
+    /// Query the match was started for
+    pub query: String,
+    receiver: Receiver<MatcherEvent>,
+    /// Whether any update was returned by poll()
+    received: bool,
+}
+
+impl ExternalMatch {
+    /// Drain the events received so far (never blocks)
+    pub(super) fn poll(&mut self) -> MatchUpdate {
+        let mut update = MatchUpdate::default();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(MatcherEvent::Matches(indices)) => update.matches.extend(indices),
+                Ok(MatcherEvent::Done) => {
+                    update.done = true;
+                    break;
+                }
+                Ok(MatcherEvent::Failed(error)) => {
+                    update.done = true;
+                    update.error = Some(error);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    update.done = true;
+                    break;
+                }
+            }
+        }
+        if !update.matches.is_empty() || update.done {
+            update.first = !self.received;
+            self.received = true;
+        }
+        update
+    }
+}
+
+/// Built-in matcher: fuzzy match (see fuzzy_score), best matches first
+/// Items with the same score keep their order (an empty query lists all items in order).
+pub(super) fn builtin_match<'a>(labels: impl Iterator<Item = &'a str>, query: &str) -> Vec<usize> {
+    let mut matches: Vec<(i64, usize)> = labels
+        .enumerate()
+        .filter_map(|(i, label)| fuzzy_score(label, query).map(|score| (score, i)))
+        .collect();
+    matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    matches.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Fuzzy score of `text` for `query`: case-insensitive subsequence match, None if
+/// the query characters do not all appear in order (whitespace in the query is ignored)
+/// Consecutive characters and characters starting a word (after `/`, `_`, `-`, `.`)
+/// score higher, gaps lower; the best alignment over all starting positions is used.
+pub(super) fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let Some(&first) = query.first() else {
+        return Some(0);
+    };
+
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| fuzzy_score_from(&text, &query, start))
+        .max()
+}
+
+/// Score of a greedy match of `query` starting at `text[start]`
+fn fuzzy_score_from(text: &[char], query: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut prev: Option<usize> = None;
+    for &c in query {
+        let from = prev.map_or(start, |p| p + 1);
+        let i = (from..text.len()).find(|&i| text[i] == c)?;
+        score += 1;
+        match prev {
+            Some(p) if p + 1 == i => score += 5,
+            Some(p) => score -= (i - p - 1).min(10) as i64,
+            None => {}
+        }
+        if prev.is_none_or(|p| p + 1 != i) && (i == 0 || "/_-. ".contains(text[i - 1])) {
+            score += 3;
+        }
+        prev = Some(i);
+    }
+    Some(score)
+}
+
+/// Start `fzf --filter` for `query` on a background thread
+/// Dropping the returned ExternalMatch stops the process (stale queries are abandoned).
+pub(super) fn spawn_fzf(
+    fzf_path: &str,
+    labels: Vec<String>,
+    query: &str,
+) -> Result<ExternalMatch, String> {
+    let mut child = fzf_command(fzf_path, query)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", fzf_path, e))?;
+
+    // Feed "index<TAB>label" lines; fzf only matches the label (--with-nth=2..)
+    let mut stdin = child.stdin.take().ok_or("fzf stdin unavailable")?;
+    std::thread::spawn(move || {
+        for (i, label) in labels.iter().enumerate() {
+            let line = format!("{}\t{}\n", i, label.replace(['\n', '\r'], " "));
+            if stdin.write_all(line.as_bytes()).is_err() {
+                // fzf exited early (stale query killed)
+                return;
+            }
+        }
+    });
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let event = read_fzf_output(&mut child, &sender);
+        if event.is_none() {
+            // Receiver dropped: a newer query replaced this one
+            let _ = child.kill();
+        }
+        let _ = child.wait();
+    });
+
+    Ok(ExternalMatch {
+        query: query.to_string(),
+        receiver,
+        received: false,
+    })
+}
+
+/// Stream fzf's output to `sender`. Returns None if the receiver was dropped.
+fn read_fzf_output(child: &mut Child, sender: &mpsc::Sender<MatcherEvent>) -> Option<()> {
+    let stdout = child.stdout.take()?;
+    let mut batch = Vec::with_capacity(MATCH_BATCH_SIZE);
+    for line in BufReader::new(stdout).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if let Some(index) = line
+            .split_once('\t')
+            .and_then(|(index, _)| index.parse::<usize>().ok())
+        {
+            batch.push(index);
+        }
+        if batch.len() >= MATCH_BATCH_SIZE {
+            sender
+                .send(MatcherEvent::Matches(std::mem::take(&mut batch)))
+                .ok()?;
+        }
+    }
+    if !batch.is_empty() {
+        sender.send(MatcherEvent::Matches(batch)).ok()?;
+    }
+
+    let event = match child.wait() {
+        Ok(status) if status.success() || status.code() == Some(FZF_EXIT_NO_MATCH) => {
+            MatcherEvent::Done
+        }
+        Ok(status) => {
+            let mut stderr = String::new();
+            if let Some(mut err) = child.stderr.take() {
+                let _ = err.read_to_string(&mut stderr);
+            }
+            MatcherEvent::Failed(format!("fzf exited with {}: {}", status, stderr.trim()))
+        }
+        Err(e) => MatcherEvent::Failed(e.to_string()),
+    };
+    sender.send(event).ok()
+}
+
+/// Build the fzf command with platform-specific settings
+fn fzf_command(fzf_path: &str, query: &str) -> Command {
+    let mut command = Command::new(fzf_path);
+    command
+        .arg("--filter")
+        .arg(query)
+        .arg("--delimiter=\t")
+        .arg("--with-nth=2..");
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_matches_subsequence() {
+        assert!(fuzzy_score("res://scripts/player.gd", "plyr").is_some());
+        assert!(fuzzy_score("res://scripts/player.gd", "PLAYER").is_some());
+        assert!(fuzzy_score("res://scripts/player.gd", "enemy").is_none());
+        assert_eq!(fuzzy_score("res://scripts/player.gd", ""), Some(0));
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts_and_runs() {
+        let player = fuzzy_score("res://scripts/player.gd", "player").unwrap();
+        let scattered = fuzzy_score("res://scripts/p_l_a_y_e_r.gd", "player").unwrap();
+        assert!(player > scattered);
+
+        let word_start = fuzzy_score("res://ui/main_menu.gd", "menu").unwrap();
+        let inner = fuzzy_score("res://ui/mainmenu.gd", "menu").unwrap();
+        assert!(word_start > inner);
+    }
+}
@@ -0,0 +1,141 @@
+//! Neovim messages in the status bar (ext_messages)
+//!
+//! With ext_messages Neovim sends its messages (`E486: Pattern not found`,
+//! `3 lines yanked`, errors of user plugins) as msg_show events instead of
+//! drawing them on its grid, where they were never seen. The last one is shown
+//! next to the mode label of the current editor for MESSAGE_DURATION, or until
+//! Neovim clears its message area. Messages of several lines (`:messages`,
+//! `:registers`) are also printed to the Output panel.
+
+use super::GodotNeovimPlugin;
+use crate::neovim::NeovimMessage;
+use godot::classes::Label;
+use godot::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How long a message stays in the status bar
+const MESSAGE_DURATION: Duration = Duration::from_secs(4);
+
+/// Name of the message label in a status bar
+const MESSAGE_LABEL_NAME: &str = "NeovimMessageLabel";
+
+/// Message kinds that are not shown: the search index is shown by the plugin
+/// (search_count), and prompts wait for input the plugin never sends
+const IGNORED_KINDS: &[&str] = &["search_count", "return_prompt", "confirm", "confirm_sub"];
+
+/// Whether a message kind is an error
+fn is_error(kind: &str) -> bool {
+    matches!(kind, "emsg" | "echoerr" | "lua_error" | "rpc_error")
+}
+
+/// Status bar text of a message: its first line (the rest counted)
+fn status_text(text: &str) -> String {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let first = lines.next().unwrap_or_default().trim();
+    match lines.count() {
+        0 => first.to_string(),
+        more => format!("{} (+{} lines, see Output)", first, more),
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Show the messages taken from Neovim (`cleared`: the message area was
+    /// cleared before them)
+    pub(super) fn show_neovim_messages(&mut self, cleared: bool, messages: Vec<NeovimMessage>) {
+        if cleared {
+            self.hide_neovim_message();
+        }
+        let messages: Vec<NeovimMessage> = messages
+            .into_iter()
+            .filter(|message| {
+                !IGNORED_KINDS.contains(&message.kind.as_str()) && !message.text.trim().is_empty()
+            })
+            .collect();
+        for message in &messages {
+            crate::verbose_print!(
+                "[godot-neovim] msg_show ({}): {}",
+                message.kind,
+                message.text
+            );
+            if message.text.trim().lines().count() > 1 {
+                godot_print!("[godot-neovim] {}", message.text.trim_end());
+            }
+        }
+        let Some(message) = messages.last() else {
+            return;
+        };
+
+        let Some(mut label) = self.message_label() else {
+            return;
+        };
+        let color = if is_error(&message.kind) {
+            Color::from_rgb(1.0, 0.3, 0.3)
+        } else if message.kind == "wmsg" {
+            Color::from_rgb(1.0, 1.0, 0.4)
+        } else {
+            Color::from_rgb(1.0, 1.0, 1.0)
+        };
+        label.add_theme_color_override("font_color", color);
+        label.set_text(&format!(" {} ", status_text(&message.text)));
+        label.set_visible(true);
+        self.message_shown = Some(Instant::now());
+    }
+
+    /// Hide the message of the status bars
+    pub(super) fn hide_neovim_message(&mut self) {
+        self.message_shown = None;
+        self.message_labels
+            .retain(|label| label.is_instance_valid());
+        for label in self.message_labels.iter_mut() {
+            label.set_visible(false);
+        }
+    }
+
+    /// Hide the message after MESSAGE_DURATION (called every frame)
+    pub(super) fn poll_message_timeout(&mut self) {
+        if self
+            .message_shown
+            .is_some_and(|shown| shown.elapsed() >= MESSAGE_DURATION)
+        {
+            self.hide_neovim_message();
+        }
+    }
+
+    /// Free the message labels (plugin deactivation)
+    pub(super) fn remove_message_labels(&mut self) {
+        self.message_shown = None;
+        for mut label in self.message_labels.drain(..) {
+            if label.is_instance_valid() {
+                label.queue_free();
+            }
+        }
+    }
+
+    /// Message label of the current editor's status bar (created on first use)
+    /// Other status bars keep their labels hidden.
+    fn message_label(&mut self) -> Option<Gd<Label>> {
+        // After the mode and recording labels
+        let (mut label, created) = self.status_bar_label(MESSAGE_LABEL_NAME, 2)?;
+        self.hide_neovim_message();
+        if created {
+            label.set_clip_text(true);
+            label.set_h_size_flags(godot::classes::control::SizeFlags::EXPAND_FILL);
+            self.message_labels.push(label.clone());
+        }
+        Some(label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_text() {
+        assert_eq!(status_text("3 lines yanked"), "3 lines yanked");
+        assert_eq!(
+            status_text("\n:ls\n  1 %a  \"a.gd\"\n  2     \"b.gd\""),
+            ":ls (+2 lines, see Output)"
+        );
+    }
+}
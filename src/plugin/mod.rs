@@ -4,39 +4,91 @@
 const VERSION: &str = env!("BUILD_VERSION");
 
 mod actions;
+mod auto_save;
+mod autocmd;
+mod code_actions;
 mod commands;
+mod comment;
+mod definition;
+mod diagnostics;
 mod editing;
 mod editor;
+mod editor_actions;
+mod encoding;
 pub(crate) mod filetype;
+mod float;
+mod folding;
+mod grep;
+mod health;
+mod highlight;
+mod idle_hover;
+mod ime;
+mod indent;
 mod input;
+mod interception;
 mod keys;
+mod large_file;
+mod layout;
+mod leader;
+mod lsp;
 mod macros;
 mod marks;
+mod matcher;
+mod messages;
 mod motions;
+mod multicursor;
 mod neovim;
+mod operator;
+mod options;
+mod picker;
+mod project_files;
+mod quickfix;
 mod recovery;
+mod register_picker;
 mod registers;
+mod relative_number;
+mod replace;
+mod scripting;
 mod search;
+mod search_history;
+mod search_index;
+mod showcmd;
+mod smooth_scroll;
+mod sneak;
+mod snippets;
 mod state;
+mod status_dock;
+mod surround;
+mod symbols;
+mod text_object;
+mod transaction;
 mod ui;
+mod undo_file;
+mod undotree;
+mod user_mappings;
 mod visual;
+mod wildmenu;
+mod window;
 
 use crate::lsp::GodotLspClient;
-use crate::neovim::NeovimClient;
+use crate::neovim::{IndentOptions, NeovimClient};
 use crate::settings;
 use crate::sync::SyncManager;
 use godot::classes::{
-    CodeEdit, ConfirmationDialog, EditorInterface, EditorPlugin, IEditorPlugin, Label,
-    ProjectSettings,
+    AcceptDialog, CodeEdit, ConfirmationDialog, Control, EditorInterface, EditorPlugin,
+    IEditorPlugin, ItemList, Label, PanelContainer, TextEdit, VBoxContainer,
 };
 use godot::global::Key;
 use godot::prelude::*;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Instant;
 
+/// Quiet time after the last editor size/zoom change before the Neovim grid is resized
+const GRID_RESIZE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(50);
+
 /// Type of editor currently active
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum EditorType {
@@ -137,6 +189,28 @@ pub struct GodotNeovimPlugin {
     /// Separate recording indicator label for ShaderEditor
     #[init(val = None)]
     shader_recording_label: Option<Gd<Label>>,
+    /// Neovim message labels (one per status bar a message was shown in)
+    #[init(val = Vec::new())]
+    message_labels: Vec<Gd<Label>>,
+    /// When the current Neovim message was shown (None: hidden)
+    #[init(val = None)]
+    message_shown: Option<Instant>,
+    /// Whether the caret line of relative line numbers shows its absolute number
+    /// ('number' of the current script, read when the gutter is applied)
+    #[init(val = true)]
+    relative_number_absolute: bool,
+    /// Pending command labels (one per status bar it was shown in)
+    #[init(val = Vec::new())]
+    showcmd_labels: Vec<Gd<Label>>,
+    /// Pending command currently shown ("" if none)
+    #[init(val = String::new())]
+    showcmd: String,
+    /// Snippets File as last loaded (reloaded when it changes)
+    #[init(val = snippets::SnippetCache::default())]
+    snippet_cache: snippets::SnippetCache,
+    /// Fields of the snippet being filled in (Tab/Shift+Tab move between them)
+    #[init(val = None)]
+    snippet_session: Option<snippets::SnippetSession>,
     #[init(val = None)]
     current_editor: Option<Gd<CodeEdit>>,
     /// Type of the current editor (Script, Shader, Unknown)
@@ -177,9 +251,31 @@ pub struct GodotNeovimPlugin {
     /// Last find was till (t/T) vs on (f/F)
     #[init(val = false)]
     last_find_till: bool,
+    /// Cursor position after the last f/F/t/T while clever-f repeats it with f/F/t/T
+    #[init(val = None)]
+    clever_f_position: Option<(i32, i32)>,
     /// Pending operator waiting for character input (f, F, t, T, r)
     #[init(val = None)]
     pending_char_op: Option<char>,
+    /// Accent of a dead key typed for the pending f/t/r, composed with the next character
+    #[init(val = None)]
+    pending_dead_key: Option<char>,
+    /// Keys of a surround command being typed (ys, cs, ds and their arguments)
+    #[init(val = None)]
+    pending_surround: Option<String>,
+    /// Keys of a sneak being typed (s{char}{char}, then a label)
+    #[init(val = None)]
+    pending_sneak: Option<sneak::SneakState>,
+    /// Godot-side operator waiting for its text object (gci(, "adi")
+    #[init(val = None)]
+    pending_text_object: Option<text_object::PendingTextObject>,
+    /// Register of a pending gr (replace with register) operator
+    #[init(val = '"')]
+    replace_register: char,
+    /// When Escape was pressed during an IME composition (Insert mode is left once it
+    /// has ended)
+    #[init(val = None)]
+    ime_exit_requested: Option<Instant>,
     /// Command history for ':' commands
     #[init(val = Vec::new())]
     command_history: Vec<String>,
@@ -189,6 +285,18 @@ pub struct GodotNeovimPlugin {
     /// Temporary buffer for current input when browsing history
     #[init(val = String::new())]
     command_history_temp: String,
+    /// Tab completion in progress in the command line (candidates and their float)
+    #[init(val = None)]
+    wildmenu: Option<wildmenu::Wildmenu>,
+    /// Command line was opened from visual mode (Neovim still has the selection)
+    #[init(val = false)]
+    command_from_visual: bool,
+    /// Output of the last Ex command, shown after the command line closes (e.g., :set ic?)
+    #[init(val = None)]
+    command_output: Option<String>,
+    /// Whether 'smartcase' applies to the last search pattern (false after * and #)
+    #[init(val = true)]
+    search_smartcase: bool,
     /// Flag indicating search mode is active (/ or ?)
     #[init(val = false)]
     search_mode: bool,
@@ -198,45 +306,82 @@ pub struct GodotNeovimPlugin {
     /// Search direction (true = forward /, false = backward ?)
     #[init(val = true)]
     search_forward: bool,
-    /// Marks storage: char -> (line, col) - 0-indexed
+    /// Caret (line, col) and scroll position when search mode was opened
+    /// (incremental search moves the caret live; Escape restores it)
+    #[init(val = None)]
+    search_start: Option<(i32, i32, f64)>,
+    /// Operator (with register/count) already sent to Neovim that the search completes
+    /// as its motion (d/foo<CR>, c?bar<CR>)
+    #[init(val = None)]
+    search_operator: Option<String>,
+    /// Count typed before / or ? (3/foo goes to the third match), sent with the search
+    #[init(val = String::new())]
+    search_count_keys: String,
+    /// Patterns of executed / and ? searches, oldest first (persisted per project)
+    #[init(val = Vec::new())]
+    search_history: Vec<String>,
+    /// Current position in search history (None = not browsing history)
+    #[init(val = None)]
+    search_history_index: Option<usize>,
+    /// Temporary buffer for the typed pattern when browsing search history
+    #[init(val = String::new())]
+    search_history_temp: String,
+    /// Search match highlights drawn by the highlight overlay
+    #[init(val = Vec::new())]
+    search_highlights: Vec<highlight::HighlightRange>,
+    /// Matches of the last search pattern per buffer (script path -> index)
     #[init(val = HashMap::new())]
-    marks: HashMap<char, (i32, i32)>,
+    search_indexes: HashMap<String, search_index::SearchIndex>,
+    /// Encoding and line ending of each opened file on disk (script path -> format,
+    /// detected on open)
+    #[init(val = HashMap::new())]
+    file_formats: HashMap<String, encoding::FileFormat>,
+    /// Active substitute preview (original lines to restore)
+    #[init(val = None)]
+    substitute_preview: Option<commands::SubstitutePreview>,
+    /// Occurrences of the clever-f character highlighted by the highlight overlay
+    #[init(val = Vec::new())]
+    find_char_highlights: Vec<highlight::HighlightRange>,
+    /// Yanked region briefly highlighted after a yank
+    #[init(val = Vec::new())]
+    yank_highlights: Vec<highlight::HighlightRange>,
+    /// Generation of the current yank highlight (stale clear timers are ignored)
+    #[init(val = 0)]
+    yank_highlight_id: i64,
+    /// Highlight overlay control (child of the current CodeEdit)
+    #[init(val = None)]
+    highlight_overlay: Option<Gd<Control>>,
     /// Pending mark operation: Some('m') for set mark, Some('\'') for jump to line, Some('`') for jump to position
     #[init(val = None)]
     pending_mark_op: Option<char>,
-    /// Macro storage: char -> Vec of key sequences
-    #[init(val = HashMap::new())]
-    macros: HashMap<char, Vec<String>>,
     /// Currently recording macro (None if not recording)
+    /// Neovim records the macro into the register itself (native `q`)
     #[init(val = None)]
     recording_macro: Option<char>,
-    /// Buffer for keys being recorded
+    /// Keys typed while recording (for the live keystroke display)
     #[init(val = Vec::new())]
     macro_buffer: Vec<String>,
-    /// Last played macro register (for @@)
-    #[init(val = None)]
-    last_macro: Option<char>,
-    /// Flag to prevent recursive macro recording
+    /// Keys handled in Godot's insert mode since the last flush to the recording
+    #[init(val = Vec::new())]
+    macro_insert_keys: Vec<String>,
+    /// Show the keys recorded so far next to the recording indicator (read at `q{reg}`)
     #[init(val = false)]
-    playing_macro: bool,
+    show_macro_keys: bool,
+    /// Number of recorded keys currently shown in the recording indicator
+    #[init(val = 0)]
+    macro_keys_shown: usize,
     /// Pending macro operation: Some('q') for record, Some('@') for play
     #[init(val = None)]
     pending_macro_op: Option<char>,
-    /// Named registers storage: char -> content
-    #[init(val = HashMap::new())]
-    registers: HashMap<char, String>,
     /// Currently selected register for next yank/paste (None = default/system clipboard)
     #[init(val = None)]
     selected_register: Option<char>,
-    /// Jump list: stores (line, col) positions for Ctrl+O/Ctrl+I navigation
-    #[init(val = Vec::new())]
-    jump_list: Vec<(i32, i32)>,
-    /// Current position in jump list (index into jump_list, or len() if at end)
-    #[init(val = 0)]
-    jump_list_pos: usize,
     /// Count prefix buffer for commands like 3dd, 5yy
     #[init(val = String::new())]
     count_buffer: String,
+    /// Count handed to a key dispatched to the GDScript keymap (action runs deferred)
+    #[init(val = String::new())]
+    dispatched_count: String,
     /// Last synced cursor position: (line, col) for detecting external cursor changes
     /// Used to prevent sync loops between Godot and Neovim
     #[init(val = (-1, -1))]
@@ -251,6 +396,12 @@ pub struct GodotNeovimPlugin {
     /// Pending file path to open (for deferred cmd_edit to avoid borrow conflicts)
     #[init(val = None)]
     pending_file_path: Option<String>,
+    /// Cursor to apply once pending_file_path is opened: (res path, line, col), 0-indexed
+    #[init(val = None)]
+    pending_file_cursor: Option<(String, i32, i32)>,
+    /// File mark jump to send to Neovim once pending_file_path is opened: (res path, keys)
+    #[init(val = None)]
+    pending_mark_jump: Option<(String, String)>,
     /// Expected script path after script change (for verifying correct CodeEdit)
     #[init(val = None)]
     expected_script_path: Option<String>,
@@ -280,6 +431,18 @@ pub struct GodotNeovimPlugin {
     /// Buffer synchronization manager (ComradeNeovim-style changedtick sync)
     #[init(val = SyncManager::new())]
     sync_manager: SyncManager,
+    /// Editor whose complex operation (undo step) an edit transaction holds open
+    /// until Neovim's changes have been applied (see begin_edit_transaction)
+    #[init(val = None)]
+    transaction_editor: Option<Gd<CodeEdit>>,
+    /// Multicursor mode (<C-n>): the editor's carets and selections are the cursors
+    /// Normal mode keys go to handle_multicursor_key while it is active
+    #[init(val = false)]
+    multicursor: bool,
+    /// Insert mode entered from multicursor mode: typing applies at all carets and
+    /// is synced to Neovim on Esc as one undoable change
+    #[init(val = false)]
+    multicursor_insert: bool,
     /// Flag to skip cursor sync in on_script_changed (set by cmd_close)
     #[init(val = false)]
     cursor_synced_before_close: bool,
@@ -301,6 +464,9 @@ pub struct GodotNeovimPlugin {
     /// Collected during closing_all_tabs to avoid sync commands during dialog processing
     #[init(val = Vec::new())]
     pending_buffer_deletions: Vec<String>,
+    /// :bd of a script other than the current one (its tab is shown, then closed)
+    #[init(val = None)]
+    pending_buffer_delete: Option<commands::PendingBufferDelete>,
     /// Last Neovim line we synced to (to prevent repeated clamping syncs)
     /// This is separate from last_synced_cursor because we need to track the NEOVIM line,
     /// not the Godot line, to prevent loops when user clicks on clamped line with different columns
@@ -310,20 +476,43 @@ pub struct GodotNeovimPlugin {
     /// Prevents RPC calls during caret update (which causes timeout on rapid key presses)
     #[init(val = false)]
     syncing_from_grid: bool,
+    /// A local motion is computing the target of a pending operator: its caret
+    /// moves are not synced to Neovim
+    #[init(val = false)]
+    resolving_operator_motion: bool,
+    /// Neovim waits for the motion of an operator: predicted from the keys sent
+    /// (their mode_change may not have arrived yet), see note_keys_sent
+    #[init(val = false)]
+    operator_pending: bool,
     /// Flag to skip viewport sync when cursor was changed by user interaction (click)
     /// This prevents Neovim from overriding user's scroll position
     #[init(val = false)]
     user_cursor_sync: bool,
-    /// Last known visible line count (for detecting editor resize)
-    /// Used to resize Neovim UI when Godot editor size changes
+    /// Last Neovim grid size (columns, rows) sent via ui_try_resize
+    /// Used to resize Neovim UI when the editor size, font size or editor scale changes
+    #[init(val = (0, 0))]
+    last_grid_size: (i64, i64),
+    /// Time of the last editor size/zoom change not yet sent to Neovim
+    /// The grid is resized once changes settle (Ctrl+wheel zoom fires in bursts)
+    #[init(val = None)]
+    grid_resize_requested: Option<Instant>,
+    /// When the save scheduled by leaving Insert mode is due (Auto Save setting)
+    #[init(val = None)]
+    auto_save_due: Option<Instant>,
+    /// Keys queued by run_keys(), fed one per frame
+    #[init(val = VecDeque::new())]
+    scripted_keys: VecDeque<Gd<godot::classes::InputEventKey>>,
+    /// Frames left until the last fed key (and its keymap action) is handled
     #[init(val = 0)]
-    last_visible_lines: i32,
-    /// Flag to skip grid_cursor_goto sync after buffer switch
-    /// When buffer is switched, viewport values may be the same as before close,
-    /// causing take_viewport() to return None and grid_cursor_goto to be used
-    /// This flag prevents incorrect cursor positioning after :q and reopen
+    scripted_keys_settle: u8,
+    /// wait_sync() was called: emit sync_completed once the queued keys are handled
     #[init(val = false)]
-    skip_grid_cursor_after_switch: bool,
+    sync_requested: bool,
+    /// Sequence number of the last viewport (win_viewport) seen
+    /// Only newer viewports are applied; a buffer switch skips the ones sent
+    /// for the previous buffer
+    #[init(val = 0)]
+    viewport_seq: u64,
     /// Flag to sync cursor at insert mode entry even across frame boundaries
     /// Set when entering insert mode without a viewport_change in the same frame
     /// (e.g., cw: mode_change arrives Frame N, buf_lines+viewport arrive Frame N+1).
@@ -331,11 +520,9 @@ pub struct GodotNeovimPlugin {
     /// leaving the cursor at Godot's auto-moved position instead of the Neovim position.
     #[init(val = false)]
     pending_insert_cursor_sync: bool,
-    /// Flag to apply cursor correction after Ctrl+B
-    /// With ext_multigrid, Ctrl+B at end of file reports wrong viewport height,
-    /// causing cursor to barely move. This flag triggers correction after viewport sync.
-    #[init(val = false)]
-    pending_page_up_correction: bool,
+    /// Running animation of a large viewport jump (Smooth Scroll setting)
+    #[init(val = None)]
+    smooth_scroll: Option<smooth_scroll::SmoothScroll>,
     /// Flag indicating mouse is being dragged (for visual mode sync on release)
     #[init(val = false)]
     mouse_dragging: bool,
@@ -347,6 +534,9 @@ pub struct GodotNeovimPlugin {
     /// Neovim returns "visual" for all visual modes, so we track the key pressed
     #[init(val = 'v')]
     visual_mode_type: char,
+    /// Size of the Visual selection shown next to the mode ("12 lines", "5x20")
+    #[init(val = String::new())]
+    visual_selection_size: String,
     /// Timestamps of recent timeout errors for recovery detection
     #[init(val = Vec::new())]
     timeout_timestamps: Vec<Instant>,
@@ -356,6 +546,98 @@ pub struct GodotNeovimPlugin {
     /// Recovery dialog reference
     #[init(val = None)]
     recovery_dialog: Option<Gd<ConfirmationDialog>>,
+    /// Results picker dialog (LSP references, etc.)
+    #[init(val = None)]
+    picker_dialog: Option<Gd<ConfirmationDialog>>,
+    /// Results picker item list
+    #[init(val = None)]
+    picker_list: Option<Gd<ItemList>>,
+    /// All items in the results picker
+    #[init(val = Vec::new())]
+    picker_items: Vec<picker::PickerItem>,
+    /// Indices into picker_items matching the current filter
+    #[init(val = Vec::new())]
+    picker_filtered: Vec<usize>,
+    /// Register picker dialog (:registers)
+    #[init(val = None)]
+    register_picker_dialog: Option<Gd<ConfirmationDialog>>,
+    /// Register picker item list
+    #[init(val = None)]
+    register_picker_list: Option<Gd<ItemList>>,
+    /// Register picker content preview
+    #[init(val = None)]
+    register_picker_preview: Option<Gd<TextEdit>>,
+    /// Registers shown in the register picker
+    #[init(val = Vec::new())]
+    register_picker_entries: Vec<crate::neovim::RegisterEntry>,
+    /// External fzf match in progress for the picker filter
+    #[init(val = None)]
+    picker_external_match: Option<matcher::ExternalMatch>,
+    /// fzf failed for this picker - use the built-in matcher until it is reopened
+    #[init(val = false)]
+    picker_fzf_failed: bool,
+    /// What the picker lists (plain items, the quickfix list, live grep)
+    #[init(val = picker::PickerSource::Items)]
+    picker_source: picker::PickerSource,
+    /// Search running for the live grep picker's pattern
+    #[init(val = None)]
+    picker_grep: Option<grep::GrepSearch>,
+    /// Matches listed by the live grep picker (parallel to picker_items)
+    #[init(val = Vec::new())]
+    live_grep_matches: Vec<grep::GrepMatch>,
+    /// Code actions listed in the picker (`<leader>ca`)
+    #[init(val = Vec::new())]
+    code_actions: Vec<lsp_types::CodeActionOrCommand>,
+    /// Undo sequence numbers of the states listed in the picker (:undotree)
+    #[init(val = Vec::new())]
+    undo_seqs: Vec<i64>,
+    /// Pattern of the live grep picker's matches
+    #[init(val = String::new())]
+    live_grep_pattern: String,
+    /// Search started by :grep (results become the quickfix list when done)
+    #[init(val = None)]
+    grep_search: Option<grep::GrepSearch>,
+    /// Quickfix list: matches of the last :grep (or live grep that was opened)
+    #[init(val = Vec::new())]
+    quickfix: Vec<grep::GrepMatch>,
+    /// Current quickfix entry (index into quickfix)
+    #[init(val = 0)]
+    quickfix_index: usize,
+    /// Title of the quickfix list (the grep pattern)
+    #[init(val = String::new())]
+    quickfix_title: String,
+    /// Last visual selection poll (limited in large-file mode)
+    #[init(val = None)]
+    last_visual_poll: Option<Instant>,
+    /// A visual selection update was skipped by the large-file poll limit
+    #[init(val = false)]
+    visual_poll_pending: bool,
+    /// Large files whose chunks failed to load, with whether their last chunk has
+    /// answered: the Neovim buffer is then re-sent whole from the editor
+    #[init(val = HashMap::new())]
+    large_file_resync: HashMap<String, bool>,
+    /// Godot's symbol lookup started by gd, reported if it finds nothing
+    #[init(val = None)]
+    symbol_lookup: Option<definition::SymbolLookup>,
+    /// LSP document symbols per script path, with the changedtick they were read at
+    #[init(val = HashMap::new())]
+    symbol_cache: HashMap<String, (i64, Vec<symbols::OutlineSymbol>)>,
+    /// Float closed by the next key: LSP hover shown by K (hover mode), diagnostics
+    /// of the caret line (`<leader>e`)
+    #[init(val = None)]
+    hover_float: Option<Gd<PanelContainer>>,
+    /// LSP diagnostics of the current script, ordered by position
+    #[init(val = Vec::new())]
+    diagnostics: Vec<diagnostics::LineDiagnostic>,
+    /// Control over the CodeEdit drawing the diagnostics' virtual text
+    #[init(val = None)]
+    diagnostics_overlay: Option<Gd<Control>>,
+    /// Polling of the LSP connection for diagnostics
+    #[init(val = diagnostics::DiagnosticsState::default())]
+    diagnostics_state: diagnostics::DiagnosticsState,
+    /// LSP signature help float shown while typing call arguments in Insert mode
+    #[init(val = None)]
+    signature_float: Option<Gd<PanelContainer>>,
     /// Timestamp of last key sent to Neovim (for detecting no-response)
     #[init(val = None)]
     last_key_send_time: Option<Instant>,
@@ -371,6 +653,97 @@ pub struct GodotNeovimPlugin {
     /// This enables GDScript-based keybinding customization without recompiling the GDExtension.
     #[init(val = None)]
     input_handler: Option<Callable>,
+    /// Leader mappings of the GDScript keymap: keys after `<leader>` (e.g. "ff") to
+    /// the name of their action
+    #[init(val = BTreeMap::new())]
+    leader_mappings: BTreeMap<String, String>,
+    /// Which-key style list of the leader mappings, shown when a leader sequence
+    /// is held for timeoutlen
+    #[init(val = None)]
+    leader_palette: Option<Gd<PanelContainer>>,
+    /// Whether the project was running at the last check (shown in the mode label)
+    #[init(val = false)]
+    project_running: bool,
+    /// Bottom panel item shown before the last `<leader>tb` hid the panel
+    #[init(val = None)]
+    last_bottom_panel_item: Option<Gd<Control>>,
+    /// Normal mode mappings of the user's Neovim config (normalized keys)
+    #[init(val = Vec::new())]
+    user_mappings: Vec<Vec<String>>,
+    /// Key sequences of the Always Forward Keys setting (normalized keys)
+    #[init(val = Vec::new())]
+    forward_keys: Vec<Vec<String>>,
+    /// Entries of the Key Interception setting
+    #[init(val = Vec::new())]
+    key_interception: Vec<interception::InterceptionRule>,
+    /// Key Layout Overrides setting: typed character -> Vim key
+    #[init(val = HashMap::new())]
+    layout_overrides: HashMap<char, char>,
+    /// Keys held while they may be a user mapping (event, Neovim notation)
+    #[init(val = Vec::new())]
+    user_mapping_pending: Vec<(Gd<godot::classes::InputEventKey>, String)>,
+    /// When the last held key of a user mapping was typed (for timeoutlen)
+    #[init(val = None)]
+    user_mapping_time: Option<Instant>,
+    /// The held keys of an unmatched user mapping are being handled again
+    #[init(val = false)]
+    replaying_keys: bool,
+    /// Problems found by the installation self-check at activation
+    #[init(val = Vec::new())]
+    install_problems: Vec<String>,
+    /// Marks of the current Neovim saved by the last snapshot (restored after a crash)
+    #[init(val = None)]
+    marks_snapshot: Option<recovery::MarksSnapshot>,
+    /// When the last marks snapshot was requested
+    #[init(val = None)]
+    marks_snapshot_time: Option<Instant>,
+    /// Editor whose Neovim a marks snapshot was requested from (None: no request in flight)
+    #[init(val = None)]
+    marks_snapshot_type: Option<EditorType>,
+    /// Snapshot to restore once the current script is registered in a respawned Neovim
+    #[init(val = None)]
+    pending_marks_restore: Option<recovery::MarksSnapshot>,
+    /// Status dock in the bottom panel
+    #[init(val = None)]
+    status_dock: Option<Gd<VBoxContainer>>,
+    /// Text of the status dock
+    #[init(val = None)]
+    status_label: Option<Gd<Label>>,
+    /// Last refresh of the status dock
+    #[init(val = None)]
+    status_refreshed: Option<Instant>,
+    /// When the status dock's latency ping was sent (None: no ping in flight)
+    #[init(val = None)]
+    status_ping_sent: Option<Instant>,
+    /// RPC latency measured by the last status ping
+    #[init(val = None)]
+    status_latency: Option<std::time::Duration>,
+    /// Dialog showing the :checkhealth report
+    #[init(val = None)]
+    health_dialog: Option<Gd<AcceptDialog>>,
+    /// Text of the last :checkhealth report (for Copy to Clipboard)
+    #[init(val = String::new())]
+    health_report: String,
+    /// Whether Neovim reported 'hlsearch' highlighting as active on the last refresh
+    /// (buffer changes only re-query matches while this is set)
+    #[init(val = false)]
+    hlsearch_active: bool,
+    /// A 'hlsearch' refresh is waiting for Neovim's answers (search state, then the
+    /// matches of the lines not searched yet)
+    #[init(val = false)]
+    hlsearch_query_pending: bool,
+    /// Another 'hlsearch' refresh was asked for while one was pending
+    #[init(val = false)]
+    hlsearch_refresh_again: bool,
+    /// Last search pattern reported by Neovim (for the [n/N] search count)
+    #[init(val = String::new())]
+    last_search_pattern: String,
+    /// Show the [n/N] search count once the pending 'hlsearch' refresh is answered
+    #[init(val = false)]
+    search_count_pending: bool,
+    /// Window-local options per script path (:setlocal wrap/number/list)
+    #[init(val = HashMap::new())]
+    local_options: HashMap<String, options::LocalOptions>,
 }
 
 #[godot_api]
@@ -421,11 +794,50 @@ impl IEditorPlugin for GodotNeovimPlugin {
                     let pending = std::mem::take(&mut self.pending_buffer_deletions);
                     for path in pending {
                         self.delete_neovim_buffer(&path, EditorType::Script);
+                        self.search_indexes.remove(&path);
+                        self.file_formats.remove(&path);
                     }
                 }
             }
         }
 
+        // Keep the live keystroke display of a macro being recorded up to date
+        if self.show_macro_keys
+            && self.recording_macro.is_some()
+            && self.macro_buffer.len() != self.macro_keys_shown
+        {
+            self.refresh_macro_keys_display();
+        }
+
+        // Feed keys queued by run_keys() (editor tests, tool scripts)
+        if !self.scripted_keys.is_empty() || self.sync_requested {
+            self.process_scripted_keys();
+        }
+
+        // Resize the Neovim grid once editor size/zoom changes have settled
+        if self
+            .grid_resize_requested
+            .is_some_and(|requested| requested.elapsed() >= GRID_RESIZE_DEBOUNCE)
+        {
+            self.grid_resize_requested = None;
+            self.resize_grid_to_editor();
+        }
+
+        // Leave Insert mode once the IME composition that took Escape has ended
+        if self.ime_exit_requested.is_some() {
+            self.poll_ime_insert_exit();
+        }
+
+        // Animate a large viewport jump
+        if self.smooth_scroll.is_some() {
+            self.poll_smooth_scroll();
+        }
+
+        // Save after leaving Insert mode once the Auto Save Delay has passed
+        if self.auto_save_due.is_some() {
+            self.poll_auto_save();
+        }
+
         // Handle deferred close after save (ZZ/:wq)
         // This ensures save completes before close by waiting one frame
         if self.pending_close_after_save {
@@ -434,6 +846,11 @@ impl IEditorPlugin for GodotNeovimPlugin {
             self.cmd_close();
         }
 
+        // Handle :bd of another script (show it, close it, go back)
+        if self.pending_buffer_delete.is_some() {
+            self.poll_buffer_delete();
+        }
+
         // Handle deferred shader focus after close
         // ShaderEditor doesn't have on_script_close signal, so we handle focus here
         if self.focus_shader_after_close {
@@ -513,9 +930,51 @@ impl IEditorPlugin for GodotNeovimPlugin {
             }
         }
 
+        // Respawn a Neovim whose process died
+        self.check_neovim_exit();
+
         // Check for pending updates from Neovim redraw events
         self.process_neovim_updates();
 
+        // Handle responses to asynchronous requests (cursor sync, BufEnter)
+        self.process_rpc_responses();
+
+        // Apply the visual selection update deferred in large-file mode
+        self.poll_deferred_visual_selection();
+
+        // Apply matches streamed from an external picker matcher (fzf)
+        self.poll_picker_matches();
+
+        // Collect results of background project searches (:grep, live grep)
+        self.poll_grep();
+
+        // Keep the status dock up to date while it is shown
+        self.update_status_dock();
+
+        // Show in the mode label whether the project is running
+        self.poll_run_state();
+
+        // gd that fell back to Godot's symbol lookup: report when nothing was found
+        self.poll_symbol_lookup();
+
+        // Send changed text to the LSP and show the diagnostics it publishes
+        self.poll_diagnostics();
+
+        // Neovim messages are shown for a moment
+        self.poll_message_timeout();
+
+        // Show the command being typed (showcmd)
+        self.update_showcmd();
+
+        // Save the marks to restore if Neovim crashes
+        self.snapshot_marks();
+
+        // Keys held for a user mapping are resolved after timeoutlen
+        self.poll_user_mapping_timeout();
+
+        // A held leader sequence opens the leader palette instead of timing out
+        self.poll_leader_palette();
+
         // Check for key sequence timeout (like Neovim's timeoutlen)
         // Only applies in Normal mode - Insert/Replace/Visual modes don't use operator-pending
         // If last_key has been pending too long, cancel it
@@ -537,12 +996,13 @@ impl IEditorPlugin for GodotNeovimPlugin {
                         }
                         // Clear directly here (not using clear_last_key() to avoid double clearing last_key_time)
                         self.last_key.clear();
+                        // The count belonged to the cancelled sequence (a count alone never times out)
+                        self.count_buffer.clear();
                     }
                     self.last_key_time = None;
 
                     // Also clear related pending states on timeout
                     self.selected_register = None;
-                    self.count_buffer.clear();
                 }
             }
         }
@@ -569,6 +1029,17 @@ impl IEditorPlugin for GodotNeovimPlugin {
             .clone()
             .try_cast::<godot::classes::InputEventMouseButton>()
         {
+            // Middle-click pastes the primary selection through Neovim ("*P)
+            if mouse_event.get_button_index() == godot::global::MouseButton::MIDDLE
+                && mouse_event.is_pressed()
+                && self.handle_middle_click_paste()
+            {
+                if let Some(mut viewport) = self.base().get_viewport() {
+                    viewport.set_input_as_handled();
+                }
+                return;
+            }
+
             // Only handle left mouse button when editor has focus
             if mouse_event.get_button_index() == godot::global::MouseButton::LEFT
                 && self.editor_has_focus()
@@ -605,11 +1076,27 @@ impl IEditorPlugin for GodotNeovimPlugin {
             return;
         }
 
+        // Ctrl+W window commands (the prefix also works while a dock has the focus)
+        if self.handle_window_command_key(&key_event) {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
         // Check if the current editor has focus
         if !self.editor_has_focus() {
             return;
         }
 
+        // Any key dismisses the K hover float (Escape only dismisses it)
+        if self.dismiss_hover_float(&key_event) {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
         // Check if Neovim is connected
         if self.get_current_neovim().is_none() {
             crate::verbose_print!("[godot-neovim] input: No neovim");
@@ -629,65 +1116,13 @@ impl IEditorPlugin for GodotNeovimPlugin {
         // This flag might be set from previous mouse interactions
         self.user_cursor_sync = false;
 
-        // Handle command-line mode input
-        if self.command_mode {
-            self.handle_command_mode_input(&key_event);
-            return;
-        }
-
-        // Handle search mode input (/ or ?)
-        if self.search_mode {
-            self.handle_search_mode_input(&key_event);
-            return;
-        }
-
-        // Handle pending character operator (f, F, t, T, r)
-        if self.handle_pending_char_op(&key_event) {
-            return;
-        }
-
-        // Handle pending mark operation (m, ', `)
-        if self.handle_pending_mark_op(&key_event) {
-            return;
-        }
-
-        // Handle pending macro operation (q for record, @ for play)
-        if self.handle_pending_macro_op(&key_event) {
-            return;
-        }
-
-        // Handle pending register selection (waiting for register char after ")
-        if self.handle_pending_register(&key_event) {
-            return;
-        }
-
-        // Handle insert mode
-        if self.is_insert_mode() {
-            self.handle_insert_mode_input(&key_event);
-            return;
-        }
-
-        // Handle replace mode
-        if self.is_replace_mode() {
-            self.handle_replace_mode_input(&key_event);
+        // Keys the Key Interception setting leaves to Godot are not handled
+        if self.is_key_left_to_godot(&key_event) {
+            crate::verbose_print!("[godot-neovim] input: Key left to Godot");
             return;
         }
 
-        // Handle normal/visual mode input
-        if self.input_handler.is_some() {
-            // GDScript dispatch path: process key in Rust, defer keymap lookup to GDScript.
-            // Cannot call GDScript Callable directly here (re-entrant &mut self borrow).
-            let result = self.process_key_event_impl(&key_event);
-            if result.get_or_nil("needs_dispatch").to::<bool>() {
-                let resolved_key = result.get_or_nil("resolved_key");
-                let mode = result.get_or_nil("mode");
-                self.base_mut()
-                    .call_deferred("_dispatch_key_to_gdscript", &[resolved_key, mode]);
-            }
-        } else {
-            // Fallback: built-in Rust handling
-            self.handle_normal_mode_input(&key_event);
-        }
+        self.route_key_event(&key_event);
     }
 }
 
@@ -698,6 +1133,10 @@ impl GodotNeovimPlugin {
     #[signal]
     fn key_sent(key: GString);
 
+    /// Signal emitted when the keys queued by run_keys() are handled (see wait_sync)
+    #[signal]
+    fn sync_completed();
+
     /// Activate or deactivate the plugin.
     /// Called by plugin.gd's _enter_tree/_exit_tree/_disable_plugin to control the lifecycle.
     /// This is needed because GDExtension EditorPlugin classes are auto-loaded by Godot
@@ -714,7 +1153,32 @@ impl GodotNeovimPlugin {
 
     #[func]
     fn on_editor_resized(&mut self) {
-        // Resize Neovim UI to match new editor size
+        // Debounced: the grid is resized in process() once changes settle
+        self.grid_resize_requested = Some(Instant::now());
+    }
+
+    /// Code editor zoom changed (Ctrl+wheel, Ctrl+=/-, zoom menu)
+    /// The visible line count changes without the editor being resized
+    #[func]
+    fn on_editor_zoomed(&mut self, _zoom_factor: f32) {
+        self.grid_resize_requested = Some(Instant::now());
+    }
+
+    /// Called when the CodeEdit regains focus (from another dock, panel or window)
+    #[func]
+    fn on_editor_focus_entered(&mut self) {
+        self.enter_normal_mode_on_focus();
+        self.load_clipboard_registers();
+    }
+
+    /// Called when the CodeEdit loses focus (another dock, panel, window or tab)
+    #[func]
+    fn on_editor_focus_exited(&mut self) {
+        self.auto_save_on_focus_lost();
+    }
+
+    /// Resize Neovim UI to match the editor's current size and font
+    fn resize_grid_to_editor(&mut self) {
         let Some(ref editor) = self.current_editor else {
             return;
         };
@@ -723,14 +1187,16 @@ impl GodotNeovimPlugin {
         // even though the signal is connected (timing issue with call_deferred)
         if !editor.is_instance_valid() {
             crate::verbose_print!(
-                "[godot-neovim] on_editor_resized: editor is no longer valid, skipping"
+                "[godot-neovim] resize_grid_to_editor: editor is no longer valid, skipping"
             );
             return;
         }
 
-        let visible_lines = editor.get_visible_line_count();
-        if visible_lines != self.last_visible_lines && visible_lines > 0 {
-            self.last_visible_lines = visible_lines;
+        let Some((width, height)) = Self::editor_grid_size(editor) else {
+            return;
+        };
+        if (width, height) != self.last_grid_size {
+            self.last_grid_size = (width, height);
 
             // Clear user_cursor_sync flag since resize might trigger caret_changed
             // but we still want to apply viewport changes from Neovim after resize
@@ -744,19 +1210,27 @@ impl GodotNeovimPlugin {
                 return;
             };
 
-            let width = 120i64;
-            let height = (visible_lines as i64).max(10);
             crate::verbose_print!(
-                "[godot-neovim] Resize on editor resize: visible_lines={}, height={}",
-                visible_lines,
+                "[godot-neovim] Resize on editor resize: width={}, height={}",
+                width,
                 height
             );
             client.ui_try_resize(width, height);
         }
     }
 
+    /// Font size or editor scale changed (Ctrl+wheel zoom, theme or settings change)
+    /// The line height is updated with the theme, the layout on the next frame
+    #[func]
+    fn on_editor_theme_changed(&mut self) {
+        self.base_mut().call_deferred("on_editor_resized", &[]);
+    }
+
     #[func]
     fn on_caret_changed(&mut self) {
+        // Any caret movement (also with the mouse) dismisses the hover float
+        self.close_hover_float();
+
         // Skip if syncing from grid (to prevent RPC during caret update)
         // This happens when set_caret_line/column are called from sync_cursor_from_grid
         if self.syncing_from_grid {
@@ -769,7 +1243,8 @@ impl GodotNeovimPlugin {
         }
 
         // Skip sync in Insert/Replace modes - cursor moves with every keystroke
-        // and Neovim isn't receiving the input, so syncing is meaningless and causes freezes
+        // Insert: Neovim isn't receiving the input, so syncing is meaningless and causes freezes
+        // Replace: Neovim is the source of truth for the cursor, so syncing back would fight it
         if self.is_insert_mode() || self.is_replace_mode() {
             return;
         }
@@ -846,6 +1321,70 @@ impl GodotNeovimPlugin {
         crate::verbose_print!("[godot-neovim] Synced buffer after toggle comment");
     }
 
+    /// Draw search highlights (connected to the highlight overlay's draw signal)
+    #[func]
+    fn on_highlight_overlay_draw(&mut self) {
+        self.draw_highlight_overlay();
+    }
+
+    /// Redraw the highlight overlay when the CodeEdit redraws (scroll, edits, resize)
+    #[func]
+    fn on_highlight_editor_draw(&mut self) {
+        if let Some(ref mut overlay) = self.highlight_overlay {
+            if overlay.is_instance_valid() {
+                overlay.queue_redraw();
+            }
+        }
+    }
+
+    /// Draw a line of the diagnostics gutter (custom draw callback, bound to its CodeEdit)
+    #[func]
+    fn on_diagnostics_gutter_draw(
+        &mut self,
+        line: i64,
+        _gutter: i64,
+        rect: Rect2,
+        mut editor: Gd<CodeEdit>,
+    ) {
+        self.draw_diagnostics_gutter(&mut editor, line as i32, rect);
+    }
+
+    /// Draw the diagnostics' virtual text
+    #[func]
+    fn on_diagnostics_overlay_draw(&mut self) {
+        self.draw_diagnostics_overlay();
+    }
+
+    /// Redraw the diagnostics' virtual text when the CodeEdit redraws
+    #[func]
+    fn on_diagnostics_editor_draw(&mut self) {
+        self.redraw_diagnostics_overlay();
+    }
+
+    /// Draw a line of the relative number gutter (custom draw callback, bound to its CodeEdit)
+    #[func]
+    fn on_relative_number_draw(
+        &mut self,
+        line: i64,
+        gutter: i64,
+        rect: Rect2,
+        mut editor: Gd<CodeEdit>,
+    ) {
+        self.draw_relative_number(&mut editor, line as i32, gutter as i32, rect);
+    }
+
+    /// Clear the yank highlight when its timer expires
+    #[func]
+    fn on_yank_highlight_timeout(&mut self, id: i64) {
+        self.clear_yank_highlight(id);
+    }
+
+    /// Request signature help after Godot has inserted `(` or `,` (deferred from Insert mode input)
+    #[func]
+    fn update_signature_help(&mut self) {
+        self.lsp_signature_help();
+    }
+
     /// Sync mouse selection to Neovim on mouse release
     /// If there's a selection (drag), enter visual mode and sync selection range
     /// If no selection (click), just sync cursor position
@@ -973,6 +1512,17 @@ impl GodotNeovimPlugin {
 
         // Sync indent settings to Neovim when editor settings change
         self.sync_indent_settings_to_neovim();
+        self.sync_comment_continuation_to_neovim();
+        self.sync_formatter_to_neovim();
+        self.sync_undo_dir_to_neovim();
+        self.apply_neovim_options();
+        self.refresh_forward_keys();
+        self.refresh_key_interception();
+        self.refresh_layout_overrides();
+        self.apply_relative_number();
+
+        // Code font size / display scale may have changed the grid size
+        self.base_mut().call_deferred("on_editor_resized", &[]);
     }
 
     /// Sync current editor's indent settings to Neovim
@@ -1002,11 +1552,34 @@ impl GodotNeovimPlugin {
             indent_size
         );
 
-        // Sync to both Neovim instances
-        if let Some(ref neovim) = self.script_neovim {
-            if let Ok(client) = neovim.try_lock() {
-                if let Err(e) = client.set_indent_options(use_spaces, indent_size) {
-                    crate::verbose_print!(
+        // The shown script keeps its own indentation (the other buffers get theirs
+        // when they are shown again)
+        let global = IndentOptions {
+            use_spaces,
+            indent_size,
+        };
+        let lines: Vec<String> = self
+            .current_editor
+            .as_ref()
+            .filter(|editor| editor.is_instance_valid())
+            .map(|editor| {
+                (0..editor.get_line_count())
+                    .map(|line| editor.get_line(line).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let current = self.script_indent(&lines, global);
+        let (script, shader) = if self.current_editor_type == EditorType::Shader {
+            (global, current)
+        } else {
+            (current, global)
+        };
+
+        // Sync to both Neovim instances
+        if let Some(ref neovim) = self.script_neovim {
+            if let Ok(client) = neovim.try_lock() {
+                if let Err(e) = client.set_indent_options(script.use_spaces, script.indent_size) {
+                    crate::verbose_print!(
                         "[godot-neovim] Failed to sync indent to ScriptEditor Neovim: {}",
                         e
                     );
@@ -1015,7 +1588,7 @@ impl GodotNeovimPlugin {
         }
         if let Some(ref neovim) = self.shader_neovim {
             if let Ok(client) = neovim.try_lock() {
-                if let Err(e) = client.set_indent_options(use_spaces, indent_size) {
+                if let Err(e) = client.set_indent_options(shader.use_spaces, shader.indent_size) {
                     crate::verbose_print!(
                         "[godot-neovim] Failed to sync indent to ShaderEditor Neovim: {}",
                         e
@@ -1025,6 +1598,38 @@ impl GodotNeovimPlugin {
         }
     }
 
+    /// Sync the comment continuation setting to both Neovim instances (o/O)
+    fn sync_comment_continuation_to_neovim(&mut self) {
+        let continuation = settings::get_comment_continuation();
+        for neovim in [&self.script_neovim, &self.shader_neovim]
+            .into_iter()
+            .flatten()
+        {
+            if let Ok(client) = neovim.try_lock() {
+                if let Err(e) = client
+                    .set_comment_continuation(continuation.on_enter(), continuation.on_open_line())
+                {
+                    crate::verbose_print!("[godot-neovim] {}", e);
+                }
+            }
+        }
+    }
+
+    /// Sync the formatter run by gq and :format to both Neovim instances
+    fn sync_formatter_to_neovim(&mut self) {
+        let formatter = settings::get_formatter_path();
+        for neovim in [&self.script_neovim, &self.shader_neovim]
+            .into_iter()
+            .flatten()
+        {
+            if let Ok(client) = neovim.try_lock() {
+                if let Err(e) = client.set_formatter(&formatter) {
+                    crate::verbose_print!("[godot-neovim] {}", e);
+                }
+            }
+        }
+    }
+
     #[func]
     fn on_script_changed(&mut self, script: Option<Gd<godot::classes::Script>>) {
         // Skip processing during :qa (Close All) to avoid errors
@@ -1034,6 +1639,16 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Leave Insert/Visual mode while the previous script's editor is still current,
+        // so text typed in it is synced to its own buffer
+        if self
+            .current_editor
+            .as_ref()
+            .is_some_and(|editor| editor.is_instance_valid())
+        {
+            self.enter_normal_mode_on_focus();
+        }
+
         // Sync cursor to Neovim before switching files
         // Skip if cursor was already synced by cmd_close (to avoid overwriting with wrong position)
         if self.cursor_synced_before_close {
@@ -1124,12 +1739,28 @@ impl GodotNeovimPlugin {
         // Delete the buffer from Neovim immediately
         // on_script_close is only connected to ScriptEditor
         self.delete_neovim_buffer(&path, EditorType::Script);
+        self.search_indexes.remove(&path);
+        self.file_formats.remove(&path);
 
         // Set flag to grab focus after script change processing completes
         // This ensures focus is set after the new CodeEdit is visible
         self.focus_after_script_change = true;
     }
 
+    /// Called (deferred) after Godot saved a resource
+    /// Converts files that were not UTF-8 with LF line endings on disk back to their
+    /// encoding and line ending, and writes
+    /// the undo file of the saved script
+    #[func]
+    fn on_resource_saved(&mut self, resource: Gd<godot::classes::Resource>) {
+        let path = resource.get_path().to_string();
+        if path.is_empty() {
+            return;
+        }
+        self.restore_file_format(&path);
+        self.write_undo_file(&path);
+    }
+
     #[func]
     fn handle_script_changed_deferred(&mut self) {
         // Check if this operation is stale (a newer switch was initiated)
@@ -1145,6 +1776,14 @@ impl GodotNeovimPlugin {
 
         crate::verbose_print!("[godot-neovim] Script changed (deferred processing)");
 
+        // The floats and the highlight overlay belong to the previous CodeEdit
+        self.close_hover_float();
+        self.close_signature_help();
+        self.close_leader_palette();
+        self.smooth_scroll = None;
+        self.free_highlight_overlay();
+        self.clear_diagnostics_view();
+
         self.find_current_code_edit();
 
         // For ShaderEditor and external CodeEdits (Unknown), skip ScriptEditor-based verification
@@ -1286,6 +1925,8 @@ impl GodotNeovimPlugin {
         self.expected_script_path = None;
 
         self.reposition_mode_label();
+        self.apply_local_options();
+        self.apply_relative_number();
 
         // Switch to Neovim buffer for this file (creates if not exists)
         // Returns cursor position from Neovim and whether buffer was newly created
@@ -1325,9 +1966,42 @@ impl GodotNeovimPlugin {
             }
         }
 
+        // A large file whose load failed while another script was current
+        self.resync_large_file();
+
+        // Marks saved before Neovim crashed (the buffer is registered again now)
+        self.restore_pending_marks();
+
+        // Search highlights belong to the previous buffer; re-query for this one
+        self.refresh_hlsearch();
+
+        // Apply cursor from a cross-file jump (gd, picker) - overrides Neovim's position
+        if let Some((path, line, col)) = self.pending_file_cursor.take() {
+            if path == self.current_script_path {
+                if let Some(ref mut editor) = self.current_editor {
+                    let safe_line = line.min(editor.get_line_count() - 1).max(0);
+                    self.syncing_from_grid = true;
+                    editor.set_caret_line(safe_line);
+                    editor.set_caret_column(col.max(0));
+                    editor.center_viewport_to_caret();
+                    self.syncing_from_grid = false;
+                }
+            }
+        }
+
         self.update_cursor_from_editor();
         self.sync_cursor_to_neovim();
 
+        // File mark jump ('A): the mark's buffer is now current, Neovim jumps within it
+        if let Some((path, keys)) = self.pending_mark_jump.take() {
+            if path == self.current_script_path {
+                self.send_keys(&keys);
+            }
+        }
+
+        // Switches not reported by on_script_changed (float windows, shader editor)
+        self.enter_normal_mode_on_focus();
+
         // If script was closed, grab focus on the new CodeEdit
         if self.focus_after_script_change {
             self.focus_after_script_change = false;
@@ -1364,6 +2038,15 @@ impl GodotNeovimPlugin {
             .clone()
             .try_cast::<godot::classes::InputEventMouseButton>()
         {
+            if mouse_event.get_button_index() == godot::global::MouseButton::MIDDLE
+                && mouse_event.is_pressed()
+                && self.handle_middle_click_paste()
+            {
+                if let Some(ref mut editor) = self.current_editor {
+                    editor.accept_event();
+                }
+                return;
+            }
             if mouse_event.get_button_index() == godot::global::MouseButton::LEFT {
                 if mouse_event.is_pressed() {
                     self.mouse_dragging = true;
@@ -1396,13 +2079,21 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Any key dismisses the K hover float (Escape only dismisses it)
+        if self.dismiss_hover_float(&key_event) {
+            if let Some(ref mut editor) = self.current_editor {
+                editor.accept_event();
+            }
+            return;
+        }
+
         // Clear user_cursor_sync flag to allow viewport sync from Neovim
         self.user_cursor_sync = false;
 
         // Accept the event to prevent CodeEdit from processing it
-        // This must be done in Normal/Visual modes to prevent characters from being typed
-        // In Insert/Replace modes, we let CodeEdit handle the input normally
-        let should_consume = !self.is_insert_mode() && !self.is_replace_mode();
+        // This must be done in Normal/Visual/Replace modes to prevent characters from being typed
+        // (Replace mode is handled by Neovim). In Insert mode, we let CodeEdit handle the input
+        let should_consume = !self.is_insert_mode();
         if should_consume {
             if let Some(ref mut editor) = self.current_editor {
                 editor.accept_event();
@@ -1426,6 +2117,21 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Handle pending surround command (ys, cs, ds)
+        if self.handle_pending_surround(&key_event) {
+            return;
+        }
+
+        // Handle pending sneak (s{char}{char}, then a label)
+        if self.handle_pending_sneak(&key_event) {
+            return;
+        }
+
+        // Handle pending text object of a Godot-side operator (gci(, "adi")
+        if self.handle_pending_text_object(&key_event) {
+            return;
+        }
+
         // Handle pending mark operation (m, ', `)
         if self.handle_pending_mark_op(&key_event) {
             return;
@@ -1511,6 +2217,93 @@ impl GodotNeovimPlugin {
         self.cleanup_recovery_dialog();
     }
 
+    /// Status dock: Restart Neovim
+    #[func]
+    fn on_status_restart_pressed(&mut self) {
+        self.restart_neovim_from_dock();
+    }
+
+    /// :checkhealth dialog: closed (OK or window close)
+    #[func]
+    fn on_health_dialog_closed(&mut self) {
+        self.close_health_dialog();
+    }
+
+    /// :checkhealth dialog: Copy to Clipboard
+    #[func]
+    fn on_health_dialog_action(&mut self, action: GString) {
+        if action == "copy" {
+            self.copy_health_report();
+        }
+    }
+
+    /// Picker: filter text changed
+    #[func]
+    fn on_picker_filter_changed(&mut self, text: GString) {
+        self.update_picker_filter(&text.to_string());
+    }
+
+    /// Picker: Up/Down/Ctrl+N/Ctrl+P in filter move the list selection
+    #[func]
+    fn on_picker_filter_input(&mut self, event: Gd<godot::classes::InputEvent>) {
+        let Ok(key_event) = event.try_cast::<godot::classes::InputEventKey>() else {
+            return;
+        };
+        if self.handle_picker_filter_key(&key_event) {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+        }
+    }
+
+    /// Picker: Enter in filter opens the selected item
+    #[func]
+    fn on_picker_submitted(&mut self, _text: GString) {
+        self.open_selected_picker_item();
+    }
+
+    /// Picker: double-click / Enter on a list item
+    #[func]
+    fn on_picker_item_activated(&mut self, index: i64) {
+        self.open_picker_item(index as i32);
+    }
+
+    /// Picker: Open button
+    #[func]
+    fn on_picker_confirmed(&mut self) {
+        self.open_selected_picker_item();
+    }
+
+    /// Picker: Cancel / close
+    #[func]
+    fn on_picker_canceled(&mut self) {
+        self.close_picker();
+    }
+
+    /// Register picker: selection changed - preview the register content
+    #[func]
+    fn on_register_picker_selected(&mut self, index: i64) {
+        self.preview_register(index as i32);
+    }
+
+    /// Register picker: double-click / Enter on a register
+    #[func]
+    fn on_register_picker_activated(&mut self, index: i64) {
+        self.paste_register_at(index as i32);
+    }
+
+    /// Register picker: Paste button
+    #[func]
+    fn on_register_picker_confirmed(&mut self) {
+        self.paste_selected_register();
+    }
+
+    /// Register picker: Cancel / close
+    #[func]
+    fn on_register_picker_canceled(&mut self) {
+        self.close_register_picker();
+    }
+
     // =========================================================================
     // Input handler API: GDScript-based keybinding dispatch
     // =========================================================================
@@ -1534,6 +2327,18 @@ impl GodotNeovimPlugin {
         }
     }
 
+    /// Set the leader mappings of the GDScript keymap: the keys after `<leader>`
+    /// (e.g. "ff" for `<leader>ff`) to the name of their action (listed by the
+    /// leader palette). Called whenever the keymap changes.
+    #[func]
+    fn set_leader_mappings(&mut self, mappings: VarDictionary) {
+        self.leader_mappings = mappings
+            .iter_shared()
+            .map(|(keys, action)| (keys.to_string(), action.to_string()))
+            .collect();
+        crate::verbose_print!("[godot-neovim] Leader mappings: {:?}", self.leader_mappings);
+    }
+
     /// Clear the GDScript input handler, reverting to built-in Rust handling.
     #[func]
     fn clear_input_handler(&mut self) {
@@ -1586,6 +2391,15 @@ impl GodotNeovimPlugin {
         if self.handle_pending_char_op(&event) {
             return;
         }
+        if self.handle_pending_surround(&event) {
+            return;
+        }
+        if self.handle_pending_sneak(&event) {
+            return;
+        }
+        if self.handle_pending_text_object(&event) {
+            return;
+        }
         if self.handle_pending_mark_op(&event) {
             return;
         }
@@ -1652,6 +2466,18 @@ impl GodotNeovimPlugin {
         self.action_redo_impl();
     }
 
+    /// Go to the older text state, also across undo branches (g-)
+    #[func]
+    fn action_undo_earlier(&mut self) {
+        self.action_undo_earlier_impl();
+    }
+
+    /// Go to the newer text state, also across undo branches (g+)
+    #[func]
+    fn action_undo_later(&mut self) {
+        self.action_undo_later_impl();
+    }
+
     /// Page up (Ctrl+B)
     #[func]
     fn action_page_up(&mut self) {
@@ -1754,6 +2580,24 @@ impl GodotNeovimPlugin {
         self.action_search_word_backward_impl();
     }
 
+    /// Jump to two characters on screen (s{char}{char})
+    #[func]
+    fn action_sneak(&mut self) {
+        self.action_sneak_impl();
+    }
+
+    /// Search word under cursor forward, also inside other words (g*)
+    #[func]
+    fn action_search_partial_word_forward(&mut self) {
+        self.action_search_partial_word_forward_impl();
+    }
+
+    /// Search word under cursor backward, also inside other words (g#)
+    #[func]
+    fn action_search_partial_word_backward(&mut self) {
+        self.action_search_partial_word_backward_impl();
+    }
+
     /// Search next forward (n)
     #[func]
     fn action_search_next(&mut self) {
@@ -1766,12 +2610,48 @@ impl GodotNeovimPlugin {
         self.action_search_prev_impl();
     }
 
-    /// Go to definition (gd) - uses Godot LSP
+    /// Go to definition (gd) - LSP, then tags, then Godot's symbol lookup
     #[func]
     fn action_goto_definition(&mut self) {
         self.action_goto_definition_impl();
     }
 
+    /// Go to declaration in the current file (gD)
+    #[func]
+    fn action_goto_declaration(&mut self) {
+        self.action_goto_declaration_impl();
+    }
+
+    /// Show the diagnostics of the caret line in a float (`<leader>e`)
+    #[func]
+    fn action_show_line_diagnostics(&mut self) {
+        self.action_show_line_diagnostics_impl();
+    }
+
+    /// Code actions at the cursor or selection in the picker (`<leader>ca`) - uses Godot LSP
+    #[func]
+    fn action_code_actions(&mut self) {
+        self.action_code_actions_impl();
+    }
+
+    /// Outline of the current script's symbols in the picker - uses Godot LSP
+    #[func]
+    fn action_document_symbols(&mut self) {
+        self.action_document_symbols_impl();
+    }
+
+    /// Find references to symbol under cursor (grr) - uses Godot LSP
+    #[func]
+    fn action_lsp_references(&mut self) {
+        self.action_lsp_references_impl();
+    }
+
+    /// Rename symbol under cursor (gR) - uses Godot LSP
+    #[func]
+    fn action_lsp_rename(&mut self) {
+        self.action_lsp_rename_impl();
+    }
+
     /// Go to file under cursor (gf)
     #[func]
     fn action_goto_file(&mut self) {
@@ -1796,6 +2676,54 @@ impl GodotNeovimPlugin {
         self.action_prev_tab_impl();
     }
 
+    /// Fuzzy find a project file (<leader>ff)
+    #[func]
+    fn action_find_files(&mut self) {
+        self.action_find_files_impl();
+    }
+
+    /// Search the project as the pattern is typed (<leader>fg)
+    #[func]
+    fn action_live_grep(&mut self) {
+        self.action_live_grep_impl();
+    }
+
+    /// Run the project (<leader>rp)
+    #[func]
+    fn action_run_project(&mut self) {
+        self.action_run_project_impl();
+    }
+
+    /// Run the current scene (<leader>rs)
+    #[func]
+    fn action_run_current_scene(&mut self) {
+        self.action_run_current_scene_impl();
+    }
+
+    /// Stop the running project (<leader>rq)
+    #[func]
+    fn action_stop_project(&mut self) {
+        self.action_stop_project_impl();
+    }
+
+    /// Toggle the bottom panel (<leader>tb)
+    #[func]
+    fn action_toggle_bottom_panel(&mut self) {
+        self.action_toggle_bottom_panel_impl();
+    }
+
+    /// Focus the FileSystem dock (<leader>fe)
+    #[func]
+    fn action_focus_filesystem(&mut self) {
+        self.action_focus_filesystem_impl();
+    }
+
+    /// Open the editor's help search (<leader>fh)
+    #[func]
+    fn action_search_help(&mut self) {
+        self.action_search_help_impl();
+    }
+
     /// Toggle visual block mode (gv / Ctrl+V alternative)
     #[func]
     fn action_visual_block_toggle(&mut self) {
@@ -1862,6 +2790,30 @@ impl GodotNeovimPlugin {
         self.action_word_end_backward_impl();
     }
 
+    /// Select word under cursor / add next occurrence as a cursor (<C-n>)
+    #[func]
+    fn action_multicursor_next(&mut self) {
+        self.action_multicursor_next_impl();
+    }
+
+    /// Move to start of next sub-word (<A-w>)
+    #[func]
+    fn action_subword_forward(&mut self) {
+        self.action_subword_forward_impl();
+    }
+
+    /// Move to start of previous sub-word (<A-b>)
+    #[func]
+    fn action_subword_backward(&mut self) {
+        self.action_subword_backward_impl();
+    }
+
+    /// Move to end of next sub-word (<A-e>)
+    #[func]
+    fn action_subword_end(&mut self) {
+        self.action_subword_end_impl();
+    }
+
     /// Move to start of display line (g0)
     #[func]
     fn action_display_line_start(&mut self) {
@@ -1916,7 +2868,7 @@ impl GodotNeovimPlugin {
         self.action_open_documentation_impl();
     }
 
-    /// Save and close (ZZ / :wq)
+    /// Save if modified and close (ZZ / :x)
     #[func]
     fn action_save_and_close(&mut self) {
         self.action_save_and_close_impl();
@@ -1991,6 +2943,31 @@ impl GodotNeovimPlugin {
     fn is_recording_macro(&self) -> bool {
         self.is_recording_macro_impl()
     }
+
+    // =========================================================================
+    // Scripting API: drive the plugin from GDScript (editor tests, tool scripts)
+    // =========================================================================
+
+    /// Type keys in Vim notation into the current editor (e.g. "ciwfoo<Esc>")
+    /// The keys are fed one per frame; await wait_sync() for the result.
+    #[func]
+    fn run_keys(&mut self, keys: GString) {
+        self.run_keys_impl(&keys.to_string());
+    }
+
+    /// Signal emitted once the keys queued by run_keys() are handled and Neovim's
+    /// updates are applied: `await plugin.wait_sync()`
+    #[func]
+    fn wait_sync(&mut self) -> Signal {
+        self.sync_requested = true;
+        Signal::from_object_signal(&self.to_gd(), "sync_completed")
+    }
+
+    /// Text of Neovim's current buffer (lines joined with "\n")
+    #[func]
+    fn get_buffer_text(&self) -> GString {
+        self.get_buffer_text_impl()
+    }
 }
 
 /// Private helper methods for Neovim instance management
@@ -2010,6 +2987,108 @@ impl GodotNeovimPlugin {
         self.neovim_for(self.current_editor_type)
     }
 
+    /// Route a key event by mode to its handler (after the checks of input())
+    /// Also replays the keys held for a user mapping that did not match.
+    fn route_key_event(&mut self, key_event: &Gd<godot::classes::InputEventKey>) {
+        // Handle command-line mode input
+        if self.command_mode {
+            self.handle_command_mode_input(key_event);
+            return;
+        }
+
+        // Handle search mode input (/ or ?)
+        if self.search_mode {
+            self.handle_search_mode_input(key_event);
+            return;
+        }
+
+        // Handle pending character operator (f, F, t, T, r)
+        if self.handle_pending_char_op(key_event) {
+            return;
+        }
+
+        // Handle pending surround command (ys, cs, ds)
+        if self.handle_pending_surround(key_event) {
+            return;
+        }
+
+        // Handle pending sneak (s{char}{char}, then a label)
+        if self.handle_pending_sneak(key_event) {
+            return;
+        }
+
+        // Handle pending text object of a Godot-side operator (gci(, "adi")
+        if self.handle_pending_text_object(key_event) {
+            return;
+        }
+
+        // Handle pending mark operation (m, ', `)
+        if self.handle_pending_mark_op(key_event) {
+            return;
+        }
+
+        // Handle pending macro operation (q for record, @ for play)
+        if self.handle_pending_macro_op(key_event) {
+            return;
+        }
+
+        // Handle pending register selection (waiting for register char after ")
+        if self.handle_pending_register(key_event) {
+            return;
+        }
+
+        // Handle insert mode
+        if self.is_insert_mode() {
+            self.handle_insert_mode_input(key_event);
+            return;
+        }
+
+        // Handle replace mode
+        if self.is_replace_mode() {
+            self.handle_replace_mode_input(key_event);
+            return;
+        }
+
+        // Clever-f: f/F/t/T right after a find repeat it
+        if self.handle_clever_f_key(key_event) {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Handle keys for multicursor selections (<C-n>)
+        if self.multicursor && self.handle_multicursor_key(key_event) {
+            return;
+        }
+
+        // Keys of Neovim user mappings and Always Forward Keys go to Neovim
+        if self.feed_user_mapping_key(key_event) {
+            if let Some(mut viewport) = self.base().get_viewport() {
+                viewport.set_input_as_handled();
+            }
+            return;
+        }
+
+        // Handle normal/visual mode input
+        let count_before = self.resolve_count_before_key();
+        if self.input_handler.is_some() {
+            // GDScript dispatch path: process key in Rust, defer keymap lookup to GDScript.
+            // Cannot call GDScript Callable directly here (re-entrant &mut self borrow).
+            let result = self.process_key_event_impl(key_event);
+            if result.get_or_nil("needs_dispatch").to::<bool>() {
+                let resolved_key = result.get_or_nil("resolved_key");
+                let mode = result.get_or_nil("mode");
+                self.base_mut()
+                    .call_deferred("_dispatch_key_to_gdscript", &[resolved_key, mode]);
+            }
+        } else {
+            // Fallback: built-in Rust handling
+            self.handle_normal_mode_input(key_event);
+        }
+        self.resolve_count_after_key(key_event, &count_before);
+    }
+
     /// Initialize the plugin. Called by plugin.gd via set_plugin_active(true).
     /// Separated from enter_tree() because GDExtension plugins are auto-loaded by Godot
     /// regardless of the addon enabled/disabled state in Project Settings.
@@ -2018,6 +3097,23 @@ impl GodotNeovimPlugin {
 
         // Initialize settings first
         settings::initialize_settings();
+        self.load_local_options();
+        self.load_search_history();
+
+        // Report mismatched or incomplete installs before anything depends on them
+        self.install_problems = health::check_installation();
+        for problem in &self.install_problems {
+            godot_error!("[godot-neovim] {}", problem);
+        }
+
+        // Kill Neovim processes left by an editor session that crashed
+        let orphans = crate::neovim::sweep_orphans();
+        if orphans > 0 {
+            godot_warn!(
+                "[godot-neovim] Killed {} orphaned Neovim process(es) from a previous session",
+                orphans
+            );
+        }
 
         // Validate Neovim path
         let validation = settings::validate_current_path();
@@ -2025,56 +3121,40 @@ impl GodotNeovimPlugin {
             godot_warn!("[godot-neovim] Neovim validation failed, plugin may not work correctly");
         }
 
-        // Get addons path for Lua plugin
-        let addons_path = ProjectSettings::singleton()
-            .globalize_path("res://addons/godot-neovim")
-            .to_string();
-
-        // Initialize Neovim client for ScriptEditor
-        match NeovimClient::new() {
-            Ok(mut client) => {
-                if let Err(e) = client.start(Some(&addons_path)) {
-                    godot_error!(
-                        "[godot-neovim] Failed to start Neovim for ScriptEditor: {}",
-                        e
-                    );
-                    return;
-                }
+        // Initialize Neovim client for ScriptEditor (attached to the Neovim at
+        // the Connection Address setting, if set)
+        match recovery::spawn_neovim_client(EditorType::Script) {
+            Ok(client) => {
                 self.script_neovim = Some(Mutex::new(client));
                 crate::verbose_print!("[godot-neovim] ScriptEditor Neovim initialized");
             }
             Err(e) => {
-                godot_error!(
-                    "[godot-neovim] Failed to create Neovim client for ScriptEditor: {}",
-                    e
-                );
+                godot_error!("[godot-neovim] ScriptEditor: {}", e);
                 return;
             }
         }
 
-        // Initialize Neovim client for ShaderEditor (separate instance)
-        match NeovimClient::new() {
-            Ok(mut client) => {
-                if let Err(e) = client.start(Some(&addons_path)) {
-                    godot_error!(
-                        "[godot-neovim] Failed to start Neovim for ShaderEditor: {}",
-                        e
-                    );
-                    // Continue with ScriptEditor only
-                } else {
-                    self.shader_neovim = Some(Mutex::new(client));
-                    crate::verbose_print!("[godot-neovim] ShaderEditor Neovim initialized");
-                }
+        // Initialize Neovim client for ShaderEditor (separate embedded instance)
+        match recovery::spawn_neovim_client(EditorType::Shader) {
+            Ok(client) => {
+                self.shader_neovim = Some(Mutex::new(client));
+                crate::verbose_print!("[godot-neovim] ShaderEditor Neovim initialized");
             }
             Err(e) => {
-                godot_warn!(
-                    "[godot-neovim] Failed to create Neovim client for ShaderEditor: {}",
-                    e
-                );
                 // Continue with ScriptEditor only
+                godot_warn!("[godot-neovim] ShaderEditor: {}", e);
             }
         }
 
+        self.sync_comment_continuation_to_neovim();
+        self.sync_formatter_to_neovim();
+        self.sync_undo_dir_to_neovim();
+        self.apply_neovim_options();
+        self.restore_last_search_pattern();
+        self.refresh_user_mappings();
+        self.refresh_key_interception();
+        self.refresh_layout_overrides();
+
         // Create LSP client only if use_thread is enabled in editor settings
         // (LSP server won't respond without threading enabled)
         let use_thread = EditorInterface::singleton()
@@ -2099,6 +3179,7 @@ impl GodotNeovimPlugin {
         // Create mode indicator label and recording indicator
         self.create_mode_label();
         self.create_recording_label();
+        self.create_status_dock();
 
         // Connect to script editor signals
         self.connect_script_editor_signals();
@@ -2130,6 +3211,10 @@ impl GodotNeovimPlugin {
         // Disable process() first
         self.base_mut().set_process(false);
 
+        // IME Auto Off: leave the IME on for Godot
+        self.ime_exit_requested = None;
+        self.set_ime_for_insert_mode(true);
+
         // Cleanup mode labels (check if still valid before freeing)
         if let Some(mut label) = self.mode_label.take() {
             if label.is_instance_valid() {
@@ -2152,6 +3237,14 @@ impl GodotNeovimPlugin {
             }
         }
 
+        self.remove_message_labels();
+        self.remove_showcmd_labels();
+        self.close_leader_palette();
+        self.remove_relative_number_gutters();
+        self.clear_diagnostics_view();
+        self.close_health_dialog();
+        self.remove_status_dock();
+
         // Restore standard line caret before disconnecting
         if let Some(ref mut editor) = self.current_editor {
             editor.set_caret_type(godot::classes::text_edit::CaretType::LINE);
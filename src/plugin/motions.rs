@@ -2,48 +2,79 @@
 
 use super::GodotNeovimPlugin;
 
-impl GodotNeovimPlugin {
-    /// Handle scroll and fold command sequences (za, zo, zc, zM, zR)
-    /// Note: zz, zt, zb are now handled by Neovim via win_viewport events
-    pub(super) fn handle_scroll_command(&mut self, keys: &str) -> bool {
-        if self.last_key == "z" {
-            match keys {
-                // zz, zt, zb are handled by Neovim - just clear last_key but don't handle locally
-                "z" | "t" | "b" => {
-                    self.clear_last_key();
-                    return false; // Let Neovim handle via win_viewport
-                }
-                "a" => {
-                    self.toggle_fold();
-                    self.clear_last_key();
-                    return true;
-                }
-                "o" => {
-                    self.unfold_current_line();
-                    self.clear_last_key();
-                    return true;
-                }
-                "c" => {
-                    self.fold_current_line();
-                    self.clear_last_key();
-                    return true;
-                }
-                "M" => {
-                    self.fold_all();
-                    self.clear_last_key();
-                    return true;
-                }
-                "R" => {
-                    self.unfold_all();
-                    self.clear_last_key();
-                    return true;
-                }
-                _ => {}
-            }
-        }
-        false
+/// Sub-word motions (<A-w>, <A-b>, <A-e> by default)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum SubwordMotion {
+    /// Start of the next sub-word
+    Forward,
+    /// Start of the previous sub-word
+    Backward,
+    /// End of the next sub-word
+    EndForward,
+}
+
+/// Vim's character class (see utf_class() in Neovim's mbyte.c)
+/// 0: blank, 1: punctuation, 2: word character, CJK scripts: block start code point
+/// (so Hiragana, Katakana, ideographs and Hangul each form separate words)
+fn char_class(c: char) -> u32 {
+    match c {
+        ' ' | '\t' | '\u{3000}' => 0,
+        '\u{3040}'..='\u{309f}' => 0x3040,
+        '\u{30a0}'..='\u{30ff}' => 0x30a0,
+        '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}' => 0x4e00,
+        '\u{ac00}'..='\u{d7a3}' => 0xac00,
+        '\u{3001}'..='\u{303f}' | '\u{ff01}'..='\u{ff0f}' | '\u{ff1a}'..='\u{ff20}' => 1,
+        '\u{ff10}'..='\u{ff19}' | '\u{ff21}'..='\u{ff3a}' | '\u{ff41}'..='\u{ff5a}' => 2,
+        c if c.is_alphanumeric() || c == '_' => 2,
+        c if c.is_whitespace() => 0,
+        _ => 1,
+    }
+}
+
+/// Whether `chars[i]` starts a sub-word: a word start, or a camelCase / snake_case /
+/// letter-digit boundary inside a word (`getHTTPResponse_v2` → get|HTTP|Response|_v|2)
+fn is_subword_start(chars: &[char], i: usize) -> bool {
+    let c = chars[i];
+    let class = char_class(c);
+    if class == 0 {
+        return false;
     }
+    let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) else {
+        return true;
+    };
+    if char_class(prev) != class {
+        return true;
+    }
+    if class != 2 {
+        // Punctuation and CJK runs have no sub-words
+        return false;
+    }
+    if c == '_' {
+        return false;
+    }
+    if prev == '_' {
+        return true;
+    }
+    let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+    (c.is_uppercase() && !prev.is_uppercase())
+        || (c.is_uppercase() && prev.is_uppercase() && next_lower)
+        || (c.is_numeric() != prev.is_numeric())
+}
+
+/// Whether `chars[i]` ends a sub-word (the character before the next boundary)
+fn is_subword_end(chars: &[char], i: usize) -> bool {
+    let c = chars[i];
+    if char_class(c) == 0 || (c == '_' && char_class(chars.get(i + 1).copied().unwrap_or(' ')) == 2)
+    {
+        return false;
+    }
+    i + 1 >= chars.len()
+        || chars[i + 1] == '_'
+        || is_subword_start(chars, i + 1)
+        || char_class(chars[i + 1]) == 0
+}
 
+impl GodotNeovimPlugin {
     // Note: zz, zt, zb, H, M, L are now handled by Neovim via win_viewport events
     // Local implementations have been removed
 
@@ -130,55 +161,47 @@ impl GodotNeovimPlugin {
     }
 
     /// Move to end of previous word (ge command)
+    /// Uses Vim's character classes, so `foo.bar`, `snake_case` and CJK runs split
+    /// the same way as in Neovim (which receives `ge` afterwards)
     pub(super) fn move_to_word_end_backward(&mut self) {
         let Some(ref editor) = self.current_editor else {
             return;
         };
 
         let mut line = editor.get_caret_line();
-        let mut col = editor.get_caret_column() as usize;
+        let mut chars: Vec<char> = editor.get_line(line).to_string().chars().collect();
+        let mut col = (editor.get_caret_column() as usize).min(chars.len().saturating_sub(1));
 
-        // Get current line text
-        let mut line_text = editor.get_line(line).to_string();
-        let mut chars: Vec<char> = line_text.chars().collect();
-
-        // If we're at or past the end of line, move to last character
-        if col > 0 && col >= chars.len() {
-            col = chars.len() - 1;
-        }
-
-        // Move back one position to start search
-        if col > 0 {
+        // Skip the rest of the word under the cursor (same class, same line)
+        let start_class = chars.get(col).map_or(0, |&c| char_class(c));
+        while col > 0 && start_class != 0 && char_class(chars[col - 1]) == start_class {
             col -= 1;
-        } else if line > 0 {
-            // Move to previous line
-            line -= 1;
-            line_text = editor.get_line(line).to_string();
-            chars = line_text.chars().collect();
-            col = if chars.is_empty() { 0 } else { chars.len() - 1 };
         }
 
-        // Skip whitespace going backward
+        // Step back to the previous non-blank character; an empty line is a word
         loop {
-            if col < chars.len() && !chars[col].is_whitespace() {
-                break;
-            }
             if col > 0 {
                 col -= 1;
             } else if line > 0 {
                 line -= 1;
-                line_text = editor.get_line(line).to_string();
-                chars = line_text.chars().collect();
-                col = if chars.is_empty() { 0 } else { chars.len() - 1 };
+                chars = editor.get_line(line).to_string().chars().collect();
+                if chars.is_empty() {
+                    col = 0;
+                    break;
+                }
+                col = chars.len() - 1;
             } else {
                 // At beginning of document
                 self.move_cursor_to(0, 0);
                 crate::verbose_print!("[godot-neovim] ge: At start of document");
                 return;
             }
+            if char_class(chars[col]) != 0 {
+                break;
+            }
         }
 
-        // We're now on a non-whitespace char - this is the end of the previous word
+        // We're now on the last character of the previous word
         self.move_cursor_to(line, col as i32);
         crate::verbose_print!(
             "[godot-neovim] ge: Moved to word end at {}:{}",
@@ -187,6 +210,71 @@ impl GodotNeovimPlugin {
         );
     }
 
+    /// Sub-word motion (camelCase / snake_case segments), repeated `count` times
+    /// The target is computed locally and sent to Neovim as a `<Cmd>` cursor move,
+    /// so it also works as a Visual mode extension and after an operator (`d<A-w>`).
+    pub(super) fn move_by_subword(&mut self, motion: SubwordMotion, count: i32) {
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+
+        let line_count = editor.get_line_count();
+        let mut line = editor.get_caret_line();
+        let mut chars: Vec<char> = editor.get_line(line).to_string().chars().collect();
+        let mut col = (editor.get_caret_column() as usize).min(chars.len().saturating_sub(1));
+
+        'count: for _ in 0..count.max(1) {
+            loop {
+                // Step one character forward/backward, crossing lines
+                if motion == SubwordMotion::Backward {
+                    if col > 0 {
+                        col -= 1;
+                    } else if line > 0 {
+                        line -= 1;
+                        chars = editor.get_line(line).to_string().chars().collect();
+                        col = chars.len().saturating_sub(1);
+                    } else {
+                        break 'count;
+                    }
+                } else if col + 1 < chars.len() {
+                    col += 1;
+                } else if line + 1 < line_count {
+                    line += 1;
+                    chars = editor.get_line(line).to_string().chars().collect();
+                    col = 0;
+                } else {
+                    break 'count;
+                }
+
+                let found = match motion {
+                    // An empty line is a stop for w/b, like in Vim
+                    SubwordMotion::Forward | SubwordMotion::Backward => {
+                        chars.is_empty() || is_subword_start(&chars, col)
+                    }
+                    SubwordMotion::EndForward => !chars.is_empty() && is_subword_end(&chars, col),
+                };
+                if found {
+                    break;
+                }
+            }
+        }
+
+        // Neovim's cursor() takes a 1-based byte column
+        let byte_col: usize = chars.iter().take(col).map(|c| c.len_utf8()).sum();
+        self.move_cursor_to(line, col as i32);
+        self.send_keys(&format!(
+            "<Cmd>call cursor({}, {})<CR>",
+            line + 1,
+            byte_col + 1
+        ));
+        crate::verbose_print!(
+            "[godot-neovim] {:?}: Moved to sub-word at {}:{}",
+            motion,
+            line + 1,
+            col
+        );
+    }
+
     /// Move cursor to specified position (Godot local only, does not sync to Neovim)
     /// Caller is responsible for sending the corresponding key to Neovim
     pub(super) fn move_cursor_to(&mut self, line: i32, col: i32) {
@@ -220,9 +308,6 @@ impl GodotNeovimPlugin {
 
     /// Jump to matching bracket (% command)
     pub(super) fn jump_to_matching_bracket(&mut self) {
-        // Add to jump list before jumping
-        self.add_to_jump_list();
-
         let Some(ref editor) = self.current_editor else {
             return;
         };
@@ -0,0 +1,288 @@
+//! Multicursor editing (vim-visual-multi style `<C-n>`)
+//!
+//! `<C-n>` selects the word under the cursor (or the Visual selection) and each
+//! further `<C-n>` adds a selection at its next occurrence, using CodeEdit's
+//! multiple carets. The selections are then changed, deleted or edited at all
+//! carets in Godot; the result is synced to Neovim as one undoable change.
+
+use super::GodotNeovimPlugin;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+
+impl GodotNeovimPlugin {
+    /// `<C-n>`: select the word under the cursor, or add the next occurrence
+    pub(super) fn multicursor_add_next(&mut self) {
+        if !self.multicursor {
+            self.start_multicursor();
+            return;
+        }
+
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        let before = editor.get_caret_count();
+        editor.add_selection_for_next_occurrence();
+        if editor.get_caret_count() == before {
+            self.show_status_message("[multicursor] No more occurrences");
+            return;
+        }
+        self.show_multicursor_status();
+    }
+
+    /// Select the Visual selection (single line) or the word under the cursor
+    fn start_multicursor(&mut self) {
+        let from_visual = self.is_in_visual_mode();
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+
+        let range = if from_visual
+            && editor.has_selection()
+            && editor.get_selection_from_line() == editor.get_selection_to_line()
+        {
+            Some((
+                editor.get_selection_from_line(),
+                editor.get_selection_from_column(),
+                editor.get_selection_to_column(),
+            ))
+        } else {
+            Self::word_bounds_at_caret(editor)
+        };
+        let Some((line, from_col, to_col)) = range else {
+            self.show_status_message("[multicursor] No word under cursor");
+            return;
+        };
+
+        if from_visual {
+            // The selection is Godot's from here on; Neovim goes back to Normal mode
+            if let Some(neovim) = self.get_current_neovim() {
+                if let Ok(client) = neovim.try_lock() {
+                    if let Err(e) = client.command("exe \"normal! \\<Esc>\"") {
+                        godot_warn!("[godot-neovim] Failed to leave visual mode: {}", e);
+                    }
+                }
+            }
+        }
+
+        self.multicursor = true;
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        editor.remove_secondary_carets();
+        editor.set_selecting_enabled(true);
+        editor.select(line, from_col, line, to_col);
+        crate::verbose_print!(
+            "[godot-neovim] Multicursor: started on line {} cols {}..{}",
+            line + 1,
+            from_col,
+            to_col
+        );
+        self.show_multicursor_status();
+    }
+
+    /// Word (alphanumeric and underscore) at the caret as (line, from_col, to_col)
+    fn word_bounds_at_caret(editor: &Gd<godot::classes::CodeEdit>) -> Option<(i32, i32, i32)> {
+        let line = editor.get_caret_line();
+        let chars: Vec<char> = editor.get_line(line).to_string().chars().collect();
+        let col = editor.get_caret_column() as usize;
+        if col >= chars.len() {
+            return None;
+        }
+
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        if !is_word_char(chars[col]) {
+            return None;
+        }
+        let mut start = col;
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = col;
+        while end < chars.len() && is_word_char(chars[end]) {
+            end += 1;
+        }
+        Some((line, start as i32, end as i32))
+    }
+
+    fn show_multicursor_status(&mut self) {
+        let count = self
+            .current_editor
+            .as_ref()
+            .map_or(0, |editor| editor.get_caret_count());
+        self.show_status_message(&format!("[multicursor] {} cursor(s)", count));
+    }
+
+    /// Handle a Normal mode key while multicursor selections are active
+    /// Returns true if the key was consumed. Other keys leave multicursor mode
+    /// and are processed normally.
+    pub(super) fn handle_multicursor_key(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        let keycode = key_event.get_keycode();
+        if matches!(
+            keycode,
+            Key::SHIFT | Key::CTRL | Key::ALT | Key::META | Key::CAPSLOCK | Key::NUMLOCK
+        ) {
+            return false;
+        }
+
+        let ctrl = key_event.is_ctrl_pressed();
+        let consumed = if keycode == Key::ESCAPE || (ctrl && keycode == Key::BRACKETLEFT) {
+            self.exit_multicursor();
+            true
+        } else if ctrl && keycode == Key::N {
+            self.multicursor_add_next();
+            true
+        } else if ctrl || key_event.is_alt_pressed() {
+            false
+        } else {
+            match char::from_u32(key_event.get_unicode()) {
+                // Skip the last occurrence and select the next one instead
+                Some('q') => {
+                    if let Some(ref mut editor) = self.current_editor {
+                        editor.skip_selection_for_next_occurrence();
+                    }
+                    self.show_multicursor_status();
+                    true
+                }
+                // Remove the last occurrence
+                Some('Q') => {
+                    if let Some(ref mut editor) = self.current_editor {
+                        let count = editor.get_caret_count();
+                        if count > 1 {
+                            editor.remove_caret(count - 1);
+                        }
+                    }
+                    self.show_multicursor_status();
+                    true
+                }
+                Some('c') | Some('s') => {
+                    self.start_multicursor_insert(MulticursorInsert::Change);
+                    true
+                }
+                Some('i') => {
+                    self.start_multicursor_insert(MulticursorInsert::Before);
+                    true
+                }
+                Some('a') => {
+                    self.start_multicursor_insert(MulticursorInsert::After);
+                    true
+                }
+                Some('d') | Some('x') => {
+                    self.delete_multicursor_selections();
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        if !consumed {
+            self.exit_multicursor();
+            return false;
+        }
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+        true
+    }
+
+    /// Enter insert mode at all carets
+    /// The edits until Esc (including the deleted selections) are one transaction.
+    fn start_multicursor_insert(&mut self, insert: MulticursorInsert) {
+        self.begin_edit_transaction();
+
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        match insert {
+            MulticursorInsert::Change => editor.delete_selection(),
+            MulticursorInsert::Before | MulticursorInsert::After => {
+                for caret in 0..editor.get_caret_count() {
+                    let (line, col) = if insert == MulticursorInsert::Before {
+                        (
+                            editor
+                                .get_selection_from_line_ex()
+                                .caret_index(caret)
+                                .done(),
+                            editor
+                                .get_selection_from_column_ex()
+                                .caret_index(caret)
+                                .done(),
+                        )
+                    } else {
+                        (
+                            editor.get_selection_to_line_ex().caret_index(caret).done(),
+                            editor
+                                .get_selection_to_column_ex()
+                                .caret_index(caret)
+                                .done(),
+                        )
+                    };
+                    editor.deselect_ex().caret_index(caret).done();
+                    editor.set_caret_line_ex(line).caret_index(caret).done();
+                    editor.set_caret_column_ex(col).caret_index(caret).done();
+                }
+            }
+        }
+
+        // Neovim enters insert mode at the first caret, so syncing its cursor back
+        // on the mode change keeps the carets in place
+        let line = editor.get_caret_line();
+        let col = editor.get_caret_column();
+        let line_text = editor.get_line(line).to_string();
+        let byte_col = Self::char_col_to_byte_col(&line_text, col);
+        if let Some(neovim) = self.get_current_neovim() {
+            if let Ok(client) = neovim.try_lock() {
+                let _ = client.set_cursor((line + 1) as i64, byte_col as i64);
+            }
+        }
+
+        self.multicursor_insert = true;
+        self.send_keys("i");
+    }
+
+    /// Delete the selections at all carets and leave multicursor mode
+    fn delete_multicursor_selections(&mut self) {
+        self.begin_edit_transaction();
+        if let Some(ref mut editor) = self.current_editor {
+            editor.delete_selection();
+        }
+        self.sync_buffer_to_neovim_keep_undo();
+        self.commit_edit_transaction();
+        self.exit_multicursor();
+    }
+
+    /// Insert mode at all carets is over: the buffer was synced to Neovim by the
+    /// Esc handling (one change), which completes the transaction
+    pub(super) fn finish_multicursor_insert(&mut self) {
+        self.commit_edit_transaction();
+        self.exit_multicursor();
+    }
+
+    /// Back to a single caret without selection
+    pub(super) fn exit_multicursor(&mut self) {
+        if !self.multicursor {
+            return;
+        }
+        self.multicursor = false;
+        self.multicursor_insert = false;
+        if let Some(ref mut editor) = self.current_editor {
+            if editor.is_instance_valid() {
+                editor.remove_secondary_carets();
+                editor.deselect();
+            }
+        }
+        crate::verbose_print!("[godot-neovim] Multicursor: exited");
+        self.sync_cursor_to_neovim();
+    }
+}
+
+/// Where typing starts at each caret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MulticursorInsert {
+    /// Replace the selections (c, s)
+    Change,
+    /// Before the selections (i)
+    Before,
+    /// After the selections (a)
+    After,
+}
@@ -1,15 +1,66 @@
 //! Neovim communication: buffer sync, cursor sync, key sending
 
 use super::GodotNeovimPlugin;
+use crate::neovim::IndentOptions;
+use godot::classes::CodeEdit;
 use godot::prelude::*;
 
+/// Grid size (columns, rows) used when the editor size is not known yet
+const DEFAULT_GRID_SIZE: (i64, i64) = (120, 10);
+
+/// Minimum grid size: Neovim misbehaves with very small windows
+const MIN_GRID_SIZE: (i64, i64) = (20, 10);
+
+/// First line of the last page: the top line that shows the end of the file with
+/// no space below it (the furthest the editor scrolls without Scroll Past End of
+/// File). `rows` gives the screen rows of a line (wrapped lines take several).
+fn last_page_topline(line_count: i32, screen_rows: i32, rows: impl Fn(i32) -> i32) -> i32 {
+    let mut topline = line_count;
+    let mut used = 0;
+    while topline > 0 {
+        used += rows(topline - 1);
+        if used > screen_rows {
+            break;
+        }
+        topline -= 1;
+    }
+    topline
+}
+
 impl GodotNeovimPlugin {
+    /// Neovim grid size (columns, rows) matching the editor's text area
+    /// Derived from the current font and size, so editor scale (DPI) and font zoom are
+    /// reflected. Returns None if the editor has not been laid out yet.
+    pub(super) fn editor_grid_size(editor: &Gd<CodeEdit>) -> Option<(i64, i64)> {
+        let visible_lines = editor.get_visible_line_count();
+        if visible_lines <= 0 {
+            return None;
+        }
+
+        let font_size = editor.get_theme_font_size("font_size");
+        let char_width = editor
+            .get_theme_font("font")
+            .map(|font| font.get_string_size_ex("M").font_size(font_size).done().x)
+            .unwrap_or(0.0);
+        let text_width = editor.get_size().x - editor.get_total_gutter_width() as f32;
+        let columns = if char_width > 0.0 {
+            (text_width / char_width) as i64
+        } else {
+            DEFAULT_GRID_SIZE.0
+        };
+
+        Some((
+            columns.max(MIN_GRID_SIZE.0),
+            (visible_lines as i64).max(MIN_GRID_SIZE.1),
+        ))
+    }
+
     /// Switch to Neovim buffer for the current file
     /// Creates buffer if not exists, initializes content if new
     /// Returns (line, col, is_new) - cursor position and whether buffer was newly created
     pub(super) fn switch_to_neovim_buffer(&mut self) -> Option<(i64, i64, bool)> {
         // First, gather all data from editor (to avoid borrow conflicts)
-        let (text, godot_line_count, use_spaces, indent_size, visible_lines) = {
+        let (text, godot_line_count, use_spaces, indent_size, grid_size) = {
             let Some(ref editor) = self.current_editor else {
                 crate::verbose_print!("[godot-neovim] switch_to_neovim_buffer: No current editor");
                 return None;
@@ -19,7 +70,7 @@ impl GodotNeovimPlugin {
                 editor.get_line_count(),
                 editor.is_indent_using_spaces(),
                 editor.get_indent_size(),
-                editor.get_visible_line_count(),
+                Self::editor_grid_size(editor),
             )
         };
 
@@ -38,6 +89,9 @@ impl GodotNeovimPlugin {
             self.current_script_path.clone()
         };
 
+        // Files that are not UTF-8 on disk are decoded here (Godot assumes UTF-8)
+        let text = self.decode_file_on_open(&text).unwrap_or(text);
+
         // Get text from Godot and normalize: remove trailing newline to match Neovim's line count
         // Neovim treats trailing newline as implicit (eol option), not as an extra line
         let trimmed = text.trim_end_matches('\n');
@@ -50,6 +104,18 @@ impl GodotNeovimPlugin {
                 .collect()
         };
 
+        // The script's own indentation wins over the editor's indent settings
+        let IndentOptions {
+            use_spaces,
+            indent_size,
+        } = self.script_indent(
+            &lines,
+            IndentOptions {
+                use_spaces,
+                indent_size,
+            },
+        );
+
         // Now access Neovim - access field directly to allow borrowing other fields
         let neovim_ref = match self.current_editor_type {
             super::EditorType::Shader => self.shader_neovim.as_ref(),
@@ -76,7 +142,17 @@ impl GodotNeovimPlugin {
 
         // Switch to buffer (creates if not exists)
         // Note: Don't pass indent_opts here - they must be set AFTER filetype
+        // A large file is switched to with its first chunk; the rest is appended after
         let nvim_line_count = lines.len() as i32;
+        let (lines, chunks) = if super::large_file::is_large(lines.len()) {
+            super::large_file::split_chunks(lines)
+        } else {
+            (lines, Vec::new())
+        };
+        // Viewports sent so far belong to the previous buffer: only apply newer ones.
+        // Read before switching, as the new buffer's viewport may arrive before the
+        // switch returns (events are received on the runtime's worker threads)
+        let viewport_seq = client.viewport_seq();
         match client.switch_to_buffer(&abs_path, Some(lines), None) {
             Ok(result) => {
                 crate::verbose_print!(
@@ -94,6 +170,23 @@ impl GodotNeovimPlugin {
                 self.sync_manager.set_attached(result.attached);
                 self.sync_manager.set_line_count(nvim_line_count);
 
+                if result.initialized && !chunks.is_empty() {
+                    crate::verbose_print!(
+                        "[godot-neovim] Large file: loading {} more chunk(s)",
+                        chunks.len()
+                    );
+                    if !super::large_file::queue_chunks(
+                        &client,
+                        result.bufnr,
+                        &self.current_script_path,
+                        chunks,
+                    ) {
+                        godot_warn!(
+                            "[godot-neovim] Large file: Could not queue the remaining lines"
+                        );
+                    }
+                }
+
                 // For external CodeEdits, configure as scratch buffer
                 if self.current_editor_type == super::EditorType::Unknown {
                     let _ = client.command("setlocal buftype=nofile");
@@ -145,31 +238,21 @@ impl GodotNeovimPlugin {
 
                 // Resize Neovim UI to match Godot editor's visible area
                 // This is important for viewport commands (zz, zt, zb) to work correctly
-                self.last_visible_lines = visible_lines;
-                // Use reasonable default width (doesn't affect viewport commands)
-                let width = 120i64;
-                // Ensure at least 10 lines to avoid too small window
-                let height = (visible_lines as i64).max(10);
+                let (width, height) = grid_size.unwrap_or(DEFAULT_GRID_SIZE);
+                self.last_grid_size = (width, height);
                 crate::verbose_print!(
-                    "[godot-neovim] Resize on script open: visible_lines={}, height={}",
-                    visible_lines,
+                    "[godot-neovim] Resize on script open: width={}, height={}",
+                    width,
                     height
                 );
                 client.ui_try_resize(width, height);
 
-                // Force viewport_changed flag to ensure next viewport event is processed
-                // This is needed because viewport values may be same as before close
-                client.force_viewport_changed();
+                self.viewport_seq = viewport_seq;
 
                 // Mark buffer as saved in Godot (only for new buffers to prevent false dirty flag on open)
                 // Don't call for existing buffers - it would clear dirty flag on tab switch
                 drop(client);
 
-                // Skip grid_cursor_goto sync until we receive viewport change
-                // This prevents incorrect cursor positioning when reopening a file after :q
-                // (viewport values may be same as before close, causing take_viewport() to return None)
-                self.skip_grid_cursor_after_switch = true;
-
                 if result.is_new {
                     if let Some(ref mut editor) = self.current_editor {
                         editor.tag_saved_version();
@@ -200,6 +283,16 @@ impl GodotNeovimPlugin {
     /// Sync buffer from Godot editor to Neovim (for ESC from insert mode)
     /// Preserves undo history
     pub(super) fn sync_buffer_to_neovim_keep_undo(&mut self) {
+        self.sync_buffer_to_neovim_impl(true);
+    }
+
+    /// Sync buffer from Godot editor to Neovim as freshly loaded content (for :e!)
+    /// Clears the modified flag; undo history is the one saved with the file, if any
+    pub(super) fn sync_buffer_to_neovim_reset_undo(&mut self) {
+        self.sync_buffer_to_neovim_impl(false);
+    }
+
+    fn sync_buffer_to_neovim_impl(&mut self, keep_undo: bool) {
         // First gather data from editor
         let text = {
             let Some(ref editor) = self.current_editor else {
@@ -239,7 +332,11 @@ impl GodotNeovimPlugin {
 
         // ESC sync: update buffer preserving undo history
         // Collect results first, then update sync_manager after releasing lock
-        let update_result = client.buffer_update(lines);
+        let update_result = if keep_undo {
+            client.buffer_update(lines)
+        } else {
+            client.buffer_register(lines)
+        };
         let attach_result = update_result
             .as_ref()
             .ok()
@@ -311,6 +408,11 @@ impl GodotNeovimPlugin {
 
     /// Sync cursor position from Godot editor to Neovim
     pub(super) fn sync_cursor_to_neovim(&mut self) {
+        // The caret only previews the target of an operator motion
+        if self.resolving_operator_motion {
+            return;
+        }
+
         // Skip if buffer not yet initialized (e.g., during hot reload)
         if self.sync_manager.get_line_count() == 0 {
             crate::verbose_print!(
@@ -384,23 +486,12 @@ impl GodotNeovimPlugin {
             return;
         };
 
-        let cursor_result = client.set_cursor(nvim_line, nvim_col);
-        // Release lock before potentially showing dialog
-        drop(client);
-
-        if let Err(e) = cursor_result {
-            let error_str = e.to_string();
-            // Check if this is a timeout or connection error
-            // "Timeout" = RPC timeout, "Failed to" = Neovim process died
-            if (error_str.contains("Timeout")
-                || error_str.contains("timeout")
-                || error_str.contains("Failed to"))
-                && self.record_timeout_error()
-            {
-                self.show_recovery_dialog();
-            }
-            godot_error!("[godot-neovim] Failed to sync cursor: {}", e);
+        // Queued after the keys sent so far; a failure arrives with the responses
+        // (process_rpc_responses)
+        if !client.set_cursor_async(nvim_line, nvim_col) {
+            godot_error!("[godot-neovim] Failed to queue cursor sync");
         }
+        drop(client);
 
         // Update tracking
         // Only track last_nvim_synced_line when clamping (to prevent repeated clamping)
@@ -419,6 +510,12 @@ impl GodotNeovimPlugin {
     /// buffered in pending_keys_after_exit and sent together after exit completes.
     /// This prevents key loss during mode transitions (vscode-neovim style).
     pub(super) fn send_keys(&mut self, keys: &str) -> bool {
+        // The count typed before the command goes to Neovim with it
+        let keys = match self.take_count() {
+            Some(count) => format!("{}{}", count, keys),
+            None => keys.to_string(),
+        };
+        let keys = keys.as_str();
         crate::verbose_print!("[godot-neovim] send_keys: {}", keys);
 
         // Clear user_cursor_sync flag when sending keys
@@ -460,6 +557,8 @@ impl GodotNeovimPlugin {
         }
         // Lock released here
 
+        self.note_keys_sent(keys);
+
         // Track key send time for no-response detection
         self.last_key_send_time = Some(std::time::Instant::now());
         self.pending_key_count += 1;
@@ -487,11 +586,16 @@ impl GodotNeovimPlugin {
         if let Some(ref mut editor) = self.current_editor {
             editor.cancel_code_completion();
         }
+        self.close_signature_help();
+        self.snippet_session = None;
 
         // Set flag to buffer any keys pressed during the exit process
         // This prevents key loss when user types quickly after pressing Escape
         // Check for both short ("i", "R") and long ("insert", "replace") mode names
         let was_insert = matches!(self.current_mode.as_str(), "i" | "insert" | "R" | "replace");
+        // Replace mode is driven by Neovim (see input/replace.rs), so Neovim already has
+        // the buffer and cursor - Godot must not sync its (possibly stale) state back
+        let was_replace = self.is_replace_mode();
         if was_insert {
             self.is_exiting_insert_mode = true;
             self.pending_keys_after_exit.clear();
@@ -506,7 +610,7 @@ impl GodotNeovimPlugin {
 
         // For gi command support: sync buffer and cursor BEFORE sending Escape
         // This way Neovim will set '^' mark at the correct position when exiting insert mode
-        if was_insert {
+        if was_insert && !was_replace {
             // Sync buffer from Godot to Neovim (user was typing in Godot)
             // Use keep_undo variant to preserve undo history so 'u' works
             self.sync_buffer_to_neovim_keep_undo();
@@ -537,6 +641,14 @@ impl GodotNeovimPlugin {
             }
         }
 
+        // Typing at multiple carets is over; the sync above was its Neovim change
+        if self.multicursor_insert {
+            self.finish_multicursor_insert();
+        }
+
+        // Keys typed in Godot while recording a macro go into the recording before <Esc>
+        self.flush_macro_insert_keys();
+
         // Send Escape to Neovim via channel
         // Neovim will automatically set '^' mark at current cursor position
         let escape_result = {
@@ -566,11 +678,17 @@ impl GodotNeovimPlugin {
         // For non-insert modes, sync buffer after Escape
         if !was_insert {
             self.sync_buffer_to_neovim_keep_undo();
+        } else {
+            self.schedule_auto_save();
         }
 
         // Process any buffer events triggered by sync_buffer_to_neovim
         // to prevent them from moving cursor later
-        let buf_events: Vec<BufEvent> = {
+        // Replace mode: there was no sync, so pending events are real Neovim changes
+        // (e.g. count repetition of 5Rfoo<Esc>) and are applied by process_neovim_updates
+        let buf_events: Vec<BufEvent> = if was_replace {
+            Vec::new()
+        } else {
             let neovim_ref = match self.current_editor_type {
                 super::EditorType::Shader => self.shader_neovim.as_ref(),
                 _ => self.script_neovim.as_ref(),
@@ -604,6 +722,11 @@ impl GodotNeovimPlugin {
         for event in buf_events {
             match event {
                 BufEvent::Lines(buf_lines_event) => {
+                    self.on_search_index_lines(
+                        buf_lines_event.first_line,
+                        buf_lines_event.last_line,
+                        buf_lines_event.line_data.len(),
+                    );
                     // Ignore content - this is echo from our sync
                     // But still update changedtick to keep sync state valid
                     self.sync_manager
@@ -640,7 +763,7 @@ impl GodotNeovimPlugin {
                     } else if bang {
                         self.cmd_close_discard();
                     } else {
-                        self.cmd_close();
+                        self.cmd_quit();
                     }
                 }
                 BufEvent::SaveAndClose => {
@@ -652,11 +775,21 @@ impl GodotNeovimPlugin {
                     self.cmd_save_all();
                     self.cmd_close_all();
                 }
+                BufEvent::Yank(yank) => {
+                    self.on_register_yanked(&yank);
+                }
+                BufEvent::ClipboardSet { register, text } => {
+                    self.on_clipboard_set(&register, &text);
+                }
+                BufEvent::Autocmd(event) => {
+                    self.on_autocmd(&event);
+                }
             }
         }
 
         // Restore cursor position in Godot after handling buffer events
-        if let Some((line, col)) = saved_cursor {
+        // (Replace mode: Neovim moves the cursor on <Esc>, win_viewport will sync it)
+        if let Some((line, col)) = saved_cursor.filter(|_| !was_replace) {
             if let Some(ref mut editor) = self.current_editor {
                 self.last_synced_cursor = (line as i64, col as i64);
                 editor.set_caret_line(line);
@@ -672,14 +805,21 @@ impl GodotNeovimPlugin {
 
         // Force mode to normal (ESC always returns to normal mode)
         self.current_mode = "n".to_string();
+        self.operator_pending = false;
+        self.ime_exit_requested = None;
+        if was_insert {
+            self.set_ime_for_insert_mode(false);
+        }
 
         // Clear all pending states (Escape cancels everything)
         self.clear_last_key();
         self.pending_char_op = None;
+        self.pending_dead_key = None;
         self.pending_mark_op = None;
         self.pending_macro_op = None;
         self.selected_register = None;
         self.count_buffer.clear();
+        self.dispatched_count.clear();
 
         // Clear any visual selection
         self.clear_visual_selection();
@@ -707,6 +847,81 @@ impl GodotNeovimPlugin {
         crate::verbose_print!("[godot-neovim] Escaped to normal mode, buffer synced");
     }
 
+    /// Handle the responses to asynchronous requests (cursor syncs, BufEnter
+    /// cursor queries, large-file chunks, status pings, mark snapshots, search
+    /// queries) received since the last frame
+    pub(super) fn process_rpc_responses(&mut self) {
+        use crate::neovim::RpcRequest;
+
+        let responses: Vec<_> = [self.script_neovim.as_ref(), self.shader_neovim.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(|neovim| neovim.try_lock().ok())
+            .flat_map(|client| client.take_responses())
+            .collect();
+
+        for response in responses {
+            match (response.request, response.result) {
+                (RpcRequest::SetCursor, Err(e)) => {
+                    // "Timeout" = RPC timeout, "Failed to" = Neovim process died
+                    if (e.contains("Timeout") || e.contains("timeout") || e.contains("Failed to"))
+                        && self.record_timeout_error()
+                    {
+                        self.show_recovery_dialog();
+                    }
+                    godot_error!("[godot-neovim] Failed to sync cursor: {}", e);
+                }
+                (RpcRequest::SetCursor, Ok(_)) => {}
+                (RpcRequest::BufEnterCursor(path), result) => {
+                    let cursor = match result {
+                        Ok(value) => Self::parse_char_position(&value),
+                        Err(e) => {
+                            crate::verbose_print!(
+                                "[godot-neovim] BufEnter: Failed to get cursor: {}",
+                                e
+                            );
+                            None
+                        }
+                    };
+                    self.switch_godot_script_tab(&path, cursor);
+                }
+                (RpcRequest::BufferChunk { path, last }, result) => {
+                    self.on_buffer_chunk(&path, last, result);
+                }
+                (RpcRequest::StatusPing, result) => {
+                    self.on_status_ping(result.map(|_| ()));
+                }
+                (RpcRequest::MarksSnapshot(path), result) => {
+                    self.on_marks_snapshot(path, result);
+                }
+                (RpcRequest::SearchMatches(pattern), result) => {
+                    self.on_search_matches(&pattern, result);
+                }
+                (RpcRequest::SearchState(path), result) => {
+                    self.on_search_state(&path, result);
+                }
+                (
+                    RpcRequest::SearchLines {
+                        path,
+                        pattern,
+                        generation,
+                        ranges,
+                    },
+                    result,
+                ) => {
+                    self.on_search_lines(&path, &pattern, generation, ranges, result);
+                }
+                (RpcRequest::SearchCursor, result) => {
+                    self.on_search_cursor(result);
+                }
+                (RpcRequest::SetTopline, Err(e)) => {
+                    crate::verbose_print!("[godot-neovim] Failed to move Neovim's topline: {}", e);
+                }
+                (RpcRequest::SetTopline, Ok(_)) => {}
+            }
+        }
+    }
+
     /// Process pending updates from Neovim redraw events
     pub(super) fn process_neovim_updates(&mut self) {
         use crate::neovim::BufEvent;
@@ -725,7 +940,7 @@ impl GodotNeovimPlugin {
         }
 
         // Collect data from Neovim while holding lock, then release and process
-        let (state_from_redraw, buf_events, viewport_change, debug_messages) = {
+        let (state_from_redraw, buf_events, viewport_change, debug_messages, messages) = {
             let Some(neovim) = self.get_current_neovim() else {
                 return;
             };
@@ -734,9 +949,7 @@ impl GodotNeovimPlugin {
                 return;
             };
 
-            // Poll the runtime to process async events (including redraw)
-            client.poll();
-
+            // Events are received by the runtime's worker threads; collect them
             // Collect buffer events
             let buf_events: Vec<BufEvent> = if client.has_buf_events() {
                 let events_arc = client.get_buf_events();
@@ -751,8 +964,9 @@ impl GodotNeovimPlugin {
                 Vec::new()
             };
 
-            // Get state from redraw events (mode_change, grid_cursor_goto)
-            // This is non-blocking and doesn't make RPC calls
+            // Get the mode from redraw events (mode_change)
+            // Only the final state of the redraw batches received since the last
+            // frame is seen (see RedrawBatch); this doesn't make RPC calls
             let state_from_redraw = client.take_state();
             if let Some((ref mode, cursor)) = state_from_redraw {
                 crate::verbose_print!(
@@ -763,26 +977,39 @@ impl GodotNeovimPlugin {
                 );
             }
 
-            // Get viewport changes (win_viewport events)
-            let viewport_change = client.take_viewport();
+            // Get the viewport if Neovim sent a new one (win_viewport events)
+            let viewport_change = client.viewport_since(self.viewport_seq);
 
             // Get debug messages from Lua
             let debug_messages = client.take_debug_messages();
 
+            // Get messages shown by Neovim (ext_messages)
+            let messages = client.take_messages();
+
             (
                 state_from_redraw,
                 buf_events,
                 viewport_change,
                 debug_messages,
+                messages,
             )
         };
         // Lock is now released
+        if let Some(ref viewport) = viewport_change {
+            self.viewport_seq = viewport.seq;
+        }
 
         // Print debug messages to Godot Output panel
         for msg in debug_messages {
             godot_print!("[godot-neovim] {}", msg);
         }
 
+        // Show Neovim's messages in the status bar
+        let (messages_cleared, messages) = messages;
+        if messages_cleared || !messages.is_empty() {
+            self.show_neovim_messages(messages_cleared, messages);
+        }
+
         // Check for response from Neovim (any state/viewport update counts as response)
         let got_response =
             state_from_redraw.is_some() || viewport_change.is_some() || !buf_events.is_empty();
@@ -818,11 +1045,25 @@ impl GodotNeovimPlugin {
         }
 
         // Process buffer events
+        let mut buffer_changed = false;
         for event in buf_events {
             match event {
                 BufEvent::Lines(buf_lines_event) => {
+                    self.on_search_index_lines(
+                        buf_lines_event.first_line,
+                        buf_lines_event.last_line,
+                        buf_lines_event.line_data.len(),
+                    );
                     if let Some(change) = self.sync_manager.on_nvim_buf_lines(buf_lines_event) {
+                        // Changes received together (e.g. :g/pat/d deleting many lines)
+                        // become a single Godot undo step
+                        if !buffer_changed {
+                            if let Some(ref mut editor) = self.current_editor {
+                                editor.begin_complex_operation();
+                            }
+                        }
                         self.apply_nvim_change(&change);
+                        buffer_changed = true;
                     }
                 }
                 BufEvent::ChangedTick { tick, .. } => {
@@ -860,7 +1101,7 @@ impl GodotNeovimPlugin {
                     } else if bang {
                         self.cmd_close_discard();
                     } else {
-                        self.cmd_close();
+                        self.cmd_quit();
                     }
                 }
                 BufEvent::SaveAndClose => {
@@ -872,9 +1113,31 @@ impl GodotNeovimPlugin {
                     self.cmd_save_all();
                     self.cmd_close_all();
                 }
+                BufEvent::Yank(yank) => {
+                    self.on_register_yanked(&yank);
+                }
+                BufEvent::ClipboardSet { register, text } => {
+                    self.on_clipboard_set(&register, &text);
+                }
+                BufEvent::Autocmd(event) => {
+                    self.on_autocmd(&event);
+                }
             }
         }
 
+        if buffer_changed {
+            if let Some(ref mut editor) = self.current_editor {
+                editor.end_complex_operation();
+            }
+        }
+        // A bulk edit stays one undo step until all of its changes have arrived
+        self.finish_edit_transaction();
+
+        // Match positions shift with edits (including undo/redo from Neovim)
+        if buffer_changed && self.hlsearch_active {
+            self.refresh_hlsearch();
+        }
+
         // Track visual mode state for use in both redraw and viewport_change processing
         // Initialize from current mode - this handles cases where H/M/L are pressed in visual mode
         // without triggering a mode_change event (is_visual would otherwise stay false)
@@ -886,16 +1149,25 @@ impl GodotNeovimPlugin {
         // Track insert mode state for viewport_change processing
         // Used to skip cursor sync while in insert mode (Godot controls cursor)
         let mut is_insert = self.is_insert_mode() || self.is_replace_mode();
+        // Replace mode is driven by Neovim, so its cursor is synced like Normal mode
+        let mut is_replace = self.is_replace_mode();
         let mut entering_insert = false;
 
         // Process state update from redraw events
-        if let Some((ref mode, cursor)) = state_from_redraw {
+        if let Some((ref mode, _)) = state_from_redraw {
             let old_mode = self.current_mode.clone();
             self.current_mode = mode.clone();
 
+            // No operator waits in the other modes (in Normal and operator-pending
+            // mode the keys sent since may not be reflected yet)
+            if !matches!(mode.as_str(), "n" | "normal") && !Self::is_operator_pending_mode(mode) {
+                self.operator_pending = false;
+            }
+
             // Check if entering/leaving insert/replace mode
             // Update outer variables for use in viewport_change processing
             is_insert = mode == "i" || mode == "insert" || mode == "R" || mode == "replace";
+            is_replace = mode == "R" || mode == "replace";
             let was_insert =
                 old_mode == "i" || old_mode == "insert" || old_mode == "R" || old_mode == "replace";
             entering_insert = is_insert && !was_insert;
@@ -907,45 +1179,11 @@ impl GodotNeovimPlugin {
             // Use visual_mode_type since Neovim returns "visual" for all visual modes
             visual_line_mode = self.visual_mode_type == 'V';
             let entering_visual = is_visual && !was_visual;
-            let leaving_visual = was_visual && !is_visual;
-
-            // Check if in operator-pending mode (d, c, y, etc. waiting for motion)
-            // In operator-pending mode, grid_cursor_goto returns screen-relative position
-            let was_operator_pending =
-                Self::is_operator_pending_mode(&old_mode) && !Self::is_operator_pending_mode(mode);
-            let is_operator_pending = Self::is_operator_pending_mode(mode);
-
-            // Only sync cursor from grid_cursor_goto if no viewport_change
-            // When ext_multigrid is enabled, grid_cursor_goto gives screen position,
-            // while win_viewport gives accurate buffer position
-            // IMPORTANT: Skip cursor sync during mode transitions (insert/visual) without viewport_change
-            // because grid_cursor_goto gives screen-relative position which is wrong
-            // Also skip after buffer switch until we receive viewport change
-            // Also skip in operator-pending mode (d, c, y waiting for motion)
-            // Also skip when leaving operator-pending mode (e.g., after yL completes)
-            // Also skip when user_cursor_sync is set (mouse click in progress)
-            let skip_grid_cursor = entering_insert
-                || leaving_insert
-                || entering_visual
-                || leaving_visual
-                || is_operator_pending
-                || was_operator_pending
-                || self.skip_grid_cursor_after_switch
-                || self.user_cursor_sync;
-            if viewport_change.is_none() && !skip_grid_cursor {
-                self.current_cursor = cursor;
-
-                // Update mode display
-                let display_cursor = (cursor.0 + 1, cursor.1);
-                self.update_mode_display_with_cursor(mode, Some(display_cursor));
 
-                // Sync cursor to Godot editor
-                self.sync_cursor_from_grid(cursor);
-            }
-
-            // Update mode display during mode transitions using current_cursor
-            // (grid_cursor_goto is wrong during transitions, use last known buffer position)
-            if skip_grid_cursor && viewport_change.is_none() {
+            // The cursor is only taken from win_viewport (grid_cursor_goto gives a
+            // screen-relative position with ext_multigrid). Without a viewport in this
+            // frame the cursor did not move: show the mode with the last known position
+            if viewport_change.is_none() {
                 let display_cursor = (self.current_cursor.0 + 1, self.current_cursor.1);
                 self.update_mode_display_with_cursor(mode, Some(display_cursor));
 
@@ -956,6 +1194,11 @@ impl GodotNeovimPlugin {
                 }
             }
 
+            // IME Auto Off follows Insert mode (also when Neovim changes the mode)
+            if entering_insert || leaving_insert {
+                self.set_ime_for_insert_mode(entering_insert);
+            }
+
             // Clear pending key state when entering Insert/Replace/Visual mode
             // This prevents 'v' from being treated as a pending operator
             if entering_insert || entering_visual {
@@ -991,12 +1234,10 @@ impl GodotNeovimPlugin {
 
         // Apply viewport changes from Neovim (zz, zt, zb, Ctrl+F, Ctrl+B, etc.)
         // win_viewport provides both viewport position and cursor position in buffer coordinates
-        if let Some((topline, botline, curline, curcol)) = viewport_change {
-            // Clear skip_grid_cursor_after_switch flag - we now have valid viewport data
-            self.skip_grid_cursor_after_switch = false;
+        if let Some(viewport) = viewport_change {
+            let (topline, curline, curcol) = (viewport.topline, viewport.curline, viewport.curcol);
 
-            // Use curline/curcol from win_viewport for cursor sync
-            // This is more accurate than grid_cursor_goto which gives screen position
+            // win_viewport is the source of the cursor position (in buffer coordinates)
             let cursor = (curline, curcol);
             self.current_cursor = cursor;
 
@@ -1014,7 +1255,11 @@ impl GodotNeovimPlugin {
                 if let Some((ref mode, _)) = state_from_redraw {
                     self.update_mode_display_with_cursor(mode, Some(display_cursor));
                 }
-            } else if is_insert && !entering_insert && !self.pending_insert_cursor_sync {
+            } else if is_insert
+                && !is_replace
+                && !entering_insert
+                && !self.pending_insert_cursor_sync
+            {
                 // Skip cursor sync while in insert mode (after initial entry)
                 // Godot controls cursor during insert mode, syncing would override user's position
                 // and cause typed characters to appear in reverse order
@@ -1027,7 +1272,8 @@ impl GodotNeovimPlugin {
                 );
 
                 // Still apply viewport for scroll position
-                self.apply_viewport_from_neovim(topline);
+                let scroll_before = self.editor_v_scroll();
+                self.apply_viewport_from_neovim(topline, scroll_before);
 
                 // Update mode display
                 let display_cursor = (curline + 1, curcol);
@@ -1037,11 +1283,14 @@ impl GodotNeovimPlugin {
             } else {
                 // Clear pending_insert_cursor_sync since we're about to sync the cursor
                 self.pending_insert_cursor_sync = false;
+                // Where the view was, for a smooth scroll from there
+                let scroll_before = self.editor_v_scroll();
+
                 // Set cursor FIRST - this may trigger Godot's auto-scroll
                 self.sync_cursor_from_grid(cursor);
 
                 // Then set viewport - this OVERRIDES any auto-scroll from cursor setting
-                self.apply_viewport_from_neovim(topline);
+                self.apply_viewport_from_neovim(topline, scroll_before);
 
                 // Update mode display with buffer position
                 let display_cursor = (curline + 1, curcol);
@@ -1065,79 +1314,27 @@ impl GodotNeovimPlugin {
                 }
 
                 crate::verbose_print!(
-                    "[godot-neovim] win_viewport cursor: ({}, {})",
+                    "[godot-neovim] win_viewport #{} cursor: ({}, {})",
+                    viewport.seq,
                     curline,
                     curcol
                 );
-
-                // Debug: Compare win_viewport cursor with nvim_win_get_cursor
-                // This helps identify if the issue is with win_viewport or Neovim itself
-                if let Some(neovim) = self.get_current_neovim() {
-                    if let Ok(client) = neovim.try_lock() {
-                        if let Ok((api_line, api_col)) = client.get_cursor() {
-                            // api_line is 1-indexed, curline is 0-indexed
-                            if api_line - 1 != curline || api_col != curcol {
-                                crate::verbose_print!(
-                                    "[godot-neovim] CURSOR MISMATCH: win_viewport=({}, {}), nvim_win_get_cursor=({}, {})",
-                                    curline,
-                                    curcol,
-                                    api_line - 1,
-                                    api_col
-                                );
-                            }
-                        }
-                    }
-                }
-
-                // Ctrl+B cursor correction for ext_multigrid
-                // When at end of file, Neovim reports wrong viewport height (e.g., 2 lines instead of 33),
-                // causing cursor to barely move. Correct by moving cursor to top of viewport.
-                if self.pending_page_up_correction {
-                    self.pending_page_up_correction = false;
-
-                    let viewport_height = botline - topline;
-                    // Check if cursor is near bottom of viewport (within last 3 lines)
-                    if curline > topline + viewport_height - 3 {
-                        // Move cursor to near top of viewport
-                        // At file beginning (topline=0): move to line 0
-                        // Otherwise: move to 2 lines from top
-                        let corrected_line = if topline == 0 { 0 } else { topline + 2 };
-                        crate::verbose_print!(
-                            "[godot-neovim] Ctrl+B cursor correction: {} -> {} (topline={}, botline={})",
-                            curline,
-                            corrected_line,
-                            topline,
-                            botline
-                        );
-
-                        // Update internal cursor state
-                        self.current_cursor = (corrected_line, curcol);
-
-                        // Sync corrected cursor to Godot editor
-                        self.sync_cursor_from_grid((corrected_line, curcol));
-
-                        // Sync corrected cursor to Neovim
-                        if let Some(neovim) = self.get_current_neovim() {
-                            if let Ok(client) = neovim.try_lock() {
-                                // set_cursor expects 1-indexed line
-                                let _ = client.set_cursor(corrected_line + 1, curcol);
-                            }
-                        }
-
-                        // Update mode display with corrected position
-                        let display_cursor = (corrected_line + 1, curcol);
-                        if let Some((ref mode, _)) = state_from_redraw {
-                            self.update_mode_display_with_cursor(mode, Some(display_cursor));
-                        }
-                    }
-                }
             }
         }
     }
 
+    /// Scroll position of the current editor (0.0 without one)
+    fn editor_v_scroll(&self) -> f64 {
+        self.current_editor
+            .as_ref()
+            .map(|editor| editor.get_v_scroll())
+            .unwrap_or_default()
+    }
+
     /// Apply viewport (scroll position) from Neovim to Godot editor
-    /// topline is the first visible line (0-indexed)
-    fn apply_viewport_from_neovim(&mut self, topline: i64) {
+    /// topline is the first visible line (0-indexed); scroll_before is the scroll
+    /// position before the cursor sync, where a smooth scroll starts
+    fn apply_viewport_from_neovim(&mut self, topline: i64, scroll_before: f64) {
         // Skip if mouse selection is being synced (preserve user's viewport)
         if self.mouse_selection_syncing {
             crate::verbose_print!(
@@ -1147,7 +1344,7 @@ impl GodotNeovimPlugin {
             return;
         }
 
-        let Some(ref mut editor) = self.current_editor else {
+        let Some(ref editor) = self.current_editor else {
             return;
         };
 
@@ -1164,6 +1361,26 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Neovim scrolls past the end of the file (Ctrl+F on the last page, zz or zt
+        // on the last lines) showing filler lines, which the editor cannot show.
+        // Keep Neovim's top line where the editor can be, so the next Ctrl+B/Ctrl+F
+        // pages from the view on screen rather than from one the user never saw.
+        let mut topline = topline;
+        if !editor.is_scroll_past_end_of_file_enabled() {
+            let last_page = last_page_topline(line_count, editor.get_visible_line_count(), |line| {
+                editor.get_line_wrap_count(line) + 1
+            }) as i64;
+            if topline > last_page {
+                crate::verbose_print!(
+                    "[godot-neovim] Neovim topline {} is past the last page, moving it to {}",
+                    topline,
+                    last_page
+                );
+                self.set_neovim_topline(last_page);
+                topline = last_page;
+            }
+        }
+
         crate::verbose_print!(
             "[godot-neovim] Applying viewport from Neovim: topline={}",
             topline
@@ -1171,7 +1388,27 @@ impl GodotNeovimPlugin {
 
         // Use set_line_as_first_visible for direct control of which line is at the top
         // This is more reliable than set_v_scroll which uses pixel values
-        editor.set_line_as_first_visible(topline as i32);
+        // (animated for jumps of more than a screen with Smooth Scroll)
+        self.scroll_to_topline(topline as i32, scroll_before);
+    }
+
+    /// Make `topline` (0-indexed) Neovim's first visible line, after the keys sent
+    /// so far
+    fn set_neovim_topline(&self, topline: i64) {
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            crate::verbose_print!("[godot-neovim] Mutex busy, Neovim topline not moved");
+            return;
+        };
+        if !client.request_lua(
+            crate::neovim::RpcRequest::SetTopline,
+            "vim.fn.winrestview({ topline = ... })",
+            vec![rmpv::Value::from(topline + 1)],
+        ) {
+            crate::verbose_print!("[godot-neovim] Failed to queue the Neovim topline");
+        }
     }
 
     /// Apply a change from Neovim to Godot editor
@@ -1196,98 +1433,36 @@ impl GodotNeovimPlugin {
         self.sync_manager.begin_nvim_change();
 
         // Prevent caret_changed from syncing Godot's cursor back to Neovim while
-        // we are modifying the buffer. Buffer edits (remove_line_at, insert_line_at,
-        // set_text) cause Godot to reposition the caret automatically, and without
+        // we are modifying the buffer. Buffer edits (set_line, insert_text,
+        // remove_text) cause Godot to reposition the caret automatically, and without
         // this guard that stale position would be sent to Neovim, overriding the
         // correct post-change cursor that Neovim sends separately (e.g. cc → col 0).
         self.syncing_from_grid = true;
 
-        let line_count = editor.get_line_count() as i64;
-        let first = change.first_line.max(0) as i32;
+        // Only the lines that differ are edited: unchanged lines keep their folds,
+        // bookmarks and breakpoints, and the scroll position is not reset
+        let line_count = editor.get_line_count();
+        let first = change.first_line.clamp(0, line_count as i64) as i32;
         let last = if change.last_line < 0 {
-            line_count as i32
+            line_count
         } else {
-            change.last_line as i32
+            (change.last_line as i32).clamp(first, line_count)
         };
-
-        // For full buffer replacement (first=0 and replacing ALL lines),
-        // use set_text directly to avoid line count drift issues
-        // Note: Only treat as full replacement when replacing the entire buffer
-        let is_full_replacement = first == 0 && last as i64 >= line_count;
-
-        if is_full_replacement && !change.new_lines.is_empty() {
-            // Full buffer replacement: use set_text for reliability
-            let new_text = change.new_lines.join("\n");
-            editor.set_text(&new_text);
-            // Record Godot's caret after the edit so the deferred caret_changed matches.
-            // See comment before end_nvim_change() below for details.
-            let after_line = editor.get_caret_line() as i64;
-            let after_col = editor.get_caret_column() as i64;
-            self.last_synced_cursor = (after_line, after_col);
-            self.sync_manager.end_nvim_change();
-            self.syncing_from_grid = false;
-            return;
-        }
-
-        // Handle different change types
-        if change.new_lines.is_empty() {
-            // Deletion: remove lines from first to last
-            let safe_last = last.min(editor.get_line_count());
-            for line in (first..safe_last).rev() {
-                if line < editor.get_line_count() {
-                    editor.remove_line_at(line);
-                }
-            }
-        } else if first == last {
-            // Insertion: insert new lines at first position
-            let current_line_count = editor.get_line_count();
-            if first >= current_line_count {
-                // Appending at end of buffer: use set_text since insert_line_at is out of bounds
-                let text = editor.get_text().to_string();
-                let new_lines_text = change.new_lines.join("\n");
-                let new_text = if text.ends_with('\n') {
-                    format!("{}{}", text, new_lines_text)
-                } else if text.is_empty() {
-                    new_lines_text
-                } else {
-                    format!("{}\n{}", text, new_lines_text)
-                };
-                editor.set_text(&new_text);
-            } else {
-                // Insert within buffer
-                for (i, line_text) in change.new_lines.iter().enumerate() {
-                    let insert_at = first + i as i32;
-                    editor.insert_line_at(insert_at, line_text);
-                }
-            }
+        let old_lines: Vec<String> = (first..last)
+            .map(|line| editor.get_line(line).to_string())
+            .collect();
+        let edits = if first as i64 == change.first_line {
+            change.line_edits(&old_lines)
         } else {
-            // Partial replacement: delete old lines, insert new lines
-            let safe_last = last.min(editor.get_line_count());
-            // First, delete old lines (in reverse to maintain indices)
-            for line in (first..safe_last).rev() {
-                if line < editor.get_line_count() {
-                    editor.remove_line_at(line);
-                }
-            }
-            // Then, insert new lines
-            for (i, line_text) in change.new_lines.iter().enumerate() {
-                let insert_at = first + i as i32;
-                if insert_at >= editor.get_line_count() {
-                    // Need to append remaining lines
-                    let text = editor.get_text().to_string();
-                    let remaining_lines: Vec<&str> =
-                        change.new_lines[i..].iter().map(|s| s.as_str()).collect();
-                    let new_lines_text = remaining_lines.join("\n");
-                    let new_text = if text.ends_with('\n') || text.is_empty() {
-                        format!("{}{}", text, new_lines_text)
-                    } else {
-                        format!("{}\n{}", text, new_lines_text)
-                    };
-                    editor.set_text(&new_text);
-                    break;
-                }
-                editor.insert_line_at(insert_at, line_text);
+            crate::sync::DocumentChange {
+                first_line: first as i64,
+                ..change.clone()
             }
+            .line_edits(&old_lines)
+        };
+
+        for edit in edits {
+            Self::apply_line_edit(editor, edit);
         }
 
         // Record Godot's caret position right after the buffer edit so that the deferred
@@ -1303,6 +1478,42 @@ impl GodotNeovimPlugin {
         self.syncing_from_grid = false;
     }
 
+    /// Apply one line edit to the Godot editor as a single text operation
+    fn apply_line_edit(editor: &mut Gd<CodeEdit>, edit: crate::sync::LineEdit) {
+        use crate::sync::LineEdit;
+
+        let line_len = |editor: &Gd<CodeEdit>, line: i32| editor.get_line(line).len() as i32;
+        let line_count = editor.get_line_count();
+        match edit {
+            LineEdit::Set { line, text } => editor.set_line(line as i32, &text),
+            LineEdit::Insert { line, lines } => {
+                let text = lines.join("\n");
+                if (line as i32) < line_count {
+                    editor.insert_text(&format!("{}\n", text), line as i32, 0);
+                } else {
+                    // Append after the last line
+                    let last = line_count - 1;
+                    let column = line_len(editor, last);
+                    editor.insert_text(&format!("\n{}", text), last, column);
+                }
+            }
+            LineEdit::Remove { line, count } => {
+                let (line, end) = (line as i32, (line + count) as i32);
+                if end < line_count {
+                    editor.remove_text(line, 0, end, 0);
+                } else if line > 0 {
+                    // Trailing lines: remove them with the line break before them
+                    let last = line_count - 1;
+                    let (from_column, to_column) =
+                        (line_len(editor, line - 1), line_len(editor, last));
+                    editor.remove_text(line - 1, from_column, last, to_column);
+                } else {
+                    editor.set_text("");
+                }
+            }
+        }
+    }
+
     /// Convert byte column to character column for a given line
     /// Neovim uses byte positions, Godot uses character positions
     /// For multi-byte characters (e.g., Japanese), this conversion is essential
@@ -1328,6 +1539,10 @@ impl GodotNeovimPlugin {
 
     /// Sync cursor from Neovim grid position to Godot editor
     pub(super) fn sync_cursor_from_grid(&mut self, cursor: (i64, i64)) {
+        // Godot owns the carets while multicursor selections are active
+        if self.multicursor {
+            return;
+        }
         let Some(ref mut editor) = self.current_editor else {
             return;
         };
@@ -1383,3 +1598,21 @@ impl GodotNeovimPlugin {
         self.update_mode_display_with_cursor(&self.current_mode.clone(), Some(display_cursor));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_page_topline() {
+        // 100 lines on a 30-row screen: lines 70..99 are the last page
+        assert_eq!(last_page_topline(100, 30, |_| 1), 70);
+        // A short file fits on the first page
+        assert_eq!(last_page_topline(20, 30, |_| 1), 0);
+        // The last line wraps onto 3 rows
+        assert_eq!(
+            last_page_topline(100, 30, |line| if line == 99 { 3 } else { 1 }),
+            72
+        );
+    }
+}
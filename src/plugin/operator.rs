@@ -0,0 +1,152 @@
+//! Operator-pending passthrough for motions implemented locally
+//!
+//! Display-line motions (gj, gk, g0, g^, g$) and sub-word motions move the Godot
+//! caret and sync it to Neovim. While an operator waits for its motion (Neovim is
+//! in `no` mode, e.g. after `d`), syncing the cursor would leave the operator
+//! pending. Instead the target is computed locally and handed to Neovim as the
+//! motion: `d<Cmd>call cursor(l, c)<CR>` applies the operator up to it, forced
+//! inclusive (`v`) or linewise (`V`) like the Vim motion.
+//!
+//! Motions Neovim runs itself and only previews locally (f/t, `;`/`,`, `%`, ge)
+//! skip the preview while an operator is pending.
+//!
+//! Whether an operator is pending is tracked from the keys sent to Neovim rather
+//! than asked for: the mode_change of an operator key typed just before the motion
+//! may not have arrived yet, and a round trip per motion would stall the editor.
+
+use super::GodotNeovimPlugin;
+
+/// How an operator applies to the text up to a locally computed motion target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MotionKind {
+    Exclusive,
+    Inclusive,
+    Linewise,
+}
+
+impl MotionKind {
+    /// Key forcing the motion type in operator-pending mode (a `<Cmd>` motion is
+    /// charwise exclusive)
+    fn force_key(self) -> &'static str {
+        match self {
+            MotionKind::Exclusive => "",
+            MotionKind::Inclusive => "v",
+            MotionKind::Linewise => "V",
+        }
+    }
+}
+
+/// Operators that wait for a motion
+const OPERATORS: &[&str] = &[
+    "d", "c", "y", "<", ">", "=", "!", "g~", "gu", "gU", "g?", "gq", "gw", "g@", "zf",
+];
+
+/// Whether `keys` (with an optional register and count) is an operator
+fn is_operator_keys(keys: &str) -> bool {
+    let keys = match keys.strip_prefix('"') {
+        Some(rest) => rest.get(1..).unwrap_or_default(),
+        None => keys,
+    };
+    OPERATORS.contains(&keys.trim_start_matches(|c: char| c.is_ascii_digit()))
+}
+
+impl GodotNeovimPlugin {
+    /// Whether Neovim is waiting for the motion of an operator
+    pub(super) fn is_operator_pending(&self) -> bool {
+        self.operator_pending
+            && !self.is_in_visual_mode()
+            && !self.is_insert_mode()
+            && !self.is_replace_mode()
+    }
+
+    /// Track the pending operator after `keys` were sent to Neovim
+    /// The key after an operator completes it (motion, text object, doubled
+    /// operator) or cancels it; an operator key typed in Normal mode starts one.
+    pub(super) fn note_keys_sent(&mut self, keys: &str) {
+        self.operator_pending = !self.operator_pending
+            && !self.is_in_visual_mode()
+            && !self.is_insert_mode()
+            && !self.is_replace_mode()
+            && is_operator_keys(keys);
+    }
+
+    /// Whether a motion Neovim runs itself may be previewed by moving the caret
+    /// ahead of Neovim (not while it is the motion of an operator)
+    pub(super) fn should_preview_motion(&self) -> bool {
+        !self.is_operator_pending()
+    }
+
+    /// Run a locally implemented motion: a cursor move, or the motion of the
+    /// pending operator
+    pub(super) fn run_local_motion(&mut self, kind: MotionKind, motion: impl FnOnce(&mut Self)) {
+        if !self.is_operator_pending() {
+            motion(self);
+            return;
+        }
+
+        let Some(start) = self
+            .current_editor
+            .as_ref()
+            .map(|editor| (editor.get_caret_line(), editor.get_caret_column()))
+        else {
+            return;
+        };
+        let saved_cursor = (self.current_cursor, self.last_synced_cursor);
+
+        // Find the target with the motion itself, without syncing the caret
+        self.resolving_operator_motion = true;
+        motion(self);
+        let target = self.current_editor.as_mut().map(|editor| {
+            let target = (editor.get_caret_line(), editor.get_caret_column());
+            let line_text = editor.get_line(target.0).to_string();
+            editor.set_caret_line(start.0);
+            editor.set_caret_column(start.1);
+            (target, line_text)
+        });
+        self.resolving_operator_motion = false;
+        (self.current_cursor, self.last_synced_cursor) = saved_cursor;
+
+        let Some(((line, col), line_text)) = target else {
+            return;
+        };
+
+        // An empty motion cancels the operator (like a failed motion in Vim)
+        if kind != MotionKind::Linewise && (line, col) == start {
+            crate::verbose_print!("[godot-neovim] Operator motion did not move: cancelled");
+            self.send_keys("<Esc>");
+            return;
+        }
+
+        let byte_col = Self::char_col_to_byte_col(&line_text, col);
+        let keys = format!(
+            "{}<Cmd>call cursor({}, {})<CR>",
+            kind.force_key(),
+            line + 1,
+            byte_col + 1
+        );
+        crate::verbose_print!(
+            "[godot-neovim] Operator motion ({:?}) to {}:{}",
+            kind,
+            line + 1,
+            col
+        );
+        self.send_keys(&keys);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_operator_keys() {
+        assert!(is_operator_keys("d"));
+        assert!(is_operator_keys("2gU"));
+        assert!(is_operator_keys("\"ay"));
+        assert!(is_operator_keys("\"a3c"));
+        assert!(!is_operator_keys("dd"));
+        assert!(!is_operator_keys("w"));
+        assert!(!is_operator_keys("<Esc>"));
+        assert!(!is_operator_keys("\""));
+    }
+}
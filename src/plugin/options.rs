@@ -0,0 +1,683 @@
+//! Options: `:setlocal` window-local options per script and `:set` for Neovim options
+//!
+//! `:setlocal wrap`, `number` and `list` are bridged to the corresponding CodeEdit
+//! property (line wrapping mode, line number gutter, whitespace drawing), remembered
+//! per script path and re-applied when switching tabs. The table is stored in the
+//! project metadata (`.godot/editor/project_metadata.cfg`) so it survives
+//! editor restarts. Other `:setlocal` options are forwarded to Neovim unchanged.
+//!
+//! `:set {option}?` queries any option of the embedded Neovim. Only a safe subset
+//! can be changed with `:set`; the chosen values are persisted in the Editor
+//! Settings and applied again whenever Neovim starts. `:set relativenumber` is
+//! handled by Godot (see relative_number.rs) and stored in its own setting.
+//! `:set wrap`, `number` and `list` act like `:setlocal` on the current script, and
+//! `:set expandtab`/`shiftwidth` change the indentation of the current buffer in
+//! both Neovim and the CodeEdit (Godot's indent settings stay the default).
+
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::text_edit::LineWrappingMode;
+use godot::classes::EditorInterface;
+use godot::prelude::*;
+
+/// Options that can be changed with `:set`: (name, short name, is boolean)
+const SETTABLE_OPTIONS: &[(&str, &str, bool)] = &[
+    ("ignorecase", "ic", true),
+    ("smartcase", "scs", true),
+    ("hlsearch", "hls", true),
+    ("scrolloff", "so", false),
+    ("timeoutlen", "tm", false),
+    ("clipboard", "cb", false),
+];
+
+/// Buffer options of the indentation, also applied to the CodeEdit (not persisted)
+const INDENT_OPTIONS: &[(&str, &str, bool)] =
+    &[("expandtab", "et", true), ("shiftwidth", "sw", false)];
+
+/// Names of 'relativenumber' (drawn by the plugin, not a Neovim option)
+const RELATIVE_NUMBER_NAMES: &[&str] = &["relativenumber", "rnu"];
+
+/// Project metadata section/key for the persisted option table
+const METADATA_SECTION: &str = "godot_neovim";
+const METADATA_KEY: &str = "local_options";
+
+/// A window-local option bridged to a CodeEdit property
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LocalOption {
+    /// 'wrap' - line wrapping at word boundaries
+    Wrap,
+    /// 'number' / 'nu' - line number gutter
+    Number,
+    /// 'list' - draw tabs and spaces
+    List,
+}
+
+impl LocalOption {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "wrap" => Some(Self::Wrap),
+            "number" | "nu" => Some(Self::Number),
+            "list" => Some(Self::List),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Wrap => "wrap",
+            Self::Number => "number",
+            Self::List => "list",
+        }
+    }
+
+    /// EditorSettings key holding the editor-wide default
+    fn editor_setting(self) -> &'static str {
+        match self {
+            Self::Wrap => "text_editor/appearance/lines/word_wrap",
+            Self::Number => "text_editor/appearance/gutters/show_line_numbers",
+            Self::List => "text_editor/appearance/whitespace/draw_tabs",
+        }
+    }
+}
+
+/// What a `:setlocal` argument does to an option
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OptionAction {
+    /// `wrap` / `nowrap`
+    Set(bool),
+    /// `wrap!` / `invwrap`
+    Toggle,
+    /// `wrap?`
+    Query,
+    /// `wrap&` - back to the editor default
+    Reset,
+}
+
+/// Parse a single `:setlocal` argument. Returns None for options not handled locally.
+fn parse_option_arg(arg: &str) -> Option<(LocalOption, OptionAction)> {
+    let (name, suffix_action) = if let Some(name) = arg.strip_suffix('!') {
+        (name, Some(OptionAction::Toggle))
+    } else if let Some(name) = arg.strip_suffix('?') {
+        (name, Some(OptionAction::Query))
+    } else if let Some(name) = arg.strip_suffix('&') {
+        (name, Some(OptionAction::Reset))
+    } else {
+        (arg, None)
+    };
+
+    if let Some(option) = LocalOption::from_name(name) {
+        return Some((option, suffix_action.unwrap_or(OptionAction::Set(true))));
+    }
+    // Prefixed forms only take the plain name (`nowrap`, `invwrap`)
+    if suffix_action.is_some() {
+        return None;
+    }
+    if let Some(option) = name.strip_prefix("no").and_then(LocalOption::from_name) {
+        return Some((option, OptionAction::Set(false)));
+    }
+    if let Some(option) = name.strip_prefix("inv").and_then(LocalOption::from_name) {
+        return Some((option, OptionAction::Toggle));
+    }
+    None
+}
+
+/// Parse a `:set` argument for 'relativenumber' (None: another option)
+fn parse_relative_number_arg(arg: &str) -> Option<OptionAction> {
+    let is_name = |name: &str| RELATIVE_NUMBER_NAMES.contains(&name);
+    if let Some(name) = arg.strip_suffix('!') {
+        return is_name(name).then_some(OptionAction::Toggle);
+    }
+    if let Some(name) = arg.strip_suffix('?') {
+        return is_name(name).then_some(OptionAction::Query);
+    }
+    if let Some(name) = arg.strip_suffix('&') {
+        return is_name(name).then_some(OptionAction::Reset);
+    }
+    if is_name(arg) {
+        Some(OptionAction::Set(true))
+    } else if arg.strip_prefix("no").is_some_and(is_name) {
+        Some(OptionAction::Set(false))
+    } else if arg.strip_prefix("inv").is_some_and(is_name) {
+        Some(OptionAction::Toggle)
+    } else {
+        None
+    }
+}
+
+/// Window-local option values for one script (None = editor default)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(super) struct LocalOptions {
+    pub wrap: Option<bool>,
+    pub number: Option<bool>,
+    pub list: Option<bool>,
+}
+
+impl LocalOptions {
+    fn get(&self, option: LocalOption) -> Option<bool> {
+        match option {
+            LocalOption::Wrap => self.wrap,
+            LocalOption::Number => self.number,
+            LocalOption::List => self.list,
+        }
+    }
+
+    fn set(&mut self, option: LocalOption, value: Option<bool>) {
+        match option {
+            LocalOption::Wrap => self.wrap = value,
+            LocalOption::Number => self.number = value,
+            LocalOption::List => self.list = value,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn to_dictionary(self) -> VarDictionary {
+        let mut dict = VarDictionary::new();
+        for option in [LocalOption::Wrap, LocalOption::Number, LocalOption::List] {
+            if let Some(value) = self.get(option) {
+                dict.set(option.name(), value);
+            }
+        }
+        dict
+    }
+
+    fn from_dictionary(dict: &VarDictionary) -> Self {
+        let mut options = Self::default();
+        for option in [LocalOption::Wrap, LocalOption::Number, LocalOption::List] {
+            if let Some(value) = dict
+                .get(option.name())
+                .and_then(|v| v.try_to::<bool>().ok())
+            {
+                options.set(option, Some(value));
+            }
+        }
+        options
+    }
+}
+
+/// Full name and kind of an option of `table`
+fn find_option(
+    table: &[(&'static str, &'static str, bool)],
+    name: &str,
+) -> Option<(&'static str, bool)> {
+    table
+        .iter()
+        .find(|(full, short, _)| name == *full || name == *short)
+        .map(|&(full, _, is_bool)| (full, is_bool))
+}
+
+/// Full name and kind of a `:set`-able option
+fn settable_option(name: &str) -> Option<(&'static str, bool)> {
+    find_option(SETTABLE_OPTIONS, name).or_else(|| find_option(INDENT_OPTIONS, name))
+}
+
+/// Option name of a `:set` argument (`noic`, `ic!`, `so=5`, `cb+=unnamed`, `ic&`)
+fn set_arg_option_name(arg: &str) -> &str {
+    let name_end = arg
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(arg.len());
+    let name = &arg[..name_end];
+    if settable_option(name).is_some() {
+        return name;
+    }
+    name.strip_prefix("no")
+        .or_else(|| name.strip_prefix("inv"))
+        .filter(|stripped| settable_option(stripped).is_some())
+        .unwrap_or(name)
+}
+
+/// Format an option value like Vim (`ignorecase` / `noignorecase` / `scrolloff=5`)
+fn format_option_value(name: &str, value: &rmpv::Value) -> String {
+    match value {
+        rmpv::Value::Boolean(true) => name.to_string(),
+        rmpv::Value::Boolean(false) => format!("no{}", name),
+        rmpv::Value::String(s) => format!("{}={}", name, s.as_str().unwrap_or("")),
+        other => format!("{}={}", name, other),
+    }
+}
+
+/// Convert an option value for the Editor Settings
+fn option_value_to_variant(value: &rmpv::Value) -> Option<Variant> {
+    match value {
+        rmpv::Value::Boolean(b) => Some(b.to_variant()),
+        rmpv::Value::Integer(i) => i.as_i64().map(|i| i.to_variant()),
+        rmpv::Value::String(s) => s.as_str().map(|s| s.to_variant()),
+        _ => None,
+    }
+}
+
+/// Convert a persisted option value for Neovim
+fn variant_to_option_value(value: &Variant) -> Option<rmpv::Value> {
+    if let Ok(b) = value.try_to::<bool>() {
+        Some(rmpv::Value::from(b))
+    } else if let Ok(i) = value.try_to::<i64>() {
+        Some(rmpv::Value::from(i))
+    } else {
+        value
+            .try_to::<GString>()
+            .ok()
+            .map(|s| rmpv::Value::from(s.to_string()))
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// :set {args} - query any Neovim option, change and persist the safe subset
+    pub(super) fn cmd_set(&mut self, args: &str) {
+        let args = args.trim();
+        if args.is_empty() {
+            self.show_set_options();
+            return;
+        }
+
+        let mut output: Vec<String> = Vec::new();
+        for arg in args.split_whitespace() {
+            // 'relativenumber' is drawn by Godot
+            if let Some(action) = parse_relative_number_arg(arg) {
+                output.push(self.set_relative_number(action));
+                continue;
+            }
+
+            // 'wrap', 'number' and 'list' are CodeEdit properties of the current script
+            if let Some((option, action)) = parse_option_arg(arg) {
+                output.push(self.apply_local_option_arg(option, action));
+                continue;
+            }
+
+            // :set {option}? - any option can be queried
+            if let Some(name) = arg.strip_suffix('?') {
+                output.extend(self.query_neovim_option(name));
+                continue;
+            }
+
+            let name = set_arg_option_name(arg);
+            let Some((full_name, is_bool)) = settable_option(name) else {
+                godot_warn!(
+                    "[godot-neovim] :set {}: not supported (supported: {})",
+                    arg,
+                    SETTABLE_OPTIONS
+                        .iter()
+                        .chain(INDENT_OPTIONS)
+                        .map(|(full, _, _)| *full)
+                        .chain(["wrap", "number", "list", "relativenumber"])
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                continue;
+            };
+            // :set scrolloff shows the value of a non-boolean option, like Vim
+            if !is_bool && arg == name {
+                output.extend(self.query_neovim_option(name));
+                continue;
+            }
+
+            self.cmd_forward_to_neovim(&format!("set {}", arg));
+            let Some(value) = self.get_neovim_option(full_name) else {
+                continue;
+            };
+            if find_option(INDENT_OPTIONS, full_name).is_some() {
+                // Buffer-local: the CodeEdit follows, nothing is persisted
+                self.apply_neovim_indent();
+            } else if full_name == "timeoutlen" {
+                // Stored in the plugin's own Timeoutlen setting (key sequence timeout)
+                if let Some(timeout) = value.as_i64() {
+                    settings::set_timeoutlen(timeout);
+                }
+            } else if arg.ends_with('&') {
+                settings::set_neovim_option(full_name, None);
+            } else if let Some(variant) = option_value_to_variant(&value) {
+                settings::set_neovim_option(full_name, Some(&variant));
+            }
+            if full_name == "hlsearch" {
+                self.refresh_hlsearch();
+            }
+            output.push(format_option_value(full_name, &value));
+        }
+
+        if !output.is_empty() {
+            self.command_output = Some(output.join("  "));
+        }
+    }
+
+    /// :set relativenumber (also `norelativenumber`, `rnu!`, `rnu?`, `rnu&`)
+    /// Returns the resulting value formatted like Vim.
+    fn set_relative_number(&mut self, action: OptionAction) -> String {
+        let current = settings::get_relative_number();
+        let enabled = match action {
+            OptionAction::Set(value) => value,
+            OptionAction::Toggle => !current,
+            OptionAction::Query => current,
+            OptionAction::Reset => false,
+        };
+        if enabled != current {
+            settings::set_relative_number(enabled);
+            self.apply_relative_number();
+        }
+        format_option_value("relativenumber", &rmpv::Value::from(enabled))
+    }
+
+    /// Indent the current CodeEdit like the Neovim buffer ('expandtab', 'shiftwidth')
+    fn apply_neovim_indent(&mut self) {
+        let use_spaces = self
+            .get_neovim_option("expandtab")
+            .and_then(|value| value.as_bool());
+        // 'shiftwidth' 0 uses 'tabstop', like Vim
+        let indent_size = match self
+            .get_neovim_option("shiftwidth")
+            .and_then(|value| value.as_i64())
+        {
+            Some(0) => self
+                .get_neovim_option("tabstop")
+                .and_then(|value| value.as_i64()),
+            size => size,
+        };
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        if !editor.is_instance_valid() {
+            return;
+        }
+        if let Some(use_spaces) = use_spaces {
+            editor.set_indent_using_spaces(use_spaces);
+        }
+        if let Some(size) = indent_size.filter(|&size| size > 0) {
+            editor.set_indent_size(size as i32);
+        }
+        crate::verbose_print!(
+            "[godot-neovim] Indent: {} (size {})",
+            if editor.is_indent_using_spaces() {
+                "spaces"
+            } else {
+                "tabs"
+            },
+            editor.get_indent_size()
+        );
+    }
+
+    /// :set without arguments - list the options changed with :set
+    fn show_set_options(&mut self) {
+        let options = settings::get_neovim_options();
+        if options.is_empty() {
+            godot_print!("[godot-neovim] No options set with :set");
+            return;
+        }
+        godot_print!("[godot-neovim] --- Options ---");
+        for (name, _) in options.iter_shared() {
+            let name = name.to_string();
+            if let Some(value) = self.get_neovim_option(&name) {
+                godot_print!("  {}", format_option_value(&name, &value));
+            }
+        }
+    }
+
+    /// Query an option and print it (`:set {option}?`). Returns the formatted value.
+    fn query_neovim_option(&mut self, name: &str) -> Option<String> {
+        let value = self.get_neovim_option(name)?;
+        let text = format_option_value(name, &value);
+        godot_print!("[godot-neovim] {}", text);
+        Some(text)
+    }
+
+    /// Get the effective value of a Neovim option (current buffer and window)
+    fn get_neovim_option(&self, name: &str) -> Option<rmpv::Value> {
+        let neovim = self.get_current_neovim()?;
+        let Ok(client) = neovim.try_lock() else {
+            godot_warn!("[godot-neovim] :set {}?: Failed to lock Neovim", name);
+            return None;
+        };
+        match client.execute_lua_with_args(
+            "return vim.api.nvim_get_option_value(..., {})",
+            vec![rmpv::Value::from(name)],
+        ) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                godot_warn!("[godot-neovim] Failed to get option '{}': {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Apply the options persisted by :set to both Neovim instances (after start)
+    pub(super) fn apply_neovim_options(&mut self) {
+        let mut options = settings::get_neovim_options();
+        options.set("timeoutlen", settings::get_timeoutlen() as i64);
+        // CursorHold (idle hover) fires after 'updatetime'
+        options.set("updatetime", settings::get_idle_hover_delay() as i64);
+        for neovim in [&self.script_neovim, &self.shader_neovim]
+            .into_iter()
+            .flatten()
+        {
+            let Ok(client) = neovim.try_lock() else {
+                continue;
+            };
+            for (name, value) in options.iter_shared() {
+                let Some(value) = variant_to_option_value(&value) else {
+                    continue;
+                };
+                if let Err(e) = client.execute_lua_with_args(
+                    "vim.api.nvim_set_option_value(..., {})",
+                    vec![rmpv::Value::from(name.to_string()), value],
+                ) {
+                    godot_warn!("[godot-neovim] Failed to set option '{}': {}", name, e);
+                }
+            }
+        }
+    }
+
+    /// :setlocal {args} - window-local options handled by Godot, the rest goes to Neovim
+    pub(super) fn cmd_setlocal(&mut self, args: &str) {
+        let args = args.trim();
+        if args.is_empty() {
+            self.show_local_options();
+            return;
+        }
+
+        let mut forwarded: Vec<&str> = Vec::new();
+        for arg in args.split_whitespace() {
+            let Some((option, action)) = parse_option_arg(arg) else {
+                forwarded.push(arg);
+                continue;
+            };
+
+            let text = self.apply_local_option_arg(option, action);
+            if action == OptionAction::Query {
+                godot_print!("[godot-neovim] {}", text);
+            }
+        }
+
+        if !forwarded.is_empty() {
+            self.cmd_forward_to_neovim(&format!("setlocal {}", forwarded.join(" ")));
+        }
+    }
+
+    /// Apply a `:set`/`:setlocal` argument of a window-local option
+    /// Returns the resulting value formatted like Vim.
+    fn apply_local_option_arg(&mut self, option: LocalOption, action: OptionAction) -> String {
+        let current = self.current_option_value(option);
+        let value = match action {
+            OptionAction::Query => current,
+            OptionAction::Set(value) => {
+                self.set_local_option(option, Some(value));
+                value
+            }
+            OptionAction::Toggle => {
+                self.set_local_option(option, Some(!current));
+                !current
+            }
+            OptionAction::Reset => {
+                self.set_local_option(option, None);
+                Self::default_option_value(option)
+            }
+        };
+        format_option_value(option.name(), &rmpv::Value::from(value))
+    }
+
+    /// Print the local options set for the current script
+    fn show_local_options(&self) {
+        let options = self
+            .local_options
+            .get(&self.current_script_path)
+            .copied()
+            .unwrap_or_default();
+        if options.is_empty() {
+            godot_print!("[godot-neovim] No local options set for this script");
+            return;
+        }
+        godot_print!("[godot-neovim] --- Local option values ---");
+        for option in [LocalOption::Wrap, LocalOption::Number, LocalOption::List] {
+            if let Some(value) = options.get(option) {
+                godot_print!("  {}{}", if value { "" } else { "no" }, option.name());
+            }
+        }
+    }
+
+    /// Current effective value of an option (as shown by the CodeEdit)
+    fn current_option_value(&self, option: LocalOption) -> bool {
+        let Some(ref editor) = self.current_editor else {
+            return Self::default_option_value(option);
+        };
+        match option {
+            LocalOption::Wrap => editor.get_line_wrapping_mode() != LineWrappingMode::NONE,
+            // The line number gutter is hidden by relative numbers
+            LocalOption::Number => self.number_option_value(),
+            LocalOption::List => editor.is_drawing_tabs(),
+        }
+    }
+
+    /// Editor-wide default of an option (from EditorSettings)
+    fn default_option_value(option: LocalOption) -> bool {
+        let Some(settings) = EditorInterface::singleton().get_editor_settings() else {
+            return false;
+        };
+        let value = settings.get_setting(option.editor_setting());
+        match option {
+            // word_wrap is an enum (0 = None, 1 = Boundary)
+            LocalOption::Wrap => value.try_to::<i64>().unwrap_or(0) != 0,
+            _ => value.try_to::<bool>().unwrap_or(false),
+        }
+    }
+
+    /// Store an option for the current script, apply it and persist the table
+    fn set_local_option(&mut self, option: LocalOption, value: Option<bool>) {
+        if self.current_script_path.is_empty() {
+            godot_warn!("[godot-neovim] :setlocal: No script open");
+            return;
+        }
+
+        let entry = self
+            .local_options
+            .entry(self.current_script_path.clone())
+            .or_default();
+        entry.set(option, value);
+        if entry.is_empty() {
+            self.local_options.remove(&self.current_script_path);
+        }
+
+        let applied = value.unwrap_or_else(|| Self::default_option_value(option));
+        self.apply_option_to_editor(option, applied);
+        self.save_local_options();
+        if option == LocalOption::Number {
+            // The caret line of relative numbers is absolute with 'number'
+            self.apply_relative_number();
+        }
+
+        crate::verbose_print!(
+            "[godot-neovim] :setlocal {}{} for {}",
+            if applied { "" } else { "no" },
+            option.name(),
+            self.current_script_path
+        );
+    }
+
+    /// Apply one option to the current CodeEdit
+    fn apply_option_to_editor(&mut self, option: LocalOption, value: bool) {
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        if !editor.is_instance_valid() {
+            return;
+        }
+        match option {
+            LocalOption::Wrap => editor.set_line_wrapping_mode(if value {
+                LineWrappingMode::BOUNDARY
+            } else {
+                LineWrappingMode::NONE
+            }),
+            // Relative line numbers replace the line number gutter
+            LocalOption::Number => {
+                editor.set_draw_line_numbers(value && !settings::get_relative_number())
+            }
+            LocalOption::List => {
+                editor.set_draw_tabs(value);
+                editor.set_draw_spaces(value);
+            }
+        }
+    }
+
+    /// Effective 'number' of the current script (local option or editor default)
+    pub(super) fn number_option_value(&self) -> bool {
+        self.local_options
+            .get(&self.current_script_path)
+            .and_then(|options| options.number)
+            .unwrap_or_else(Self::default_number_option)
+    }
+
+    /// Editor-wide default of 'number' (Godot's Show Line Numbers setting)
+    pub(super) fn default_number_option() -> bool {
+        Self::default_option_value(LocalOption::Number)
+    }
+
+    /// Re-apply the current script's local options (called after switching tabs)
+    pub(super) fn apply_local_options(&mut self) {
+        let Some(options) = self.local_options.get(&self.current_script_path).copied() else {
+            return;
+        };
+        for option in [LocalOption::Wrap, LocalOption::Number, LocalOption::List] {
+            if let Some(value) = options.get(option) {
+                self.apply_option_to_editor(option, value);
+            }
+        }
+    }
+
+    /// Load the persisted option table from the project metadata
+    pub(super) fn load_local_options(&mut self) {
+        let Some(settings) = EditorInterface::singleton().get_editor_settings() else {
+            return;
+        };
+        let stored = settings
+            .get_project_metadata_ex(METADATA_SECTION, METADATA_KEY)
+            .default(&VarDictionary::new().to_variant())
+            .done();
+        let Ok(table) = stored.try_to::<VarDictionary>() else {
+            return;
+        };
+
+        self.local_options.clear();
+        for (path, options) in table.iter_shared() {
+            let Ok(options) = options.try_to::<VarDictionary>() else {
+                continue;
+            };
+            let options = LocalOptions::from_dictionary(&options);
+            if !options.is_empty() {
+                self.local_options.insert(path.to_string(), options);
+            }
+        }
+        crate::verbose_print!(
+            "[godot-neovim] Loaded local options for {} script(s)",
+            self.local_options.len()
+        );
+    }
+
+    /// Persist the option table to the project metadata
+    fn save_local_options(&self) {
+        let Some(mut settings) = EditorInterface::singleton().get_editor_settings() else {
+            return;
+        };
+        let mut table = VarDictionary::new();
+        for (path, options) in &self.local_options {
+            table.set(path.as_str(), &options.to_dictionary());
+        }
+        settings.set_project_metadata(METADATA_SECTION, METADATA_KEY, &table.to_variant());
+    }
+}
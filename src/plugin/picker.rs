@@ -0,0 +1,375 @@
+//! Results picker: filterable popup list of locations (LSP references, files, etc.)
+//!
+//! Filtering uses the built-in fuzzy matcher, or fzf when selected in the
+//! Editor Settings (see matcher.rs). The live grep picker instead searches the
+//! project with the filter text as the pattern (see quickfix.rs).
+
+use super::matcher;
+use super::GodotNeovimPlugin;
+use crate::settings::{self, PickerBackend};
+use godot::classes::{
+    ConfirmationDialog, EditorInterface, InputEventKey, ItemList, LineEdit, ProjectSettings,
+    VBoxContainer,
+};
+use godot::global::Key;
+use godot::prelude::*;
+
+/// A single entry in the picker
+#[derive(Debug, Clone)]
+pub struct PickerItem {
+    /// Text shown in the list (also used for filtering)
+    pub label: String,
+    /// Absolute file path of the location
+    pub path: String,
+    /// Line and column (0-indexed, character position); None opens the file
+    /// without moving its cursor
+    pub position: Option<(i32, i32)>,
+}
+
+/// What the picker lists, and what the filter does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum PickerSource {
+    /// Fixed items filtered by the matcher
+    #[default]
+    Items,
+    /// The quickfix list: opening an item makes it the current entry
+    Quickfix,
+    /// Live grep: the filter is the grep pattern, opening a match makes the
+    /// results the quickfix list
+    LiveGrep,
+    /// LSP code actions: opening an item applies the action
+    CodeActions,
+    /// Neovim's undo tree: opening a state restores it
+    UndoTree,
+    /// Search history (q/, q?): opening a pattern searches it in this direction
+    SearchHistory { forward: bool },
+}
+
+impl GodotNeovimPlugin {
+    /// Show the picker popup with the given items
+    pub(super) fn show_picker(&mut self, title: &str, items: Vec<PickerItem>) {
+        self.show_picker_with_source(title, items, PickerSource::Items);
+    }
+
+    /// Show the picker popup listing `source`
+    pub(super) fn show_picker_with_source(
+        &mut self,
+        title: &str,
+        items: Vec<PickerItem>,
+        source: PickerSource,
+    ) {
+        self.close_picker();
+
+        let mut dialog = ConfirmationDialog::new_alloc();
+        dialog.set_title(title);
+        dialog.set_ok_button_text(match source {
+            PickerSource::CodeActions => "Apply",
+            PickerSource::UndoTree => "Restore",
+            PickerSource::SearchHistory { .. } => "Search",
+            _ => "Open",
+        });
+
+        let mut vbox = VBoxContainer::new_alloc();
+
+        let mut filter = LineEdit::new_alloc();
+        filter.set_placeholder("Filter...");
+        filter.connect(
+            "text_changed",
+            &self.base().callable("on_picker_filter_changed"),
+        );
+        filter.connect(
+            "text_submitted",
+            &self.base().callable("on_picker_submitted"),
+        );
+        filter.connect("gui_input", &self.base().callable("on_picker_filter_input"));
+        vbox.add_child(&filter);
+
+        let mut list = ItemList::new_alloc();
+        list.set_custom_minimum_size(Vector2::new(720.0, 360.0));
+        list.set_v_size_flags(godot::classes::control::SizeFlags::EXPAND_FILL);
+        list.connect(
+            "item_activated",
+            &self.base().callable("on_picker_item_activated"),
+        );
+        vbox.add_child(&list);
+
+        dialog.add_child(&vbox);
+        dialog.connect("confirmed", &self.base().callable("on_picker_confirmed"));
+        dialog.connect("canceled", &self.base().callable("on_picker_canceled"));
+
+        if let Some(mut base_control) = EditorInterface::singleton().get_base_control() {
+            base_control.add_child(&dialog);
+            dialog.popup_centered();
+        }
+
+        self.picker_items = items;
+        self.picker_source = source;
+        self.picker_fzf_failed = false;
+        self.picker_dialog = Some(dialog);
+        self.picker_list = Some(list);
+        self.update_picker_filter("");
+
+        filter.grab_focus();
+    }
+
+    /// Rebuild the list with items matching the filter
+    /// With the fzf backend the list is updated when the matches arrive (poll_picker_matches)
+    pub(super) fn update_picker_filter(&mut self, filter: &str) {
+        if self.picker_source == PickerSource::LiveGrep {
+            self.update_live_grep(filter);
+            return;
+        }
+
+        // A newer query replaces any running match
+        self.picker_external_match = None;
+
+        if !filter.is_empty()
+            && !self.picker_fzf_failed
+            && settings::get_picker_backend() == PickerBackend::Fzf
+        {
+            let labels = self.picker_items.iter().map(|item| item.label.clone());
+            match matcher::spawn_fzf(&settings::get_fzf_path(), labels.collect(), filter) {
+                Ok(external) => {
+                    self.picker_external_match = Some(external);
+                    return;
+                }
+                Err(e) => self.fall_back_to_builtin_matcher(&e),
+            }
+        }
+
+        self.picker_filtered =
+            matcher::builtin_match(self.picker_items.iter().map(|i| i.label.as_str()), filter);
+        self.refresh_picker_list();
+    }
+
+    /// Apply matches received from fzf (called every frame)
+    pub(super) fn poll_picker_matches(&mut self) {
+        let Some(ref mut external) = self.picker_external_match else {
+            return;
+        };
+        let update = external.poll();
+        if update.matches.is_empty() && !update.done {
+            return;
+        }
+
+        if let Some(error) = update.error {
+            let query = external.query.clone();
+            self.picker_external_match = None;
+            self.fall_back_to_builtin_matcher(&error);
+            self.update_picker_filter(&query);
+            return;
+        }
+
+        // First update replaces the previous query's results, later batches append
+        if update.first {
+            self.picker_filtered.clear();
+        }
+        if update.done {
+            self.picker_external_match = None;
+        }
+        self.picker_filtered.extend(
+            update
+                .matches
+                .into_iter()
+                .filter(|&i| i < self.picker_items.len()),
+        );
+        self.refresh_picker_list();
+    }
+
+    /// Warn once and use the built-in matcher for the rest of this picker session
+    fn fall_back_to_builtin_matcher(&mut self, error: &str) {
+        godot_warn!(
+            "[godot-neovim] fzf unavailable, using the built-in matcher: {}",
+            error
+        );
+        self.picker_fzf_failed = true;
+    }
+
+    /// Show the filtered items in the list, keeping the first one selected
+    pub(super) fn refresh_picker_list(&mut self) {
+        let Some(ref mut list) = self.picker_list else {
+            return;
+        };
+        list.clear();
+        for &i in &self.picker_filtered {
+            list.add_item(&self.picker_items[i].label);
+        }
+        if !self.picker_filtered.is_empty() {
+            list.select(0);
+        }
+    }
+
+    /// Move the list selection by `delta` (used for Up/Down and Ctrl+N/P in the filter)
+    pub(super) fn move_picker_selection(&mut self, delta: i32) {
+        let Some(ref mut list) = self.picker_list else {
+            return;
+        };
+        let count = list.get_item_count();
+        if count == 0 {
+            return;
+        }
+        let selected = list.get_selected_items();
+        let current = if selected.is_empty() { 0 } else { selected[0] };
+        let next = (current + delta).clamp(0, count - 1);
+        list.select(next);
+        list.ensure_current_is_visible();
+    }
+
+    /// Handle keys in the filter LineEdit. Returns true if the key was consumed.
+    pub(super) fn handle_picker_filter_key(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        if !key_event.is_pressed() {
+            return false;
+        }
+        let ctrl = key_event.is_ctrl_pressed();
+        match key_event.get_keycode() {
+            Key::DOWN => self.move_picker_selection(1),
+            Key::UP => self.move_picker_selection(-1),
+            Key::N if ctrl => self.move_picker_selection(1),
+            Key::P if ctrl => self.move_picker_selection(-1),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Open the currently selected item and close the picker
+    pub(super) fn open_selected_picker_item(&mut self) {
+        let index = self
+            .picker_list
+            .as_ref()
+            .and_then(|list| list.get_selected_items().as_slice().first().copied())
+            .unwrap_or(0);
+        self.open_picker_item(index);
+    }
+
+    /// Open the item at `index` (in the filtered list) and close the picker
+    pub(super) fn open_picker_item(&mut self, index: i32) {
+        let item_index = self.picker_filtered.get(index.max(0) as usize).copied();
+        let item = item_index.and_then(|i| self.picker_items.get(i)).cloned();
+        let source = self.picker_source;
+        let live_grep_matches = std::mem::take(&mut self.live_grep_matches);
+        let mut code_actions = std::mem::take(&mut self.code_actions);
+        let undo_seqs = std::mem::take(&mut self.undo_seqs);
+        self.close_picker();
+
+        if let Some(i) = item_index {
+            match source {
+                PickerSource::Items => {}
+                PickerSource::CodeActions => {
+                    if i < code_actions.len() {
+                        self.apply_code_action(code_actions.swap_remove(i));
+                    }
+                    return;
+                }
+                PickerSource::UndoTree => {
+                    if let Some(&seq) = undo_seqs.get(i) {
+                        self.restore_undo_state(seq);
+                    }
+                    return;
+                }
+                PickerSource::SearchHistory { forward } => {
+                    if let Some(item) = item {
+                        self.search_pattern(&item.label, forward);
+                    }
+                    return;
+                }
+                PickerSource::Quickfix => self.quickfix_index = i,
+                PickerSource::LiveGrep => {
+                    self.set_quickfix(self.live_grep_pattern.clone(), live_grep_matches);
+                    self.quickfix_index = i;
+                }
+            }
+        }
+
+        match item {
+            Some(PickerItem {
+                path,
+                position: Some((line, col)),
+                ..
+            }) => self.jump_to_location(&path, line, col),
+            Some(item) => self.open_file(&item.path),
+            None => {}
+        }
+    }
+
+    /// Close the picker and return focus to the editor
+    pub(super) fn close_picker(&mut self) {
+        if let Some(mut dialog) = self.picker_dialog.take() {
+            if dialog.is_instance_valid() {
+                dialog.queue_free();
+            }
+        }
+        self.picker_list = None;
+        self.picker_items.clear();
+        self.picker_filtered.clear();
+        self.picker_external_match = None;
+        self.picker_source = PickerSource::Items;
+        self.picker_grep = None;
+        self.live_grep_matches.clear();
+        self.code_actions.clear();
+        self.undo_seqs.clear();
+
+        if let Some(ref mut editor) = self.current_editor {
+            if editor.is_instance_valid() {
+                editor.grab_focus();
+            }
+        }
+    }
+
+    /// Jump to a location (absolute path, 0-indexed line/col)
+    /// Same file: move cursor directly; other file: open it and position after switch
+    pub(super) fn jump_to_location(&mut self, abs_path: &str, line: i32, col: i32) {
+        self.add_to_jump_list();
+        self.open_location(abs_path, line, col);
+    }
+
+    /// Move the cursor to a location (absolute path, 0-indexed line/col) without
+    /// recording a jump
+    pub(super) fn open_location(&mut self, abs_path: &str, line: i32, col: i32) {
+        let path_normalized = abs_path.replace('\\', "/");
+        let current_abs = self.current_script_abs_path().replace('\\', "/");
+
+        if path_normalized == current_abs || path_normalized == self.current_script_path {
+            if let Some(ref mut editor) = self.current_editor {
+                let safe_line = line.min(editor.get_line_count() - 1).max(0);
+                editor.set_caret_line(safe_line);
+                editor.set_caret_column(col.max(0));
+                editor.center_viewport_to_caret();
+            }
+            self.sync_cursor_to_neovim();
+            return;
+        }
+
+        let res_path = ProjectSettings::singleton()
+            .localize_path(&path_normalized)
+            .to_string();
+        let res_path = if res_path.starts_with("res://") {
+            res_path
+        } else {
+            path_normalized
+        };
+
+        crate::verbose_print!(
+            "[godot-neovim] Jump: Opening {} at {}:{}",
+            res_path,
+            line + 1,
+            col
+        );
+        self.pending_file_path = Some(res_path.clone());
+        self.pending_file_cursor = Some((res_path, line, col));
+    }
+
+    /// Open a file (absolute path) at its current cursor, recording a jump
+    pub(super) fn open_file(&mut self, abs_path: &str) {
+        let path_normalized = abs_path.replace('\\', "/");
+        if path_normalized == self.current_script_abs_path().replace('\\', "/") {
+            return;
+        }
+        let res_path = ProjectSettings::singleton()
+            .localize_path(&path_normalized)
+            .to_string();
+
+        crate::verbose_print!("[godot-neovim] Opening {}", res_path);
+        self.add_to_jump_list();
+        self.pending_file_path = Some(res_path);
+    }
+}
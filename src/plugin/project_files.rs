@@ -0,0 +1,115 @@
+//! Project file index and fuzzy file finder (`<leader>ff`)
+//!
+//! The project directory (res://) is walked when the finder opens (or a grep
+//! starts), skipping hidden
+//! directories (`.godot`, `.git`) and directories with a `.gdignore` file, which
+//! Godot does not import either.
+
+use super::picker::PickerItem;
+use super::GodotNeovimPlugin;
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+use std::path::Path;
+
+/// Extensions of the files listed by the file finder
+pub(super) const FINDER_EXTENSIONS: &[&str] = &["gd", "gdshader", "tres"];
+
+/// Files under `root` with one of `extensions`: paths relative to root with
+/// forward slashes, sorted
+pub(super) fn project_files(root: &Path, extensions: &[&str]) -> Vec<String> {
+    let mut files = Vec::new();
+    collect_files(root, "", extensions, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_files(dir: &Path, prefix: &str, extensions: &[&str], files: &mut Vec<String>) {
+    if dir.join(".gdignore").exists() {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let relative = format!("{}{}", prefix, name);
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &format!("{}/", relative), extensions, files);
+        } else if Path::new(&name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.contains(&ext))
+        {
+            files.push(relative);
+        }
+    }
+}
+
+/// Absolute path of the project directory (res://), forward slashes, no trailing slash
+pub(super) fn project_root() -> String {
+    ProjectSettings::singleton()
+        .globalize_path("res://")
+        .to_string()
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+impl GodotNeovimPlugin {
+    /// Fuzzy find a project file (`*.gd`, `*.gdshader`, `*.tres`) in the picker
+    pub(super) fn show_file_finder(&mut self) {
+        let root = project_root();
+        let files = project_files(Path::new(&root), FINDER_EXTENSIONS);
+        if files.is_empty() {
+            self.show_status_message("No files found");
+            return;
+        }
+        crate::verbose_print!("[godot-neovim] File finder: {} file(s)", files.len());
+
+        let items = files
+            .into_iter()
+            .map(|file| PickerItem {
+                path: format!("{}/{}", root, file),
+                label: file,
+                position: None,
+            })
+            .collect();
+        self.show_picker("Find Files", items);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_project_files_filters_and_skips_ignored_dirs() {
+        let root = std::env::temp_dir().join(format!("godot_neovim_files_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("scripts/ui")).unwrap();
+        fs::create_dir_all(root.join(".godot")).unwrap();
+        fs::create_dir_all(root.join("exports")).unwrap();
+        fs::write(root.join("scripts/player.gd"), "").unwrap();
+        fs::write(root.join("scripts/ui/menu.gd"), "").unwrap();
+        fs::write(root.join("water.gdshader"), "").unwrap();
+        fs::write(root.join("icon.svg"), "").unwrap();
+        fs::write(root.join(".godot/cache.gd"), "").unwrap();
+        fs::write(root.join("exports/.gdignore"), "").unwrap();
+        fs::write(root.join("exports/build.gd"), "").unwrap();
+
+        let files = project_files(&root, FINDER_EXTENSIONS);
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(
+            files,
+            vec!["scripts/player.gd", "scripts/ui/menu.gd", "water.gdshader"]
+        );
+    }
+}
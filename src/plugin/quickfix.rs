@@ -0,0 +1,207 @@
+//! Project grep (:grep, `<leader>fg` live grep) and the quickfix list
+//!
+//! :grep runs a background search (grep.rs) and lists the matches in the picker
+//! when it completes. They become the quickfix list, navigated with :cnext,
+//! :cprev, :cfirst, :clast and :cc, and shown again with :copen. The live grep
+//! picker searches as the pattern is typed; opening a match makes its results
+//! the quickfix list.
+
+use super::grep::{self, GrepMatch};
+use super::picker::{PickerItem, PickerSource};
+use super::project_files::project_root;
+use super::GodotNeovimPlugin;
+
+/// Picker label of a match: `path:line:col  text` (path relative to res://)
+fn match_label(root: &str, m: &GrepMatch) -> String {
+    let path = m
+        .path
+        .strip_prefix(root)
+        .map_or(m.path.as_str(), |path| path.trim_start_matches('/'));
+    format!("{}:{}:{}  {}", path, m.line + 1, m.col + 1, m.text.trim())
+}
+
+fn match_item(root: &str, m: &GrepMatch) -> PickerItem {
+    PickerItem {
+        label: match_label(root, m),
+        path: m.path.clone(),
+        position: Some((m.line, m.col)),
+    }
+}
+
+/// Pattern of :grep, without the quotes a shell grep would need
+fn unquote(pattern: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = pattern
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    pattern
+}
+
+impl GodotNeovimPlugin {
+    /// :grep {pattern} - search the project's GDScript files
+    pub(super) fn cmd_grep(&mut self, pattern: &str) {
+        let pattern = unquote(pattern.trim());
+        if pattern.is_empty() {
+            self.show_status_message("E471: Argument required");
+            return;
+        }
+
+        match grep::spawn_grep(&project_root(), pattern) {
+            Ok(search) => {
+                crate::verbose_print!("[godot-neovim] :grep {}", pattern);
+                self.set_quickfix(pattern.to_string(), Vec::new());
+                self.grep_search = Some(search);
+                self.show_status_message(&format!("Searching for {}...", pattern));
+            }
+            Err(e) => self.show_status_message(&format!("E383: Invalid pattern: {}", e)),
+        }
+    }
+
+    /// Replace the quickfix list
+    pub(super) fn set_quickfix(&mut self, title: String, matches: Vec<GrepMatch>) {
+        self.quickfix_title = title;
+        self.quickfix = matches;
+        self.quickfix_index = 0;
+    }
+
+    /// Collect results of the running searches (called every frame)
+    pub(super) fn poll_grep(&mut self) {
+        self.poll_live_grep();
+
+        let Some(ref mut search) = self.grep_search else {
+            return;
+        };
+        let update = search.poll();
+        self.quickfix.extend(update.matches);
+        if !update.done {
+            return;
+        }
+        self.grep_search = None;
+
+        if self.quickfix.is_empty() {
+            let msg = format!("E480: No match: {}", self.quickfix_title);
+            self.show_status_message(&msg);
+            return;
+        }
+        let msg = format!(
+            "{} match(es) for {}{}",
+            self.quickfix.len(),
+            self.quickfix_title,
+            if update.truncated { " (truncated)" } else { "" }
+        );
+        crate::verbose_print!("[godot-neovim] :grep: {}", msg);
+        self.show_quickfix_picker();
+        self.show_status_message(&msg);
+    }
+
+    /// :copen - show the quickfix list in the picker
+    pub(super) fn show_quickfix_picker(&mut self) {
+        if self.quickfix.is_empty() {
+            self.show_status_message("E42: No Errors");
+            return;
+        }
+        let root = project_root();
+        let items = self.quickfix.iter().map(|m| match_item(&root, m)).collect();
+        let title = format!("Quickfix: {}", self.quickfix_title);
+        self.show_picker_with_source(&title, items, PickerSource::Quickfix);
+    }
+
+    /// Jump to quickfix entry `index` (0-indexed) and make it the current one
+    fn quickfix_jump(&mut self, index: usize) {
+        let Some(m) = self.quickfix.get(index).cloned() else {
+            return;
+        };
+        self.quickfix_index = index;
+        self.jump_to_location(&m.path, m.line, m.col);
+        let msg = format!(
+            "({} of {}): {}",
+            index + 1,
+            self.quickfix.len(),
+            m.text.trim()
+        );
+        self.show_status_message(&msg);
+    }
+
+    /// :cnext / :cprevious - move `delta` entries through the quickfix list
+    pub(super) fn cmd_quickfix_step(&mut self, delta: isize) {
+        if self.quickfix.is_empty() {
+            self.show_status_message("E42: No Errors");
+            return;
+        }
+        match self.quickfix_index.checked_add_signed(delta) {
+            Some(index) if index < self.quickfix.len() => self.quickfix_jump(index),
+            _ => self.show_status_message("E553: No more items"),
+        }
+    }
+
+    /// :cc [N], :cfirst, :clast - jump to entry N (1-indexed, None: current),
+    /// limited to the last entry
+    pub(super) fn cmd_quickfix_goto(&mut self, number: Option<usize>) {
+        if self.quickfix.is_empty() {
+            self.show_status_message("E42: No Errors");
+            return;
+        }
+        let index = number.map_or(self.quickfix_index, |n| n.saturating_sub(1));
+        self.quickfix_jump(index.min(self.quickfix.len() - 1));
+    }
+
+    /// `<leader>fg` - picker searching the project as the pattern is typed
+    pub(super) fn show_live_grep(&mut self) {
+        self.show_picker_with_source("Live Grep", Vec::new(), PickerSource::LiveGrep);
+    }
+
+    /// Restart the live grep for the picker's filter text
+    pub(super) fn update_live_grep(&mut self, pattern: &str) {
+        self.picker_grep = None;
+        self.picker_items.clear();
+        self.picker_filtered.clear();
+        self.live_grep_matches.clear();
+        self.live_grep_pattern = pattern.to_string();
+        self.refresh_picker_list();
+
+        if pattern.is_empty() {
+            return;
+        }
+        // An incomplete pattern (e.g. while typing "foo(") lists nothing yet
+        match grep::spawn_grep(&project_root(), pattern) {
+            Ok(search) => self.picker_grep = Some(search),
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Live grep: {}", e);
+            }
+        }
+    }
+
+    /// Append the live grep matches found since the last frame to the picker
+    fn poll_live_grep(&mut self) {
+        let Some(ref mut search) = self.picker_grep else {
+            return;
+        };
+        let update = search.poll();
+        if update.done {
+            self.picker_grep = None;
+        }
+        if update.matches.is_empty() {
+            return;
+        }
+
+        let root = project_root();
+        let Some(ref mut list) = self.picker_list else {
+            return;
+        };
+        let was_empty = self.picker_items.is_empty();
+        for m in update.matches {
+            let item = match_item(&root, &m);
+            list.add_item(&item.label);
+            self.picker_filtered.push(self.picker_items.len());
+            self.picker_items.push(item);
+            self.live_grep_matches.push(m);
+        }
+        if was_empty {
+            list.select(0);
+        }
+    }
+}
@@ -1,13 +1,60 @@
-//! Neovim timeout recovery: automatic recovery when Neovim becomes unresponsive
+//! Neovim recovery: automatic recovery when Neovim becomes unresponsive or exits
+//!
+//! A Neovim that stops responding is offered a restart (recovery dialog). A
+//! Neovim process that exits (crash, OOM, killed externally) is detected by the
+//! client's supervisor and respawned right away: the current script is registered
+//! again from Godot's text, keeping Godot's caret, and the marks saved by the
+//! last periodic snapshot are set again.
 
-use super::GodotNeovimPlugin;
-use crate::neovim::NeovimClient;
+use super::{EditorType, GodotNeovimPlugin};
+use crate::neovim::{NeovimClient, RpcRequest};
 use crate::neovim::{TIMEOUT_RECOVERY_THRESHOLD, TIMEOUT_RECOVERY_WINDOW_SECS};
+use crate::settings;
 use godot::classes::{ConfirmationDialog, EditorInterface, ProjectSettings, ResourceSaver};
 use godot::prelude::*;
+use rmpv::Value;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+/// Interval between the mark snapshots restored after a Neovim crash
+const MARKS_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Marks of a Neovim, saved periodically to be restored after a crash
+#[derive(Debug, Clone)]
+pub(super) struct MarksSnapshot {
+    /// Editor whose Neovim the marks were read from
+    editor_type: EditorType,
+    /// Script whose buffer marks were saved
+    path: String,
+    /// Result of get_marks (passed back to restore_marks as is)
+    marks: Value,
+}
+
+/// Create and start a Neovim client with the addon's Lua plugin
+/// The ScriptEditor's client attaches to the Neovim at the Connection Address
+/// setting if one is set, and falls back to an embedded Neovim if it cannot.
+pub(super) fn spawn_neovim_client(editor_type: EditorType) -> Result<NeovimClient, String> {
+    let addons_path = ProjectSettings::singleton()
+        .globalize_path("res://addons/godot-neovim")
+        .to_string();
+    let address = settings::get_connection_address();
+    if editor_type != EditorType::Shader && !address.is_empty() {
+        let mut client =
+            NeovimClient::new().map_err(|e| format!("Failed to create Neovim client: {}", e))?;
+        client.set_remote_address(&address);
+        match client.start(Some(&addons_path)) {
+            Ok(()) => return Ok(client),
+            Err(e) => godot_warn!("[godot-neovim] {}; starting an embedded Neovim instead", e),
+        }
+    }
+    let mut client =
+        NeovimClient::new().map_err(|e| format!("Failed to create Neovim client: {}", e))?;
+    client
+        .start(Some(&addons_path))
+        .map_err(|e| format!("Failed to start Neovim: {}", e))?;
+    Ok(client)
+}
+
 impl GodotNeovimPlugin {
     /// Record a timeout error and check if recovery should be triggered.
     /// Returns true if the recovery dialog should be shown.
@@ -153,65 +200,61 @@ impl GodotNeovimPlugin {
         self.sync_manager.reset();
         self.reset_timeout_counter();
 
-        // Get addons path for Lua plugin
-        let addons_path = ProjectSettings::singleton()
-            .globalize_path("res://addons/godot-neovim")
-            .to_string();
-
         // Create new Neovim client for ScriptEditor
-        match NeovimClient::new() {
-            Ok(mut client) => {
-                if let Err(e) = client.start(Some(&addons_path)) {
-                    godot_error!(
-                        "[godot-neovim] Recovery: Failed to start Neovim for ScriptEditor: {}",
-                        e
-                    );
-                    return;
-                }
-
+        match spawn_neovim_client(EditorType::Script) {
+            Ok(client) => {
                 self.script_neovim = Some(Mutex::new(client));
                 crate::verbose_print!(
                     "[godot-neovim] Recovery: ScriptEditor Neovim restarted successfully"
                 );
             }
             Err(e) => {
-                godot_error!(
-                    "[godot-neovim] Recovery: Failed to create Neovim client for ScriptEditor: {}",
-                    e
-                );
+                godot_error!("[godot-neovim] Recovery: ScriptEditor: {}", e);
                 return;
             }
         }
 
         // Create new Neovim client for ShaderEditor
-        match NeovimClient::new() {
-            Ok(mut client) => {
-                if let Err(e) = client.start(Some(&addons_path)) {
-                    godot_error!(
-                        "[godot-neovim] Recovery: Failed to start Neovim for ShaderEditor: {}",
-                        e
-                    );
-                    // Continue with ScriptEditor only
-                } else {
-                    self.shader_neovim = Some(Mutex::new(client));
-                    crate::verbose_print!(
-                        "[godot-neovim] Recovery: ShaderEditor Neovim restarted successfully"
-                    );
-                }
+        match spawn_neovim_client(EditorType::Shader) {
+            Ok(client) => {
+                self.shader_neovim = Some(Mutex::new(client));
+                crate::verbose_print!(
+                    "[godot-neovim] Recovery: ShaderEditor Neovim restarted successfully"
+                );
             }
             Err(e) => {
-                godot_warn!(
-                    "[godot-neovim] Recovery: Failed to create Neovim client for ShaderEditor: {}",
-                    e
-                );
                 // Continue with ScriptEditor only
+                godot_warn!("[godot-neovim] Recovery: ShaderEditor: {}", e);
             }
         }
 
-        // Reinitialize current buffer
+        self.sync_comment_continuation_to_neovim();
+        self.sync_formatter_to_neovim();
+        self.sync_undo_dir_to_neovim();
+        self.apply_neovim_options();
+        self.restore_last_search_pattern();
+        self.refresh_user_mappings();
+
+        // Requests queued to the old Neovim are never answered
+        self.hlsearch_query_pending = false;
+        self.operator_pending = false;
+        self.large_file_resync.clear();
+
+        // Reinitialize current buffer, then set the marks of the old Neovim again
+        self.marks_snapshot_type = None;
+        self.pending_marks_restore = self.marks_snapshot.take();
         self.script_changed_pending.set(true);
     }
 
+    /// :PluginReloadConfig - restart Neovim to apply the config settings (Neovim
+    /// Clean, init file, config files)
+    pub(super) fn cmd_reload_config(&mut self) {
+        self.status_ping_sent = None;
+        self.status_latency = None;
+        self.restart_neovim();
+        self.command_output = Some("Neovim restarted with the current config".to_string());
+    }
+
     /// Clean up the recovery dialog
     pub(super) fn cleanup_recovery_dialog(&mut self) {
         if let Some(mut dialog) = self.recovery_dialog.take() {
@@ -221,4 +264,153 @@ impl GodotNeovimPlugin {
         }
         self.recovery_dialog_open = false;
     }
+
+    /// Respawn a Neovim whose process exited unexpectedly (called every frame)
+    pub(super) fn check_neovim_exit(&mut self) {
+        for editor_type in [EditorType::Script, EditorType::Shader] {
+            let status = self
+                .neovim_for(editor_type)
+                .and_then(|neovim| neovim.try_lock().ok())
+                .and_then(|client| client.process_exit());
+            if let Some(status) = status {
+                self.respawn_neovim(editor_type, &status);
+            }
+        }
+    }
+
+    /// Replace the exited Neovim of `editor_type`, then register the current
+    /// script again if that Neovim is the current one
+    fn respawn_neovim(&mut self, editor_type: EditorType, status: &str) {
+        godot_warn!(
+            "[godot-neovim] Neovim ({:?}) exited unexpectedly ({}), restarting",
+            editor_type,
+            status
+        );
+        let client = match spawn_neovim_client(editor_type) {
+            Ok(client) => Some(Mutex::new(client)),
+            Err(e) => {
+                godot_error!("[godot-neovim] Could not restart Neovim: {}", e);
+                None
+            }
+        };
+        let restarted = client.is_some();
+        // A client that could not be restarted is dropped so the exit is reported once
+        match editor_type {
+            EditorType::Shader => self.shader_neovim = client,
+            _ => self.script_neovim = client,
+        }
+        if !restarted {
+            self.show_status_message("Neovim exited and could not be restarted (see Output)");
+            return;
+        }
+
+        self.sync_comment_continuation_to_neovim();
+        self.sync_formatter_to_neovim();
+        self.sync_undo_dir_to_neovim();
+        self.apply_neovim_options();
+        self.restore_last_search_pattern();
+        self.refresh_user_mappings();
+        self.reset_timeout_counter();
+
+        // A snapshot (or search query) requested from the exited Neovim is never answered
+        self.marks_snapshot_type = None;
+        self.hlsearch_query_pending = false;
+        self.operator_pending = false;
+        self.large_file_resync.clear();
+        if self.is_current_neovim(editor_type) {
+            // Godot's text is intact: register it again (Godot keeps its caret)
+            self.sync_manager.reset();
+            self.pending_marks_restore = self.marks_snapshot.take();
+            self.script_changed_pending.set(true);
+        }
+        self.show_status_message(&format!(
+            "Neovim exited unexpectedly ({}) and was restarted",
+            status
+        ));
+    }
+
+    /// Whether the Neovim of `editor_type` is the one of the current editor
+    fn is_current_neovim(&self, editor_type: EditorType) -> bool {
+        match self.current_editor_type {
+            EditorType::Shader => editor_type == EditorType::Shader,
+            _ => editor_type != EditorType::Shader,
+        }
+    }
+
+    /// Save the marks of the current Neovim for a crash (called every frame,
+    /// requested every MARKS_SNAPSHOT_INTERVAL without waiting)
+    pub(super) fn snapshot_marks(&mut self) {
+        if self.marks_snapshot_type.is_some()
+            || self.current_script_path.is_empty()
+            || self
+                .marks_snapshot_time
+                .is_some_and(|time| time.elapsed() < MARKS_SNAPSHOT_INTERVAL)
+        {
+            return;
+        }
+        self.marks_snapshot_time = Some(Instant::now());
+
+        let path = self.current_script_path.clone();
+        let requested = self.get_current_neovim().is_some_and(|neovim| {
+            neovim.try_lock().is_ok_and(|client| {
+                client.request_lua(
+                    RpcRequest::MarksSnapshot(path),
+                    "return _G.godot_neovim.get_marks()",
+                    vec![],
+                )
+            })
+        });
+        if requested {
+            self.marks_snapshot_type = Some(self.current_editor_type);
+        }
+    }
+
+    /// Response to snapshot_marks
+    pub(super) fn on_marks_snapshot(&mut self, path: String, result: Result<Value, String>) {
+        let Some(editor_type) = self.marks_snapshot_type.take() else {
+            return;
+        };
+        match result {
+            Ok(marks) => {
+                self.marks_snapshot = Some(MarksSnapshot {
+                    editor_type,
+                    path,
+                    marks,
+                })
+            }
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Marks snapshot failed: {}", e);
+            }
+        }
+    }
+
+    /// Set the marks saved before a crash, once the current script is registered
+    /// in the restarted Neovim
+    pub(super) fn restore_pending_marks(&mut self) {
+        let Some(snapshot) = self.pending_marks_restore.take() else {
+            return;
+        };
+        if !self.is_current_neovim(snapshot.editor_type) {
+            return;
+        }
+        let buffer_marks = snapshot.path == self.current_script_path;
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            return;
+        };
+        match client.execute_lua_with_args(
+            "return _G.godot_neovim.restore_marks(...)",
+            vec![snapshot.marks, Value::from(buffer_marks)],
+        ) {
+            Ok(count) => {
+                crate::verbose_print!(
+                    "[godot-neovim] Restored {} mark(s) after restart",
+                    count.as_i64().unwrap_or(0)
+                );
+            }
+            Err(e) => godot_warn!("[godot-neovim] Could not restore marks: {}", e),
+        }
+    }
 }
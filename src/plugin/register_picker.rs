@@ -0,0 +1,167 @@
+//! Register picker: popup list of Neovim's registers (:registers)
+//!
+//! Contents are queried from Neovim (getreg), so the list always matches what
+//! `"xp` would paste. Selecting an entry previews its full content; activating
+//! it pastes the register after the cursor through Neovim.
+
+use super::GodotNeovimPlugin;
+use godot::classes::{ConfirmationDialog, EditorInterface, HSplitContainer, ItemList, TextEdit};
+use godot::prelude::*;
+
+/// Maximum characters of register content shown in the list
+const LIST_PREVIEW_CHARS: usize = 60;
+
+impl GodotNeovimPlugin {
+    /// :registers - Show Neovim's registers in the picker popup
+    /// `names`: only show these registers (`:reg a"`); empty shows all
+    pub(super) fn show_register_picker(&mut self, names: &str) {
+        let mut registers = {
+            let Some(neovim) = self.get_current_neovim() else {
+                godot_warn!("[godot-neovim] :registers - Neovim not connected");
+                return;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                godot_warn!("[godot-neovim] :registers - Neovim is busy");
+                return;
+            };
+            match client.get_registers() {
+                Ok(registers) => registers,
+                Err(e) => {
+                    godot_warn!("[godot-neovim] :registers - {}", e);
+                    return;
+                }
+            }
+        };
+        let names: Vec<char> = names.chars().filter(|c| !c.is_whitespace()).collect();
+        if !names.is_empty() {
+            registers.retain(|register| names.contains(&register.name));
+        }
+        if registers.is_empty() {
+            godot_print!("[godot-neovim] :registers - No registers set");
+            return;
+        }
+
+        self.close_register_picker();
+
+        let mut dialog = ConfirmationDialog::new_alloc();
+        dialog.set_title("Registers");
+        dialog.set_ok_button_text("Paste");
+
+        let mut split = HSplitContainer::new_alloc();
+
+        let mut list = ItemList::new_alloc();
+        list.set_custom_minimum_size(Vector2::new(420.0, 360.0));
+        for register in &registers {
+            let preview: String = register
+                .text
+                .replace('\n', "^J")
+                .chars()
+                .take(LIST_PREVIEW_CHARS)
+                .collect();
+            let kind = register.regtype.chars().next().map_or('v', |c| {
+                if c == '<' {
+                    'b'
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            });
+            list.add_item(&format!("\"{}  {}  {}", register.name, kind, preview));
+        }
+        list.connect(
+            "item_selected",
+            &self.base().callable("on_register_picker_selected"),
+        );
+        list.connect(
+            "item_activated",
+            &self.base().callable("on_register_picker_activated"),
+        );
+        split.add_child(&list);
+
+        let mut preview = TextEdit::new_alloc();
+        preview.set_editable(false);
+        preview.set_custom_minimum_size(Vector2::new(360.0, 360.0));
+        preview.set_h_size_flags(godot::classes::control::SizeFlags::EXPAND_FILL);
+        split.add_child(&preview);
+
+        dialog.add_child(&split);
+        dialog.connect(
+            "confirmed",
+            &self.base().callable("on_register_picker_confirmed"),
+        );
+        dialog.connect(
+            "canceled",
+            &self.base().callable("on_register_picker_canceled"),
+        );
+
+        if let Some(mut base_control) = EditorInterface::singleton().get_base_control() {
+            base_control.add_child(&dialog);
+            dialog.popup_centered();
+        }
+
+        self.register_picker_entries = registers;
+        self.register_picker_dialog = Some(dialog);
+        self.register_picker_preview = Some(preview);
+        list.select(0);
+        list.grab_focus();
+        self.register_picker_list = Some(list);
+        self.preview_register(0);
+    }
+
+    /// Show the full content of the register at `index` in the preview pane
+    pub(super) fn preview_register(&mut self, index: i32) {
+        let Some(register) = self.register_picker_entries.get(index.max(0) as usize) else {
+            return;
+        };
+        if let Some(ref mut preview) = self.register_picker_preview {
+            preview.set_text(&register.text);
+        }
+    }
+
+    /// Paste the selected register after the cursor and close the picker
+    pub(super) fn paste_selected_register(&mut self) {
+        let index = self
+            .register_picker_list
+            .as_ref()
+            .and_then(|list| list.get_selected_items().as_slice().first().copied())
+            .unwrap_or(0);
+        self.paste_register_at(index);
+    }
+
+    /// Paste the register at `index` after the cursor and close the picker
+    pub(super) fn paste_register_at(&mut self, index: i32) {
+        let name = self
+            .register_picker_entries
+            .get(index.max(0) as usize)
+            .map(|register| register.name);
+        self.close_register_picker();
+
+        if let Some(name) = name {
+            // Neovim pastes; the change comes back through buffer events
+            self.load_register_for_paste(name);
+            let keys = format!("\"{}p", name);
+            if self.recording_macro.is_some() {
+                self.macro_buffer.push(keys.clone());
+            }
+            self.send_keys(&keys);
+            crate::verbose_print!("[godot-neovim] :registers - Pasted register {}", name);
+        }
+    }
+
+    /// Close the register picker and return focus to the editor
+    pub(super) fn close_register_picker(&mut self) {
+        if let Some(mut dialog) = self.register_picker_dialog.take() {
+            if dialog.is_instance_valid() {
+                dialog.queue_free();
+            }
+        }
+        self.register_picker_list = None;
+        self.register_picker_preview = None;
+        self.register_picker_entries.clear();
+
+        if let Some(ref mut editor) = self.current_editor {
+            if editor.is_instance_valid() {
+                editor.grab_focus();
+            }
+        }
+    }
+}
@@ -1,7 +1,272 @@
 //! Named registers for yank, delete, and paste
 //!
-//! Note: All register operations are sent to Neovim
-//! (Neovim Master design - see DESIGN_V2.md)
+//! Neovim is the single source of truth for registers (Neovim Master design -
+//! see DESIGN_V2.md): after `"{reg}`, the command that uses the register is sent
+//! to Neovim as `"{reg}{count}{keys}` and no register content is kept locally.
 //!
-//! This module is kept for potential future use but currently empty
-//! as all register operations go through Neovim.
+//! This module also bridges the `*` register with the primary selection
+//! (Linux/X11): visual selections and `"*y` update the primary selection,
+//! and `"*p` / middle-click paste read it back through Neovim.
+
+use super::text_object::is_text_object_prefix;
+use super::GodotNeovimPlugin;
+use crate::neovim::YankEvent;
+use crate::settings;
+use godot::classes::display_server::Feature;
+use godot::classes::{DisplayServer, InputEventKey};
+use godot::prelude::*;
+
+/// Operators that take a register and wait for a motion or text object
+const REGISTER_OPERATORS: &[&str] = &["y", "d", "c"];
+
+/// Commands that take a register and complete immediately
+const REGISTER_COMMANDS: &[&str] = &["p", "P", "x", "X", "D", "C", "Y", "s", "S", "<Del>"];
+
+/// Commands after a `g` prefix that take a register (`gp`, `gP`)
+const REGISTER_G_COMMANDS: &[&str] = &["p", "P"];
+
+/// Registers backed by the OS clipboard
+const CLIPBOARD_REGISTERS: &[char] = &['+', '*'];
+
+/// Whether `reg` is backed by the OS clipboard (`+`, `*`)
+pub(super) fn is_clipboard_register(reg: char) -> bool {
+    CLIPBOARD_REGISTERS.contains(&reg)
+}
+
+/// Whether `c` can follow `"` (`a-z`, `A-Z` append, `0-9`, `"`, `-`, `+`, `*`, `_`,
+/// and the read-only `.`, `:`, `%`, `#`, `/`)
+pub(super) fn is_valid_register(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "\"-+*_.:%#/".contains(c)
+}
+
+impl GodotNeovimPlugin {
+    /// Handle a key after `"{reg}` was selected (Normal mode)
+    /// Returns true if the key was consumed. Other keys cancel the register selection
+    /// and are processed normally.
+    pub(super) fn handle_register_key(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        let Some(reg) = self.selected_register.filter(|&reg| reg != '\0') else {
+            return false;
+        };
+
+        // Count after the register ("a3yy)
+        if let Some(c) = char::from_u32(key_event.get_unicode()) {
+            if c.is_ascii_digit() && (c != '0' || !self.count_buffer.is_empty()) {
+                self.count_buffer.push(c);
+                return true;
+            }
+        }
+
+        let Some(keys) = self.key_event_to_nvim_string(key_event) else {
+            return false;
+        };
+        let pending = self.last_key.clone();
+
+        // Operator waiting for its motion: "ayy, "adw, "ad/foo<CR> ("aci( in text_object.rs)
+        if REGISTER_OPERATORS.contains(&pending.as_str()) {
+            self.selected_register = None;
+            self.clear_last_key();
+            if is_text_object_prefix(&keys) {
+                let count = self.take_count_string();
+                self.start_text_object(format!("\"{}{}", reg, pending), count, &keys);
+                return true;
+            }
+            let operator = format!("\"{}{}{}", reg, self.take_count_string(), pending);
+            if keys == "/" || keys == "?" {
+                // The search pattern is typed in search mode
+                self.open_operator_search(&operator, keys == "/");
+            } else {
+                self.send_keys(&format!("{}{}", operator, keys));
+            }
+            return true;
+        }
+
+        // gp / gP
+        if pending == "g" && REGISTER_G_COMMANDS.contains(&keys.as_str()) {
+            self.send_register_command(reg, &format!("g{}", keys));
+            self.clear_last_key();
+            return true;
+        }
+
+        // "agr{motion}: replace with the register (the count stays for the motion)
+        if pending == "g" && keys == "r" && Self::gr_is_replace_operator() {
+            self.selected_register = None;
+            self.clear_last_key();
+            self.start_replace_operator(reg);
+            return true;
+        }
+
+        if REGISTER_OPERATORS.contains(&keys.as_str()) || keys == "g" {
+            self.set_last_key(keys);
+            return true;
+        }
+
+        if REGISTER_COMMANDS.contains(&keys.as_str()) {
+            self.send_register_command(reg, &keys);
+            return true;
+        }
+
+        // Other keys cancel register selection
+        self.selected_register = None;
+        self.count_buffer.clear();
+        false
+    }
+
+    /// Send `"{reg}{count}{keys}` to Neovim and clear the register selection
+    fn send_register_command(&mut self, reg: char, keys: &str) {
+        if keys.ends_with(['p', 'P']) {
+            self.load_register_for_paste(reg);
+        }
+        let count = self.take_count_string();
+        self.send_keys(&format!("\"{}{}{}", reg, count, keys));
+        self.selected_register = None;
+    }
+
+    /// Take the typed count as a key prefix ("" for no count or 1)
+    pub(super) fn take_count_string(&mut self) -> String {
+        let count = self.get_and_clear_count();
+        if count > 1 {
+            count.to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Whether `*` is tied to the primary selection (setting enabled and platform supports it)
+    pub(super) fn primary_selection_enabled() -> bool {
+        settings::get_primary_selection()
+            && DisplayServer::singleton().has_feature(Feature::CLIPBOARD_PRIMARY)
+    }
+
+    /// Handle a yank reported by Neovim (TextYankPost)
+    pub(super) fn on_register_yanked(&mut self, yank: &YankEvent) {
+        self.flash_yank_highlight(yank);
+    }
+
+    /// Handle a copy to `"+` / `"*` reported by the clipboard provider (also yanks
+    /// to the unnamed register with clipboard=unnamedplus)
+    pub(super) fn on_clipboard_set(&mut self, register: &str, text: &str) {
+        let Some(reg) = register
+            .chars()
+            .next()
+            .filter(|&c| is_clipboard_register(c))
+        else {
+            return;
+        };
+        Self::set_clipboard_register_text(reg, text);
+        crate::verbose_print!("[godot-neovim] \"{}: OS clipboard updated", reg);
+    }
+
+    /// Text of the OS clipboard behind a clipboard register: `+` is the clipboard,
+    /// `*` the primary selection where enabled and the clipboard otherwise
+    fn clipboard_register_text(reg: char) -> String {
+        let display = DisplayServer::singleton();
+        if reg == '*' && Self::primary_selection_enabled() {
+            display.clipboard_get_primary().to_string()
+        } else {
+            display.clipboard_get().to_string()
+        }
+    }
+
+    /// Write a clipboard register to the OS clipboard (see clipboard_register_text)
+    fn set_clipboard_register_text(reg: char, text: &str) {
+        let mut display = DisplayServer::singleton();
+        if reg == '*' && Self::primary_selection_enabled() {
+            display.clipboard_set_primary(text);
+        } else {
+            display.clipboard_set(text);
+        }
+    }
+
+    /// Copy the current Godot selection (mirrors Neovim's visual selection) to the primary selection
+    pub(super) fn update_primary_from_selection(&self) {
+        if !Self::primary_selection_enabled() {
+            return;
+        }
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        if !editor.has_selection() {
+            return;
+        }
+        DisplayServer::singleton().clipboard_set_primary(&editor.get_selected_text());
+    }
+
+    /// Before pasting from `reg`, load the OS clipboard into the Neovim register
+    /// (`+` and `*`, returned by the clipboard provider in clipboard.lua)
+    pub(super) fn load_register_for_paste(&mut self, reg: char) {
+        if !is_clipboard_register(reg) {
+            return;
+        }
+
+        let text = Self::clipboard_register_text(reg);
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            crate::verbose_print!("[godot-neovim] \"{}: Mutex busy, clipboard not loaded", reg);
+            return;
+        };
+        if let Err(e) = client.execute_lua_with_args(
+            "_G.godot_neovim.clipboard_load(...)",
+            vec![
+                rmpv::Value::from(reg.to_string()),
+                rmpv::Value::from(text.as_str()),
+            ],
+        ) {
+            godot_warn!(
+                "[godot-neovim] \"{}: Failed to load the clipboard: {}",
+                reg,
+                e
+            );
+        }
+    }
+
+    /// Load the OS clipboard into all clipboard registers, before keys that Neovim
+    /// handles itself may paste from them (`"+p` in Visual mode, `p` with
+    /// clipboard=unnamedplus after the clipboard changed in another application)
+    pub(super) fn load_clipboard_registers(&mut self) {
+        for &reg in CLIPBOARD_REGISTERS {
+            self.load_register_for_paste(reg);
+        }
+    }
+
+    /// Middle-click: paste the primary selection at the mouse position (Normal mode)
+    /// Returns true if the click was handled
+    pub(super) fn handle_middle_click_paste(&mut self) -> bool {
+        if !Self::primary_selection_enabled() {
+            return false;
+        }
+
+        // Insert mode: Godot pastes natively, the text is synced on Escape
+        if self.is_insert_mode() || self.is_in_visual_mode() {
+            return false;
+        }
+
+        let Some(ref mut editor) = self.current_editor else {
+            return false;
+        };
+        if !editor.is_instance_valid()
+            || !editor
+                .get_global_rect()
+                .contains_point(editor.get_global_mouse_position())
+        {
+            return false;
+        }
+
+        let local_pos = editor.get_local_mouse_position();
+        let pos =
+            editor.get_line_column_at_pos(Vector2i::new(local_pos.x as i32, local_pos.y as i32));
+        editor.set_caret_line(pos.y);
+        editor.set_caret_column(pos.x);
+        self.sync_cursor_to_neovim();
+
+        self.load_register_for_paste('*');
+        self.send_keys("\"*P");
+        crate::verbose_print!(
+            "[godot-neovim] Middle-click: Pasted primary selection at ({}, {})",
+            pos.y,
+            pos.x
+        );
+        true
+    }
+}
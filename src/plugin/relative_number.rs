@@ -0,0 +1,165 @@
+//! Relative line numbers ('relativenumber')
+//!
+//! With the Relative Number setting (`:set relativenumber`) the CodeEdit's line
+//! number gutter is replaced by a custom gutter showing each line's distance to
+//! the caret line. The caret line shows its absolute number, or 0 with
+//! `:setlocal nonumber`, like Vim. The gutter is drawn by a callback that reads the
+//! caret at draw time, so it follows caret moves and edits with the CodeEdit's own
+//! redraws.
+
+use super::editor::gutter_index;
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::text_edit::GutterType;
+use godot::classes::{CodeEdit, EditorInterface};
+use godot::prelude::*;
+
+/// Name of the relative number gutter
+const GUTTER_NAME: &str = "godot_neovim_relative_numbers";
+
+/// Name of CodeEdit's own line number gutter (the relative one is put before it)
+const LINE_NUMBER_GUTTER: &str = "line_numbers";
+
+/// Space on each side of the numbers (pixels)
+const GUTTER_PADDING: f32 = 4.0;
+
+/// Number shown for `line` with the caret on `caret_line` (0-indexed)
+/// `number`: the caret line shows its absolute number instead of 0
+fn relative_number(line: i32, caret_line: i32, number: bool) -> i32 {
+    match line - caret_line {
+        0 if number => line + 1,
+        delta => delta.abs(),
+    }
+}
+
+/// Digits of the widest number shown (at least 3, like Godot's line numbers)
+fn number_digits(line_count: i32) -> i32 {
+    line_count.max(1).to_string().len().max(3) as i32
+}
+
+/// Remove the relative number gutter of an editor (true if it had one)
+fn remove_gutter(editor: &mut Gd<CodeEdit>) -> bool {
+    let Some(index) = gutter_index(editor, GUTTER_NAME) else {
+        return false;
+    };
+    editor.remove_gutter(index);
+    true
+}
+
+impl GodotNeovimPlugin {
+    /// Add or remove the relative number gutter of the current editor (after
+    /// switching scripts, :set relativenumber, :setlocal number and settings changes)
+    pub(super) fn apply_relative_number(&mut self) {
+        let enabled = settings::get_relative_number();
+        let number = self.number_option_value();
+        self.relative_number_absolute = number;
+        let Some(mut editor) = self.current_editor.clone() else {
+            return;
+        };
+        if !editor.is_instance_valid() {
+            return;
+        }
+
+        if !enabled {
+            if remove_gutter(&mut editor) {
+                editor.set_draw_line_numbers(number);
+                crate::verbose_print!("[godot-neovim] Relative line numbers: off");
+            }
+            return;
+        }
+
+        editor.set_draw_line_numbers(false);
+        if gutter_index(&editor, GUTTER_NAME).is_some() {
+            editor.queue_redraw();
+            return;
+        }
+        let index = gutter_index(&editor, LINE_NUMBER_GUTTER).unwrap_or(editor.get_gutter_count());
+        editor.add_gutter_ex().at(index).done();
+        editor.set_gutter_name(index, GUTTER_NAME);
+        editor.set_gutter_type(index, GutterType::CUSTOM);
+        let callable = self
+            .base()
+            .callable("on_relative_number_draw")
+            .bind(&[editor.to_variant()]);
+        editor.set_gutter_custom_draw(index, &callable);
+        crate::verbose_print!("[godot-neovim] Relative line numbers: on");
+    }
+
+    /// Remove the relative number gutters of all open scripts (plugin deactivation)
+    pub(super) fn remove_relative_number_gutters(&mut self) {
+        let mut editors: Vec<Gd<CodeEdit>> = self.current_editor.iter().cloned().collect();
+        if let Some(script_editor) = EditorInterface::singleton().get_script_editor() {
+            editors.extend(
+                script_editor
+                    .get_open_script_editors()
+                    .iter_shared()
+                    .filter_map(|editor| editor.get_base_editor())
+                    .filter_map(|control| control.try_cast::<CodeEdit>().ok()),
+            );
+        }
+        let number = Self::default_number_option();
+        for mut editor in editors {
+            if editor.is_instance_valid() && remove_gutter(&mut editor) {
+                editor.set_draw_line_numbers(number);
+            }
+        }
+    }
+
+    /// Draw the number of one line (custom draw callback of the gutter)
+    pub(super) fn draw_relative_number(
+        &self,
+        editor: &mut Gd<CodeEdit>,
+        line: i32,
+        gutter: i32,
+        rect: Rect2,
+    ) {
+        let Some(font) = editor.get_theme_font("font") else {
+            return;
+        };
+        let font_size = editor.get_theme_font_size("font_size");
+
+        // Grow the gutter with the line count (redrawn at the new width)
+        let digit_width = font.get_string_size_ex("0").font_size(font_size).done().x;
+        let width = (digit_width * number_digits(editor.get_line_count()) as f32
+            + GUTTER_PADDING * 2.0)
+            .ceil() as i32;
+        if editor.get_gutter_width(gutter) != width {
+            editor.set_gutter_width(gutter, width);
+        }
+
+        let caret_line = editor.get_caret_line();
+        let text = relative_number(line, caret_line, self.relative_number_absolute).to_string();
+        let color = editor.get_theme_color(if line == caret_line {
+            "font_color"
+        } else {
+            "line_number_color"
+        });
+        let text_width = font.get_string_size_ex(&text).font_size(font_size).done().x;
+        let ascent = font.get_ascent_ex().font_size(font_size).done();
+        let height = font.get_height_ex().font_size(font_size).done();
+        let position = Vector2::new(
+            rect.position.x + rect.size.x - text_width - GUTTER_PADDING,
+            rect.position.y + (rect.size.y - height) / 2.0 + ascent,
+        );
+        editor
+            .draw_string_ex(&font, position, &text)
+            .font_size(font_size)
+            .modulate(color)
+            .done();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_number() {
+        assert_eq!(relative_number(9, 9, true), 10);
+        assert_eq!(relative_number(9, 9, false), 0);
+        assert_eq!(relative_number(4, 9, true), 5);
+        assert_eq!(relative_number(12, 9, true), 3);
+        assert_eq!(number_digits(42), 3);
+        assert_eq!(number_digits(12345), 5);
+    }
+}
@@ -0,0 +1,136 @@
+//! Replace with register (gr{motion}, Visual gr) and exchange (cx{motion}, cxx, cxc)
+//!
+//! Both make Neovim's `g@` an operator from replace.lua and hand it the motion:
+//! the edit is a single undo step synced back through buffer events, `.`
+//! repeats it, and replacing does not overwrite the unnamed register.
+//! `gr` is the replace operator only with the gr Behavior setting on Replace
+//! Operator (`grr` then lists the LSP references, like Neovim's default `grr`);
+//! by default `gr` keeps listing the references.
+
+use super::text_object::is_text_object_prefix;
+use super::GodotNeovimPlugin;
+use crate::settings::{self, GrBehavior};
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+
+/// Keys that make `g@` the exchange operator
+const EXCHANGE_OPERATOR_KEYS: &str = "<Cmd>lua require('godot_neovim').exchange_operator()<CR>";
+
+impl GodotNeovimPlugin {
+    /// Whether `gr` is the replace operator (gr Behavior setting)
+    pub(super) fn gr_is_replace_operator() -> bool {
+        settings::get_gr_behavior() == GrBehavior::ReplaceOperator
+    }
+
+    /// Send keys to Neovim, recording them in the macro being recorded
+    fn send_operator_keys(&mut self, keys: &str) {
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(keys.to_string());
+        }
+        self.send_keys(keys);
+    }
+
+    /// Count typed before the operator, as a prefix of `g@`
+    fn take_operator_count(&mut self) -> String {
+        self.take_count()
+            .map(|count| count.to_string())
+            .unwrap_or_default()
+    }
+
+    /// `gr` typed (`register`: `"` unless selected with `"{reg}`): replace the
+    /// Visual selection, or wait for the motion
+    pub(super) fn start_replace_operator(&mut self, register: char) {
+        self.replace_register = register;
+        if !self.is_in_visual_mode() {
+            self.set_last_key("gr");
+            return;
+        }
+
+        self.clear_last_key();
+        self.take_count();
+        let keys = format!("{}g@", self.replace_operator_keys());
+        self.send_operator_keys(&keys);
+    }
+
+    /// Motion after `gr` (`grr` is handled by the caller)
+    pub(super) fn finish_replace_operator(&mut self, key: &str) {
+        self.clear_last_key();
+        if is_text_object_prefix(key) {
+            let count = self.take_operator_count();
+            self.start_text_object(format!("{}g@", self.replace_operator_keys()), count, key);
+            return;
+        }
+        // The count goes to g@, after the <Cmd> that sets it up
+        let keys = format!(
+            "{}{}g@{}",
+            self.replace_operator_keys(),
+            self.take_operator_count(),
+            key
+        );
+        crate::verbose_print!("[godot-neovim] gr{}", key);
+        self.send_operator_keys(&keys);
+    }
+
+    /// Keys that make `g@` the replace operator for the selected register
+    fn replace_operator_keys(&self) -> String {
+        format!(
+            "<Cmd>lua require('godot_neovim').replace_operator('{}')<CR>",
+            self.replace_register
+        )
+    }
+
+    /// `x` after the `c` operator starts an exchange
+    /// Returns true if the key was consumed.
+    pub(super) fn try_start_exchange(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        if key_event.get_keycode() != Key::X
+            || key_event.is_shift_pressed()
+            || key_event.is_ctrl_pressed()
+            || key_event.is_alt_pressed()
+            || key_event.is_meta_pressed()
+            || self.is_in_visual_mode()
+            || self.last_key != "c"
+        {
+            return false;
+        }
+
+        // c is pending in Neovim; cx is sent as g@ once the motion is typed
+        self.cancel_pending_operator();
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push("<Esc>".to_string());
+        }
+        self.set_last_key("cx");
+        crate::verbose_print!("[godot-neovim] Exchange: cx");
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+        true
+    }
+
+    /// Key after `cx`: `x` exchanges the current line(s), `c` clears a pending
+    /// exchange, anything else starts the motion
+    pub(super) fn finish_exchange(&mut self, key: &str) {
+        self.clear_last_key();
+        if key == "c" {
+            self.take_count();
+            self.send_operator_keys("<Cmd>lua require('godot_neovim').exchange_clear()<CR>");
+            return;
+        }
+
+        if is_text_object_prefix(key) {
+            let count = self.take_operator_count();
+            self.start_text_object(format!("{}g@", EXCHANGE_OPERATOR_KEYS), count, key);
+            return;
+        }
+        let motion = if key == "x" { "_" } else { key };
+        let keys = format!(
+            "{}{}g@{}",
+            EXCHANGE_OPERATOR_KEYS,
+            self.take_operator_count(),
+            motion
+        );
+        crate::verbose_print!("[godot-neovim] cx{}", key);
+        self.send_operator_keys(&keys);
+    }
+}
@@ -0,0 +1,266 @@
+//! Scripting API: drive the plugin from GDScript (editor tests, tool scripts)
+//!
+//! `run_keys()` queues keys in Vim notation; they are fed one per frame through
+//! Godot's input pipeline, exactly like typed keys, after Neovim has processed
+//! the previous one. `wait_sync()` returns a signal emitted once all queued keys
+//! are handled and Neovim's updates are applied:
+//!
+//! ```gdscript
+//! plugin.run_keys("ciwfoo<Esc>")
+//! await plugin.wait_sync()
+//! assert(plugin.get_buffer_text().begins_with("foo"))
+//! ```
+
+use super::GodotNeovimPlugin;
+use godot::classes::{Input, InputEventKey};
+use godot::global::Key;
+use godot::prelude::*;
+
+/// Frames to wait after the last fed key before a sync can complete: the key is
+/// handled in the next frame and its keymap action runs deferred after that
+const SCRIPTED_KEY_SETTLE_FRAMES: u8 = 2;
+
+/// A key parsed from Vim notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScriptedKey {
+    keycode: Key,
+    /// Character typed by the key (None for special keys)
+    unicode: Option<char>,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+}
+
+impl ScriptedKey {
+    fn character(c: char) -> Self {
+        let keycode = if c.is_ascii_alphabetic() {
+            Key::from_ord(c.to_ascii_uppercase() as i32)
+        } else if c.is_ascii() {
+            Key::from_ord(c as i32)
+        } else {
+            Key::UNKNOWN
+        };
+        Self {
+            keycode,
+            unicode: Some(c),
+            ctrl: false,
+            alt: false,
+            shift: c.is_ascii_uppercase(),
+        }
+    }
+
+    fn special(keycode: Key) -> Self {
+        Self {
+            keycode,
+            unicode: None,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    fn to_event(self) -> Gd<InputEventKey> {
+        let mut event = InputEventKey::new_gd();
+        event.set_keycode(self.keycode);
+        event.set_physical_keycode(self.keycode);
+        // Ctrl/Alt combinations do not type a character
+        if let Some(c) = self.unicode.filter(|_| !self.ctrl && !self.alt) {
+            event.set_unicode(c as u32);
+        }
+        event.set_ctrl_pressed(self.ctrl);
+        event.set_alt_pressed(self.alt);
+        event.set_shift_pressed(self.shift);
+        event.set_pressed(true);
+        event
+    }
+}
+
+/// Key of a `<...>` name (without modifiers)
+fn special_key(name: &str) -> Option<ScriptedKey> {
+    let key = match name.to_ascii_lowercase().as_str() {
+        "esc" => Key::ESCAPE,
+        "cr" | "enter" | "return" => Key::ENTER,
+        "tab" => Key::TAB,
+        "bs" => Key::BACKSPACE,
+        "del" => Key::DELETE,
+        "up" => Key::UP,
+        "down" => Key::DOWN,
+        "left" => Key::LEFT,
+        "right" => Key::RIGHT,
+        "home" => Key::HOME,
+        "end" => Key::END,
+        "pageup" => Key::PAGEUP,
+        "pagedown" => Key::PAGEDOWN,
+        "space" => return Some(ScriptedKey::character(' ')),
+        "lt" => return Some(ScriptedKey::character('<')),
+        "bar" => return Some(ScriptedKey::character('|')),
+        "bslash" => return Some(ScriptedKey::character('\\')),
+        _ => {
+            let mut chars = name.chars();
+            let c = chars.next()?;
+            return chars.next().is_none().then(|| ScriptedKey::character(c));
+        }
+    };
+    Some(ScriptedKey::special(key))
+}
+
+/// Parse a `<...>` key (the text between the brackets), e.g. `C-r`, `Esc`, `A-S-w`
+fn parse_bracket_key(inner: &str) -> Option<ScriptedKey> {
+    let (mut ctrl, mut alt, mut shift) = (false, false, false);
+    let mut name = inner;
+    // Modifier prefixes; a name of one character may itself be "-" (e.g. <C-->)
+    while name.len() > 2 && name.as_bytes()[1] == b'-' {
+        match name.as_bytes()[0].to_ascii_uppercase() {
+            b'C' => ctrl = true,
+            b'A' | b'M' => alt = true,
+            b'S' => shift = true,
+            _ => return None,
+        }
+        name = &name[2..];
+    }
+
+    let mut key = special_key(name)?;
+    key.ctrl |= ctrl;
+    key.alt |= alt;
+    key.shift |= shift;
+    // <C-a> and <C-A> are the same key in Vim
+    if ctrl && key.unicode.is_some_and(|c| c.is_ascii_alphabetic()) {
+        key.unicode = key.unicode.map(|c| c.to_ascii_lowercase());
+        key.shift = shift;
+    }
+    Some(key)
+}
+
+/// Parse keys in Vim notation (`dd`, `ciwfoo<Esc>`, `<C-r>`, `<lt>`)
+/// A `<` that does not start a known key is typed literally.
+fn parse_keys(keys: &str) -> Vec<ScriptedKey> {
+    let mut parsed = Vec::new();
+    let mut rest = keys;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            if let Some(end) = rest[1..].find('>') {
+                if let Some(key) = parse_bracket_key(&rest[1..end + 1]) {
+                    parsed.push(key);
+                    rest = &rest[end + 2..];
+                    continue;
+                }
+            }
+        }
+        parsed.push(ScriptedKey::character(c));
+        rest = &rest[c.len_utf8()..];
+    }
+    parsed
+}
+
+impl GodotNeovimPlugin {
+    /// Queue keys (Vim notation) to be typed into the current editor
+    pub(super) fn run_keys_impl(&mut self, keys: &str) {
+        let parsed = parse_keys(keys);
+        crate::verbose_print!(
+            "[godot-neovim] run_keys: {:?} ({} keys)",
+            keys,
+            parsed.len()
+        );
+
+        // Typed keys only reach the plugin (and Insert mode text the CodeEdit)
+        // while the editor has focus
+        if let Some(ref mut editor) = self.current_editor {
+            if editor.is_instance_valid() && !editor.has_focus() {
+                editor.grab_focus();
+            }
+        }
+        self.scripted_keys
+            .extend(parsed.into_iter().map(ScriptedKey::to_event));
+    }
+
+    /// Feed the next queued key, once Neovim has processed the previous one
+    /// Called every frame from process().
+    pub(super) fn process_scripted_keys(&mut self) {
+        if self.scripted_keys.is_empty() {
+            self.scripted_keys_settle = self.scripted_keys_settle.saturating_sub(1);
+            if self.sync_requested && self.scripted_keys_settle == 0 {
+                self.sync_requested = false;
+                self.sync_with_neovim();
+                // Deferred: the awaiting script resumes outside of process()
+                self.base_mut()
+                    .call_deferred("emit_signal", &["sync_completed".to_variant()]);
+            }
+            return;
+        }
+
+        if self.scripted_keys_settle > 0 {
+            self.scripted_keys_settle -= 1;
+            return;
+        }
+
+        self.sync_with_neovim();
+        if let Some(event) = self.scripted_keys.pop_front() {
+            Input::singleton().parse_input_event(&event);
+            self.scripted_keys_settle = SCRIPTED_KEY_SETTLE_FRAMES;
+        }
+    }
+
+    /// Wait until Neovim has processed the keys sent so far and apply its updates
+    fn sync_with_neovim(&mut self) {
+        if let Some(neovim) = self.get_current_neovim() {
+            if let Ok(client) = neovim.try_lock() {
+                if let Err(e) = client.wait_for_input() {
+                    godot_warn!("[godot-neovim] {}", e);
+                }
+            }
+        }
+        self.process_neovim_updates();
+    }
+
+    /// Text of Neovim's current buffer (lines joined with "\n")
+    pub(super) fn get_buffer_text_impl(&self) -> GString {
+        let Some(neovim) = self.get_current_neovim() else {
+            return GString::new();
+        };
+        let Ok(client) = neovim.try_lock() else {
+            return GString::new();
+        };
+        match client.execute_lua_with_result(
+            "return table.concat(vim.api.nvim_buf_get_lines(0, 0, -1, false), '\\n')",
+        ) {
+            Ok(value) => GString::from(value.as_str().unwrap_or_default()),
+            Err(e) => {
+                godot_warn!("[godot-neovim] get_buffer_text: {}", e);
+                GString::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        let keys = parse_keys("cW<lt>x<Esc><C-r><A-w><Nope>");
+        let chars: Vec<Option<char>> = keys.iter().map(|k| k.unicode).collect();
+        assert_eq!(
+            chars,
+            vec![
+                Some('c'),
+                Some('W'),
+                Some('<'),
+                Some('x'),
+                None,
+                Some('r'),
+                Some('w'),
+                Some('<'),
+                Some('N'),
+                Some('o'),
+                Some('p'),
+                Some('e'),
+                Some('>'),
+            ]
+        );
+        assert!(keys[1].shift);
+        assert_eq!(keys[4].keycode, Key::ESCAPE);
+        assert!(keys[5].ctrl && keys[5].keycode == Key::R);
+        assert!(keys[6].alt && !keys[6].ctrl);
+    }
+}
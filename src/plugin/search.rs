@@ -1,85 +1,171 @@
-//! Search operations: character find, Neovim search
+//! Search operations: character find, Neovim search, incremental search
 
+use super::highlight::HighlightRange;
+use super::layout::is_command_chord;
+use super::search_index::SearchIndex;
 use super::{EditorType, GodotNeovimPlugin};
+use crate::neovim::{parse_line_matches, parse_matches, parse_search_state, RpcRequest};
+use crate::settings;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+use rmpv::Value;
+
+/// Maximum number of matches highlighted by incremental search
+const MAX_SEARCH_MATCHES: i64 = 1000;
+
+/// Split a typed search (without the leading '/' or '?') into the pattern and the
+/// offset after the first unescaped `delimiter` (`foo/e` -> `foo`, `e`)
+/// Neovim applies the offset; Godot only needs the pattern for incremental search.
+pub(super) fn split_search_offset(search: &str, delimiter: char) -> (&str, Option<&str>) {
+    let mut chars = search.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == delimiter {
+            return (&search[..i], Some(&search[i + c.len_utf8()..]));
+        }
+    }
+    (search, None)
+}
+
+/// Position (line, column) of the count-th `c` after (forward) or before the cursor
+/// for f/t/F/T, on the cursor line or, with `across_lines`, also on the following
+/// (preceding) lines
+fn find_char_position(
+    get_line: impl Fn(i32) -> String,
+    line_count: i32,
+    cursor: (i32, usize),
+    c: char,
+    forward: bool,
+    count: usize,
+    across_lines: bool,
+) -> Option<(i32, usize)> {
+    let (cursor_line, cursor_col) = cursor;
+    let lines: Vec<i32> = match (forward, across_lines) {
+        (_, false) => vec![cursor_line],
+        (true, true) => (cursor_line..line_count).collect(),
+        (false, true) => (0..=cursor_line).rev().collect(),
+    };
+
+    let mut remaining = count.max(1);
+    for line in lines {
+        let chars: Vec<char> = get_line(line).chars().collect();
+        let cols: Vec<usize> = match (forward, line == cursor_line) {
+            (true, true) => (cursor_col + 1..chars.len()).collect(),
+            (true, false) => (0..chars.len()).collect(),
+            (false, true) => (0..cursor_col.min(chars.len())).rev().collect(),
+            (false, false) => (0..chars.len()).rev().collect(),
+        };
+        for col in cols.into_iter().filter(|&col| chars[col] == c) {
+            remaining -= 1;
+            if remaining == 0 {
+                return Some((line, col));
+            }
+        }
+    }
+    None
+}
 
 impl GodotNeovimPlugin {
-    /// Find character forward on current line (f/t commands)
-    pub(super) fn find_char_forward(&mut self, c: char, till: bool) {
+    /// Find the count-th `c` forward (f/t) or backward (F/T) and remember it for ; and ,
+    /// With Find Across Lines, the search continues on the following (preceding)
+    /// lines, except for an operator (`dfx` stays on the line, like Vim). Returns true
+    /// if it was found on another line: Neovim's f/t only search the cursor line, so
+    /// Neovim's cursor was moved here and the keys must not be sent.
+    pub(super) fn find_char(&mut self, c: char, forward: bool, till: bool, count: i32) -> bool {
         let Some(ref editor) = self.current_editor else {
-            return;
+            return false;
         };
 
         let line_idx = editor.get_caret_line();
         let col_idx = editor.get_caret_column() as usize;
-        let line_text = editor.get_line(line_idx).to_string();
-        let chars: Vec<char> = line_text.chars().collect();
+        let across_lines = settings::get_find_across_lines() && self.current_mode != "operator";
+        let found = find_char_position(
+            |line| editor.get_line(line).to_string(),
+            editor.get_line_count(),
+            (line_idx, col_idx),
+            c,
+            forward,
+            count.max(1) as usize,
+            across_lines,
+        );
+        let key = match (forward, till) {
+            (true, false) => 'f',
+            (true, true) => 't',
+            (false, false) => 'F',
+            (false, true) => 'T',
+        };
+        let Some((line, i)) = found else {
+            crate::verbose_print!("[godot-neovim] {}: Character '{}' not found", key, c);
+            return false;
+        };
 
-        // Search for character after cursor
-        for (i, &ch) in chars.iter().enumerate().skip(col_idx + 1) {
-            if ch == c {
-                let target_col = if till { i - 1 } else { i };
-                self.move_cursor_to(line_idx, target_col as i32);
+        let target_col = match (forward, till) {
+            (true, true) => i.saturating_sub(1),
+            (false, true) => i + 1,
+            _ => i,
+        } as i32;
+        if self.should_preview_motion() {
+            self.move_cursor_to(line, target_col);
+        }
 
-                // Save for ; and ,
-                self.last_find_char = Some(c);
-                self.last_find_forward = true;
-                self.last_find_till = till;
+        // Save for ; and ,
+        self.last_find_char = Some(c);
+        self.last_find_forward = forward;
+        self.last_find_till = till;
 
-                crate::verbose_print!(
-                    "[godot-neovim] {}{}: Found '{}' at col {}",
-                    if till { "t" } else { "f" },
-                    c,
-                    c,
-                    target_col
-                );
-                return;
-            }
+        crate::verbose_print!(
+            "[godot-neovim] {}{}: Found '{}' at {}:{}",
+            key,
+            c,
+            c,
+            line,
+            target_col
+        );
+
+        if settings::get_clever_f() && self.current_mode != "operator" {
+            self.start_clever_f(c, (line, target_col), across_lines);
         }
 
-        crate::verbose_print!("[godot-neovim] f/t: Character '{}' not found", c);
+        if line == line_idx {
+            return false;
+        }
+        self.take_count();
+        self.jump_to_found_char(c, forward, till, line, target_col);
+        true
     }
 
-    /// Find character backward on current line (F/T commands)
-    pub(super) fn find_char_backward(&mut self, c: char, till: bool) {
-        let Some(ref editor) = self.current_editor else {
+    /// Move Neovim's cursor to a character found on another line, and make it the
+    /// character of ; and , in Neovim too
+    fn jump_to_found_char(&mut self, c: char, forward: bool, till: bool, line: i32, col: i32) {
+        let Some(neovim) = self.get_current_neovim() else {
             return;
         };
-
-        let line_idx = editor.get_caret_line();
-        let col_idx = editor.get_caret_column() as usize;
-        let line_text = editor.get_line(line_idx).to_string();
-        let chars: Vec<char> = line_text.chars().collect();
-
-        // Search for character before cursor
-        for i in (0..col_idx).rev() {
-            if chars[i] == c {
-                let target_col = if till { i + 1 } else { i };
-                self.move_cursor_to(line_idx, target_col as i32);
-
-                // Save for ; and ,
-                self.last_find_char = Some(c);
-                self.last_find_forward = false;
-                self.last_find_till = till;
-
-                crate::verbose_print!(
-                    "[godot-neovim] {}{}: Found '{}' at col {}",
-                    if till { "T" } else { "F" },
-                    c,
-                    c,
-                    target_col
-                );
-                return;
-            }
+        let Ok(client) = neovim.try_lock() else {
+            godot_warn!("[godot-neovim] f/t: Neovim is busy, cursor not moved");
+            return;
+        };
+        if let Err(e) = client.execute_lua_with_args(
+            "_G.godot_neovim.find_char_jump(...)",
+            vec![
+                rmpv::Value::from(c.to_string()),
+                rmpv::Value::from(forward),
+                rmpv::Value::from(till),
+                rmpv::Value::from(line as i64 + 1),
+                rmpv::Value::from(col as i64 + 1),
+            ],
+        ) {
+            godot_warn!("[godot-neovim] f/t: Failed to move the cursor: {}", e);
         }
-
-        crate::verbose_print!("[godot-neovim] F/T: Character '{}' not found", c);
     }
 
-    /// Repeat last f/F/t/T command (; and , commands)
-    pub(super) fn repeat_find_char(&mut self, same_direction: bool) {
+    /// Repeat last f/F/t/T command count times (; and , commands)
+    /// Returns true if Neovim's cursor was already moved (see find_char).
+    pub(super) fn repeat_find_char(&mut self, same_direction: bool, count: i32) -> bool {
         let Some(c) = self.last_find_char else {
             crate::verbose_print!("[godot-neovim] ;/,: No previous find");
-            return;
+            return false;
         };
 
         let forward = if same_direction {
@@ -89,20 +175,120 @@ impl GodotNeovimPlugin {
         };
         let till = self.last_find_till;
 
-        if forward {
-            self.find_char_forward(c, till);
+        let moved = self.find_char(c, forward, till, count);
+        // ; and , keep the direction of the original find
+        self.last_find_forward = if same_direction { forward } else { !forward };
+        moved
+    }
+
+    /// ; (same direction) and , (opposite direction): repeat the last f/F/t/T in
+    /// Godot and Neovim
+    pub(super) fn repeat_find(&mut self, same_direction: bool) {
+        let key = if same_direction { ";" } else { "," };
+        if !self.repeat_find_char(same_direction, self.peek_count()) {
+            self.send_keys(key);
+        }
+        // Record to local macro buffer (early return skips normal recording)
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(key.to_string());
+        }
+    }
+
+    /// Clever-f: remember where the find left the cursor and highlight the other
+    /// occurrences of the character that f/F/t/T reach from there
+    fn start_clever_f(&mut self, c: char, position: (i32, i32), across_lines: bool) {
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        let lines = if across_lines {
+            editor.get_first_visible_line()..=editor.get_last_full_visible_line()
         } else {
-            self.find_char_backward(c, till);
+            position.0..=position.0
+        };
+        let mut ranges = Vec::new();
+        for line in lines {
+            let text = editor.get_line(line).to_string();
+            ranges.extend(
+                text.chars()
+                    .enumerate()
+                    .filter(|&(_, ch)| ch == c)
+                    .map(|(col, _)| HighlightRange {
+                        line,
+                        start_col: col as i32,
+                        end_col: col as i32 + 1,
+                    }),
+            );
+        }
+        self.clever_f_position = Some(position);
+        self.find_char_highlights = ranges;
+        self.redraw_highlight_overlay();
+    }
+
+    /// End clever-f (another key, or the cursor moved away from the found character)
+    pub(super) fn end_clever_f(&mut self) {
+        if self.clever_f_position.take().is_none() {
+            return;
         }
+        self.find_char_highlights.clear();
+        self.redraw_highlight_overlay();
     }
 
-    /// Execute * or # word search: send to Neovim and sync cursor
+    /// Clever-f: f/F/t/T pressed while the cursor is still on the found character
+    /// repeat the last find, the key of the same case in its direction (like ;) and
+    /// the other case in the opposite one (like ,). Any other key ends clever-f.
+    /// Returns true if the key repeated the find.
+    pub(super) fn handle_clever_f_key(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        let Some(position) = self.clever_f_position else {
+            return false;
+        };
+        // Modifier-only key presses come before Shift+F
+        if matches!(
+            key_event.get_keycode(),
+            Key::SHIFT | Key::CTRL | Key::ALT | Key::META | Key::CAPSLOCK | Key::NUMLOCK
+        ) {
+            return false;
+        }
+
+        let key = if is_command_chord(key_event) {
+            None
+        } else {
+            self.vim_key(key_event)
+        };
+        let repeat = match key {
+            Some('f' | 'F') => !self.last_find_till,
+            Some('t' | 'T') => self.last_find_till,
+            _ => false,
+        };
+        let cursor = self
+            .current_editor
+            .as_ref()
+            .map(|editor| (editor.get_caret_line(), editor.get_caret_column()));
+        if !repeat || cursor != Some(position) {
+            self.end_clever_f();
+            return false;
+        }
+
+        self.repeat_find(key.is_some_and(|k| k.is_ascii_lowercase()));
+        true
+    }
+
+    /// Execute */# (whole word) or g*/g# (also inside other words) search: send to
+    /// Neovim and sync cursor
+    /// Neovim builds the pattern (`\<word\>` for */#) and puts it in the search
+    /// register, so n/N, 'hlsearch' and the [n/N] count use the same pattern.
     pub(super) fn search_word(&mut self, key: &str) {
         crate::verbose_print!("[godot-neovim] search_word: {}", key);
 
-        // Send * or # to Neovim synchronously and sync cursor
+        // Send the keys to Neovim synchronously and sync cursor
         // Must use synchronous input to ensure search completes before getting cursor
-        self.send_search_and_sync_cursor(key);
+        let keys = self.take_count_string() + key;
+        self.send_search_and_sync_cursor(&keys);
+        // Like Vim, 'ignorecase' applies but 'smartcase' is not used for the word
+        // under the cursor
+        self.search_smartcase = false;
+        self.refresh_hlsearch();
+        self.search_forward = key.ends_with('*');
+        self.request_search_count();
     }
 
     /// Execute n/N search: send to Neovim and sync cursor
@@ -112,7 +298,10 @@ impl GodotNeovimPlugin {
 
         // Send n or N to Neovim synchronously and sync cursor
         // Must use synchronous input to ensure search completes before getting cursor
-        self.send_search_and_sync_cursor(key);
+        let keys = self.take_count_string() + key;
+        self.send_search_and_sync_cursor(&keys);
+        self.refresh_hlsearch();
+        self.request_search_count();
     }
 
     /// Open search mode (/ for forward, ? for backward)
@@ -125,6 +314,28 @@ impl GodotNeovimPlugin {
         } else {
             "?".to_string()
         };
+        self.search_count_keys.clear();
+
+        // An operator already sent to Neovim waits for its motion (d/foo<CR>):
+        // the search is the motion
+        if matches!(self.last_key.as_str(), "d" | "c" | "y" | "=" | "gq")
+            || Self::is_operator_pending_mode(&self.current_mode)
+        {
+            self.search_operator = Some(self.last_key.clone());
+            self.clear_last_key();
+        } else {
+            // 3/foo: the third match
+            self.search_count_keys = self.take_count_string();
+        }
+
+        // Remember where the search started (incremental search moves the caret live)
+        self.search_start = self.current_editor.as_ref().map(|editor| {
+            (
+                editor.get_caret_line(),
+                editor.get_caret_column(),
+                editor.get_v_scroll(),
+            )
+        });
 
         // Show search prompt in mode label
         let label = match self.current_editor_type {
@@ -141,10 +352,26 @@ impl GodotNeovimPlugin {
         );
     }
 
+    /// Open search mode as the motion of an operator that is sent to Neovim first
+    /// (register-aware operators are only sent once the motion is known: "ad/foo<CR>)
+    pub(super) fn open_operator_search(&mut self, operator_keys: &str, forward: bool) {
+        self.send_keys(operator_keys);
+        self.clear_last_key();
+        self.open_search_mode(forward);
+        self.search_operator = Some(operator_keys.to_string());
+    }
+
     /// Close search mode
     pub(super) fn close_search_mode(&mut self) {
         self.search_mode = false;
         self.search_buffer.clear();
+        self.search_start = None;
+        self.search_operator = None;
+        self.search_history_index = None;
+        self.search_history_temp.clear();
+        // Incremental matches are replaced by the 'hlsearch' matches of the executed
+        // (or, when cancelled, the previous) search pattern
+        self.refresh_hlsearch();
 
         // Restore mode display
         let display_cursor = (self.current_cursor.0 + 1, self.current_cursor.1);
@@ -164,6 +391,327 @@ impl GodotNeovimPlugin {
         }
     }
 
+    /// Cancel search mode (Escape): restore the caret and scroll position from before
+    /// incremental search moved them
+    pub(super) fn cancel_search_mode(&mut self) {
+        // Abort the operator waiting in Neovim for the search motion
+        if self.search_operator.take().is_some() {
+            self.send_keys("<Esc>");
+        }
+        if let Some((line, col, scroll)) = self.search_start {
+            if let Some(ref mut editor) = self.current_editor {
+                self.syncing_from_grid = true;
+                editor.set_caret_line(line);
+                editor.set_caret_column(col);
+                editor.set_v_scroll(scroll);
+                self.syncing_from_grid = false;
+            }
+        }
+        self.close_search_mode();
+    }
+
+    /// Incremental search ('incsearch' + 'hlsearch'): highlight all matches of the
+    /// pattern typed so far and move the caret to the next match from the start position
+    /// Neovim's cursor is not moved until the search is executed with Enter.
+    /// The matches are searched after the keys sent so far (on_search_matches).
+    pub(super) fn update_incremental_search(&mut self) {
+        if self.search_start.is_none() {
+            return;
+        }
+        let pattern = self.typed_search_pattern();
+        if pattern.is_empty() {
+            self.show_incremental_matches(&[]);
+            return;
+        }
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            crate::verbose_print!("[godot-neovim] Mutex busy, skipping incremental search");
+            return;
+        };
+        if !client.request_matches(&pattern, MAX_SEARCH_MATCHES, true) {
+            crate::verbose_print!("[godot-neovim] Failed to queue incremental search");
+        }
+    }
+
+    /// Pattern typed in search mode, without the delimiter and the offset
+    fn typed_search_pattern(&self) -> String {
+        let delimiter = if self.search_forward { '/' } else { '?' };
+        let Some(search) = self.search_buffer.get(delimiter.len_utf8()..) else {
+            return String::new();
+        };
+        split_search_offset(search, delimiter).0.to_string()
+    }
+
+    /// Matches of an incremental search pattern from Neovim
+    /// Answers for a pattern that is no longer typed are dropped.
+    pub(super) fn on_search_matches(&mut self, pattern: &str, result: Result<Value, String>) {
+        if !self.search_mode || self.typed_search_pattern() != pattern {
+            return;
+        }
+        let matches = match result {
+            Ok(value) => parse_matches(value),
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Incremental search failed: {}", e);
+                Vec::new()
+            }
+        };
+        self.show_incremental_matches(&matches);
+    }
+
+    /// Highlight the matches of an incremental search and move the caret to the
+    /// next one from the start position
+    fn show_incremental_matches(&mut self, matches: &[(i64, i64, i64)]) {
+        let Some((start_line, start_col, start_scroll)) = self.search_start else {
+            return;
+        };
+        let ranges: Vec<HighlightRange> = matches
+            .iter()
+            .map(|&(line, start_col, end_col)| HighlightRange {
+                line: line as i32,
+                start_col: start_col as i32,
+                end_col: end_col as i32,
+            })
+            .collect();
+
+        // Next match after the start position (before it for ?), wrapping around
+        let start = (start_line, start_col);
+        let target = if self.search_forward {
+            ranges
+                .iter()
+                .find(|r| (r.line, r.start_col) > start)
+                .or_else(|| ranges.first())
+        } else {
+            ranges
+                .iter()
+                .rev()
+                .find(|r| (r.line, r.start_col) < start)
+                .or_else(|| ranges.last())
+        }
+        .map(|r| (r.line, r.start_col));
+
+        if let Some(ref mut editor) = self.current_editor {
+            self.syncing_from_grid = true;
+            match target {
+                Some((line, col)) => {
+                    editor.set_caret_line(line);
+                    editor.set_caret_column(col);
+                    editor.adjust_viewport_to_caret();
+                }
+                None => {
+                    // No match: back to where the search started
+                    editor.set_caret_line(start_line);
+                    editor.set_caret_column(start_col);
+                    editor.set_v_scroll(start_scroll);
+                }
+            }
+            self.syncing_from_grid = false;
+        }
+
+        self.set_search_highlights(ranges);
+    }
+
+    /// 'hlsearch': highlight all matches of Neovim's last search pattern
+    /// Neovim is the source of truth (@/ register, v:hlsearch), so :nohlsearch and
+    /// 'nohlsearch' clear the highlights until the next search.
+    /// The state is asked for after the keys sent so far and the highlights are
+    /// updated when the answers arrive (on_search_state, on_search_lines).
+    pub(super) fn refresh_hlsearch(&mut self) {
+        // Incremental search owns the highlights while typing a pattern
+        if self.search_mode {
+            return;
+        }
+
+        // No hlsearch overlay in large-file mode (indexing every match is too slow)
+        if self.is_large_file() {
+            self.hlsearch_active = false;
+            self.search_count_pending = false;
+            self.clear_search_highlights();
+            return;
+        }
+
+        // One refresh at a time: another one asked for meanwhile follows it
+        if self.hlsearch_query_pending {
+            self.hlsearch_refresh_again = true;
+            return;
+        }
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            crate::verbose_print!("[godot-neovim] Mutex busy, skipping hlsearch refresh");
+            return;
+        };
+        let queued = client.request_search_state(&self.current_script_path, self.search_smartcase);
+        drop(client);
+        self.hlsearch_query_pending = queued;
+    }
+
+    /// Search state from Neovim: bring the script's search index up to date with the
+    /// last search pattern. The index is rebuilt when the pattern (or its case)
+    /// changed, otherwise only the lines changed since the last update are searched.
+    pub(super) fn on_search_state(&mut self, path: &str, result: Result<Value, String>) {
+        let state = result.and_then(|value| parse_search_state(&value));
+        let (pattern, case_pattern, hlsearch) = match state {
+            Ok(state) => state,
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] hlsearch refresh failed: {}", e);
+                self.finish_hlsearch_refresh();
+                return;
+            }
+        };
+        // The script was switched meanwhile: its own refresh follows
+        if path != self.current_script_path {
+            self.finish_hlsearch_refresh();
+            return;
+        }
+        self.last_search_pattern = pattern;
+        self.hlsearch_active = hlsearch;
+
+        if case_pattern.is_empty() {
+            self.search_indexes.remove(path);
+            self.finish_hlsearch_refresh();
+            return;
+        }
+
+        let line_count = self
+            .current_editor
+            .as_ref()
+            .map_or(0, |editor| editor.get_line_count().max(0) as usize);
+        let index = self
+            .search_indexes
+            .entry(path.to_string())
+            .or_insert_with(|| SearchIndex::new(&case_pattern, line_count));
+        // Missed changes (line count differs) or a new pattern: search everything again
+        if index.pattern != case_pattern || index.line_count() != line_count {
+            *index = SearchIndex::new(&case_pattern, line_count);
+        }
+
+        let ranges = index.dirty_ranges();
+        let generation = index.generation();
+        if ranges.is_empty() {
+            self.finish_hlsearch_refresh();
+            return;
+        }
+        let queued = self.get_current_neovim().is_some_and(|neovim| {
+            neovim.try_lock().is_ok_and(|client| {
+                client.request_line_matches(path, &case_pattern, generation, ranges)
+            })
+        });
+        if !queued {
+            crate::verbose_print!("[godot-neovim] Failed to queue search index update");
+            self.finish_hlsearch_refresh();
+        }
+    }
+
+    /// Matches of the lines a search index still had to search
+    /// Dropped if the buffer changed since they were asked for (the lines are
+    /// searched again by the next refresh).
+    pub(super) fn on_search_lines(
+        &mut self,
+        path: &str,
+        pattern: &str,
+        generation: u64,
+        ranges: Vec<(usize, usize)>,
+        result: Result<Value, String>,
+    ) {
+        match result {
+            Ok(Value::Array(results)) => {
+                if let Some(index) = self
+                    .search_indexes
+                    .get_mut(path)
+                    .filter(|index| index.pattern == pattern && index.generation() == generation)
+                {
+                    for ((first, _), lines) in ranges.into_iter().zip(results) {
+                        let matches = parse_line_matches(lines)
+                            .into_iter()
+                            .map(|line| {
+                                line.into_iter()
+                                    .map(|(start, end)| (start as i32, end as i32))
+                                    .collect()
+                            })
+                            .collect();
+                        index.fill(first, matches);
+                    }
+                }
+            }
+            Ok(_) => {
+                crate::verbose_print!("[godot-neovim] Search index update: invalid result");
+            }
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Search index update failed: {}", e);
+            }
+        }
+        self.finish_hlsearch_refresh();
+    }
+
+    /// The answers of a 'hlsearch' refresh are in: show the highlights (and the
+    /// search count asked for), then run the refresh asked for meanwhile
+    fn finish_hlsearch_refresh(&mut self) {
+        self.hlsearch_query_pending = false;
+
+        let index = self.search_indexes.get(&self.current_script_path);
+        let ranges = match index {
+            Some(index) if self.hlsearch_active => index.highlight_ranges(),
+            _ => Vec::new(),
+        };
+        if !self.search_mode {
+            if ranges.is_empty() {
+                self.clear_search_highlights();
+            } else {
+                self.set_search_highlights(ranges);
+            }
+        }
+
+        if std::mem::take(&mut self.hlsearch_refresh_again) {
+            self.refresh_hlsearch();
+        } else if std::mem::take(&mut self.search_count_pending) {
+            self.show_search_count();
+        }
+    }
+
+    /// Keep the search indexes in sync with a buffer change from Neovim
+    /// (lines first..last replaced by `new_count` lines)
+    pub(super) fn on_search_index_lines(&mut self, first: i64, last: i64, new_count: usize) {
+        if let Some(index) = self.search_indexes.get_mut(&self.current_script_path) {
+            index.on_lines(first, last, new_count);
+        }
+    }
+
+    /// Show the search pattern with Vim's [n/N] match count in the status line
+    /// once the 'hlsearch' refresh asked for with the search is answered
+    fn request_search_count(&mut self) {
+        self.search_count_pending = true;
+        if !self.hlsearch_query_pending {
+            self.refresh_hlsearch();
+        }
+    }
+
+    /// Show the search pattern with Vim's [n/N] match count in the status line
+    fn show_search_count(&mut self) {
+        let Some(index) = self.search_indexes.get(&self.current_script_path) else {
+            return;
+        };
+        if !index.is_complete() {
+            return;
+        }
+
+        let total = index.total();
+        let (line, col) = self.current_cursor;
+        let position = index.position(line.max(0) as usize, col as i32);
+        let prefix = if self.search_forward { '/' } else { '?' };
+        let msg = if total == 0 {
+            format!("E486: Pattern not found: {}", self.last_search_pattern)
+        } else {
+            format!(
+                "{}{}  [{}/{}]",
+                prefix, self.last_search_pattern, position, total
+            )
+        };
+        self.show_status_message(&msg);
+    }
+
     /// Execute the search: send to Neovim and sync cursor
     pub(super) fn execute_search(&mut self) {
         let search_pattern = self.search_buffer.clone();
@@ -175,19 +723,35 @@ impl GodotNeovimPlugin {
         }
 
         crate::verbose_print!("[godot-neovim] Executing search: {}", search_pattern);
+        self.add_search_history(&search_pattern[1..]);
+        // A typed pattern uses 'smartcase' (also for n/N and 'hlsearch' afterwards)
+        self.search_smartcase = true;
+
+        // Operator + search motion: Neovim applies the change, which comes back through
+        // buffer events and redraw (mode/cursor) like any other operator + motion
+        if let Some(operator) = self.search_operator.take() {
+            crate::verbose_print!(
+                "[godot-neovim] Search as motion for operator '{}'",
+                operator
+            );
+            self.send_keys(&format!("{}\r", search_pattern));
+            self.close_search_mode();
+            return;
+        }
 
-        // Send search command to Neovim with Enter synchronously and sync cursor
-        let nvim_cmd = format!("{}\r", search_pattern);
+        // Send search command (with its offset, e.g. /foo/e) to Neovim with Enter
+        // synchronously and sync cursor
+        let nvim_cmd = format!("{}{}\r", self.search_count_keys, search_pattern);
         self.send_search_and_sync_cursor(&nvim_cmd);
 
         self.close_search_mode();
+        self.request_search_count();
     }
 
-    /// Send search command to Neovim synchronously and sync cursor
+    /// Send a search command to Neovim and sync the cursor to where it lands
     ///
-    /// This function uses synchronous input instead of the async channel to ensure
-    /// the search command is fully processed by Neovim before getting the cursor position.
-    /// Without this, the cursor position returned would be from BEFORE the search.
+    /// A cursor query is queued right after the keys, so it is answered once Neovim
+    /// has run the search (on_search_cursor) without waiting for it here.
     fn send_search_and_sync_cursor(&mut self, keys: &str) {
         let Some(neovim) = self.get_current_neovim() else {
             return;
@@ -198,18 +762,29 @@ impl GodotNeovimPlugin {
             return;
         };
 
-        // Send keys synchronously (waits for RPC acknowledgment)
-        if let Err(e) = client.input(keys) {
-            crate::verbose_print!("[godot-neovim] Failed to send search keys: {}", e);
-            return;
+        if !client.send_key_via_channel(keys)
+            || !client.request_lua(
+                RpcRequest::SearchCursor,
+                "return vim.api.nvim_win_get_cursor(0)",
+                vec![],
+            )
+        {
+            crate::verbose_print!("[godot-neovim] Failed to queue search keys: {}", keys);
         }
+        drop(client);
+        // The search is the motion of a pending operator (d/foo<CR>)
+        self.note_keys_sent(keys);
+    }
 
-        // Poll to ensure Neovim processes the input and updates cursor
-        // This gives Neovim time to execute the search and update state
-        client.poll();
-
-        // Get cursor position from Neovim
-        match client.get_cursor() {
+    /// Neovim's cursor after a search command: move the caret there and center it
+    pub(super) fn on_search_cursor(&mut self, result: Result<Value, String>) {
+        let cursor = result.and_then(|value| {
+            let position = value
+                .as_array()
+                .and_then(|pos| Some((pos.first()?.as_i64()?, pos.get(1)?.as_i64()?)));
+            position.ok_or_else(|| "Invalid cursor".to_string())
+        });
+        match cursor {
             Ok((line, col)) => {
                 crate::verbose_print!(
                     "[godot-neovim] Search cursor from Neovim: ({}, {})",
@@ -217,9 +792,6 @@ impl GodotNeovimPlugin {
                     col
                 );
 
-                // Drop the lock before accessing editor
-                drop(client);
-
                 // Update Godot editor cursor (Neovim uses 1-indexed lines)
                 if let Some(ref mut editor) = self.current_editor {
                     // Set flag to prevent on_caret_changed from triggering sync back
@@ -264,3 +836,45 @@ impl GodotNeovimPlugin {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_search_offset() {
+        assert_eq!(split_search_offset("foo", '/'), ("foo", None));
+        assert_eq!(split_search_offset("foo/e", '/'), ("foo", Some("e")));
+        assert_eq!(split_search_offset("foo/b+2", '/'), ("foo", Some("b+2")));
+        assert_eq!(
+            split_search_offset("a\\/b/e-1", '/'),
+            ("a\\/b", Some("e-1"))
+        );
+        assert_eq!(split_search_offset("a/b?s", '?'), ("a/b", Some("s")));
+        assert_eq!(split_search_offset("foo/", '/'), ("foo", Some("")));
+    }
+
+    #[test]
+    fn test_find_char_position() {
+        let lines = ["a.b.c", "x.y", ".z"];
+        let find = |cursor, forward, count, across_lines| {
+            find_char_position(
+                |line| lines[line as usize].to_string(),
+                lines.len() as i32,
+                cursor,
+                '.',
+                forward,
+                count,
+                across_lines,
+            )
+        };
+        assert_eq!(find((0, 0), true, 1, false), Some((0, 1)));
+        assert_eq!(find((0, 0), true, 2, false), Some((0, 3)));
+        assert_eq!(find((0, 3), true, 1, false), None);
+        assert_eq!(find((0, 3), true, 1, true), Some((1, 1)));
+        assert_eq!(find((0, 3), true, 2, true), Some((2, 0)));
+        assert_eq!(find((2, 1), false, 1, false), Some((2, 0)));
+        assert_eq!(find((2, 0), false, 1, true), Some((1, 1)));
+        assert_eq!(find((2, 0), false, 3, true), Some((0, 1)));
+    }
+}
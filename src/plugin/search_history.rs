@@ -0,0 +1,210 @@
+//! Search history: the patterns of / and ? searches, browsed with Up/Down in search
+//! mode and listed by `q/` / `q?`
+//!
+//! The history is kept in the project metadata, so it survives restarting the
+//! editor. A new Neovim instance gets the last pattern as its search register, so
+//! n/N continue the previous session's search (without highlighting it again).
+
+use super::picker::{PickerItem, PickerSource};
+use super::search::split_search_offset;
+use super::GodotNeovimPlugin;
+use godot::classes::EditorInterface;
+use godot::prelude::*;
+
+/// Project metadata section/key for the persisted search history
+const METADATA_SECTION: &str = "godot_neovim";
+const METADATA_KEY: &str = "search_history";
+
+/// Maximum number of remembered search patterns
+const SEARCH_HISTORY_MAX: usize = 100;
+
+/// Add `pattern` as the newest entry (an older copy of it is removed, like Vim)
+fn push_history(history: &mut Vec<String>, pattern: &str) {
+    history.retain(|entry| entry != pattern);
+    history.push(pattern.to_string());
+    if history.len() > SEARCH_HISTORY_MAX {
+        history.drain(..history.len() - SEARCH_HISTORY_MAX);
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Remember an executed search pattern (without the '/' or '?')
+    pub(super) fn add_search_history(&mut self, pattern: &str) {
+        push_history(&mut self.search_history, pattern);
+        self.save_search_history();
+    }
+
+    /// Browse search history (older), keeping the '/' or '?' of the prompt
+    pub(super) fn search_history_up(&mut self) {
+        if self.search_history.is_empty() {
+            return;
+        }
+
+        match self.search_history_index {
+            None => {
+                // Save current input and start browsing
+                self.search_history_temp = self.search_buffer.chars().skip(1).collect();
+                self.search_history_index = Some(self.search_history.len() - 1);
+            }
+            Some(0) => {
+                // Already at oldest
+                return;
+            }
+            Some(idx) => {
+                self.search_history_index = Some(idx - 1);
+            }
+        }
+
+        if let Some(idx) = self.search_history_index {
+            let pattern = self.search_history[idx].clone();
+            self.set_search_input(&pattern);
+        }
+    }
+
+    /// Browse search history (newer)
+    pub(super) fn search_history_down(&mut self) {
+        let Some(idx) = self.search_history_index else {
+            return;
+        };
+
+        let pattern = if idx >= self.search_history.len() - 1 {
+            // Return to current input
+            self.search_history_index = None;
+            self.search_history_temp.clone()
+        } else {
+            self.search_history_index = Some(idx + 1);
+            self.search_history[idx + 1].clone()
+        };
+        self.set_search_input(&pattern);
+    }
+
+    /// Replace the pattern typed after the '/' or '?'
+    fn set_search_input(&mut self, pattern: &str) {
+        let prompt = if self.search_forward { '/' } else { '?' };
+        self.search_buffer = format!("{}{}", prompt, pattern);
+        self.update_search_display();
+        self.update_incremental_search();
+    }
+
+    /// q/ and q? - Pick a pattern of the search history and search it forward/backward
+    pub(super) fn show_search_history_picker(&mut self, forward: bool) {
+        if self.search_history.is_empty() {
+            self.show_status_message("Search history is empty");
+            return;
+        }
+
+        // Newest first
+        let items: Vec<PickerItem> = self
+            .search_history
+            .iter()
+            .rev()
+            .map(|pattern| PickerItem {
+                label: pattern.clone(),
+                path: String::new(),
+                position: None,
+            })
+            .collect();
+        self.show_picker_with_source(
+            if forward {
+                "Search History (/)"
+            } else {
+                "Search History (?)"
+            },
+            items,
+            PickerSource::SearchHistory { forward },
+        );
+    }
+
+    /// Search `pattern` as if typed after / or ?
+    pub(super) fn search_pattern(&mut self, pattern: &str, forward: bool) {
+        self.open_search_mode(forward);
+        self.set_search_input(pattern);
+        self.execute_search();
+    }
+
+    /// Load the search history from the project metadata
+    pub(super) fn load_search_history(&mut self) {
+        let Some(settings) = EditorInterface::singleton().get_editor_settings() else {
+            return;
+        };
+        let stored = settings
+            .get_project_metadata_ex(METADATA_SECTION, METADATA_KEY)
+            .default(&PackedStringArray::new().to_variant())
+            .done();
+        let Ok(patterns) = stored.try_to::<PackedStringArray>() else {
+            return;
+        };
+
+        self.search_history = patterns
+            .as_slice()
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+        crate::verbose_print!(
+            "[godot-neovim] Loaded {} search pattern(s)",
+            self.search_history.len()
+        );
+    }
+
+    /// Persist the search history to the project metadata
+    fn save_search_history(&self) {
+        let Some(mut settings) = EditorInterface::singleton().get_editor_settings() else {
+            return;
+        };
+        let patterns: PackedStringArray = self
+            .search_history
+            .iter()
+            .map(|pattern| GString::from(pattern.as_str()))
+            .collect();
+        settings.set_project_metadata(METADATA_SECTION, METADATA_KEY, &patterns.to_variant());
+    }
+
+    /// Put the last search pattern into the search register of both Neovim instances
+    /// (after start), so n/N work without searching first
+    pub(super) fn restore_last_search_pattern(&mut self) {
+        let Some(search) = self.search_history.last() else {
+            return;
+        };
+        // The offset of the last search (/foo/e) is not restored
+        let (pattern, _) = split_search_offset(search, '/');
+        for neovim in [&self.script_neovim, &self.shader_neovim]
+            .into_iter()
+            .flatten()
+        {
+            let Ok(client) = neovim.try_lock() else {
+                continue;
+            };
+            if let Err(e) = client.execute_lua_with_args(
+                "vim.fn.setreg('/', ...); vim.v.hlsearch = 0",
+                vec![rmpv::Value::from(pattern)],
+            ) {
+                godot_warn!("[godot-neovim] Failed to restore the search pattern: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_history() {
+        let mut history = Vec::new();
+        push_history(&mut history, "foo");
+        push_history(&mut history, "bar");
+        push_history(&mut history, "foo");
+        assert_eq!(history, vec!["bar", "foo"]);
+
+        for i in 0..SEARCH_HISTORY_MAX {
+            push_history(&mut history, &i.to_string());
+        }
+        assert_eq!(history.len(), SEARCH_HISTORY_MAX);
+        assert_eq!(history.first().map(String::as_str), Some("0"));
+        assert_eq!(
+            history.last().cloned(),
+            Some((SEARCH_HISTORY_MAX - 1).to_string())
+        );
+    }
+}
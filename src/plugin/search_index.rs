@@ -0,0 +1,170 @@
+//! Search match index: matches of the last search pattern per line of a buffer
+//!
+//! Built once per pattern with Neovim's regex engine, then kept up to date from
+//! buffer line events: only the lines an edit touched are marked dirty and
+//! re-queried, so 'hlsearch' and the [n/N] count stay cheap on huge files.
+
+use super::highlight::HighlightRange;
+
+/// Matches of one line: (start_col, end_col), character columns, end exclusive
+type LineMatches = Vec<(i32, i32)>;
+
+/// Matches of a search pattern per line (None: line changed since it was searched)
+#[derive(Debug, Clone)]
+pub(super) struct SearchIndex {
+    /// Pattern with its case flag applied (rebuild when the pattern or case changes)
+    pub pattern: String,
+    lines: Vec<Option<LineMatches>>,
+    /// Increased by every buffer change, so matches searched before it are dropped
+    generation: u64,
+}
+
+impl SearchIndex {
+    /// Create an index with every line still to be searched
+    pub(super) fn new(pattern: &str, line_count: usize) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            lines: vec![None; line_count],
+            generation: 0,
+        }
+    }
+
+    /// Number of lines covered
+    pub(super) fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Number of buffer changes applied since the index was created
+    pub(super) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Apply a buffer change: lines first..last (-1: to the end) were replaced by
+    /// `new_count` lines, which must be searched again
+    pub(super) fn on_lines(&mut self, first: i64, last: i64, new_count: usize) {
+        let len = self.lines.len();
+        let first = (first.max(0) as usize).min(len);
+        let last = if last < 0 {
+            len
+        } else {
+            (last as usize).clamp(first, len)
+        };
+        self.lines
+            .splice(first..last, std::iter::repeat_n(None, new_count));
+        self.generation += 1;
+    }
+
+    /// Ranges of lines still to be searched: (first, last), last exclusive
+    pub(super) fn dirty_ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut start = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            match (line.is_none(), start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    ranges.push((s, i));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            ranges.push((s, self.lines.len()));
+        }
+        ranges
+    }
+
+    /// Store the matches of lines first.. (one entry per line)
+    pub(super) fn fill(&mut self, first: usize, matches: Vec<LineMatches>) {
+        for (line, line_matches) in self.lines.iter_mut().skip(first).zip(matches) {
+            *line = Some(line_matches);
+        }
+    }
+
+    /// Whether every line has been searched
+    pub(super) fn is_complete(&self) -> bool {
+        self.lines.iter().all(Option::is_some)
+    }
+
+    /// All matches as highlight ranges, ordered by position
+    pub(super) fn highlight_ranges(&self) -> Vec<HighlightRange> {
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line, matches)| {
+                matches
+                    .iter()
+                    .flatten()
+                    .map(move |&(start_col, end_col)| HighlightRange {
+                        line: line as i32,
+                        start_col,
+                        end_col,
+                    })
+            })
+            .collect()
+    }
+
+    /// Total number of matches
+    pub(super) fn total(&self) -> usize {
+        self.lines.iter().flatten().map(Vec::len).sum()
+    }
+
+    /// 1-based index of the match at or before the cursor (0 if the cursor is before
+    /// the first match), as shown in Vim's [n/N] search count
+    pub(super) fn position(&self, line: usize, col: i32) -> usize {
+        let before: usize = self.lines.iter().take(line).flatten().map(Vec::len).sum();
+        let on_line = self
+            .lines
+            .get(line)
+            .and_then(Option::as_ref)
+            .map_or(0, |matches| {
+                matches.iter().filter(|&&(start, _)| start <= col).count()
+            });
+        before + on_line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete_index(lines: Vec<LineMatches>) -> SearchIndex {
+        let mut index = SearchIndex::new("\\cfoo", lines.len());
+        index.fill(0, lines);
+        index
+    }
+
+    #[test]
+    fn test_on_lines_marks_changed_lines_dirty() {
+        let mut index = complete_index(vec![vec![(0, 3)], vec![], vec![(2, 5)], vec![]]);
+        assert!(index.is_complete());
+
+        // Line 1 replaced by three lines
+        index.on_lines(1, 2, 3);
+        assert_eq!(index.generation(), 1);
+        assert_eq!(index.line_count(), 6);
+        assert_eq!(index.dirty_ranges(), vec![(1, 4)]);
+        assert_eq!(index.total(), 2);
+
+        index.fill(1, vec![vec![], vec![(1, 4)], vec![]]);
+        assert!(index.is_complete());
+        assert_eq!(index.total(), 3);
+
+        // Delete to the end of the buffer
+        index.on_lines(4, -1, 0);
+        assert_eq!(index.line_count(), 4);
+        assert!(index.is_complete());
+        assert_eq!(index.total(), 2);
+    }
+
+    #[test]
+    fn test_position_counts_matches_before_cursor() {
+        let index = complete_index(vec![vec![(0, 3), (6, 9)], vec![], vec![(2, 5)]]);
+        assert_eq!(index.total(), 3);
+        assert_eq!(index.position(0, 0), 1);
+        assert_eq!(index.position(0, 6), 2);
+        assert_eq!(index.position(1, 0), 2);
+        assert_eq!(index.position(2, 2), 3);
+        assert_eq!(index.position(2, 1), 2);
+    }
+}
@@ -0,0 +1,99 @@
+//! Pending command display, like Vim's 'showcmd'
+//!
+//! The part of a Normal/Visual mode command typed so far (register, count,
+//! operator or prefix key, pending f/t/r, mark, macro, surround and sneak keys,
+//! keys held for a user mapping) is shown next to the mode label, e.g. `"a3d`. It
+//! is rebuilt every frame from the pending state, so it disappears when the
+//! command completes, is cancelled or times out.
+
+use super::leader::LEADER_PREFIX;
+use super::sneak::SneakState;
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::prelude::*;
+
+/// Name of the showcmd label in a status bar
+const SHOWCMD_LABEL_NAME: &str = "NeovimShowcmdLabel";
+
+impl GodotNeovimPlugin {
+    /// Keys of the command being typed ("" if none)
+    fn pending_command_text(&self) -> String {
+        if self.command_mode || self.search_mode {
+            return String::new();
+        }
+        let mut text = String::new();
+        match self.selected_register {
+            // Waiting for the register name
+            Some('\0') => text.push('"'),
+            Some(register) => {
+                text.push('"');
+                text.push(register);
+            }
+            None => {}
+        }
+        text.push_str(&self.count_buffer);
+        text.push_str(&self.last_key);
+        let pending_ops = [
+            self.pending_char_op,
+            self.pending_mark_op,
+            self.pending_macro_op,
+        ];
+        text.extend(pending_ops.into_iter().flatten());
+        if let Some(ref keys) = self.pending_surround {
+            text.push_str(keys);
+        }
+        if let Some(SneakState::Chars(ref chars)) = self.pending_sneak {
+            text.push('s');
+            text.push_str(chars);
+        }
+        if let Some(ref pending) = self.pending_text_object {
+            text.push_str(&pending.object);
+        }
+        for (_, keys) in &self.user_mapping_pending {
+            text.push_str(keys);
+        }
+        // The leader as configured (`<Space>ff`)
+        if text.contains(LEADER_PREFIX) {
+            text = text.replace(LEADER_PREFIX, &settings::get_leader_key());
+        }
+        text
+    }
+
+    /// Show the pending command if it changed (called every frame)
+    pub(super) fn update_showcmd(&mut self) {
+        let text = self.pending_command_text();
+        if text == self.showcmd {
+            return;
+        }
+        self.showcmd = text;
+
+        self.showcmd_labels
+            .retain(|label| label.is_instance_valid());
+        for label in self.showcmd_labels.iter_mut() {
+            label.set_visible(false);
+        }
+        if self.showcmd.is_empty() {
+            return;
+        }
+        // Right after the mode label
+        let Some((mut label, created)) = self.status_bar_label(SHOWCMD_LABEL_NAME, 1) else {
+            return;
+        };
+        if created {
+            label.add_theme_color_override("font_color", Color::from_rgb(1.0, 1.0, 1.0));
+            self.showcmd_labels.push(label.clone());
+        }
+        label.set_text(&format!(" {} ", self.showcmd));
+        label.set_visible(true);
+    }
+
+    /// Free the showcmd labels (plugin deactivation)
+    pub(super) fn remove_showcmd_labels(&mut self) {
+        self.showcmd.clear();
+        for mut label in self.showcmd_labels.drain(..) {
+            if label.is_instance_valid() {
+                label.queue_free();
+            }
+        }
+    }
+}
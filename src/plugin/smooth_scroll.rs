@@ -0,0 +1,103 @@
+//! Smooth scrolling of large viewport jumps (gg, G, Ctrl+F, search)
+//!
+//! When Neovim moves the viewport by more than a screen, the CodeEdit scroll is
+//! animated from where it was to the new top line over the Smooth Scroll Duration,
+//! driven from process(). Smaller moves and a disabled setting snap as before.
+
+use super::GodotNeovimPlugin;
+use crate::settings;
+use std::time::{Duration, Instant};
+
+/// A running scroll animation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SmoothScroll {
+    /// Scroll position (CodeEdit v_scroll) the animation starts from
+    from: f64,
+    /// Scroll position of the target top line
+    to: f64,
+    /// Target first visible line (0-indexed), set exactly at the end
+    topline: i32,
+    started: Instant,
+    duration: Duration,
+}
+
+/// Ease-out cubic: fast at first, slowing down into the target
+fn ease_out(t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    1.0 - (1.0 - t).powi(3)
+}
+
+impl GodotNeovimPlugin {
+    /// Scroll the current editor so `topline` is the first visible line, animated
+    /// from `scroll_before` if that is more than a screen away
+    pub(super) fn scroll_to_topline(&mut self, topline: i32, scroll_before: f64) {
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        let to = editor.get_scroll_pos_for_line(topline);
+        let screen = editor.get_visible_line_count() as f64;
+        let duration = Duration::from_millis(settings::get_smooth_scroll_duration());
+
+        if !settings::get_smooth_scroll()
+            || duration.is_zero()
+            || (to - scroll_before).abs() <= screen
+        {
+            self.smooth_scroll = None;
+            editor.set_line_as_first_visible(topline);
+            return;
+        }
+
+        crate::verbose_print!(
+            "[godot-neovim] Smooth scroll: {:.1} -> {:.1} (line {})",
+            scroll_before,
+            to,
+            topline
+        );
+        // Undo the jump of the caret sync; the animation starts where the view was
+        editor.set_v_scroll(scroll_before);
+        self.smooth_scroll = Some(SmoothScroll {
+            from: scroll_before,
+            to,
+            topline,
+            started: Instant::now(),
+            duration,
+        });
+    }
+
+    /// Advance the scroll animation (called every frame while one runs)
+    pub(super) fn poll_smooth_scroll(&mut self) {
+        let Some(scroll) = self.smooth_scroll else {
+            return;
+        };
+        let Some(ref mut editor) = self.current_editor else {
+            self.smooth_scroll = None;
+            return;
+        };
+        if !editor.is_instance_valid() {
+            self.smooth_scroll = None;
+            return;
+        }
+
+        let t = scroll.started.elapsed().as_secs_f64() / scroll.duration.as_secs_f64();
+        if t >= 1.0 {
+            self.smooth_scroll = None;
+            editor.set_line_as_first_visible(scroll.topline);
+            return;
+        }
+        editor.set_v_scroll(scroll.from + (scroll.to - scroll.from) * ease_out(t));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ease_out() {
+        assert_eq!(ease_out(0.0), 0.0);
+        assert_eq!(ease_out(1.0), 1.0);
+        assert_eq!(ease_out(2.0), 1.0);
+        assert!(ease_out(0.5) > 0.5);
+        assert!(ease_out(0.25) < ease_out(0.5));
+    }
+}
@@ -0,0 +1,205 @@
+//! Two-character jump (vim-sneak / EasyMotion style `s{char}{char}`)
+//!
+//! `s` and two characters label every occurrence of the two characters on the
+//! visible lines; typing a label jumps there (a single occurrence is jumped to at
+//! once, and any other key ends the sneak and is handled normally). The labels are
+//! drawn by the highlight overlay. The jump is made in Neovim with a jumplist
+//! entry, so Ctrl+O returns. With the Sneak setting off, `s` substitutes the
+//! character as in Vim.
+
+use super::layout::{is_command_chord, typed_char};
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+
+/// Label characters, the easiest to reach first
+const SNEAK_LABELS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// An occurrence of the sneak characters with its label
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SneakTarget {
+    /// Key that jumps to the occurrence
+    pub label: char,
+    /// Line (0-indexed)
+    pub line: i32,
+    /// Column of the first character (0-indexed, character position)
+    pub col: i32,
+}
+
+/// Input of a pending sneak
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum SneakState {
+    /// Characters typed so far (fewer than two)
+    Chars(String),
+    /// Occurrences labeled on screen, waiting for a label
+    Labels(Vec<SneakTarget>),
+}
+
+/// Character columns where `pattern` starts in `text`
+fn find_occurrences(text: &str, pattern: &[char]) -> Vec<i32> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| *window == pattern)
+        .map(|(col, _)| col as i32)
+        .collect()
+}
+
+/// Label the occurrences nearest to the cursor first (as many as there are labels)
+fn label_targets(mut positions: Vec<(i32, i32)>, cursor: (i32, i32)) -> Vec<SneakTarget> {
+    positions.sort_by_key(|&(line, col)| ((line - cursor.0).abs(), (col - cursor.1).abs()));
+    positions
+        .into_iter()
+        .zip(SNEAK_LABELS.chars())
+        .map(|((line, col), label)| SneakTarget { label, line, col })
+        .collect()
+}
+
+impl GodotNeovimPlugin {
+    /// `s` in Normal mode: wait for the two characters to jump to (substitute the
+    /// character with the Sneak setting off, and the selection in Visual mode)
+    pub(super) fn start_sneak(&mut self) {
+        if !settings::get_sneak() || self.is_in_visual_mode() {
+            self.action_send_keys_impl("s");
+            return;
+        }
+        // A count has no meaning for the jump
+        self.take_count();
+        self.clear_pending_input_states();
+        self.pending_sneak = Some(SneakState::Chars(String::new()));
+        crate::verbose_print!("[godot-neovim] Sneak: waiting for two characters");
+    }
+
+    /// Collect the keys of a pending sneak: the two characters, then a label
+    /// Returns true if the key was consumed.
+    pub(in crate::plugin) fn handle_pending_sneak(
+        &mut self,
+        key_event: &Gd<InputEventKey>,
+    ) -> bool {
+        let Some(state) = self.pending_sneak.take() else {
+            return false;
+        };
+
+        // Ignore modifier-only key presses - wait for the actual character
+        if matches!(
+            key_event.get_keycode(),
+            Key::SHIFT | Key::CTRL | Key::ALT | Key::META | Key::CAPSLOCK | Key::NUMLOCK
+        ) {
+            self.pending_sneak = Some(state);
+            return false;
+        }
+
+        // Escape, modifier combinations and non-printable keys end the sneak
+        let c = typed_char(key_event).filter(|_| !is_command_chord(key_event));
+        let Some(c) = c else {
+            crate::verbose_print!("[godot-neovim] Sneak cancelled");
+            self.redraw_highlight_overlay();
+            return false;
+        };
+
+        match state {
+            SneakState::Chars(mut chars) => {
+                chars.push(c);
+                if chars.chars().count() < 2 {
+                    self.pending_sneak = Some(SneakState::Chars(chars));
+                } else {
+                    self.label_sneak_targets(&chars);
+                }
+            }
+            SneakState::Labels(targets) => {
+                self.redraw_highlight_overlay();
+                let Some(target) = targets.into_iter().find(|target| target.label == c) else {
+                    // Not a label: the key is handled normally
+                    crate::verbose_print!("[godot-neovim] Sneak: '{}' is not a label", c);
+                    return false;
+                };
+                self.sneak_jump(target.line, target.col);
+            }
+        }
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+        true
+    }
+
+    /// Label the occurrences of `chars` on the visible lines, or jump to the only one
+    fn label_sneak_targets(&mut self, chars: &str) {
+        let Some(ref editor) = self.current_editor else {
+            return;
+        };
+        let cursor = (editor.get_caret_line(), editor.get_caret_column());
+        let pattern: Vec<char> = chars.chars().collect();
+        let mut positions = Vec::new();
+        for line in editor.get_first_visible_line()..=editor.get_last_full_visible_line() {
+            let text = editor.get_line(line).to_string();
+            positions.extend(
+                find_occurrences(&text, &pattern)
+                    .into_iter()
+                    .map(|col| (line, col))
+                    .filter(|&position| position != cursor),
+            );
+        }
+
+        let targets = label_targets(positions, cursor);
+        crate::verbose_print!(
+            "[godot-neovim] Sneak '{}': {} target(s)",
+            chars,
+            targets.len()
+        );
+        match targets.as_slice() {
+            [] => self.show_status_message(&format!("Sneak: '{}' not found", chars)),
+            [target] => self.sneak_jump(target.line, target.col),
+            _ => {
+                self.pending_sneak = Some(SneakState::Labels(targets));
+                self.redraw_highlight_overlay();
+            }
+        }
+    }
+
+    /// Jump to a sneak target (with a jumplist entry)
+    fn sneak_jump(&mut self, line: i32, col: i32) {
+        self.move_cursor_to(line, col);
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            godot_warn!("[godot-neovim] Sneak: Neovim is busy, cursor not moved");
+            return;
+        };
+        if let Err(e) = client.execute_lua_with_args(
+            "_G.godot_neovim.sneak_jump(...)",
+            vec![
+                rmpv::Value::from(line as i64 + 1),
+                rmpv::Value::from(col as i64 + 1),
+            ],
+        ) {
+            godot_warn!("[godot-neovim] Sneak: Failed to move the cursor: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sneak_targets() {
+        assert_eq!(find_occurrences("abcab", &['a', 'b']), vec![0, 3]);
+        assert_eq!(find_occurrences("äöäö", &['ä', 'ö']), vec![0, 2]);
+        assert_eq!(find_occurrences("a", &['a', 'b']), Vec::<i32>::new());
+
+        let targets = label_targets(vec![(0, 9), (5, 0), (2, 1), (2, 8)], (2, 4));
+        let order: Vec<(char, i32, i32)> = targets
+            .iter()
+            .map(|target| (target.label, target.line, target.col))
+            .collect();
+        assert_eq!(
+            order,
+            vec![('a', 2, 1), ('s', 2, 8), ('d', 0, 9), ('f', 5, 0)]
+        );
+    }
+}
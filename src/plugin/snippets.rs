@@ -0,0 +1,513 @@
+//! Insert mode snippets
+//!
+//! Tab after a snippet prefix in Insert mode replaces it with the snippet body,
+//! then Tab / Shift+Tab move between its fields (`$1`, `${2:default}`, ... and `$0`
+//! last, the end of the snippet if it has none). The body is inserted into the
+//! CodeEdit like typed text, so Neovim gets it with the rest of the insert, and
+//! Tab is left to Godot's code completion while its popup is open.
+//!
+//! The project's snippets come from the Snippets File (JSON, reloaded when it
+//! changes) and win over the built-in GDScript ones:
+//!
+//! ```json
+//! { "Print": { "prefix": "pr", "body": ["print(${1:value})$0"], "scope": "gdscript" } }
+//! ```
+
+use super::filetype::detect_filetype;
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::FileAccess;
+use godot::prelude::*;
+use serde_json::Value;
+
+/// Built-in GDScript snippets: (prefix, body). A tab starting a line is one indent level.
+const BUILTIN_SNIPPETS: &[(&str, &str)] = &[
+    ("_ready", "func _ready() -> void:\n\t${0:pass}"),
+    (
+        "_process",
+        "func _process(delta: float) -> void:\n\t${0:pass}",
+    ),
+    (
+        "_physics_process",
+        "func _physics_process(delta: float) -> void:\n\t${0:pass}",
+    ),
+    (
+        "_input",
+        "func _input(event: InputEvent) -> void:\n\t${0:pass}",
+    ),
+    ("func", "func ${1:name}(${2}) -> ${3:void}:\n\t${0:pass}"),
+    (
+        "signalfn",
+        "func _on_${1:node}_${2:signal}(${3}) -> void:\n\t${0:pass}",
+    ),
+    ("forin", "for ${1:item} in ${2:items}:\n\t${0:pass}"),
+    ("fori", "for ${1:i} in range(${2:10}):\n\t${0:pass}"),
+    ("match", "match ${1:value}:\n\t${2:pattern}:\n\t\t${0:pass}"),
+];
+
+/// A snippet of the Snippets File
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Snippet {
+    prefix: String,
+    body: String,
+    /// Filetypes the snippet is limited to (`gdscript`, `gdshader`), empty for all
+    scope: Vec<String>,
+}
+
+/// The Snippets File as last loaded
+#[derive(Debug, Default)]
+pub(super) struct SnippetCache {
+    path: String,
+    /// Modification time of the file (0: no file)
+    modified: u64,
+    snippets: Vec<Snippet>,
+}
+
+/// A field of an expanded snippet (0-indexed line, character columns)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SnippetField {
+    line: i32,
+    start: i32,
+    end: i32,
+}
+
+/// Fields of the snippet being filled in
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct SnippetSession {
+    /// In jump order ($1, $2, ..., $0)
+    fields: Vec<SnippetField>,
+    /// Index of the field the caret is in
+    current: usize,
+}
+
+impl SnippetSession {
+    /// The current field now ends at `end` (text was typed in it): move the
+    /// fields after it along
+    fn move_current_field_end(&mut self, end: (i32, i32)) {
+        let field = self.fields[self.current];
+        let (end_line, end_column) = end;
+        for (i, other) in self.fields.iter_mut().enumerate() {
+            if i == self.current || (other.line, other.start) < (field.line, field.end) {
+                continue;
+            }
+            if other.line == field.line {
+                other.start = end_column + (other.start - field.end);
+                other.end = end_column + (other.end - field.end);
+            }
+            other.line += end_line - field.line;
+        }
+        // A field typed across lines is only a position when going back to it
+        let current = &mut self.fields[self.current];
+        current.end = if end_line == current.line {
+            end_column
+        } else {
+            current.start
+        };
+    }
+}
+
+/// Parse the Snippets File: an object of `{"prefix": ..., "body": ..., "scope": ...}`
+/// entries (VS Code format). `prefix` (default: the entry name) and `body` may be
+/// lists; `scope` is a comma-separated list of filetypes.
+fn parse_snippets(json: &str) -> Result<Vec<Snippet>, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let Some(entries) = value.as_object() else {
+        return Err("not a JSON object".to_string());
+    };
+
+    let strings = |value: &Value| -> Vec<String> {
+        match value {
+            Value::String(s) => vec![s.clone()],
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+    let mut snippets = Vec::new();
+    for (name, entry) in entries {
+        let Some(body) = entry.get("body").map(|body| strings(body).join("\n")) else {
+            return Err(format!("'{}' has no body", name));
+        };
+        let prefixes = entry
+            .get("prefix")
+            .map(strings)
+            .unwrap_or_else(|| vec![name.clone()]);
+        let scope: Vec<String> = entry
+            .get("scope")
+            .and_then(|scope| scope.as_str())
+            .map(|scope| {
+                scope
+                    .split(',')
+                    .map(|filetype| filetype.trim().to_string())
+                    .filter(|filetype| !filetype.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        for prefix in prefixes {
+            snippets.push(Snippet {
+                prefix,
+                body: body.clone(),
+                scope: scope.clone(),
+            });
+        }
+    }
+    Ok(snippets)
+}
+
+/// Body of the snippet for `prefix` in a file of `filetype`: the project's, else a built-in
+fn find_snippet(snippets: &[Snippet], prefix: &str, filetype: &str) -> Option<String> {
+    snippets
+        .iter()
+        .find(|snippet| {
+            snippet.prefix == prefix
+                && (snippet.scope.is_empty() || snippet.scope.iter().any(|s| s == filetype))
+        })
+        .map(|snippet| snippet.body.clone())
+        .or_else(|| {
+            BUILTIN_SNIPPETS
+                .iter()
+                .find(|(builtin, _)| filetype == "gdscript" && *builtin == prefix)
+                .map(|(_, body)| body.to_string())
+        })
+}
+
+/// Snippet prefix (word characters) before `column` of a line
+fn prefix_before(line: &str, column: usize) -> String {
+    let chars: Vec<char> = line.chars().take(column).collect();
+    let start = chars
+        .iter()
+        .rposition(|&c| !(c.is_alphanumeric() || c == '_'))
+        .map_or(0, |p| p + 1);
+    chars[start..].iter().collect()
+}
+
+/// Indent a body inserted on a line indented with `indent`: tabs starting a body
+/// line are indent levels (`unit` each), lines after the first get `indent`
+fn indent_body(body: &str, indent: &str, unit: &str) -> String {
+    body.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            if line.is_empty() {
+                return String::new();
+            }
+            let levels = line.chars().take_while(|&c| c == '\t').count();
+            let indent = if i == 0 { "" } else { indent };
+            format!("{}{}{}", indent, unit.repeat(levels), &line[levels..])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `$N`, `${N}` or `${N:default}` after the `$` at `start - 1`: number, default
+/// and the index after it
+fn parse_placeholder(chars: &[char], start: usize) -> Option<(u32, String, usize)> {
+    let braced = chars.get(start) == Some(&'{');
+    let digits_start = start + braced as usize;
+    let digits_end = chars
+        .get(digits_start..)?
+        .iter()
+        .position(|c| !c.is_ascii_digit())
+        .map_or(chars.len(), |p| digits_start + p);
+    let number: u32 = chars[digits_start..digits_end]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    if !braced {
+        return Some((number, String::new(), digits_end));
+    }
+    match chars.get(digits_end) {
+        Some('}') => Some((number, String::new(), digits_end + 1)),
+        Some(':') => {
+            let mut default = String::new();
+            let mut i = digits_end + 1;
+            while i < chars.len() {
+                match chars[i] {
+                    '\\' if matches!(chars.get(i + 1), Some('$' | '}' | '\\')) => {
+                        default.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    '}' => return Some((number, default, i + 1)),
+                    c => {
+                        default.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Text of a body without its placeholders, and the fields as (start, end)
+/// character offsets in jump order ($0 last, the end of the text if not given)
+/// A number used twice is a field at its first place only.
+fn parse_body(body: &str) -> (String, Vec<(usize, usize)>) {
+    let chars: Vec<char> = body.chars().collect();
+    let mut text = String::new();
+    let mut length = 0;
+    let mut fields: Vec<(u32, usize, usize)> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && matches!(chars.get(i + 1), Some('$' | '}' | '\\')) {
+            text.push(chars[i + 1]);
+            length += 1;
+            i += 2;
+            continue;
+        }
+        if c == '$' {
+            if let Some((number, default, next)) = parse_placeholder(&chars, i + 1) {
+                let default_length = default.chars().count();
+                if !fields.iter().any(|&(n, _, _)| n == number) {
+                    fields.push((number, length, length + default_length));
+                }
+                text.push_str(&default);
+                length += default_length;
+                i = next;
+                continue;
+            }
+        }
+        text.push(c);
+        length += 1;
+        i += 1;
+    }
+    if !fields.iter().any(|&(number, _, _)| number == 0) {
+        fields.push((0, length, length));
+    }
+    fields.sort_by_key(|&(number, _, _)| if number == 0 { u32::MAX } else { number });
+    let fields = fields
+        .into_iter()
+        .map(|(_, start, end)| (start, end))
+        .collect();
+    (text, fields)
+}
+
+/// Line and column of a character offset of `text` inserted at (line, column)
+fn offset_position(text: &str, offset: usize, line: i32, column: i32) -> (i32, i32) {
+    let before: Vec<char> = text.chars().take(offset).collect();
+    let newlines = before.iter().filter(|&&c| c == '\n').count() as i32;
+    match before.iter().rposition(|&c| c == '\n') {
+        Some(p) => (line + newlines, (before.len() - p - 1) as i32),
+        None => (line, column + before.len() as i32),
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Tab in Insert mode: next field of the snippet being filled in (`backward`:
+    /// Shift+Tab, previous field), or expand the snippet whose prefix is before the
+    /// caret. Returns false to let Godot handle the key.
+    pub(super) fn handle_snippet_tab(&mut self, backward: bool) -> bool {
+        let Some(ref editor) = self.current_editor else {
+            return false;
+        };
+        if !editor.is_instance_valid() || editor.get_caret_count() > 1 {
+            return false;
+        }
+        // Tab accepts Godot's code completion
+        if editor.get_code_completion_selected_index() >= 0 {
+            return false;
+        }
+        if self.snippet_session.is_some() {
+            return self.jump_snippet_field(backward);
+        }
+        if backward || editor.has_selection() {
+            return false;
+        }
+        self.expand_snippet()
+    }
+
+    /// Replace the prefix before the caret with its snippet
+    fn expand_snippet(&mut self) -> bool {
+        self.refresh_snippets();
+        let filetype = detect_filetype(&self.current_script_path);
+        let Some(ref mut editor) = self.current_editor else {
+            return false;
+        };
+        let line = editor.get_caret_line();
+        let column = editor.get_caret_column();
+        let line_text = editor.get_line(line).to_string();
+        let prefix = prefix_before(&line_text, column as usize);
+        if prefix.is_empty() {
+            return false;
+        }
+        let Some(body) = find_snippet(&self.snippet_cache.snippets, &prefix, filetype) else {
+            return false;
+        };
+
+        let indent: String = line_text
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect();
+        let unit = if editor.is_indent_using_spaces() {
+            " ".repeat(editor.get_indent_size().max(1) as usize)
+        } else {
+            "\t".to_string()
+        };
+        let (text, fields) = parse_body(&indent_body(&body, &indent, &unit));
+        let start_column = column - prefix.chars().count() as i32;
+
+        // One undo step in Godot
+        editor.begin_complex_operation();
+        editor.select(line, start_column, line, column);
+        editor.insert_text_at_caret(&text);
+        editor.end_complex_operation();
+
+        let fields = fields
+            .into_iter()
+            .map(|(start, end)| {
+                let (field_line, field_start) = offset_position(&text, start, line, start_column);
+                let (end_line, field_end) = offset_position(&text, end, line, start_column);
+                SnippetField {
+                    line: field_line,
+                    start: field_start,
+                    end: if end_line == field_line {
+                        field_end
+                    } else {
+                        field_start
+                    },
+                }
+            })
+            .collect();
+        crate::verbose_print!("[godot-neovim] Expanded snippet '{}'", prefix);
+        self.snippet_session = Some(SnippetSession { fields, current: 0 });
+        self.select_snippet_field();
+        true
+    }
+
+    /// Move to the next (previous) field of the snippet
+    fn jump_snippet_field(&mut self, backward: bool) -> bool {
+        let Some(mut session) = self.snippet_session.take() else {
+            return false;
+        };
+        let Some(ref editor) = self.current_editor else {
+            return false;
+        };
+        let caret = (editor.get_caret_line(), editor.get_caret_column());
+        let field = session.fields[session.current];
+        // The caret left the snippet: Tab is Godot's again
+        if caret < (field.line, field.start) {
+            crate::verbose_print!("[godot-neovim] Snippet fields ended (caret moved away)");
+            return false;
+        }
+
+        session.move_current_field_end(caret);
+        session.current = if backward {
+            session.current.saturating_sub(1)
+        } else {
+            session.current + 1
+        };
+        self.snippet_session = Some(session);
+        self.select_snippet_field();
+        true
+    }
+
+    /// Select the current field (typing replaces its default); reaching the last
+    /// one ends the snippet
+    fn select_snippet_field(&mut self) {
+        let Some(ref session) = self.snippet_session else {
+            return;
+        };
+        let field = session.fields[session.current];
+        let last = session.current + 1 == session.fields.len();
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+        if field.start < field.end {
+            editor.select(field.line, field.start, field.line, field.end);
+        } else {
+            editor.deselect();
+            editor.set_caret_line(field.line);
+            editor.set_caret_column(field.start);
+        }
+        if last {
+            self.snippet_session = None;
+        }
+    }
+
+    /// Reload the Snippets File if it or the setting changed
+    fn refresh_snippets(&mut self) {
+        let path = settings::get_snippets_file();
+        let modified = if !path.is_empty() && FileAccess::file_exists(&path) {
+            FileAccess::get_modified_time(&path)
+        } else {
+            0
+        };
+        if path == self.snippet_cache.path && modified == self.snippet_cache.modified {
+            return;
+        }
+
+        self.snippet_cache = SnippetCache {
+            path: path.clone(),
+            modified,
+            snippets: Vec::new(),
+        };
+        if modified == 0 {
+            return;
+        }
+        let json = FileAccess::get_file_as_string(&path).to_string();
+        match parse_snippets(&json) {
+            Ok(snippets) => {
+                crate::verbose_print!(
+                    "[godot-neovim] Loaded {} snippet(s) from {}",
+                    snippets.len(),
+                    path
+                );
+                self.snippet_cache.snippets = snippets;
+            }
+            Err(e) => godot_warn!("[godot-neovim] Snippets File {}: {}", path, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_expansion() {
+        let snippets = parse_snippets(
+            r#"{"For": {"prefix": ["forin", "fr"], "body": ["for ${1:x} in $2:", "\t$0"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(snippets.len(), 2);
+        let body = find_snippet(&snippets, "fr", "gdshader").unwrap();
+        assert!(find_snippet(&snippets, "_ready", "gdshader").is_none());
+        assert!(find_snippet(&snippets, "_ready", "gdscript").is_some());
+        assert_eq!(prefix_before("    var x = fr", 14), "fr");
+
+        let (text, fields) = parse_body(&indent_body(&body, "    ", "  "));
+        assert_eq!(text, "for x in :\n      ");
+        assert_eq!(fields, vec![(4, 5), (9, 9), (17, 17)]);
+        assert_eq!(offset_position(&text, 17, 3, 4), (4, 6));
+
+        // "items" typed into $2 moves the fields after it only
+        let mut session = SnippetSession {
+            fields: vec![
+                SnippetField {
+                    line: 0,
+                    start: 4,
+                    end: 5,
+                },
+                SnippetField {
+                    line: 0,
+                    start: 9,
+                    end: 9,
+                },
+                SnippetField {
+                    line: 0,
+                    start: 10,
+                    end: 10,
+                },
+            ],
+            current: 1,
+        };
+        session.move_current_field_end((0, 14));
+        assert_eq!(session.fields[1].end, 14);
+        assert_eq!(session.fields[2].start, 15);
+        assert_eq!(session.fields[0].start, 4);
+    }
+}
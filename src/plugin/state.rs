@@ -42,6 +42,10 @@ impl GodotNeovimPlugin {
         self.command_mode = false;
         self.search_mode = false;
         self.pending_char_op = None;
+        self.pending_dead_key = None;
+        self.pending_surround = None;
+        self.pending_sneak = None;
+        self.pending_text_object = None;
         self.pending_mark_op = None;
         self.pending_macro_op = None;
         // Clear register waiting state (Some('\0')) but preserve selected register
@@ -70,6 +74,7 @@ impl GodotNeovimPlugin {
                 "[godot-neovim] Cancelling pending operator: '{}'",
                 self.last_key
             );
+            self.operator_pending = false;
             // Send Escape to cancel Neovim's pending operator via channel
             if let Some(neovim) = self.get_current_neovim() {
                 if let Ok(client) = neovim.try_lock() {
@@ -89,6 +94,33 @@ impl GodotNeovimPlugin {
         }
     }
 
+    /// Return to Normal mode when the editor regains focus or the script is switched
+    /// (godot_neovim/normal_mode_on_focus). Insert mode is left through send_escape,
+    /// which syncs the text typed in Godot to Neovim first, so it is not lost.
+    pub(super) fn enter_normal_mode_on_focus(&mut self) {
+        if !crate::settings::get_normal_mode_on_focus() {
+            return;
+        }
+
+        if self.command_mode {
+            self.close_command_line();
+        }
+        if self.search_mode {
+            self.close_search_mode();
+        }
+        self.clear_pending_input_states();
+        self.cancel_pending_operator();
+        self.take_count();
+
+        if self.is_insert_mode() || self.is_replace_mode() {
+            crate::verbose_print!("[godot-neovim] Focus: leaving {} mode", self.current_mode);
+            self.send_escape();
+        } else if self.is_in_visual_mode() || Self::is_operator_pending_mode(&self.current_mode) {
+            crate::verbose_print!("[godot-neovim] Focus: leaving {} mode", self.current_mode);
+            self.send_keys("<Esc>");
+        }
+    }
+
     pub(super) fn update_mode_display_with_cursor(
         &mut self,
         mode: &str,
@@ -137,12 +169,23 @@ impl GodotNeovimPlugin {
             _ => mode,
         };
 
+        // Selection size in Visual modes, like Vim's showcmd ("V-LINE 12 lines")
+        let mode_name = if Self::is_visual_mode(mode) && !self.visual_selection_size.is_empty() {
+            format!("{} {}", mode_name, self.visual_selection_size)
+        } else {
+            mode_name.to_string()
+        };
+
         // Format with cursor position if available
-        let display_text = if let Some((line, col)) = cursor {
+        let mut display_text = if let Some((line, col)) = cursor {
             format!(" {} {}:{} ", mode_name, line, col)
         } else {
             format!(" {} ", mode_name)
         };
+        // The project is running (:run, F5)
+        if self.project_running {
+            display_text.push_str("[Running] ");
+        }
 
         label.set_text(&display_text);
 
@@ -192,7 +235,16 @@ impl GodotNeovimPlugin {
             return;
         }
 
-        let display_text = format!(" godot-neovim v{} ", VERSION);
+        let display_text = if self.install_problems.is_empty() {
+            format!(" godot-neovim v{} ", VERSION)
+        } else {
+            // Details are in the Output panel
+            format!(
+                " godot-neovim v{} ({} install problem(s), see Output) ",
+                VERSION,
+                self.install_problems.len()
+            )
+        };
         label.set_text(&display_text);
         // White color for version display
         label.add_theme_color_override("font_color", Color::from_rgb(1.0, 1.0, 1.0));
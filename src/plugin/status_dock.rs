@@ -0,0 +1,197 @@
+//! Neovim status dock in the editor's bottom panel
+//!
+//! Shows the state of the Neovim instance of the current editor (process ID or
+//! attached address, version, uptime, RPC latency), the buffer it mirrors and the
+//! LSP connection, with a button that restarts Neovim without reloading the plugin. The text is
+//! refreshed every STATUS_REFRESH_INTERVAL while the dock is visible; the latency
+//! is measured by an asynchronous request through the input queue, so the dock
+//! never blocks the editor.
+
+use super::GodotNeovimPlugin;
+use crate::neovim::RpcRequest;
+use godot::classes::{Button, Label, VBoxContainer};
+use godot::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How often the dock text is refreshed while visible
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A latency ping without a response after this is lost (e.g. Neovim restarted)
+const STATUS_PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Uptime as `1h 02m 03s` (`2m 03s`, `3s` when shorter)
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Add the status dock to the bottom panel
+    pub(super) fn create_status_dock(&mut self) {
+        let mut dock = VBoxContainer::new_alloc();
+        dock.set_name("Neovim");
+        dock.set_custom_minimum_size(Vector2::new(0.0, 120.0));
+
+        let label = Label::new_alloc();
+        dock.add_child(&label);
+
+        let mut restart_button = Button::new_alloc();
+        restart_button.set_text("Restart Neovim");
+        restart_button.set_h_size_flags(godot::classes::control::SizeFlags::SHRINK_BEGIN);
+        let callable = self.base().callable("on_status_restart_pressed");
+        restart_button.connect("pressed", &callable);
+        dock.add_child(&restart_button);
+
+        self.base_mut().add_control_to_bottom_panel(&dock, "Neovim");
+        self.status_label = Some(label);
+        self.status_dock = Some(dock);
+        self.status_refreshed = None;
+    }
+
+    /// Remove the status dock from the bottom panel
+    pub(super) fn remove_status_dock(&mut self) {
+        self.status_label = None;
+        if let Some(mut dock) = self.status_dock.take() {
+            if dock.is_instance_valid() {
+                self.base_mut().remove_control_from_bottom_panel(&dock);
+                dock.queue_free();
+            }
+        }
+    }
+
+    /// Refresh the dock while it is visible (called every frame)
+    pub(super) fn update_status_dock(&mut self) {
+        if !self
+            .status_dock
+            .as_ref()
+            .is_some_and(|dock| dock.is_instance_valid() && dock.is_visible_in_tree())
+        {
+            return;
+        }
+        if self
+            .status_refreshed
+            .is_some_and(|time| time.elapsed() < STATUS_REFRESH_INTERVAL)
+        {
+            return;
+        }
+        self.status_refreshed = Some(Instant::now());
+        self.refresh_status_dock();
+    }
+
+    /// Rewrite the dock text and measure the RPC latency for the next refresh
+    pub(super) fn refresh_status_dock(&mut self) {
+        let editor_name = match self.current_editor_type {
+            super::EditorType::Shader => "Shader editor",
+            _ => "Script editor",
+        };
+        if self
+            .status_ping_sent
+            .is_some_and(|sent| sent.elapsed() > STATUS_PING_TIMEOUT)
+        {
+            self.status_ping_sent = None;
+            self.status_latency = None;
+        }
+        let mut lines = Vec::new();
+        let mut ping_sent = false;
+
+        match self.get_current_neovim().map(|neovim| neovim.try_lock()) {
+            None => lines.push(format!("Neovim ({}): Not running", editor_name)),
+            Some(Err(_)) => lines.push(format!("Neovim ({}): Busy", editor_name)),
+            Some(Ok(client)) => match client.uptime() {
+                None => lines.push(format!("Neovim ({}): Not running", editor_name)),
+                Some(uptime) => {
+                    lines.push(format!("Neovim ({}): Running", editor_name));
+                    match client.remote_address() {
+                        Some(address) => lines.push(format!("Attached to: {}", address)),
+                        None => {
+                            lines.push(format!("PID: {}", client.process_id().unwrap_or_default()))
+                        }
+                    }
+                    lines.push(format!(
+                        "Version: {}",
+                        client
+                            .version_string()
+                            .unwrap_or_else(|| "unknown".to_string())
+                    ));
+                    lines.push(format!("Uptime: {}", format_uptime(uptime)));
+                    lines.push(match self.status_latency {
+                        Some(latency) => {
+                            format!("RPC latency: {:.1} ms", latency.as_secs_f64() * 1000.0)
+                        }
+                        None => "RPC latency: measuring...".to_string(),
+                    });
+
+                    // A ping still in flight is not replaced (its latency is pending)
+                    ping_sent = self.status_ping_sent.is_none()
+                        && client.request_lua(RpcRequest::StatusPing, "return 0", vec![]);
+                }
+            },
+        }
+        if ping_sent {
+            self.status_ping_sent = Some(Instant::now());
+        }
+
+        lines.push(if self.current_script_path.is_empty() {
+            "Buffer: none".to_string()
+        } else {
+            format!(
+                "Buffer: {} ({} lines, {})",
+                self.current_script_path,
+                self.sync_manager.get_line_count(),
+                if self.sync_manager.is_attached() {
+                    "attached"
+                } else {
+                    "not attached"
+                }
+            )
+        });
+        lines.push(match self.godot_lsp {
+            None => "LSP: Disabled (enable Network > Language Server > Use Thread)".to_string(),
+            Some(ref lsp) if lsp.is_initialized() => "LSP: Connected".to_string(),
+            Some(_) => "LSP: Not connected (connects on first use)".to_string(),
+        });
+
+        if let Some(ref mut label) = self.status_label {
+            if label.is_instance_valid() {
+                label.set_text(&lines.join("\n"));
+            }
+        }
+    }
+
+    /// Response to the latency ping of the dock
+    pub(super) fn on_status_ping(&mut self, result: Result<(), String>) {
+        let Some(sent) = self.status_ping_sent.take() else {
+            return;
+        };
+        self.status_latency = result.ok().map(|()| sent.elapsed());
+    }
+
+    /// "Restart Neovim" button of the dock
+    pub(super) fn restart_neovim_from_dock(&mut self) {
+        crate::verbose_print!("[godot-neovim] Status dock: Restarting Neovim");
+        self.status_ping_sent = None;
+        self.status_latency = None;
+        self.restart_neovim();
+        self.refresh_status_dock();
+        self.show_status_message("Neovim restarted");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::from_secs(3)), "3s");
+        assert_eq!(format_uptime(Duration::from_secs(123)), "2m 03s");
+        assert_eq!(format_uptime(Duration::from_secs(3723)), "1h 02m 03s");
+    }
+}
@@ -0,0 +1,197 @@
+//! Surround operations (vim-surround style ys, cs, ds)
+//!
+//! The keys after `ys`, `cs` and `ds` are collected here: the motion and the
+//! surround characters (`"`, `'`, `<`, `[`...) would otherwise be taken by register,
+//! mark and bracket handling. The complete command runs in Neovim (surround.lua)
+//! as an operatorfunc, so the edit comes back through buffer events and `.`
+//! repeats it.
+
+use super::GodotNeovimPlugin;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+
+impl GodotNeovimPlugin {
+    /// `s` after the `y`, `c` or `d` operator starts a surround command
+    /// Returns true if the key was consumed.
+    pub(super) fn try_start_surround(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        if key_event.get_keycode() != Key::S
+            || key_event.is_shift_pressed()
+            || key_event.is_ctrl_pressed()
+            || key_event.is_alt_pressed()
+            || key_event.is_meta_pressed()
+            || self.is_in_visual_mode()
+        {
+            return false;
+        }
+        let operator = match self.last_key.as_str() {
+            "y" | "c" | "d" => self.last_key.clone(),
+            _ => return false,
+        };
+
+        // The operator is pending in Neovim; the whole command is sent once complete
+        self.cancel_pending_operator();
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push("<Esc>".to_string());
+        }
+        self.pending_surround = Some(format!("{}s", operator));
+        crate::verbose_print!("[godot-neovim] Surround: {}s", operator);
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+        true
+    }
+
+    /// Collect the keys of a pending surround command
+    /// Returns true if the key was consumed.
+    pub(in crate::plugin) fn handle_pending_surround(
+        &mut self,
+        key_event: &Gd<InputEventKey>,
+    ) -> bool {
+        let Some(mut keys) = self.pending_surround.take() else {
+            return false;
+        };
+
+        let keycode = key_event.get_keycode();
+
+        // Ignore modifier-only key presses - wait for the actual character
+        if matches!(
+            keycode,
+            Key::SHIFT | Key::CTRL | Key::ALT | Key::META | Key::CAPSLOCK | Key::NUMLOCK
+        ) {
+            self.pending_surround = Some(keys);
+            return false;
+        }
+
+        // Cancel on Escape, modifier combinations and non-printable keys
+        let c = char::from_u32(key_event.get_unicode()).filter(|c| !c.is_control());
+        let Some(c) = c.filter(|_| {
+            keycode != Key::ESCAPE
+                && !key_event.is_ctrl_pressed()
+                && !key_event.is_alt_pressed()
+                && !key_event.is_meta_pressed()
+        }) else {
+            crate::verbose_print!("[godot-neovim] Surround '{}' cancelled", keys);
+            return false;
+        };
+
+        keys.push(c);
+        if surround_complete(&keys) {
+            self.run_surround(&keys);
+        } else {
+            self.pending_surround = Some(keys);
+        }
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+        true
+    }
+
+    /// Run a complete surround command in Neovim
+    fn run_surround(&mut self, keys: &str) {
+        let chars: Vec<char> = keys.chars().collect();
+        let args = &chars[2..];
+        let call = match chars[0] {
+            'd' => format!("surround_delete({})", lua_string(&args[0].to_string())),
+            'c' => format!(
+                "surround_change({}, {})",
+                lua_string(&args[0].to_string()),
+                lua_string(&args[1].to_string())
+            ),
+            _ => {
+                let len = motion_len(args).unwrap_or(0);
+                let motion: String = args[..len].iter().collect();
+                format!(
+                    "surround_add({}, {})",
+                    lua_string(&motion),
+                    lua_string(&args[len].to_string())
+                )
+            }
+        };
+        crate::verbose_print!("[godot-neovim] Surround: {}", keys);
+
+        let cmd = format!("<Cmd>lua require('godot_neovim').{}<CR>", call);
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(cmd.clone());
+        }
+        self.send_keys(&cmd);
+    }
+}
+
+/// Whether the keys of a surround command (`ys`, `cs` or `ds` and its
+/// arguments) are complete
+fn surround_complete(keys: &str) -> bool {
+    let chars: Vec<char> = keys.chars().collect();
+    let args = &chars[2..];
+    match chars[0] {
+        'd' => !args.is_empty(),
+        'c' => args.len() >= 2,
+        _ => motion_len(args).is_some_and(|len| args.len() > len),
+    }
+}
+
+/// Length of the motion (with its count) at the start of the keys, if complete
+fn motion_len(keys: &[char]) -> Option<usize> {
+    // A count cannot start with 0 (that is the motion)
+    let digits = if keys.first() == Some(&'0') {
+        0
+    } else {
+        keys.iter().take_while(|c| c.is_ascii_digit()).count()
+    };
+    // Text objects, find-char, marks and prefixed motions take one more key
+    let argument = match keys.get(digits)? {
+        'i' | 'a' | 'f' | 'F' | 't' | 'T' | '\'' | '`' | 'g' | '[' | ']' => 1,
+        _ => 0,
+    };
+    let len = digits + 1 + argument;
+    (keys.len() >= len).then_some(len)
+}
+
+/// Lua string literal for the `<Cmd>` line (`<` would start a key notation)
+fn lua_string(s: &str) -> String {
+    let mut literal = String::from("'");
+    for c in s.chars() {
+        match c {
+            '\\' | '\'' => {
+                literal.push('\\');
+                literal.push(c);
+            }
+            '<' => literal.push_str("<lt>"),
+            _ => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surround_complete() {
+        assert!(!surround_complete("ds"));
+        assert!(surround_complete("ds("));
+        assert!(!surround_complete("cs\""));
+        assert!(surround_complete("cs\"'"));
+
+        assert!(!surround_complete("ysiw"));
+        assert!(surround_complete("ysiw)"));
+        assert!(!surround_complete("ys2w"));
+        assert!(surround_complete("ys2w]"));
+        assert!(!surround_complete("ysf,"));
+        assert!(surround_complete("ysf,\""));
+        assert!(surround_complete("yss)"));
+        assert!(surround_complete("ys$'"));
+        assert!(surround_complete("ys0'"));
+    }
+
+    #[test]
+    fn test_lua_string() {
+        assert_eq!(lua_string("iw"), "'iw'");
+        assert_eq!(lua_string("'"), "'\\''");
+        assert_eq!(lua_string("<"), "'<lt>'");
+    }
+}
@@ -0,0 +1,449 @@
+//! Document symbols: the outline picker (:symbols, `<leader>o`) and ]f/[f, and the
+//! project's symbols (:wsymbols)
+//!
+//! Symbols come from Godot's LSP (textDocument/documentSymbol) and are cached per
+//! script until Neovim's changedtick of the buffer moves. Locals (symbols inside a
+//! function) are left out of the outline. ]f/[f fall back to scanning the script
+//! for `func` lines while the LSP is not connected, so they work without it.
+//! :wsymbols asks the LSP for the symbols of every script (workspace/symbol) and
+//! opens the picked one like any other picker location.
+
+use super::picker::PickerItem;
+use super::GodotNeovimPlugin;
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+use lsp_types::{
+    DocumentSymbol, DocumentSymbolResponse, OneOf, Range, SymbolKind, WorkspaceSymbolResponse,
+};
+
+/// A symbol of the outline
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct OutlineSymbol {
+    name: String,
+    kind: SymbolKind,
+    /// Position of the declaration (0-based line and column)
+    line: i32,
+    col: i32,
+}
+
+impl OutlineSymbol {
+    fn is_function(&self) -> bool {
+        matches!(
+            self.kind,
+            SymbolKind::FUNCTION | SymbolKind::METHOD | SymbolKind::CONSTRUCTOR
+        )
+    }
+}
+
+/// Keyword shown before a symbol in the outline
+fn kind_label(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::FUNCTION | SymbolKind::METHOD | SymbolKind::CONSTRUCTOR => "func",
+        SymbolKind::VARIABLE | SymbolKind::PROPERTY | SymbolKind::FIELD => "var",
+        SymbolKind::CONSTANT => "const",
+        SymbolKind::EVENT => "signal",
+        SymbolKind::ENUM => "enum",
+        SymbolKind::ENUM_MEMBER => "value",
+        SymbolKind::CLASS => "class",
+        _ => "symbol",
+    }
+}
+
+/// Whether `inner` lies inside `outer` (and is not `outer` itself)
+fn range_contains(outer: &Range, inner: &Range) -> bool {
+    outer != inner && outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// Symbols of a documentSymbol response in file order, without locals and without
+/// the class of the script itself (which contains every other symbol)
+fn outline_symbols(response: DocumentSymbolResponse) -> Vec<OutlineSymbol> {
+    fn flatten(symbols: Vec<DocumentSymbol>, out: &mut Vec<(OutlineSymbol, Range)>) {
+        for symbol in symbols {
+            let start = symbol.selection_range.start;
+            out.push((
+                OutlineSymbol {
+                    name: symbol.name,
+                    kind: symbol.kind,
+                    line: start.line as i32,
+                    col: start.character as i32,
+                },
+                symbol.range,
+            ));
+            flatten(symbol.children.unwrap_or_default(), out);
+        }
+    }
+
+    let mut symbols = Vec::new();
+    match response {
+        DocumentSymbolResponse::Flat(infos) => {
+            for info in infos {
+                let start = info.location.range.start;
+                symbols.push((
+                    OutlineSymbol {
+                        name: info.name,
+                        kind: info.kind,
+                        line: start.line as i32,
+                        col: start.character as i32,
+                    },
+                    info.location.range,
+                ));
+            }
+        }
+        DocumentSymbolResponse::Nested(nested) => flatten(nested, &mut symbols),
+    }
+
+    let function_ranges: Vec<Range> = symbols
+        .iter()
+        .filter(|(symbol, _)| symbol.is_function())
+        .map(|(_, range)| *range)
+        .collect();
+    let script_class = symbols
+        .iter()
+        .position(|(symbol, range)| {
+            symbol.kind == SymbolKind::CLASS
+                && symbols
+                    .iter()
+                    .all(|(_, other)| other == range || range_contains(range, other))
+        })
+        .filter(|_| symbols.len() > 1);
+
+    let mut outline: Vec<OutlineSymbol> = symbols
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != script_class)
+        .filter(|(_, (_, range))| {
+            !function_ranges
+                .iter()
+                .any(|function| range_contains(function, range))
+        })
+        .map(|(_, (symbol, _))| symbol)
+        .collect();
+    outline.sort_by_key(|symbol| (symbol.line, symbol.col));
+    outline
+}
+
+/// Symbols of a workspace/symbol response with the URI of their script, ordered by
+/// script and position
+fn workspace_symbols(response: WorkspaceSymbolResponse) -> Vec<(String, OutlineSymbol)> {
+    let mut symbols: Vec<(String, OutlineSymbol)> = match response {
+        WorkspaceSymbolResponse::Flat(infos) => infos
+            .into_iter()
+            .map(|info| {
+                let start = info.location.range.start;
+                (
+                    info.location.uri.to_string(),
+                    OutlineSymbol {
+                        name: info.name,
+                        kind: info.kind,
+                        line: start.line as i32,
+                        col: start.character as i32,
+                    },
+                )
+            })
+            .collect(),
+        WorkspaceSymbolResponse::Nested(nested) => nested
+            .into_iter()
+            .map(|symbol| {
+                // A location without a range points at the top of the script
+                let (uri, line, col) = match symbol.location {
+                    OneOf::Left(location) => (
+                        location.uri,
+                        location.range.start.line as i32,
+                        location.range.start.character as i32,
+                    ),
+                    OneOf::Right(location) => (location.uri, 0, 0),
+                };
+                (
+                    uri.to_string(),
+                    OutlineSymbol {
+                        name: symbol.name,
+                        kind: symbol.kind,
+                        line,
+                        col,
+                    },
+                )
+            })
+            .collect(),
+    };
+    symbols.sort_by(|(a_uri, a), (b_uri, b)| (a_uri, a.line, a.col).cmp(&(b_uri, b.line, b.col)));
+    symbols
+}
+
+/// (line, column) of the `func` declarations in a script (`static func` included)
+fn function_lines(lines: &[String]) -> Vec<(i32, i32)> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let trimmed = text.trim_start();
+            let declaration = trimmed
+                .strip_prefix("static")
+                .filter(|rest| rest.starts_with(char::is_whitespace))
+                .map_or(trimmed, str::trim_start);
+            let rest = declaration.strip_prefix("func")?;
+            if !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let indent = text.chars().count() - trimmed.chars().count();
+            Some((line as i32, indent as i32))
+        })
+        .collect()
+}
+
+/// The `count`th function after (or before) `from_line`, or the last one in that
+/// direction if there are fewer
+fn step_function(
+    functions: &[(i32, i32)],
+    from_line: i32,
+    forward: bool,
+    count: i32,
+) -> Option<(i32, i32)> {
+    let count = count.max(1) as usize;
+    if forward {
+        let ahead: Vec<_> = functions
+            .iter()
+            .filter(|(line, _)| *line > from_line)
+            .collect();
+        ahead
+            .get(count - 1)
+            .or(ahead.last())
+            .map(|&&position| position)
+    } else {
+        let behind: Vec<_> = functions
+            .iter()
+            .rev()
+            .filter(|(line, _)| *line < from_line)
+            .collect();
+        behind
+            .get(count - 1)
+            .or(behind.last())
+            .map(|&&position| position)
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Symbols of the current script from the LSP, cached until the changedtick moves
+    /// None if the LSP could not be used (the reason is shown in the status line)
+    fn document_symbols(&mut self, tag: &str) -> Option<Vec<OutlineSymbol>> {
+        let path = self.current_script_path.clone();
+        let changedtick = self.sync_manager.get_changedtick();
+        if let Some((cached_tick, symbols)) = self.symbol_cache.get(&path) {
+            if *cached_tick == changedtick {
+                return Some(symbols.clone());
+            }
+        }
+
+        let (lsp, uri) = self.prepare_lsp_request(tag)?;
+        let symbols = match lsp.document_symbols(&uri) {
+            Ok(response) => response.map(outline_symbols).unwrap_or_default(),
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] {}: LSP error: {}", tag, e);
+                self.show_status_message(&format!("LSP error: {}", e));
+                return None;
+            }
+        };
+        crate::verbose_print!(
+            "[godot-neovim] {}: {} symbol(s) in {}",
+            tag,
+            symbols.len(),
+            path
+        );
+        self.symbol_cache
+            .insert(path, (changedtick, symbols.clone()));
+        Some(symbols)
+    }
+
+    /// :symbols / `<leader>o` - Outline of the current script in the picker
+    pub(super) fn show_symbols_picker(&mut self) {
+        let Some(symbols) = self.document_symbols("symbols") else {
+            return;
+        };
+        if symbols.is_empty() {
+            self.show_status_message("No symbols found");
+            return;
+        }
+
+        let path = self.current_script_abs_path();
+        let items: Vec<PickerItem> = symbols
+            .into_iter()
+            .map(|symbol| PickerItem {
+                label: format!(
+                    "{:<6} {}  :{}",
+                    kind_label(symbol.kind),
+                    symbol.name,
+                    symbol.line + 1
+                ),
+                path: path.clone(),
+                position: Some((symbol.line, symbol.col)),
+            })
+            .collect();
+        self.show_picker(&format!("Symbols ({})", items.len()), items);
+    }
+
+    /// :wsym[bols] [query] - Symbols of the whole project in the picker (type to
+    /// filter further); a query matching a single symbol jumps to it
+    pub(super) fn cmd_workspace_symbols(&mut self, query: &str) {
+        let Some((lsp, _)) = self.prepare_lsp_request("wsymbols") else {
+            return;
+        };
+        let symbols = match lsp.workspace_symbols(query) {
+            Ok(response) => response.map(workspace_symbols).unwrap_or_default(),
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] wsymbols: LSP error: {}", e);
+                self.show_status_message(&format!("LSP error: {}", e));
+                return;
+            }
+        };
+        crate::verbose_print!(
+            "[godot-neovim] wsymbols: {} symbol(s) for '{}'",
+            symbols.len(),
+            query
+        );
+        if symbols.is_empty() {
+            self.show_status_message(&format!("No symbols found: {}", query));
+            return;
+        }
+
+        let items: Vec<PickerItem> = symbols
+            .into_iter()
+            .map(|(uri, symbol)| {
+                let path = Self::uri_to_file_path(&uri);
+                let display_path = ProjectSettings::singleton()
+                    .localize_path(&path)
+                    .to_string();
+                PickerItem {
+                    label: format!(
+                        "{:<6} {}  {}:{}",
+                        kind_label(symbol.kind),
+                        symbol.name,
+                        display_path,
+                        symbol.line + 1
+                    ),
+                    path,
+                    position: Some((symbol.line, symbol.col)),
+                }
+            })
+            .collect();
+
+        if let [PickerItem {
+            path,
+            position: Some((line, col)),
+            ..
+        }] = items.as_slice()
+        {
+            if !query.is_empty() {
+                self.jump_to_location(path, *line, *col);
+                return;
+            }
+        }
+        self.show_picker(&format!("Workspace Symbols ({})", items.len()), items);
+    }
+
+    /// ]f / [f - Move to the start of the next / previous function
+    pub(super) fn move_to_function(&mut self, forward: bool) {
+        let count = self.get_and_clear_count();
+        let Some(editor) = self.current_editor.as_ref() else {
+            return;
+        };
+        let caret_line = editor.get_caret_line();
+
+        let lsp_connected = self
+            .godot_lsp
+            .as_ref()
+            .is_some_and(|lsp| lsp.is_connected());
+        let from_lsp = if lsp_connected {
+            self.document_symbols(if forward { "]f" } else { "[f" })
+        } else {
+            None
+        };
+        let functions: Vec<(i32, i32)> = match from_lsp {
+            Some(symbols) => symbols
+                .iter()
+                .filter(|symbol| symbol.is_function())
+                .map(|symbol| (symbol.line, symbol.col))
+                .collect(),
+            None => {
+                let Some(editor) = self.current_editor.as_ref() else {
+                    return;
+                };
+                let lines: Vec<String> = (0..editor.get_line_count())
+                    .map(|line| editor.get_line(line).to_string())
+                    .collect();
+                function_lines(&lines)
+            }
+        };
+
+        let Some((line, col)) = step_function(&functions, caret_line, forward, count) else {
+            return;
+        };
+        if let Some(ref mut editor) = self.current_editor {
+            editor.set_caret_line(line);
+            editor.set_caret_column(col);
+            editor.adjust_viewport_to_caret();
+        }
+        self.sync_cursor_to_neovim();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline_symbols() {
+        let symbol = |name: &str, kind: u32, start: (u32, u32), end: (u32, u32)| {
+            serde_json::json!({
+                "name": name,
+                "kind": kind,
+                "location": {
+                    "uri": "file:///project/player.gd",
+                    "range": {
+                        "start": { "line": start.0, "character": start.1 },
+                        "end": { "line": end.0, "character": end.1 },
+                    },
+                },
+            })
+        };
+        // Godot sends the script class, its members and the locals of functions
+        let response: DocumentSymbolResponse = serde_json::from_value(serde_json::json!([
+            symbol("player", 5, (0, 0), (12, 0)),
+            symbol("hit", 24, (2, 0), (2, 10)),
+            symbol("_ready", 12, (6, 0), (9, 0)),
+            symbol("step", 13, (7, 1), (7, 14)),
+            symbol("speed", 13, (4, 0), (4, 20)),
+        ]))
+        .unwrap();
+
+        let outline = outline_symbols(response);
+        let names: Vec<&str> = outline.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["hit", "speed", "_ready"]);
+        assert_eq!(kind_label(outline[0].kind), "signal");
+        assert!(outline[2].is_function());
+    }
+
+    #[test]
+    fn test_step_function() {
+        let lines: Vec<String> = [
+            "extends Node",
+            "func _ready():",
+            "\tpass",
+            "static func make():",
+            "\tvar function = 1",
+            "class Inner:",
+            "\tfunc run():",
+            "\t\tpass",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        let functions = function_lines(&lines);
+        assert_eq!(functions, [(1, 0), (3, 0), (6, 1)]);
+        assert_eq!(step_function(&functions, 0, true, 1), Some((1, 0)));
+        assert_eq!(step_function(&functions, 1, true, 2), Some((6, 1)));
+        // A count past the last function stops at the last one
+        assert_eq!(step_function(&functions, 1, true, 5), Some((6, 1)));
+        assert_eq!(step_function(&functions, 4, false, 1), Some((3, 0)));
+        assert_eq!(step_function(&functions, 1, false, 1), None);
+    }
+}
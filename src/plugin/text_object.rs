@@ -0,0 +1,174 @@
+//! Text objects for the operators handled in Godot (gc, gr, cx, "{reg}y/d/c)
+//!
+//! These operators are sent to Neovim as a whole once their motion is typed, so the
+//! keys of a text object (`i(`, `a"`, `2it`, ...) are collected here rather than taken
+//! by the Godot-side handlers (`"` would select a register, `t` start a find).
+//! Neovim resolves the range of the object (text_object_range in buffer.lua); when it
+//! applies, the range is selected and the operator is applied to the Visual
+//! selection, so `.` repeats it on the same amount of text.
+
+use super::layout::{is_command_chord, typed_char};
+use super::GodotNeovimPlugin;
+use godot::classes::InputEventKey;
+use godot::global::Key;
+use godot::prelude::*;
+
+/// Operator waiting for the character of its text object (after `i` or `a`)
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct PendingTextObject {
+    /// Keys that apply the operator to the Visual selection
+    pub operator: String,
+    /// Keys of the text object typed so far (count, then `i` or `a`)
+    pub object: String,
+}
+
+/// Range of a text object
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct TextObjectRange {
+    /// First character (line 1-indexed, column 0-indexed character position)
+    pub start: (i64, i64),
+    /// Last character (inclusive, same units as `start`)
+    pub end: (i64, i64),
+    /// Whether the object is made of whole lines (`ip`)
+    pub linewise: bool,
+}
+
+/// Whether a key after an operator starts a text object
+pub(super) fn is_text_object_prefix(key: &str) -> bool {
+    key == "i" || key == "a"
+}
+
+/// Parse the `{ start_line, start_col, end_line, end_col, linewise }` returned by
+/// text_object_range (nil when the object does not apply)
+fn parse_text_object_range(value: &rmpv::Value) -> Option<TextObjectRange> {
+    let rmpv::Value::Array(fields) = value else {
+        return None;
+    };
+    let number = |i: usize| fields.get(i).and_then(|v| v.as_i64());
+    Some(TextObjectRange {
+        start: (number(0)?, number(1)?),
+        end: (number(2)?, number(3)?),
+        linewise: fields.get(4).and_then(|v| v.as_bool()).unwrap_or(false),
+    })
+}
+
+/// Keys that select a range in Visual mode, from Normal mode
+fn select_range_keys(range: &TextObjectRange) -> String {
+    format!(
+        "<Cmd>call setcursorcharpos({}, {})<CR>{}<Cmd>call setcursorcharpos({}, {})<CR>",
+        range.start.0,
+        range.start.1 + 1,
+        if range.linewise { "V" } else { "v" },
+        range.end.0,
+        range.end.1 + 1
+    )
+}
+
+impl GodotNeovimPlugin {
+    /// `i`/`a` typed as the motion of a Godot-side operator: wait for the text object
+    /// `operator`: keys that apply the operator to the Visual selection
+    /// `count`: count typed before the operator (applies to the text object)
+    pub(super) fn start_text_object(&mut self, operator: String, count: String, prefix: &str) {
+        self.clear_pending_input_states();
+        self.pending_text_object = Some(PendingTextObject {
+            operator,
+            object: format!("{}{}", count, prefix),
+        });
+        crate::verbose_print!(
+            "[godot-neovim] Text object: waiting for the object after {}",
+            prefix
+        );
+    }
+
+    /// Handle the key completing a pending text object
+    /// Returns true if the key was consumed.
+    pub(in crate::plugin) fn handle_pending_text_object(
+        &mut self,
+        key_event: &Gd<InputEventKey>,
+    ) -> bool {
+        let Some(pending) = self.pending_text_object.take() else {
+            return false;
+        };
+
+        // Ignore modifier-only key presses - wait for the actual character
+        if matches!(
+            key_event.get_keycode(),
+            Key::SHIFT | Key::CTRL | Key::ALT | Key::META | Key::CAPSLOCK | Key::NUMLOCK
+        ) {
+            self.pending_text_object = Some(pending);
+            return false;
+        }
+
+        if let Some(mut viewport) = self.base().get_viewport() {
+            viewport.set_input_as_handled();
+        }
+
+        // Escape, modifier combinations and non-printable keys cancel the operator
+        let c = typed_char(key_event).filter(|_| !is_command_chord(key_event));
+        let Some(c) = c else {
+            crate::verbose_print!("[godot-neovim] Text object cancelled");
+            return true;
+        };
+
+        let object = format!("{}{}", pending.object, c);
+        match self.resolve_text_object(&object) {
+            Some(range) => {
+                crate::verbose_print!("[godot-neovim] Text object {}: {:?}", object, range);
+                let keys = format!("{}{}", select_range_keys(&range), pending.operator);
+                if self.recording_macro.is_some() {
+                    self.macro_buffer.push(keys.clone());
+                }
+                self.send_keys(&keys);
+            }
+            None => {
+                crate::verbose_print!("[godot-neovim] Text object {}: not found", object);
+            }
+        }
+        true
+    }
+
+    /// Ask Neovim for the range of a text object at the cursor (`i(`, `2a"`, ...)
+    /// Returns None if the object does not apply there.
+    fn resolve_text_object(&self, object: &str) -> Option<TextObjectRange> {
+        let neovim = self.get_current_neovim()?;
+        let Ok(client) = neovim.try_lock() else {
+            godot_warn!("[godot-neovim] Text object: Neovim is busy");
+            return None;
+        };
+        // The keys are typed literally by :normal (no <> notation)
+        match client.execute_lua_with_args(
+            "return _G.godot_neovim.text_object_range(...)",
+            vec![rmpv::Value::from(object)],
+        ) {
+            Ok(value) => parse_text_object_range(&value),
+            Err(e) => {
+                godot_warn!("[godot-neovim] Text object: Failed to resolve: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_object_range() {
+        let value = rmpv::Value::Array(vec![
+            rmpv::Value::from(3),
+            rmpv::Value::from(4),
+            rmpv::Value::from(5),
+            rmpv::Value::from(0),
+            rmpv::Value::from(false),
+        ]);
+        let range = parse_text_object_range(&value).unwrap();
+        assert_eq!(range.start, (3, 4));
+        assert_eq!(range.end, (5, 0));
+        assert_eq!(
+            select_range_keys(&range),
+            "<Cmd>call setcursorcharpos(3, 5)<CR>v<Cmd>call setcursorcharpos(5, 1)<CR>"
+        );
+        assert_eq!(parse_text_object_range(&rmpv::Value::Nil), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Edit transactions: bulk edits as a single undo step
+//!
+//! Plugin operations that rewrite many lines (:s, :g, :sort, LSP rename, ...) must
+//! be undone with one `u` in Neovim and one Ctrl+Z in Godot. Inside a transaction,
+//! Neovim joins the changes with :undojoin, and Godot keeps one complex operation
+//! open until every change up to the committed changedtick has been applied.
+
+use super::GodotNeovimPlugin;
+
+impl GodotNeovimPlugin {
+    /// Begin an edit transaction in the current buffer
+    /// Transactions do not nest: a begin inside an open transaction joins it.
+    pub(super) fn begin_edit_transaction(&mut self) {
+        if !self.sync_manager.begin_transaction() {
+            return;
+        }
+        // A previous transaction whose changes never arrived
+        self.end_transaction_undo_step();
+
+        if let Some(ref mut editor) = self.current_editor {
+            editor.begin_complex_operation();
+            self.transaction_editor = Some(editor.clone());
+        }
+
+        if let Err(e) = self.transaction_lua("_G.godot_neovim.transaction_begin()", vec![]) {
+            crate::verbose_print!("[godot-neovim] Failed to begin transaction: {}", e);
+        }
+    }
+
+    /// Run an Ex command in Neovim as part of the open transaction
+    pub(super) fn transaction_command(&mut self, cmd: &str) -> Result<(), String> {
+        self.transaction_lua(
+            "_G.godot_neovim.transaction_command(...)",
+            vec![rmpv::Value::from(cmd)],
+        )
+        .map(|_| ())
+    }
+
+    /// Commit the open transaction
+    /// The Godot undo step ends once Neovim's changes have been applied
+    /// (immediately if there were none, otherwise in process_neovim_updates).
+    pub(super) fn commit_edit_transaction(&mut self) {
+        if !self.sync_manager.in_transaction() && self.transaction_editor.is_none() {
+            return;
+        }
+        let tick = self
+            .transaction_lua("return _G.godot_neovim.transaction_commit()", vec![])
+            .ok()
+            .and_then(|value| value.as_i64());
+        let complete = match tick {
+            Some(tick) => self.sync_manager.commit(tick),
+            None => self.sync_manager.abort_transaction(),
+        };
+        // A full buffer sync (Godot-side edits) resets the sync manager, which
+        // closes the transaction: its only change is already in both editors
+        if complete || !self.sync_manager.in_transaction() {
+            self.end_transaction_undo_step();
+        }
+    }
+
+    /// End the Godot undo step of a transaction whose changes have all been applied,
+    /// or that was closed by a buffer switch (called after Neovim updates)
+    pub(super) fn finish_edit_transaction(&mut self) {
+        if self.transaction_editor.is_none() {
+            return;
+        }
+        if self.sync_manager.take_completed_transaction() || !self.sync_manager.in_transaction() {
+            self.end_transaction_undo_step();
+        }
+    }
+
+    fn end_transaction_undo_step(&mut self) {
+        if let Some(mut editor) = self.transaction_editor.take() {
+            if editor.is_instance_valid() {
+                editor.end_complex_operation();
+            }
+        }
+    }
+
+    /// Run Lua code of a transaction (after the keys still in the input queue)
+    pub(super) fn transaction_lua(
+        &self,
+        code: &str,
+        args: Vec<rmpv::Value>,
+    ) -> Result<rmpv::Value, String> {
+        let Some(neovim) = self.get_current_neovim() else {
+            return Err("Neovim not connected".to_string());
+        };
+        let Ok(client) = neovim.try_lock() else {
+            return Err("Neovim busy".to_string());
+        };
+        client.execute_lua_with_args(code, args)
+    }
+}
@@ -1,7 +1,7 @@
 //! UI-related operations: mode label, status bar, signal connections
 
 use super::{EditorType, GodotNeovimPlugin};
-use godot::classes::{Control, EditorInterface, Label};
+use godot::classes::{CodeEdit, Control, EditorInterface, Label};
 use godot::prelude::*;
 
 impl GodotNeovimPlugin {
@@ -103,7 +103,8 @@ impl GodotNeovimPlugin {
     }
 
     /// Update recording indicator visibility and text
-    pub(super) fn update_recording_label(&mut self, register: Option<char>) {
+    /// `keys` are the keys recorded so far (empty: indicator only)
+    pub(super) fn update_recording_label(&mut self, register: Option<char>, keys: &str) {
         let label = match self.current_editor_type {
             EditorType::Shader => self.shader_recording_label.as_mut(),
             _ => self.recording_label.as_mut(),
@@ -118,10 +119,14 @@ impl GodotNeovimPlugin {
         }
 
         match register {
-            Some(reg) => {
+            Some(reg) if keys.is_empty() => {
                 label.set_text(&format!(" recording @{} ", reg));
                 label.set_visible(true);
             }
+            Some(reg) => {
+                label.set_text(&format!(" recording @{}  {} ", reg, keys));
+                label.set_visible(true);
+            }
             None => {
                 label.set_text("");
                 label.set_visible(false);
@@ -129,6 +134,29 @@ impl GodotNeovimPlugin {
         }
     }
 
+    /// Label named `name` in the current editor's status bar, created hidden at
+    /// `index` if the status bar has none (the bool is true if it was created)
+    pub(super) fn status_bar_label(&self, name: &str, index: i32) -> Option<(Gd<Label>, bool)> {
+        let code_edit = self.current_editor.clone()?;
+        if !code_edit.is_instance_valid() {
+            return None;
+        }
+        let mut status_bar = self.find_status_bar(code_edit.upcast())?;
+        if let Some(label) = status_bar.get_node_or_null(name) {
+            if let Ok(label) = label.try_cast::<Label>() {
+                return Some((label, false));
+            }
+        }
+
+        let mut label = Label::new_alloc();
+        label.set_name(name);
+        label.set_visible(false);
+        status_bar.add_child(&label);
+        let index = index.min(status_bar.get_child_count() - 1);
+        status_bar.move_child(&label, index);
+        Some((label, true))
+    }
+
     /// Find the status bar HBoxContainer in the editor hierarchy
     pub(super) fn find_status_bar(&self, node: Gd<Control>) -> Option<Gd<Control>> {
         // The status bar is an HBoxContainer inside CodeTextEditor (sibling of CodeEdit)
@@ -189,6 +217,17 @@ impl GodotNeovimPlugin {
                 script_editor.connect("script_close", &close_callable);
             }
         }
+
+        // Connect to resource saved signal (for files that are not UTF-8 on disk)
+        // Deferred: saving may be triggered by :w while the plugin is borrowed
+        let saved_callable = self.base().callable("on_resource_saved");
+        if !self.base().is_connected("resource_saved", &saved_callable) {
+            self.base_mut().connect_flags(
+                "resource_saved",
+                &saved_callable,
+                godot::classes::object::ConnectFlags::DEFERRED,
+            );
+        }
     }
 
     /// Connect to EditorSettings changed signal
@@ -236,10 +275,56 @@ impl GodotNeovimPlugin {
         }
     }
 
+    /// Connect to CodeEdit focus_entered and focus_exited signals
+    pub(super) fn connect_focus_signal(&mut self) {
+        // Create callables first to avoid borrow conflicts
+        let callable = self.base().callable("on_editor_focus_entered");
+        let exited_callable = self.base().callable("on_editor_focus_exited");
+
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+
+        // Focus returning from another dock, panel or window
+        if !editor.is_connected("focus_entered", &callable) {
+            editor.connect("focus_entered", &callable);
+            crate::verbose_print!("[godot-neovim] Connected to focus_entered signal");
+        }
+
+        // Focus leaving for another dock, panel, window or tab (Auto Save)
+        if !editor.is_connected("focus_exited", &exited_callable) {
+            editor.connect("focus_exited", &exited_callable);
+            crate::verbose_print!("[godot-neovim] Connected to focus_exited signal");
+        }
+    }
+
+    /// Disconnect from CodeEdit focus_entered and focus_exited signals
+    pub(super) fn disconnect_focus_signal(&mut self) {
+        // Create callables first to avoid borrow conflicts
+        let callable = self.base().callable("on_editor_focus_entered");
+        let exited_callable = self.base().callable("on_editor_focus_exited");
+
+        let Some(ref mut editor) = self.current_editor else {
+            return;
+        };
+
+        if editor.is_connected("focus_entered", &callable) {
+            editor.disconnect("focus_entered", &callable);
+            crate::verbose_print!("[godot-neovim] Disconnected from focus_entered signal");
+        }
+
+        if editor.is_connected("focus_exited", &exited_callable) {
+            editor.disconnect("focus_exited", &exited_callable);
+            crate::verbose_print!("[godot-neovim] Disconnected from focus_exited signal");
+        }
+    }
+
     /// Connect to CodeEdit resized signal
     pub(super) fn connect_resized_signal(&mut self) {
-        // Create callable first to avoid borrow conflicts
+        // Create callables first to avoid borrow conflicts
         let callable = self.base().callable("on_editor_resized");
+        let theme_callable = self.base().callable("on_editor_theme_changed");
+        let zoom_callable = self.base().callable("on_editor_zoomed");
 
         let Some(ref mut editor) = self.current_editor else {
             return;
@@ -251,12 +336,40 @@ impl GodotNeovimPlugin {
             editor.connect("resized", &callable);
             crate::verbose_print!("[godot-neovim] Connected to resized signal");
         }
+
+        // Font size changes (Ctrl+wheel zoom, editor scale) change the grid without resizing
+        if !editor.is_connected("theme_changed", &theme_callable) {
+            editor.connect("theme_changed", &theme_callable);
+        }
+
+        // Zoom is applied by the CodeTextEditor around the CodeEdit; theme_changed
+        // does not always follow it, so listen to its zoomed signal as well
+        if let Some(mut code_text_editor) = Self::find_zoom_source(editor) {
+            if !code_text_editor.is_connected("zoomed", &zoom_callable) {
+                code_text_editor.connect("zoomed", &zoom_callable);
+                crate::verbose_print!("[godot-neovim] Connected to zoomed signal");
+            }
+        }
+    }
+
+    /// Nearest ancestor of the editor that emits `zoomed` (Godot's CodeTextEditor)
+    fn find_zoom_source(editor: &Gd<CodeEdit>) -> Option<Gd<Node>> {
+        let mut node = editor.get_parent();
+        while let Some(current) = node {
+            if current.has_signal("zoomed") {
+                return Some(current);
+            }
+            node = current.get_parent();
+        }
+        None
     }
 
     /// Disconnect from CodeEdit resized signal
     pub(super) fn disconnect_resized_signal(&mut self) {
-        // Create callable first to avoid borrow conflicts
+        // Create callables first to avoid borrow conflicts
         let callable = self.base().callable("on_editor_resized");
+        let theme_callable = self.base().callable("on_editor_theme_changed");
+        let zoom_callable = self.base().callable("on_editor_zoomed");
 
         let Some(ref mut editor) = self.current_editor else {
             return;
@@ -267,6 +380,14 @@ impl GodotNeovimPlugin {
             editor.disconnect("resized", &callable);
             crate::verbose_print!("[godot-neovim] Disconnected from resized signal");
         }
+        if editor.is_connected("theme_changed", &theme_callable) {
+            editor.disconnect("theme_changed", &theme_callable);
+        }
+        if let Some(mut code_text_editor) = Self::find_zoom_source(editor) {
+            if code_text_editor.is_connected("zoomed", &zoom_callable) {
+                code_text_editor.disconnect("zoomed", &zoom_callable);
+            }
+        }
     }
 
     /// Connect to CodeEdit gui_input signal for float window input handling
@@ -0,0 +1,71 @@
+//! Persistent undo: each script's undo history is kept in an undo file
+//!
+//! Neovim writes undo files itself only when it writes the file, but here Godot
+//! does (BufWriteCmd), so the undo file is written after each save of a script
+//! (`:wundo`). A buffer created for a script reads it back (`:rundo`) after its
+//! initial content was set; Neovim only accepts it when the text is the one it was
+//! written for, so a script changed outside the editor starts a new history.
+
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+
+/// Project-local folder of the undo files (not under version control)
+const UNDO_DIR: &str = "res://.godot/godot-neovim/undo";
+
+impl GodotNeovimPlugin {
+    /// Sync the undo folder (or no persistent undo) to both Neovim instances
+    pub(super) fn sync_undo_dir_to_neovim(&mut self) {
+        let dir = if settings::get_persistent_undo() {
+            ProjectSettings::singleton()
+                .globalize_path(UNDO_DIR)
+                .to_string()
+        } else {
+            String::new()
+        };
+        for neovim in [&self.script_neovim, &self.shader_neovim]
+            .into_iter()
+            .flatten()
+        {
+            if let Ok(client) = neovim.try_lock() {
+                if let Err(e) = client.set_undo_dir(&dir) {
+                    crate::verbose_print!("[godot-neovim] {}", e);
+                }
+            }
+        }
+    }
+
+    /// Write the undo file of a saved script (`path`: res:// or absolute)
+    pub(super) fn write_undo_file(&self, path: &str) {
+        if !settings::get_persistent_undo() {
+            return;
+        }
+        let abs_path = if path.starts_with("res://") {
+            ProjectSettings::singleton()
+                .globalize_path(path)
+                .to_string()
+        } else {
+            path.to_string()
+        };
+        // The buffer is in the Neovim of its editor type; the other has none
+        for neovim in [&self.script_neovim, &self.shader_neovim]
+            .into_iter()
+            .flatten()
+        {
+            let Ok(client) = neovim.try_lock() else {
+                continue;
+            };
+            match client.write_undo_file(&abs_path) {
+                Ok(true) => {
+                    crate::verbose_print!("[godot-neovim] Undo file written: {}", abs_path);
+                    return;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    crate::verbose_print!("[godot-neovim] {}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Undo tree (:undotree): restore any state of the buffer's undo history
+//!
+//! Neovim keeps every undo state, also the ones on branches that `u` can no longer
+//! reach (`g-`/`g+` walk them in time order). :undotree lists them in the picker, newest first, with alternate branches
+//! indented; opening a state runs `:undo {seq}` in Neovim, and the restored text
+//! comes back through the buffer sync like any other change.
+
+use super::picker::{PickerItem, PickerSource};
+use super::GodotNeovimPlugin;
+use godot::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How long ago a change was made, like :undolist
+fn format_age(seconds: i64) -> String {
+    match seconds.max(0) {
+        s if s < 60 => format!("{} seconds ago", s),
+        s if s < 3600 => format!("{} minutes ago", s / 60),
+        s if s < 86400 => format!("{} hours ago", s / 3600),
+        s => format!("{} days ago", s / 86400),
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// :undotree - Pick a state of the undo tree to restore
+    pub(in crate::plugin) fn show_undotree_picker(&mut self) {
+        let tree = {
+            let Some(neovim) = self.get_current_neovim() else {
+                godot_warn!("[godot-neovim] :undotree - Neovim not connected");
+                return;
+            };
+            let Ok(client) = neovim.try_lock() else {
+                godot_warn!("[godot-neovim] :undotree - Neovim is busy");
+                return;
+            };
+            match client.get_undotree() {
+                Ok(tree) => tree,
+                Err(e) => {
+                    godot_warn!("[godot-neovim] :undotree - {}", e);
+                    return;
+                }
+            }
+        };
+
+        if tree.nodes.is_empty() {
+            self.command_output = Some("Nothing to undo".to_string());
+            return;
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or(0);
+        let marker = |seq: i64| if seq == tree.seq_cur { '●' } else { '○' };
+
+        let mut items = Vec::with_capacity(tree.nodes.len() + 1);
+        let mut seqs = Vec::with_capacity(tree.nodes.len() + 1);
+        for node in &tree.nodes {
+            items.push(PickerItem {
+                label: format!(
+                    "{}{} {:>4}  {}{}",
+                    "│ ".repeat(node.depth),
+                    marker(node.seq),
+                    node.seq,
+                    format_age(now - node.time),
+                    if node.saved { "  (saved)" } else { "" }
+                ),
+                path: String::new(),
+                position: None,
+            });
+            seqs.push(node.seq);
+        }
+        items.push(PickerItem {
+            label: format!("{} {:>4}  original", marker(0), 0),
+            path: String::new(),
+            position: None,
+        });
+        seqs.push(0);
+
+        crate::verbose_print!(
+            "[godot-neovim] :undotree - {} states, current {}",
+            seqs.len(),
+            tree.seq_cur
+        );
+        self.show_picker_with_source(
+            &format!("Undo Tree (current: {})", tree.seq_cur),
+            items,
+            PickerSource::UndoTree,
+        );
+        self.undo_seqs = seqs;
+    }
+
+    /// Restore the text of undo state `seq` (0: the original text)
+    pub(super) fn restore_undo_state(&mut self, seq: i64) {
+        self.cmd_forward_to_neovim(&format!("undo {}", seq));
+        self.show_status_message(&format!("Undo state {}", seq));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(5), "5 seconds ago");
+        assert_eq!(format_age(-1), "0 seconds ago");
+        assert_eq!(format_age(150), "2 minutes ago");
+        assert_eq!(format_age(7200), "2 hours ago");
+        assert_eq!(format_age(3 * 86400), "3 days ago");
+    }
+}
@@ -0,0 +1,295 @@
+//! Neovim user mappings (`nnoremap <leader>w :w<CR>`) and always-forwarded keys
+//!
+//! Normal mode keys that start a mapping of the user's Neovim config, or a
+//! sequence of the Always Forward Keys setting, bypass the plugin's own key
+//! handlers. The keys are collected until they complete a sequence, which is sent
+//! to Neovim as typed so Neovim runs the mapping. Keys that stop matching, or
+//! time out before completing one, are handled again as if nothing was mapped.
+
+use super::GodotNeovimPlugin;
+use crate::settings;
+use godot::classes::InputEventKey;
+use godot::prelude::*;
+use std::time::Instant;
+
+/// Normalize the inside of a `<...>` key (`C-W`, `Space`, `CS-X` from Godot)
+/// Modifiers are sorted and uppercase, Ctrl+letter is case-insensitive like in
+/// Vim, and keys with a character of their own (`<Space>`, `<lt>`) become it.
+fn normalize_special(inner: &str) -> String {
+    let (mods, name) = match inner.rfind('-') {
+        Some(i) if i + 1 < inner.len() => (&inner[..i], &inner[i + 1..]),
+        _ => ("", inner),
+    };
+    let name = match name.to_ascii_lowercase().as_str() {
+        "space" => " ".to_string(),
+        "lt" => "<".to_string(),
+        "bslash" => "\\".to_string(),
+        "bar" => "|".to_string(),
+        "return" | "enter" => "cr".to_string(),
+        "escape" => "esc".to_string(),
+        lower if name.chars().count() > 1 => lower.to_string(),
+        _ => name.to_string(),
+    };
+
+    let mut flags: Vec<char> = mods
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| match c.to_ascii_uppercase() {
+            'M' => 'A',
+            c => c,
+        })
+        .collect();
+    flags.sort_unstable();
+    flags.dedup();
+
+    let single = name.chars().count() == 1;
+    if flags.is_empty() {
+        return if single { name } else { format!("<{}>", name) };
+    }
+    let name = if single && flags.contains(&'C') {
+        name.to_ascii_lowercase()
+    } else {
+        name
+    };
+    let flags: Vec<String> = flags.iter().map(char::to_string).collect();
+    format!("<{}-{}>", flags.join("-"), name)
+}
+
+/// Split a key sequence in Neovim notation into normalized keys, so `<C-W>j`
+/// from nvim_get_keymap matches the keys typed in Godot (`<C-w>`, `j`)
+/// `<leader>` is replaced by the keys of `leader` (empty: kept as is).
+pub(super) fn parse_keys(notation: &str, leader: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut rest = notation;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            let inner = rest[1..].find('>').map(|end| &rest[1..end + 1]);
+            if let Some(inner) = inner.filter(|inner| !inner.is_empty() && !inner.contains('<')) {
+                if inner.eq_ignore_ascii_case("leader") && !leader.is_empty() {
+                    keys.extend(parse_keys(leader, ""));
+                } else {
+                    keys.push(normalize_special(inner));
+                }
+                rest = &rest[inner.len() + 2..];
+                continue;
+            }
+        }
+        keys.push(c.to_string());
+        rest = &rest[c.len_utf8()..];
+    }
+    keys
+}
+
+/// How typed keys relate to a set of key sequences
+#[derive(Debug, PartialEq)]
+enum MappingMatch {
+    /// No sequence starts with the keys
+    None,
+    /// Longer sequences start with the keys (the keys may be one as well)
+    Prefix { exact: bool },
+    /// The keys are a sequence and no longer one starts with them
+    Exact,
+}
+
+fn match_keys<'a>(
+    typed: &[String],
+    sequences: impl Iterator<Item = &'a Vec<String>>,
+) -> MappingMatch {
+    let mut exact = false;
+    let mut longer = false;
+    for sequence in sequences {
+        if sequence.starts_with(typed) {
+            if sequence.len() == typed.len() {
+                exact = true;
+            } else {
+                longer = true;
+            }
+        }
+    }
+    match (exact, longer) {
+        (_, true) => MappingMatch::Prefix { exact },
+        (true, false) => MappingMatch::Exact,
+        (false, false) => MappingMatch::None,
+    }
+}
+
+impl GodotNeovimPlugin {
+    /// Read the user's mappings from the current Neovim and the Always Forward
+    /// Keys setting (at activation, after a restart and :PluginReloadConfig)
+    pub(super) fn refresh_user_mappings(&mut self) {
+        self.refresh_forward_keys();
+        self.user_mappings.clear();
+        let Some(neovim) = self.get_current_neovim() else {
+            return;
+        };
+        let Ok(client) = neovim.try_lock() else {
+            return;
+        };
+        let result = client.execute_lua_with_result("return _G.godot_neovim.get_user_mappings()");
+        drop(client);
+        match result {
+            Ok(rmpv::Value::Array(lhs)) => {
+                self.user_mappings = lhs
+                    .iter()
+                    .filter_map(rmpv::Value::as_str)
+                    .map(|lhs| parse_keys(lhs, ""))
+                    .filter(|keys| !keys.is_empty())
+                    .collect();
+                crate::verbose_print!("[godot-neovim] User mappings: {:?}", self.user_mappings);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                crate::verbose_print!("[godot-neovim] Failed to read user mappings: {}", e);
+            }
+        }
+    }
+
+    /// Read the Always Forward Keys setting (also when the settings change)
+    pub(super) fn refresh_forward_keys(&mut self) {
+        let leader = settings::get_leader_key();
+        self.forward_keys = settings::get_always_forward_keys()
+            .iter()
+            .map(|keys| parse_keys(keys, &leader))
+            .filter(|keys| !keys.is_empty())
+            .collect();
+    }
+
+    /// Normal mode key that may start or continue a user mapping
+    /// Returns true if the key was consumed (held, or sent to Neovim).
+    pub(super) fn feed_user_mapping_key(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        if self.replaying_keys
+            || self.current_mode != "n"
+            || (self.user_mappings.is_empty() && self.forward_keys.is_empty())
+        {
+            return false;
+        }
+        let pending = !self.user_mapping_pending.is_empty();
+        let Some(keys) = self.key_event_to_nvim_string(key_event) else {
+            // Modifier-only key: keep waiting
+            return pending;
+        };
+        // A sequence of the plugin (or a count) is in progress
+        if !pending && (!self.last_key.is_empty() || keys.chars().all(|c| c.is_ascii_digit())) {
+            return false;
+        }
+
+        let mut typed: Vec<String> = self
+            .user_mapping_pending
+            .iter()
+            .flat_map(|(_, keys)| parse_keys(keys, ""))
+            .collect();
+        typed.extend(parse_keys(&keys, ""));
+
+        let sequences = self.user_mappings.iter().chain(self.forward_keys.iter());
+        match match_keys(&typed, sequences) {
+            MappingMatch::Exact => {
+                self.user_mapping_pending.push((key_event.clone(), keys));
+                self.send_user_mapping();
+                true
+            }
+            MappingMatch::Prefix { .. } => {
+                self.user_mapping_pending.push((key_event.clone(), keys));
+                self.user_mapping_time = Some(Instant::now());
+                true
+            }
+            MappingMatch::None if pending => {
+                self.user_mapping_pending.push((key_event.clone(), keys));
+                self.replay_user_mapping_keys();
+                true
+            }
+            MappingMatch::None => false,
+        }
+    }
+
+    /// Resolve a pending user mapping after timeoutlen (called every frame):
+    /// keys that are a mapping are sent, others are handled again
+    pub(super) fn poll_user_mapping_timeout(&mut self) {
+        if self
+            .user_mapping_time
+            .is_none_or(|time| time.elapsed().as_millis() <= settings::get_timeoutlen() as u128)
+        {
+            return;
+        }
+        let typed: Vec<String> = self
+            .user_mapping_pending
+            .iter()
+            .flat_map(|(_, keys)| parse_keys(keys, ""))
+            .collect();
+        let sequences = self.user_mappings.iter().chain(self.forward_keys.iter());
+        match match_keys(&typed, sequences) {
+            MappingMatch::Exact | MappingMatch::Prefix { exact: true } => self.send_user_mapping(),
+            _ => self.replay_user_mapping_keys(),
+        }
+    }
+
+    /// Send the keys of a completed user mapping to Neovim as typed
+    fn send_user_mapping(&mut self) {
+        self.user_mapping_time = None;
+        let keys: String = std::mem::take(&mut self.user_mapping_pending)
+            .into_iter()
+            .map(|(_, keys)| {
+                if keys == "<" {
+                    "<LT>".to_string()
+                } else {
+                    keys
+                }
+            })
+            .collect();
+        crate::verbose_print!("[godot-neovim] User mapping: {}", keys);
+        if self.recording_macro.is_some() {
+            self.macro_buffer.push(keys.clone());
+        }
+        self.send_keys(&keys);
+    }
+
+    /// Handle the held keys of an unmatched user mapping as if nothing was mapped
+    fn replay_user_mapping_keys(&mut self) {
+        self.user_mapping_time = None;
+        let events = std::mem::take(&mut self.user_mapping_pending);
+        crate::verbose_print!(
+            "[godot-neovim] Not a user mapping: {}",
+            events
+                .iter()
+                .map(|(_, keys)| keys.as_str())
+                .collect::<String>()
+        );
+        self.replaying_keys = true;
+        for (event, _) in events {
+            self.route_key_event(&event);
+        }
+        self.replaying_keys = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys_normalizes_notation() {
+        assert_eq!(parse_keys("<Space>w", ""), vec![" ", "w"]);
+        assert_eq!(parse_keys("<leader>ff", "<Space>"), vec![" ", "f", "f"]);
+        assert_eq!(parse_keys("<leader>", ""), vec!["<leader>"]);
+        // Ctrl+letter ignores case; Godot's combined modifiers match Vim's
+        assert_eq!(parse_keys("<C-W>j", ""), parse_keys("<C-w>j", ""));
+        assert_eq!(parse_keys("<CS-X>", ""), parse_keys("<S-C-x>", ""));
+        assert_eq!(parse_keys("<M-a>", ""), vec!["<A-a>"]);
+        assert_eq!(parse_keys("<lt>a<", ""), vec!["<", "a", "<"]);
+        assert_eq!(parse_keys("<Return>", ""), parse_keys("<CR>", ""));
+
+        let sequences = [parse_keys("gx", ""), parse_keys("g", "")];
+        let g = parse_keys("g", "");
+        assert_eq!(
+            match_keys(&g, sequences.iter()),
+            MappingMatch::Prefix { exact: true }
+        );
+        assert_eq!(
+            match_keys(&parse_keys("gx", ""), sequences.iter()),
+            MappingMatch::Exact
+        );
+        assert_eq!(
+            match_keys(&parse_keys("gd", ""), sequences.iter()),
+            MappingMatch::None
+        );
+    }
+}
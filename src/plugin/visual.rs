@@ -15,6 +15,11 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Large files poll the selection at a limited rate
+        if !self.visual_poll_due() {
+            return;
+        }
+
         let Some(neovim) = self.get_current_neovim() else {
             return;
         };
@@ -63,6 +68,19 @@ impl GodotNeovimPlugin {
         // Enable selecting and update Godot selection
         editor.set_selecting_enabled(true);
         editor.select(from_line as i32, from_col, to_line as i32, to_col);
+        self.update_primary_from_selection();
+
+        let lines = to_line - from_line + 1;
+        self.visual_selection_size = if self.visual_mode_type == '\x16' {
+            // Block: lines x columns between the two corners
+            let columns = ((to_col - 1) - from_col).abs() + 1;
+            format!("{}x{}", lines, columns)
+        } else if lines == 1 {
+            format!("{} chars", to_col - from_col)
+        } else {
+            format!("{} lines", lines)
+        };
+        self.refresh_visual_mode_display();
     }
 
     /// Update visual line selection in Godot editor (V mode - selects entire lines)
@@ -77,6 +95,11 @@ impl GodotNeovimPlugin {
             return;
         }
 
+        // Large files poll the selection at a limited rate
+        if !self.visual_poll_due() {
+            return;
+        }
+
         let Some(neovim) = self.get_current_neovim() else {
             return;
         };
@@ -118,15 +141,31 @@ impl GodotNeovimPlugin {
         // Enable selecting and update Godot selection (from start of first line to end of last line)
         editor.set_selecting_enabled(true);
         editor.select(from_line as i32, 0, to_line as i32, to_line_length as i32);
+        self.update_primary_from_selection();
+
+        self.visual_selection_size = format!("{} lines", to_line - from_line + 1);
+        self.refresh_visual_mode_display();
+    }
+
+    /// Show the selection size next to the mode (the mode label may have been
+    /// updated before the selection in this frame)
+    fn refresh_visual_mode_display(&mut self) {
+        let (line, col) = self.current_cursor;
+        self.update_mode_display_with_cursor(&self.current_mode.clone(), Some((line + 1, col)));
     }
 
     /// Clear visual selection in Godot editor
     pub(super) fn clear_visual_selection(&mut self) {
+        // Multicursor selections replaced Neovim's Visual selection
+        if self.multicursor {
+            return;
+        }
         let Some(ref mut editor) = self.current_editor else {
             return;
         };
 
         editor.deselect();
+        self.visual_selection_size.clear();
         crate::verbose_print!("[godot-neovim] Cleared visual selection");
     }
 }
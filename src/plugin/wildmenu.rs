@@ -0,0 +1,334 @@
+//! Command-line completion (Tab after `:`): Ex command names, the project's files
+//! for :e/:w/:saveas and the open buffers for :b/:bd
+//!
+//! With several candidates the first Tab inserts the first one and lists them all in
+//! a float above the status bar (the wildmenu). Tab/Shift+Tab cycle through them and
+//! back to the typed text after the last one; any other key keeps the inserted text
+//! and closes the list, like Vim's 'wildmenu'.
+
+use super::float::FloatPlacement;
+use super::project_files::{project_files, project_root, FINDER_EXTENSIONS};
+use super::GodotNeovimPlugin;
+use godot::classes::{PanelContainer, ProjectSettings};
+use godot::prelude::*;
+use std::path::Path;
+
+/// Ex commands run by the command line (full names), completed by Tab
+const EX_COMMANDS: &[&str] = &[
+    "PluginReloadConfig",
+    "bdelete",
+    "bnext",
+    "bprevious",
+    "buffer",
+    "buffers",
+    "cNext",
+    "cc",
+    "cfirst",
+    "changes",
+    "checkhealth",
+    "clast",
+    "cnext",
+    "copen",
+    "copy",
+    "cprevious",
+    "crewind",
+    "cwindow",
+    "display",
+    "edit",
+    "exit",
+    "format",
+    "global",
+    "grep",
+    "help",
+    "jumps",
+    "ls",
+    "make",
+    "marks",
+    "move",
+    "nohlsearch",
+    "normal",
+    "qall",
+    "quit",
+    "registers",
+    "rename",
+    "run",
+    "saveas",
+    "set",
+    "setlocal",
+    "sort",
+    "stop",
+    "substitute",
+    "symbols",
+    "undotree",
+    "version",
+    "vglobal",
+    "wall",
+    "wq",
+    "wqall",
+    "write",
+    "wsymbols",
+    "xall",
+    "xit",
+];
+
+/// Candidates shown in the wildmenu at once
+const WILDMENU_ROWS: usize = 10;
+
+/// What the word before the cursor is completed with
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompletionKind {
+    Command,
+    File,
+    Buffer,
+}
+
+/// Candidates of a completion in progress
+pub(super) struct Wildmenu {
+    /// Command line before the completed word (with the leading ':')
+    head: String,
+    /// The word as typed
+    typed: String,
+    candidates: Vec<String>,
+    /// Inserted candidate (None: the typed word)
+    selected: Option<usize>,
+    float: Option<Gd<PanelContainer>>,
+}
+
+/// Length of the line range at the start of a command line (`'<,'>`, `1,$`, `%`)
+fn range_prefix_len(cmdline: &str) -> usize {
+    let mut chars = cmdline.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '0'..='9' | '.' | ',' | ';' | '$' | '%' | '+' | '-' => {}
+            // A mark: the next character belongs to it
+            '\'' => {
+                chars.next();
+            }
+            _ => return i,
+        }
+    }
+    cmdline.len()
+}
+
+/// Where the word to complete starts in `cmdline` (without ':') and what it is
+/// completed with, None when it is not completed (e.g. a :s pattern)
+fn completion_target(cmdline: &str) -> Option<(usize, CompletionKind)> {
+    let range_len = range_prefix_len(cmdline);
+    let rest = &cmdline[range_len..];
+    let name_len = rest.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+    let (name, args) = rest.split_at(name_len);
+    if args.is_empty() {
+        return Some((range_len, CompletionKind::Command));
+    }
+    if range_len > 0 || !args.strip_prefix('!').unwrap_or(args).starts_with(' ') {
+        return None;
+    }
+
+    let abbreviates = |full: &str, min_len: usize| name.len() >= min_len && full.starts_with(name);
+    let kind = if abbreviates("edit", 1) || abbreviates("write", 1) || abbreviates("saveas", 3) {
+        CompletionKind::File
+    } else if abbreviates("buffer", 1) || abbreviates("bdelete", 2) {
+        CompletionKind::Buffer
+    } else {
+        return None;
+    };
+    // The last argument (paths with spaces are not completed)
+    let start = cmdline.rfind(' ').map_or(0, |i| i + 1);
+    Some((start, kind))
+}
+
+/// Ex command names starting with `typed`
+fn command_candidates(typed: &str) -> Vec<String> {
+    EX_COMMANDS
+        .iter()
+        .filter(|name| name.starts_with(typed))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// res:// paths whose res:// path, project-relative path or file name starts with `typed`
+fn path_candidates(paths: &[String], typed: &str) -> Vec<String> {
+    paths
+        .iter()
+        .filter(|path| {
+            let relative = path.strip_prefix("res://").unwrap_or(path);
+            let file_name = relative.rsplit('/').next().unwrap_or(relative);
+            path.starts_with(typed) || relative.starts_with(typed) || file_name.starts_with(typed)
+        })
+        .cloned()
+        .collect()
+}
+
+/// BBCode of the wildmenu: the page of candidates around the selected one
+fn wildmenu_bbcode(candidates: &[String], selected: Option<usize>) -> String {
+    let page = selected.unwrap_or(0) / WILDMENU_ROWS;
+    let mut lines: Vec<String> = candidates
+        .iter()
+        .enumerate()
+        .skip(page * WILDMENU_ROWS)
+        .take(WILDMENU_ROWS)
+        .map(|(i, candidate)| {
+            let text = candidate.replace('[', "[lb]");
+            if Some(i) == selected {
+                format!("[color=yellow]{}[/color]", text)
+            } else {
+                text
+            }
+        })
+        .collect();
+    if candidates.len() > WILDMENU_ROWS {
+        lines.push(format!(
+            "[i]{}/{}[/i]",
+            selected.map_or(0, |i| i + 1),
+            candidates.len()
+        ));
+    }
+    lines.join("\n")
+}
+
+impl GodotNeovimPlugin {
+    /// Tab/Shift+Tab in the command line: complete the word before the cursor, or
+    /// insert the next/previous candidate while the wildmenu is open
+    pub(super) fn complete_command_line(&mut self, backward: bool) {
+        if self.wildmenu.is_none() {
+            let cmdline = self
+                .command_buffer
+                .strip_prefix(':')
+                .unwrap_or(&self.command_buffer)
+                .to_string();
+            let Some((start, kind)) = completion_target(&cmdline) else {
+                return;
+            };
+            let typed = cmdline[start..].to_string();
+            let candidates = match kind {
+                CompletionKind::Command => command_candidates(&typed),
+                CompletionKind::File => {
+                    let paths: Vec<String> =
+                        project_files(Path::new(&project_root()), FINDER_EXTENSIONS)
+                            .into_iter()
+                            .map(|relative| format!("res://{}", relative))
+                            .collect();
+                    path_candidates(&paths, &typed)
+                }
+                CompletionKind::Buffer => {
+                    let paths: Vec<String> = self
+                        .list_buffers()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|buffer| {
+                            ProjectSettings::singleton()
+                                .localize_path(&buffer.file.replace('\\', "/"))
+                                .to_string()
+                        })
+                        .collect();
+                    path_candidates(&paths, &typed)
+                }
+            };
+            crate::verbose_print!(
+                "[godot-neovim] Completion ({:?}) of '{}': {} candidate(s)",
+                kind,
+                typed,
+                candidates.len()
+            );
+
+            let head = format!(":{}", &cmdline[..start]);
+            match candidates.len() {
+                0 => return,
+                1 => {
+                    self.command_buffer = format!("{}{}", head, candidates[0]);
+                    self.update_command_display();
+                    return;
+                }
+                _ => {}
+            }
+            self.wildmenu = Some(Wildmenu {
+                head,
+                typed,
+                candidates,
+                selected: None,
+                float: None,
+            });
+        }
+
+        let Some(mut menu) = self.wildmenu.take() else {
+            return;
+        };
+        let last = menu.candidates.len() - 1;
+        menu.selected = match (menu.selected, backward) {
+            (None, false) => Some(0),
+            (None, true) => Some(last),
+            (Some(i), false) if i < last => Some(i + 1),
+            (Some(i), true) if i > 0 => Some(i - 1),
+            _ => None,
+        };
+        let word = menu
+            .selected
+            .map_or(menu.typed.as_str(), |i| menu.candidates[i].as_str());
+        self.command_buffer = format!("{}{}", menu.head, word);
+        self.update_command_display();
+
+        Self::free_editor_float(menu.float.take());
+        menu.float = self.create_editor_float(
+            &wildmenu_bbcode(&menu.candidates, menu.selected),
+            FloatPlacement::BottomLeft,
+        );
+        self.wildmenu = Some(menu);
+    }
+
+    /// Close the wildmenu, keeping the inserted candidate
+    pub(super) fn close_wildmenu(&mut self) {
+        if let Some(menu) = self.wildmenu.take() {
+            Self::free_editor_float(menu.float);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_target_and_candidates() {
+        assert_eq!(completion_target("wri"), Some((0, CompletionKind::Command)));
+        assert_eq!(
+            completion_target("'<,'>so"),
+            Some((5, CompletionKind::Command))
+        );
+        assert_eq!(completion_target("e scr"), Some((2, CompletionKind::File)));
+        assert_eq!(
+            completion_target("w! res://a"),
+            Some((3, CompletionKind::File))
+        );
+        assert_eq!(
+            completion_target("b pla"),
+            Some((2, CompletionKind::Buffer))
+        );
+        assert_eq!(
+            completion_target("bd! 3"),
+            Some((4, CompletionKind::Buffer))
+        );
+        assert_eq!(completion_target("s/a/b"), None);
+        assert_eq!(completion_target("grep foo"), None);
+
+        assert_eq!(command_candidates("wq"), vec!["wq", "wqall"]);
+        assert_eq!(command_candidates("Plug"), vec!["PluginReloadConfig"]);
+
+        let paths = vec![
+            "res://player.gd".to_string(),
+            "res://scripts/enemy.gd".to_string(),
+            "res://scripts/player_state.gd".to_string(),
+        ];
+        assert_eq!(
+            path_candidates(&paths, "pla"),
+            vec!["res://player.gd", "res://scripts/player_state.gd"]
+        );
+        assert_eq!(
+            path_candidates(&paths, "res://scripts/"),
+            vec!["res://scripts/enemy.gd", "res://scripts/player_state.gd"]
+        );
+        assert_eq!(
+            path_candidates(&paths, "scripts/e"),
+            vec!["res://scripts/enemy.gd"]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Window commands (Ctrl+W prefix)
+//!
+//! The editor's docks stand in for Vim windows: `Ctrl+W h/j/k/l` moves the focus
+//! to the nearest of the script editor, Scene, FileSystem and Inspector docks in
+//! that direction (by their position on screen, so any dock layout works),
+//! `Ctrl+W w`/`W` cycles through them and `Ctrl+W o` toggles distraction-free
+//! mode. The prefix is also accepted while a dock has the focus, so the script
+//! editor can be reached again without the mouse.
+
+use super::GodotNeovimPlugin;
+use godot::classes::control::FocusMode;
+use godot::classes::{Control, EditorInterface, InputEventKey, Node};
+use godot::global::Key;
+use godot::prelude::*;
+
+/// last_key while waiting for the key after Ctrl+W
+pub(super) const WINDOW_PREFIX: &str = "<C-w>";
+
+/// Class of the Scene dock (not exposed by EditorInterface)
+const SCENE_DOCK_CLASS: &str = "SceneTreeDock";
+
+/// Direction of a focus move
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Left,
+    Down,
+    Up,
+    Right,
+}
+
+/// Distance from `from` to `to` in a direction: (gap along it, offset across it)
+/// None if `to` is not in that direction
+fn direction_distance(from: Rect2, to: Rect2, direction: Direction) -> Option<(f32, f32)> {
+    // Adjacent docks share an edge; the splitter between them is a few pixels wide
+    const TOLERANCE: f32 = 8.0;
+    let from_center = from.center();
+    let to_center = to.center();
+    let (gap, offset) = match direction {
+        Direction::Left => (from.position.x - to.end().x, to_center.y - from_center.y),
+        Direction::Right => (to.position.x - from.end().x, to_center.y - from_center.y),
+        Direction::Up => (from.position.y - to.end().y, to_center.x - from_center.x),
+        Direction::Down => (to.position.y - from.end().y, to_center.x - from_center.x),
+    };
+    (gap >= -TOLERANCE).then_some((gap.max(0.0), offset.abs()))
+}
+
+/// Index of the nearest area in a direction (gap first, then offset)
+fn nearest_in_direction(areas: &[Rect2], from: usize, direction: Direction) -> Option<usize> {
+    areas
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != from)
+        .filter_map(|(i, &area)| {
+            direction_distance(areas[from], area, direction).map(|distance| (i, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+}
+
+/// First visible descendant of `node` (or `node` itself) that takes keyboard focus
+fn first_focusable(node: Gd<Node>) -> Option<Gd<Control>> {
+    if let Ok(control) = node.clone().try_cast::<Control>() {
+        if !control.is_visible_in_tree() {
+            return None;
+        }
+        if control.get_focus_mode() == FocusMode::ALL {
+            return Some(control);
+        }
+    }
+    node.get_children().iter_shared().find_map(first_focusable)
+}
+
+/// First descendant of `node` of a class
+fn find_by_class(node: Gd<Node>, class: &str) -> Option<Gd<Node>> {
+    if node.is_class(class) {
+        return Some(node);
+    }
+    node.get_children()
+        .iter_shared()
+        .find_map(|child| find_by_class(child, class))
+}
+
+/// Control of a dock that gets the focus: its tree (Scene, FileSystem), else its
+/// first focusable control (Inspector)
+pub(super) fn dock_focus_control(dock: &Gd<Control>) -> Option<Gd<Control>> {
+    find_by_class(dock.clone().upcast(), "Tree")
+        .and_then(|tree| tree.try_cast::<Control>().ok())
+        .filter(|tree| tree.is_visible_in_tree())
+        .or_else(|| first_focusable(dock.clone().upcast()))
+}
+
+/// A place the focus can move to
+struct WindowTarget {
+    /// Dock (or script editor) whose rectangle is compared
+    area: Gd<Control>,
+    /// Control that gets the focus
+    focus: Gd<Control>,
+}
+
+impl GodotNeovimPlugin {
+    /// Handle Ctrl+W and the key after it (called for every key press, before the
+    /// editor focus check). Returns true if the key was used.
+    pub(super) fn handle_window_command_key(&mut self, key_event: &Gd<InputEventKey>) -> bool {
+        if self.last_key == WINDOW_PREFIX {
+            // Modifier-only key: keep waiting
+            let Some(keys) = self.key_event_to_nvim_string(key_event) else {
+                return true;
+            };
+            self.clear_last_key();
+            self.finish_window_command(&keys);
+            return true;
+        }
+
+        // Ctrl+W in the script editor is handled with the Normal mode keys
+        let is_ctrl_w = key_event.get_keycode() == Key::W
+            && key_event.is_ctrl_pressed()
+            && !key_event.is_shift_pressed()
+            && !key_event.is_alt_pressed();
+        if !is_ctrl_w || !self.dock_has_focus() {
+            return false;
+        }
+        self.set_last_key(WINDOW_PREFIX);
+        true
+    }
+
+    /// Key after Ctrl+W
+    fn finish_window_command(&mut self, keys: &str) {
+        match keys {
+            "h" | "<C-h>" | "<Left>" | "<BS>" => self.focus_window_in_direction(Direction::Left),
+            "j" | "<C-j>" | "<Down>" => self.focus_window_in_direction(Direction::Down),
+            "k" | "<C-k>" | "<Up>" => self.focus_window_in_direction(Direction::Up),
+            "l" | "<C-l>" | "<Right>" => self.focus_window_in_direction(Direction::Right),
+            "w" | "<C-w>" => self.focus_window_cycle(1),
+            "W" => self.focus_window_cycle(-1),
+            "t" | "<C-t>" => self.focus_window_index(0),
+            "o" | "<C-o>" => {
+                let mut editor = EditorInterface::singleton();
+                let enabled = !editor.is_distraction_free_mode_enabled();
+                editor.set_distraction_free_mode(enabled);
+                crate::verbose_print!("[godot-neovim] Ctrl+W o: distraction free={}", enabled);
+            }
+            "<Esc>" => {}
+            _ => {
+                crate::verbose_print!("[godot-neovim] Ctrl+W {}: not supported", keys);
+            }
+        }
+    }
+
+    /// Script editor and the visible docks, the script editor first
+    fn window_targets(&self) -> Vec<WindowTarget> {
+        let editor = EditorInterface::singleton();
+        let mut targets = Vec::new();
+
+        // The current CodeEdit (hidden while the 2D/3D/AssetLib screen is shown)
+        if let Some(code_edit) = self.current_editor_control() {
+            if code_edit.is_visible_in_tree() {
+                targets.push(WindowTarget {
+                    area: code_edit.clone(),
+                    focus: code_edit,
+                });
+            }
+        }
+
+        let mut docks: Vec<Gd<Control>> = Vec::new();
+        if let Some(base) = editor.get_base_control() {
+            if let Some(scene_dock) = find_by_class(base.upcast(), SCENE_DOCK_CLASS) {
+                docks.extend(scene_dock.try_cast::<Control>().ok());
+            }
+        }
+        docks.extend(editor.get_file_system_dock().map(|dock| dock.upcast()));
+        docks.extend(editor.get_inspector().map(|inspector| inspector.upcast()));
+
+        for dock in docks {
+            if !dock.is_visible_in_tree() {
+                continue;
+            }
+            if let Some(focus) = dock_focus_control(&dock) {
+                targets.push(WindowTarget { area: dock, focus });
+            }
+        }
+        targets
+    }
+
+    /// Whether one of the docks (not the script editor) has the focus
+    fn dock_has_focus(&self) -> bool {
+        let targets = self.window_targets();
+        self.focused_window_target(&targets).is_some_and(|index| {
+            Some(&targets[index].focus) != self.current_editor_control().as_ref()
+        })
+    }
+
+    /// The current CodeEdit as a Control
+    fn current_editor_control(&self) -> Option<Gd<Control>> {
+        self.current_editor
+            .as_ref()
+            .filter(|editor| editor.is_instance_valid())
+            .map(|editor| editor.clone().upcast())
+    }
+
+    /// Index of the target that has the focus (None: the focus is elsewhere)
+    fn focused_window_target(&self, targets: &[WindowTarget]) -> Option<usize> {
+        let focus_owner = self.base().get_viewport()?.gui_get_focus_owner()?;
+        let focus_node: Gd<Node> = focus_owner.upcast();
+        targets.iter().position(|target| {
+            let area: Gd<Node> = target.area.clone().upcast();
+            area == focus_node || area.is_ancestor_of(&focus_node)
+        })
+    }
+
+    /// Ctrl+W h/j/k/l
+    fn focus_window_in_direction(&mut self, direction: Direction) {
+        let targets = self.window_targets();
+        let from = self.focused_window_target(&targets).unwrap_or(0);
+        let areas: Vec<Rect2> = targets
+            .iter()
+            .map(|target| target.area.get_global_rect())
+            .collect();
+        let Some(index) = nearest_in_direction(&areas, from, direction) else {
+            crate::verbose_print!("[godot-neovim] Ctrl+W: no dock {:?}", direction);
+            return;
+        };
+        self.focus_window_target(&targets[index]);
+    }
+
+    /// Ctrl+W w/W
+    fn focus_window_cycle(&mut self, offset: i32) {
+        let targets = self.window_targets();
+        if targets.is_empty() {
+            return;
+        }
+        let count = targets.len() as i32;
+        let from = self.focused_window_target(&targets).unwrap_or(0) as i32;
+        let index = (from + offset).rem_euclid(count) as usize;
+        self.focus_window_target(&targets[index]);
+    }
+
+    /// Ctrl+W t: back to the script editor
+    fn focus_window_index(&mut self, index: usize) {
+        let targets = self.window_targets();
+        if let Some(target) = targets.get(index) {
+            self.focus_window_target(target);
+        }
+    }
+
+    fn focus_window_target(&mut self, target: &WindowTarget) {
+        let mut focus = target.focus.clone();
+        focus.grab_focus();
+        crate::verbose_print!("[godot-neovim] Ctrl+W: focus {}", target.area.get_class());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_in_direction() {
+        // Scene (top left), FileSystem (bottom left), script editor, Inspector (right)
+        let areas = [
+            Rect2::new(Vector2::new(300.0, 0.0), Vector2::new(900.0, 800.0)),
+            Rect2::new(Vector2::new(0.0, 0.0), Vector2::new(296.0, 400.0)),
+            Rect2::new(Vector2::new(0.0, 404.0), Vector2::new(296.0, 396.0)),
+            Rect2::new(Vector2::new(1204.0, 0.0), Vector2::new(300.0, 800.0)),
+        ];
+        assert_eq!(nearest_in_direction(&areas, 0, Direction::Right), Some(3));
+        assert_eq!(nearest_in_direction(&areas, 1, Direction::Down), Some(2));
+        assert_eq!(nearest_in_direction(&areas, 2, Direction::Up), Some(1));
+        assert_eq!(nearest_in_direction(&areas, 2, Direction::Right), Some(0));
+        assert_eq!(nearest_in_direction(&areas, 3, Direction::Right), None);
+        assert_eq!(nearest_in_direction(&areas, 0, Direction::Up), None);
+    }
+}
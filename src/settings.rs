@@ -5,14 +5,173 @@ use std::process::{Command, Output};
 
 const SETTING_NEOVIM_PATH: &str = "godot_neovim/neovim_executable_path";
 const SETTING_NEOVIM_CLEAN: &str = "godot_neovim/neovim_clean";
+const SETTING_NEOVIM_INIT_PATH: &str = "godot_neovim/neovim_init_path";
+const SETTING_NEOVIM_CONFIG_FILES: &str = "godot_neovim/neovim_config_files";
 const SETTING_TIMEOUTLEN: &str = "godot_neovim/timeoutlen";
+const SETTING_INSERT_NAVIGATION: &str = "godot_neovim/insert_mode_navigation";
+const SETTING_K_BEHAVIOR: &str = "godot_neovim/k_behavior";
+const SETTING_GR_BEHAVIOR: &str = "godot_neovim/gr_behavior";
+const SETTING_PRIMARY_SELECTION: &str = "godot_neovim/primary_selection";
+const SETTING_YANK_HIGHLIGHT_DURATION: &str = "godot_neovim/yank_highlight_duration";
+const SETTING_YANK_HIGHLIGHT_COLOR: &str = "godot_neovim/yank_highlight_color";
+const SETTING_NEOVIM_OPTIONS: &str = "godot_neovim/neovim_options";
+const SETTING_COMMENT_CONTINUATION: &str = "godot_neovim/comment_continuation";
+const SETTING_PICKER_BACKEND: &str = "godot_neovim/picker_backend";
+const SETTING_FZF_PATH: &str = "godot_neovim/fzf_executable_path";
+const SETTING_SHOW_MACRO_KEYS: &str = "godot_neovim/show_macro_keys";
+const SETTING_NORMAL_MODE_ON_FOCUS: &str = "godot_neovim/normal_mode_on_focus";
+const SETTING_RELATIVE_NUMBER: &str = "godot_neovim/relative_number";
+const SETTING_LEADER_KEY: &str = "godot_neovim/leader_key";
+const SETTING_ALWAYS_FORWARD_KEYS: &str = "godot_neovim/always_forward_keys";
+const SETTING_KEY_INTERCEPTION: &str = "godot_neovim/key_interception";
+const SETTING_KEY_LAYOUT_OVERRIDES: &str = "godot_neovim/key_layout_overrides";
+const SETTING_LARGE_FILE_LINES: &str = "godot_neovim/large_file_lines";
+const SETTING_SNIPPETS_FILE: &str = "godot_neovim/snippets_file";
+const SETTING_AUTO_SAVE: &str = "godot_neovim/auto_save";
+const SETTING_AUTO_SAVE_DELAY: &str = "godot_neovim/auto_save_delay";
+const SETTING_CONNECTION_ADDRESS: &str = "godot_neovim/connection/address";
+const SETTING_DIAGNOSTICS: &str = "godot_neovim/diagnostics";
+const SETTING_FORMATTER_PATH: &str = "godot_neovim/formatter_executable_path";
+const SETTING_DETECT_INDENT: &str = "godot_neovim/detect_indent";
+const SETTING_PERSISTENT_UNDO: &str = "godot_neovim/persistent_undo";
+const SETTING_IME_AUTO_OFF: &str = "godot_neovim/ime_auto_off";
+const SETTING_CLEVER_F: &str = "godot_neovim/clever_f";
+const SETTING_FIND_ACROSS_LINES: &str = "godot_neovim/find_across_lines";
+const SETTING_SNEAK: &str = "godot_neovim/sneak";
+const SETTING_IDLE_HOVER: &str = "godot_neovim/idle_hover";
+const SETTING_IDLE_HOVER_DELAY: &str = "godot_neovim/idle_hover_delay";
+const SETTING_SMOOTH_SCROLL: &str = "godot_neovim/smooth_scroll";
+const SETTING_SMOOTH_SCROLL_DURATION: &str = "godot_neovim/smooth_scroll_duration";
 
 const PROPERTY_HINT_RANGE: i32 = 1;
+const PROPERTY_HINT_ENUM: i32 = 2;
+const PROPERTY_HINT_FILE: i32 = 13;
 const PROPERTY_HINT_GLOBAL_FILE: i32 = 23;
 
 /// Default timeout for multi-key sequences (matches Neovim's default)
 pub const DEFAULT_TIMEOUTLEN_MS: i64 = 1000;
 
+/// Default duration of the yank highlight flash (matches vim.hl.on_yank)
+pub const DEFAULT_YANK_HIGHLIGHT_DURATION_MS: i64 = 150;
+
+/// Default key of `<leader>` in keymaps (Neovim notation)
+pub const DEFAULT_LEADER_KEY: &str = "<Space>";
+
+/// Default line count above which a script is opened in large-file mode
+pub const DEFAULT_LARGE_FILE_LINES: i64 = 10000;
+
+/// Default project file of the Insert mode snippets
+pub const DEFAULT_SNIPPETS_FILE: &str = "res://snippets.json";
+
+/// Default delay between leaving Insert mode and the auto-save
+pub const DEFAULT_AUTO_SAVE_DELAY_MS: i64 = 1000;
+
+/// Default time the caret rests before the idle hover float ('updatetime')
+pub const DEFAULT_IDLE_HOVER_DELAY_MS: i64 = 800;
+
+/// Default duration of the smooth scroll animation
+pub const DEFAULT_SMOOTH_SCROLL_DURATION_MS: i64 = 150;
+
+/// Default yank highlight color (translucent yellow)
+pub const DEFAULT_YANK_HIGHLIGHT_COLOR: Color = Color::from_rgba(1.0, 0.85, 0.3, 0.35);
+
+/// Whether arrow/Home/End keys in Insert mode are forwarded to Neovim
+/// Godot moves its caret either way.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum InsertNavigationPolicy {
+    /// Neovim is not told (fast, but its insert still starts where Insert mode was
+    /// entered, so `.` and gi do not follow the move)
+    #[default]
+    Off,
+    /// Sync the typed text to Neovim and forward the key, so Neovim starts a new
+    /// insert at the new position exactly like native Vim
+    Forward,
+}
+
+/// What `K` does for the word under cursor
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum KBehavior {
+    /// Open the class/member page in Godot's help documentation tab
+    #[default]
+    Help,
+    /// Show the LSP hover text in a floating panel next to the cursor
+    Hover,
+}
+
+/// What `gr` does
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum GrBehavior {
+    /// List the LSP references of the symbol under cursor
+    #[default]
+    References,
+    /// Replace with register operator (gr{motion}); `grr` lists the references,
+    /// like Neovim's default `grr`
+    ReplaceOperator,
+}
+
+/// When a comment leader (`#`, `##`, `//`) is continued on a new line
+/// (like the 'r' and 'o' flags of 'formatoptions')
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CommentContinuation {
+    /// Never continue comments
+    #[default]
+    Off,
+    /// Enter in Insert mode ('r')
+    Enter,
+    /// `o` / `O` in Normal mode ('o')
+    OpenLine,
+    /// Both Enter and `o` / `O` ('ro')
+    Both,
+}
+
+impl CommentContinuation {
+    /// Continue the leader when Enter is pressed in Insert mode
+    pub fn on_enter(self) -> bool {
+        matches!(self, Self::Enter | Self::Both)
+    }
+
+    /// Continue the leader when opening a line with `o` / `O`
+    pub fn on_open_line(self) -> bool {
+        matches!(self, Self::OpenLine | Self::Both)
+    }
+}
+
+/// When modified scripts are saved automatically
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AutoSave {
+    /// Only :w and Godot's own saving
+    #[default]
+    Off,
+    /// Leaving Insert mode (after the Auto Save Delay)
+    InsertLeave,
+    /// The script editor losing focus
+    FocusLost,
+    /// Both leaving Insert mode and losing focus
+    Both,
+}
+
+impl AutoSave {
+    /// Save after leaving Insert mode
+    pub fn on_insert_leave(self) -> bool {
+        matches!(self, Self::InsertLeave | Self::Both)
+    }
+
+    /// Save when the script editor loses focus
+    pub fn on_focus_lost(self) -> bool {
+        matches!(self, Self::FocusLost | Self::Both)
+    }
+}
+
+/// How the picker filters its items
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PickerBackend {
+    /// Case-insensitive fuzzy match inside the plugin
+    #[default]
+    BuiltIn,
+    /// Delegate matching to an external `fzf --filter` process (falls back to built-in)
+    Fzf,
+}
+
 /// Result of validating Neovim executable path
 #[derive(Debug, Clone)]
 pub enum ValidationResult {
@@ -71,6 +230,45 @@ pub fn initialize_settings() {
 
     settings.add_property_info(&clean_info);
 
+    // Add neovim_init_path setting if it doesn't exist (empty: the user's default init)
+    if !settings.has_setting(SETTING_NEOVIM_INIT_PATH) {
+        settings.set_setting(SETTING_NEOVIM_INIT_PATH, &Variant::from(""));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_NEOVIM_INIT_PATH, &Variant::from(""), false);
+
+    // Add property info for neovim_init_path (file picker)
+    let mut init_path_info = VarDictionary::new();
+    init_path_info.set("name", SETTING_NEOVIM_INIT_PATH);
+    init_path_info.set("type", VariantType::STRING.ord());
+    init_path_info.set("hint", PROPERTY_HINT_GLOBAL_FILE);
+    init_path_info.set("hint_string", "*.lua,*.vim");
+
+    settings.add_property_info(&init_path_info);
+
+    // Add neovim_config_files setting if it doesn't exist (files sourced even in clean mode)
+    if !settings.has_setting(SETTING_NEOVIM_CONFIG_FILES) {
+        settings.set_setting(
+            SETTING_NEOVIM_CONFIG_FILES,
+            &PackedStringArray::new().to_variant(),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_NEOVIM_CONFIG_FILES,
+        &PackedStringArray::new().to_variant(),
+        false,
+    );
+
+    // Add property info for neovim_config_files (list of paths)
+    let mut config_files_info = VarDictionary::new();
+    config_files_info.set("name", SETTING_NEOVIM_CONFIG_FILES);
+    config_files_info.set("type", VariantType::PACKED_STRING_ARRAY.ord());
+
+    settings.add_property_info(&config_files_info);
+
     // Add timeoutlen setting if it doesn't exist (advanced setting)
     if !settings.has_setting(SETTING_TIMEOUTLEN) {
         settings.set_setting(SETTING_TIMEOUTLEN, &Variant::from(DEFAULT_TIMEOUTLEN_MS));
@@ -92,92 +290,1462 @@ pub fn initialize_settings() {
 
     settings.add_property_info(&timeoutlen_info);
 
-    crate::verbose_print!(
-        "[godot-neovim] Settings initialized. Neovim path: {}, Clean: {}, Timeoutlen: {}ms",
-        get_neovim_path(),
-        get_neovim_clean(),
-        get_timeoutlen()
+    // Add insert_mode_navigation setting if it doesn't exist
+    if !settings.has_setting(SETTING_INSERT_NAVIGATION) {
+        settings.set_setting(SETTING_INSERT_NAVIGATION, &Variant::from(0i64));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_INSERT_NAVIGATION, &Variant::from(0i64), false);
+
+    // Add property info for insert_mode_navigation (enum dropdown)
+    let mut navigation_info = VarDictionary::new();
+    navigation_info.set("name", SETTING_INSERT_NAVIGATION);
+    navigation_info.set("type", VariantType::INT.ord());
+    navigation_info.set("hint", PROPERTY_HINT_ENUM);
+    navigation_info.set("hint_string", "Off,Forward");
+
+    settings.add_property_info(&navigation_info);
+
+    // Add k_behavior setting if it doesn't exist
+    if !settings.has_setting(SETTING_K_BEHAVIOR) {
+        settings.set_setting(SETTING_K_BEHAVIOR, &Variant::from(0i64));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_K_BEHAVIOR, &Variant::from(0i64), false);
+
+    // Add property info for k_behavior (enum dropdown)
+    let mut k_behavior_info = VarDictionary::new();
+    k_behavior_info.set("name", SETTING_K_BEHAVIOR);
+    k_behavior_info.set("type", VariantType::INT.ord());
+    k_behavior_info.set("hint", PROPERTY_HINT_ENUM);
+    k_behavior_info.set("hint_string", "Help,Hover");
+
+    settings.add_property_info(&k_behavior_info);
+
+    // Add gr_behavior setting if it doesn't exist
+    if !settings.has_setting(SETTING_GR_BEHAVIOR) {
+        settings.set_setting(SETTING_GR_BEHAVIOR, &Variant::from(0i64));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_GR_BEHAVIOR, &Variant::from(0i64), false);
+
+    // Add property info for gr_behavior (enum dropdown)
+    let mut gr_behavior_info = VarDictionary::new();
+    gr_behavior_info.set("name", SETTING_GR_BEHAVIOR);
+    gr_behavior_info.set("type", VariantType::INT.ord());
+    gr_behavior_info.set("hint", PROPERTY_HINT_ENUM);
+    gr_behavior_info.set("hint_string", "References,Replace Operator");
+
+    settings.add_property_info(&gr_behavior_info);
+
+    // Add primary_selection setting if it doesn't exist
+    if !settings.has_setting(SETTING_PRIMARY_SELECTION) {
+        settings.set_setting(SETTING_PRIMARY_SELECTION, &Variant::from(true));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_PRIMARY_SELECTION, &Variant::from(true), false);
+
+    // Add property info for primary_selection
+    let mut primary_info = VarDictionary::new();
+    primary_info.set("name", SETTING_PRIMARY_SELECTION);
+    primary_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&primary_info);
+
+    // Add yank_highlight_duration setting if it doesn't exist
+    if !settings.has_setting(SETTING_YANK_HIGHLIGHT_DURATION) {
+        settings.set_setting(
+            SETTING_YANK_HIGHLIGHT_DURATION,
+            &Variant::from(DEFAULT_YANK_HIGHLIGHT_DURATION_MS),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_YANK_HIGHLIGHT_DURATION,
+        &Variant::from(DEFAULT_YANK_HIGHLIGHT_DURATION_MS),
+        false,
     );
-}
 
-/// Get platform-specific default Neovim path
-fn get_default_neovim_path() -> GString {
-    #[cfg(target_os = "windows")]
-    {
-        GString::from("nvim.exe")
+    // Add property info for yank_highlight_duration (0 disables the flash)
+    let mut yank_duration_info = VarDictionary::new();
+    yank_duration_info.set("name", SETTING_YANK_HIGHLIGHT_DURATION);
+    yank_duration_info.set("type", VariantType::INT.ord());
+    yank_duration_info.set("hint", PROPERTY_HINT_RANGE);
+    yank_duration_info.set("hint_string", "0,2000,10"); // min, max, step
+
+    settings.add_property_info(&yank_duration_info);
+
+    // Add yank_highlight_color setting if it doesn't exist
+    if !settings.has_setting(SETTING_YANK_HIGHLIGHT_COLOR) {
+        settings.set_setting(
+            SETTING_YANK_HIGHLIGHT_COLOR,
+            &Variant::from(DEFAULT_YANK_HIGHLIGHT_COLOR),
+        );
     }
-    #[cfg(not(target_os = "windows"))]
-    {
-        GString::from("nvim")
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_YANK_HIGHLIGHT_COLOR,
+        &Variant::from(DEFAULT_YANK_HIGHLIGHT_COLOR),
+        false,
+    );
+
+    // Add property info for yank_highlight_color
+    let mut yank_color_info = VarDictionary::new();
+    yank_color_info.set("name", SETTING_YANK_HIGHLIGHT_COLOR);
+    yank_color_info.set("type", VariantType::COLOR.ord());
+
+    settings.add_property_info(&yank_color_info);
+
+    // Add neovim_options setting if it doesn't exist (values chosen with :set)
+    if !settings.has_setting(SETTING_NEOVIM_OPTIONS) {
+        settings.set_setting(SETTING_NEOVIM_OPTIONS, &VarDictionary::new().to_variant());
     }
-}
 
-/// Get platform-specific file filter for file dialog
-fn get_file_filter() -> GString {
-    #[cfg(target_os = "windows")]
-    {
-        GString::from("*.exe")
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_NEOVIM_OPTIONS,
+        &VarDictionary::new().to_variant(),
+        false,
+    );
+
+    // Add property info for neovim_options
+    let mut neovim_options_info = VarDictionary::new();
+    neovim_options_info.set("name", SETTING_NEOVIM_OPTIONS);
+    neovim_options_info.set("type", VariantType::DICTIONARY.ord());
+
+    settings.add_property_info(&neovim_options_info);
+
+    // Add comment_continuation setting if it doesn't exist
+    if !settings.has_setting(SETTING_COMMENT_CONTINUATION) {
+        settings.set_setting(SETTING_COMMENT_CONTINUATION, &Variant::from(0i64));
     }
-    #[cfg(not(target_os = "windows"))]
-    {
-        GString::from("*")
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_COMMENT_CONTINUATION, &Variant::from(0i64), false);
+
+    // Add property info for comment_continuation (enum dropdown)
+    let mut comment_info = VarDictionary::new();
+    comment_info.set("name", SETTING_COMMENT_CONTINUATION);
+    comment_info.set("type", VariantType::INT.ord());
+    comment_info.set("hint", PROPERTY_HINT_ENUM);
+    comment_info.set("hint_string", "Off,Enter,o/O,Enter and o/O");
+
+    settings.add_property_info(&comment_info);
+
+    // Add picker_backend setting if it doesn't exist
+    if !settings.has_setting(SETTING_PICKER_BACKEND) {
+        settings.set_setting(SETTING_PICKER_BACKEND, &Variant::from(0i64));
     }
-}
 
-/// Get the configured Neovim executable path
-pub fn get_neovim_path() -> String {
-    let editor = EditorInterface::singleton();
-    let Some(settings) = editor.get_editor_settings() else {
-        return get_default_neovim_path().to_string();
-    };
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_PICKER_BACKEND, &Variant::from(0i64), false);
 
-    if settings.has_setting(SETTING_NEOVIM_PATH) {
-        let value = settings.get_setting(SETTING_NEOVIM_PATH);
-        if let Ok(path) = value.try_to::<GString>() {
-            let path_str = path.to_string();
-            if !path_str.is_empty() {
-                return path_str;
-            }
-        }
+    // Add property info for picker_backend (enum dropdown)
+    let mut picker_backend_info = VarDictionary::new();
+    picker_backend_info.set("name", SETTING_PICKER_BACKEND);
+    picker_backend_info.set("type", VariantType::INT.ord());
+    picker_backend_info.set("hint", PROPERTY_HINT_ENUM);
+    picker_backend_info.set("hint_string", "Built-in,fzf");
+
+    settings.add_property_info(&picker_backend_info);
+
+    // Add fzf_executable_path setting if it doesn't exist
+    if !settings.has_setting(SETTING_FZF_PATH) {
+        settings.set_setting(SETTING_FZF_PATH, &Variant::from(get_default_fzf_path()));
     }
 
-    get_default_neovim_path().to_string()
-}
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_FZF_PATH,
+        &Variant::from(get_default_fzf_path()),
+        false,
+    );
 
-/// Get the configured neovim clean mode
-/// Default is true (--clean) for safety - avoids plugin compatibility issues
-pub fn get_neovim_clean() -> bool {
-    let editor = EditorInterface::singleton();
-    let Some(settings) = editor.get_editor_settings() else {
-        return true; // Default to clean mode
-    };
+    // Add property info for fzf_executable_path
+    let mut fzf_path_info = VarDictionary::new();
+    fzf_path_info.set("name", SETTING_FZF_PATH);
+    fzf_path_info.set("type", VariantType::STRING.ord());
+    fzf_path_info.set("hint", PROPERTY_HINT_GLOBAL_FILE);
+    fzf_path_info.set("hint_string", &get_file_filter());
 
-    if settings.has_setting(SETTING_NEOVIM_CLEAN) {
-        let value = settings.get_setting(SETTING_NEOVIM_CLEAN);
-        if let Ok(clean) = value.try_to::<bool>() {
-            return clean;
-        }
+    settings.add_property_info(&fzf_path_info);
+
+    // Add show_macro_keys setting if it doesn't exist
+    if !settings.has_setting(SETTING_SHOW_MACRO_KEYS) {
+        settings.set_setting(SETTING_SHOW_MACRO_KEYS, &Variant::from(false));
     }
 
-    true // Default to clean mode
-}
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_SHOW_MACRO_KEYS, &Variant::from(false), false);
 
-/// Get the configured timeoutlen (multi-key sequence timeout in milliseconds)
-pub fn get_timeoutlen() -> u64 {
-    let editor = EditorInterface::singleton();
-    let Some(settings) = editor.get_editor_settings() else {
-        return DEFAULT_TIMEOUTLEN_MS as u64;
-    };
+    // Add property info for show_macro_keys
+    let mut macro_keys_info = VarDictionary::new();
+    macro_keys_info.set("name", SETTING_SHOW_MACRO_KEYS);
+    macro_keys_info.set("type", VariantType::BOOL.ord());
 
-    if settings.has_setting(SETTING_TIMEOUTLEN) {
-        let value = settings.get_setting(SETTING_TIMEOUTLEN);
-        if let Ok(timeout) = value.try_to::<i64>() {
-            // Clamp to valid range (0 to 10000ms)
-            return timeout.clamp(0, 10000) as u64;
-        }
+    settings.add_property_info(&macro_keys_info);
+
+    // Add normal_mode_on_focus setting if it doesn't exist
+    if !settings.has_setting(SETTING_NORMAL_MODE_ON_FOCUS) {
+        settings.set_setting(SETTING_NORMAL_MODE_ON_FOCUS, &Variant::from(false));
     }
 
-    DEFAULT_TIMEOUTLEN_MS as u64
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_NORMAL_MODE_ON_FOCUS, &Variant::from(false), false);
+
+    // Add property info for normal_mode_on_focus
+    let mut normal_on_focus_info = VarDictionary::new();
+    normal_on_focus_info.set("name", SETTING_NORMAL_MODE_ON_FOCUS);
+    normal_on_focus_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&normal_on_focus_info);
+
+    // Add relative_number setting if it doesn't exist
+    if !settings.has_setting(SETTING_RELATIVE_NUMBER) {
+        settings.set_setting(SETTING_RELATIVE_NUMBER, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_RELATIVE_NUMBER, &Variant::from(false), false);
+
+    // Add property info for relative_number
+    let mut relative_number_info = VarDictionary::new();
+    relative_number_info.set("name", SETTING_RELATIVE_NUMBER);
+    relative_number_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&relative_number_info);
+
+    // Add leader_key setting if it doesn't exist
+    if !settings.has_setting(SETTING_LEADER_KEY) {
+        settings.set_setting(SETTING_LEADER_KEY, &Variant::from(DEFAULT_LEADER_KEY));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_LEADER_KEY,
+        &Variant::from(DEFAULT_LEADER_KEY),
+        false,
+    );
+
+    // Add property info for leader_key
+    let mut leader_key_info = VarDictionary::new();
+    leader_key_info.set("name", SETTING_LEADER_KEY);
+    leader_key_info.set("type", VariantType::STRING.ord());
+
+    settings.add_property_info(&leader_key_info);
+
+    // Add always_forward_keys setting if it doesn't exist
+    if !settings.has_setting(SETTING_ALWAYS_FORWARD_KEYS) {
+        settings.set_setting(
+            SETTING_ALWAYS_FORWARD_KEYS,
+            &PackedStringArray::new().to_variant(),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_ALWAYS_FORWARD_KEYS,
+        &PackedStringArray::new().to_variant(),
+        false,
+    );
+
+    // Add property info for always_forward_keys (list of key sequences)
+    let mut forward_keys_info = VarDictionary::new();
+    forward_keys_info.set("name", SETTING_ALWAYS_FORWARD_KEYS);
+    forward_keys_info.set("type", VariantType::PACKED_STRING_ARRAY.ord());
+
+    settings.add_property_info(&forward_keys_info);
+
+    // Add key_interception setting if it doesn't exist (key -> intercepted by the plugin)
+    if !settings.has_setting(SETTING_KEY_INTERCEPTION) {
+        settings.set_setting(SETTING_KEY_INTERCEPTION, &VarDictionary::new().to_variant());
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_KEY_INTERCEPTION,
+        &VarDictionary::new().to_variant(),
+        false,
+    );
+
+    // Add property info for key_interception
+    let mut key_interception_info = VarDictionary::new();
+    key_interception_info.set("name", SETTING_KEY_INTERCEPTION);
+    key_interception_info.set("type", VariantType::DICTIONARY.ord());
+
+    settings.add_property_info(&key_interception_info);
+
+    // Add key_layout_overrides setting if it doesn't exist (typed character -> Vim key)
+    if !settings.has_setting(SETTING_KEY_LAYOUT_OVERRIDES) {
+        settings.set_setting(
+            SETTING_KEY_LAYOUT_OVERRIDES,
+            &VarDictionary::new().to_variant(),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_KEY_LAYOUT_OVERRIDES,
+        &VarDictionary::new().to_variant(),
+        false,
+    );
+
+    // Add property info for key_layout_overrides
+    let mut key_layout_overrides_info = VarDictionary::new();
+    key_layout_overrides_info.set("name", SETTING_KEY_LAYOUT_OVERRIDES);
+    key_layout_overrides_info.set("type", VariantType::DICTIONARY.ord());
+
+    settings.add_property_info(&key_layout_overrides_info);
+
+    // Add large_file_lines setting if it doesn't exist
+    if !settings.has_setting(SETTING_LARGE_FILE_LINES) {
+        settings.set_setting(
+            SETTING_LARGE_FILE_LINES,
+            &Variant::from(DEFAULT_LARGE_FILE_LINES),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_LARGE_FILE_LINES,
+        &Variant::from(DEFAULT_LARGE_FILE_LINES),
+        false,
+    );
+
+    // Add property info for large_file_lines (0 disables large-file mode)
+    let mut large_file_info = VarDictionary::new();
+    large_file_info.set("name", SETTING_LARGE_FILE_LINES);
+    large_file_info.set("type", VariantType::INT.ord());
+    large_file_info.set("hint", PROPERTY_HINT_RANGE);
+    large_file_info.set("hint_string", "0,1000000,1000"); // min, max, step
+
+    settings.add_property_info(&large_file_info);
+
+    // Add snippets_file setting if it doesn't exist (project file, res:// path)
+    if !settings.has_setting(SETTING_SNIPPETS_FILE) {
+        settings.set_setting(SETTING_SNIPPETS_FILE, &Variant::from(DEFAULT_SNIPPETS_FILE));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_SNIPPETS_FILE,
+        &Variant::from(DEFAULT_SNIPPETS_FILE),
+        false,
+    );
+
+    // Add property info for snippets_file (file picker in the project)
+    let mut snippets_file_info = VarDictionary::new();
+    snippets_file_info.set("name", SETTING_SNIPPETS_FILE);
+    snippets_file_info.set("type", VariantType::STRING.ord());
+    snippets_file_info.set("hint", PROPERTY_HINT_FILE);
+    snippets_file_info.set("hint_string", "*.json");
+
+    settings.add_property_info(&snippets_file_info);
+
+    // Add auto_save setting if it doesn't exist
+    if !settings.has_setting(SETTING_AUTO_SAVE) {
+        settings.set_setting(SETTING_AUTO_SAVE, &Variant::from(0i64));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_AUTO_SAVE, &Variant::from(0i64), false);
+
+    // Add property info for auto_save (enum dropdown)
+    let mut auto_save_info = VarDictionary::new();
+    auto_save_info.set("name", SETTING_AUTO_SAVE);
+    auto_save_info.set("type", VariantType::INT.ord());
+    auto_save_info.set("hint", PROPERTY_HINT_ENUM);
+    auto_save_info.set(
+        "hint_string",
+        "Off,Insert Leave,Focus Lost,Insert Leave and Focus Lost",
+    );
+
+    settings.add_property_info(&auto_save_info);
+
+    // Add auto_save_delay setting if it doesn't exist
+    if !settings.has_setting(SETTING_AUTO_SAVE_DELAY) {
+        settings.set_setting(
+            SETTING_AUTO_SAVE_DELAY,
+            &Variant::from(DEFAULT_AUTO_SAVE_DELAY_MS),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_AUTO_SAVE_DELAY,
+        &Variant::from(DEFAULT_AUTO_SAVE_DELAY_MS),
+        false,
+    );
+
+    // Add property info for auto_save_delay
+    let mut auto_save_delay_info = VarDictionary::new();
+    auto_save_delay_info.set("name", SETTING_AUTO_SAVE_DELAY);
+    auto_save_delay_info.set("type", VariantType::INT.ord());
+    auto_save_delay_info.set("hint", PROPERTY_HINT_RANGE);
+    auto_save_delay_info.set("hint_string", "0,10000,100"); // min, max, step
+
+    settings.add_property_info(&auto_save_delay_info);
+
+    // Add connection address setting if it doesn't exist
+    if !settings.has_setting(SETTING_CONNECTION_ADDRESS) {
+        settings.set_setting(SETTING_CONNECTION_ADDRESS, &Variant::from(""));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_CONNECTION_ADDRESS, &Variant::from(""), false);
+
+    // Add property info for connection address (empty: spawn an embedded Neovim)
+    let mut address_info = VarDictionary::new();
+    address_info.set("name", SETTING_CONNECTION_ADDRESS);
+    address_info.set("type", VariantType::STRING.ord());
+
+    settings.add_property_info(&address_info);
+
+    // Add diagnostics setting if it doesn't exist
+    if !settings.has_setting(SETTING_DIAGNOSTICS) {
+        settings.set_setting(SETTING_DIAGNOSTICS, &Variant::from(true));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_DIAGNOSTICS, &Variant::from(true), false);
+
+    // Add property info for diagnostics
+    let mut diagnostics_info = VarDictionary::new();
+    diagnostics_info.set("name", SETTING_DIAGNOSTICS);
+    diagnostics_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&diagnostics_info);
+
+    // Add formatter_executable_path setting if it doesn't exist
+    if !settings.has_setting(SETTING_FORMATTER_PATH) {
+        settings.set_setting(
+            SETTING_FORMATTER_PATH,
+            &Variant::from(get_default_formatter_path()),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_FORMATTER_PATH,
+        &Variant::from(get_default_formatter_path()),
+        false,
+    );
+
+    // Add property info for formatter_executable_path
+    let mut formatter_path_info = VarDictionary::new();
+    formatter_path_info.set("name", SETTING_FORMATTER_PATH);
+    formatter_path_info.set("type", VariantType::STRING.ord());
+    formatter_path_info.set("hint", PROPERTY_HINT_GLOBAL_FILE);
+    formatter_path_info.set("hint_string", &get_file_filter());
+
+    settings.add_property_info(&formatter_path_info);
+
+    // Add detect_indent setting if it doesn't exist
+    if !settings.has_setting(SETTING_DETECT_INDENT) {
+        settings.set_setting(SETTING_DETECT_INDENT, &Variant::from(true));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_DETECT_INDENT, &Variant::from(true), false);
+
+    // Add property info for detect_indent
+    let mut detect_indent_info = VarDictionary::new();
+    detect_indent_info.set("name", SETTING_DETECT_INDENT);
+    detect_indent_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&detect_indent_info);
+
+    // Add persistent_undo setting if it doesn't exist
+    if !settings.has_setting(SETTING_PERSISTENT_UNDO) {
+        settings.set_setting(SETTING_PERSISTENT_UNDO, &Variant::from(true));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_PERSISTENT_UNDO, &Variant::from(true), false);
+
+    // Add property info for persistent_undo
+    let mut persistent_undo_info = VarDictionary::new();
+    persistent_undo_info.set("name", SETTING_PERSISTENT_UNDO);
+    persistent_undo_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&persistent_undo_info);
+
+    // Add ime_auto_off setting if it doesn't exist
+    if !settings.has_setting(SETTING_IME_AUTO_OFF) {
+        settings.set_setting(SETTING_IME_AUTO_OFF, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_IME_AUTO_OFF, &Variant::from(false), false);
+
+    // Add property info for ime_auto_off
+    let mut ime_auto_off_info = VarDictionary::new();
+    ime_auto_off_info.set("name", SETTING_IME_AUTO_OFF);
+    ime_auto_off_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&ime_auto_off_info);
+
+    // Add clever_f setting if it doesn't exist
+    if !settings.has_setting(SETTING_CLEVER_F) {
+        settings.set_setting(SETTING_CLEVER_F, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_CLEVER_F, &Variant::from(false), false);
+
+    // Add property info for clever_f
+    let mut clever_f_info = VarDictionary::new();
+    clever_f_info.set("name", SETTING_CLEVER_F);
+    clever_f_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&clever_f_info);
+
+    // Add find_across_lines setting if it doesn't exist
+    if !settings.has_setting(SETTING_FIND_ACROSS_LINES) {
+        settings.set_setting(SETTING_FIND_ACROSS_LINES, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_FIND_ACROSS_LINES, &Variant::from(false), false);
+
+    // Add property info for find_across_lines
+    let mut find_across_lines_info = VarDictionary::new();
+    find_across_lines_info.set("name", SETTING_FIND_ACROSS_LINES);
+    find_across_lines_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&find_across_lines_info);
+
+    // Add sneak setting if it doesn't exist
+    if !settings.has_setting(SETTING_SNEAK) {
+        settings.set_setting(SETTING_SNEAK, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_SNEAK, &Variant::from(false), false);
+
+    // Add property info for sneak
+    let mut sneak_info = VarDictionary::new();
+    sneak_info.set("name", SETTING_SNEAK);
+    sneak_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&sneak_info);
+
+    // Add idle_hover setting if it doesn't exist
+    if !settings.has_setting(SETTING_IDLE_HOVER) {
+        settings.set_setting(SETTING_IDLE_HOVER, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_IDLE_HOVER, &Variant::from(false), false);
+
+    // Add property info for idle_hover
+    let mut idle_hover_info = VarDictionary::new();
+    idle_hover_info.set("name", SETTING_IDLE_HOVER);
+    idle_hover_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&idle_hover_info);
+
+    // Add idle_hover_delay setting if it doesn't exist
+    if !settings.has_setting(SETTING_IDLE_HOVER_DELAY) {
+        settings.set_setting(
+            SETTING_IDLE_HOVER_DELAY,
+            &Variant::from(DEFAULT_IDLE_HOVER_DELAY_MS),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_IDLE_HOVER_DELAY,
+        &Variant::from(DEFAULT_IDLE_HOVER_DELAY_MS),
+        false,
+    );
+
+    // Add property info for idle_hover_delay
+    let mut idle_hover_delay_info = VarDictionary::new();
+    idle_hover_delay_info.set("name", SETTING_IDLE_HOVER_DELAY);
+    idle_hover_delay_info.set("type", VariantType::INT.ord());
+    idle_hover_delay_info.set("hint", PROPERTY_HINT_RANGE);
+    idle_hover_delay_info.set("hint_string", "100,5000,50"); // min, max, step
+
+    settings.add_property_info(&idle_hover_delay_info);
+
+    // Add smooth_scroll setting if it doesn't exist
+    if !settings.has_setting(SETTING_SMOOTH_SCROLL) {
+        settings.set_setting(SETTING_SMOOTH_SCROLL, &Variant::from(false));
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(SETTING_SMOOTH_SCROLL, &Variant::from(false), false);
+
+    // Add property info for smooth_scroll
+    let mut smooth_scroll_info = VarDictionary::new();
+    smooth_scroll_info.set("name", SETTING_SMOOTH_SCROLL);
+    smooth_scroll_info.set("type", VariantType::BOOL.ord());
+
+    settings.add_property_info(&smooth_scroll_info);
+
+    // Add smooth_scroll_duration setting if it doesn't exist
+    if !settings.has_setting(SETTING_SMOOTH_SCROLL_DURATION) {
+        settings.set_setting(
+            SETTING_SMOOTH_SCROLL_DURATION,
+            &Variant::from(DEFAULT_SMOOTH_SCROLL_DURATION_MS),
+        );
+    }
+
+    // Set initial value for Revert button (update_current=false: don't overwrite user's value)
+    settings.set_initial_value(
+        SETTING_SMOOTH_SCROLL_DURATION,
+        &Variant::from(DEFAULT_SMOOTH_SCROLL_DURATION_MS),
+        false,
+    );
+
+    // Add property info for smooth_scroll_duration
+    let mut smooth_scroll_duration_info = VarDictionary::new();
+    smooth_scroll_duration_info.set("name", SETTING_SMOOTH_SCROLL_DURATION);
+    smooth_scroll_duration_info.set("type", VariantType::INT.ord());
+    smooth_scroll_duration_info.set("hint", PROPERTY_HINT_RANGE);
+    smooth_scroll_duration_info.set("hint_string", "0,1000,10"); // min, max, step
+
+    settings.add_property_info(&smooth_scroll_duration_info);
+
+    crate::verbose_print!(
+        "[godot-neovim] Settings initialized. Neovim path: {}, Clean: {}, Timeoutlen: {}ms",
+        get_neovim_path(),
+        get_neovim_clean(),
+        get_timeoutlen()
+    );
+}
+
+/// Get platform-specific default Neovim path
+fn get_default_neovim_path() -> GString {
+    #[cfg(target_os = "windows")]
+    {
+        GString::from("nvim.exe")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        GString::from("nvim")
+    }
+}
+
+/// Get platform-specific default fzf path
+fn get_default_fzf_path() -> GString {
+    #[cfg(target_os = "windows")]
+    {
+        GString::from("fzf.exe")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        GString::from("fzf")
+    }
+}
+
+/// Get platform-specific default gdformat (gdtoolkit) path
+fn get_default_formatter_path() -> GString {
+    #[cfg(target_os = "windows")]
+    {
+        GString::from("gdformat.exe")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        GString::from("gdformat")
+    }
+}
+
+/// Get platform-specific file filter for file dialog
+fn get_file_filter() -> GString {
+    #[cfg(target_os = "windows")]
+    {
+        GString::from("*.exe")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        GString::from("*")
+    }
+}
+
+/// Get the configured Neovim executable path
+pub fn get_neovim_path() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return get_default_neovim_path().to_string();
+    };
+
+    if settings.has_setting(SETTING_NEOVIM_PATH) {
+        let value = settings.get_setting(SETTING_NEOVIM_PATH);
+        if let Ok(path) = value.try_to::<GString>() {
+            let path_str = path.to_string();
+            if !path_str.is_empty() {
+                return path_str;
+            }
+        }
+    }
+
+    get_default_neovim_path().to_string()
+}
+
+/// Get the configured neovim clean mode
+/// Default is true (--clean) for safety - avoids plugin compatibility issues
+pub fn get_neovim_clean() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return true; // Default to clean mode
+    };
+
+    if settings.has_setting(SETTING_NEOVIM_CLEAN) {
+        let value = settings.get_setting(SETTING_NEOVIM_CLEAN);
+        if let Ok(clean) = value.try_to::<bool>() {
+            return clean;
+        }
+    }
+
+    true // Default to clean mode
+}
+
+/// Get the init file loaded with `-u` when Neovim Clean is off
+/// Empty loads the user's default config (init.lua / init.vim)
+pub fn get_neovim_init_path() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return String::new();
+    };
+
+    if settings.has_setting(SETTING_NEOVIM_INIT_PATH) {
+        let value = settings.get_setting(SETTING_NEOVIM_INIT_PATH);
+        if let Ok(path) = value.try_to::<GString>() {
+            return path.to_string().trim().to_string();
+        }
+    }
+
+    String::new()
+}
+
+/// Get the allow-list of config files sourced into the embedded Neovim after
+/// it starts, also in clean mode (e.g. `~/.config/nvim/lua/keymaps.lua`)
+pub fn get_neovim_config_files() -> Vec<String> {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return Vec::new();
+    };
+
+    if settings.has_setting(SETTING_NEOVIM_CONFIG_FILES) {
+        let value = settings.get_setting(SETTING_NEOVIM_CONFIG_FILES);
+        if let Ok(files) = value.try_to::<PackedStringArray>() {
+            return files
+                .as_slice()
+                .iter()
+                .map(|file| file.to_string().trim().to_string())
+                .filter(|file| !file.is_empty())
+                .collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Get the configured timeoutlen (multi-key sequence timeout in milliseconds)
+pub fn get_timeoutlen() -> u64 {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_TIMEOUTLEN_MS as u64;
+    };
+
+    if settings.has_setting(SETTING_TIMEOUTLEN) {
+        let value = settings.get_setting(SETTING_TIMEOUTLEN);
+        if let Ok(timeout) = value.try_to::<i64>() {
+            // Clamp to valid range (0 to 10000ms)
+            return timeout.clamp(0, 10000) as u64;
+        }
+    }
+
+    DEFAULT_TIMEOUTLEN_MS as u64
+}
+
+/// Store the timeoutlen (`:set timeoutlen={ms}`)
+pub fn set_timeoutlen(timeout: i64) {
+    let editor = EditorInterface::singleton();
+    if let Some(mut settings) = editor.get_editor_settings() {
+        settings.set_setting(SETTING_TIMEOUTLEN, &Variant::from(timeout.clamp(0, 10000)));
+    }
+}
+
+/// Get the Neovim option values chosen with `:set` (option name -> value)
+pub fn get_neovim_options() -> VarDictionary {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return VarDictionary::new();
+    };
+
+    if settings.has_setting(SETTING_NEOVIM_OPTIONS) {
+        let value = settings.get_setting(SETTING_NEOVIM_OPTIONS);
+        if let Ok(options) = value.try_to::<VarDictionary>() {
+            return options;
+        }
+    }
+
+    VarDictionary::new()
+}
+
+/// Store a Neovim option value chosen with `:set` (None removes it: `:set {option}&`)
+pub fn set_neovim_option(name: &str, value: Option<&Variant>) {
+    let editor = EditorInterface::singleton();
+    let Some(mut settings) = editor.get_editor_settings() else {
+        return;
+    };
+
+    let mut options = get_neovim_options();
+    match value {
+        Some(value) => options.set(name, value),
+        None => {
+            options.remove(name);
+        }
+    }
+    settings.set_setting(SETTING_NEOVIM_OPTIONS, &options.to_variant());
+}
+
+/// Get the configured Insert mode navigation key policy
+pub fn get_insert_navigation_policy() -> InsertNavigationPolicy {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return InsertNavigationPolicy::default();
+    };
+
+    if settings.has_setting(SETTING_INSERT_NAVIGATION) {
+        let value = settings.get_setting(SETTING_INSERT_NAVIGATION);
+        if let Ok(policy) = value.try_to::<i64>() {
+            return match policy {
+                1 => InsertNavigationPolicy::Forward,
+                _ => InsertNavigationPolicy::Off,
+            };
+        }
+    }
+
+    InsertNavigationPolicy::default()
+}
+
+/// Get the configured `gr` behavior (LSP references or replace operator)
+pub fn get_gr_behavior() -> GrBehavior {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return GrBehavior::default();
+    };
+
+    if settings.has_setting(SETTING_GR_BEHAVIOR) {
+        let value = settings.get_setting(SETTING_GR_BEHAVIOR);
+        if let Ok(behavior) = value.try_to::<i64>() {
+            return match behavior {
+                1 => GrBehavior::ReplaceOperator,
+                _ => GrBehavior::References,
+            };
+        }
+    }
+
+    GrBehavior::default()
+}
+
+/// Get the configured `K` behavior (help tab or hover float)
+pub fn get_k_behavior() -> KBehavior {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return KBehavior::default();
+    };
+
+    if settings.has_setting(SETTING_K_BEHAVIOR) {
+        let value = settings.get_setting(SETTING_K_BEHAVIOR);
+        if let Ok(behavior) = value.try_to::<i64>() {
+            return match behavior {
+                1 => KBehavior::Hover,
+                _ => KBehavior::Help,
+            };
+        }
+    }
+
+    KBehavior::default()
+}
+
+/// Get whether the `*` register is tied to the primary selection (Linux/X11)
+/// Default is true - matches native Vim behavior on X11
+pub fn get_primary_selection() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return true;
+    };
+
+    if settings.has_setting(SETTING_PRIMARY_SELECTION) {
+        let value = settings.get_setting(SETTING_PRIMARY_SELECTION);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    true
+}
+
+/// Get the yank highlight duration in milliseconds (0 = disabled)
+pub fn get_yank_highlight_duration() -> u64 {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_YANK_HIGHLIGHT_DURATION_MS as u64;
+    };
+
+    if settings.has_setting(SETTING_YANK_HIGHLIGHT_DURATION) {
+        let value = settings.get_setting(SETTING_YANK_HIGHLIGHT_DURATION);
+        if let Ok(duration) = value.try_to::<i64>() {
+            // Clamp to valid range (0 to 2000ms)
+            return duration.clamp(0, 2000) as u64;
+        }
+    }
+
+    DEFAULT_YANK_HIGHLIGHT_DURATION_MS as u64
+}
+
+/// Get the yank highlight color
+pub fn get_yank_highlight_color() -> Color {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_YANK_HIGHLIGHT_COLOR;
+    };
+
+    if settings.has_setting(SETTING_YANK_HIGHLIGHT_COLOR) {
+        let value = settings.get_setting(SETTING_YANK_HIGHLIGHT_COLOR);
+        if let Ok(color) = value.try_to::<Color>() {
+            return color;
+        }
+    }
+
+    DEFAULT_YANK_HIGHLIGHT_COLOR
+}
+
+/// Get when comment leaders are continued on a new line
+pub fn get_comment_continuation() -> CommentContinuation {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return CommentContinuation::default();
+    };
+
+    if settings.has_setting(SETTING_COMMENT_CONTINUATION) {
+        let value = settings.get_setting(SETTING_COMMENT_CONTINUATION);
+        if let Ok(continuation) = value.try_to::<i64>() {
+            return match continuation {
+                1 => CommentContinuation::Enter,
+                2 => CommentContinuation::OpenLine,
+                3 => CommentContinuation::Both,
+                _ => CommentContinuation::Off,
+            };
+        }
+    }
+
+    CommentContinuation::default()
+}
+
+/// Get the configured picker backend
+pub fn get_picker_backend() -> PickerBackend {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return PickerBackend::default();
+    };
+
+    if settings.has_setting(SETTING_PICKER_BACKEND) {
+        let value = settings.get_setting(SETTING_PICKER_BACKEND);
+        if let Ok(backend) = value.try_to::<i64>() {
+            return match backend {
+                1 => PickerBackend::Fzf,
+                _ => PickerBackend::BuiltIn,
+            };
+        }
+    }
+
+    PickerBackend::default()
+}
+
+/// Get the configured fzf executable path
+pub fn get_fzf_path() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return get_default_fzf_path().to_string();
+    };
+
+    if settings.has_setting(SETTING_FZF_PATH) {
+        let value = settings.get_setting(SETTING_FZF_PATH);
+        if let Ok(path) = value.try_to::<GString>() {
+            let path_str = path.to_string();
+            if !path_str.is_empty() {
+                return path_str;
+            }
+        }
+    }
+
+    get_default_fzf_path().to_string()
+}
+
+/// Get whether the keys of a macro being recorded are shown next to the indicator
+/// Default is false - only "recording @a" is shown, like Vim
+pub fn get_show_macro_keys() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_SHOW_MACRO_KEYS) {
+        let value = settings.get_setting(SETTING_SHOW_MACRO_KEYS);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get whether the editor returns to Normal mode when it regains focus or the
+/// script is switched
+/// Default is false - the mode that was left is kept
+pub fn get_normal_mode_on_focus() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_NORMAL_MODE_ON_FOCUS) {
+        let value = settings.get_setting(SETTING_NORMAL_MODE_ON_FOCUS);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get whether line numbers are relative to the caret line (`:set relativenumber`)
+pub fn get_relative_number() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_RELATIVE_NUMBER) {
+        let value = settings.get_setting(SETTING_RELATIVE_NUMBER);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Store whether line numbers are relative (`:set relativenumber`)
+pub fn set_relative_number(enabled: bool) {
+    let editor = EditorInterface::singleton();
+    if let Some(mut settings) = editor.get_editor_settings() {
+        settings.set_setting(SETTING_RELATIVE_NUMBER, &Variant::from(enabled));
+    }
+}
+
+/// Get the key that starts `<leader>` mappings (Neovim notation, e.g. `<Space>`, `,`)
+/// Empty disables leader mappings
+pub fn get_leader_key() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_LEADER_KEY.to_string();
+    };
+
+    if settings.has_setting(SETTING_LEADER_KEY) {
+        let value = settings.get_setting(SETTING_LEADER_KEY);
+        if let Ok(key) = value.try_to::<GString>() {
+            return key.to_string().trim().to_string();
+        }
+    }
+
+    DEFAULT_LEADER_KEY.to_string()
+}
+
+/// Get the Normal mode key sequences always sent to Neovim instead of the plugin's
+/// handlers (Neovim notation, e.g. `gd`, `<C-p>`, `<leader>w`)
+pub fn get_always_forward_keys() -> Vec<String> {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return Vec::new();
+    };
+
+    if settings.has_setting(SETTING_ALWAYS_FORWARD_KEYS) {
+        let value = settings.get_setting(SETTING_ALWAYS_FORWARD_KEYS);
+        if let Ok(keys) = value.try_to::<PackedStringArray>() {
+            return keys
+                .as_slice()
+                .iter()
+                .map(|key| key.to_string().trim().to_string())
+                .filter(|key| !key.is_empty())
+                .collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Get the Key Interception table: key (Neovim notation, optionally prefixed with
+/// `n:`, `i:` or `v:`) -> whether the plugin intercepts it (false: Godot handles it)
+/// Entries whose value is not a bool are ignored.
+pub fn get_key_interception() -> Vec<(String, bool)> {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return Vec::new();
+    };
+
+    if settings.has_setting(SETTING_KEY_INTERCEPTION) {
+        let value = settings.get_setting(SETTING_KEY_INTERCEPTION);
+        if let Ok(table) = value.try_to::<VarDictionary>() {
+            return table
+                .iter_shared()
+                .filter_map(|(key, intercept)| {
+                    let key = key.try_to::<GString>().ok()?.to_string().trim().to_string();
+                    let intercept = intercept.try_to::<bool>().ok()?;
+                    (!key.is_empty()).then_some((key, intercept))
+                })
+                .collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Get the Key Layout Overrides table: character typed on the keyboard -> Vim key
+/// it stands for in Normal/Visual mode commands (e.g. `ö` -> `[`)
+/// Entries whose key or value is not a String are ignored.
+pub fn get_key_layout_overrides() -> Vec<(String, String)> {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return Vec::new();
+    };
+
+    if settings.has_setting(SETTING_KEY_LAYOUT_OVERRIDES) {
+        let value = settings.get_setting(SETTING_KEY_LAYOUT_OVERRIDES);
+        if let Ok(table) = value.try_to::<VarDictionary>() {
+            return table
+                .iter_shared()
+                .filter_map(|(typed, vim_key)| {
+                    let typed = typed.try_to::<GString>().ok()?.to_string();
+                    let vim_key = vim_key.try_to::<GString>().ok()?.to_string();
+                    Some((typed, vim_key))
+                })
+                .collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Get the line count above which a script is opened in large-file mode
+/// (chunked loading, no visual selection polling or hlsearch overlay)
+/// 0 disables large-file mode
+pub fn get_large_file_lines() -> usize {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_LARGE_FILE_LINES as usize;
+    };
+
+    if settings.has_setting(SETTING_LARGE_FILE_LINES) {
+        let value = settings.get_setting(SETTING_LARGE_FILE_LINES);
+        if let Ok(lines) = value.try_to::<i64>() {
+            return lines.max(0) as usize;
+        }
+    }
+
+    DEFAULT_LARGE_FILE_LINES as usize
+}
+
+/// Get the JSON file of the project's Insert mode snippets
+/// Empty disables the file (the built-in snippets stay available)
+pub fn get_snippets_file() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_SNIPPETS_FILE.to_string();
+    };
+
+    if settings.has_setting(SETTING_SNIPPETS_FILE) {
+        let value = settings.get_setting(SETTING_SNIPPETS_FILE);
+        if let Ok(path) = value.try_to::<GString>() {
+            return path.to_string().trim().to_string();
+        }
+    }
+
+    DEFAULT_SNIPPETS_FILE.to_string()
+}
+
+/// Get when modified scripts are saved automatically
+pub fn get_auto_save() -> AutoSave {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return AutoSave::default();
+    };
+
+    if settings.has_setting(SETTING_AUTO_SAVE) {
+        let value = settings.get_setting(SETTING_AUTO_SAVE);
+        if let Ok(auto_save) = value.try_to::<i64>() {
+            return match auto_save {
+                1 => AutoSave::InsertLeave,
+                2 => AutoSave::FocusLost,
+                3 => AutoSave::Both,
+                _ => AutoSave::Off,
+            };
+        }
+    }
+
+    AutoSave::default()
+}
+
+/// Get the delay between leaving Insert mode and the auto-save (milliseconds)
+/// Typing again in the meantime postpones the save to the next Insert mode exit.
+pub fn get_auto_save_delay() -> u64 {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_AUTO_SAVE_DELAY_MS as u64;
+    };
+
+    if settings.has_setting(SETTING_AUTO_SAVE_DELAY) {
+        let value = settings.get_setting(SETTING_AUTO_SAVE_DELAY);
+        if let Ok(delay) = value.try_to::<i64>() {
+            return delay.max(0) as u64;
+        }
+    }
+
+    DEFAULT_AUTO_SAVE_DELAY_MS as u64
+}
+
+/// Get the address of a running Neovim to attach to (`--listen` address:
+/// `host:port`, a Unix socket path or a Windows named pipe)
+/// Empty spawns an embedded Neovim
+pub fn get_connection_address() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return String::new();
+    };
+
+    if settings.has_setting(SETTING_CONNECTION_ADDRESS) {
+        let value = settings.get_setting(SETTING_CONNECTION_ADDRESS);
+        if let Ok(address) = value.try_to::<GString>() {
+            return address.to_string().trim().to_string();
+        }
+    }
+
+    String::new()
+}
+
+/// Get whether the LSP diagnostics of the current script are shown
+pub fn get_diagnostics() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return true;
+    };
+
+    if settings.has_setting(SETTING_DIAGNOSTICS) {
+        let value = settings.get_setting(SETTING_DIAGNOSTICS);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    true
+}
+
+/// Get the formatter used by gq and :format (gdformat, reading stdin with `-`)
+/// Empty turns the formatter off (gq formats like Neovim does without it)
+pub fn get_formatter_path() -> String {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return get_default_formatter_path().to_string();
+    };
+
+    if settings.has_setting(SETTING_FORMATTER_PATH) {
+        let value = settings.get_setting(SETTING_FORMATTER_PATH);
+        if let Ok(path) = value.try_to::<GString>() {
+            return path.to_string().trim().to_string();
+        }
+    }
+
+    get_default_formatter_path().to_string()
+}
+
+/// Get whether each script is indented like it already is (tabs or spaces and
+/// the indent width) instead of like the editor's indent settings
+pub fn get_detect_indent() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return true;
+    };
+
+    if settings.has_setting(SETTING_DETECT_INDENT) {
+        let value = settings.get_setting(SETTING_DETECT_INDENT);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    true
+}
+
+/// Get whether the undo history of each script is kept in an undo file, so it
+/// survives closing the script and restarting Godot
+pub fn get_persistent_undo() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return true;
+    };
+
+    if settings.has_setting(SETTING_PERSISTENT_UNDO) {
+        let value = settings.get_setting(SETTING_PERSISTENT_UNDO);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    true
+}
+
+/// Get whether the input method (IME) is turned off when leaving Insert mode (and
+/// on again when entering it), so Normal mode keys are not composed
+pub fn get_ime_auto_off() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_IME_AUTO_OFF) {
+        let value = settings.get_setting(SETTING_IME_AUTO_OFF);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get whether f/F/t/T pressed right after a find repeat it (clever-f)
+pub fn get_clever_f() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_CLEVER_F) {
+        let value = settings.get_setting(SETTING_CLEVER_F);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get whether f/t (F/T) continue on the following (preceding) lines when the
+/// character is not found on the cursor line
+pub fn get_find_across_lines() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_FIND_ACROSS_LINES) {
+        let value = settings.get_setting(SETTING_FIND_ACROSS_LINES);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get whether `s` jumps to two characters on screen (sneak) instead of
+/// substituting the character
+pub fn get_sneak() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_SNEAK) {
+        let value = settings.get_setting(SETTING_SNEAK);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get whether a float with the diagnostics or documentation under the caret is
+/// shown when the caret rests in Normal mode
+pub fn get_idle_hover() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_IDLE_HOVER) {
+        let value = settings.get_setting(SETTING_IDLE_HOVER);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get the time the caret rests before the idle hover float (Neovim's 'updatetime')
+pub fn get_idle_hover_delay() -> u64 {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_IDLE_HOVER_DELAY_MS as u64;
+    };
+
+    if settings.has_setting(SETTING_IDLE_HOVER_DELAY) {
+        let value = settings.get_setting(SETTING_IDLE_HOVER_DELAY);
+        if let Ok(delay) = value.try_to::<i64>() {
+            // Clamp to valid range (100 to 5000ms)
+            return delay.clamp(100, 5000) as u64;
+        }
+    }
+
+    DEFAULT_IDLE_HOVER_DELAY_MS as u64
+}
+
+/// Get whether viewport jumps of more than a screen are animated
+pub fn get_smooth_scroll() -> bool {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return false;
+    };
+
+    if settings.has_setting(SETTING_SMOOTH_SCROLL) {
+        let value = settings.get_setting(SETTING_SMOOTH_SCROLL);
+        if let Ok(enabled) = value.try_to::<bool>() {
+            return enabled;
+        }
+    }
+
+    false
+}
+
+/// Get the duration of the smooth scroll animation in milliseconds (0 snaps)
+pub fn get_smooth_scroll_duration() -> u64 {
+    let editor = EditorInterface::singleton();
+    let Some(settings) = editor.get_editor_settings() else {
+        return DEFAULT_SMOOTH_SCROLL_DURATION_MS as u64;
+    };
+
+    if settings.has_setting(SETTING_SMOOTH_SCROLL_DURATION) {
+        let value = settings.get_setting(SETTING_SMOOTH_SCROLL_DURATION);
+        if let Ok(duration) = value.try_to::<i64>() {
+            // Clamp to valid range (0 to 1000ms)
+            return duration.clamp(0, 1000) as u64;
+        }
+    }
+
+    DEFAULT_SMOOTH_SCROLL_DURATION_MS as u64
 }
 
 /// Validate the Neovim executable path
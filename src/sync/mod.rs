@@ -34,6 +34,63 @@ pub struct DocumentChange {
     pub new_lines: Vec<String>,
 }
 
+/// Line edit of the Godot buffer (0-indexed lines)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineEdit {
+    /// Replace the text of a line
+    Set { line: i64, text: String },
+    /// Insert lines before `line` (`line` == line count appends)
+    Insert { line: i64, lines: Vec<String> },
+    /// Remove `count` lines starting at `line`
+    Remove { line: i64, count: i64 },
+}
+
+impl DocumentChange {
+    /// Edits turning `old` (the lines first_line..last_line as Godot has them)
+    /// into new_lines, touching only the lines that differ
+    /// Unchanged lines keep their folds, bookmarks and breakpoints in Godot, and
+    /// a changed line is rewritten in place rather than removed and inserted.
+    pub fn line_edits(&self, old: &[String]) -> Vec<LineEdit> {
+        let new = &self.new_lines;
+        let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let old_changed = &old[prefix..old.len() - suffix];
+        let new_changed = &new[prefix..new.len() - suffix];
+
+        let start = self.first_line.max(0) + prefix as i64;
+        let common = old_changed.len().min(new_changed.len());
+        let mut edits: Vec<LineEdit> = old_changed
+            .iter()
+            .zip(new_changed)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, (_, text))| LineEdit::Set {
+                line: start + i as i64,
+                text: text.clone(),
+            })
+            .collect();
+
+        let rest = start + common as i64;
+        if new_changed.len() > common {
+            edits.push(LineEdit::Insert {
+                line: rest,
+                lines: new_changed[common..].to_vec(),
+            });
+        } else if old_changed.len() > common {
+            edits.push(LineEdit::Remove {
+                line: rest,
+                count: (old_changed.len() - common) as i64,
+            });
+        }
+        edits
+    }
+}
+
 /// Manages buffer synchronization between Neovim and Godot
 pub struct SyncManager {
     /// Neovim's buffer change counter
@@ -55,6 +112,17 @@ pub struct SyncManager {
 
     /// Neovim buffer line count (used to clamp cursor position)
     nvim_line_count: i32,
+
+    /// Open edit transaction (see begin_transaction)
+    transaction: Option<EditTransaction>,
+}
+
+/// A bulk edit that must be a single undo step on both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EditTransaction {
+    /// Neovim changedtick after the last change (set by commit)
+    /// The transaction is complete once changes up to this tick have been received
+    commit_tick: Option<i64>,
 }
 
 impl SyncManager {
@@ -66,6 +134,7 @@ impl SyncManager {
             attached: false,
             initial_sync_tick: None,
             nvim_line_count: 0,
+            transaction: None,
         }
     }
 
@@ -77,6 +146,7 @@ impl SyncManager {
         self.attached = false;
         self.initial_sync_tick = None;
         self.nvim_line_count = 0;
+        self.transaction = None;
     }
 
     /// Set Neovim buffer line count
@@ -96,6 +166,16 @@ impl SyncManager {
         crate::verbose_print!("[SyncManager] Initial sync tick set to {}", tick);
     }
 
+    /// Whether the buffer is attached for change notifications
+    pub fn is_attached(&self) -> bool {
+        self.attached
+    }
+
+    /// Last Neovim changedtick applied (-1 before the first sync)
+    pub fn get_changedtick(&self) -> i64 {
+        self.changedtick
+    }
+
     /// Mark buffer as attached
     pub fn set_attached(&mut self, attached: bool) {
         self.attached = attached;
@@ -188,6 +268,49 @@ impl SyncManager {
     pub fn end_nvim_change(&mut self) {
         self.changed_by_nvim = false;
     }
+
+    /// Begin an edit transaction: the following changes (made in Neovim with
+    /// undojoin, applied to Godot in one complex operation) form one undo step.
+    /// Returns false if a transaction is already open; the changes join it.
+    pub fn begin_transaction(&mut self) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        self.transaction = Some(EditTransaction { commit_tick: None });
+        true
+    }
+
+    /// Commit the open transaction with Neovim's changedtick after its last change.
+    /// Returns true if it is already complete (no changes still to be received).
+    pub fn commit(&mut self, tick: i64) -> bool {
+        if let Some(transaction) = self.transaction.as_mut() {
+            transaction.commit_tick = Some(tick);
+        }
+        self.take_completed_transaction()
+    }
+
+    /// Whether an edit transaction is open (begun, or committed but incomplete)
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Close the transaction if all of its changes have been received from Neovim.
+    /// Returns true when it was closed (the Godot undo step can be ended).
+    pub fn take_completed_transaction(&mut self) -> bool {
+        let complete = self
+            .transaction
+            .and_then(|transaction| transaction.commit_tick)
+            .is_some_and(|tick| self.changedtick == -1 || self.changedtick >= tick);
+        if complete {
+            self.transaction = None;
+        }
+        complete
+    }
+
+    /// Close the transaction without waiting for its changes (buffer switch, error)
+    pub fn abort_transaction(&mut self) -> bool {
+        self.transaction.take().is_some()
+    }
 }
 
 impl Default for SyncManager {
@@ -223,6 +346,88 @@ mod tests {
         assert_eq!(change.new_lines, vec!["new line".to_string()]);
     }
 
+    #[test]
+    fn test_transaction_completes_at_commit_tick() {
+        let mut sync = SyncManager::new();
+        sync.changedtick = 5;
+
+        assert!(sync.begin_transaction());
+        // A nested begin joins the open transaction
+        assert!(!sync.begin_transaction());
+
+        // Neovim made two changes (ticks 6 and 7); only tick 6 has been received
+        sync.on_nvim_changedtick(6);
+        assert!(!sync.commit(7));
+        assert!(sync.in_transaction());
+        assert!(!sync.take_completed_transaction());
+
+        sync.on_nvim_changedtick(7);
+        assert!(sync.take_completed_transaction());
+        assert!(!sync.in_transaction());
+
+        // Nothing changed: complete at commit
+        assert!(sync.begin_transaction());
+        assert!(sync.commit(7));
+        assert!(!sync.abort_transaction());
+    }
+
+    fn change(first_line: i64, last_line: i64, new_lines: &[&str]) -> DocumentChange {
+        DocumentChange {
+            first_line,
+            last_line,
+            new_lines: new_lines.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn old(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_line_edits_touch_only_changed_lines() {
+        // One line of a replaced range changed: rewritten in place
+        let edits = change(10, 13, &["a", "B", "c"]).line_edits(&old(&["a", "b", "c"]));
+        assert_eq!(
+            edits,
+            vec![LineEdit::Set {
+                line: 11,
+                text: "B".to_string()
+            }]
+        );
+
+        // dd in the middle
+        let edits = change(4, 5, &[]).line_edits(&old(&["x"]));
+        assert_eq!(edits, vec![LineEdit::Remove { line: 4, count: 1 }]);
+
+        // o after line 2 (Neovim may report it as a rewrite of line 2)
+        let edits = change(2, 3, &["foo", ""]).line_edits(&old(&["foo"]));
+        assert_eq!(
+            edits,
+            vec![LineEdit::Insert {
+                line: 3,
+                lines: vec![String::new()]
+            }]
+        );
+
+        // Joining two lines: first rewritten, second removed
+        let edits = change(0, 2, &["a b"]).line_edits(&old(&["a", "b"]));
+        assert_eq!(
+            edits,
+            vec![
+                LineEdit::Set {
+                    line: 0,
+                    text: "a b".to_string()
+                },
+                LineEdit::Remove { line: 1, count: 1 }
+            ]
+        );
+
+        // Reload with the same text: nothing to do
+        assert!(change(0, -1, &["a", "b"])
+            .line_edits(&old(&["a", "b"]))
+            .is_empty());
+    }
+
     // Note: Tests for duplicate tick detection and initial sync echo
     // are not included here because they hit verbose_print! paths
     // which require Godot engine. These are tested manually.